@@ -52,14 +52,83 @@ service SystemInfoService {
     let proto_file = proto_dir.join("system_info.proto");
     std::fs::write(&proto_file, system_info_proto)?;
 
-    // Compile the proto file
+    let chat_proto = r#"syntax = "proto3";
+
+package graph_os;
+
+// ChatMessage is a single message in a conversation
+message ChatMessage {
+  string role = 1;              // Message role: "user", "assistant" or "system"
+  string content = 2;           // Message text
+}
+
+// ChatRequest carries the conversation history plus settings
+message ChatRequest {
+  repeated ChatMessage messages = 1; // Conversation history so far
+  string model = 2;                  // Optional model override (empty = server default)
+}
+
+// ChatResponse is a single streamed chunk of assistant output
+message ChatResponse {
+  string content = 1;           // Token or chunk of assistant output
+  bool done = 2;                // True on the final chunk of a turn
+}
+
+// ChatService provides chat over gRPC with bidirectional streaming
+service ChatService {
+  // Chat streams requests up and response tokens down over one connection
+  rpc Chat(stream ChatRequest) returns (stream ChatResponse);
+}
+"#;
+
+    let chat_proto_file = proto_dir.join("chat.proto");
+    std::fs::write(&chat_proto_file, chat_proto)?;
+
+    // Standard gRPC Health Checking Protocol (grpc.health.v1), trimmed to
+    // the unary Check RPC which is all the client uses
+    let health_proto = r#"syntax = "proto3";
+
+package grpc.health.v1;
+
+// HealthCheckRequest names the service to probe (empty = whole server)
+message HealthCheckRequest {
+  string service = 1;           // Fully-qualified service name, or ""
+}
+
+// HealthCheckResponse carries the serving status of one service
+message HealthCheckResponse {
+  enum ServingStatus {
+    UNKNOWN = 0;                // Status not known to the server
+    SERVING = 1;                // Service is up and accepting calls
+    NOT_SERVING = 2;            // Service is registered but down
+    SERVICE_UNKNOWN = 3;        // Server has no such service
+  }
+  ServingStatus status = 1;     // Status of the requested service
+}
+
+// Health is the standard health checking service
+service Health {
+  // Check returns the current serving status of a service
+  rpc Check(HealthCheckRequest) returns (HealthCheckResponse);
+}
+"#;
+
+    let health_proto_file = proto_dir.join("health.proto");
+    std::fs::write(&health_proto_file, health_proto)?;
+
+    // Compile the proto files
     tonic_build::configure()
         .build_server(false)
         .build_client(true)
-        .compile(&["src/proto/system_info.proto"], &["src/proto"])?;
+        .compile(
+            &["src/proto/system_info.proto", "src/proto/chat.proto", "src/proto/health.proto"],
+            &["src/proto"],
+        )?;
 
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=src/proto/system_info.proto");
+    println!("cargo:rerun-if-changed=src/proto/chat.proto");
+    println!("cargo:rerun-if-changed=src/proto/health.proto");
 
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,142 @@
+//! Dynamic gRPC invocation from user-supplied proto files. The proto
+//! is compiled at runtime (no protoc needed), requests are built from
+//! JSON via prost-reflect, and responses decode back to JSON — so any
+//! GraphOS service can be called without recompiling the CLI.
+
+use std::path::Path;
+
+use prost::Message as _;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor};
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::Status;
+
+use crate::error::{GraphOsError, Result};
+
+/// Compile a proto file into a descriptor pool. The file's own
+/// directory and the current directory serve as the import path.
+pub fn load_descriptor_pool(proto: &Path) -> Result<DescriptorPool> {
+    let parent = proto.parent().filter(|p| !p.as_os_str().is_empty());
+    let includes = [parent.unwrap_or_else(|| Path::new(".")), Path::new(".")];
+
+    let file_set = protox::compile([proto], includes)
+        .map_err(|e| GraphOsError::Config(format!("Failed to compile {}: {}", proto.display(), e)))?;
+
+    // Round-trip through the wire encoding so protox's descriptor types
+    // and prost-reflect's agree regardless of their prost versions
+    DescriptorPool::decode(file_set.encode_to_vec().as_slice())
+        .map_err(|e| GraphOsError::Config(format!("Invalid descriptor set: {}", e)))
+}
+
+/// Resolve a `Service/Method` spec against the pool. The service part
+/// matches either the fully-qualified name (`graph_os.ChatService`) or
+/// the bare name when that is unambiguous.
+pub fn resolve_method(pool: &DescriptorPool, spec: &str) -> Result<MethodDescriptor> {
+    let (service_name, method_name) = spec
+        .split_once('/')
+        .ok_or_else(|| GraphOsError::Config(format!("Expected <service>/<method>, got '{}'", spec)))?;
+
+    let matches: Vec<_> = pool
+        .services()
+        .filter(|s| s.full_name() == service_name || s.name() == service_name)
+        .collect();
+
+    let service = match matches.as_slice() {
+        [service] => service.clone(),
+        [] => {
+            let known: Vec<_> = pool.services().map(|s| s.full_name().to_string()).collect();
+            return Err(GraphOsError::Config(format!(
+                "No service '{}' in the proto (available: {})",
+                service_name,
+                known.join(", ")
+            )));
+        }
+        _ => {
+            return Err(GraphOsError::Config(format!(
+                "Service name '{}' is ambiguous; use the fully-qualified name",
+                service_name
+            )));
+        }
+    };
+
+    service.methods().find(|m| m.name() == method_name).ok_or_else(|| {
+        let known: Vec<_> = service.methods().map(|m| m.name().to_string()).collect();
+        GraphOsError::Config(format!(
+            "Service {} has no method '{}' (available: {})",
+            service.full_name(),
+            method_name,
+            known.join(", ")
+        ))
+    })
+}
+
+/// Build the request message for a method from its JSON representation
+pub fn request_from_json(method: &MethodDescriptor, json: &str) -> Result<DynamicMessage> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let message = DynamicMessage::deserialize(method.input(), &mut deserializer)
+        .map_err(|e| GraphOsError::Decode(format!("Invalid request JSON: {}", e)))?;
+    deserializer
+        .end()
+        .map_err(|e| GraphOsError::Decode(format!("Invalid request JSON: {}", e)))?;
+    Ok(message)
+}
+
+/// Render a response message as pretty-printed JSON
+pub fn response_to_json(message: &DynamicMessage) -> Result<String> {
+    serde_json::to_string_pretty(message)
+        .map_err(|e| GraphOsError::Decode(format!("Failed to render response: {}", e)))
+}
+
+/// Codec that encodes any DynamicMessage and decodes responses against
+/// the method's output descriptor, standing in for the generated
+/// per-message prost codec
+#[derive(Debug, Clone)]
+pub struct DynamicCodec {
+    response: MessageDescriptor,
+}
+
+impl DynamicCodec {
+    pub fn new(response: MessageDescriptor) -> Self {
+        Self { response }
+    }
+}
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicEncoder;
+    type Decoder = DynamicDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicEncoder
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicDecoder(self.response.clone())
+    }
+}
+
+#[derive(Debug)]
+pub struct DynamicEncoder;
+
+impl Encoder for DynamicEncoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: DynamicMessage, dst: &mut EncodeBuf<'_>) -> Result<(), Status> {
+        item.encode(dst).map_err(|e| Status::internal(e.to_string()))
+    }
+}
+
+#[derive(Debug)]
+pub struct DynamicDecoder(MessageDescriptor);
+
+impl Decoder for DynamicDecoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<DynamicMessage>, Status> {
+        DynamicMessage::decode(self.0.clone(), src)
+            .map(Some)
+            .map_err(|e| Status::internal(format!("Failed to decode response: {}", e)))
+    }
+}
@@ -1,5 +1,15 @@
-use anyhow::{anyhow, Result};
-use tonic::{transport::Channel, Request, transport::Uri};
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+use crate::audit::{AuditLog, AuditRecord};
+use crate::error::{GraphOsError, Result};
+use crate::config::EndpointConfig;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::{transport::Channel, Request, Status, transport::Uri};
 use std::time::Duration;
 
 // Include the generated Proto code
@@ -7,66 +17,915 @@ pub mod graph_os {
     tonic::include_proto!("graph_os");
 }
 
+// Standard gRPC Health Checking Protocol (grpc.health.v1)
+pub mod health {
+    tonic::include_proto!("grpc.health.v1");
+}
+
+use health::health_client::HealthClient;
+use health::HealthCheckRequest;
+
+use graph_os::chat_service_client::ChatServiceClient;
 use graph_os::system_info_service_client::SystemInfoServiceClient;
-use graph_os::{GetSystemInfoRequest, ListSystemInfoRequest, SystemInfo, SystemInfoList};
+use graph_os::{
+    ChatMessage, ChatRequest, GetSystemInfoRequest, ListSystemInfoRequest, SystemInfo,
+    SystemInfoList,
+};
+use std::sync::{Arc, Mutex as StdMutex};
+use tokio::sync::Mutex;
+
+/// Base delay between reconnect rounds; doubles after each failed round
+const RECONNECT_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Deadline applied to every gRPC call
+const CALL_DEADLINE: Duration = Duration::from_secs(30);
+
+/// Rounds through the endpoint list before a connect attempt gives up
+const MAX_CONNECT_ROUNDS: u32 = 3;
+
+/// How often the latency probe re-evaluates the endpoint choice when
+/// several endpoints are configured
+const PROBE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Connection state of a managed channel, exposed for the status line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    Reconnecting,
+    Disconnected,
+}
+
+/// Fully-qualified name of the system info service, for health probes
+pub const SYSTEM_INFO_SERVICE: &str = "graph_os.SystemInfoService";
+
+/// Fully-qualified name of the chat service, for health probes
+pub const CHAT_SERVICE: &str = "graph_os.ChatService";
+
+/// Services this client calls, in the order the status report lists
+/// them. The empty name probes the server as a whole.
+pub const KNOWN_SERVICES: [&str; 3] = ["", SYSTEM_INFO_SERVICE, CHAT_SERVICE];
+
+/// Serving status of one service as reported by grpc.health.v1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthStatus {
+    Serving,
+    NotServing,
+    ServiceUnknown,
+    Unknown,
+    /// Server answered Unimplemented: it predates the health service
+    Unsupported,
+}
+
+impl HealthStatus {
+    /// Map the wire enum, treating unrecognized values as unknown
+    fn from_code(code: i32) -> Self {
+        use health::health_check_response::ServingStatus;
+        match ServingStatus::try_from(code) {
+            Ok(ServingStatus::Serving) => HealthStatus::Serving,
+            Ok(ServingStatus::NotServing) => HealthStatus::NotServing,
+            Ok(ServingStatus::ServiceUnknown) => HealthStatus::ServiceUnknown,
+            _ => HealthStatus::Unknown,
+        }
+    }
+}
+
+impl std::fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            HealthStatus::Serving => "serving",
+            HealthStatus::NotServing => "not serving",
+            HealthStatus::ServiceUnknown => "unknown to server",
+            HealthStatus::Unknown => "unknown",
+            HealthStatus::Unsupported => "health checking unsupported",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Channel wrapper that connects lazily, fails over between endpoints in
+/// round-robin order and reconnects with backoff after transport errors
+#[derive(Clone)]
+pub struct ManagedChannel {
+    endpoints: Vec<String>,
+    channel: Arc<Mutex<Option<Channel>>>,
+    /// Index of the next endpoint to try
+    next_endpoint: Arc<StdMutex<usize>>,
+    /// Current state and the endpoint the active channel points at
+    state: Arc<StdMutex<(ConnectionState, Option<String>)>>,
+    /// When the endpoints were last latency-probed, None before the
+    /// startup probe
+    last_probe: Arc<StdMutex<Option<Instant>>>,
+}
+
+impl ManagedChannel {
+    pub fn new(endpoints: Vec<String>) -> Result<Self> {
+        if endpoints.is_empty() {
+            return Err(GraphOsError::Config("No gRPC endpoints configured".to_string()));
+        }
+
+        Ok(Self {
+            endpoints,
+            channel: Arc::new(Mutex::new(None)),
+            next_endpoint: Arc::new(StdMutex::new(0)),
+            state: Arc::new(StdMutex::new((ConnectionState::Disconnected, None))),
+            last_probe: Arc::new(StdMutex::new(None)),
+        })
+    }
+
+    /// Current connection state (safe to call from the render loop)
+    pub fn state(&self) -> ConnectionState {
+        self.state.lock().unwrap().0
+    }
+
+    /// Endpoint the active channel is connected to, if any
+    pub fn active_endpoint(&self) -> Option<String> {
+        self.state.lock().unwrap().1.clone()
+    }
+
+    fn set_state(&self, state: ConnectionState, endpoint: Option<String>) {
+        *self.state.lock().unwrap() = (state, endpoint);
+    }
+
+    /// Get the active channel, connecting if there is none. Holding the
+    /// channel lock across the connect serializes concurrent reconnects.
+    pub async fn get(&self) -> Result<Channel> {
+        let mut guard = self.channel.lock().await;
+        if let Some(channel) = guard.as_ref() {
+            self.maybe_reprobe();
+            return Ok(channel.clone());
+        }
+
+        self.set_state(ConnectionState::Reconnecting, None);
+        let mut backoff = RECONNECT_BACKOFF;
+        let mut last_error = None;
+
+        for round in 0..MAX_CONNECT_ROUNDS {
+            if round > 0 {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+
+            // Try each endpoint once per round, continuing round-robin from
+            // wherever the previous attempt left off
+            for _ in 0..self.endpoints.len() {
+                let endpoint = {
+                    let mut next = self.next_endpoint.lock().unwrap();
+                    let endpoint = self.endpoints[*next].clone();
+                    *next = (*next + 1) % self.endpoints.len();
+                    endpoint
+                };
+
+                match Self::connect(&endpoint).await {
+                    Ok(channel) => {
+                        *guard = Some(channel.clone());
+                        self.set_state(ConnectionState::Connected, Some(endpoint));
+                        return Ok(channel);
+                    }
+                    Err(e) => last_error = Some(e),
+                }
+            }
+        }
+
+        self.set_state(ConnectionState::Disconnected, None);
+        Err(last_error.unwrap_or_else(|| GraphOsError::Config("No gRPC endpoints configured".to_string())))
+    }
+
+    async fn connect(endpoint: &str) -> Result<Channel> {
+        let uri = endpoint
+            .parse::<Uri>()
+            .map_err(|e| GraphOsError::Config(format!("Invalid endpoint URL {}: {}", endpoint, e)))?;
+
+        Channel::builder(uri)
+            .timeout(Duration::from_secs(10))  // Set a 10 second connection timeout
+            .connect_timeout(Duration::from_secs(5))  // 5 second connect timeout
+            .connect()
+            .await
+            .map_err(|e| GraphOsError::Transport(format!("Connection error ({}): {}", endpoint, e)))
+    }
+
+    /// Drop the active channel after a transport error so the next call
+    /// reconnects, possibly on a different endpoint
+    pub async fn invalidate(&self) {
+        *self.channel.lock().await = None;
+        self.set_state(ConnectionState::Reconnecting, None);
+    }
+
+    /// Connect to every endpoint concurrently, timing the handshakes.
+    /// Unreachable endpoints probe as None.
+    async fn probe_latencies(endpoints: &[String]) -> Vec<(String, Option<(Duration, Channel)>)> {
+        let probes = endpoints.iter().map(|endpoint| async move {
+            let started = Instant::now();
+            match Self::connect(endpoint).await {
+                Ok(channel) => (endpoint.clone(), Some((started.elapsed(), channel))),
+                Err(_) => (endpoint.clone(), None),
+            }
+        });
+        futures_util::future::join_all(probes).await
+    }
+
+    /// Probe every endpoint and adopt the lowest-latency healthy one,
+    /// logging a switch to the audit log; the status bar picks the new
+    /// endpoint up through active_endpoint. With one endpoint, or when
+    /// nothing answers the probe, this falls back to the round-robin
+    /// connect.
+    pub async fn select_fastest(&self) -> Result<Channel> {
+        if self.endpoints.len() < 2 {
+            return self.get().await;
+        }
+
+        *self.last_probe.lock().unwrap() = Some(Instant::now());
+        let best = Self::probe_latencies(&self.endpoints)
+            .await
+            .into_iter()
+            .filter_map(|(endpoint, probe)| probe.map(|(latency, channel)| (endpoint, latency, channel)))
+            .min_by_key(|(_, latency, _)| *latency);
+
+        let Some((endpoint, latency, channel)) = best else {
+            return self.get().await;
+        };
+
+        let previous = self.active_endpoint();
+        *self.channel.lock().await = Some(channel.clone());
+        self.set_state(ConnectionState::Connected, Some(endpoint.clone()));
+
+        // A switch away from a previously active endpoint is worth an
+        // audit trail entry; the very first selection is not a switch
+        if previous.as_deref().is_some_and(|p| p != endpoint) {
+            AuditLog::instance().record(&AuditRecord {
+                timestamp: Utc::now(),
+                transport: "grpc".to_string(),
+                method: "endpoint.switch".to_string(),
+                endpoint: endpoint.clone(),
+                params: json!({ "from": previous, "latency_ms": latency.as_millis() as u64 }),
+                latency_ms: latency.as_millis() as u64,
+                status: "ok".to_string(),
+                token_usage: None,
+            });
+        }
+
+        Ok(channel)
+    }
+
+    /// Kick off a background re-probe when the last one has gone stale,
+    /// so a faster replica coming back gets adopted mid-session without
+    /// blocking the call that noticed
+    fn maybe_reprobe(&self) {
+        if self.endpoints.len() < 2 {
+            return;
+        }
+        {
+            let last_probe = self.last_probe.lock().unwrap();
+            if matches!(*last_probe, Some(at) if at.elapsed() < PROBE_INTERVAL) {
+                return;
+            }
+        }
+        let channel = self.clone();
+        tokio::spawn(async move {
+            let _ = channel.select_fastest().await;
+        });
+    }
+}
+
+/// Whether a status means the channel itself failed rather than the call
+fn is_transport_error(status: &tonic::Status) -> bool {
+    matches!(status.code(), tonic::Code::Unavailable | tonic::Code::DeadlineExceeded)
+}
+
+/// Capture a protobuf response in the recording, base64-encoding its
+/// wire form since the generated types do not implement serde
+fn record_proto<M: prost::Message>(method: &str, request: &Value, response: &M) {
+    use base64::Engine;
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(response.encode_to_vec());
+    crate::adapters::recording::record("grpc", method, request, &json!({ "message_b64": encoded }));
+}
+
+/// Decode the recorded protobuf response when replay mode is active
+fn replay_proto<M: prost::Message + Default>(method: &str, request: &Value) -> Option<Result<M>> {
+    use base64::Engine;
+
+    let recorded = match crate::adapters::recording::replay("grpc", method, request)? {
+        Ok(value) => value,
+        Err(e) => return Some(Err(GraphOsError::Decode(e.to_string()))),
+    };
+
+    let decoded = recorded
+        .get("message_b64")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| GraphOsError::Decode("Recorded gRPC exchange has no message_b64 field".to_string()))
+        .and_then(|encoded| {
+            base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|e| GraphOsError::Decode(format!("Corrupt recorded message: {}", e)))
+        })
+        .and_then(|bytes| {
+            M::decode(bytes.as_slice())
+                .map_err(|e| GraphOsError::Decode(format!("Failed to decode recorded message: {}", e)))
+        });
+
+    Some(decoded)
+}
+
+/// Authentication credentials attached to every gRPC call
+#[derive(Clone, Default)]
+pub struct GrpcAuth {
+    /// Bearer token sent as `authorization` metadata
+    pub token: Option<String>,
+    /// Shared secret sent as `x-graphos-auth` metadata
+    pub secret: Option<String>,
+    /// Extra metadata pairs from the endpoint config, e.g. X-Org-Id
+    pub extra_metadata: Vec<(String, String)>,
+    /// Refreshes the bearer token after an Unauthenticated answer, when
+    /// the endpoint has OIDC refresh credentials
+    pub refresher: Option<crate::auth::TokenRefresher>,
+}
+
+impl GrpcAuth {
+    /// Pull credentials from the named endpoint's config, matching what
+    /// the JSON-RPC client sends over HTTP headers; the name is where a
+    /// mid-session token refresh persists its result
+    pub fn from_endpoint(name: &str, endpoint: &EndpointConfig) -> Self {
+        Self {
+            token: endpoint.token.clone(),
+            secret: endpoint.secret.clone(),
+            extra_metadata: endpoint
+                .extra_headers
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+            refresher: crate::auth::TokenRefresher::for_endpoint(name, endpoint),
+        }
+    }
+}
+
+/// Interceptor that sets the per-call deadline and injects auth metadata
+#[derive(Clone)]
+struct CallInterceptor {
+    /// Shared across clones so a mid-session token refresh reaches the
+    /// per-call service clients already built from this interceptor
+    authorization: Arc<StdMutex<Option<MetadataValue<Ascii>>>>,
+    auth_secret: Option<MetadataValue<Ascii>>,
+    /// Endpoint-configured metadata, pre-parsed like the credentials
+    extra: Vec<(tonic::metadata::MetadataKey<Ascii>, MetadataValue<Ascii>)>,
+    deadline: Duration,
+}
+
+impl CallInterceptor {
+    fn new(auth: &GrpcAuth, deadline: Duration) -> Self {
+        // Credentials with characters invalid in metadata are dropped
+        // here rather than failing every call later
+        let authorization = auth
+            .token
+            .as_ref()
+            .and_then(|t| format!("Bearer {}", t).parse().ok());
+        let auth_secret = auth.secret.as_ref().and_then(|s| s.parse().ok());
+
+        // gRPC metadata keys must be lowercase; header-style names from
+        // the config are folded rather than rejected
+        let extra = auth
+            .extra_metadata
+            .iter()
+            .filter_map(|(name, value)| {
+                match (name.to_lowercase().parse(), value.parse()) {
+                    (Ok(key), Ok(value)) => Some((key, value)),
+                    _ => {
+                        eprintln!("Ignoring invalid extra metadata '{}'", name);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Self {
+            authorization: Arc::new(StdMutex::new(authorization)),
+            auth_secret,
+            extra,
+            deadline,
+        }
+    }
+
+    /// Swap in a freshly minted bearer token; every clone sees it on
+    /// its next call
+    fn set_token(&self, token: &str) {
+        if let Ok(authorization) = format!("Bearer {}", token).parse() {
+            *self.authorization.lock().unwrap() = Some(authorization);
+        }
+    }
+}
+
+impl Interceptor for CallInterceptor {
+    fn call(&mut self, mut request: tonic::Request<()>) -> Result<tonic::Request<()>, Status> {
+        request.set_timeout(self.deadline);
+
+        if let Some(authorization) = self.authorization.lock().unwrap().clone() {
+            request.metadata_mut().insert("authorization", authorization);
+        }
+        if let Some(secret) = &self.auth_secret {
+            request.metadata_mut().insert("x-graphos-auth", secret.clone());
+        }
+        for (key, value) in &self.extra {
+            request.metadata_mut().insert(key.clone(), value.clone());
+        }
+
+        Ok(request)
+    }
+}
 
 /// GrpcClient for connecting to the GraphOS server
+#[derive(Clone)]
 pub struct GrpcClient {
-    client: SystemInfoServiceClient<Channel>,
+    channel: ManagedChannel,
+    endpoint: String,
+    interceptor: CallInterceptor,
+    /// Refreshes the bearer token after an Unauthenticated answer, when
+    /// the endpoint has OIDC refresh credentials
+    refresher: Option<crate::auth::TokenRefresher>,
 }
 
 impl GrpcClient {
     /// Create a new gRPC client
     pub async fn new(endpoint: &str) -> Result<Self> {
-        println!("Creating gRPC client for endpoint: {}", endpoint);
-        
-        // Parse the endpoint as a URI
-        let uri = endpoint.parse::<Uri>()?;
-        
-        println!("Connecting to gRPC server...");
-        
-        // Set up the channel with timeout and keepalive settings
-        match Channel::builder(uri)
-            .timeout(Duration::from_secs(10))  // Set a 10 second connection timeout
-            .connect_timeout(Duration::from_secs(5))  // 5 second connect timeout
-            .connect()
-            .await {
-                Ok(channel) => {
-                    println!("Connected to gRPC endpoint");
-                    let client = SystemInfoServiceClient::new(channel);
-                    Ok(Self { client })
-                },
-                Err(e) => {
-                    println!("Failed to connect to gRPC server: {}", e);
-                    println!("Error details: {:?}", e);
-                    Err(anyhow!("Connection error: {}", e))
+        Self::with_endpoints(vec![endpoint.to_string()]).await
+    }
+
+    /// Create a client that fails over between several endpoints
+    pub async fn with_endpoints(endpoints: Vec<String>) -> Result<Self> {
+        Self::with_endpoints_auth(endpoints, GrpcAuth::default()).await
+    }
+
+    /// Create a client with auth credentials attached to every call
+    pub async fn with_endpoints_auth(endpoints: Vec<String>, auth: GrpcAuth) -> Result<Self> {
+        let endpoint = endpoints
+            .first()
+            .cloned()
+            .ok_or_else(|| GraphOsError::Config("No gRPC endpoints configured".to_string()))?;
+        let channel = ManagedChannel::new(endpoints)?;
+
+        // Establish the first connection eagerly so startup surfaces
+        // configuration errors; with several endpoints the startup probe
+        // picks the lowest-latency one. Later drops recover lazily.
+        channel.select_fastest().await?;
+
+        Ok(Self {
+            channel,
+            endpoint,
+            interceptor: CallInterceptor::new(&auth, CALL_DEADLINE),
+            refresher: auth.refresher,
+        })
+    }
+
+    /// After an Unauthenticated answer, mint a fresh token and swap it
+    /// into the shared interceptor, so the retry (and every clone's next
+    /// call) carries it. Returns whether a retry is worth making; without
+    /// refresh credentials a stale-token retry would only fail again.
+    async fn refresh_auth(&self) -> bool {
+        let Some(refresher) = &self.refresher else {
+            return false;
+        };
+        match refresher.refresh_now().await {
+            Ok(token) => {
+                self.interceptor.set_token(&token);
+                true
+            }
+            Err(e) => {
+                // The Unauthenticated status is the real story; the
+                // failed refresh is only context
+                eprintln!("Token refresh after Unauthenticated failed: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Connection state of the underlying channel, for the status line
+    pub fn connection_state(&self) -> ConnectionState {
+        self.channel.state()
+    }
+
+    /// Endpoint the client is currently connected to, if any
+    pub fn active_endpoint(&self) -> Option<String> {
+        self.channel.active_endpoint()
+    }
+
+    async fn system_info_client(
+        &self,
+    ) -> Result<SystemInfoServiceClient<InterceptedService<Channel, CallInterceptor>>> {
+        Ok(SystemInfoServiceClient::with_interceptor(
+            self.channel.get().await?,
+            self.interceptor.clone(),
+        ))
+    }
+
+    async fn health_client(
+        &self,
+    ) -> Result<HealthClient<InterceptedService<Channel, CallInterceptor>>> {
+        Ok(HealthClient::with_interceptor(
+            self.channel.get().await?,
+            self.interceptor.clone(),
+        ))
+    }
+
+    async fn chat_service_client(
+        &self,
+    ) -> Result<ChatServiceClient<InterceptedService<Channel, CallInterceptor>>> {
+        Ok(ChatServiceClient::with_interceptor(
+            self.channel.get().await?,
+            self.interceptor.clone(),
+        ))
+    }
+
+    /// Write an audit record for a completed call
+    fn audit<T>(&self, method: &str, params: Value, started: Instant, result: &Result<T>) {
+        let status = match result {
+            Ok(_) => "ok".to_string(),
+            Err(e) => format!("error: {}", e),
+        };
+
+        AuditLog::instance().record(&AuditRecord {
+            timestamp: Utc::now(),
+            transport: "grpc".to_string(),
+            method: method.to_string(),
+            endpoint: self.channel.active_endpoint().unwrap_or_else(|| self.endpoint.clone()),
+            params,
+            latency_ms: started.elapsed().as_millis() as u64,
+            status,
+            token_usage: None,
+        });
+    }
+
+    /// Probe the serving status of one service via grpc.health.v1.
+    /// An empty service name asks about the server as a whole.
+    pub async fn check_health(&mut self, service: &str) -> Result<HealthStatus> {
+        let started = Instant::now();
+        let result = self.send_check_health(service).await;
+        self.audit("Health.Check", json!({ "service": service }), started, &result);
+        result
+    }
+
+    pub(crate) async fn send_check_health(&self, service: &str) -> Result<HealthStatus> {
+        let request_key = json!({ "service": service });
+        if let Some(recorded) =
+            replay_proto::<health::HealthCheckResponse>("Health.Check", &request_key)
+        {
+            return recorded.map(|response| HealthStatus::from_code(response.status));
+        }
+
+        let request = HealthCheckRequest { service: service.to_string() };
+        let mut client = self.health_client().await?;
+
+        let result = match client.check(Request::new(request.clone())).await {
+            Ok(response) => Ok(response.into_inner()),
+            // Servers that predate the health service answer Unimplemented;
+            // report that rather than failing the probe
+            Err(status) if status.code() == tonic::Code::Unimplemented => {
+                return Ok(HealthStatus::Unsupported);
+            }
+            Err(status) if is_transport_error(&status) => {
+                // Drop the broken channel and retry once on a fresh one
+                self.channel.invalidate().await;
+                let mut client = self.health_client().await?;
+                match client.check(Request::new(request)).await {
+                    Ok(response) => Ok(response.into_inner()),
+                    Err(status) if status.code() == tonic::Code::Unimplemented => {
+                        return Ok(HealthStatus::Unsupported);
+                    }
+                    Err(status) => Err(GraphOsError::from(status)),
+                }
+            }
+            Err(status) if status.code() == tonic::Code::Unauthenticated => {
+                // Refresh the rejected token and retry once with the
+                // fresh one; without refresh credentials the auth
+                // error stands
+                if self.refresh_auth().await {
+                    let mut client = self.health_client().await?;
+                    client.check(Request::new(request))
+                        .await
+                        .map(|response| response.into_inner())
+                        .map_err(GraphOsError::from)
+                } else {
+                    Err(GraphOsError::from(status))
                 }
             }
+            Err(status) => Err(GraphOsError::from(status)),
+        };
+
+        if let Ok(response) = &result {
+            record_proto("Health.Check", &request_key, response);
+        }
+        result.map(|response| HealthStatus::from_code(response.status))
     }
 
     /// Get current system information
     pub async fn get_system_info(&mut self) -> Result<SystemInfo> {
-        let request = Request::new(GetSystemInfoRequest {});
-        
-        let response = self.client.get_system_info(request)
-            .await
-            .map_err(|e| anyhow!("gRPC error: {}", e))?;
-            
-        Ok(response.into_inner())
+        let started = Instant::now();
+        let result = self.send_get_system_info().await;
+        self.audit("SystemInfoService.GetSystemInfo", json!({}), started, &result);
+        result
+    }
+
+    pub(crate) async fn send_get_system_info(&self) -> Result<SystemInfo> {
+        let request_key = json!({});
+        if let Some(recorded) = replay_proto("SystemInfoService.GetSystemInfo", &request_key) {
+            return recorded;
+        }
+
+        let mut client = self.system_info_client().await?;
+
+        let result = match client.get_system_info(Request::new(GetSystemInfoRequest {})).await {
+            Ok(response) => Ok(response.into_inner()),
+            Err(status) if is_transport_error(&status) => {
+                // Drop the broken channel and retry once on a fresh one
+                self.channel.invalidate().await;
+                let mut client = self.system_info_client().await?;
+                client.get_system_info(Request::new(GetSystemInfoRequest {}))
+                    .await
+                    .map(|response| response.into_inner())
+                    .map_err(GraphOsError::from)
+            }
+            Err(status) if status.code() == tonic::Code::Unauthenticated => {
+                // Refresh the rejected token and retry once with the
+                // fresh one; without refresh credentials the auth
+                // error stands
+                if self.refresh_auth().await {
+                    let mut client = self.system_info_client().await?;
+                    client.get_system_info(Request::new(GetSystemInfoRequest {}))
+                        .await
+                        .map(|response| response.into_inner())
+                        .map_err(GraphOsError::from)
+                } else {
+                    Err(GraphOsError::from(status))
+                }
+            }
+            Err(status) => Err(GraphOsError::from(status)),
+        };
+
+        if let Ok(info) = &result {
+            record_proto("SystemInfoService.GetSystemInfo", &request_key, info);
+        }
+        result
     }
 
     /// Get historical system information
     pub async fn list_system_info(&mut self, limit: Option<i32>, since: Option<i64>) -> Result<SystemInfoList> {
-        let request = Request::new(ListSystemInfoRequest {
+        let started = Instant::now();
+        let result = self.send_list_system_info(limit, since).await;
+
+        self.audit(
+            "SystemInfoService.ListSystemInfo",
+            json!({ "limit": limit, "since": since }),
+            started,
+            &result,
+        );
+
+        result
+    }
+
+    pub(crate) async fn send_list_system_info(&self, limit: Option<i32>, since: Option<i64>) -> Result<SystemInfoList> {
+        let request_key = json!({ "limit": limit, "since": since });
+        if let Some(recorded) = replay_proto("SystemInfoService.ListSystemInfo", &request_key) {
+            return recorded;
+        }
+
+        let request = ListSystemInfoRequest {
             limit: limit.unwrap_or(0),
             since: since.unwrap_or(0),
+        };
+
+        let mut client = self.system_info_client().await?;
+
+        let result = match client.list_system_info(Request::new(request.clone())).await {
+            Ok(response) => Ok(response.into_inner()),
+            Err(status) if is_transport_error(&status) => {
+                // Drop the broken channel and retry once on a fresh one
+                self.channel.invalidate().await;
+                let mut client = self.system_info_client().await?;
+                client.list_system_info(Request::new(request))
+                    .await
+                    .map(|response| response.into_inner())
+                    .map_err(GraphOsError::from)
+            }
+            Err(status) if status.code() == tonic::Code::Unauthenticated => {
+                // Refresh the rejected token and retry once with the
+                // fresh one; without refresh credentials the auth
+                // error stands
+                if self.refresh_auth().await {
+                    let mut client = self.system_info_client().await?;
+                    client.list_system_info(Request::new(request))
+                        .await
+                        .map(|response| response.into_inner())
+                        .map_err(GraphOsError::from)
+                } else {
+                    Err(GraphOsError::from(status))
+                }
+            }
+            Err(status) => Err(GraphOsError::from(status)),
+        };
+
+        if let Ok(list) = &result {
+            record_proto("SystemInfoService.ListSystemInfo", &request_key, list);
+        }
+        result
+    }
+
+    /// Call an arbitrary unary method described by a user-supplied
+    /// proto file, encoding and decoding messages at runtime. The
+    /// usual auth metadata and deadline apply.
+    pub async fn call_dynamic(
+        &mut self,
+        method: &prost_reflect::MethodDescriptor,
+        request: prost_reflect::DynamicMessage,
+    ) -> Result<prost_reflect::DynamicMessage> {
+        let service = method.parent_service();
+        let full_method = format!("{}.{}", service.full_name(), method.name());
+
+        let started = Instant::now();
+        let result = self.send_call_dynamic(method, request).await;
+        self.audit(&full_method, json!({ "dynamic": true }), started, &result);
+        result
+    }
+
+    async fn send_call_dynamic(
+        &self,
+        method: &prost_reflect::MethodDescriptor,
+        request: prost_reflect::DynamicMessage,
+    ) -> Result<prost_reflect::DynamicMessage> {
+        use super::dynamic::DynamicCodec;
+
+        if method.is_client_streaming() || method.is_server_streaming() {
+            return Err(GraphOsError::Config(format!(
+                "{} is a streaming method; only unary methods can be called dynamically",
+                method.name()
+            )));
+        }
+
+        let service = method.parent_service();
+        let path: tonic::codegen::http::uri::PathAndQuery =
+            format!("/{}/{}", service.full_name(), method.name())
+                .parse()
+                .map_err(|_| GraphOsError::Config(format!("Invalid method path for {}", method.name())))?;
+
+        let grpc_call = |channel: Channel| {
+            let path = path.clone();
+            let request = request.clone();
+            let interceptor = self.interceptor.clone();
+            let codec = DynamicCodec::new(method.output());
+            async move {
+                let mut grpc = tonic::client::Grpc::new(InterceptedService::new(channel, interceptor));
+                grpc.ready()
+                    .await
+                    .map_err(|e| Status::unknown(format!("Service was not ready: {}", e)))?;
+                grpc.unary(Request::new(request), path, codec).await
+            }
+        };
+
+        match grpc_call(self.channel.get().await?).await {
+            Ok(response) => Ok(response.into_inner()),
+            Err(status) if is_transport_error(&status) => {
+                // Drop the broken channel and retry once on a fresh one
+                self.channel.invalidate().await;
+                grpc_call(self.channel.get().await?)
+                    .await
+                    .map(|response| response.into_inner())
+                    .map_err(GraphOsError::from)
+            }
+            Err(status) if status.code() == tonic::Code::Unauthenticated => {
+                // Refresh the rejected token and retry once; the call
+                // builds a fresh interceptor clone, which sees it
+                if self.refresh_auth().await {
+                    grpc_call(self.channel.get().await?)
+                        .await
+                        .map(|response| response.into_inner())
+                        .map_err(GraphOsError::from)
+                } else {
+                    Err(GraphOsError::from(status))
+                }
+            }
+            Err(status) => Err(GraphOsError::from(status)),
+        }
+    }
+
+    /// Send a conversation over the bidirectional chat stream and forward
+    /// response tokens through the provided channel
+    pub async fn chat_stream(
+        &mut self,
+        messages: Vec<ChatMessage>,
+        model: Option<String>,
+        sender: mpsc::Sender<String>,
+    ) -> Result<()> {
+        let started = Instant::now();
+        let params = json!({
+            "messages": "[redacted]",
+            "message_count": messages.len(),
+            "model": model,
         });
-        
-        let response = self.client.list_system_info(request)
-            .await
-            .map_err(|e| anyhow!("gRPC error: {}", e))?;
-            
-        Ok(response.into_inner())
+        let result = self.send_chat_stream(messages, model, sender).await;
+        self.audit("ChatService.Chat", params, started, &result);
+        result
+    }
+
+    /// Drive the bidirectional chat stream without audit bookkeeping
+    async fn send_chat_stream(
+        &mut self,
+        messages: Vec<ChatMessage>,
+        model: Option<String>,
+        sender: mpsc::Sender<String>,
+    ) -> Result<()> {
+        // Chat responses stream as plain text, so the recording stores the
+        // full transcript rather than an encoded protobuf message
+        let request_key = json!({
+            "messages": messages
+                .iter()
+                .map(|m| json!({ "role": m.role, "content": m.content }))
+                .collect::<Vec<_>>(),
+            "model": model,
+        });
+        if let Some(recorded) = crate::adapters::recording::replay("grpc", "ChatService.Chat", &request_key) {
+            let content = recorded
+                .map_err(|e| GraphOsError::Decode(e.to_string()))?
+                .get("content")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string();
+            if !content.is_empty() {
+                sender.send(content).await.ok();
+            }
+            return Ok(());
+        }
+
+        // The client side sends a single request carrying the conversation;
+        // the server streams tokens back until it marks the turn done
+        let request = ChatRequest {
+            messages,
+            model: model.unwrap_or_default(),
+        };
+        let mut chat_client = self.chat_service_client().await?;
+        let outbound = futures_util::stream::iter(vec![request.clone()]);
+
+        let response = match chat_client.chat(Request::new(outbound)).await {
+            Ok(response) => response,
+            Err(status) if is_transport_error(&status) => {
+                // Drop the broken channel and retry the stream once; nothing
+                // has been delivered yet so the retry is safe
+                self.channel.invalidate().await;
+                let mut chat_client = self.chat_service_client().await?;
+                let outbound = futures_util::stream::iter(vec![request]);
+                chat_client.chat(Request::new(outbound))
+                    .await
+                    .map_err(GraphOsError::from)?
+            }
+            Err(status) if status.code() == tonic::Code::Unauthenticated => {
+                // Refresh the rejected token and retry the stream once;
+                // nothing has been delivered yet so the retry is safe
+                if !self.refresh_auth().await {
+                    return Err(GraphOsError::from(status));
+                }
+                let mut chat_client = self.chat_service_client().await?;
+                let outbound = futures_util::stream::iter(vec![request]);
+                chat_client.chat(Request::new(outbound))
+                    .await
+                    .map_err(GraphOsError::from)?
+            }
+            Err(status) => return Err(GraphOsError::from(status)),
+        };
+
+        let mut inbound = response.into_inner();
+        let mut transcript = String::new();
+
+        loop {
+            let chunk = match inbound.message().await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(status) => {
+                    // Mid-stream failures cannot be replayed; surface the
+                    // error and let the next call reconnect
+                    if is_transport_error(&status) {
+                        self.channel.invalidate().await;
+                    }
+                    return Err(GraphOsError::from(status));
+                }
+            };
+
+            if !chunk.content.is_empty() {
+                transcript.push_str(&chunk.content);
+                // Send the content through the channel
+                if sender.send(chunk.content).await.is_err() {
+                    // Channel closed, stop processing
+                    return Ok(());
+                }
+            }
+
+            if chunk.done {
+                break;
+            }
+        }
+
+        crate::adapters::recording::record(
+            "grpc",
+            "ChatService.Chat",
+            &request_key,
+            &json!({ "content": transcript }),
+        );
+
+        Ok(())
     }
 }
 
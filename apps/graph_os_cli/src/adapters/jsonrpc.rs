@@ -1,12 +1,20 @@
-use anyhow::Error;
+use chrono::Utc;
 use futures_util::StreamExt;
-use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_TYPE};
-use reqwest::{Client, Response};
+use std::collections::HashMap;
+use std::time::Instant;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE};
+use reqwest::{Certificate, Client, Proxy, Response};
+use std::path::PathBuf;
+use std::time::Duration;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
+use crate::adapters::translate::{self, Dialect};
+use crate::audit::{redact_params, AuditLog, AuditRecord};
+use crate::error::{GraphOsError, Result};
+
 /// A message role for conversation context
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MessageRole {
@@ -18,14 +26,402 @@ pub enum MessageRole {
     System,
 }
 
+/// One part of a multi-part message body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    /// Plain text
+    Text { text: String },
+    /// A reference to an image by URL
+    ImageUrl { url: String },
+    /// An inline base64-encoded image
+    Image { media_type: String, data: String },
+}
+
+/// Message content: either plain text or a typed multi-part body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+impl From<String> for MessageContent {
+    fn from(text: String) -> Self {
+        MessageContent::Text(text)
+    }
+}
+
+impl From<&str> for MessageContent {
+    fn from(text: &str) -> Self {
+        MessageContent::Text(text.to_string())
+    }
+}
+
+impl MessageContent {
+    /// Flatten the content to plain text, dropping image parts
+    pub fn as_text(&self) -> String {
+        match self {
+            MessageContent::Text(text) => text.clone(),
+            MessageContent::Parts(parts) => {
+                let texts: Vec<&str> = parts
+                    .iter()
+                    .filter_map(|part| match part {
+                        ContentPart::Text { text } => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                texts.join("\n")
+            }
+        }
+    }
+
+    /// Render the content for terminal display, drawing images inline when
+    /// the terminal supports it and saving them to temp files otherwise
+    pub fn render_for_terminal(&self) -> String {
+        use base64::Engine;
+
+        match self {
+            MessageContent::Text(text) => text.clone(),
+            MessageContent::Parts(parts) => {
+                let mut rendered = Vec::new();
+                for part in parts {
+                    match part {
+                        ContentPart::Text { text } => rendered.push(text.clone()),
+                        ContentPart::ImageUrl { url } => {
+                            rendered.push(format!("[image: {}]", url));
+                        }
+                        ContentPart::Image { media_type, data } => {
+                            let name = format!("image.{}", media_type.rsplit('/').next().unwrap_or("png"));
+                            match base64::engine::general_purpose::STANDARD.decode(data) {
+                                Ok(bytes) => rendered.push(crate::terminal::render_image(&bytes, &name)),
+                                Err(e) => rendered.push(format!("[invalid image data: {}]", e)),
+                            }
+                        }
+                    }
+                }
+                rendered.join("\n")
+            }
+        }
+    }
+}
+
 /// A message in a conversation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub role: MessageRole,
-    pub content: String,
+    pub content: MessageContent,
+}
+
+/// Which HTTP version to speak to an endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HttpVersion {
+    /// Try HTTP/2 first (prior knowledge, as the GraphOS backend
+    /// speaks h2c) and fall back to HTTP/1.1 when the server turns
+    /// out not to understand it
+    #[default]
+    Auto,
+    /// HTTP/1.1 only, e.g. behind a reverse proxy without h2c support
+    Http1,
+    /// HTTP/2 only, with no fallback
+    Http2,
+}
+
+impl HttpVersion {
+    /// Parse the `http_version` endpoint config value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(HttpVersion::Auto),
+            "http1" | "http1.1" | "1.1" => Some(HttpVersion::Http1),
+            "http2" | "h2" | "2" => Some(HttpVersion::Http2),
+            _ => None,
+        }
+    }
 }
 
-/// A JSONRPC client for communicating with the API over HTTP/2
+/// Options controlling how the underlying HTTP client is built
+#[derive(Debug, Clone, Default)]
+pub struct HttpClientOptions {
+    /// Explicit proxy URL (http://, https:// or socks5://); HTTP_PROXY
+    /// and HTTPS_PROXY from the environment are honored automatically
+    /// when this is unset
+    pub proxy: Option<String>,
+    /// Path to a PEM bundle of additional root certificates
+    pub ca_bundle: Option<PathBuf>,
+    /// Accept invalid TLS certificates (development only)
+    pub danger_accept_invalid_certs: bool,
+    /// Maximum idle connections kept per host
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long idle connections are kept alive
+    pub pool_idle_timeout: Option<Duration>,
+    /// Extra headers attached to every request, e.g. for reverse proxies
+    pub extra_headers: HashMap<String, String>,
+    /// Query parameters appended to the endpoint URL
+    pub query_params: HashMap<String, String>,
+    /// Compression: None advertises gzip and zstd for responses only,
+    /// Some(true) also gzips request bodies over the threshold, and
+    /// Some(false) disables both directions
+    pub compress: Option<bool>,
+    /// Request body size in bytes below which compression is skipped
+    pub compress_threshold: Option<usize>,
+    /// Key id for HMAC request signing
+    pub signing_key_id: Option<String>,
+    /// Shared key for HMAC-SHA256 request signing; requests are signed
+    /// only when both this and the key id are set
+    pub signing_key: Option<String>,
+    /// Which HTTP version to speak; Auto negotiates down to HTTP/1.1
+    /// when the server refuses HTTP/2
+    pub http_version: HttpVersion,
+    /// Refreshes the bearer token after a 401, when the endpoint has
+    /// OIDC refresh credentials
+    pub refresher: Option<crate::auth::TokenRefresher>,
+}
+
+impl HttpClientOptions {
+    /// Build options from environment variables
+    pub fn from_env() -> Self {
+        let proxy = std::env::var("HTTPS_PROXY")
+            .or_else(|_| std::env::var("HTTP_PROXY"))
+            .ok();
+        let ca_bundle = std::env::var("GRAPHOS_CA_BUNDLE").ok().map(PathBuf::from);
+        let danger_accept_invalid_certs =
+            std::env::var("GRAPHOS_ACCEPT_INVALID_CERTS").is_ok_and(|v| v == "1" || v == "true");
+
+        Self {
+            proxy,
+            ca_bundle,
+            danger_accept_invalid_certs,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            extra_headers: HashMap::new(),
+            query_params: HashMap::new(),
+            compress: None,
+            compress_threshold: None,
+            signing_key_id: None,
+            signing_key: None,
+            http_version: HttpVersion::default(),
+            refresher: None,
+        }
+    }
+
+    /// Overlay settings from an endpoint configuration, which wins over env
+    pub fn merge_endpoint(mut self, endpoint: Option<&crate::config::EndpointConfig>) -> Self {
+        if let Some(endpoint) = endpoint {
+            if let Some(ca_bundle) = &endpoint.ca_bundle {
+                self.ca_bundle = Some(PathBuf::from(ca_bundle));
+            }
+            if let Some(accept) = endpoint.danger_accept_invalid_certs {
+                self.danger_accept_invalid_certs = accept;
+            }
+            if let Some(proxy) = &endpoint.proxy {
+                self.proxy = Some(proxy.clone());
+            }
+            if !endpoint.extra_headers.is_empty() {
+                self.extra_headers = endpoint.extra_headers.clone();
+            }
+            if !endpoint.query_params.is_empty() {
+                self.query_params = endpoint.query_params.clone();
+            }
+            if endpoint.compress.is_some() {
+                self.compress = endpoint.compress;
+            }
+            if let Some(threshold) = endpoint.compress_threshold {
+                self.compress_threshold = Some(threshold as usize);
+            }
+            if endpoint.signing_key_id.is_some() {
+                self.signing_key_id = endpoint.signing_key_id.clone();
+            }
+            if endpoint.signing_key.is_some() {
+                self.signing_key = endpoint.signing_key.clone();
+            }
+            if let Some(version) = &endpoint.http_version {
+                match HttpVersion::parse(version) {
+                    Some(parsed) => self.http_version = parsed,
+                    None => eprintln!(
+                        "Ignoring unknown http_version '{}' (expected auto, http1 or http2)",
+                        version
+                    ),
+                }
+            }
+            // Every merge site overlays the default endpoint, so a
+            // refreshed token persists under that name
+            self.refresher = crate::auth::TokenRefresher::for_endpoint("default", endpoint);
+        }
+        self
+    }
+}
+
+/// Keep-alive cadence for pooled connections: TCP probes and HTTP/2
+/// PING frames at this interval keep idle connections open and detect
+/// dead ones before a real request is waiting on them
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long an unanswered HTTP/2 keep-alive PING is given before the
+/// connection is declared dead and dropped from the pool
+const KEEP_ALIVE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default lifetime of idle pooled connections. Long enough that a
+/// thinking pause between messages does not cost a fresh TCP+TLS+HTTP/2
+/// setup on the next send.
+const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The builder-relevant subset of [`HttpClientOptions`]: two options
+/// values producing the same key can safely share one reqwest client.
+/// Headers and query params are attached per request, so they stay out
+/// of the key.
+#[derive(Clone, PartialEq, Eq)]
+struct ClientKey {
+    proxy: Option<String>,
+    ca_bundle: Option<PathBuf>,
+    danger_accept_invalid_certs: bool,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    version: HttpVersion,
+}
+
+impl ClientKey {
+    fn from_options(options: &HttpClientOptions, version: HttpVersion) -> Self {
+        Self {
+            proxy: options.proxy.clone(),
+            ca_bundle: options.ca_bundle.clone(),
+            danger_accept_invalid_certs: options.danger_accept_invalid_certs,
+            pool_max_idle_per_host: options.pool_max_idle_per_host,
+            pool_idle_timeout: options.pool_idle_timeout,
+            version,
+        }
+    }
+}
+
+/// Built clients keyed by their transport options, so every provider —
+/// including the fallback chain — reuses one connection pool per
+/// distinct transport setup instead of each warming its own
+static CLIENT_CACHE: std::sync::OnceLock<std::sync::Mutex<Vec<(ClientKey, Client)>>> =
+    std::sync::OnceLock::new();
+
+/// Build a reqwest client honoring proxy, TLS and pool options.
+/// Clients are cached per distinct transport setup (a reqwest Client is
+/// an Arc around its pool), so providers sharing a proxy and TLS setup
+/// also share warm connections.
+/// The `version` here is a build flavor: Http2 forces prior knowledge,
+/// Http1 pins 1.1, and Auto builds a plain negotiating client — the
+/// fallback half of an Auto endpoint, whose primary half is an Http2
+/// build.
+fn build_http_client(options: &HttpClientOptions, version: HttpVersion) -> Client {
+    let key = ClientKey::from_options(options, version);
+    let cache = CLIENT_CACHE.get_or_init(Default::default);
+    if let Ok(cache) = cache.lock()
+        && let Some((_, client)) = cache.iter().find(|(k, _)| *k == key)
+    {
+        return client.clone();
+    }
+
+    let mut builder = Client::builder()
+        // Keep pooled connections alive and verified so the next
+        // message rides an existing stream instead of paying connection
+        // setup in time-to-first-token
+        .tcp_keepalive(KEEP_ALIVE_INTERVAL)
+        .pool_idle_timeout(options.pool_idle_timeout.unwrap_or(DEFAULT_POOL_IDLE_TIMEOUT));
+
+    builder = match version {
+        HttpVersion::Http2 => builder.http2_prior_knowledge(),
+        HttpVersion::Http1 => builder.http1_only(),
+        // ALPN over TLS, HTTP/1.1 over plain connections
+        HttpVersion::Auto => builder,
+    };
+    if version != HttpVersion::Http1 {
+        builder = builder
+            .http2_keep_alive_interval(KEEP_ALIVE_INTERVAL)
+            .http2_keep_alive_timeout(KEEP_ALIVE_TIMEOUT)
+            .http2_keep_alive_while_idle(true)
+            // Scale the receive window with the link instead of
+            // stalling large streamed responses on a fixed 64 KiB window
+            .http2_adaptive_window(true);
+    }
+
+    if let Some(proxy) = &options.proxy {
+        match Proxy::all(proxy) {
+            Ok(proxy) => builder = builder.proxy(proxy),
+            Err(e) => eprintln!("Ignoring invalid proxy URL: {}", e),
+        }
+    }
+
+    if let Some(ca_bundle) = &options.ca_bundle {
+        match std::fs::read(ca_bundle).map_err(anyhow::Error::from).and_then(|pem| {
+            Certificate::from_pem_bundle(&pem).map_err(anyhow::Error::from)
+        }) {
+            Ok(certs) => {
+                for cert in certs {
+                    builder = builder.add_root_certificate(cert);
+                }
+            }
+            Err(e) => eprintln!("Ignoring unreadable CA bundle {}: {}", ca_bundle.display(), e),
+        }
+    }
+
+    if options.danger_accept_invalid_certs {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    if let Some(max_idle) = options.pool_max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(max_idle);
+    }
+
+    let client = builder.build().expect("Failed to create HTTP client");
+    if let Ok(mut cache) = cache.lock() {
+        cache.push((key, client.clone()));
+    }
+    client
+}
+
+/// The full error text of a reqwest failure, sources included —
+/// reqwest's Display alone often just says "error sending request"
+fn error_chain_text(e: &reqwest::Error) -> String {
+    let mut text = e.to_string();
+    let mut source = std::error::Error::source(e);
+    while let Some(cause) = source {
+        text.push_str(": ");
+        text.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    text
+}
+
+/// Whether a send failure looks like the server not speaking our HTTP
+/// version, as opposed to not being reachable at all. A refused or
+/// timed-out connection is not a protocol problem; an established
+/// connection that then chokes on HTTP/2 framing is.
+fn looks_like_protocol_mismatch(e: &reqwest::Error) -> bool {
+    if e.is_connect() || e.is_timeout() {
+        return false;
+    }
+    let chain = error_chain_text(e).to_lowercase();
+    chain.contains("http2") || chain.contains("frame") || chain.contains("go_away")
+}
+
+/// Turn a reqwest failure into a [`GraphOsError`] that says what is
+/// actually wrong instead of hyper's framing internals
+fn describe_transport_error(endpoint: &str, e: reqwest::Error) -> GraphOsError {
+    if e.is_connect() {
+        return GraphOsError::Transport(format!(
+            "Could not connect to {} ({}). Is the server running?",
+            endpoint,
+            error_chain_text(&e)
+        ));
+    }
+    if looks_like_protocol_mismatch(&e) {
+        return GraphOsError::Transport(format!(
+            "{} answered, but not in the expected HTTP version ({}). \
+             It may be an HTTP/1.1-only reverse proxy; set http_version = \"http1\" \
+             (or \"auto\") on this endpoint.",
+            endpoint,
+            error_chain_text(&e)
+        ));
+    }
+    GraphOsError::from(e)
+}
+
+/// A JSONRPC client for communicating with the API, leading with
+/// HTTP/2 and negotiating down to HTTP/1.1 when the endpoint allows it
 #[derive(Clone)]
 pub struct JsonRpcClient {
     client: Client,
@@ -33,6 +429,52 @@ pub struct JsonRpcClient {
     pub api_key: Option<String>,
     pub model: Option<String>,
     pub rpc_secret: Option<String>,
+    /// Extra headers from the endpoint config, pre-validated so send
+    /// paths can attach them without re-parsing
+    extra_headers: HeaderMap,
+    /// Probed request dialect, shared across clones so the endpoint is
+    /// only probed once per process
+    dialect: std::sync::Arc<tokio::sync::OnceCell<Dialect>>,
+    /// Whether to advertise gzip and zstd for responses (Accept-Encoding)
+    accept_compressed: bool,
+    /// Whether to gzip request bodies over the threshold
+    compress_requests: bool,
+    /// Request body size in bytes below which compression is skipped
+    compress_threshold: usize,
+    /// Where server-initiated notifications found on streaming
+    /// connections are delivered; None drops them silently
+    notification_sender: Option<mpsc::Sender<ServerNotification>>,
+    /// HMAC signer for request bodies, when the endpoint configures one
+    signer: Option<crate::signing::RequestSigner>,
+    /// HTTP/1.1-capable fallback client, present in Auto mode
+    http1_fallback: Option<Client>,
+    /// Set once the fallback succeeded, so later requests skip the
+    /// doomed HTTP/2 attempt; shared across clones like the dialect
+    http1_active: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    /// Refreshes the bearer token after a 401, when the endpoint has
+    /// OIDC refresh credentials
+    refresher: Option<crate::auth::TokenRefresher>,
+    /// Token minted by a mid-session refresh, sent in place of api_key;
+    /// shared across clones so one refresh serves them all
+    bearer_override: std::sync::Arc<std::sync::RwLock<Option<String>>>,
+}
+
+/// Whether a response declares a gzip-compressed body
+/// Whether stream parse diagnostics go to stderr, from the GOS_DEBUG
+/// environment variable
+fn stream_debug() -> bool {
+    std::env::var("GOS_DEBUG")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// The Content-Encoding a response declares, when it is one of ours
+fn response_encoding(response: &Response) -> Option<crate::compress::Encoding> {
+    response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .and_then(crate::compress::Encoding::parse)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -51,14 +493,6 @@ pub struct JsonRpcResponse {
     id: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct JsonRpcStreamChunk {
-    jsonrpc: String,
-    result: Option<Value>,
-    error: Option<JsonRpcError>,
-    id: String,
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JsonRpcError {
     code: i32,
@@ -66,47 +500,511 @@ pub struct JsonRpcError {
     data: Option<Value>,
 }
 
+/// A server-initiated JSON-RPC notification: a frame with a `method`
+/// but no `id`, so it answers nothing the client asked. Servers use
+/// these on streaming connections for out-of-band messages such as
+/// maintenance notices or context purge warnings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerNotification {
+    /// The notification method, e.g. "server.maintenance"
+    pub method: String,
+    /// The notification's params, verbatim
+    pub params: Option<Value>,
+}
+
+impl ServerNotification {
+    /// Recognize the notification shape in a parsed frame. Requests
+    /// and responses carry an `id`; a `method` without one is a
+    /// notification per JSON-RPC 2.0.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let method = value.get("method").and_then(Value::as_str)?;
+        if value.get("id").is_some_and(|id| !id.is_null()) {
+            return None;
+        }
+        Some(ServerNotification {
+            method: method.to_string(),
+            params: value.get("params").cloned(),
+        })
+    }
+
+    /// One-line text for banners: the params' message when the server
+    /// sent one, otherwise the method name
+    pub fn summary(&self) -> String {
+        self.params
+            .as_ref()
+            .and_then(|p| p.get("message").or_else(|| p.get("text")))
+            .and_then(Value::as_str)
+            .map(|text| format!("{}: {}", self.method, text))
+            .unwrap_or_else(|| self.method.clone())
+    }
+}
+
+/// One typed event parsed out of a streaming chat response, regardless
+/// of what else rode in the same frame: a single NDJSON line can carry
+/// content, a finish reason and a usage block at once, and each becomes
+/// its own event
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent {
+    /// A piece of assistant text
+    ContentDelta(String),
+    /// A piece of a streamed tool call; the name arrives on the first
+    /// fragment, the arguments accumulate across fragments
+    ToolCallDelta {
+        name: Option<String>,
+        arguments: String,
+    },
+    /// Token usage reported by the server, usually on the final frame
+    Usage {
+        prompt_tokens: Option<u64>,
+        completion_tokens: Option<u64>,
+    },
+    /// The server marked the stream finished
+    Done { finish_reason: Option<String> },
+    /// A server-initiated notification rode the stream; it is not part
+    /// of the answer
+    Notification(ServerNotification),
+    /// The server reported an application-level error
+    Error {
+        code: i32,
+        message: String,
+        data: Option<Value>,
+    },
+    /// A frame that was not valid JSON; carried as data instead of
+    /// being dropped so debug mode can show what the server actually
+    /// sent
+    Malformed { snippet: String, error: String },
+}
+
+/// Incremental parser for newline-delimited JSON streams. Bytes go in
+/// as they arrive from the network — including chunks that end mid-JSON
+/// — and complete frames come out as typed [`StreamEvent`]s; a partial
+/// trailing line is buffered until its newline arrives. Pure, so the
+/// chunk-boundary handling is testable without a server.
+#[derive(Debug, Default)]
+pub struct NdjsonStreamParser {
+    buffer: Vec<u8>,
+}
+
+impl NdjsonStreamParser {
+    pub fn new() -> Self {
+        NdjsonStreamParser::default()
+    }
+
+    /// Feed bytes from the wire, returning the events for every line
+    /// completed by this chunk
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<StreamEvent> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut events = Vec::new();
+        let mut start = 0;
+        for i in 0..self.buffer.len() {
+            if self.buffer[i] == b'\n' {
+                if i > start {
+                    events.extend(parse_stream_frame(&self.buffer[start..i]));
+                }
+                start = i + 1;
+            }
+        }
+        if start > 0 {
+            self.buffer.drain(0..start);
+        }
+        events
+    }
+
+    /// Flush at end of stream: a final frame without a trailing newline
+    /// is still parsed instead of silently discarded
+    pub fn finish(&mut self) -> Vec<StreamEvent> {
+        let remainder = std::mem::take(&mut self.buffer);
+        if remainder.iter().all(u8::is_ascii_whitespace) {
+            return Vec::new();
+        }
+        parse_stream_frame(&remainder)
+    }
+}
+
+/// Parse one complete NDJSON frame into its events. Frames may be bare
+/// payloads or JSON-RPC envelopes with the payload under `result`;
+/// role-only deltas produce no events, and unparseable frames become a
+/// single [`StreamEvent::Malformed`].
+fn parse_stream_frame(frame: &[u8]) -> Vec<StreamEvent> {
+    let value: Value = match serde_json::from_slice(frame) {
+        Ok(value) => value,
+        Err(e) => {
+            let snippet: String = String::from_utf8_lossy(frame).chars().take(120).collect();
+            return vec![StreamEvent::Malformed {
+                snippet,
+                error: e.to_string(),
+            }];
+        }
+    };
+
+    // A JSON-RPC error envelope is terminal and carries nothing else
+    if let Some(error) = value.get("error").filter(|e| !e.is_null()) {
+        return vec![StreamEvent::Error {
+            code: error.get("code").and_then(Value::as_i64).unwrap_or(0) as i32,
+            message: error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown error")
+                .to_string(),
+            data: error.get("data").cloned(),
+        }];
+    }
+
+    // A method without an id is a server-initiated notification, not
+    // part of the answer
+    if let Some(notification) = ServerNotification::from_value(&value) {
+        return vec![StreamEvent::Notification(notification)];
+    }
+
+    // Unwrap the JSON-RPC envelope when present; OpenAI-style frames
+    // additionally nest the interesting fields under choices[0]
+    let result = value.get("result").unwrap_or(&value);
+    let delta = result
+        .pointer("/choices/0/delta")
+        .or_else(|| result.get("delta"))
+        .unwrap_or(result);
+
+    let mut events = Vec::new();
+
+    if let Some(text) = delta.get("content").and_then(Value::as_str)
+        && !text.is_empty()
+    {
+        events.push(StreamEvent::ContentDelta(text.to_string()));
+    }
+
+    // Tool call fragments: OpenAI nests them as tool_calls[].function,
+    // the GraphOS server sends a flat tool_call object
+    let tool_calls = delta
+        .get("tool_calls")
+        .and_then(Value::as_array)
+        .cloned()
+        .or_else(|| delta.get("tool_call").map(|call| vec![call.clone()]))
+        .unwrap_or_default();
+    for call in &tool_calls {
+        let function = call.get("function").unwrap_or(call);
+        events.push(StreamEvent::ToolCallDelta {
+            name: function
+                .get("name")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            arguments: function
+                .get("arguments")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        });
+    }
+
+    if let Some(usage) = result.get("usage").filter(|u| u.is_object()) {
+        events.push(StreamEvent::Usage {
+            prompt_tokens: usage.get("prompt_tokens").and_then(Value::as_u64),
+            completion_tokens: usage.get("completion_tokens").and_then(Value::as_u64),
+        });
+    }
+
+    let finish_reason = result
+        .pointer("/choices/0/finish_reason")
+        .or_else(|| result.get("finish_reason"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    if finish_reason.is_some() || result.get("done").and_then(Value::as_bool) == Some(true) {
+        events.push(StreamEvent::Done { finish_reason });
+    }
+
+    events
+}
+
 impl JsonRpcClient {
     /// Create a new JSONRPC client
     pub fn new(host: &str, port: u16, use_https: bool, api_key: Option<String>, model: Option<String>, rpc_secret: Option<String>) -> Self {
-        // Create a client with HTTP/2 enabled
-        let client = Client::builder()
-            .http2_prior_knowledge()  // Force HTTP/2
-            .build()
-            .expect("Failed to create HTTP client");
-
         // Construct the endpoint URL
         let scheme = if use_https { "https" } else { "http" };
         let endpoint = format!("{}://{}:{}/api/jsonrpc", scheme, host, port);
 
-        Self { 
-            client, 
-            endpoint,
-            api_key,
-            model,
-            rpc_secret,
-        }
+        Self::with_endpoint_options(endpoint, api_key, model, rpc_secret, &HttpClientOptions::from_env())
     }
     
     /// Create a new JSONRPC client from a custom endpoint
     pub fn with_endpoint(endpoint: String, api_key: Option<String>, model: Option<String>, rpc_secret: Option<String>) -> Self {
-        // Create a client with HTTP/2 enabled
-        let client = Client::builder()
-            .http2_prior_knowledge()  // Force HTTP/2
-            .build()
-            .expect("Failed to create HTTP client");
-
-        Self { 
-            client, 
+        Self::with_endpoint_options(endpoint, api_key, model, rpc_secret, &HttpClientOptions::from_env())
+    }
+    
+    /// Create a new JSONRPC client with explicit HTTP client options
+    pub fn with_endpoint_options(
+        endpoint: String,
+        api_key: Option<String>,
+        model: Option<String>,
+        rpc_secret: Option<String>,
+        options: &HttpClientOptions,
+    ) -> Self {
+        // Http1 pins the only client to 1.1; Auto and Http2 both lead
+        // with HTTP/2, Auto additionally keeping a negotiating client
+        // to retry with when the server refuses it
+        let client = match options.http_version {
+            HttpVersion::Http1 => build_http_client(options, HttpVersion::Http1),
+            _ => build_http_client(options, HttpVersion::Http2),
+        };
+        let http1_fallback = (options.http_version == HttpVersion::Auto)
+            .then(|| build_http_client(options, HttpVersion::Auto));
+
+        // Bake configured query params into the endpoint URL once
+        let endpoint = if options.query_params.is_empty() {
+            endpoint
+        } else {
+            match reqwest::Url::parse_with_params(&endpoint, options.query_params.iter()) {
+                Ok(url) => url.to_string(),
+                Err(e) => {
+                    eprintln!("Ignoring configured query params on invalid URL: {}", e);
+                    endpoint
+                }
+            }
+        };
+
+        // Validate configured headers here rather than failing every call
+        let mut extra_headers = HeaderMap::new();
+        for (name, value) in &options.extra_headers {
+            match (name.parse::<HeaderName>(), HeaderValue::from_str(value)) {
+                (Ok(name), Ok(value)) => {
+                    extra_headers.insert(name, value);
+                }
+                _ => eprintln!("Ignoring invalid extra header '{}'", name),
+            }
+        }
+
+        Self {
+            client,
             endpoint,
             api_key,
             model,
             rpc_secret,
+            extra_headers,
+            dialect: std::sync::Arc::new(tokio::sync::OnceCell::new()),
+            // Responses are accepted compressed unless explicitly
+            // disabled; request bodies only compress when opted in
+            accept_compressed: options.compress != Some(false),
+            compress_requests: options.compress == Some(true),
+            compress_threshold: options
+                .compress_threshold
+                .unwrap_or(crate::compress::DEFAULT_COMPRESS_THRESHOLD),
+            notification_sender: None,
+            // Signing only engages when the endpoint configures both
+            // the key id and the shared key
+            signer: match (&options.signing_key_id, &options.signing_key) {
+                (Some(key_id), Some(key)) => {
+                    Some(crate::signing::RequestSigner::new(key_id, key))
+                }
+                _ => None,
+            },
+            http1_fallback,
+            http1_active: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            refresher: options.refresher.clone(),
+            bearer_override: std::sync::Arc::new(std::sync::RwLock::new(None)),
+        }
+    }
+
+    /// The bearer token to send: a token minted by a mid-session
+    /// refresh wins over the key the client was built with
+    fn bearer_token(&self) -> Option<String> {
+        if let Ok(refreshed) = self.bearer_override.read()
+            && refreshed.is_some()
+        {
+            return refreshed.clone();
+        }
+        self.api_key.clone()
+    }
+
+    /// Deliver server-initiated notifications seen on streaming
+    /// connections to the given channel. Without a sender they are
+    /// logged in debug mode and otherwise dropped.
+    pub fn set_notification_sender(&mut self, sender: mpsc::Sender<ServerNotification>) {
+        self.notification_sender = Some(sender);
+    }
+
+    /// Advertise compressed responses when compression is not disabled
+    fn accept_encoding(&self, headers: &mut HeaderMap) {
+        if self.accept_compressed {
+            headers.insert(ACCEPT_ENCODING, HeaderValue::from_static(crate::compress::Encoding::ACCEPT));
+        }
+    }
+
+    /// Attach a JSON body, signing it when the endpoint configures a
+    /// key and gzipping it when the endpoint opted in and the payload
+    /// is large enough to be worth the framing
+    fn attach_body<T: serde::Serialize>(
+        &self,
+        builder: reqwest::RequestBuilder,
+        body: &T,
+    ) -> reqwest::RequestBuilder {
+        // Signing serializes here so the signed bytes are exactly the
+        // bytes sent. The signature covers the plain JSON: compression
+        // below is transfer encoding, verified after decompression.
+        if let Some(signer) = &self.signer
+            && let Ok(bytes) = serde_json::to_vec(body)
+        {
+            let mut builder = builder;
+            if let Ok(value) = HeaderValue::from_str(&signer.header_value(&bytes)) {
+                builder = builder.header(crate::signing::SIGNATURE_HEADER, value);
+            }
+            if self.compress_requests && bytes.len() >= self.compress_threshold {
+                let compressed = crate::compress::gzip_compress(&bytes);
+                if compressed.len() < bytes.len() {
+                    return builder
+                        .header(CONTENT_ENCODING, HeaderValue::from_static("gzip"))
+                        .body(compressed);
+                }
+            }
+            return builder.body(bytes);
+        }
+
+        if self.compress_requests
+            && let Ok(bytes) = serde_json::to_vec(body)
+            && bytes.len() >= self.compress_threshold
+        {
+            let compressed = crate::compress::gzip_compress(&bytes);
+            // Only send compressed when it actually shrank the payload
+            if compressed.len() < bytes.len() {
+                return builder
+                    .header(CONTENT_ENCODING, HeaderValue::from_static("gzip"))
+                    .body(compressed);
+            }
+            return builder.body(bytes);
+        }
+        builder.json(body)
+    }
+
+    /// POST a body to an explicit URL, negotiating the HTTP version in
+    /// Auto mode: a protocol-mismatch failure from the HTTP/2 client is
+    /// retried once over HTTP/1.1, and a success there sticks for the
+    /// rest of the process. Failures come back as errors that say
+    /// whether the server was absent or just spoke the wrong protocol.
+    async fn post_body_to<T: serde::Serialize>(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+        body: &T,
+    ) -> Result<Response> {
+        use std::sync::atomic::Ordering;
+
+        if let Some(fallback) = &self.http1_fallback
+            && self.http1_active.load(Ordering::Relaxed)
+        {
+            let builder = fallback.post(url).headers(headers);
+            return self
+                .attach_body(builder, body)
+                .send()
+                .await
+                .map_err(|e| describe_transport_error(url, e));
+        }
+
+        let builder = self.client.post(url).headers(headers.clone());
+        match self.attach_body(builder, body).send().await {
+            Ok(response) => Ok(response),
+            Err(e) if looks_like_protocol_mismatch(&e) && self.http1_fallback.is_some() => {
+                let fallback = self.http1_fallback.as_ref().unwrap();
+                let builder = fallback.post(url).headers(headers);
+                match self.attach_body(builder, body).send().await {
+                    Ok(response) => {
+                        self.http1_active.store(true, Ordering::Relaxed);
+                        Ok(response)
+                    }
+                    Err(e) => Err(describe_transport_error(url, e)),
+                }
+            }
+            Err(e) => Err(describe_transport_error(url, e)),
         }
     }
 
+    /// POST a JSON-RPC body with one retry on 401: when the
+    /// endpoint can refresh its token, mint a fresh one, swap it into
+    /// the Authorization header and resend. The new token is kept (and
+    /// shared with clones) so later requests do not pay another
+    /// round trip. Without refresh credentials the 401 response is
+    /// returned as-is for the caller's usual handling.
+    async fn post_body_refreshing<T: serde::Serialize>(
+        &self,
+        headers: HeaderMap,
+        body: &T,
+    ) -> Result<Response> {
+        let endpoint = self.endpoint.clone();
+        self.post_body_to_refreshing(&endpoint, headers, body).await
+    }
+
+    /// [`post_body_refreshing`](Self::post_body_refreshing) against an
+    /// explicit URL
+    async fn post_body_to_refreshing<T: serde::Serialize>(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+        body: &T,
+    ) -> Result<Response> {
+        let response = self.post_body_to(url, headers.clone(), body).await?;
+        if response.status() != reqwest::StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+        let Some(refresher) = &self.refresher else {
+            return Ok(response);
+        };
+
+        let token = match refresher.refresh_now().await {
+            Ok(token) => token,
+            Err(e) => {
+                // The 401 is the real story; the failed refresh is
+                // only context
+                eprintln!("Token refresh after HTTP 401 failed: {}", e);
+                return Ok(response);
+            }
+        };
+        if let Ok(mut refreshed) = self.bearer_override.write() {
+            *refreshed = Some(token.clone());
+        }
+
+        let mut headers = headers;
+        if let Ok(header_value) = HeaderValue::from_str(&format!("Bearer {}", token)) {
+            headers.insert("Authorization", header_value);
+        }
+        self.post_body_to(url, headers, body).await
+    }
+
+    /// Read a response body, transparently decompressing when the
+    /// server compressed it
+    async fn read_body(response: Response) -> Result<Vec<u8>> {
+        let encoding = response_encoding(&response);
+        let bytes = response.bytes().await?;
+        match encoding {
+            Some(encoding) => encoding.decompress(&bytes).map_err(GraphOsError::Decode),
+            None => Ok(bytes.to_vec()),
+        }
+    }
+
+    /// Which dialect the endpoint speaks, probing it on first use. A
+    /// JSON-RPC-shaped answer to ping (even an error) means GraphOS;
+    /// otherwise an endpoint that answers `GET /v1/models` is taken as
+    /// OpenAI-compatible. GraphOS remains the fallback so a server that
+    /// is simply down fails with JSON-RPC errors, not translated ones.
+    pub async fn dialect(&self) -> Dialect {
+        *self
+            .dialect
+            .get_or_init(|| async {
+                match self.send_request("ping", json!({})).await {
+                    Ok(_) | Err(GraphOsError::RpcError { .. }) => Dialect::GraphOs,
+                    Err(_) => {
+                        let mut probe = self.client.get(translate::models_url(&self.endpoint));
+                        if let Some(api_key) = self.bearer_token() {
+                            probe = probe.bearer_auth(api_key);
+                        }
+                        match probe.send().await {
+                            Ok(response) if response.status().is_success() => Dialect::OpenAi,
+                            _ => Dialect::GraphOs,
+                        }
+                    }
+                }
+            })
+            .await
+    }
+
     /// Ping the server to check connectivity
-    pub async fn ping(&self) -> Result<bool, Error> {
+    pub async fn ping(&self) -> Result<bool> {
         match self.request("ping", json!({})).await {
             Ok(_) => Ok(true),
             Err(e) => {
@@ -116,8 +1014,213 @@ impl JsonRpcClient {
         }
     }
 
+    /// Open (or revalidate) the pooled connection with a throwaway
+    /// ping, so the next real request starts on a live HTTP/2 stream
+    /// instead of paying TCP+TLS+HTTP/2 setup in time-to-first-token.
+    /// Errors are ignored — even a refused ping has done the handshake
+    /// work, and the real request carries the error handling.
+    pub async fn prewarm(&self) {
+        let _ = self.send_request("ping", json!({})).await;
+    }
+
+    /// Ask the server which models it serves. Returns `Ok(None)` when
+    /// the server does not implement model listing, so callers can skip
+    /// validation instead of failing.
+    pub async fn list_models(&self) -> Result<Option<Vec<String>>> {
+        let result = match self.request("models.list", json!({})).await {
+            Ok(result) => result,
+            // -32601 is "method not found": an older server, not an error
+            Err(GraphOsError::RpcError { code: -32601, .. }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        // Accept both a bare array of names and the common
+        // {"models": [...]} / {"data": [{"id": ...}]} envelopes
+        let entries = result
+            .get("models")
+            .or_else(|| result.get("data"))
+            .and_then(|v| v.as_array())
+            .cloned()
+            .or_else(|| result.as_array().cloned())
+            .ok_or_else(|| GraphOsError::Decode("Unrecognized models.list response shape".to_string()))?;
+
+        let names = entries
+            .iter()
+            .filter_map(|entry| {
+                entry
+                    .as_str()
+                    .or_else(|| entry.get("id").and_then(|id| id.as_str()))
+                    .map(|s| s.to_string())
+            })
+            .collect();
+        Ok(Some(names))
+    }
+
+    /// Send several calls as one JSON-RPC batch array, cutting round
+    /// trips for commands that need a handful of small requests. Results
+    /// come back in call order, each succeeding or failing on its own.
+    /// Falls back to sequential requests when the server rejects the
+    /// batch envelope, so older servers keep working.
+    pub async fn batch(&self, calls: &[(&str, Value)]) -> Result<Vec<Result<Value>>> {
+        if calls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let started = Instant::now();
+        let result = self.send_batch(calls).await;
+
+        // One audit record per call, so the log reads the same whether
+        // the calls were batched or sent individually
+        match &result {
+            Ok(results) => {
+                for ((method, params), call_result) in calls.iter().zip(results) {
+                    let status = match call_result {
+                        Ok(_) => "ok".to_string(),
+                        Err(e) => format!("error: {}", e),
+                    };
+                    self.audit(method, params, started, status, None);
+                }
+            }
+            Err(e) => {
+                for (method, params) in calls {
+                    self.audit(method, params, started, format!("error: {}", e), None);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Send a batch without audit bookkeeping
+    async fn send_batch(&self, calls: &[(&str, Value)]) -> Result<Vec<Result<Value>>> {
+        // Build the batch array, remembering each request's id so the
+        // responses (which may arrive in any order) can be correlated
+        let requests: Vec<JsonRpcRequest> = calls
+            .iter()
+            .map(|(method, params)| JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                method: method.to_string(),
+                params: params.clone(),
+                id: Uuid::new_v4().to_string(),
+            })
+            .collect();
+
+        // Create headers
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        // Endpoint-configured headers, e.g. for reverse proxies
+        headers.extend(self.extra_headers.clone());
+
+        // Add API key if available for LLM services
+        if let Some(api_key) = self.bearer_token()
+            && let Ok(header_value) = HeaderValue::from_str(&format!("Bearer {}", api_key)) {
+                headers.insert("Authorization", header_value);
+            }
+
+        // Add RPC secret for GraphOS authentication if available
+        if let Some(rpc_secret) = &self.rpc_secret
+            && let Ok(header_value) = HeaderValue::from_str(&format!("Bearer {}", rpc_secret)) {
+                headers.insert("X-GraphOS-Auth", header_value);
+            }
+        self.accept_encoding(&mut headers);
+
+        // Send the batch, retrying once with a refreshed token on 401
+        let response = self.post_body_refreshing(headers, &requests).await?;
+
+        // Check status code
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(GraphOsError::Auth(
+                "HTTP 401 Unauthorized (token expired or invalid; run `gos login`)".to_string(),
+            ));
+        }
+        if !response.status().is_success() {
+            return Err(GraphOsError::Transport(format!("HTTP error: {}", response.status())));
+        }
+
+        let body: Value = serde_json::from_slice(&Self::read_body(response).await?)?;
+
+        // A server without batch support answers the array with a single
+        // error object (invalid request / parse error); fall back to
+        // sending the calls one at a time
+        let responses = match body {
+            Value::Array(responses) => responses,
+            Value::Object(_) => {
+                let mut results = Vec::with_capacity(calls.len());
+                for (method, params) in calls {
+                    results.push(self.send_request(method, params.clone()).await);
+                }
+                return Ok(results);
+            }
+            _ => return Err(GraphOsError::Decode("Unexpected batch response shape".to_string())),
+        };
+
+        // Correlate responses to requests by id; a missing response is
+        // reported as an error for that call, not for the whole batch
+        let mut by_id: HashMap<String, JsonRpcResponse> = HashMap::new();
+        for response in responses {
+            if let Ok(parsed) = serde_json::from_value::<JsonRpcResponse>(response) {
+                by_id.insert(parsed.id.clone(), parsed);
+            }
+        }
+
+        let results = requests
+            .iter()
+            .map(|request| match by_id.remove(&request.id) {
+                Some(response) => {
+                    if let Some(error) = response.error {
+                        Err(GraphOsError::RpcError {
+                            code: error.code,
+                            message: error.message,
+                            data: error.data,
+                        })
+                    } else {
+                        Ok(response.result.unwrap_or(json!(null)))
+                    }
+                }
+                None => Err(GraphOsError::Decode(format!(
+                    "No response for batched call {}", request.method
+                ))),
+            })
+            .collect();
+
+        Ok(results)
+    }
+
     /// Send a JSONRPC request to the server
-    pub async fn request(&self, method: &str, params: Value) -> Result<Value, Error> {
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let started = Instant::now();
+        let result = self.send_request(method, params.clone()).await;
+        let token_usage = result.as_ref().ok().and_then(|v| v.get("usage").cloned());
+        let status = match &result {
+            Ok(_) => "ok".to_string(),
+            Err(e) => format!("error: {}", e),
+        };
+        self.audit(method, &params, started, status, token_usage);
+        result
+    }
+
+    /// Write an audit record for a completed call
+    fn audit(&self, method: &str, params: &Value, started: Instant, status: String, token_usage: Option<Value>) {
+        AuditLog::instance().record(&AuditRecord {
+            timestamp: Utc::now(),
+            transport: "jsonrpc".to_string(),
+            method: method.to_string(),
+            endpoint: self.endpoint.clone(),
+            params: redact_params(params),
+            latency_ms: started.elapsed().as_millis() as u64,
+            status,
+            token_usage,
+        });
+    }
+
+    /// Send a JSONRPC request without audit bookkeeping
+    async fn send_request(&self, method: &str, params: Value) -> Result<Value> {
+        // In replay mode the recorded response stands in for the network
+        if let Some(recorded) = crate::adapters::recording::replay("jsonrpc", method, &params) {
+            return recorded.map_err(|e| GraphOsError::Decode(e.to_string()));
+        }
+
         // Create a JSONRPC request
         let request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
@@ -130,43 +1233,51 @@ impl JsonRpcClient {
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
         headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        // Endpoint-configured headers, e.g. for reverse proxies
+        headers.extend(self.extra_headers.clone());
         
         // Add API key if available for LLM services
-        if let Some(api_key) = &self.api_key {
-            if let Ok(header_value) = HeaderValue::from_str(&format!("Bearer {}", api_key)) {
+        if let Some(api_key) = self.bearer_token()
+            && let Ok(header_value) = HeaderValue::from_str(&format!("Bearer {}", api_key)) {
                 headers.insert("Authorization", header_value);
             }
-        }
         
         // Add RPC secret for GraphOS authentication if available
-        if let Some(rpc_secret) = &self.rpc_secret {
-            if let Ok(header_value) = HeaderValue::from_str(&format!("Bearer {}", rpc_secret)) {
+        if let Some(rpc_secret) = &self.rpc_secret
+            && let Ok(header_value) = HeaderValue::from_str(&format!("Bearer {}", rpc_secret)) {
                 headers.insert("X-GraphOS-Auth", header_value);
             }
-        }
+        self.accept_encoding(&mut headers);
+
+        // Send the request, retrying once with a refreshed token on 401
+        let response = self.post_body_refreshing(headers, &request).await?;
 
-        // Send the request
-        let response = self.client.post(&self.endpoint)
-            .headers(headers)
-            .json(&request)
-            .send()
-            .await?;
-        
         // Check status code
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(GraphOsError::Auth(
+                "HTTP 401 Unauthorized (token expired or invalid; run `gos login`)".to_string(),
+            ));
+        }
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!("HTTP error: {}", response.status()));
+            return Err(GraphOsError::Transport(format!("HTTP error: {}", response.status())));
         }
-        
+
         // Parse the response as JSON
-        let rpc_response: JsonRpcResponse = response.json().await?;
+        let rpc_response: JsonRpcResponse = serde_json::from_slice(&Self::read_body(response).await?)?;
         
         // Handle the response
         if let Some(error) = rpc_response.error {
-            return Err(anyhow::anyhow!("JSONRPC error: {} (code: {})", error.message, error.code));
+            return Err(GraphOsError::RpcError {
+                code: error.code,
+                message: error.message,
+                data: error.data,
+            });
         }
         
         // Return the result
-        Ok(rpc_response.result.unwrap_or(json!(null)))
+        let result = rpc_response.result.unwrap_or(json!(null));
+        crate::adapters::recording::record("jsonrpc", method, &request.params, &result);
+        Ok(result)
     }
     
     /// Send a streaming request and return chunks through a channel
@@ -175,7 +1286,39 @@ impl JsonRpcClient {
         method: &str, 
         params: Value,
         sender: mpsc::Sender<String>,
-    ) -> Result<(), Error> {
+    ) -> Result<()> {
+        let started = Instant::now();
+        let result = self.send_request_streaming(method, params.clone(), sender).await;
+        let status = match &result {
+            Ok(_) => "ok".to_string(),
+            Err(e) => format!("error: {}", e),
+        };
+        self.audit(method, &params, started, status, None);
+        result
+    }
+
+    /// Send a streaming request without audit bookkeeping
+    async fn send_request_streaming(
+        &self, 
+        method: &str, 
+        params: Value,
+        sender: mpsc::Sender<String>,
+    ) -> Result<()> {
+        // In replay mode the recorded transcript is delivered as a single
+        // chunk instead of hitting the network
+        if let Some(recorded) = crate::adapters::recording::replay("jsonrpc", method, &params) {
+            let content = recorded
+                .map_err(|e| GraphOsError::Decode(e.to_string()))?
+                .get("content")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string();
+            if !content.is_empty() {
+                sender.send(content).await.ok();
+            }
+            return Ok(());
+        }
+
         // Create a JSONRPC request
         let request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
@@ -187,96 +1330,235 @@ impl JsonRpcClient {
         // Create headers
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        headers.insert(ACCEPT, HeaderValue::from_static("application/json-seq"));
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json-seq, text/event-stream"));
+        // Endpoint-configured headers, e.g. for reverse proxies
+        headers.extend(self.extra_headers.clone());
         
         // Add API key if available for LLM services
-        if let Some(api_key) = &self.api_key {
-            if let Ok(header_value) = HeaderValue::from_str(&format!("Bearer {}", api_key)) {
+        if let Some(api_key) = self.bearer_token()
+            && let Ok(header_value) = HeaderValue::from_str(&format!("Bearer {}", api_key)) {
                 headers.insert("Authorization", header_value);
             }
-        }
         
         // Add RPC secret for GraphOS authentication if available
-        if let Some(rpc_secret) = &self.rpc_secret {
-            if let Ok(header_value) = HeaderValue::from_str(&format!("Bearer {}", rpc_secret)) {
+        if let Some(rpc_secret) = &self.rpc_secret
+            && let Ok(header_value) = HeaderValue::from_str(&format!("Bearer {}", rpc_secret)) {
                 headers.insert("X-GraphOS-Auth", header_value);
             }
-        }
-        
-        // Send the request
-        let response = self.client.post(&self.endpoint)
-            .headers(headers)
-            .json(&request)
-            .send()
-            .await?;
-        
+        self.accept_encoding(&mut headers);
+
+        // Send the request, retrying once with a refreshed token on 401
+        let response = self.post_body_refreshing(headers, &request).await?;
+
         // Check status code
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(GraphOsError::Auth(
+                "HTTP 401 Unauthorized (token expired or invalid; run `gos login`)".to_string(),
+            ));
+        }
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!("HTTP error: {}", response.status()));
+            return Err(GraphOsError::Transport(format!("HTTP error: {}", response.status())));
         }
-        
+
         // Process the streaming response
-        self.process_streaming_response(response, sender).await?;
-        
+        let transcript = self.process_streaming_response(response, sender).await?;
+        crate::adapters::recording::record(
+            "jsonrpc",
+            method,
+            &request.params,
+            &json!({ "content": transcript }),
+        );
+
         Ok(())
     }
-    
-    /// Process a streaming response from the server
+
+    /// Process a streaming response from the server, negotiating the
+    /// framing from the content type: SSE for `text/event-stream`,
+    /// newline-delimited JSON otherwise. Returns the full transcript for
+    /// recording.
     async fn process_streaming_response(
         &self,
         response: Response,
         sender: mpsc::Sender<String>,
-    ) -> Result<(), Error> {
+    ) -> Result<String> {
+        let is_sse = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+        if is_sse {
+            return self.process_sse_response(response, sender).await;
+        }
+
+        // A compressed stream is decoded incrementally ahead of the
+        // line framing
+        let mut decoder = response_encoding(&response).map(crate::compress::Encoding::decoder);
         let mut stream = response.bytes_stream();
-        
-        let mut buffer = Vec::new();
-        
+
+        let mut parser = NdjsonStreamParser::new();
+        let mut transcript = String::new();
+
         while let Some(chunk) = stream.next().await {
             let chunk = chunk?;
-            buffer.extend_from_slice(&chunk);
-            
-            // Process any complete JSON objects in the buffer
-            let mut start = 0;
-            for (i, &byte) in buffer.iter().enumerate() {
-                if byte == b'\n' {
-                    if i > start {
-                        let slice = &buffer[start..i];
-                        if let Ok(chunk) = serde_json::from_slice::<JsonRpcStreamChunk>(slice) {
-                            if let Some(result) = chunk.result {
-                                if let Some(content) = result.get("content") {
-                                    if let Some(text) = content.as_str() {
-                                        // Send the content through the channel
-                                        if sender.send(text.to_string()).await.is_err() {
-                                            // Channel closed, stop processing
-                                            return Ok(());
-                                        }
-                                    }
-                                }
-                            } else if let Some(error) = chunk.error {
-                                return Err(anyhow::anyhow!("Stream error: {} (code: {})", error.message, error.code));
-                            }
-                        }
+            let chunk = match &mut decoder {
+                Some(decoder) => decoder.feed(&chunk).map_err(GraphOsError::Decode)?,
+                None => chunk.to_vec(),
+            };
+            for event in parser.push(&chunk) {
+                let notifications = self.notification_sender.as_ref();
+                if !Self::apply_stream_event(event, &sender, notifications, &mut transcript).await? {
+                    return Ok(transcript);
+                }
+            }
+        }
+        // A final frame without a trailing newline still counts
+        for event in parser.finish() {
+            let notifications = self.notification_sender.as_ref();
+            if !Self::apply_stream_event(event, &sender, notifications, &mut transcript).await? {
+                break;
+            }
+        }
+
+        Ok(transcript)
+    }
+
+    /// Fold one stream event into the transcript and the caller's
+    /// channel. Returns false when processing should stop: the channel
+    /// closed or the server said it is done.
+    async fn apply_stream_event(
+        event: StreamEvent,
+        sender: &mpsc::Sender<String>,
+        notifications: Option<&mpsc::Sender<ServerNotification>>,
+        transcript: &mut String,
+    ) -> Result<bool> {
+        match event {
+            StreamEvent::ContentDelta(text) => {
+                transcript.push_str(&text);
+                // A closed channel means the user stopped the stream
+                Ok(sender.send(text).await.is_ok())
+            }
+            StreamEvent::ToolCallDelta { name, arguments } => {
+                // The chat UI has no tool execution; show the call
+                // inline instead of dropping it on the floor
+                let mut rendered = String::new();
+                if let Some(name) = name {
+                    rendered.push_str(&format!("\n[tool call: {}] ", name));
+                }
+                rendered.push_str(&arguments);
+                transcript.push_str(&rendered);
+                Ok(sender.send(rendered).await.is_ok())
+            }
+            StreamEvent::Usage { prompt_tokens, completion_tokens } => {
+                if stream_debug() {
+                    eprintln!(
+                        "stream usage: prompt {:?}, completion {:?}",
+                        prompt_tokens, completion_tokens
+                    );
+                }
+                Ok(true)
+            }
+            StreamEvent::Done { finish_reason } => {
+                // Truncation and filtering are worth telling the user
+                // about; a normal stop ends the stream silently
+                if let Some(reason) = finish_reason.filter(|r| r != "stop" && r != "end_turn") {
+                    let note = format!("\n[response ended: {}]", reason);
+                    transcript.push_str(&note);
+                    sender.send(note).await.ok();
+                }
+                Ok(false)
+            }
+            StreamEvent::Notification(notification) => {
+                // Out-of-band: route to the notification channel, not
+                // the transcript. Best effort — a missing or closed
+                // receiver never stalls the answer.
+                match notifications {
+                    Some(tx) => {
+                        tx.send(notification).await.ok();
+                    }
+                    None if stream_debug() => {
+                        eprintln!("dropped server notification: {}", notification.summary());
                     }
-                    start = i + 1;
+                    None => {}
                 }
+                Ok(true)
             }
-            
-            // Remove processed data from buffer
-            if start > 0 {
-                buffer.drain(0..start);
+            StreamEvent::Error { code, message, data } => {
+                Err(GraphOsError::RpcError { code, message, data })
+            }
+            StreamEvent::Malformed { snippet, error } => {
+                if stream_debug() {
+                    eprintln!("dropped malformed stream frame ({}): {}", error, snippet);
+                }
+                Ok(true)
             }
         }
-        
-        Ok(())
+    }
+
+    /// Process a text/event-stream response: each event's data payload
+    /// is a JSON chunk, terminated by a `[DONE]` sentinel. Returns the
+    /// full transcript for recording.
+    async fn process_sse_response(
+        &self,
+        response: Response,
+        sender: mpsc::Sender<String>,
+    ) -> Result<String> {
+        // A compressed stream is decoded incrementally ahead of the
+        // event framing
+        let mut decoder = response_encoding(&response).map(crate::compress::Encoding::decoder);
+        let mut stream = response.bytes_stream();
+        let mut parser = crate::adapters::sse::SseParser::new();
+        let mut transcript = String::new();
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            let chunk = match &mut decoder {
+                Some(decoder) => decoder.feed(&chunk).map_err(GraphOsError::Decode)?,
+                None => chunk.to_vec(),
+            };
+            for event in parser.push(&chunk) {
+                // OpenAI-compatible gateways end the stream with [DONE]
+                if event.data.trim() == "[DONE]" {
+                    return Ok(transcript);
+                }
+
+                // Server-initiated notifications ride the same event
+                // stream; they go out of band, not into the answer
+                if let Ok(value) = serde_json::from_str::<Value>(&event.data)
+                    && let Some(notification) = ServerNotification::from_value(&value)
+                {
+                    if let Some(tx) = &self.notification_sender {
+                        tx.send(notification).await.ok();
+                    } else if stream_debug() {
+                        eprintln!("dropped server notification: {}", notification.summary());
+                    }
+                    continue;
+                }
+
+                if let Some(content) = crate::adapters::sse::extract_stream_content(&event.data)? {
+                    transcript.push_str(&content);
+                    if sender.send(content).await.is_err() {
+                        // Channel closed, stop processing
+                        return Ok(transcript);
+                    }
+                }
+            }
+        }
+
+        Ok(transcript)
     }
     
     /// Send a conversation to the chat API
     pub async fn chat(
-        &self, 
+        &self,
         messages: Vec<Message>,
         stream: bool,
         sender: Option<mpsc::Sender<String>>,
-    ) -> Result<String, Error> {
+    ) -> Result<String> {
+        // An OpenAI-compatible endpoint gets the translated REST shape
+        if self.dialect().await == Dialect::OpenAi {
+            return self.chat_openai(messages, stream, sender).await;
+        }
+
         // Prepare the parameters
         let mut params = json!({
             "messages": messages,
@@ -294,17 +1576,165 @@ impl JsonRpcClient {
                 self.request_streaming("chat", params, tx).await?;
                 Ok("".to_string())
             } else {
-                Err(anyhow::anyhow!("No channel provided for streaming response"))
+                Err(GraphOsError::Config("No channel provided for streaming response".to_string()))
             }
         } else {
             // Handle regular response
             let response = self.request("chat", params).await?;
             
-            // Extract the message from the response
+            // Extract the message from the response; the body may be plain
+            // text or a typed multi-part structure with images
             match response.get("message") {
-                Some(msg) => Ok(msg.as_str().unwrap_or("Response could not be parsed").to_string()),
+                Some(msg) => match serde_json::from_value::<MessageContent>(msg.clone()) {
+                    Ok(content) => Ok(content.render_for_terminal()),
+                    Err(_) => Ok(msg.as_str().unwrap_or("Response could not be parsed").to_string()),
+                },
                 None => Ok("Received a response without a message field".to_string())
             }
         }
     }
+
+    /// Send a conversation to an OpenAI-compatible endpoint via
+    /// `/v1/chat/completions`. The streaming response is SSE with delta
+    /// chunks, which the shared stream processor already understands.
+    async fn chat_openai(
+        &self,
+        messages: Vec<Message>,
+        stream: bool,
+        sender: Option<mpsc::Sender<String>>,
+    ) -> Result<String> {
+        let body = translate::to_openai_request(&messages, self.model.as_deref(), stream);
+        let url = translate::chat_completions_url(&self.endpoint);
+        let started = Instant::now();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json, text/event-stream"));
+        // Endpoint-configured headers, e.g. for reverse proxies
+        headers.extend(self.extra_headers.clone());
+
+        // Only the API key matters here; the RPC secret is a GraphOS
+        // concept these servers would reject
+        if let Some(api_key) = self.bearer_token()
+            && let Ok(header_value) = HeaderValue::from_str(&format!("Bearer {}", api_key)) {
+                headers.insert("Authorization", header_value);
+            }
+
+        let result = self.send_openai_chat(&url, headers, &body, stream, sender).await;
+        let status = match &result {
+            Ok(_) => "ok".to_string(),
+            Err(e) => format!("error: {}", e),
+        };
+        self.audit("chat.completions", &body, started, status, None);
+        result
+    }
+
+    async fn send_openai_chat(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+        body: &Value,
+        stream: bool,
+        sender: Option<mpsc::Sender<String>>,
+    ) -> Result<String> {
+        let mut headers = headers;
+        self.accept_encoding(&mut headers);
+        let response = self.post_body_to_refreshing(url, headers, body).await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(GraphOsError::Auth(
+                "HTTP 401 Unauthorized (check the endpoint's API key)".to_string(),
+            ));
+        }
+        if !response.status().is_success() {
+            return Err(GraphOsError::Transport(format!("HTTP error: {}", response.status())));
+        }
+
+        if stream {
+            let Some(tx) = sender else {
+                return Err(GraphOsError::Config("No channel provided for streaming response".to_string()));
+            };
+            self.process_streaming_response(response, tx).await?;
+            Ok(String::new())
+        } else {
+            let body: Value = serde_json::from_slice(&Self::read_body(response).await?)?;
+            translate::from_openai_response(&body)
+        }
+    }
+
+    /// Embed a batch of texts through the endpoint, dispatching on its
+    /// dialect: GraphOS servers expose an `embeddings` JSON-RPC method,
+    /// OpenAI-compatible ones answer POST `/v1/embeddings`. Vectors
+    /// come back in input order, one per text.
+    pub async fn embeddings(&self, texts: &[String], model: Option<&str>) -> Result<Vec<Vec<f32>>> {
+        if self.dialect().await == Dialect::OpenAi {
+            return self.embeddings_openai(texts, model).await;
+        }
+
+        let params = json!({ "input": texts, "model": model });
+        let response = self.request("embeddings", params).await?;
+        let Some(embeddings) = response.get("embeddings").and_then(|e| e.as_array()) else {
+            return Err(GraphOsError::Decode(
+                "Embeddings response has no embeddings array".to_string(),
+            ));
+        };
+        let vectors: Vec<Vec<f32>> = embeddings
+            .iter()
+            .filter_map(|v| v.as_array())
+            .map(|values| values.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect())
+            .collect();
+        if vectors.len() != texts.len() {
+            return Err(GraphOsError::Decode(format!(
+                "Expected {} embedding vectors, got {}",
+                texts.len(),
+                vectors.len()
+            )));
+        }
+        Ok(vectors)
+    }
+
+    /// Embed via an OpenAI-compatible `/v1/embeddings` route
+    async fn embeddings_openai(&self, texts: &[String], model: Option<&str>) -> Result<Vec<Vec<f32>>> {
+        let body = translate::to_openai_embeddings_request(texts, model);
+        let url = translate::embeddings_url(&self.endpoint);
+        let started = Instant::now();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        // Endpoint-configured headers, e.g. for reverse proxies
+        headers.extend(self.extra_headers.clone());
+
+        // Only the API key matters here; the RPC secret is a GraphOS
+        // concept these servers would reject
+        if let Some(api_key) = self.bearer_token()
+            && let Ok(header_value) = HeaderValue::from_str(&format!("Bearer {}", api_key)) {
+                headers.insert("Authorization", header_value);
+            }
+        self.accept_encoding(&mut headers);
+
+        let result = async {
+            let response = self.post_body_to_refreshing(&url, headers, &body).await?;
+            if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+                return Err(GraphOsError::Auth(
+                    "HTTP 401 Unauthorized (check the endpoint's API key)".to_string(),
+                ));
+            }
+            if !response.status().is_success() {
+                return Err(GraphOsError::Transport(format!("HTTP error: {}", response.status())));
+            }
+            let parsed: Value = serde_json::from_slice(&Self::read_body(response).await?)?;
+            translate::from_openai_embeddings(&parsed, texts.len())
+        }
+        .await;
+
+        let status = match &result {
+            Ok(_) => "ok".to_string(),
+            Err(e) => format!("error: {}", e),
+        };
+        // Texts stay out of the audit log; the count is enough to see
+        // the shape of the call
+        self.audit("embeddings", &json!({ "input_count": texts.len(), "model": model }), started, status, None);
+        result
+    }
 }
\ No newline at end of file
@@ -1,8 +1,17 @@
+pub mod dynamic;
 pub mod jsonrpc;
 pub mod grpc;
+pub mod recording;
+pub mod sse;
+pub mod translate;
 
 // Re-export types for easier imports elsewhere
+pub use jsonrpc::HttpClientOptions;
+pub use jsonrpc::HttpVersion;
 pub use jsonrpc::JsonRpcClient;
+pub use jsonrpc::ContentPart;
 pub use jsonrpc::Message;
+pub use jsonrpc::MessageContent;
 pub use jsonrpc::MessageRole;
-pub use grpc::GrpcClient;
\ No newline at end of file
+pub use jsonrpc::ServerNotification;
+pub use grpc::{ConnectionState, GrpcAuth, GrpcClient};
\ No newline at end of file
@@ -0,0 +1,129 @@
+//! Request/response recording and replay for debugging.
+//!
+//! `--record <dir>` captures every JSON-RPC and gRPC exchange to one
+//! JSON file per request, keyed by a hash of the request body. `--replay
+//! <dir>` serves responses from such a recording instead of the network,
+//! which makes user bug reports reproducible and chat flows
+//! deterministic in tests.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+
+/// Whether exchanges are being captured to or served from a directory
+#[derive(Debug, Clone)]
+pub enum RecordingMode {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+static MODE: OnceLock<RecordingMode> = OnceLock::new();
+
+/// Install the global recording mode; called once at startup from the
+/// `--record` / `--replay` flags
+pub fn init(mode: RecordingMode) -> Result<()> {
+    match &mode {
+        RecordingMode::Record(dir) => {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create recording directory {}", dir.display()))?;
+        }
+        RecordingMode::Replay(dir) => {
+            if !dir.is_dir() {
+                bail!("Replay directory {} does not exist", dir.display());
+            }
+        }
+    }
+
+    MODE.set(mode)
+        .map_err(|_| anyhow!("Recording mode already initialized"))
+}
+
+/// Whether responses are being served from a recording
+pub fn is_replaying() -> bool {
+    matches!(MODE.get(), Some(RecordingMode::Replay(_)))
+}
+
+/// Whether exchanges are being captured to disk
+pub fn is_recording() -> bool {
+    matches!(MODE.get(), Some(RecordingMode::Record(_)))
+}
+
+/// File a given exchange lives in: the method plus a hash of the request
+/// body, so identical requests replay the same response. Volatile `id`
+/// fields are excluded from the hash.
+pub fn exchange_path(dir: &Path, transport: &str, method: &str, request: &Value) -> PathBuf {
+    let mut request = request.clone();
+    if let Some(object) = request.as_object_mut() {
+        object.remove("id");
+    }
+
+    let digest = ring::digest::digest(
+        &ring::digest::SHA256,
+        format!("{}\n{}\n{}", transport, method, request).as_bytes(),
+    );
+    let hash: String = digest.as_ref()[..8].iter().map(|b| format!("{:02x}", b)).collect();
+
+    let method = method.replace(['.', '/'], "_");
+    dir.join(format!("{}-{}-{}.json", transport, method, hash))
+}
+
+/// Write one exchange to a recording directory
+pub fn write_exchange(
+    dir: &Path,
+    transport: &str,
+    method: &str,
+    request: &Value,
+    response: &Value,
+) -> Result<PathBuf> {
+    let path = exchange_path(dir, transport, method, request);
+    let exchange = json!({
+        "transport": transport,
+        "method": method,
+        "request": request,
+        "response": response,
+    });
+
+    std::fs::write(&path, serde_json::to_string_pretty(&exchange)?)
+        .with_context(|| format!("Failed to write recording {}", path.display()))?;
+    Ok(path)
+}
+
+/// Read the recorded response for a request from a recording directory
+pub fn read_exchange(dir: &Path, transport: &str, method: &str, request: &Value) -> Result<Value> {
+    let path = exchange_path(dir, transport, method, request);
+    let contents = std::fs::read_to_string(&path).map_err(|_| {
+        anyhow!(
+            "No recorded response for {} {} (expected {})",
+            transport,
+            method,
+            path.display()
+        )
+    })?;
+
+    let exchange: Value = serde_json::from_str(&contents)
+        .with_context(|| format!("Corrupt recording {}", path.display()))?;
+    exchange
+        .get("response")
+        .cloned()
+        .ok_or_else(|| anyhow!("Recording {} has no response field", path.display()))
+}
+
+/// Capture one exchange when recording is active. Failures are reported
+/// but never fail the call that produced the response.
+pub fn record(transport: &str, method: &str, request: &Value, response: &Value) {
+    if let Some(RecordingMode::Record(dir)) = MODE.get()
+        && let Err(e) = write_exchange(dir, transport, method, request, response) {
+            eprintln!("Recording failed: {}", e);
+        }
+}
+
+/// Look up the recorded response when replay is active. None when not
+/// replaying; an error when replaying and the exchange was not recorded.
+pub fn replay(transport: &str, method: &str, request: &Value) -> Option<Result<Value>> {
+    match MODE.get() {
+        Some(RecordingMode::Replay(dir)) => Some(read_exchange(dir, transport, method, request)),
+        _ => None,
+    }
+}
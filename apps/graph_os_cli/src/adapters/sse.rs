@@ -0,0 +1,127 @@
+//! Incremental parser for `text/event-stream` (Server-Sent Events)
+//! responses, used when a gateway streams chat completions over SSE
+//! instead of newline-delimited JSON.
+
+use serde_json::Value;
+
+use crate::error::{GraphOsError, Result};
+
+/// One parsed SSE event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    /// Value of the `event:` field, if any
+    pub event: Option<String>,
+    /// Concatenated `data:` lines, joined with newlines
+    pub data: String,
+    /// Value of the `id:` field, if any
+    pub id: Option<String>,
+}
+
+/// Incremental SSE parser. Feed it raw network chunks; it buffers
+/// partial lines and emits events as their terminating blank line
+/// arrives.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: String,
+    event: Option<String>,
+    data: Vec<String>,
+    id: Option<String>,
+}
+
+impl SseParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a chunk of bytes, returning any events completed by it
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.push_str(&String::from_utf8_lossy(chunk));
+
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.find('\n') {
+            let line: String = self.buffer.drain(..=pos).collect();
+            let line = line.trim_end_matches(['\n', '\r']);
+            self.process_line(line, &mut events);
+        }
+
+        events
+    }
+
+    fn process_line(&mut self, line: &str, events: &mut Vec<SseEvent>) {
+        // Blank line dispatches the accumulated event
+        if line.is_empty() {
+            if !self.data.is_empty() {
+                events.push(SseEvent {
+                    event: self.event.take(),
+                    data: self.data.join("\n"),
+                    id: self.id.take(),
+                });
+                self.data.clear();
+            }
+            return;
+        }
+
+        // Lines starting with a colon are comments (keep-alives)
+        if line.starts_with(':') {
+            return;
+        }
+
+        // Field lines are "name: value"; a single leading space in the
+        // value is part of the separator
+        let (name, value) = match line.split_once(':') {
+            Some((name, value)) => (name, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match name {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            "id" => self.id = Some(value.to_string()),
+            // "retry" and unknown fields are ignored
+            _ => {}
+        }
+    }
+}
+
+/// Extract streamed text from an event payload. Understands both our
+/// JSON-RPC chunk framing (`result.content`) and the OpenAI delta format
+/// (`choices[0].delta.content`). Returns Ok(None) for chunks that carry
+/// no text, such as role announcements and finish markers.
+pub fn extract_stream_content(data: &str) -> Result<Option<String>> {
+    let value: Value = serde_json::from_str(data)
+        .map_err(|e| GraphOsError::Decode(format!("Malformed stream chunk: {}", e)))?;
+
+    if let Some(error) = value.get("error") {
+        return Err(GraphOsError::RpcError {
+            code: error.get("code").and_then(|c| c.as_i64()).unwrap_or(-1) as i32,
+            message: error
+                .get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("unknown error")
+                .to_string(),
+            data: error.get("data").cloned(),
+        });
+    }
+
+    // JSON-RPC chunk framing
+    if let Some(content) = value
+        .get("result")
+        .and_then(|r| r.get("content"))
+        .and_then(|c| c.as_str())
+    {
+        return Ok(Some(content.to_string()));
+    }
+
+    // OpenAI-compatible delta framing
+    if let Some(content) = value
+        .get("choices")
+        .and_then(|c| c.get(0))
+        .and_then(|c| c.get("delta"))
+        .and_then(|d| d.get("content"))
+        .and_then(|c| c.as_str())
+    {
+        return Ok(Some(content.to_string()));
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,183 @@
+//! Request translation between the internal message format and the
+//! OpenAI-compatible `/v1/chat/completions` dialect.
+//!
+//! Many local inference servers (Ollama, vLLM, LM Studio) speak the
+//! OpenAI REST shape rather than GraphOS JSON-RPC. This module builds
+//! their request payloads and decodes their responses so an
+//! `ApiProvider::Custom` endpoint can point straight at one of them;
+//! the JSON-RPC client probes the endpoint once to pick the dialect.
+
+use serde_json::{json, Value};
+
+use crate::adapters::jsonrpc::{ContentPart, Message, MessageContent};
+use crate::error::{GraphOsError, Result};
+
+/// The request/response shape an endpoint speaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// GraphOS JSON-RPC (the default)
+    GraphOs,
+    /// OpenAI-compatible REST (`/v1/chat/completions`)
+    OpenAi,
+}
+
+/// Rewrite an endpoint URL to the given OpenAI-style path, preserving
+/// scheme, authority and any baked-in query parameters
+fn with_path(endpoint: &str, path: &str) -> String {
+    match reqwest::Url::parse(endpoint) {
+        Ok(mut url) => {
+            url.set_path(path);
+            url.to_string()
+        }
+        // A malformed endpoint will fail on send with a clearer error
+        Err(_) => format!("{}{}", endpoint.trim_end_matches('/'), path),
+    }
+}
+
+/// The chat completions URL for an endpoint
+pub fn chat_completions_url(endpoint: &str) -> String {
+    with_path(endpoint, "/v1/chat/completions")
+}
+
+/// The model listing URL for an endpoint, used as the dialect probe
+/// because every OpenAI-compatible server implements it as a plain GET
+pub fn models_url(endpoint: &str) -> String {
+    with_path(endpoint, "/v1/models")
+}
+
+/// Convert internal content to the OpenAI content shape: a bare string
+/// for text, a typed part array when images are involved
+fn to_openai_content(content: &MessageContent) -> Value {
+    match content {
+        MessageContent::Text(text) => json!(text),
+        MessageContent::Parts(parts) => {
+            let parts: Vec<Value> = parts
+                .iter()
+                .map(|part| match part {
+                    ContentPart::Text { text } => json!({ "type": "text", "text": text }),
+                    ContentPart::ImageUrl { url } => {
+                        json!({ "type": "image_url", "image_url": { "url": url } })
+                    }
+                    // Inline images travel as data URLs in this dialect
+                    ContentPart::Image { media_type, data } => json!({
+                        "type": "image_url",
+                        "image_url": { "url": format!("data:{};base64,{}", media_type, data) }
+                    }),
+                })
+                .collect();
+            json!(parts)
+        }
+    }
+}
+
+/// Build a `/v1/chat/completions` request body from internal messages
+pub fn to_openai_request(messages: &[Message], model: Option<&str>, stream: bool) -> Value {
+    let messages: Vec<Value> = messages
+        .iter()
+        .map(|message| {
+            json!({
+                "role": message.role,
+                "content": to_openai_content(&message.content),
+            })
+        })
+        .collect();
+
+    let mut request = json!({
+        "messages": messages,
+        "stream": stream,
+    });
+    if let Some(model) = model {
+        request["model"] = json!(model);
+    }
+    request
+}
+
+/// Pull the assistant text out of a non-streaming completions response
+pub fn from_openai_response(body: &Value) -> Result<String> {
+    // Servers report failures in an "error" object with the same shape
+    if let Some(error) = body.get("error") {
+        let message = error
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("unknown error");
+        return Err(GraphOsError::Transport(format!("Completions error: {}", message)));
+    }
+
+    let content = body
+        .get("choices")
+        .and_then(|c| c.get(0))
+        .and_then(|c| c.get("message"))
+        .and_then(|m| m.get("content"));
+
+    match content {
+        Some(Value::String(text)) => Ok(text.clone()),
+        // Some servers answer with a part array even for text
+        Some(parts @ Value::Array(_)) => {
+            match serde_json::from_value::<Vec<ContentPart>>(parts.clone()) {
+                Ok(parts) => Ok(MessageContent::Parts(parts).as_text()),
+                Err(e) => Err(GraphOsError::Decode(format!(
+                    "Unrecognized completions content: {}",
+                    e
+                ))),
+            }
+        }
+        _ => Err(GraphOsError::Decode(
+            "Completions response has no choices[0].message.content".to_string(),
+        )),
+    }
+}
+
+/// The embeddings URL for an endpoint
+pub fn embeddings_url(endpoint: &str) -> String {
+    with_path(endpoint, "/v1/embeddings")
+}
+
+/// Build an OpenAI `/v1/embeddings` request body
+pub fn to_openai_embeddings_request(texts: &[String], model: Option<&str>) -> Value {
+    let mut request = json!({ "input": texts });
+    if let Some(model) = model {
+        request["model"] = json!(model);
+    }
+    request
+}
+
+/// Pull the vectors out of an embeddings response, restored to input
+/// order via the per-item `index` the format carries for exactly this
+/// purpose
+pub fn from_openai_embeddings(body: &Value, expected: usize) -> Result<Vec<Vec<f32>>> {
+    // Servers report failures in an "error" object with the same shape
+    if let Some(error) = body.get("error") {
+        let message = error
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("unknown error");
+        return Err(GraphOsError::Transport(format!("Embeddings error: {}", message)));
+    }
+
+    let Some(data) = body.get("data").and_then(|d| d.as_array()) else {
+        return Err(GraphOsError::Decode(
+            "Embeddings response has no data array".to_string(),
+        ));
+    };
+
+    let mut vectors: Vec<Option<Vec<f32>>> = vec![None; expected];
+    for item in data {
+        let index = item.get("index").and_then(|i| i.as_u64()).map(|i| i as usize);
+        let vector = item
+            .get("embedding")
+            .and_then(|e| e.as_array())
+            .map(|values| values.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect());
+        if let (Some(index), Some(vector)) = (index, vector)
+            && index < expected
+        {
+            vectors[index] = Some(vector);
+        }
+    }
+
+    vectors
+        .into_iter()
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| {
+            GraphOsError::Decode("Embeddings response is missing vectors for some inputs".to_string())
+        })
+}
@@ -0,0 +1,421 @@
+//! Session archival (`gos archive` / `gos restore`).
+//!
+//! Uploads compressed session bundles to a storage backend configured
+//! in the `[archive]` table — a local directory, an S3-compatible
+//! object store or a WebDAV share — and removes the local session file
+//! once the upload succeeds. `gos restore` pulls a bundle back into the
+//! sessions directory. Bundles carry the raw session file bytes, so
+//! encrypted-at-rest sessions stay encrypted on the remote side.
+//!
+//! Archival operates on session files directly; run it when no chat
+//! listener is active so a concurrent autosave cannot recreate a file
+//! that was just shipped off.
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use base64::Engine;
+
+use crate::config::ArchiveConfig;
+
+// --- Bundle format -------------------------------------------------------
+//
+// magic "GOSB", one version byte, the uncompressed length as u32 LE,
+// then the LZSS stream. The explicit length lets decompression allocate
+// once and reject truncated downloads.
+
+const BUNDLE_MAGIC: &[u8; 4] = b"GOSB";
+const BUNDLE_VERSION: u8 = 1;
+
+/// Wrap raw session file bytes in a compressed archive bundle
+pub fn bundle(contents: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(contents.len() / 2 + 9);
+    out.extend_from_slice(BUNDLE_MAGIC);
+    out.push(BUNDLE_VERSION);
+    out.extend_from_slice(&(contents.len() as u32).to_le_bytes());
+    out.extend_from_slice(&lzss_compress(contents));
+    out
+}
+
+/// Unwrap a bundle back into the original session file bytes
+pub fn unbundle(data: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < 9 || &data[..4] != BUNDLE_MAGIC {
+        bail!("Not a session bundle (bad magic)");
+    }
+    if data[4] != BUNDLE_VERSION {
+        bail!("Unsupported bundle version {}", data[4]);
+    }
+    let expected = u32::from_le_bytes([data[5], data[6], data[7], data[8]]) as usize;
+    lzss_decompress(&data[9..], expected)
+}
+
+// --- LZSS codec ----------------------------------------------------------
+//
+// A small self-contained LZ77 variant: no compression dependency to
+// audit, and session JSON is repetitive enough that it does well. Eight
+// tokens share a flag byte; a set bit is a back-reference encoded in
+// two bytes (12-bit distance, 4-bit length), a clear bit is a literal.
+
+const LZSS_WINDOW: usize = 4096;
+const LZSS_MIN_MATCH: usize = 3;
+const LZSS_MAX_MATCH: usize = 18;
+
+fn lzss_compress(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() / 2 + 16);
+    let mut pos = 0;
+
+    while pos < data.len() {
+        let flag_index = out.len();
+        out.push(0);
+        let mut flags = 0u8;
+
+        for bit in 0..8 {
+            if pos >= data.len() {
+                break;
+            }
+            let (distance, length) = longest_match(data, pos);
+            if length >= LZSS_MIN_MATCH {
+                flags |= 1 << bit;
+                let offset = distance - 1;
+                out.push((offset & 0xFF) as u8);
+                out.push((((offset >> 8) as u8) << 4) | ((length - LZSS_MIN_MATCH) as u8));
+                pos += length;
+            } else {
+                out.push(data[pos]);
+                pos += 1;
+            }
+        }
+
+        out[flag_index] = flags;
+    }
+
+    out
+}
+
+/// Longest match for the bytes at `pos` within the sliding window,
+/// as (distance back, length)
+fn longest_match(data: &[u8], pos: usize) -> (usize, usize) {
+    let max_len = LZSS_MAX_MATCH.min(data.len() - pos);
+    let mut best = (0, 0);
+    if max_len < LZSS_MIN_MATCH {
+        return best;
+    }
+
+    for candidate in pos.saturating_sub(LZSS_WINDOW)..pos {
+        let mut len = 0;
+        while len < max_len && data[candidate + len] == data[pos + len] {
+            len += 1;
+        }
+        if len > best.1 {
+            best = (pos - candidate, len);
+            if len == max_len {
+                break;
+            }
+        }
+    }
+
+    best
+}
+
+fn lzss_decompress(data: &[u8], expected: usize) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(expected);
+    let mut pos = 0;
+
+    while pos < data.len() && out.len() < expected {
+        let flags = data[pos];
+        pos += 1;
+
+        for bit in 0..8 {
+            if out.len() >= expected || pos >= data.len() {
+                break;
+            }
+            if flags & (1 << bit) != 0 {
+                if pos + 1 >= data.len() {
+                    bail!("Truncated bundle");
+                }
+                let low = data[pos] as usize;
+                let high = data[pos + 1] as usize;
+                pos += 2;
+                let distance = ((high >> 4) << 8 | low) + 1;
+                let length = (high & 0xF) + LZSS_MIN_MATCH;
+                if distance > out.len() {
+                    bail!("Corrupt bundle: back-reference before start of data");
+                }
+                // Byte-at-a-time copy so overlapping references work
+                let from = out.len() - distance;
+                for i in 0..length {
+                    let byte = out[from + i];
+                    out.push(byte);
+                }
+            } else {
+                out.push(data[pos]);
+                pos += 1;
+            }
+        }
+    }
+
+    if out.len() != expected {
+        bail!(
+            "Bundle length mismatch: expected {} bytes, got {}",
+            expected,
+            out.len()
+        );
+    }
+    Ok(out)
+}
+
+// --- Age parsing ---------------------------------------------------------
+
+/// Parse an `--older-than` age like "90d", "12h" or "30m"; a bare
+/// number means days
+pub fn parse_age(spec: &str) -> Result<std::time::Duration> {
+    let spec = spec.trim();
+    let (value, unit) = match spec.chars().last() {
+        Some(unit) if unit.is_ascii_alphabetic() => (&spec[..spec.len() - 1], unit),
+        _ => (spec, 'd'),
+    };
+    let value: u64 = value
+        .parse()
+        .map_err(|_| anyhow!("Invalid age '{}'. Use forms like 90d, 12h or 30m", spec))?;
+
+    let seconds = match unit {
+        'm' => value * 60,
+        'h' => value * 3600,
+        'd' => value * 86400,
+        _ => bail!("Unknown age unit '{}'. Use m, h or d", unit),
+    };
+    Ok(std::time::Duration::from_secs(seconds))
+}
+
+// --- Storage backends ----------------------------------------------------
+
+/// Where archived bundles live. Implementations only need blind
+/// key/value put and get; listing stays local.
+#[async_trait]
+pub trait Backend: Send + Sync {
+    /// Human-readable destination for status output
+    fn name(&self) -> String;
+
+    /// Upload a bundle under the given key
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<()>;
+
+    /// Download the bundle stored under the given key
+    async fn get(&self, key: &str) -> Result<Vec<u8>>;
+}
+
+/// Build the backend selected by the `[archive]` config table; without
+/// one, bundles go to an `archive` directory next to the sessions
+pub fn backend_from_config(config: &ArchiveConfig) -> Result<Box<dyn Backend>> {
+    match config.backend.as_deref().unwrap_or("local") {
+        "local" => {
+            let path = config
+                .path
+                .as_ref()
+                .map(std::path::PathBuf::from)
+                .unwrap_or_else(|| crate::paths::data_dir().join("archive"));
+            Ok(Box::new(LocalDir { path }))
+        }
+        "s3" => Ok(Box::new(S3Backend {
+            endpoint: required(config, "url", &config.url)?,
+            bucket: required(config, "bucket", &config.bucket)?,
+            region: config.region.clone().unwrap_or_else(|| "us-east-1".to_string()),
+            access_key: required(config, "access_key", &config.access_key)?,
+            secret_key: required(config, "secret_key", &config.secret_key)?,
+            client: reqwest::Client::new(),
+        })),
+        "webdav" => Ok(Box::new(WebDavBackend {
+            url: required(config, "url", &config.url)?,
+            username: config.username.clone(),
+            password: config.password.clone(),
+            client: reqwest::Client::new(),
+        })),
+        other => bail!("Unknown archive backend '{}'. Use local, s3 or webdav", other),
+    }
+}
+
+fn required(_config: &ArchiveConfig, key: &str, value: &Option<String>) -> Result<String> {
+    value
+        .clone()
+        .ok_or_else(|| anyhow!("archive.{} is required for this backend", key))
+}
+
+/// Bundles in a plain directory, for NFS mounts or rsync targets
+struct LocalDir {
+    path: std::path::PathBuf,
+}
+
+#[async_trait]
+impl Backend for LocalDir {
+    fn name(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        tokio::fs::create_dir_all(&self.path)
+            .await
+            .context("Failed to create archive directory")?;
+        tokio::fs::write(self.path.join(key), bytes)
+            .await
+            .context("Failed to write bundle")
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        tokio::fs::read(self.path.join(key))
+            .await
+            .with_context(|| format!("No archived bundle '{}'", key))
+    }
+}
+
+/// S3-compatible object store, addressed path-style as
+/// `{endpoint}/{bucket}/{key}` with SigV4 request signing
+struct S3Backend {
+    endpoint: String,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+    client: reqwest::Client,
+}
+
+impl S3Backend {
+    /// Sign and send one request. Only the three headers SigV4 requires
+    /// are signed; bundles are opaque so no content-type is needed.
+    async fn request(&self, method: reqwest::Method, key: &str, body: Vec<u8>) -> Result<reqwest::Response> {
+        let path = format!("/{}/{}", self.bucket, key);
+        let url = format!("{}{}", self.endpoint.trim_end_matches('/'), path);
+        let host = reqwest::Url::parse(&url)
+            .context("Invalid archive.url")?
+            .host_str()
+            .ok_or_else(|| anyhow!("archive.url has no host"))?
+            .to_string();
+
+        let now = chrono::Utc::now();
+        let timestamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex(ring::digest::digest(&ring::digest::SHA256, &body).as_ref());
+
+        let canonical = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+            method, path, host, payload_hash, timestamp, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            timestamp,
+            scope,
+            hex(ring::digest::digest(&ring::digest::SHA256, canonical.as_bytes()).as_ref())
+        );
+
+        // Derive the signing key through the SigV4 HMAC chain
+        let mut signing_key = hmac(format!("AWS4{}", self.secret_key).as_bytes(), date.as_bytes());
+        for part in [self.region.as_bytes(), b"s3", b"aws4_request"] {
+            signing_key = hmac(&signing_key, part);
+        }
+        let signature = hex(&hmac(&signing_key, string_to_sign.as_bytes()));
+
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+            self.access_key, scope, signature
+        );
+
+        let response = self
+            .client
+            .request(method, &url)
+            .header("x-amz-date", timestamp)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("authorization", authorization)
+            .body(body)
+            .send()
+            .await
+            .context("Archive request failed")?;
+
+        if !response.status().is_success() {
+            bail!("Archive backend returned {}", response.status());
+        }
+        Ok(response)
+    }
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, key);
+    ring::hmac::sign(&key, data).as_ref().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[async_trait]
+impl Backend for S3Backend {
+    fn name(&self) -> String {
+        format!("s3://{}", self.bucket)
+    }
+
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        self.request(reqwest::Method::PUT, key, bytes.to_vec()).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        let response = self.request(reqwest::Method::GET, key, Vec::new()).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+/// WebDAV share: plain PUT/GET against `{url}/{key}` with optional
+/// basic auth
+struct WebDavBackend {
+    url: String,
+    username: Option<String>,
+    password: Option<String>,
+    client: reqwest::Client,
+}
+
+impl WebDavBackend {
+    fn builder(&self, method: reqwest::Method, key: &str) -> reqwest::RequestBuilder {
+        let url = format!("{}/{}", self.url.trim_end_matches('/'), key);
+        let mut builder = self.client.request(method, url);
+        if let Some(username) = &self.username {
+            let credentials = format!("{}:{}", username, self.password.as_deref().unwrap_or(""));
+            builder = builder.header(
+                "authorization",
+                format!(
+                    "Basic {}",
+                    base64::engine::general_purpose::STANDARD.encode(credentials)
+                ),
+            );
+        }
+        builder
+    }
+}
+
+#[async_trait]
+impl Backend for WebDavBackend {
+    fn name(&self) -> String {
+        self.url.clone()
+    }
+
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        let response = self
+            .builder(reqwest::Method::PUT, key)
+            .body(bytes.to_vec())
+            .send()
+            .await
+            .context("Archive upload failed")?;
+        if !response.status().is_success() {
+            bail!("Archive backend returned {}", response.status());
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        let response = self
+            .builder(reqwest::Method::GET, key)
+            .send()
+            .await
+            .context("Archive download failed")?;
+        if !response.status().is_success() {
+            bail!("Archive backend returned {}", response.status());
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+}
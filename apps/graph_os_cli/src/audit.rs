@@ -0,0 +1,208 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Maximum size of the active audit log before it is rotated
+const MAX_LOG_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Number of rotated log files to keep around
+const ROTATED_LOGS_KEPT: usize = 3;
+
+/// Parameter keys whose values are redacted before logging
+const REDACTED_KEYS: &[&str] = &[
+    "api_key", "apikey", "authorization", "secret", "rpc_secret", "token",
+    "password", "content", "messages",
+];
+
+/// A single audit record for an outbound API call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub timestamp: DateTime<Utc>,
+    /// Transport used: "jsonrpc" or "grpc"
+    pub transport: String,
+    pub method: String,
+    pub endpoint: String,
+    /// Request parameters with sensitive values redacted
+    pub params: Value,
+    pub latency_ms: u64,
+    /// "ok" or an error description
+    pub status: String,
+    /// Token usage as reported by the provider, if any
+    pub token_usage: Option<Value>,
+}
+
+/// Append-only JSONL audit log with size-based rotation
+#[derive(Debug)]
+pub struct AuditLog {
+    log_path: PathBuf,
+    writer: Mutex<()>,
+}
+
+impl AuditLog {
+    /// Get the shared audit log instance
+    pub fn instance() -> &'static Self {
+        static INSTANCE: std::sync::OnceLock<AuditLog> = std::sync::OnceLock::new();
+        INSTANCE.get_or_init(|| AuditLog {
+            log_path: Self::default_log_path(),
+            writer: Mutex::new(()),
+        })
+    }
+
+    /// Default location of the audit log in the state directory
+    fn default_log_path() -> PathBuf {
+        crate::paths::state_dir().join("audit.jsonl")
+    }
+
+    /// Record an API call; failures to write are reported but never fatal
+    pub fn record(&self, record: &AuditRecord) {
+        if let Err(e) = self.append(record) {
+            eprintln!("Failed to write audit record: {}", e);
+        }
+    }
+
+    fn append(&self, record: &AuditRecord) -> Result<()> {
+        let _guard = self.writer.lock().map_err(|_| anyhow!("Audit log lock poisoned"))?;
+
+        if let Some(parent) = self.log_path.parent() {
+            fs::create_dir_all(parent).context("Failed to create audit log directory")?;
+        }
+
+        // Rotate before appending if the active log is full
+        if let Ok(metadata) = fs::metadata(&self.log_path)
+            && metadata.len() >= MAX_LOG_SIZE
+        {
+            self.rotate()?;
+        }
+
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .with_context(|| format!("Failed to open audit log: {}", self.log_path.display()))?;
+        file.write_all(line.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Shift audit.jsonl -> audit.jsonl.1 -> ... and drop the oldest
+    fn rotate(&self) -> Result<()> {
+        for i in (1..=ROTATED_LOGS_KEPT).rev() {
+            let from = if i == 1 {
+                self.log_path.clone()
+            } else {
+                self.rotated_path(i - 1)
+            };
+            let to = self.rotated_path(i);
+
+            if from.exists() {
+                fs::rename(&from, &to)
+                    .with_context(|| format!("Failed to rotate audit log to {}", to.display()))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.log_path.as_os_str().to_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    /// Read records newer than `since` ago, oldest first, across rotations
+    pub fn read_since(&self, since: Option<Duration>) -> Result<Vec<AuditRecord>> {
+        let cutoff = since.map(|d| Utc::now() - chrono::Duration::from_std(d).unwrap_or_default());
+        let mut records = Vec::new();
+
+        // Oldest rotated file first, active log last
+        let mut paths: Vec<PathBuf> = (1..=ROTATED_LOGS_KEPT)
+            .rev()
+            .map(|i| self.rotated_path(i))
+            .collect();
+        paths.push(self.log_path.clone());
+
+        for path in paths {
+            if !path.exists() {
+                continue;
+            }
+
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read audit log: {}", path.display()))?;
+
+            for line in content.lines() {
+                if line.is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<AuditRecord>(line) {
+                    Ok(record) => {
+                        if cutoff.is_none_or(|c| record.timestamp >= c) {
+                            records.push(record);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Skipping malformed audit record: {}", e);
+                    }
+                }
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// Create an audit log at a custom location (used by tests and tooling)
+    pub fn with_path(path: PathBuf) -> Self {
+        AuditLog {
+            log_path: path,
+            writer: Mutex::new(()),
+        }
+    }
+}
+
+/// Replace values of sensitive keys in params before logging
+pub fn redact_params(params: &Value) -> Value {
+    match params {
+        Value::Object(map) => {
+            let mut redacted = serde_json::Map::new();
+            for (key, value) in map {
+                if REDACTED_KEYS.contains(&key.to_lowercase().as_str()) {
+                    redacted.insert(key.clone(), Value::String("[redacted]".to_string()));
+                } else {
+                    redacted.insert(key.clone(), redact_params(value));
+                }
+            }
+            Value::Object(redacted)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(redact_params).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Parse a human duration like "30s", "15m", "1h" or "2d"
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let (number, unit) = input.split_at(input.len().saturating_sub(1));
+
+    let value: u64 = number
+        .parse()
+        .with_context(|| format!("Invalid duration: {}", input))?;
+
+    let seconds = match unit {
+        "s" => value,
+        "m" => value * 60,
+        "h" => value * 3600,
+        "d" => value * 86400,
+        _ => return Err(anyhow!("Invalid duration unit in '{}', expected s/m/h/d", input)),
+    };
+
+    Ok(Duration::from_secs(seconds))
+}
@@ -0,0 +1,307 @@
+//! OAuth2 / OIDC device-flow authentication for GraphOS endpoints.
+//!
+//! `gos login` runs the device authorization grant against the identity
+//! provider configured on an endpoint (`oidc_issuer` + `oidc_client_id`),
+//! then stores the resulting tokens back into the endpoint config. Before
+//! any transport is wired up, an expired access token is refreshed
+//! automatically with the stored refresh token; a token the server
+//! rejects mid-session is refreshed through [`TokenRefresher`] and the
+//! failed call retried once.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::Value;
+
+use crate::config::{Config, ConfigFormat, ConfigManager, EndpointConfig};
+
+/// Tokens are treated as expired this many seconds early, so a token
+/// that would die mid-request is refreshed up front
+const TOKEN_EXPIRY_LEEWAY: i64 = 60;
+
+/// Scopes requested during login; offline_access asks for a refresh token
+const LOGIN_SCOPE: &str = "openid offline_access";
+
+/// The endpoints we need from the provider's discovery document
+#[derive(Debug, Clone)]
+pub struct ProviderEndpoints {
+    pub device_authorization_endpoint: String,
+    pub token_endpoint: String,
+}
+
+/// The outcome of a login or refresh
+#[derive(Debug, Clone)]
+pub struct TokenSet {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// Unix timestamp the access token expires at, if the provider said
+    pub expires_at: Option<i64>,
+}
+
+/// Parse a token endpoint response, converting `expires_in` (seconds
+/// from now) into an absolute expiry timestamp
+pub fn token_set_from_response(value: &Value, now: i64) -> Result<TokenSet> {
+    if let Some(error) = value.get("error").and_then(|e| e.as_str()) {
+        let description = value
+            .get("error_description")
+            .and_then(|d| d.as_str())
+            .unwrap_or("no description");
+        bail!("Token request failed: {} ({})", error, description);
+    }
+
+    let access_token = value
+        .get("access_token")
+        .and_then(|t| t.as_str())
+        .ok_or_else(|| anyhow!("Token response has no access_token"))?
+        .to_string();
+    let refresh_token = value
+        .get("refresh_token")
+        .and_then(|t| t.as_str())
+        .map(String::from);
+    let expires_at = value
+        .get("expires_in")
+        .and_then(|e| e.as_i64())
+        .map(|expires_in| now + expires_in);
+
+    Ok(TokenSet {
+        access_token,
+        refresh_token,
+        expires_at,
+    })
+}
+
+/// Whether a stored expiry timestamp means the token needs refreshing.
+/// Tokens without an expiry are assumed valid until the server rejects
+/// them.
+pub fn is_token_expired(expiry: Option<i64>, now: i64) -> bool {
+    expiry.is_some_and(|expiry| now + TOKEN_EXPIRY_LEEWAY >= expiry)
+}
+
+/// Copy a token set into an endpoint config for persisting
+pub fn apply_token_set(endpoint: &mut EndpointConfig, tokens: &TokenSet) {
+    endpoint.token = Some(tokens.access_token.clone());
+    // Providers may omit the refresh token on refresh; keep the old one
+    if tokens.refresh_token.is_some() {
+        endpoint.refresh_token = tokens.refresh_token.clone();
+    }
+    endpoint.token_expiry = tokens.expires_at;
+}
+
+/// Fetch the provider's OIDC discovery document
+pub async fn discover(issuer: &str) -> Result<ProviderEndpoints> {
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        issuer.trim_end_matches('/')
+    );
+    let document: Value = reqwest::get(&url)
+        .await
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .json()
+        .await
+        .with_context(|| format!("Invalid discovery document at {}", url))?;
+
+    let endpoint = |key: &str| -> Result<String> {
+        document
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow!("Discovery document has no {}", key))
+    };
+
+    Ok(ProviderEndpoints {
+        device_authorization_endpoint: endpoint("device_authorization_endpoint")?,
+        token_endpoint: endpoint("token_endpoint")?,
+    })
+}
+
+/// Run the device authorization flow: print the user code, then poll the
+/// token endpoint until the user approves (or the code expires)
+pub async fn device_flow_login(issuer: &str, client_id: &str) -> Result<TokenSet> {
+    let provider = discover(issuer).await?;
+    let client = reqwest::Client::new();
+
+    let authorization: Value = client
+        .post(&provider.device_authorization_endpoint)
+        .form(&[("client_id", client_id), ("scope", LOGIN_SCOPE)])
+        .send()
+        .await
+        .context("Device authorization request failed")?
+        .json()
+        .await
+        .context("Invalid device authorization response")?;
+
+    let device_code = authorization
+        .get("device_code")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Device authorization response has no device_code"))?;
+    let user_code = authorization
+        .get("user_code")
+        .and_then(|v| v.as_str())
+        .unwrap_or("(no code)");
+    let verification_uri = authorization
+        .get("verification_uri_complete")
+        .or_else(|| authorization.get("verification_uri"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Device authorization response has no verification_uri"))?;
+    let expires_in = authorization
+        .get("expires_in")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(300);
+    let mut interval = authorization
+        .get("interval")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(5);
+
+    println!("To sign in, open:\n\n  {}\n", verification_uri);
+    println!("and enter the code: {}\n", user_code);
+    println!("Waiting for approval...");
+
+    let deadline = std::time::Instant::now() + Duration::from_secs(expires_in as u64);
+    while std::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+
+        let response: Value = client
+            .post(&provider.token_endpoint)
+            .form(&[
+                ("client_id", client_id),
+                ("device_code", device_code),
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+            ])
+            .send()
+            .await
+            .context("Token request failed")?
+            .json()
+            .await
+            .context("Invalid token response")?;
+
+        match response.get("error").and_then(|e| e.as_str()) {
+            // The user has not approved yet; keep polling
+            Some("authorization_pending") => continue,
+            // The provider wants us to back off
+            Some("slow_down") => {
+                interval += 5;
+                continue;
+            }
+            _ => return token_set_from_response(&response, chrono::Utc::now().timestamp()),
+        }
+    }
+
+    bail!("The device code expired before the login was approved; run `gos login` again");
+}
+
+/// Exchange a refresh token for a fresh access token
+pub async fn refresh(issuer: &str, client_id: &str, refresh_token: &str) -> Result<TokenSet> {
+    let provider = discover(issuer).await?;
+
+    let response: Value = reqwest::Client::new()
+        .post(&provider.token_endpoint)
+        .form(&[
+            ("client_id", client_id),
+            ("refresh_token", refresh_token),
+            ("grant_type", "refresh_token"),
+        ])
+        .send()
+        .await
+        .context("Token refresh request failed")?
+        .json()
+        .await
+        .context("Invalid token refresh response")?;
+
+    token_set_from_response(&response, chrono::Utc::now().timestamp())
+}
+
+/// On-demand token refresh for transport clients: when a server answers
+/// 401 / Unauthenticated mid-session, the adapter calls [`refresh_now`]
+/// and retries once with the token it returns. Clones share one endpoint
+/// state, and the mutex serializes concurrent refreshes so parallel
+/// calls failing together do not each burn the refresh token.
+///
+/// [`refresh_now`]: TokenRefresher::refresh_now
+#[derive(Clone)]
+pub struct TokenRefresher {
+    name: String,
+    endpoint: std::sync::Arc<tokio::sync::Mutex<EndpointConfig>>,
+}
+
+impl std::fmt::Debug for TokenRefresher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The endpoint config holds token material; only name the endpoint
+        f.debug_struct("TokenRefresher")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TokenRefresher {
+    /// A refresher for the named endpoint, when it has everything a
+    /// refresh needs. Endpoints with static API keys get None: a 401
+    /// there means the key is wrong, and retrying cannot help.
+    pub fn for_endpoint(name: &str, endpoint: &EndpointConfig) -> Option<Self> {
+        (endpoint.oidc_issuer.is_some()
+            && endpoint.oidc_client_id.is_some()
+            && endpoint.refresh_token.is_some())
+        .then(|| Self {
+            name: name.to_string(),
+            endpoint: std::sync::Arc::new(tokio::sync::Mutex::new(endpoint.clone())),
+        })
+    }
+
+    /// Refresh the access token now, regardless of its stored expiry —
+    /// the server rejecting it is better evidence than the clock.
+    /// Persists the new tokens and returns the access token to retry
+    /// with.
+    pub async fn refresh_now(&self) -> Result<String> {
+        let mut endpoint = self.endpoint.lock().await;
+        let (Some(issuer), Some(client_id), Some(refresh_token)) = (
+            endpoint.oidc_issuer.clone(),
+            endpoint.oidc_client_id.clone(),
+            endpoint.refresh_token.clone(),
+        ) else {
+            bail!("Endpoint '{}' has no refresh credentials", self.name);
+        };
+
+        let tokens = refresh(&issuer, &client_id, &refresh_token).await?;
+        apply_token_set(&mut endpoint, &tokens);
+
+        let format = Config::find_auth_config_file()
+            .map(|(_, format)| format)
+            .unwrap_or(ConfigFormat::Toml);
+        ConfigManager::instance()
+            .set_endpoint_config(&self.name, endpoint.clone(), format)
+            .await?;
+
+        Ok(tokens.access_token)
+    }
+}
+
+/// Refresh an endpoint's access token if it has expired, persisting the
+/// new tokens. Returns the updated endpoint config, or None when there
+/// was nothing to refresh.
+pub async fn ensure_fresh(name: &str, endpoint: &EndpointConfig) -> Result<Option<EndpointConfig>> {
+    if !is_token_expired(endpoint.token_expiry, chrono::Utc::now().timestamp()) {
+        return Ok(None);
+    }
+    let (Some(issuer), Some(client_id), Some(refresh_token)) = (
+        &endpoint.oidc_issuer,
+        &endpoint.oidc_client_id,
+        &endpoint.refresh_token,
+    ) else {
+        // Expired but nothing to refresh with; the server will reject the
+        // stale token and the user can run `gos login`
+        return Ok(None);
+    };
+
+    let tokens = refresh(issuer, client_id, refresh_token).await?;
+
+    let mut updated = endpoint.clone();
+    apply_token_set(&mut updated, &tokens);
+
+    let format = Config::find_auth_config_file()
+        .map(|(_, format)| format)
+        .unwrap_or(ConfigFormat::Toml);
+    ConfigManager::instance()
+        .set_endpoint_config(name, updated.clone(), format)
+        .await?;
+
+    Ok(Some(updated))
+}
@@ -0,0 +1,217 @@
+//! Load testing for GraphOS server deployments (`gos bench grpc`).
+//!
+//! Fires concurrent gRPC requests for a fixed duration and reports
+//! latency percentiles, throughput and an error breakdown. The
+//! histogram is kept separate from the runner so other transports can
+//! reuse it.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::adapters::GrpcClient;
+use crate::error::Result;
+
+/// Which RPC the benchmark exercises
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchMethod {
+    GetSystemInfo,
+    ListSystemInfo,
+}
+
+impl BenchMethod {
+    /// Parse the --method argument
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "get" | "getsysteminfo" => Ok(BenchMethod::GetSystemInfo),
+            "list" | "listsysteminfo" => Ok(BenchMethod::ListSystemInfo),
+            other => Err(crate::error::GraphOsError::Config(format!(
+                "Unknown bench method '{}'. Available: get, list", other
+            ))),
+        }
+    }
+
+    /// RPC name for the report header
+    pub fn rpc_name(&self) -> &'static str {
+        match self {
+            BenchMethod::GetSystemInfo => "SystemInfoService.GetSystemInfo",
+            BenchMethod::ListSystemInfo => "SystemInfoService.ListSystemInfo",
+        }
+    }
+}
+
+/// Collected request latencies in microseconds
+#[derive(Debug, Default, Clone)]
+pub struct LatencyHistogram {
+    samples: Vec<u64>,
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one successful request's latency
+    pub fn record(&mut self, latency: Duration) {
+        self.samples.push(latency.as_micros() as u64);
+    }
+
+    /// Fold another histogram's samples into this one
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        self.samples.extend_from_slice(&other.samples);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Nearest-rank percentile; `p` is in [0, 100]
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let mut sorted = self.samples.clone();
+        sorted.sort_unstable();
+        let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+        let index = rank.clamp(1, sorted.len()) - 1;
+        Some(Duration::from_micros(sorted[index]))
+    }
+
+    pub fn min(&self) -> Option<Duration> {
+        self.samples.iter().min().map(|&us| Duration::from_micros(us))
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.samples.iter().max().map(|&us| Duration::from_micros(us))
+    }
+
+    pub fn mean(&self) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let total: u64 = self.samples.iter().sum();
+        Some(Duration::from_micros(total / self.samples.len() as u64))
+    }
+}
+
+/// Outcome of one benchmark run
+#[derive(Debug, Default)]
+pub struct BenchReport {
+    /// Latencies of successful requests
+    pub histogram: LatencyHistogram,
+    /// Failure counts keyed by error message
+    pub errors: BTreeMap<String, usize>,
+    /// Wall-clock time the run took
+    pub elapsed: Duration,
+}
+
+impl BenchReport {
+    /// Total requests attempted, successes and failures combined
+    pub fn total_requests(&self) -> usize {
+        self.histogram.len() + self.errors.values().sum::<usize>()
+    }
+
+    /// Render the report the way `gos bench grpc` prints it
+    pub fn render(&self, rpc_name: &str, concurrency: usize) -> String {
+        let mut out = String::new();
+        let total = self.total_requests();
+        let succeeded = self.histogram.len();
+        let secs = self.elapsed.as_secs_f64().max(f64::EPSILON);
+
+        out.push_str(&format!("Benchmark: {} ({} workers)\n", rpc_name, concurrency));
+        out.push_str(&format!(
+            "Requests:  {} total, {} ok, {} failed in {:.1}s ({:.1} req/s)\n",
+            total,
+            succeeded,
+            total - succeeded,
+            self.elapsed.as_secs_f64(),
+            total as f64 / secs,
+        ));
+
+        if !self.histogram.is_empty() {
+            let fmt = |d: Option<Duration>| match d {
+                Some(d) => format!("{:.2}ms", d.as_secs_f64() * 1000.0),
+                None => "-".to_string(),
+            };
+            out.push_str(&format!(
+                "Latency:   min {} / mean {} / p50 {} / p90 {} / p99 {} / max {}\n",
+                fmt(self.histogram.min()),
+                fmt(self.histogram.mean()),
+                fmt(self.histogram.percentile(50.0)),
+                fmt(self.histogram.percentile(90.0)),
+                fmt(self.histogram.percentile(99.0)),
+                fmt(self.histogram.max()),
+            ));
+        }
+
+        if !self.errors.is_empty() {
+            out.push_str("Errors:\n");
+            for (message, count) in &self.errors {
+                out.push_str(&format!("  {:>6}  {}\n", count, message));
+            }
+        }
+
+        out
+    }
+}
+
+/// Run `concurrency` workers against the given client for `duration`,
+/// each looping the chosen RPC as fast as the server answers. Workers
+/// share the client's channel, so this measures one connection under
+/// concurrent load -- the same shape a busy TUI instance produces.
+pub async fn run_grpc_bench(
+    client: GrpcClient,
+    method: BenchMethod,
+    concurrency: usize,
+    duration: Duration,
+) -> BenchReport {
+    let started = Instant::now();
+    let deadline = started + duration;
+
+    let mut workers = Vec::with_capacity(concurrency);
+    for _ in 0..concurrency {
+        let client = client.clone();
+        workers.push(tokio::spawn(async move {
+            let mut histogram = LatencyHistogram::new();
+            let mut errors: BTreeMap<String, usize> = BTreeMap::new();
+
+            while Instant::now() < deadline {
+                let call_started = Instant::now();
+                // The un-audited senders keep thousands of benchmark
+                // calls out of the audit log
+                let outcome = match method {
+                    BenchMethod::GetSystemInfo => {
+                        client.send_get_system_info().await.map(|_| ())
+                    }
+                    BenchMethod::ListSystemInfo => {
+                        client.send_list_system_info(Some(1), None).await.map(|_| ())
+                    }
+                };
+
+                match outcome {
+                    Ok(()) => histogram.record(call_started.elapsed()),
+                    Err(e) => *errors.entry(e.to_string()).or_insert(0) += 1,
+                }
+            }
+
+            (histogram, errors)
+        }));
+    }
+
+    let mut report = BenchReport::default();
+    for worker in workers {
+        // A panicked worker loses its samples but should not sink the run
+        if let Ok((histogram, errors)) = worker.await {
+            report.histogram.merge(&histogram);
+            for (message, count) in errors {
+                *report.errors.entry(message).or_insert(0) += count;
+            }
+        }
+    }
+    report.elapsed = started.elapsed();
+
+    report
+}
@@ -0,0 +1,155 @@
+//! Local JSON↔gRPC bridge backing `gos grpc proxy`.
+//!
+//! Exposes the gRPC SystemInfo service (and the standard health probe)
+//! over a plain REST/JSON HTTP server, so curl and scripts can consume
+//! GraphOS data without gRPC tooling. Each route maps one RPC: the
+//! request is translated from query parameters into the generated
+//! request type, and the response message is rendered field-by-field
+//! as JSON.
+
+use std::future::Future;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use crate::adapters::grpc::graph_os::SystemInfo;
+use crate::adapters::GrpcClient;
+
+/// Shared state for one bridge instance: the upstream gRPC client,
+/// behind a lock because its calls take `&mut self`
+struct BridgeState {
+    client: Mutex<GrpcClient>,
+}
+
+/// Start the bridge and run it until the process exits
+pub async fn run(listen: &str, port: u16, client: GrpcClient) -> Result<()> {
+    let (addr, server) = bind(listen, port, client)?;
+    println!("JSON bridge listening on http://{}", addr);
+    println!("  GET /v1/system-info                 -> SystemInfoService.GetSystemInfo");
+    println!("  GET /v1/system-info/history?limit=N -> SystemInfoService.ListSystemInfo");
+    println!("  GET /v1/health?service=NAME         -> grpc.health.v1.Health.Check");
+    server.await
+}
+
+/// Bind the bridge, returning the bound address and a future that
+/// serves requests. Split from [`run`] so tests can bind port 0.
+pub fn bind(
+    listen: &str,
+    port: u16,
+    client: GrpcClient,
+) -> Result<(SocketAddr, impl Future<Output = Result<()>>)> {
+    let state = Arc::new(BridgeState { client: Mutex::new(client) });
+
+    let router = Router::new()
+        .route("/v1/system-info", get(handle_get_system_info))
+        .route("/v1/system-info/history", get(handle_list_system_info))
+        .route("/v1/health", get(handle_health))
+        .with_state(state);
+
+    let addr = (listen, port)
+        .to_socket_addrs()
+        .with_context(|| format!("Failed to resolve listen address {}:{}", listen, port))?
+        .next()
+        .ok_or_else(|| anyhow!("Listen address {}:{} resolved to nothing", listen, port))?;
+
+    let server = axum::Server::try_bind(&addr)
+        .with_context(|| format!("Failed to bind {}", addr))?
+        .serve(router.into_make_service());
+    let bound = server.local_addr();
+
+    Ok((bound, async move { server.await.context("Bridge server failed") }))
+}
+
+/// Render an upstream failure as a JSON error body; the bridge itself
+/// is fine, so this is a gateway error rather than a 500
+fn upstream_error(error: impl std::fmt::Display) -> Response {
+    (
+        StatusCode::BAD_GATEWAY,
+        Json(json!({ "error": error.to_string() })),
+    )
+        .into_response()
+}
+
+/// Map a SystemInfo message to JSON, field by field. The generated
+/// types carry no serde derives, so the mapping is explicit — which
+/// also keeps the wire format stable if the proto grows fields.
+pub fn system_info_json(info: &SystemInfo) -> Value {
+    json!({
+        "id": info.id,
+        "hostname": info.hostname,
+        "timestamp": info.timestamp,
+        "cpu_count": info.cpu_count,
+        "cpu_load_1m": info.cpu_load_1m,
+        "cpu_load_5m": info.cpu_load_5m,
+        "cpu_load_15m": info.cpu_load_15m,
+        "memory_total": info.memory_total,
+        "memory_used": info.memory_used,
+        "memory_free": info.memory_free,
+        "uptime": info.uptime,
+        "os_version": info.os_version,
+        "platform": info.platform,
+        "architecture": info.architecture,
+    })
+}
+
+async fn handle_get_system_info(State(state): State<Arc<BridgeState>>) -> Response {
+    let mut client = state.client.lock().await;
+    match client.get_system_info().await {
+        Ok(info) => Json(system_info_json(&info)).into_response(),
+        Err(e) => upstream_error(e),
+    }
+}
+
+/// Query parameters for the history route, mirroring
+/// ListSystemInfoRequest
+#[derive(Deserialize)]
+struct HistoryQuery {
+    limit: Option<i32>,
+    since: Option<i64>,
+}
+
+async fn handle_list_system_info(
+    State(state): State<Arc<BridgeState>>,
+    Query(query): Query<HistoryQuery>,
+) -> Response {
+    let mut client = state.client.lock().await;
+    match client.list_system_info(query.limit, query.since).await {
+        Ok(list) => {
+            let items: Vec<Value> = list.items.iter().map(system_info_json).collect();
+            Json(json!({ "items": items })).into_response()
+        }
+        Err(e) => upstream_error(e),
+    }
+}
+
+/// Query parameters for the health route; an absent service probes the
+/// server as a whole, as the health protocol specifies
+#[derive(Deserialize)]
+struct HealthQuery {
+    service: Option<String>,
+}
+
+async fn handle_health(
+    State(state): State<Arc<BridgeState>>,
+    Query(query): Query<HealthQuery>,
+) -> Response {
+    let service = query.service.unwrap_or_default();
+    let mut client = state.client.lock().await;
+    match client.check_health(&service).await {
+        Ok(status) => Json(json!({
+            "service": service,
+            "status": status.to_string(),
+        }))
+        .into_response(),
+        Err(e) => upstream_error(e),
+    }
+}
@@ -1,15 +1,18 @@
 use std::sync::Arc;
 
-use crate::adapters::{JsonRpcClient, Message as ApiMessage, MessageRole};
+use crate::adapters::grpc::graph_os::ChatMessage as GrpcChatMessage;
+use crate::adapters::{ConnectionState, GrpcAuth, GrpcClient, JsonRpcClient, Message as ApiMessage, MessageRole};
 use crate::session::{ChatMessage as SessionChatMessage, Session, SessionManager};
 use crossterm::event::KeyEvent;
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
 };
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
+use crate::usage::{self, ModelPrice, UsageLog, UsageRecord};
+
 // Commands that can be executed with slash commands
 #[derive(Debug, Clone)]
 pub enum Command {
@@ -17,9 +20,50 @@ pub enum Command {
     Exit,
     ToggleStreaming,
     Config,
+    Fork,
+    Template(Option<String>),
+    Bookmark(Option<usize>),
+    Bookmarks,
+    /// Insert a quoted excerpt of a message into the input and thread
+    /// the next outgoing message as a reply to it
+    Quote(Option<usize>),
+    /// Keep a message in the conversation history even after the
+    /// surrounding page has been truncated away (most recent by default)
+    Pin(Option<usize>),
+    Unpin(Option<usize>),
+    /// Browse this session's restore points (snapshots)
+    History,
+    /// Pull relevant exchanges from past conversations into context,
+    /// or clear them with "off"
+    Recall(Option<String>),
     Provider(String),
     Model(String),
+    Share,
+    Run,
+    /// Compare against another session, or the parent when omitted
+    Diff(Option<String>),
+    /// Resolve a held-back large paste: insert it or attach as a file
+    Paste(Option<String>),
+    /// Ask the model to pick up where an interrupted answer stopped
+    Continue,
+    /// Route messages to named personas, or list them when omitted
+    Agents(Option<String>),
+    /// Constrain responses to a JSON Schema file, or show the active one
+    Schema(Option<String>),
     Debug(bool),
+    Context(Option<String>),
+    /// Attach, detach or list knowledge packs for this session
+    Kb(Option<String>),
+    /// Record, play or list keyboard macros
+    Macro(Option<String>),
+    /// Submit a line as a user message; produced by macro playback
+    /// only, never parsed from input
+    Send(String),
+    /// Toggle the context-visualizer pane showing what the next
+    /// request will send
+    Tokens,
+    /// Switch the layout density preset, or show the active one
+    Layout(Option<String>),
     Unknown(String),
 }
 
@@ -29,6 +73,68 @@ impl Command {
             return None;
         }
         
+        // /context keeps the original case since its argument is a path
+        let trimmed = input.trim();
+        if trimmed == "/context" {
+            return Some(Command::Context(None));
+        }
+        if let Some(arg) = trimmed.strip_prefix("/context ") {
+            let arg = arg.trim();
+            if !arg.is_empty() {
+                return Some(Command::Context(Some(arg.to_string())));
+            }
+            return Some(Command::Context(None));
+        }
+
+        // /kb keeps the original case too; pack names are case-sensitive
+        if trimmed == "/kb" {
+            return Some(Command::Kb(None));
+        }
+        if let Some(arg) = trimmed.strip_prefix("/kb ") {
+            let arg = arg.trim();
+            if !arg.is_empty() {
+                return Some(Command::Kb(Some(arg.to_string())));
+            }
+            return Some(Command::Kb(None));
+        }
+
+        // /macro keeps the original case: recorded steps and play
+        // arguments are replayed verbatim
+        if trimmed == "/macro" {
+            return Some(Command::Macro(None));
+        }
+        if let Some(arg) = trimmed.strip_prefix("/macro ") {
+            let arg = arg.trim();
+            if !arg.is_empty() {
+                return Some(Command::Macro(Some(arg.to_string())));
+            }
+            return Some(Command::Macro(None));
+        }
+
+        // /recall keeps the original case, its argument is a query
+        if trimmed == "/recall" {
+            return Some(Command::Recall(None));
+        }
+        if let Some(arg) = trimmed.strip_prefix("/recall ") {
+            let arg = arg.trim();
+            if !arg.is_empty() {
+                return Some(Command::Recall(Some(arg.to_string())));
+            }
+            return Some(Command::Recall(None));
+        }
+
+        // /schema keeps the original case too, its argument is a path
+        if trimmed == "/schema" {
+            return Some(Command::Schema(None));
+        }
+        if let Some(arg) = trimmed.strip_prefix("/schema ") {
+            let arg = arg.trim();
+            if !arg.is_empty() {
+                return Some(Command::Schema(Some(arg.to_string())));
+            }
+            return Some(Command::Schema(None));
+        }
+
         let cmd_input = input.trim().to_lowercase();
         
         // Check for commands with arguments
@@ -42,6 +148,78 @@ impl Command {
             return Some(Command::Model(model));
         }
         
+        if let Some(name) = cmd_input.strip_prefix("/template ") {
+            let name = name.trim();
+            if !name.is_empty() {
+                return Some(Command::Template(Some(name.to_string())));
+            }
+            return Some(Command::Template(None));
+        }
+        
+        if let Some(arg) = cmd_input.strip_prefix("/bookmark ") {
+            let arg = arg.trim();
+            if let Ok(index) = arg.parse::<usize>() {
+                return Some(Command::Bookmark(Some(index)));
+            }
+            return Some(Command::Unknown(cmd_input[1..].to_string()));
+        }
+
+        if let Some(arg) = cmd_input.strip_prefix("/quote ") {
+            let arg = arg.trim();
+            if let Ok(index) = arg.parse::<usize>() {
+                return Some(Command::Quote(Some(index)));
+            }
+            return Some(Command::Unknown(cmd_input[1..].to_string()));
+        }
+
+        if let Some(arg) = cmd_input.strip_prefix("/pin ") {
+            let arg = arg.trim();
+            if let Ok(index) = arg.parse::<usize>() {
+                return Some(Command::Pin(Some(index)));
+            }
+            return Some(Command::Unknown(cmd_input[1..].to_string()));
+        }
+
+        if let Some(arg) = cmd_input.strip_prefix("/unpin ") {
+            let arg = arg.trim();
+            if let Ok(index) = arg.parse::<usize>() {
+                return Some(Command::Unpin(Some(index)));
+            }
+            return Some(Command::Unknown(cmd_input[1..].to_string()));
+        }
+
+        if let Some(arg) = cmd_input.strip_prefix("/agents ") {
+            let arg = arg.trim();
+            if !arg.is_empty() {
+                return Some(Command::Agents(Some(arg.to_string())));
+            }
+            return Some(Command::Agents(None));
+        }
+
+        if let Some(arg) = cmd_input.strip_prefix("/diff ") {
+            let arg = arg.trim();
+            if !arg.is_empty() {
+                return Some(Command::Diff(Some(arg.to_string())));
+            }
+            return Some(Command::Diff(None));
+        }
+
+        if let Some(arg) = cmd_input.strip_prefix("/paste ") {
+            let arg = arg.trim();
+            if !arg.is_empty() {
+                return Some(Command::Paste(Some(arg.to_string())));
+            }
+            return Some(Command::Paste(None));
+        }
+
+        if let Some(arg) = cmd_input.strip_prefix("/layout ") {
+            let arg = arg.trim();
+            if !arg.is_empty() {
+                return Some(Command::Layout(Some(arg.to_string())));
+            }
+            return Some(Command::Layout(None));
+        }
+
         if cmd_input == "/debug on" {
             return Some(Command::Debug(true));
         }
@@ -56,21 +234,371 @@ impl Command {
             "/exit" => Some(Command::Exit),
             "/stream" => Some(Command::ToggleStreaming),
             "/config" => Some(Command::Config),
+            "/fork" => Some(Command::Fork),
+            "/template" => Some(Command::Template(None)),
+            "/bookmark" => Some(Command::Bookmark(None)),
+            "/bookmarks" => Some(Command::Bookmarks),
+            "/quote" => Some(Command::Quote(None)),
+            "/pin" => Some(Command::Pin(None)),
+            "/unpin" => Some(Command::Unpin(None)),
+            "/history" => Some(Command::History),
+            "/share" => Some(Command::Share),
+            "/run" => Some(Command::Run),
+            "/diff" => Some(Command::Diff(None)),
+            "/paste" => Some(Command::Paste(None)),
+            "/continue" => Some(Command::Continue),
+            "/agents" => Some(Command::Agents(None)),
+            "/layout" => Some(Command::Layout(None)),
+            "/tokens" => Some(Command::Tokens),
             _ => Some(Command::Unknown(cmd_input[1..].to_string())),
         }
     }
     
-    pub fn help_text() -> String {
-        "/help - Show this help message\n\
-        /exit - Exit the application\n\
-        /stream - Toggle streaming mode\n\
-        /config - Show current configuration\n\
-        /provider <name> - Switch provider (openai, anthropic, gemini, custom)\n\
-        /model <name> - Set model (e.g., gpt-4o, claude-3-opus, gemini-pro)\n\
-        /debug on|off - Toggle debug mode".to_string()
+}
+
+/// Layout density presets switched with /layout. Compact trims the
+/// commands panel and caps the input box lower, so more of the
+/// terminal goes to the transcript.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LayoutMode {
+    #[default]
+    Comfortable,
+    Compact,
+}
+
+impl LayoutMode {
+    /// Parse a /layout argument or the `mode` key of the config's
+    /// `[layout]` table; unknown names are None, not a default
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "comfortable" => Some(LayoutMode::Comfortable),
+            "compact" => Some(LayoutMode::Compact),
+            _ => None,
+        }
+    }
+
+    /// Name shown in the transcript and written to the config file
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LayoutMode::Comfortable => "comfortable",
+            LayoutMode::Compact => "compact",
+        }
+    }
+}
+
+/// Cap on the rows Ctrl+Up can add to the input box, so a held key
+/// cannot push the transcript off the screen
+pub const MAX_INPUT_EXTRA_ROWS: u16 = 8;
+
+/// How many alias indirections expansion will follow. Cycles are
+/// caught by the visited set; this bounds pathological (acyclic but
+/// deep) chains.
+const ALIAS_DEPTH_LIMIT: usize = 8;
+
+/// Expand configured aliases in a slash input into concrete commands.
+/// An alias value may chain several commands with `;` and may reference
+/// other aliases; arguments typed after the alias are appended to the
+/// last command of its expansion. Non-alias input passes through as a
+/// single entry. A cycle, an over-deep chain, or an expansion that is
+/// not a slash command is an error rather than a silent drop.
+pub fn expand_aliases(
+    input: &str,
+    aliases: &std::collections::HashMap<String, String>,
+) -> std::result::Result<Vec<String>, String> {
+    fn expand(
+        input: &str,
+        aliases: &std::collections::HashMap<String, String>,
+        visited: &mut Vec<String>,
+        out: &mut Vec<String>,
+    ) -> std::result::Result<(), String> {
+        let trimmed = input.trim();
+        let Some(rest) = trimmed.strip_prefix('/') else {
+            return Err(format!("alias expands to non-command '{}'", trimmed));
+        };
+        let (name, args) = match rest.split_once(char::is_whitespace) {
+            Some((name, args)) => (name, args.trim()),
+            None => (rest, ""),
+        };
+
+        let Some(value) = aliases.get(name) else {
+            out.push(trimmed.to_string());
+            return Ok(());
+        };
+        if visited.iter().any(|seen| seen == name) {
+            return Err(format!("alias cycle: {} -> {}", visited.join(" -> "), name));
+        }
+        if visited.len() >= ALIAS_DEPTH_LIMIT {
+            return Err(format!("alias chain deeper than {} levels", ALIAS_DEPTH_LIMIT));
+        }
+        visited.push(name.to_string());
+
+        let parts: Vec<&str> = value.split(';').map(str::trim).filter(|p| !p.is_empty()).collect();
+        if parts.is_empty() {
+            return Err(format!("alias '{}' expands to nothing", name));
+        }
+        for (i, part) in parts.iter().enumerate() {
+            // Typed arguments ride on the last command of the sequence
+            let part = if i == parts.len() - 1 && !args.is_empty() {
+                format!("{} {}", part, args)
+            } else {
+                (*part).to_string()
+            };
+            expand(&part, aliases, visited, out)?;
+        }
+
+        visited.pop();
+        Ok(())
+    }
+
+    // Only slash input can name an alias; anything else passes through
+    if !input.trim_start().starts_with('/') {
+        return Ok(vec![input.to_string()]);
+    }
+
+    let mut out = Vec::new();
+    let mut visited = Vec::new();
+    expand(input, aliases, &mut visited, &mut out)?;
+    Ok(out)
+}
+
+/// Typing after this much idle time triggers a background connection
+/// pre-warm, so handshake work overlaps with composing the message
+/// instead of adding to time-to-first-token
+const PREWARM_AFTER_IDLE: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Whether connection pre-warming is disabled, from the
+/// GRAPHOS_NO_PREWARM environment variable
+fn prewarm_disabled() -> bool {
+    std::env::var("GRAPHOS_NO_PREWARM").is_ok_and(|v| v == "1" || v == "true")
+}
+
+/// Pastes larger than this are held back instead of inserted, since
+/// they are usually logs or files better attached as context
+pub const LARGE_PASTE_THRESHOLD: usize = 8 * 1024;
+
+/// How many past exchanges /recall pulls into context
+const RECALL_LIMIT: usize = 3;
+
+/// Per-message character cap on recalled excerpts
+const RECALL_EXCERPT_CHARS: usize = 600;
+
+/// Line cap on /quote excerpts; longer messages are elided so a quote
+/// does not swamp the input box
+const QUOTE_EXCERPT_LINES: usize = 8;
+
+/// User message sent by /continue to resume an interrupted answer
+pub const CONTINUE_PROMPT: &str =
+    "Continue exactly from where your previous answer stopped, without repeating it.";
+
+/// Clean pasted text for insertion into the input buffer: normalize
+/// line endings and strip ANSI escape sequences and stray control
+/// characters that terminals sometimes leak into pastes
+pub fn sanitize_paste(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            // Drop a whole escape sequence: CSI until its final byte,
+            // OSC until BEL or ST, anything else is two characters
+            '\x1b' => match chars.next() {
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\r' => {
+                // \r\n and bare \r both become \n
+                if chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                out.push('\n');
+            }
+            '\n' | '\t' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// How far back the rolling tokens/sec window reaches
+const TELEMETRY_WINDOW: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Live telemetry for an in-flight streaming response: time to first
+/// token, a rolling tokens/sec rate for the status indicator, and the
+/// final averages stored in message metadata. Arrival times are passed
+/// in explicitly so the math is testable without sleeping.
+#[derive(Debug, Clone)]
+pub struct StreamTelemetry {
+    started: std::time::Instant,
+    first_token: Option<std::time::Instant>,
+    /// Cumulative estimated tokens
+    tokens: u64,
+    /// (arrival, cumulative tokens) per chunk; the in-window tail
+    /// drives the rolling rate
+    samples: Vec<(std::time::Instant, u64)>,
+}
+
+impl StreamTelemetry {
+    pub fn new(now: std::time::Instant) -> Self {
+        StreamTelemetry {
+            started: now,
+            first_token: None,
+            tokens: 0,
+            samples: Vec::new(),
+        }
+    }
+
+    /// Record an arrived chunk
+    pub fn on_chunk(&mut self, text: &str, now: std::time::Instant) {
+        if self.first_token.is_none() {
+            self.first_token = Some(now);
+        }
+        self.tokens += usage::estimate_tokens(text);
+        self.samples.push((now, self.tokens));
+    }
+
+    /// Milliseconds from request start to the first token
+    pub fn first_token_ms(&self) -> Option<u64> {
+        self.first_token
+            .map(|at| at.duration_since(self.started).as_millis() as u64)
+    }
+
+    /// Tokens/sec over the trailing window, for the live indicator
+    pub fn rolling_tokens_per_sec(&self, now: std::time::Instant) -> Option<f64> {
+        let cutoff = now.checked_sub(TELEMETRY_WINDOW).unwrap_or(self.started);
+        // Oldest sample still inside the window is the rate base
+        let (base_at, base_tokens) = *self
+            .samples
+            .iter()
+            .find(|(at, _)| *at >= cutoff)
+            .or_else(|| self.samples.first())?;
+
+        let elapsed = now.duration_since(base_at).as_secs_f64();
+        if elapsed <= 0.0 || self.tokens <= base_tokens {
+            return None;
+        }
+        Some((self.tokens - base_tokens) as f64 / elapsed)
+    }
+
+    /// Average tokens/sec over the whole stream, for message metadata
+    pub fn average_tokens_per_sec(&self, now: std::time::Instant) -> Option<f64> {
+        let first = self.first_token?;
+        let elapsed = now.duration_since(first).as_secs_f64();
+        if elapsed <= 0.0 || self.tokens == 0 {
+            return None;
+        }
+        Some(self.tokens as f64 / elapsed)
+    }
+
+    /// Short live status shown next to the typing indicator
+    pub fn indicator(&self, now: std::time::Instant) -> String {
+        match self.rolling_tokens_per_sec(now) {
+            Some(rate) => format!("{:.1} tok/s", rate),
+            None if self.first_token.is_none() => {
+                format!("waiting {:.1}s", now.duration_since(self.started).as_secs_f64())
+            }
+            None => format!("~{} tokens", self.tokens),
+        }
+    }
+}
+
+/// How many finished responses the status-bar sparklines look back over
+pub const SPARKLINE_SAMPLES: usize = 20;
+
+/// Ring buffer of recent response latencies and token rates, one entry
+/// per finished response, feeding the status-bar sparklines so endpoint
+/// degradation shows at a glance
+#[derive(Debug, Default)]
+pub struct ResponseHistory {
+    /// (latency in ms, tokens/sec when the stream reported one),
+    /// oldest first
+    samples: std::collections::VecDeque<(u64, Option<f64>)>,
+}
+
+impl ResponseHistory {
+    /// Record one finished response, dropping the oldest sample once
+    /// the window is full
+    pub fn push(&mut self, latency_ms: u64, tokens_per_sec: Option<f64>) {
+        if self.samples.len() == SPARKLINE_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((latency_ms, tokens_per_sec));
+    }
+
+    /// Latencies in window order, for the sparkline
+    pub fn latencies(&self) -> Vec<f64> {
+        self.samples.iter().map(|(ms, _)| *ms as f64).collect()
+    }
+
+    /// Token rates in window order; non-streaming responses carry no
+    /// rate and are skipped
+    pub fn token_rates(&self) -> Vec<f64> {
+        self.samples.iter().filter_map(|(_, rate)| *rate).collect()
+    }
+
+    /// Status-line segment with both sparklines and the newest values,
+    /// or None before the first response
+    pub fn status_segment(&self, style: &crate::render::RenderStyle) -> Option<String> {
+        let (last_ms, last_rate) = *self.samples.back()?;
+        let mut segment = format!(
+            "lat {} {}ms",
+            crate::render::sparkline(&self.latencies(), style.sparkline_ramp()),
+            last_ms
+        );
+        let rates = self.token_rates();
+        if let Some(rate) = last_rate {
+            segment.push_str(&format!(
+                " | tok/s {} {:.1}",
+                crate::render::sparkline(&rates, style.sparkline_ramp()),
+                rate
+            ));
+        }
+        Some(segment)
     }
 }
 
+/// Rank available models by similarity to a name that did not match
+/// exactly: substring matches first, then names sharing a prefix of at
+/// least three characters. Returns at most three suggestions.
+pub fn model_suggestions(requested: &str, available: &[String]) -> Vec<String> {
+    let wanted = requested.to_lowercase();
+    let mut ranked: Vec<(usize, String)> = available
+        .iter()
+        .filter_map(|candidate| {
+            let lower = candidate.to_lowercase();
+            if lower.contains(&wanted) || wanted.contains(&lower) {
+                return Some((usize::MAX, candidate.clone()));
+            }
+            let shared = lower
+                .chars()
+                .zip(wanted.chars())
+                .take_while(|(a, b)| a == b)
+                .count();
+            (shared >= 3).then(|| (shared, candidate.clone()))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    ranked.into_iter().map(|(_, name)| name).take(3).collect()
+}
+
 #[derive(Clone)]
 pub enum ChatMessage {
     User(String),
@@ -80,8 +608,8 @@ pub enum ChatMessage {
 impl From<ChatMessage> for SessionChatMessage {
     fn from(msg: ChatMessage) -> Self {
         match msg {
-            ChatMessage::User(text) => SessionChatMessage::User(text),
-            ChatMessage::Assistant(text) => SessionChatMessage::Assistant(text),
+            ChatMessage::User(text) => SessionChatMessage::user(text),
+            ChatMessage::Assistant(text) => SessionChatMessage::assistant(text),
         }
     }
 }
@@ -89,32 +617,313 @@ impl From<ChatMessage> for SessionChatMessage {
 impl From<SessionChatMessage> for ChatMessage {
     fn from(msg: SessionChatMessage) -> Self {
         match msg {
-            SessionChatMessage::User(text) => ChatMessage::User(text),
-            SessionChatMessage::Assistant(text) => ChatMessage::Assistant(text),
+            SessionChatMessage::User { text, .. } => ChatMessage::User(text),
+            SessionChatMessage::Assistant { text, .. } => ChatMessage::Assistant(text),
         }
     }
 }
 
+/// Transport used for chat requests, selected per endpoint config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatTransport {
+    JsonRpc,
+    Grpc,
+}
+
+/// Progress reports from the background startup tasks. The TUI draws
+/// immediately with a "connecting" status while the endpoint probe and
+/// session negotiation run; the event loop drains these through
+/// [`ChatApp::apply_startup_updates`] once per frame.
+pub enum StartupUpdate {
+    /// Result of the connectivity probe against the API endpoint
+    Probe { connected: bool },
+    /// gRPC chat client connected in the background
+    GrpcReady(Box<GrpcClient>),
+    /// Session state negotiated with the session manager, with the index
+    /// of the first message that was actually loaded and any pinned
+    /// messages found in the unloaded prefix
+    SessionLoaded(Box<Session>, usize, Vec<(usize, SessionChatMessage)>),
+}
+
+/// Result of a command effect run on the runtime, drained once per
+/// frame by [`ChatApp::apply_command_events`]
+pub enum CommandEvent {
+    /// A note to append to the transcript
+    Note(String),
+    /// A /provider switch finished: install the rebuilt client
+    ProviderSwitched {
+        client: Box<JsonRpcClient>,
+        provider: crate::config::ApiProvider,
+        connected: bool,
+    },
+    /// A /model change passed validation (or was waved through)
+    ModelSet(String),
+    /// /config reloaded the configuration from disk
+    ConfigReloaded(Box<crate::config::Config>),
+    /// /recall found excerpts to carry in the system prompt
+    Recalled(Vec<String>),
+    /// The effect finished; the spinner comes down
+    Finished,
+}
+
+/// Work a slash command needs the runtime for. `handle_command` builds
+/// the future and hands it to [`ChatApp::spawn_effect`], which runs it
+/// on a spawned task so command I/O never blocks drawing; the events it
+/// produces flow back through the command channel.
+pub type CommandEffect = std::pin::Pin<Box<dyn std::future::Future<Output = Vec<CommandEvent>> + Send>>;
+
+/// Everything that can happen to the app, from every producer: the
+/// terminal, the streaming task, background probes and command
+/// effects. The event loop forwards each one to [`ChatApp::update`],
+/// the single reducer that mutates app state, so new features plug in
+/// as another producer on the same channel instead of another shared
+/// Mutex polled per frame.
+pub enum AppEvent {
+    /// A key press from the terminal
+    Key(KeyEvent),
+    /// A bracketed paste from the terminal
+    Paste(String),
+    /// The terminal gained or lost focus
+    FocusChanged(bool),
+    /// The terminal was resized; the next frame re-splits the layout
+    /// against the new area, so this only needs to force a redraw
+    Resized,
+    /// A chunk of the streaming response arrived
+    StreamChunk(String),
+    /// The streaming response finished (or failed), with the final
+    /// filtered text to display
+    StreamDone(String),
+    /// A probe or provider switch changed connectivity
+    ConnectionChanged(bool),
+    /// /config reloaded the configuration from disk
+    ConfigReloaded(Box<crate::config::Config>),
+    /// The write lease on this session is held by another gos process,
+    /// either found at startup or lost to a `--force` takeover
+    LeaseLost { holder_pid: u32 },
+    /// The server pushed a notification on the streaming connection,
+    /// e.g. a maintenance notice or a context purge warning
+    ServerNotice(crate::adapters::ServerNotification),
+    /// SIGTSTP arrived (a `kill -TSTP`, or a shell that saw the Ctrl+Z
+    /// before we did); the reducer restores the terminal before the
+    /// process actually stops
+    Suspend,
+    /// SIGCONT resumed the process; re-enter raw mode and repaint over
+    /// whatever the shell drew while it had the terminal
+    Resumed,
+    /// Periodic timer, driving the spinner and other animations
+    Tick,
+}
+
+/// Context window the /tokens pane budgets against. Providers do not
+/// report their window over JSON-RPC, so the pane shows usage against
+/// a common floor rather than showing nothing.
+pub const ASSUMED_CONTEXT_WINDOW: u64 = 128_000;
+
+/// How many messages of history the TUI loads per page. The newest page
+/// arrives at startup; older pages are fetched on demand.
+pub const HISTORY_PAGE: usize = 200;
+
+/// How many messages the warm-start preview shows while the full page
+/// is still loading, when resuming an existing session
+pub const WARM_START_MESSAGES: usize = 6;
+
+/// Actions offered by the message popup, in display order
+pub const MESSAGE_ACTIONS: [&str; 5] = [
+    "Copy",
+    "Quote into input",
+    "Delete",
+    "Regenerate from here",
+    "Toggle bookmark",
+];
+
 pub struct ChatApp {
     pub messages: Vec<ChatMessage>,
+    /// How many warm-start preview messages sit at the front of
+    /// `messages`, replaced once the real history page arrives
+    pub warm_preview: usize,
+    /// How many stored messages precede `messages[0]`; non-zero when a
+    /// long history was lazily loaded starting from its newest page
+    pub history_offset: usize,
+    /// Indices into `messages` the user has bookmarked
+    pub bookmarks: Vec<usize>,
+    /// Bookmarks pointing into the unloaded history prefix, kept as
+    /// absolute indices so saving does not drop them
+    pub earlier_bookmarks: Vec<usize>,
+    /// Pinned messages from the unloaded history prefix, keyed by their
+    /// absolute stored index; these are always sent with the
+    /// conversation history even though their page is not loaded
+    pub pinned_prefix: Vec<(usize, SessionChatMessage)>,
     pub input: String,
     pub cursor_position: usize,
     pub session_id: Uuid,
     pub session_manager: Arc<SessionManager>,
     pub graph_os_client: Option<JsonRpcClient>,
+    pub grpc_client: Option<GrpcClient>,
+    /// Clients for the configured provider fallback chain, in order; a
+    /// failed chat request is retried against these before giving up
+    pub fallback_clients: Vec<(crate::config::ApiProvider, JsonRpcClient)>,
+    pub transport: ChatTransport,
     pub show_commands: bool,
+    /// Layout density preset, switched with /layout and persisted in
+    /// the user config
+    pub layout_mode: LayoutMode,
+    /// Extra rows the user added to the input box with Ctrl+Up,
+    /// persisted alongside the layout mode
+    pub input_extra_rows: u16,
     pub exit_requested: bool,
     pub connected: bool,
     pub streaming: bool,
-    pub current_stream: Arc<Mutex<String>>,
+    /// Receives [`AppEvent`]s from every producer; the event loop
+    /// drains it through `drain_events` once per frame
+    pub events_rx: mpsc::UnboundedReceiver<AppEvent>,
+    /// Sender cloned into producers (the streaming task, probes); the
+    /// event loop also clones it for terminal events
+    pub events_tx: mpsc::UnboundedSender<AppEvent>,
+    /// Set by the reducer when Enter submits the input; the event loop
+    /// takes it and calls the async `submit_message`
+    pub submit_requested: bool,
+    /// Frame counter advanced by `AppEvent::Tick`, driving the spinner
+    pub tick: usize,
     pub stream_active: bool,
+    /// Wakes the active streaming task when the user stops it; replaced
+    /// per stream so a stale permit cannot cancel the next one
+    pub stream_cancel: Arc<tokio::sync::Notify>,
+    /// Whether the last answer was cut short with stop_streaming, which
+    /// is what /continue checks before resuming it
+    pub last_interrupted: Arc<std::sync::atomic::AtomicBool>,
     pub current_provider: Option<crate::config::ApiProvider>,
     pub available_providers: Vec<crate::config::ApiProvider>,
+    /// True when --model was given on the command line; an explicit
+    /// override is never replaced by the session's stored model
+    pub model_overridden: bool,
     pub config_manager: Arc<crate::config::ConfigManager>,
     pub debug_mode: bool,
+    /// System prompt for this conversation; templates override the default
+    pub system_prompt: Option<String>,
+    /// Personas user messages are routed to; empty means normal
+    /// single-assistant mode
+    pub active_agents: Vec<String>,
+    /// JSON Schema (and the path it was loaded from) responses must
+    /// conform to; streaming is bypassed while this is set
+    pub response_schema: Option<(String, serde_json::Value)>,
+    /// Prompt templates loaded from config
+    pub templates: std::collections::HashMap<String, crate::templates::Template>,
+    /// Per-model price table for cost tracking
+    pub prices: std::collections::HashMap<String, ModelPrice>,
+    /// Accumulated cost of this session in dollars
+    pub session_cost: Arc<std::sync::Mutex<f64>>,
+    /// Notification hooks fired when responses complete
+    pub hooks: crate::hooks::HookDispatcher,
+    /// Terminal title and bell updates mirroring background activity
+    pub notifier: crate::terminal::TerminalNotifier,
+    /// Whether the terminal currently has focus, updated from
+    /// FocusGained/FocusLost events; unfocused completions fire hooks
+    pub focused: Arc<std::sync::atomic::AtomicBool>,
+    /// Workspace context paths injected into the system prompt
+    pub context_paths: Vec<String>,
+    /// Knowledge packs attached with /kb use; relevant chunks are
+    /// injected into the system prompt per request
+    pub kb_packs: Vec<String>,
+    /// Past-conversation excerpts pulled in by /recall, appended to the
+    /// system prompt until cleared with /recall off
+    pub recalled: Vec<String>,
+    /// Transcript message highlighted in selection mode
+    pub selected_message: Option<usize>,
+    /// Whether the help overlay is open (`?` with an empty input, or
+    /// /help); its contents come from the keymap registry
+    pub help_open: bool,
+    /// Search-as-you-type filter over the keymap registry
+    pub help_query: String,
+    /// Scroll offset within the filtered help listing
+    pub help_scroll: u16,
+    /// Latest server-initiated notification, shown as a banner over the
+    /// transcript until Esc dismisses it or the next one replaces it
+    pub server_notice: Option<String>,
+    /// Lines of the /tokens context-visualizer pane, open when Some;
+    /// recomputed when a message lands rather than per frame, since
+    /// building it re-reads attached context files
+    pub tokens_report: Option<Vec<String>>,
+    /// Absolute stored index /quote marked for the next outgoing
+    /// message, recorded into its metadata as `reply_to` on send
+    pub pending_reply_to: Option<usize>,
+    /// Highlighted entry of the message action popup, open when Some
+    pub selected_action: Option<usize>,
+    /// Slash commands queued by `handle_input` for the event loop to
+    /// run via `process_pending_command`; commands execute
+    /// asynchronously so they can rebuild clients and query the server.
+    /// A multi-command alias queues its whole sequence, in order.
+    pub pending_commands: std::collections::VecDeque<Command>,
+    /// Slash command aliases from the `[aliases]` config table, keyed
+    /// by name without the slash
+    pub aliases: std::collections::HashMap<String, String>,
+    /// Recorded keyboard macros from the `[macros]` config table:
+    /// name -> the input lines /macro play replays
+    pub macros: std::collections::HashMap<String, Vec<String>>,
+    /// Macro being recorded by /macro record, with the inputs captured
+    /// so far; None when not recording
+    pub macro_recording: Option<(String, Vec<String>)>,
+    /// Receives results from spawned command effects
+    pub command_rx: mpsc::UnboundedReceiver<CommandEvent>,
+    /// Sender cloned into every spawned command effect
+    pub command_tx: mpsc::UnboundedSender<CommandEvent>,
+    /// Label of the command effect currently running, shown with a
+    /// spinner in the status line; None when idle
+    pub command_running: Option<String>,
+    /// Set after a first /share; a second /share in a row confirms the
+    /// upload, any other command cancels it
+    pub share_pending: bool,
+    /// Code block shown by a first /run; a second /run in a row executes
+    /// it, any other command cancels it
+    pub run_pending: Option<crate::sandbox::CodeBlock>,
+    /// A paste too large to insert directly, held until the user picks
+    /// /paste insert or /paste file
+    pub pending_paste: Option<String>,
+    /// Telemetry for the response currently streaming, shared with the
+    /// streaming task and cleared when the stream completes
+    pub telemetry: Arc<std::sync::Mutex<Option<StreamTelemetry>>>,
+    /// Latency and token-rate window behind the status-bar sparklines,
+    /// shared with the streaming task which records each response
+    pub response_history: Arc<std::sync::Mutex<ResponseHistory>>,
+    /// Generation metadata per message index, shown in selection mode
+    /// and persisted with the session. Shared with the streaming task,
+    /// which records latency and token counts when a response completes.
+    pub message_meta: Arc<std::sync::Mutex<std::collections::HashMap<usize, crate::session::MessageMeta>>>,
+    /// Rendering strategy; accessible mode drops colors, emoji and
+    /// cursor movement for screen readers
+    pub style: crate::render::RenderStyle,
+    /// Output filters applied to every assistant response before it is
+    /// displayed or persisted; shared with the streaming task
+    pub filters: Arc<crate::filters::FilterChain>,
+    /// Outbound secret scanner run over user messages before they are
+    /// sent, per the `[redact]` config table
+    pub redactor: crate::redact::OutboundScanner,
+    /// Admin guardrails from the system config: blocked topics and the
+    /// provider allow list are checked before a request goes out
+    pub policy: crate::policy::PolicyEngine,
+    /// Placeholder -> original mapping from masked sends; masking reads
+    /// it to reuse placeholders and the restore filter reads it to show
+    /// originals locally
+    pub redactions: Arc<std::sync::Mutex<std::collections::HashMap<String, String>>>,
+    /// Stored conversation length at the last snapshot; `save_session`
+    /// takes a new restore point once the conversation has grown by
+    /// [`crate::snapshots::AUTO_SNAPSHOT_EVERY`] messages since then
+    pub snapshot_watermark: std::sync::atomic::AtomicUsize,
+    /// Pid of the gos process holding this session's write lease when it
+    /// is not us; `Some` makes the app read-only (no sends, no saves)
+    pub read_only: Option<u32>,
+    /// True until the background endpoint probe reports a result
+    pub connecting: bool,
+    /// True until the background session negotiation completes
+    pub session_loading: bool,
+    /// Channel the startup tasks report progress on
+    pub startup_rx: Option<mpsc::UnboundedReceiver<StartupUpdate>>,
+    /// When the connection last carried (or pre-warmed) a request;
+    /// typing after a long idle gap triggers a background pre-warm
+    pub last_request_at: Arc<std::sync::Mutex<std::time::Instant>>,
 }
 
 impl ChatApp {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         session_id: Uuid, 
         session_manager: Arc<SessionManager>,
@@ -124,6 +933,7 @@ impl ChatApp {
         api_config: Option<crate::config::ApiConfig>,
         model_override: Option<String>,
         rpc_secret: Option<String>,
+        force: bool,
     ) -> anyhow::Result<Self> {
         // Get the config manager
         let config_manager = Arc::new(crate::config::ConfigManager::instance().clone());
@@ -134,14 +944,29 @@ impl ChatApp {
         // Get available providers
         let available_providers = config.apis.keys().cloned().collect();
         
+        let model_overridden = model_override.is_some();
+
         // Get current provider from API config
         let current_provider = if let Some(config) = &api_config {
             Some(config.provider)
         } else {
             config.default_provider
         };
-        // Try to get existing session from the manager
-        let existing_session = session_manager.get_session(session_id).await?;
+        // Endpoint config drives transport selection and HTTP client options
+        let mut endpoint_config = config.get_endpoint_config("default");
+
+        // Refresh an expired OAuth access token before any transport is
+        // built, so both adapters start with a usable bearer token
+        if let Some(endpoint) = &endpoint_config {
+            match crate::auth::ensure_fresh("default", endpoint).await {
+                Ok(Some(updated)) => endpoint_config = Some(updated),
+                Ok(None) => {}
+                Err(e) => eprintln!("Token refresh failed: {}; run `gos login`", e),
+            }
+        }
+
+        let http_options = crate::adapters::HttpClientOptions::from_env()
+            .merge_endpoint(endpoint_config.as_ref());
         
         // Create API client
         let graph_os_client = if let Some(config) = api_config {
@@ -159,177 +984,1112 @@ impl ChatApp {
             // Determine model to use (CLI override takes precedence)
             let model = model_override.or(config.model);
             
-            Some(JsonRpcClient::with_endpoint(endpoint, Some(config.api_key), model, rpc_secret))
+            Some(JsonRpcClient::with_endpoint_options(endpoint, Some(config.api_key), model, rpc_secret, &http_options))
         } else if let (Some(host), Some(port)) = (host, port) {
-            // No API config, just use host/port
-            Some(JsonRpcClient::new(&host, port, https, None, model_override, rpc_secret))
+            // No API config, just use host/port; an OAuth access token from
+            // `gos login` doubles as the bearer token
+            let scheme = if https { "https" } else { "http" };
+            let endpoint = format!("{}://{}:{}/api/jsonrpc", scheme, host, port);
+            let token = endpoint_config.as_ref().and_then(|e| e.token.clone());
+            Some(JsonRpcClient::with_endpoint_options(endpoint, token, model_override, rpc_secret, &http_options))
         } else {
             None
         };
         
-        // Initialize messages based on whether this is a new session or existing one
-        let messages = if let Some(session) = existing_session {
-            // Convert session messages to chat messages
-            session.messages.into_iter().map(ChatMessage::from).collect()
-        } else {
-            // Create a new session
-            let session = Session {
-                id: session_id,
-                created_at: chrono::Utc::now(),
-                last_active: chrono::Utc::now(),
-                messages: vec![],
-            };
-            
-            // Store the new session
-            session_manager.update_session(session).await?;
-            
-            // Default welcome message for new sessions
-            vec![
-                ChatMessage::Assistant("Hello! I'm Vibe, your AI assistant. How can I help you today?".to_string()),
-            ]
+        // Select transport per endpoint config (the "default" endpoint wins)
+        let transport = match endpoint_config.as_ref().and_then(|e| e.transport.as_deref()) {
+            Some("grpc") => ChatTransport::Grpc,
+            _ => ChatTransport::JsonRpc,
         };
-        
-        // Check if we can actually connect to the API endpoint
-        let connected = if let Some(client) = &graph_os_client {
-            // Try a simple ping request to test connectivity
-            match client.ping().await {
-                Ok(true) => true,
-                _ => false,
+
+        // Everything that touches the network or the session listener
+        // runs in background tasks from here, so the TUI can draw its
+        // first frame immediately with a "connecting" status. Results
+        // arrive through the startup channel and are folded into the app
+        // by apply_startup_updates.
+        let (startup_tx, startup_rx) = mpsc::unbounded_channel();
+        let message_meta = Arc::new(std::sync::Mutex::new(std::collections::HashMap::new()));
+
+        // Session negotiation: fetch the conversation from the session
+        // manager, creating and persisting it when this is a new session
+        {
+            let manager = session_manager.clone();
+            let tx = startup_tx.clone();
+            tokio::spawn(async move {
+                // Fetch the header first, then only the newest page of
+                // the conversation; a long history stays on the listener
+                // until the user scrolls back into it
+                let (session, offset, pinned) = match manager.get_session_meta(session_id).await {
+                    Ok(Some((mut session, total))) => {
+                        let offset = total.saturating_sub(HISTORY_PAGE);
+                        match manager.get_session_messages(session_id, offset, HISTORY_PAGE).await {
+                            Ok(Some((messages, _))) => {
+                                session.messages = messages;
+                                // Pinned messages in the unloaded prefix
+                                // still ride along with the conversation
+                                // history, so fetch them up front
+                                let pinned = if offset > 0 {
+                                    match manager.get_session_messages(session_id, 0, offset).await {
+                                        Ok(Some((prefix, _))) => prefix
+                                            .into_iter()
+                                            .enumerate()
+                                            .filter(|(_, message)| message.meta().pinned)
+                                            .collect(),
+                                        _ => Vec::new(),
+                                    }
+                                } else {
+                                    Vec::new()
+                                };
+                                (session, offset, pinned)
+                            }
+                            _ => {
+                                // Leave the stored history untouched: with
+                                // offset = total, saving appends rather
+                                // than overwriting what failed to load
+                                eprintln!("Failed to load messages for session {}", session_id);
+                                (session, total, Vec::new())
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        let session = Session::new(session_id);
+                        if let Err(e) = manager.update_session(session.clone()).await {
+                            eprintln!("Failed to store new session: {}", e);
+                        }
+                        (session, 0, Vec::new())
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to load session {}: {}", session_id, e);
+                        (Session::new(session_id), 0, Vec::new())
+                    }
+                };
+                let _ = tx.send(StartupUpdate::SessionLoaded(Box::new(session), offset, pinned));
+            });
+        }
+
+        // Connectivity: connect the gRPC client when the endpoint asks
+        // for it, otherwise probe the JSON-RPC endpoint with a ping
+        match transport {
+            ChatTransport::Grpc => {
+                let endpoint_config = endpoint_config.clone();
+                let tx = startup_tx.clone();
+                tokio::spawn(async move {
+                    let client = if let Some(endpoint) = &endpoint_config {
+                        // A comma-separated url list enables round-robin failover
+                        let endpoints: Vec<String> = endpoint
+                            .url
+                            .split(',')
+                            .map(|u| u.trim().to_string())
+                            .filter(|u| !u.is_empty())
+                            .collect();
+                        let auth = GrpcAuth::from_endpoint("default", endpoint);
+                        GrpcClient::with_endpoints_auth(endpoints, auth).await.ok()
+                    } else {
+                        None
+                    };
+
+                    let connected = client.is_some();
+                    if let Some(client) = client {
+                        let _ = tx.send(StartupUpdate::GrpcReady(Box::new(client)));
+                    }
+                    let _ = tx.send(StartupUpdate::Probe { connected });
+                });
             }
-        } else {
-            false
+            ChatTransport::JsonRpc => {
+                let client = graph_os_client.clone();
+                let tx = startup_tx.clone();
+                tokio::spawn(async move {
+                    let connected = match &client {
+                        Some(client) => matches!(client.ping().await, Ok(true)),
+                        None => false,
+                    };
+                    let _ = tx.send(StartupUpdate::Probe { connected });
+                });
+            }
+        }
+
+        // Outbound secret scanning; in mask mode the filter chain gets
+        // a restore stage so a placeholder echoed by the model reads as
+        // the original value in the local transcript
+        let redactor = crate::redact::OutboundScanner::from_config(&config.redact());
+        let redactions = Arc::new(std::sync::Mutex::new(std::collections::HashMap::new()));
+        let mut filters = crate::filters::FilterChain::from_config(
+            &config.filters(),
+            crate::share::secret_values(&config),
+        );
+        if redactor.mode() == crate::redact::RedactMode::Mask {
+            filters.push(Box::new(crate::redact::RestoreRedactions {
+                map: redactions.clone(),
+            }));
+        }
+
+        // Admin guardrails; the response cap rides the filter chain so
+        // streamed and buffered responses are capped the same way
+        let policy = crate::policy::PolicyEngine::from_config(&config.policy());
+        if let Some(max_chars) = policy.max_response_chars() {
+            filters.push(Box::new(crate::policy::ClipResponse { max_chars }));
+        }
+
+        // Clients for the configured provider fallback chain, built up
+        // front so a failing request can retry without touching config
+        let fallback_clients = Self::build_fallback_chain(
+            &config,
+            current_provider,
+            graph_os_client.as_ref(),
+            &http_options,
+        );
+
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+
+        // Exclusive write lease: claim it (taking over with --force),
+        // then keep it renewed ahead of its TTL. A denial or a later
+        // takeover flows back as an event and makes the app read-only.
+        {
+            let manager = session_manager.clone();
+            let tx = events_tx.clone();
+            tokio::spawn(async move {
+                match manager.acquire_lease(session_id, force).await {
+                    Ok(None) => loop {
+                        tokio::time::sleep(crate::session::LEASE_RENEW_INTERVAL).await;
+                        match manager.renew_lease(session_id).await {
+                            Ok(None) => {}
+                            Ok(Some(holder_pid)) => {
+                                let _ = tx.send(AppEvent::LeaseLost { holder_pid });
+                                break;
+                            }
+                            Err(e) => eprintln!("Lease renewal failed: {}", e),
+                        }
+                    },
+                    Ok(Some(holder_pid)) => {
+                        let _ = tx.send(AppEvent::LeaseLost { holder_pid });
+                    }
+                    // No reachable session manager already means this
+                    // process is on its own; don't also lock the input
+                    Err(e) => eprintln!("Lease acquisition failed: {}", e),
+                }
+            });
+        }
+
+        // Job control: raw mode swallows the terminal's own Ctrl+Z, and
+        // a `kill -TSTP` would otherwise stop the process with the
+        // terminal still raw. Catch SIGTSTP so suspension goes through
+        // the reducer (restore the terminal first, then stop), and
+        // SIGCONT so resuming repaints.
+        #[cfg(unix)]
+        {
+            let tx = events_tx.clone();
+            tokio::spawn(async move {
+                use tokio::signal::unix::{signal, SignalKind};
+                let (Ok(mut tstp), Ok(mut cont)) = (
+                    signal(SignalKind::from_raw(nix::libc::SIGTSTP)),
+                    signal(SignalKind::from_raw(nix::libc::SIGCONT)),
+                ) else {
+                    return;
+                };
+                loop {
+                    let event = tokio::select! {
+                        _ = tstp.recv() => AppEvent::Suspend,
+                        _ = cont.recv() => AppEvent::Resumed,
+                    };
+                    if tx.send(event).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        // Warm start: when resuming an existing session, fetch just the
+        // tail of the stored conversation up front so the first frame
+        // already shows recent context. The full page still arrives
+        // through the background negotiation and replaces the preview.
+        let preview: Vec<ChatMessage> = match session_manager.get_session_meta(session_id).await {
+            Ok(Some((_, total))) => {
+                let offset = total.saturating_sub(WARM_START_MESSAGES);
+                match session_manager.get_session_messages(session_id, offset, WARM_START_MESSAGES).await {
+                    Ok(Some((messages, _))) => messages.into_iter().map(ChatMessage::from).collect(),
+                    _ => Vec::new(),
+                }
+            }
+            // A brand-new session has nothing to preview
+            _ => Vec::new(),
         };
-        
+
+        // Layout preferences saved by a previous run; unknown or
+        // missing values quietly fall back to the defaults
+        let layout = config.layout();
+
+        // From here the conversation is live; a panic should point the
+        // next launch back at this session
+        crate::crash::set_active_session(session_id);
+
         Ok(Self {
-            messages,
+            warm_preview: preview.len(),
+            messages: preview,
+            history_offset: 0,
+            bookmarks: Vec::new(),
+            earlier_bookmarks: Vec::new(),
+            pinned_prefix: Vec::new(),
             input: String::new(),
             cursor_position: 0,
             session_id,
             session_manager,
             graph_os_client,
+            grpc_client: None,
+            fallback_clients,
+            transport,
             show_commands: true, // Always show commands for testing
+            layout_mode: layout.mode.as_deref().and_then(LayoutMode::parse).unwrap_or_default(),
+            input_extra_rows: layout.input_rows.unwrap_or(0).min(MAX_INPUT_EXTRA_ROWS),
             exit_requested: false,
-            connected,
+            connected: false,
             streaming: true, // Enable streaming by default
-            current_stream: Arc::new(Mutex::new(String::new())),
+            events_rx,
+            events_tx,
+            submit_requested: false,
+            tick: 0,
             stream_active: false,
+            stream_cancel: Arc::new(tokio::sync::Notify::new()),
+            last_interrupted: Arc::new(std::sync::atomic::AtomicBool::new(false)),
             current_provider,
             available_providers,
+            model_overridden,
             config_manager,
             debug_mode: true, // Debug mode ON by default for testing
+            system_prompt: None,
+            active_agents: Vec::new(),
+            response_schema: None,
+            templates: config.templates(),
+            prices: config.prices(),
+            session_cost: Arc::new(std::sync::Mutex::new(0.0)),
+            hooks: crate::hooks::HookDispatcher::new(config.hooks()),
+            notifier: crate::terminal::TerminalNotifier::new(
+                config.hooks().terminal,
+                crate::render::RenderStyle::detect(config.accessible()).accessible,
+            ),
+            focused: Arc::new(std::sync::atomic::AtomicBool::new(true)),
+            context_paths: Vec::new(),
+            kb_packs: Vec::new(),
+            recalled: Vec::new(),
+            selected_message: None,
+            selected_action: None,
+            help_open: false,
+            help_query: String::new(),
+            help_scroll: 0,
+            server_notice: None,
+            tokens_report: None,
+            pending_reply_to: None,
+            pending_commands: std::collections::VecDeque::new(),
+            aliases: config.aliases(),
+            macros: config.macros(),
+            macro_recording: None,
+            command_rx,
+            command_tx,
+            command_running: None,
+            share_pending: false,
+            run_pending: None,
+            pending_paste: None,
+            telemetry: Arc::new(std::sync::Mutex::new(None)),
+            response_history: Arc::new(std::sync::Mutex::new(ResponseHistory::default())),
+            message_meta,
+            style: crate::render::RenderStyle::detect(config.accessible()),
+            filters: Arc::new(filters),
+            redactor,
+            policy,
+            redactions,
+            snapshot_watermark: std::sync::atomic::AtomicUsize::new(0),
+            read_only: None,
+            connecting: true,
+            session_loading: true,
+            startup_rx: Some(startup_rx),
+            last_request_at: Arc::new(std::sync::Mutex::new(std::time::Instant::now())),
         })
     }
-    
-    pub async fn save_session(&self) -> anyhow::Result<()> {
-        // Convert our local messages to session messages
-        let session_messages: Vec<SessionChatMessage> = 
-            self.messages.iter().map(|msg| {
-                match msg {
-                    ChatMessage::User(text) => SessionChatMessage::User(text.clone()),
-                    ChatMessage::Assistant(text) => SessionChatMessage::Assistant(text.clone()),
-                }
-            }).collect();
-            
-        let session = Session {
-            id: self.session_id,
-            created_at: chrono::Utc::now(), // This is just a placeholder, should be preserved
-            last_active: chrono::Utc::now(),
-            messages: session_messages,
-        };
-        
-        self.session_manager.update_session(session).await?;
-        Ok(())
+
+    /// Pre-warm the pooled connection when the user starts composing
+    /// after an idle gap. The throwaway ping runs in the background and
+    /// resets the idle clock, so at most one fires per gap.
+    fn maybe_prewarm(&mut self) {
+        if self.stream_active || prewarm_disabled() {
+            return;
+        }
+        let Some(client) = self.graph_os_client.clone() else { return };
+        let Ok(mut last) = self.last_request_at.lock() else { return };
+        if last.elapsed() < PREWARM_AFTER_IDLE {
+            return;
+        }
+        *last = std::time::Instant::now();
+        tokio::spawn(async move {
+            client.prewarm().await;
+        });
     }
 
-    pub fn push_message(&mut self, message: ChatMessage) {
-        self.messages.push(message);
+    /// Fold progress from the background startup tasks into the app;
+    /// the event loop calls this once per frame so startup I/O never
+    /// blocks drawing
+    pub fn apply_startup_updates(&mut self) {
+        let Some(rx) = self.startup_rx.as_mut() else { return };
+
+        let mut updates = Vec::new();
+        while let Ok(update) = rx.try_recv() {
+            updates.push(update);
+        }
+        for update in updates {
+            match update {
+                StartupUpdate::Probe { connected } => {
+                    self.connecting = false;
+                    self.update(AppEvent::ConnectionChanged(connected));
+                }
+                StartupUpdate::GrpcReady(client) => {
+                    self.grpc_client = Some(*client);
+                }
+                StartupUpdate::SessionLoaded(session, offset, pinned) => {
+                    self.pinned_prefix = pinned;
+                    self.adopt_session(*session, offset);
+                }
+            }
+        }
+
+        // Drop the channel once both startup tasks have reported
+        if !self.connecting && !self.session_loading {
+            self.startup_rx = None;
+        }
     }
 
-    pub async fn submit_message(&mut self) -> anyhow::Result<()> {
-        if !self.input.is_empty() {
-            let user_message = std::mem::take(&mut self.input);
-            self.push_message(ChatMessage::User(user_message.clone()));
-            
-            // Convert chat history to API message format
-            let api_messages = self.get_conversation_history();
-            
-            // Response to show to the user
-            if self.connected && self.graph_os_client.is_some() {
-                // Start a streaming response if enabled
-                if self.streaming {
-                    // Add an empty assistant message that will be updated as the stream comes in
-                    self.push_message(ChatMessage::Assistant(String::new()));
-                    
-                    // Mark streaming as active
-                    self.stream_active = true;
-                    
-                    // Get what we need for the async task
-                    let client = self.graph_os_client.as_ref().unwrap().clone();
-                    let session_id = self.session_id;
-                    let session_manager = self.session_manager.clone();
-                    let current_stream = self.current_stream.clone();
-                    let api_messages = api_messages.clone();
+    /// Take over the conversation state negotiated in the background,
+    /// keeping any messages the user already typed ahead of the load.
+    /// `offset` is the stored index of the first loaded message; with a
+    /// lazily loaded history it is non-zero and bookmarks into the
+    /// unloaded prefix are parked until their page arrives.
+    fn adopt_session(&mut self, session: Session, offset: usize) {
+        self.session_loading = false;
+        // The warm-start preview is a stale copy of the tail being
+        // adopted; drop it so the loaded page does not show up twice
+        if self.warm_preview > 0 {
+            self.messages.drain(..self.warm_preview.min(self.messages.len()));
+            self.warm_preview = 0;
+        }
+        self.history_offset = offset;
+        // Resuming an old session should not trigger an immediate
+        // automatic snapshot; start counting from its current length
+        self.snapshot_watermark.store(
+            offset + session.messages.len(),
+            std::sync::atomic::Ordering::Relaxed,
+        );
+        // Stored bookmarks are absolute; re-point the visible ones at
+        // the loaded window and park the rest
+        self.earlier_bookmarks = session.bookmarks.iter().copied().filter(|&b| b < offset).collect();
+        self.bookmarks = session
+            .bookmarks
+            .iter()
+            .filter_map(|&b| b.checked_sub(offset))
+            .collect();
+        self.system_prompt = session.system_prompt;
+        self.context_paths = session.context;
+        self.kb_packs = session.kb;
+
+        // Restore the provider and model this conversation was using.
+        // The switch goes through the same effect as /provider, so a
+        // provider that has since vanished from the config reports
+        // instead of silently degrading. An explicit --model on the
+        // command line beats the stored choice.
+        let stored_model = session.model.clone().filter(|_| !self.model_overridden);
+        match session.provider.as_deref().and_then(crate::config::ApiProvider::parse) {
+            Some(provider) if Some(provider) != self.current_provider => {
+                let config_manager = self.config_manager.clone();
+                let fallback_endpoint = self.graph_os_client.as_ref().map(|c| c.endpoint.clone());
+                let rpc_secret = self.graph_os_client.as_ref().and_then(|c| c.rpc_secret.clone());
+                self.spawn_effect("Restoring session provider", Box::pin(
+                    Self::switch_provider_effect(config_manager, fallback_endpoint, rpc_secret, provider, stored_model),
+                ));
+            }
+            _ => {
+                // Same provider (or none stored); just reapply the
+                // session's model choice to the active client
+                if let (Some(model), Some(client)) = (stored_model, self.graph_os_client.as_mut()) {
+                    client.model = Some(model);
+                }
+            }
+        }
+
+        if session.messages.is_empty() {
+            // A fresh session: greet, below anything typed ahead
+            self.messages.insert(
+                0,
+                ChatMessage::Assistant("Hello! I'm Vibe, your AI assistant. How can I help you today?".to_string()),
+            );
+            return;
+        }
+
+        // Prepend the stored history, keeping generation metadata
+        // indexed for display and re-saving. Messages pushed before the
+        // load finished keep their meta by shifting their indices.
+        let history_len = session.messages.len();
+        {
+            let mut meta_map = self.message_meta.lock().unwrap();
+            let shifted: Vec<_> = meta_map.drain().map(|(index, meta)| (index + history_len, meta)).collect();
+            meta_map.extend(shifted);
+            for (index, message) in session.messages.iter().enumerate() {
+                if !message.meta().is_empty() {
+                    meta_map.insert(index, message.meta().clone());
+                }
+            }
+        }
+        let mut messages: Vec<ChatMessage> = session.messages.into_iter().map(ChatMessage::from).collect();
+        messages.append(&mut self.messages);
+        self.messages = messages;
+    }
+    
+    /// Fetch the page of history preceding what is loaded and prepend
+    /// it, for the event loop to call when the user scrolls above the
+    /// top of the transcript. Returns how many messages were added.
+    pub async fn load_older_messages(&mut self) -> usize {
+        if self.history_offset == 0 {
+            return 0;
+        }
+
+        let new_offset = self.history_offset.saturating_sub(HISTORY_PAGE);
+        let page = match self
+            .session_manager
+            .get_session_messages(self.session_id, new_offset, self.history_offset - new_offset)
+            .await
+        {
+            Ok(Some((page, _))) => page,
+            _ => return 0,
+        };
+        let added = page.len();
+        if added == 0 {
+            return 0;
+        }
+
+        // Everything indexed by position shifts down by the page length
+        {
+            let mut meta_map = self.message_meta.lock().unwrap();
+            let shifted: Vec<_> = meta_map.drain().map(|(index, meta)| (index + added, meta)).collect();
+            meta_map.extend(shifted);
+            for (index, message) in page.iter().enumerate() {
+                if !message.meta().is_empty() {
+                    meta_map.insert(index, message.meta().clone());
+                }
+            }
+        }
+        for bookmark in &mut self.bookmarks {
+            *bookmark += added;
+        }
+        if let Some(selected) = &mut self.selected_message {
+            *selected += added;
+        }
+
+        let mut messages: Vec<ChatMessage> = page.into_iter().map(ChatMessage::from).collect();
+        messages.append(&mut self.messages);
+        self.messages = messages;
+        self.history_offset = new_offset;
+
+        // Un-park bookmarks whose page just arrived
+        let bookmarks = &mut self.bookmarks;
+        self.earlier_bookmarks.retain(|&b| {
+            if b >= new_offset {
+                bookmarks.push(b - new_offset);
+                false
+            } else {
+                true
+            }
+        });
+        self.bookmarks.sort_unstable();
+
+        // Pins whose page just arrived are now in the window; their
+        // metadata came in with the page, so drop the parked copies
+        self.pinned_prefix.retain(|(index, _)| *index < new_offset);
+
+        added
+    }
+
+    /// Record a focus change reported by the terminal
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused.store(focused, std::sync::atomic::Ordering::Relaxed);
+    }
+    
+    /// Convert the local messages to session messages, reattaching the
+    /// generation metadata tracked per index
+    fn session_messages(&self) -> Vec<SessionChatMessage> {
+        let meta_map = self.message_meta.lock().unwrap();
+        self.messages.iter().enumerate().map(|(index, msg)| {
+            let meta = meta_map.get(&index).cloned().unwrap_or_default();
+            match msg {
+                ChatMessage::User(text) => SessionChatMessage::User { text: text.clone(), meta },
+                ChatMessage::Assistant(text) => SessionChatMessage::Assistant { text: text.clone(), meta },
+            }
+        }).collect()
+    }
+
+    /// Bookmarks as absolute indices into the full stored conversation,
+    /// merging the visible ones with those parked in the unloaded prefix
+    fn absolute_bookmarks(&self) -> Vec<usize> {
+        let mut bookmarks: Vec<usize> = self
+            .earlier_bookmarks
+            .iter()
+            .copied()
+            .chain(self.bookmarks.iter().map(|b| b + self.history_offset))
+            .collect();
+        bookmarks.sort_unstable();
+        bookmarks.dedup();
+        bookmarks
+    }
+
+    pub async fn save_session(&self) -> anyhow::Result<()> {
+        // Another process holds the write lease; never clobber its saves
+        if self.read_only.is_some() {
+            return Ok(());
+        }
+
+        let session_messages = self.session_messages();
+            
+        // Preserve creation time and fork metadata from the stored session
+        let mut session = match self.session_manager.get_session(self.session_id).await? {
+            Some(session) => session,
+            None => Session::new(self.session_id),
+        };
+        // Splice the loaded window over the stored tail; with a lazily
+        // loaded history the unloaded prefix stays as it was on disk
+        session.messages.truncate(self.history_offset);
+        session.messages.extend(session_messages);
+        session.last_active = chrono::Utc::now();
+        session.bookmarks = self.absolute_bookmarks();
+        session.system_prompt = self.system_prompt.clone();
+        session.context = self.context_paths.clone();
+        session.kb = self.kb_packs.clone();
+        // The provider and model chosen with /provider and /model
+        // follow the session, so resuming picks this conversation's
+        // configuration back up instead of the config default. Only
+        // overwrite what is actually known here: a chat running without
+        // a client must not wipe a choice made with `gos new --provider`.
+        if let Some(provider) = self.current_provider {
+            session.provider = Some(provider.to_string().to_lowercase());
+        }
+        if let Some(model) = self.graph_os_client.as_ref().and_then(|c| c.model.clone()) {
+            session.model = Some(model);
+        }
+
+        // Take an automatic restore point once the conversation has
+        // grown enough since the last one; a failed snapshot never
+        // blocks the save itself
+        let total = session.messages.len();
+        let last = self.snapshot_watermark.load(std::sync::atomic::Ordering::Relaxed);
+        if total >= last + crate::snapshots::AUTO_SNAPSHOT_EVERY {
+            if let Err(e) = crate::snapshots::take_snapshot(&session, "auto") {
+                eprintln!("Failed to snapshot session: {}", e);
+            }
+            self.snapshot_watermark.store(total, std::sync::atomic::Ordering::Relaxed);
+        }
+
+        self.session_manager.update_session(session).await?;
+        Ok(())
+    }
+
+    pub fn push_message(&mut self, message: ChatMessage) {
+        self.messages.push(message);
+        // Keep the /tokens pane current as the conversation grows
+        if self.tokens_report.is_some() {
+            self.tokens_report = Some(self.token_report());
+        }
+    }
+
+    /// Stop the active stream, keeping whatever arrived as a normal
+    /// message. Wired to Esc/Ctrl+C while a response is streaming; the
+    /// partial answer can then be resumed with /continue.
+    pub fn stop_streaming(&mut self) {
+        if self.stream_active {
+            // notify_one stores a permit, so the task sees the stop even
+            // if it is mid-chunk rather than parked on the select
+            self.stream_cancel.notify_one();
+        }
+    }
+
+    pub async fn submit_message(&mut self) -> anyhow::Result<()> {
+        if let Some(holder_pid) = self.read_only {
+            self.push_message(ChatMessage::Assistant(format!(
+                "Not sent: this session's write lease is held by pid {}. \
+                 Restart with --force to take the session over.",
+                holder_pid
+            )));
+            return Ok(());
+        }
+        if !self.input.is_empty() {
+            // Admin guardrails run first; a blocked topic keeps the
+            // input so the user can edit it, and leaves an audit entry
+            if let Err(denial) = self.policy.check_prompt(&self.input) {
+                crate::policy::audit_denial("prompt", &denial);
+                self.push_message(ChatMessage::Assistant(format!(
+                    "Not sent: {}.",
+                    denial.message
+                )));
+                return Ok(());
+            }
+            // Scan the outgoing message for likely secrets before it
+            // leaves the machine; mask mode handles them at send time
+            match self.redactor.mode() {
+                crate::redact::RedactMode::Block => {
+                    let detections = self.redactor.scan(&self.input);
+                    if !detections.is_empty() {
+                        // Keep the input so the user can edit it
+                        self.push_message(ChatMessage::Assistant(format!(
+                            "Not sent: the message contains {}. Edit it, or set \
+                             redact.mode to \"warn\" or \"mask\" in the config.",
+                            crate::redact::OutboundScanner::describe(&detections)
+                        )));
+                        return Ok(());
+                    }
+                }
+                crate::redact::RedactMode::Warn => {
+                    let detections = self.redactor.scan(&self.input);
+                    if !detections.is_empty() {
+                        self.push_message(ChatMessage::Assistant(format!(
+                            "Warning: the message contains {} and was sent anyway. \
+                             Set redact.mode to \"block\" or \"mask\" to change this.",
+                            crate::redact::OutboundScanner::describe(&detections)
+                        )));
+                    }
+                }
+                crate::redact::RedactMode::Mask => {}
+            }
+            let user_message = std::mem::take(&mut self.input);
+            self.push_message(ChatMessage::User(user_message.clone()));
+
+            // A real request resets the idle clock for pre-warming
+            if let Ok(mut last) = self.last_request_at.lock() {
+                *last = std::time::Instant::now();
+            }
+            
+            // Convert chat history to API message format
+            let api_messages = self.get_conversation_history();
+            
+            // Response to show to the user
+            let has_client = match self.transport {
+                ChatTransport::JsonRpc => self.graph_os_client.is_some(),
+                ChatTransport::Grpc => self.grpc_client.is_some(),
+            };
+            // Stamp the outgoing user message, threading it as a reply
+            // when /quote marked one
+            let mut user_meta = crate::session::MessageMeta::now();
+            user_meta.reply_to = self.pending_reply_to.take();
+            self.message_meta
+                .lock()
+                .unwrap()
+                .insert(self.messages.len() - 1, user_meta);
+
+            // In agents mode the message fans out to the selected
+            // personas instead of the single-assistant path below
+            if !self.active_agents.is_empty() {
+                self.submit_to_agents().await;
+                self.save_session().await?;
+                self.cursor_position = 0;
+                return Ok(());
+            }
+
+            if self.connected && has_client {
+                // Start a streaming response if enabled; structured
+                // output needs the whole response before it can
+                // validate, so an active schema bypasses streaming
+                if self.streaming && self.response_schema.is_none() {
+                    // Add an empty assistant message that will be updated as the stream comes in
+                    self.push_message(ChatMessage::Assistant(String::new()));
+                    let stream_index = self.messages.len() - 1;
+                    let message_meta = self.message_meta.clone();
+
+                    // Mark streaming as active, with a fresh cancel
+                    // handle so stale stop requests cannot leak in
+                    self.stream_active = true;
+                    self.stream_cancel = Arc::new(tokio::sync::Notify::new());
+                    self.last_interrupted.store(false, std::sync::atomic::Ordering::Relaxed);
+                    self.notifier.notify(
+                        crate::terminal::Activity::Responding,
+                        self.focused.load(std::sync::atomic::Ordering::Relaxed),
+                    );
+                    let cancel = self.stream_cancel.clone();
+                    let interrupted_flag = self.last_interrupted.clone();
+
+                    // Get what we need for the async task
+                    let transport = self.transport;
+                    let jsonrpc_client = self.graph_os_client.clone();
+                    let grpc_client = self.grpc_client.clone();
+                    let session_id = self.session_id;
+                    let session_manager = self.session_manager.clone();
+                    let events = self.events_tx.clone();
+                    let api_messages = api_messages.clone();
                     let user_msg = user_message.clone();
-                    
+                    let prices = self.prices.clone();
+                    let provider = self.usage_provider();
+                    let model = self.usage_model();
+                    let fallback_clients = self.fallback_clients.clone();
+                    let session_cost = self.session_cost.clone();
+                    let prompt_tokens = prompt_tokens_estimate(&api_messages);
+                    let hooks = self.hooks.clone();
+                    let focused = self.focused.clone();
+                    let announce = self.style.response_complete_announcement();
+                    let filters = self.filters.clone();
+                    let started = std::time::Instant::now();
+                    let telemetry = self.telemetry.clone();
+                    *telemetry.lock().unwrap() = Some(StreamTelemetry::new(started));
+                    let response_history = self.response_history.clone();
+
                     // Process stream in a separate task
                     tokio::spawn(async move {
                         let (tx, mut rx) = mpsc::channel::<String>(32);
-                        
-                        // Start streaming request
-                        if let Err(e) = client.chat(api_messages, true, Some(tx)).await {
-                            // Update the current stream with error message
-                            let mut stream = current_stream.lock().await;
-                            *stream = format!("Error: {}. Falling back to echo: {}", e, user_msg);
-                            return;
+
+                        // Start streaming request on the configured transport
+                        let stream_result = match transport {
+                            ChatTransport::JsonRpc => {
+                                let mut client = jsonrpc_client.unwrap();
+                                // Server-initiated notifications on the
+                                // stream bypass the transcript and go to
+                                // the reducer as banner events; the
+                                // forwarder ends when the client drops
+                                // its sender
+                                let (note_tx, mut note_rx) = mpsc::channel(8);
+                                client.set_notification_sender(note_tx);
+                                let note_events = events.clone();
+                                tokio::spawn(async move {
+                                    while let Some(note) = note_rx.recv().await {
+                                        let _ = note_events.send(AppEvent::ServerNotice(note));
+                                    }
+                                });
+                                client.chat(api_messages.clone(), true, Some(tx)).await.map(|_| ())
+                            }
+                            ChatTransport::Grpc => {
+                                let mut client = grpc_client.unwrap();
+                                let grpc_messages = to_grpc_messages(&api_messages);
+                                client.chat_stream(grpc_messages, None, tx).await
+                            }
+                        };
+
+                        // When the primary provider fails, walk the
+                        // configured fallback chain before giving up; a
+                        // substitution is noted in the transcript and the
+                        // usage/metadata labels switch to the provider
+                        // that actually answered
+                        let mut provider = provider;
+                        let mut model = model;
+                        let mut fallback_note = None;
+                        if let Err(primary_err) = stream_result {
+                            let mut recovered = false;
+                            for (fb_provider, fb_client) in &fallback_clients {
+                                let (tx, fb_rx) = mpsc::channel::<String>(32);
+                                match fb_client.chat(api_messages.clone(), true, Some(tx)).await {
+                                    Ok(_) => {
+                                        fallback_note = Some(format!(
+                                            "[{} failed: {}; answered by {}]\n",
+                                            provider,
+                                            primary_err,
+                                            fb_provider.to_string().to_lowercase()
+                                        ));
+                                        provider = fb_provider.to_string().to_lowercase();
+                                        if let Some(fb_model) = &fb_client.model {
+                                            model = fb_model.clone();
+                                        }
+                                        rx = fb_rx;
+                                        recovered = true;
+                                        break;
+                                    }
+                                    Err(e) => {
+                                        eprintln!("Fallback provider {} failed too: {}", fb_provider, e);
+                                    }
+                                }
+                            }
+
+                            if !recovered {
+                                // Surface the failure in place of the answer
+                                *telemetry.lock().unwrap() = None;
+                                let _ = events.send(AppEvent::StreamDone(format!(
+                                    "Error: {}. Falling back to echo: {}", primary_err, user_msg
+                                )));
+                                return;
+                            }
                         }
-                        
-                        // Process incoming stream chunks
+
+                        // Process incoming stream chunks until the stream
+                        // ends or the user stops it
                         let mut full_response = String::new();
-                        while let Some(chunk) = rx.recv().await {
+                        if let Some(note) = fallback_note {
+                            let _ = events.send(AppEvent::StreamChunk(note.clone()));
+                            full_response.push_str(&note);
+                        }
+                        loop {
+                            let chunk = tokio::select! {
+                                chunk = rx.recv() => match chunk {
+                                    Some(chunk) => chunk,
+                                    None => break,
+                                },
+                                _ = cancel.notified() => {
+                                    // Keep the partial answer; dropping rx
+                                    // below tears down the transport stream
+                                    interrupted_flag.store(true, std::sync::atomic::Ordering::Relaxed);
+                                    break;
+                                }
+                            };
                             full_response.push_str(&chunk);
-                            
-                            // Update the current stream
-                            {
-                                let mut stream = current_stream.lock().await;
-                                *stream = full_response.clone();
+
+                            // Feed the live telemetry indicator
+                            if let Some(telemetry) = telemetry.lock().unwrap().as_mut() {
+                                telemetry.on_chunk(&chunk, std::time::Instant::now());
                             }
+
+                            // Hand the chunk to the reducer, which
+                            // appends it to the transcript
+                            let _ = events.send(AppEvent::StreamChunk(chunk));
                         }
-                        
-                        // Stream is complete, update session
-                        let mut messages = Vec::new();
-                        
-                        // Get all session messages including the last user message
-                        if let Ok(Some(session)) = session_manager.get_session(session_id).await {
-                            // Replace the last assistant message (empty one) with the full response
-                            messages = session.messages;
-                            if let Some(SessionChatMessage::Assistant(_)) = messages.last() {
-                                // Remove the last message
-                                messages.pop();
-                            }
+                        drop(rx);
+
+                        // Freeze the final stream stats for the metadata
+                        // and drop the live indicator
+                        let finished = std::time::Instant::now();
+                        let (first_token_ms, tokens_per_sec) = telemetry
+                            .lock()
+                            .unwrap()
+                            .take()
+                            .map(|t| (t.first_token_ms(), t.average_tokens_per_sec(finished)))
+                            .unwrap_or((None, None));
+
+                        // Feed the status-bar sparklines
+                        if let Ok(mut history) = response_history.lock() {
+                            history.push(
+                                finished.duration_since(started).as_millis() as u64,
+                                tokens_per_sec,
+                            );
                         }
-                        
-                        // Add the completed assistant message
-                        messages.push(SessionChatMessage::Assistant(full_response));
-                        
-                        // Update the session with the new messages
-                        let updated_session = Session {
-                            id: session_id,
-                            created_at: chrono::Utc::now(),
-                            last_active: chrono::Utc::now(),
-                            messages,
+
+                        // Run the finished response through the output
+                        // filter pipeline before display and persistence.
+                        // The screen-reader completion announcement is
+                        // display-only and stays out of the saved
+                        // transcript.
+                        let full_response = filters.apply(&full_response);
+                        let display = match announce {
+                            Some(announcement) => format!("{}{}", full_response, announcement),
+                            None => full_response.clone(),
+                        };
+                        let _ = events.send(AppEvent::StreamDone(display));
+
+                        // Stream is complete; account for its cost
+                        record_usage(
+                            &prices,
+                            &provider,
+                            &model,
+                            session_id,
+                            prompt_tokens,
+                            &full_response,
+                            &session_cost,
+                        );
+
+                        // Fire notification hooks for slow or unfocused responses
+                        let duration = started.elapsed();
+                        if hooks.should_fire(focused.load(std::sync::atomic::Ordering::Relaxed), duration) {
+                            hooks.fire(crate::hooks::HookEvent::response_complete(
+                                Some(session_id),
+                                provider.clone(),
+                                model.clone(),
+                                duration,
+                                &full_response,
+                            ));
+                        }
+
+                        // Record how this response was generated, both for
+                        // the TUI and for the session file
+                        let meta = crate::session::MessageMeta {
+                            timestamp: Some(chrono::Utc::now()),
+                            provider: Some(provider.clone()),
+                            model: Some(model.clone()),
+                            latency_ms: Some(duration.as_millis() as u64),
+                            tokens: Some(usage::estimate_tokens(&full_response)),
+                            first_token_ms,
+                            tokens_per_sec,
+                            agent: None,
+                            reply_to: None,
+                            pinned: false,
                         };
+                        message_meta.lock().unwrap().insert(stream_index, meta.clone());
+
+                        // Stream is complete, update session
+                        let mut session = match session_manager.get_session(session_id).await {
+                            Ok(Some(session)) => session,
+                            _ => Session::new(session_id),
+                        };
+
+                        // Replace the last assistant message (empty one) with the full response
+                        if let Some(SessionChatMessage::Assistant { .. }) = session.messages.last() {
+                            session.messages.pop();
+                        }
+                        session.messages.push(SessionChatMessage::Assistant { text: full_response, meta });
+                        session.last_active = chrono::Utc::now();
                         
-                        if let Err(e) = session_manager.update_session(updated_session).await {
+                        if let Err(e) = session_manager.update_session(session).await {
                             eprintln!("Error updating session after streaming: {}", e);
                         }
                     });
                 } else {
                     // Non-streaming request
-                    let client = self.graph_os_client.as_ref().unwrap();
-                    
-                    match client.chat(api_messages, false, None).await {
+                    let prompt_tokens = prompt_tokens_estimate(&api_messages);
+                    let started = std::time::Instant::now();
+                    self.notifier.notify(
+                        crate::terminal::Activity::Responding,
+                        self.focused.load(std::sync::atomic::Ordering::Relaxed),
+                    );
+                    let response = match self.transport {
+                        ChatTransport::JsonRpc => {
+                            let client = self.graph_os_client.as_ref().unwrap();
+                            match &self.response_schema {
+                                Some((_, schema)) => {
+                                    // Structured output: validate client-side
+                                    // and feed failures back before accepting
+                                    crate::schema::request_structured(
+                                        client,
+                                        api_messages.clone(),
+                                        schema,
+                                        crate::schema::DEFAULT_SCHEMA_RETRIES,
+                                    )
+                                    .await
+                                    .map(|result| match result.value {
+                                        Some(value) => serde_json::to_string_pretty(&value)
+                                            .unwrap_or(result.raw),
+                                        None => format!(
+                                            "{}\n[schema validation failed after {} attempt(s):\n{}]",
+                                            result.raw,
+                                            result.attempts,
+                                            result.errors.join("\n")
+                                        ),
+                                    })
+                                }
+                                None => client.chat(api_messages.clone(), false, None).await,
+                            }
+                        }
+                        ChatTransport::Grpc => {
+                            // The gRPC transport always streams; collect the
+                            // chunks into one response
+                            let mut client = self.grpc_client.as_ref().unwrap().clone();
+                            let grpc_messages = to_grpc_messages(&api_messages);
+                            let (tx, mut rx) = mpsc::channel::<String>(32);
+
+                            let stream_task = tokio::spawn(async move {
+                                client.chat_stream(grpc_messages, None, tx).await
+                            });
+
+                            let mut full_response = String::new();
+                            while let Some(chunk) = rx.recv().await {
+                                full_response.push_str(&chunk);
+                            }
+
+                            match stream_task.await {
+                                Ok(Ok(())) => Ok(full_response),
+                                Ok(Err(e)) => Err(e),
+                                Err(e) => Err(crate::error::GraphOsError::Session(format!(
+                                    "Stream task failed: {}",
+                                    e
+                                ))),
+                            }
+                        }
+                    };
+
+                    // When the primary provider fails, walk the configured
+                    // fallback chain. Structured output stays on the
+                    // primary: the schema retry loop owns its client.
+                    let mut substituted = None;
+                    let response = match response {
+                        Err(primary_err)
+                            if self.transport == ChatTransport::JsonRpc
+                                && self.response_schema.is_none()
+                                && !self.fallback_clients.is_empty() =>
+                        {
+                            let mut result = Err(primary_err);
+                            for (fb_provider, fb_client) in &self.fallback_clients {
+                                match fb_client.chat(api_messages.clone(), false, None).await {
+                                    Ok(response) => {
+                                        let fb_model = fb_client
+                                            .model
+                                            .clone()
+                                            .unwrap_or_else(|| "unknown".to_string());
+                                        substituted = Some((*fb_provider, fb_model));
+                                        result = Ok(response);
+                                        break;
+                                    }
+                                    Err(e) => {
+                                        eprintln!("Fallback provider {} failed too: {}", fb_provider, e);
+                                    }
+                                }
+                            }
+                            result
+                        }
+                        other => other,
+                    };
+
+                    // Usage and metadata are labeled with the provider
+                    // that actually answered
+                    let (provider_label, model_label) = match &substituted {
+                        Some((provider, model)) => (provider.to_string().to_lowercase(), model.clone()),
+                        None => (self.usage_provider(), self.usage_model()),
+                    };
+
+                    self.notifier.notify(
+                        crate::terminal::Activity::Done,
+                        self.focused.load(std::sync::atomic::Ordering::Relaxed),
+                    );
+
+                    match response {
                         Ok(response) => {
+                            // Run the response through the output filter
+                            // pipeline before display and persistence
+                            let response = self.filters.apply(&response);
+                            // Note the substitution ahead of the answer
+                            let response = match &substituted {
+                                Some((provider, _)) => format!(
+                                    "[{} failed; answered by {}]\n{}",
+                                    self.usage_provider(),
+                                    provider.to_string().to_lowercase(),
+                                    response
+                                ),
+                                None => response,
+                            };
+                            record_usage(
+                                &self.prices,
+                                &provider_label,
+                                &model_label,
+                                self.session_id,
+                                prompt_tokens,
+                                &response,
+                                &self.session_cost,
+                            );
+
+                            let duration = started.elapsed();
+
+                            // Feed the status-bar sparklines; a
+                            // non-streaming response has no token rate
+                            if let Ok(mut history) = self.response_history.lock() {
+                                history.push(duration.as_millis() as u64, None);
+                            }
+
+                            let focused = self.focused.load(std::sync::atomic::Ordering::Relaxed);
+                            if self.hooks.should_fire(focused, duration) {
+                                self.hooks.fire(crate::hooks::HookEvent::response_complete(
+                                    Some(self.session_id),
+                                    provider_label.clone(),
+                                    model_label.clone(),
+                                    duration,
+                                    &response,
+                                ));
+                            }
+
+                            let meta = crate::session::MessageMeta {
+                                timestamp: Some(chrono::Utc::now()),
+                                provider: Some(provider_label.clone()),
+                                model: Some(model_label.clone()),
+                                latency_ms: Some(duration.as_millis() as u64),
+                                tokens: Some(usage::estimate_tokens(&response)),
+                                first_token_ms: None,
+                                tokens_per_sec: None,
+                                agent: None,
+                                reply_to: None,
+                                pinned: false,
+                            };
                             self.push_message(ChatMessage::Assistant(response));
+                            self.message_meta.lock().unwrap().insert(self.messages.len() - 1, meta);
                         },
                         Err(e) => {
                             // Fall back to local response on error
@@ -337,11 +2097,11 @@ impl ChatApp {
                             self.push_message(ChatMessage::Assistant(fallback));
                         }
                     }
-                    
+
                     // Save the session after each message
                     self.save_session().await?;
                 }
-            } else if self.graph_os_client.is_some() {
+            } else if has_client {
                 // Connection configured but not available
                 let fallback = format!("Connection unavailable. Echo: {}", user_message);
                 self.push_message(ChatMessage::Assistant(fallback));
@@ -358,23 +2118,307 @@ impl ChatApp {
         Ok(())
     }
     
+    /// Send the current conversation to each selected persona in turn.
+    /// Every reply is appended before the next persona answers, so
+    /// later agents see what earlier ones said, and each response
+    /// carries the persona's name in its metadata.
+    async fn submit_to_agents(&mut self) {
+        let config = match self.config_manager.load().await {
+            Ok(config) => config,
+            Err(e) => {
+                self.push_message(ChatMessage::Assistant(format!("Failed to load configuration: {}", e)));
+                return;
+            }
+        };
+
+        for name in self.active_agents.clone() {
+            let Some(persona) = config.get_persona(&name) else {
+                self.push_message(ChatMessage::Assistant(format!(
+                    "Persona '{}' is no longer configured; skipping.", name
+                )));
+                continue;
+            };
+
+            let client = match self.agent_client(&config, &persona) {
+                Ok(client) => client,
+                Err(reason) => {
+                    self.push_message(ChatMessage::Assistant(format!("{}: {}", name, reason)));
+                    continue;
+                }
+            };
+
+            // Personas without their own system prompt inherit the
+            // session's, so a bare provider/model persona still works
+            let system = persona.system.as_deref().or(self.system_prompt.as_deref());
+            let api_messages = self.conversation_history_with_system(system);
+            let prompt_tokens = prompt_tokens_estimate(&api_messages);
+            let started = std::time::Instant::now();
+
+            match client.chat(api_messages, false, None).await {
+                Ok(response) => {
+                    let response = self.filters.apply(&response);
+                    let provider = persona
+                        .provider
+                        .clone()
+                        .unwrap_or_else(|| self.usage_provider());
+                    let model = client.model.clone().unwrap_or_else(|| "unknown".to_string());
+                    record_usage(
+                        &self.prices,
+                        &provider,
+                        &model,
+                        self.session_id,
+                        prompt_tokens,
+                        &response,
+                        &self.session_cost,
+                    );
+
+                    let meta = crate::session::MessageMeta {
+                        timestamp: Some(chrono::Utc::now()),
+                        provider: Some(provider),
+                        model: Some(model),
+                        latency_ms: Some(started.elapsed().as_millis() as u64),
+                        tokens: Some(usage::estimate_tokens(&response)),
+                        first_token_ms: None,
+                        tokens_per_sec: None,
+                        agent: Some(name.clone()),
+                        reply_to: None,
+                        pinned: false,
+                    };
+                    self.push_message(ChatMessage::Assistant(response));
+                    self.message_meta.lock().unwrap().insert(self.messages.len() - 1, meta);
+                }
+                Err(e) => {
+                    self.push_message(ChatMessage::Assistant(format!("{}: Error: {}", name, e)));
+                }
+            }
+        }
+    }
+
+    /// Build the client a persona answers with: its provider override
+    /// when it has one, otherwise the session's active client, with
+    /// the persona's model applied on top either way
+    fn agent_client(
+        &self,
+        config: &crate::config::Config,
+        persona: &crate::config::PersonaConfig,
+    ) -> Result<JsonRpcClient, String> {
+        let mut client = match persona.provider.as_deref() {
+            Some(name) => {
+                let Some(provider) = crate::config::ApiProvider::parse(name) else {
+                    return Err(format!("unknown provider '{}' in persona config", name));
+                };
+                let Some(api_config) = config.get_api_config(provider) else {
+                    return Err(format!("provider {} is not configured", provider));
+                };
+                // Like /provider, a provider without its own url keeps
+                // talking to the endpoint the current client uses
+                let endpoint = match api_config.api_url.clone().or_else(|| {
+                    self.graph_os_client.as_ref().map(|c| c.endpoint.clone())
+                }) {
+                    Some(endpoint) => endpoint,
+                    None => {
+                        return Err(format!(
+                            "provider {} has no api_url configured and there is no active endpoint to reuse",
+                            provider
+                        ));
+                    }
+                };
+                let http_options = crate::adapters::HttpClientOptions::from_env()
+                    .merge_endpoint(config.get_endpoint_config("default").as_ref());
+                let rpc_secret = self.graph_os_client.as_ref().and_then(|c| c.rpc_secret.clone());
+                JsonRpcClient::with_endpoint_options(
+                    endpoint,
+                    Some(api_config.api_key),
+                    api_config.model,
+                    rpc_secret,
+                    &http_options,
+                )
+            }
+            None => self
+                .graph_os_client
+                .clone()
+                .ok_or_else(|| "no active API client to inherit; give the persona a provider".to_string())?,
+        };
+
+        if persona.model.is_some() {
+            client.model = persona.model.clone();
+        }
+        Ok(client)
+    }
+
+    /// Build clients for the configured provider fallback chain, in
+    /// order. The current provider is dropped so the chain never retries
+    /// the client that just failed, and so are providers without an API
+    /// key. Like /provider, a provider without its own url keeps talking
+    /// to the active endpoint.
+    fn build_fallback_chain(
+        config: &crate::config::Config,
+        current: Option<crate::config::ApiProvider>,
+        active: Option<&JsonRpcClient>,
+        http_options: &crate::adapters::HttpClientOptions,
+    ) -> Vec<(crate::config::ApiProvider, JsonRpcClient)> {
+        let mut chain = Vec::new();
+        for provider in config.fallback() {
+            if Some(provider) == current {
+                continue;
+            }
+            let Some(api_config) = config.get_api_config(provider) else {
+                eprintln!("Fallback provider {} is not configured; skipping it", provider);
+                continue;
+            };
+            let endpoint = match api_config
+                .api_url
+                .clone()
+                .or_else(|| active.map(|client| client.endpoint.clone()))
+            {
+                Some(endpoint) => endpoint,
+                None => {
+                    eprintln!(
+                        "Fallback provider {} has no api_url and there is no active endpoint to reuse; skipping it",
+                        provider
+                    );
+                    continue;
+                }
+            };
+            let rpc_secret = active.and_then(|client| client.rpc_secret.clone());
+            chain.push((
+                provider,
+                JsonRpcClient::with_endpoint_options(
+                    endpoint,
+                    Some(api_config.api_key),
+                    api_config.model,
+                    rpc_secret,
+                    http_options,
+                ),
+            ));
+        }
+        chain
+    }
+
+    /// Provider label used for usage records
+    fn usage_provider(&self) -> String {
+        match self.transport {
+            ChatTransport::Grpc => "grpc".to_string(),
+            ChatTransport::JsonRpc => self
+                .current_provider
+                .map(|p| p.to_string().to_lowercase())
+                .unwrap_or_else(|| "jsonrpc".to_string()),
+        }
+    }
+
+    /// Model label used for usage records and price lookups
+    fn usage_model(&self) -> String {
+        self.graph_os_client
+            .as_ref()
+            .and_then(|client| client.model.clone())
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+
     /// Convert the chat history to the API message format
     fn get_conversation_history(&self) -> Vec<ApiMessage> {
+        // Use the template-provided system prompt when one is set
+        self.conversation_history_with_system(self.system_prompt.as_deref())
+    }
+
+    /// Conversation history with an explicit system prompt, so a
+    /// persona can answer with its own instructions in `/agents` mode
+    /// User text as it goes over the wire: in mask mode likely secrets
+    /// leave as placeholders while the local transcript keeps the
+    /// original
+    fn outbound_user_text(&self, text: &str) -> String {
+        match self.redactor.mode() {
+            crate::redact::RedactMode::Mask => {
+                self.redactor.mask(text, &mut self.redactions.lock().unwrap())
+            }
+            _ => text.to_string(),
+        }
+    }
+
+    fn conversation_history_with_system(&self, system: Option<&str>) -> Vec<ApiMessage> {
         let mut api_messages = Vec::new();
-        
-        // Add system message if desired
+
+        let mut system_prompt = system
+            .map(str::to_string)
+            .unwrap_or_else(|| "You are a helpful assistant.".to_string());
+
+        // Append attached workspace context, trimmed to its token budget
+        if !self.context_paths.is_empty() {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(&crate::context::render_context(
+                &self.context_paths,
+                crate::context::CONTEXT_TOKEN_BUDGET,
+            ));
+        }
+
+        // Attached knowledge packs inject the chunks most relevant to
+        // the latest user message, each within its own token budget
+        if !self.kb_packs.is_empty() {
+            let query = self
+                .messages
+                .iter()
+                .rev()
+                .find_map(|message| match message {
+                    ChatMessage::User(text) => Some(text.as_str()),
+                    ChatMessage::Assistant(_) => None,
+                })
+                .unwrap_or("");
+            for name in &self.kb_packs {
+                // A pack deleted since it was attached is skipped quietly
+                if let Ok(pack) = crate::kb::load(name) {
+                    system_prompt.push_str("\n\n");
+                    system_prompt.push_str(&crate::kb::retrieve(
+                        &pack,
+                        query,
+                        crate::kb::KB_TOKEN_BUDGET,
+                    ));
+                }
+            }
+        }
+
+        // Excerpts recalled from past conversations ride in the system
+        // prompt so they survive history truncation like pins do
+        if !self.recalled.is_empty() {
+            system_prompt.push_str("\n\nRelevant excerpts from the user's past conversations:\n\n");
+            system_prompt.push_str(&self.recalled.join("\n\n"));
+        }
+
+        // Structured output mode appends the schema instruction
+        if let Some((_, schema)) = &self.response_schema {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(&crate::schema::schema_instruction(schema));
+        }
+
         api_messages.push(ApiMessage {
             role: MessageRole::System,
-            content: "You are a helpful assistant.".to_string(),
+            content: system_prompt.into(),
         });
-        
+
+        // Pinned messages from the unloaded prefix ride along in stored
+        // order, so truncating old history never drops them
+        for (_, message) in &self.pinned_prefix {
+            let (role, text) = match message {
+                SessionChatMessage::User { text, .. } => (MessageRole::User, text),
+                SessionChatMessage::Assistant { text, .. } => (MessageRole::Assistant, text),
+            };
+            if !text.is_empty() {
+                let content = match role {
+                    MessageRole::User => self.outbound_user_text(text),
+                    _ => text.clone(),
+                };
+                api_messages.push(ApiMessage {
+                    role,
+                    content: content.into(),
+                });
+            }
+        }
+
         // Add conversation history
         for msg in &self.messages {
             match msg {
                 ChatMessage::User(content) => {
                     api_messages.push(ApiMessage {
                         role: MessageRole::User,
-                        content: content.clone(),
+                        content: self.outbound_user_text(content).into(),
                     });
                 },
                 ChatMessage::Assistant(content) => {
@@ -382,7 +2426,7 @@ impl ChatApp {
                     if !content.is_empty() {
                         api_messages.push(ApiMessage {
                             role: MessageRole::Assistant,
-                            content: content.clone(),
+                            content: content.clone().into(),
                         });
                     }
                 },
@@ -392,37 +2436,222 @@ impl ChatApp {
         api_messages
     }
 
+    /// Lines for the /tokens pane: the exact messages the next request
+    /// will carry, a rough token estimate for each, what the system
+    /// prompt's attachments are costing, what the unloaded history
+    /// prefix leaves out, and how much of the assumed window remains.
+    /// Estimates use the same four-chars-per-token rule as usage
+    /// tracking, so they are indicative, not billing-exact.
+    fn token_report(&self) -> Vec<String> {
+        use crate::usage::estimate_tokens;
+
+        let api_messages = self.get_conversation_history();
+        let mut lines = Vec::new();
+        let mut total: u64 = 0;
+
+        lines.push("Messages on the next request:".to_string());
+        for (i, message) in api_messages.iter().enumerate() {
+            let text = message.content.as_text();
+            let tokens = estimate_tokens(&text);
+            total += tokens;
+            let role = match message.role {
+                MessageRole::System => "system",
+                MessageRole::User => "you",
+                MessageRole::Assistant => "assistant",
+            };
+            let preview: String = text.chars().take(40).collect::<String>().replace('\n', " ");
+            let ellipsis = if text.chars().count() > 40 { "…" } else { "" };
+            lines.push(format!("{:>3} {:<9} ~{:>6}  {}{}", i, role, tokens, preview, ellipsis));
+        }
+
+        // Where the system prompt's tokens come from, so an oversized
+        // attachment is visible instead of silently eating the window
+        let mut contributors: Vec<(&str, u64)> = Vec::new();
+        if !self.context_paths.is_empty() {
+            contributors.push((
+                "workspace context (/context)",
+                estimate_tokens(&crate::context::render_context(
+                    &self.context_paths,
+                    crate::context::CONTEXT_TOKEN_BUDGET,
+                )),
+            ));
+        }
+        if !self.kb_packs.is_empty() {
+            // Same query the history assembly uses, so the estimate
+            // matches the chunks actually injected
+            let query = self
+                .messages
+                .iter()
+                .rev()
+                .find_map(|message| match message {
+                    ChatMessage::User(text) => Some(text.as_str()),
+                    ChatMessage::Assistant(_) => None,
+                })
+                .unwrap_or("");
+            let kb: u64 = self
+                .kb_packs
+                .iter()
+                .filter_map(|name| crate::kb::load(name).ok())
+                .map(|pack| estimate_tokens(&crate::kb::retrieve(&pack, query, crate::kb::KB_TOKEN_BUDGET)))
+                .sum();
+            contributors.push(("knowledge packs (/kb)", kb));
+        }
+        if !self.recalled.is_empty() {
+            contributors.push(("recalled excerpts (/recall)", estimate_tokens(&self.recalled.join("\n\n"))));
+        }
+        if let Some((_, schema)) = &self.response_schema {
+            contributors.push((
+                "schema instruction (/schema)",
+                estimate_tokens(&crate::schema::schema_instruction(schema)),
+            ));
+        }
+        if !contributors.is_empty() {
+            lines.push(String::new());
+            lines.push("Inside the system prompt:".to_string());
+            for (label, tokens) in contributors {
+                lines.push(format!("    ~{:>6}  {}", tokens, label));
+            }
+        }
+
+        // What the history paging already left out; pins from the
+        // unloaded prefix still ride along and are counted above
+        if self.history_offset > 0 {
+            lines.push(String::new());
+            let unsent = self.history_offset.saturating_sub(self.pinned_prefix.len());
+            lines.push(format!(
+                "{} older messages are not loaded and will not be sent ({} pinned ones ride along).",
+                unsent,
+                self.pinned_prefix.len()
+            ));
+        }
+
+        lines.push(String::new());
+        lines.push(format!(
+            "Total: ~{} tokens; ~{} of an assumed {}k window left.",
+            total,
+            ASSUMED_CONTEXT_WINDOW.saturating_sub(total),
+            ASSUMED_CONTEXT_WINDOW / 1000
+        ));
+        lines
+    }
+
     /// Get filtered commands based on current input
     fn get_filtered_commands(&self) -> Vec<String> {
-        let available_commands = vec![
-            "/help",
+        let available_commands = ["/help",
             "/exit",
             "/stream",
             "/config",
+            "/fork",
+            "/template",
+            "/bookmark",
+            "/bookmarks",
+            "/quote",
+            "/pin",
+            "/unpin",
+            "/history",
+            "/recall",
+            "/context",
+            "/kb",
+            "/macro",
+            "/share",
+            "/run",
+            "/diff",
+            "/paste",
+            "/continue",
+            "/agents",
+            "/schema",
+            "/layout",
+            "/tokens",
             "/provider",
             "/model",
             "/debug on",
-            "/debug off",
-        ];
-        
+            "/debug off"];
+
         if self.input.starts_with('/') {
-            // Filter commands that start with the current input
-            available_commands
+            // Filter commands that start with the current input;
+            // configured aliases complete alongside the built-ins
+            let mut filtered: Vec<String> = available_commands
                 .iter()
-                .filter(|cmd| cmd.starts_with(&self.input))
                 .map(|cmd| cmd.to_string())
-                .collect()
+                .chain(self.aliases.keys().map(|name| format!("/{}", name)))
+                .filter(|cmd| cmd.starts_with(&self.input))
+                .collect();
+            filtered.sort();
+            filtered
         } else {
             Vec::new()
         }
     }
     
     pub fn handle_input(&mut self, key: KeyEvent) -> Option<mpsc::Sender<()>> {
+        use crossterm::event::KeyModifiers;
+
+        // Windows delivers both press and release events; acting on
+        // releases would double every keystroke there
+        if key.kind == crossterm::event::KeyEventKind::Release {
+            return None;
+        }
+
+        // The help overlay swallows keys while open
+        if self.help_open {
+            self.handle_help_key(key);
+            return None;
+        }
+
+        // Selection mode swallows keys before any input editing
+        if self.selected_message.is_some() {
+            self.handle_selection_key(key);
+            return None;
+        }
+
         match key.code {
+            // Esc dismisses a server-notice banner before anything else
+            crossterm::event::KeyCode::Esc if self.server_notice.is_some() => {
+                self.server_notice = None;
+            }
+            // Then it closes the /tokens pane
+            crossterm::event::KeyCode::Esc if self.tokens_report.is_some() => {
+                self.tokens_report = None;
+            }
+            // Esc enters selection mode on the most recent message
+            crossterm::event::KeyCode::Esc if !self.messages.is_empty() => {
+                self.selected_message = Some(self.messages.len() - 1);
+            }
+            // Shift+Enter (or Alt+Enter on terminals that cannot report
+            // shifted Enter) inserts a newline instead of submitting
+            crossterm::event::KeyCode::Enter
+                if key.modifiers.intersects(KeyModifiers::SHIFT | KeyModifiers::ALT) => {
+                    self.input.insert(self.cursor_position, '\n');
+                    self.cursor_position += 1;
+                }
             crossterm::event::KeyCode::Enter => {
-                // Check if the input is a command
-                if let Some(command) = Command::from_input(&self.input) {
-                    self.handle_command(command);
+                // A recording macro captures the raw line before it is
+                // processed; the /macro control commands themselves are
+                // not part of the recording
+                if let Some((_, steps)) = &mut self.macro_recording {
+                    let line = self.input.trim();
+                    if !line.is_empty() && !line.starts_with("/macro") {
+                        steps.push(line.to_string());
+                    }
+                }
+                // Check if the input is a command; commands are queued
+                // rather than run inline because this handler is
+                // synchronous and several commands need to await the
+                // server (provider switches, model validation).
+                // Aliases expand first, so one alias may queue a whole
+                // command sequence.
+                if self.input.trim_start().starts_with('/') {
+                    match expand_aliases(&self.input, &self.aliases) {
+                        Ok(expanded) => {
+                            self.pending_commands
+                                .extend(expanded.iter().filter_map(|part| Command::from_input(part)));
+                        }
+                        Err(e) => {
+                            self.push_message(ChatMessage::Assistant(format!(
+                                "Alias error: {}. Check the [aliases] table in the config.", e
+                            )));
+                        }
+                    }
                     self.input.clear();
                     self.cursor_position = 0;
                     return None;
@@ -431,9 +2660,9 @@ impl ChatApp {
                 let tx = mpsc::channel::<()>(1).0;
                 return Some(tx); // Return channel for async processing
             }
-            crossterm::event::KeyCode::Tab => {
+            crossterm::event::KeyCode::Tab
                 // Auto-complete command if it's unambiguous
-                if self.input.starts_with('/') {
+                if self.input.starts_with('/') => {
                     let filtered = self.get_filtered_commands();
                     if filtered.len() == 1 {
                         // Add space after command if it's not a command with a toggle
@@ -464,41 +2693,340 @@ impl ChatApp {
                         }
                     }
                 }
+            // Ctrl+Up grows the input box and Ctrl+Down shrinks it
+            // back; the new size is persisted with the layout so it
+            // survives restarts
+            crossterm::event::KeyCode::Up
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && self.input_extra_rows < MAX_INPUT_EXTRA_ROWS => {
+                    self.input_extra_rows += 1;
+                    self.persist_layout();
+                }
+            crossterm::event::KeyCode::Down
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && self.input_extra_rows > 0 => {
+                    self.input_extra_rows -= 1;
+                    self.persist_layout();
+                }
+            // Ctrl+E edits the buffer in $EDITOR with the TUI suspended
+            crossterm::event::KeyCode::Char('e')
+                if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    if let Err(e) = self.open_external_editor() {
+                        self.push_message(ChatMessage::Assistant(format!("Editor error: {}", e)));
+                    }
+                }
+            // Ctrl+Z suspends to the shell; raw mode keeps the
+            // terminal driver from turning it into SIGTSTP itself, so
+            // the key routes to the same path the signal would take
+            #[cfg(unix)]
+            crossterm::event::KeyCode::Char('z')
+                if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.suspend_to_shell();
+                }
+            // ? on an empty input opens the help overlay; mid-message
+            // it is just a character
+            crossterm::event::KeyCode::Char('?') if self.input.is_empty() => {
+                self.help_open = true;
             }
             crossterm::event::KeyCode::Char(c) => {
                 self.input.insert(self.cursor_position, c);
                 self.cursor_position += 1;
-                
-                // Always show command suggestions for testing
                 self.show_commands = true;
-                
-                // Log that a character was typed for debugging
-                eprintln!("Character typed: {}", c);
+                // Composing after an idle gap: start waking the
+                // connection now, while the user is still typing
+                self.maybe_prewarm();
             }
-            crossterm::event::KeyCode::Backspace => {
-                if self.cursor_position > 0 {
+            crossterm::event::KeyCode::Backspace
+                if self.cursor_position > 0 => {
                     self.cursor_position -= 1;
                     self.input.remove(self.cursor_position);
-                    
-                    // Always show commands for testing
                     self.show_commands = true;
-                    eprintln!("Backspace pressed");
                 }
-            }
-            crossterm::event::KeyCode::Left => {
-                if self.cursor_position > 0 {
+            crossterm::event::KeyCode::Left
+                if self.cursor_position > 0 => {
                     self.cursor_position -= 1;
                 }
-            }
-            crossterm::event::KeyCode::Right => {
-                if self.cursor_position < self.input.len() {
+            crossterm::event::KeyCode::Right
+                if self.cursor_position < self.input.len() => {
                     self.cursor_position += 1;
                 }
-            }
+            crossterm::event::KeyCode::Up
+                if self.input.contains('\n') => {
+                    self.move_cursor_vertical(-1);
+                }
+            crossterm::event::KeyCode::Down
+                if self.input.contains('\n') => {
+                    self.move_cursor_vertical(1);
+                }
             _ => {}
         }
         None
     }
+
+    /// Handle a bracketed paste as one insertion. Small pastes go
+    /// straight into the input buffer; anything over
+    /// `LARGE_PASTE_THRESHOLD` is held back so a stray clipboard full
+    /// of logs does not flood the conversation.
+    pub fn handle_paste(&mut self, pasted: &str) {
+        let text = sanitize_paste(pasted);
+        if text.is_empty() {
+            return;
+        }
+
+        if text.len() > LARGE_PASTE_THRESHOLD {
+            let lines = text.lines().count();
+            let kib = text.len() / 1024;
+            self.pending_paste = Some(text);
+            self.push_message(ChatMessage::Assistant(format!(
+                "Large paste held back ({} KiB, {} lines). Use /paste insert to put it in the \
+                 input anyway, or /paste file to attach it as a context file.",
+                kib, lines
+            )));
+            return;
+        }
+
+        self.input.insert_str(self.cursor_position, &text);
+        self.cursor_position += text.len();
+    }
+
+    /// Handle a key while the help overlay is open: type to filter the
+    /// keymap registry, arrows scroll, Esc or Enter closes
+    fn handle_help_key(&mut self, key: KeyEvent) {
+        use crossterm::event::KeyCode;
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Enter => {
+                self.help_open = false;
+                self.help_query.clear();
+                self.help_scroll = 0;
+            }
+            KeyCode::Char(c) => {
+                self.help_query.push(c);
+                self.help_scroll = 0;
+            }
+            KeyCode::Backspace => {
+                self.help_query.pop();
+                self.help_scroll = 0;
+            }
+            KeyCode::Up => self.help_scroll = self.help_scroll.saturating_sub(1),
+            KeyCode::Down => self.help_scroll = self.help_scroll.saturating_add(1),
+            KeyCode::PageUp => self.help_scroll = self.help_scroll.saturating_sub(10),
+            KeyCode::PageDown => self.help_scroll = self.help_scroll.saturating_add(10),
+            _ => {}
+        }
+    }
+
+    /// (line, column) of the cursor within the input buffer
+    /// Handle a key while selection mode (or its action popup) is open
+    fn handle_selection_key(&mut self, key: KeyEvent) {
+        use crossterm::event::KeyCode;
+
+        let Some(selected) = self.selected_message else {
+            return;
+        };
+
+        // Popup open: navigate and run actions
+        if let Some(action) = self.selected_action {
+            match key.code {
+                KeyCode::Esc => self.selected_action = None,
+                KeyCode::Up => self.selected_action = Some(action.saturating_sub(1)),
+                KeyCode::Down => {
+                    self.selected_action = Some((action + 1).min(MESSAGE_ACTIONS.len() - 1));
+                }
+                KeyCode::Enter => self.run_message_action(selected, action),
+                _ => {}
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Esc => self.selected_message = None,
+            KeyCode::Up => self.selected_message = Some(selected.saturating_sub(1)),
+            KeyCode::Down => {
+                self.selected_message = Some((selected + 1).min(self.messages.len().saturating_sub(1)));
+            }
+            KeyCode::Enter => self.selected_action = Some(0),
+            _ => {}
+        }
+    }
+
+    /// Run one of MESSAGE_ACTIONS against the selected message
+    fn run_message_action(&mut self, index: usize, action: usize) {
+        self.selected_action = None;
+
+        let Some(message) = self.messages.get(index) else {
+            self.selected_message = None;
+            return;
+        };
+        let text = match message {
+            ChatMessage::User(text) | ChatMessage::Assistant(text) => text.clone(),
+        };
+
+        match MESSAGE_ACTIONS.get(action).copied().unwrap_or_default() {
+            "Copy" => {
+                copy_to_clipboard(&text);
+            }
+            "Quote into input" => {
+                self.quote_message(index);
+                self.selected_message = None;
+            }
+            "Delete" => {
+                self.messages.remove(index);
+
+                // Re-point bookmarks at their shifted indices
+                self.bookmarks.retain(|&b| b != index);
+                for bookmark in &mut self.bookmarks {
+                    if *bookmark > index {
+                        *bookmark -= 1;
+                    }
+                }
+
+                if self.messages.is_empty() {
+                    self.selected_message = None;
+                } else {
+                    self.selected_message = Some(index.min(self.messages.len() - 1));
+                }
+            }
+            "Regenerate from here" => {
+                // Walk back to the nearest user message and replay it: the
+                // truncated transcript plus pre-filled input lets Enter
+                // resend from that point
+                let start = (0..=index)
+                    .rev()
+                    .find(|&i| matches!(self.messages[i], ChatMessage::User(_)));
+                if let Some(start) = start {
+                    if let ChatMessage::User(text) = &self.messages[start] {
+                        self.input = text.clone();
+                        self.cursor_position = self.input.len();
+                    }
+                    self.messages.truncate(start);
+                    self.bookmarks.retain(|&b| b < start);
+                }
+                self.selected_message = None;
+            }
+            "Toggle bookmark" => {
+                if let Some(pos) = self.bookmarks.iter().position(|&b| b == index) {
+                    self.bookmarks.remove(pos);
+                } else {
+                    self.bookmarks.push(index);
+                    self.bookmarks.sort_unstable();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Insert a quoted excerpt of message `index` into the input and
+    /// mark the next outgoing message as a reply to it
+    fn quote_message(&mut self, index: usize) {
+        let Some(message) = self.messages.get(index) else {
+            return;
+        };
+        let text = match message {
+            ChatMessage::User(text) | ChatMessage::Assistant(text) => text,
+        };
+
+        // Prefix each line so the quote reads as a reply; long
+        // messages are elided rather than pasted whole
+        let mut quoted: String = text
+            .lines()
+            .take(QUOTE_EXCERPT_LINES)
+            .map(|line| format!("> {}\n", line))
+            .collect();
+        if text.lines().count() > QUOTE_EXCERPT_LINES {
+            quoted.push_str("> [...]\n");
+        }
+
+        self.input.insert_str(self.cursor_position, &quoted);
+        self.cursor_position += quoted.len();
+        // Threading survives page loads by using the stored index
+        self.pending_reply_to = Some(self.history_offset + index);
+    }
+
+    pub(crate) fn cursor_line_col(&self) -> (usize, usize) {
+        let before = &self.input[..self.cursor_position];
+        let line = before.matches('\n').count();
+        let col = match before.rfind('\n') {
+            Some(i) => self.cursor_position - i - 1,
+            None => self.cursor_position,
+        };
+        (line, col)
+    }
+
+    /// Move the cursor up or down one line, keeping the column if possible
+    fn move_cursor_vertical(&mut self, delta: isize) {
+        let lines: Vec<&str> = self.input.split('\n').collect();
+        let (line, col) = self.cursor_line_col();
+
+        let target = line as isize + delta;
+        if target < 0 || target as usize >= lines.len() {
+            return;
+        }
+        let target = target as usize;
+
+        // Byte offset of the target line start
+        let start: usize = lines[..target].iter().map(|l| l.len() + 1).sum();
+        self.cursor_position = start + col.min(lines[target].len());
+    }
+
+    /// Suspend into the shell, as Ctrl+Z does in a line-mode program.
+    /// Raw mode keeps the terminal driver from stopping us, so the TUI
+    /// hands the terminal back and stops the process itself with
+    /// SIGSTOP — which cannot be caught, so the stop is genuine even
+    /// though a SIGTSTP handler is installed. Active streams are not
+    /// cancelled: the response keeps accumulating in the socket buffer
+    /// while the process is stopped, and on SIGCONT the streaming task
+    /// drains the backlog through the event channel, so the first
+    /// repaint shows the conversation caught up rather than frozen
+    /// where it left off.
+    #[cfg(unix)]
+    fn suspend_to_shell(&mut self) {
+        let _ = restore_terminal();
+        let _ = nix::sys::signal::raise(nix::sys::signal::Signal::SIGSTOP);
+        // Execution continues here after SIGCONT. Re-enter the TUI
+        // immediately rather than waiting for the Resumed event, so
+        // the frame drawn on the way out of this reducer call does
+        // not land on the shell's screen
+        let _ = reclaim_terminal();
+    }
+
+    /// Open $EDITOR on the current input buffer with the TUI suspended,
+    /// and replace the input with the saved buffer on success
+    fn open_external_editor(&mut self) -> anyhow::Result<()> {
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_string());
+
+        let path = std::env::temp_dir().join(format!("gos-input-{}.md", Uuid::new_v4()));
+        std::fs::write(&path, &self.input)?;
+
+        // Hand the terminal over to the editor, then take it back
+        restore_terminal()?;
+        let status = std::process::Command::new(&editor).arg(&path).status();
+        reclaim_terminal()?;
+
+        match status {
+            Ok(status) if status.success() => {
+                let text = std::fs::read_to_string(&path)?;
+                // Editors add a trailing newline on save; drop exactly one
+                self.input = text.strip_suffix('\n').unwrap_or(&text).to_string();
+                self.cursor_position = self.input.len();
+            }
+            Ok(status) => {
+                self.push_message(ChatMessage::Assistant(format!(
+                    "Editor exited with {}; input unchanged.", status
+                )));
+            }
+            Err(e) => {
+                self.push_message(ChatMessage::Assistant(format!(
+                    "Failed to launch editor '{}': {}", editor, e
+                )));
+            }
+        }
+
+        let _ = std::fs::remove_file(&path);
+        Ok(())
+    }
     
     /// Check if provider is available in the configuration
     pub async fn is_provider_available(&self, provider: crate::config::ApiProvider) -> bool {
@@ -510,27 +3038,346 @@ impl ChatApp {
             false
         }
     }
-    
+
+    /// Switch to another configured provider mid-conversation. Reloads
+    /// the config so credential edits made while chatting are picked up,
+    /// rebuilds the JSONRPC client from the provider's entry, and pings
+    /// it so a broken switch is reported immediately.
+    /// Effect behind /provider: reload the config, rebuild the client
+    /// for the new provider and ping it before committing.
+    /// `model_override` replaces the provider's configured model, used
+    /// when restoring a resumed session's own model choice.
+    async fn switch_provider_effect(
+        config_manager: Arc<crate::config::ConfigManager>,
+        fallback_endpoint: Option<String>,
+        rpc_secret: Option<String>,
+        provider: crate::config::ApiProvider,
+        model_override: Option<String>,
+    ) -> Vec<CommandEvent> {
+        let config = match config_manager.load().await {
+            Ok(config) => config,
+            Err(e) => return vec![CommandEvent::Note(format!("Failed to load configuration: {}", e))],
+        };
+
+        let Some(api_config) = config.get_api_config(provider) else {
+            return vec![CommandEvent::Note(format!(
+                "Provider {} is not configured. Add it with `gos config set-api` and try again.", provider
+            ))];
+        };
+
+        // A provider without its own url keeps talking to the endpoint
+        // the current client uses (the host/port case from startup)
+        let endpoint = match api_config.api_url.clone().or(fallback_endpoint) {
+            Some(endpoint) => endpoint,
+            None => {
+                return vec![CommandEvent::Note(format!(
+                    "Provider {} has no api_url configured and there is no active endpoint to reuse.", provider
+                ))];
+            }
+        };
+
+        let http_options = crate::adapters::HttpClientOptions::from_env()
+            .merge_endpoint(config.get_endpoint_config("default").as_ref());
+
+        let model = model_override.or(api_config.model);
+        let client = JsonRpcClient::with_endpoint_options(
+            endpoint,
+            Some(api_config.api_key),
+            model.clone(),
+            rpc_secret,
+            &http_options,
+        );
+
+        // Verify the new client before committing to it
+        let connected = matches!(client.ping().await, Ok(true));
+
+        let model_note = model
+            .map(|m| format!(", model {}", m))
+            .unwrap_or_default();
+        let note = if connected {
+            format!("Switched to provider {}{}.", provider, model_note)
+        } else {
+            format!(
+                "Switched to provider {}{}, but the endpoint did not answer a ping. Check /config.",
+                provider, model_note
+            )
+        };
+        vec![
+            CommandEvent::ProviderSwitched { client: Box::new(client), provider, connected },
+            CommandEvent::Note(note),
+        ]
+    }
+
+    /// Effect behind /model: validate the model against the server's
+    /// model list when the server exposes one. Unknown models are
+    /// rejected with the closest matches as suggestions.
+    async fn set_model_effect(client: Option<JsonRpcClient>, model: String) -> Vec<CommandEvent> {
+        let Some(client) = client else {
+            return vec![CommandEvent::Note(
+                "No active API client. Please connect to a provider first.".to_string(),
+            )];
+        };
+
+        match client.list_models().await {
+            Ok(Some(models)) if !models.iter().any(|m| m == &model) => {
+                let suggestions = model_suggestions(&model, &models);
+                let hint = if suggestions.is_empty() {
+                    format!("Available models: {}", models.join(", "))
+                } else {
+                    format!("Did you mean: {}?", suggestions.join(", "))
+                };
+                return vec![CommandEvent::Note(format!("Unknown model '{}'. {}", model, hint))];
+            }
+            Ok(Some(_)) => {
+                // Exact match against the server's list
+            }
+            Ok(None) | Err(_) => {
+                // The server does not list models (or is unreachable);
+                // set the model unvalidated rather than block the user
+            }
+        }
+
+        vec![
+            CommandEvent::ModelSet(model.clone()),
+            CommandEvent::Note(format!("Model set to: {}", model)),
+        ]
+    }
+
+    /// Effect behind a confirmed /share: upload the saved conversation
+    /// to the configured share endpoint
+    async fn share_transcript_effect(
+        config_manager: Arc<crate::config::ConfigManager>,
+        session_manager: Arc<SessionManager>,
+        session_id: Uuid,
+    ) -> Vec<CommandEvent> {
+        let config = match config_manager.load().await {
+            Ok(config) => config,
+            Err(e) => return vec![CommandEvent::Note(format!("Failed to load configuration: {}", e))],
+        };
+
+        let Some(share) = config.share() else {
+            return vec![CommandEvent::Note(
+                "No share endpoint configured; add a [share] table with a url to the config file.".to_string(),
+            )];
+        };
+
+        let session = match session_manager.get_session(session_id).await {
+            Ok(Some(session)) => session,
+            Ok(None) => return vec![CommandEvent::Note("Session not found after saving.".to_string())],
+            Err(e) => return vec![CommandEvent::Note(format!("Failed to read session: {}", e))],
+        };
+
+        match crate::share::share_session(&config, &share, &session, None).await {
+            Ok(url) => vec![CommandEvent::Note(format!("Transcript shared: {}", url))],
+            Err(e) => vec![CommandEvent::Note(format!("Share failed: {}", e))],
+        }
+    }
+
+    /// Search the local embedding index for exchanges relevant to
+    /// `query` and hand them back for the system prompt. Other
+    /// sessions only; the current conversation is already in context.
+    async fn recall_effect(
+        config_manager: Arc<crate::config::ConfigManager>,
+        session_manager: Arc<SessionManager>,
+        current_session: Uuid,
+        query: String,
+    ) -> Vec<CommandEvent> {
+        use crate::embeddings::{Embedder, EmbeddingIndex};
+
+        let sessions = match session_manager.list_sessions().await {
+            Ok(sessions) => sessions,
+            Err(e) => return vec![CommandEvent::Note(format!("Failed to list sessions: {}", e))],
+        };
+
+        // The provider embedder when the endpoint configures an
+        // embedding_model, the local hashed one otherwise
+        let embedder = match config_manager.load().await {
+            Ok(config) => Embedder::from_config(&config),
+            Err(_) => Embedder::Local,
+        };
+
+        let path = EmbeddingIndex::default_path();
+        let mut index = EmbeddingIndex::load(&path).unwrap_or_default();
+        match index.refresh(&sessions, &embedder).await {
+            Ok(reindexed) => {
+                if reindexed > 0
+                    && let Err(e) = index.save(&path)
+                {
+                    eprintln!("Failed to save embedding index: {}", e);
+                }
+            }
+            Err(e) => return vec![CommandEvent::Note(format!("Embedding failed: {}", e))],
+        }
+
+        // Over-fetch, then drop hits from this conversation
+        let hits: Vec<_> = match index.search(&query, RECALL_LIMIT + 8, &embedder).await {
+            Ok(hits) => hits,
+            Err(e) => return vec![CommandEvent::Note(format!("Embedding failed: {}", e))],
+        };
+        let hits: Vec<_> = hits
+            .into_iter()
+            .filter(|(_, entry)| entry.session_id != current_session)
+            .take(RECALL_LIMIT)
+            .collect();
+        if hits.is_empty() {
+            return vec![CommandEvent::Note(format!(
+                "Nothing in past conversations resembles '{}'.", query
+            ))];
+        }
+
+        // Carry the hit and its reply as one exchange, trimmed so a few
+        // recalls cannot crowd out the actual conversation
+        let mut blocks = Vec::new();
+        let mut listing = String::from("Recalled from past conversations:\n");
+        for (score, hit) in &hits {
+            let Some(session) = sessions.iter().find(|s| s.id == hit.session_id) else {
+                continue;
+            };
+            let mut block = format!("[from session {}]\n", hit.session_id);
+            for message in session.messages.iter().skip(hit.index).take(2) {
+                let text: String = message.text().chars().take(RECALL_EXCERPT_CHARS).collect();
+                block.push_str(&format!("{}: {}\n", message.role(), text));
+            }
+            blocks.push(block);
+            listing.push_str(&format!("[{:.2}] {}: {}\n", score, hit.session_id, hit.preview));
+        }
+        listing.push_str("These excerpts now ride with the conversation; /recall off clears them.");
+
+        vec![CommandEvent::Recalled(blocks), CommandEvent::Note(listing)]
+    }
+
+    /// First /run: lift the last code block out of the most recent
+    /// assistant response and show it for confirmation
+    fn prepare_run(&mut self) {
+        let last_assistant = self.messages.iter().rev().find_map(|message| match message {
+            ChatMessage::Assistant(text) => Some(text.as_str()),
+            ChatMessage::User(_) => None,
+        });
+
+        let Some(text) = last_assistant else {
+            self.push_message(ChatMessage::Assistant(
+                "No assistant response to run code from yet.".to_string(),
+            ));
+            return;
+        };
+
+        let Some(block) = crate::sandbox::extract_last_code_block(text) else {
+            self.push_message(ChatMessage::Assistant(
+                "The last assistant response contains no code block.".to_string(),
+            ));
+            return;
+        };
+
+        if crate::sandbox::interpreter_for(&block.language).is_none() {
+            let language = if block.language.is_empty() { "untagged" } else { &block.language };
+            self.push_message(ChatMessage::Assistant(format!(
+                "Cannot run {} code blocks. Supported: python, sh, javascript, ruby.", language
+            )));
+            return;
+        }
+
+        let preview = format!(
+            "About to run this {} block in a sandbox ({}s timeout, no network, memory capped):\n\
+            ```{}\n{}\n```\n\
+            Run /run again to execute, or any other command to cancel.",
+            block.language,
+            crate::sandbox::RUN_TIMEOUT.as_secs(),
+            block.language,
+            block.code
+        );
+        self.run_pending = Some(block);
+        self.push_message(ChatMessage::Assistant(preview));
+    }
+
+    /// Second /run: execute the confirmed block and append its output
+    /// as a user message so it can be sent back to the model
+    async fn run_code_block(&mut self, block: crate::sandbox::CodeBlock) {
+        match crate::sandbox::run_code(&block).await {
+            Ok(output) => {
+                self.push_message(ChatMessage::User(output.render()));
+            }
+            Err(e) => {
+                self.push_message(ChatMessage::Assistant(format!("Run failed: {}", e)));
+            }
+        }
+    }
+
+    /// Compare this conversation with another session side by side,
+    /// defaulting to the parent when this session is a fork
+    async fn show_diff(&mut self, target: Option<String>) {
+        let target_id = match target {
+            Some(raw) => match raw.parse::<Uuid>() {
+                Ok(id) => id,
+                Err(_) => {
+                    self.push_message(ChatMessage::Assistant(format!(
+                        "Invalid session ID '{}'.", raw
+                    )));
+                    return;
+                }
+            },
+            None => {
+                // No argument: diff against the session we forked from
+                let parent = match self.session_manager.get_session(self.session_id).await {
+                    Ok(Some(session)) => session.parent_id,
+                    _ => None,
+                };
+                let Some(parent) = parent else {
+                    self.push_message(ChatMessage::Assistant(
+                        "This session has no parent to compare against. \
+                        Usage: /diff <session-id>".to_string()
+                    ));
+                    return;
+                };
+                parent
+            }
+        };
+
+        let other = match self.session_manager.get_session(target_id).await {
+            Ok(Some(session)) => session,
+            Ok(None) => {
+                self.push_message(ChatMessage::Assistant(format!(
+                    "No session found with ID {}.", target_id
+                )));
+                return;
+            }
+            Err(e) => {
+                self.push_message(ChatMessage::Assistant(format!(
+                    "Failed to load session {}: {}", target_id, e
+                )));
+                return;
+            }
+        };
+
+        // Compare the in-memory conversation, not the last saved copy
+        let ours = Session {
+            messages: self.session_messages(),
+            ..Session::new(self.session_id)
+        };
+
+        let diff = crate::diff::render_side_by_side(&ours, &other, 100);
+        self.push_message(ChatMessage::Assistant(diff));
+    }
+
     /// Show current configuration
     pub fn show_config(&mut self) {
         let mut config_info = String::new();
         
         // Show current provider
         if let Some(provider) = self.current_provider {
-            config_info.push_str(&format!("🔌 Current provider: {}\n", provider));
+            config_info.push_str(&format!("{}Current provider: {}\n", self.style.icon("🔌 "), provider));
         } else {
-            config_info.push_str("🔌 No provider selected\n");
+            config_info.push_str(&format!("{}No provider selected\n", self.style.icon("🔌 ")));
         }
         
         // Show connection status
         if self.connected {
             if let Some(client) = &self.graph_os_client {
-                config_info.push_str(&format!("🌐 Connected to: {}\n", client.endpoint));
+                config_info.push_str(&format!("{}Connected to: {}\n", self.style.icon("🌐 "), client.endpoint));
             } else {
-                config_info.push_str("🌐 Connection status: Connected\n");
+                config_info.push_str(&format!("{}Connection status: Connected\n", self.style.icon("🌐 ")));
             }
         } else {
-            config_info.push_str("🌐 Connection status: Disconnected\n");
+            config_info.push_str(&format!("{}Connection status: Disconnected\n", self.style.icon("🌐 ")));
         }
         
         // Show model information if available
@@ -541,44 +3388,856 @@ impl ChatApp {
                 config_info.push_str("🧠 Model: Not specified\n");
             }
         }
-        
-        // Show settings
-        config_info.push_str("\n⚙️ Settings:\n");
-        
-        // Show streaming status
-        let streaming_status = if self.streaming { "enabled" } else { "disabled" };
-        config_info.push_str(&format!("- Streaming: {}\n", streaming_status));
-        
-        // Show debug mode
-        let debug_status = if self.debug_mode { "enabled" } else { "disabled" };
-        config_info.push_str(&format!("- Debug mode: {}\n", debug_status));
-        
-        // Show available providers
-        if !self.available_providers.is_empty() {
-            config_info.push_str("\n🔌 Available providers:\n");
-            for provider in &self.available_providers {
-                let marker = if Some(*provider) == self.current_provider { "→ " } else { "  " };
-                config_info.push_str(&format!("{}{}\n", marker, provider));
+        
+        // Show settings
+        config_info.push_str("\n⚙️ Settings:\n");
+        
+        // Show streaming status
+        let streaming_status = if self.streaming { "enabled" } else { "disabled" };
+        config_info.push_str(&format!("- Streaming: {}\n", streaming_status));
+        
+        // Show debug mode
+        let debug_status = if self.debug_mode { "enabled" } else { "disabled" };
+        config_info.push_str(&format!("- Debug mode: {}\n", debug_status));
+        
+        // Show available providers
+        if !self.available_providers.is_empty() {
+            config_info.push_str(&format!("\n{}Available providers:\n", self.style.icon("🔌 ")));
+            for provider in &self.available_providers {
+                let marker = if Some(*provider) == self.current_provider { "→ " } else { "  " };
+                config_info.push_str(&format!("{}{}\n", marker, provider));
+            }
+        } else {
+            config_info.push_str(&format!("\n{}No API providers configured\n", self.style.icon("🔌 ")));
+        }
+        
+        // Add tips
+        config_info.push_str("\n💡 Tips:\n");
+        config_info.push_str("- Use /provider <name> to switch providers\n");
+        config_info.push_str("- Use /model <name> to change models\n");
+        config_info.push_str("- Use /stream to toggle streaming mode\n");
+        
+        self.push_message(ChatMessage::Assistant(config_info));
+    }
+
+    /// Apply a named prompt template, or list the configured templates
+    fn apply_template(&mut self, name: Option<String>) {
+        let Some(name) = name else {
+            if self.templates.is_empty() {
+                self.push_message(ChatMessage::Assistant(
+                    "No templates configured. Define them under `templates` in your gos config file.".to_string(),
+                ));
+                return;
+            }
+
+            let mut names: Vec<&String> = self.templates.keys().collect();
+            names.sort();
+            let mut listing = String::from("Available templates:\n");
+            for name in names {
+                let description = self.templates[name]
+                    .description
+                    .as_deref()
+                    .unwrap_or("");
+                listing.push_str(&format!("- {} {}\n", name, description));
+            }
+            listing.push_str("Apply one with /template <name>.");
+            self.push_message(ChatMessage::Assistant(listing));
+            return;
+        };
+
+        let Some(template) = self.templates.get(&name).cloned() else {
+            self.push_message(ChatMessage::Assistant(format!(
+                "Unknown template: '{}'. Use /template to list available templates.", name
+            )));
+            return;
+        };
+
+        self.system_prompt = template.system.clone();
+
+        // Pre-fill the input with the template prompt so the user can fill
+        // in any {{variable}} placeholders before sending
+        if let Some(prompt) = &template.prompt {
+            self.input = prompt.clone();
+            self.cursor_position = self.input.len();
+        }
+
+        let variables = template.variables();
+        let note = if variables.is_empty() {
+            format!("Applied template '{}'.", name)
+        } else {
+            format!(
+                "Applied template '{}'. Fill in the placeholders: {}",
+                name,
+                variables.join(", ")
+            )
+        };
+        self.push_message(ChatMessage::Assistant(note));
+    }
+
+    /// Show the bookmarked messages with a short preview of each
+    fn show_bookmarks(&mut self) {
+        if self.bookmarks.is_empty() {
+            self.push_message(ChatMessage::Assistant(
+                "No bookmarks yet. Use /bookmark [index] to bookmark a message.".to_string(),
+            ));
+            return;
+        }
+
+        let mut listing = String::from("🔖 Bookmarked messages:\n");
+        for &index in &self.bookmarks {
+            let (role, text) = match self.messages.get(index) {
+                Some(ChatMessage::User(text)) => ("you", text.as_str()),
+                Some(ChatMessage::Assistant(text)) => ("assistant", text.as_str()),
+                None => continue,
+            };
+
+            // Keep previews to a single line
+            let preview: String = text.chars().take(80).collect();
+            let ellipsis = if text.chars().count() > 80 { "..." } else { "" };
+            listing.push_str(&format!("[{}] {}: {}{}\n", index, role, preview, ellipsis));
+        }
+
+        self.push_message(ChatMessage::Assistant(listing));
+    }
+
+    /// List this session's restore points so a rollback target can be
+    /// picked without leaving the chat
+    fn show_history(&mut self) {
+        let note = match crate::snapshots::list_snapshots(self.session_id) {
+            Ok(snapshots) if snapshots.is_empty() => {
+                "No restore points yet. Snapshots are taken automatically as the \
+                 conversation grows and before destructive commands."
+                    .to_string()
+            }
+            Ok(snapshots) => {
+                let mut listing = String::from("Restore points (newest first):\n");
+                for info in &snapshots {
+                    listing.push_str(&format!("{}\n", crate::snapshots::describe(info)));
+                }
+                listing.push_str(&format!(
+                    "Restore one with: gos rollback {} --to <snapshot>",
+                    self.session_id
+                ));
+                listing
+            }
+            Err(e) => format!("Could not list restore points: {}", e),
+        };
+        self.push_message(ChatMessage::Assistant(note));
+    }
+
+    /// Snapshot the stored session before a destructive command. A
+    /// failure only logs; the command itself still runs.
+    async fn snapshot_before(&self, reason: &str) {
+        if let Ok(Some(session)) = self.session_manager.get_session(self.session_id).await {
+            if let Err(e) = crate::snapshots::take_snapshot(&session, reason) {
+                eprintln!("Failed to snapshot session: {}", e);
+                return;
+            }
+            // Push the automatic snapshot counter forward so the next
+            // save doesn't immediately take another one
+            self.snapshot_watermark
+                .store(session.messages.len(), std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    /// Pin or unpin a message by its visible index, defaulting to the
+    /// most recent. Pins live in the message metadata, so they persist
+    /// with the session and keep the message in the conversation
+    /// history even after older pages are truncated away.
+    fn set_pinned(&mut self, index: Option<usize>, pinned: bool) {
+        let verb = if pinned { "pin" } else { "unpin" };
+        let index = index.unwrap_or_else(|| self.messages.len().saturating_sub(1));
+        if index >= self.messages.len() {
+            self.push_message(ChatMessage::Assistant(format!(
+                "No message at index {} to {}.", index, verb
+            )));
+            return;
+        }
+
+        let already = {
+            let mut meta_map = self.message_meta.lock().unwrap();
+            let entry = meta_map.entry(index).or_default();
+            let already = entry.pinned == pinned;
+            entry.pinned = pinned;
+            already
+        };
+        let note = match (pinned, already) {
+            (true, true) => format!("Message {} is already pinned.", index),
+            (true, false) => format!(
+                "Pinned message {}. It will always be sent with the conversation history.",
+                index
+            ),
+            (false, true) => format!("Message {} is not pinned.", index),
+            (false, false) => format!("Unpinned message {}.", index),
+        };
+        self.push_message(ChatMessage::Assistant(note));
+    }
+
+    /// Handle the /context command: list, attach or detach workspace
+    /// context entries for this session
+    fn handle_context(&mut self, arg: Option<String>) {
+        let usage = "Usage: /context add <path>, /context ls, /context rm <path>. \
+                     Use the path 'repo' to attach git metadata.";
+
+        let Some(arg) = arg else {
+            self.show_context();
+            return;
+        };
+
+        let (action, rest) = match arg.split_once(' ') {
+            Some((action, rest)) => (action, rest.trim()),
+            None => (arg.as_str(), ""),
+        };
+
+        match action {
+            "ls" => self.show_context(),
+            "add" if !rest.is_empty() => {
+                if rest != crate::context::REPO_PSEUDO_PATH && !std::path::Path::new(rest).is_file() {
+                    self.push_message(ChatMessage::Assistant(format!("No such file: {}", rest)));
+                } else if self.context_paths.iter().any(|p| p == rest) {
+                    self.push_message(ChatMessage::Assistant(format!("{} is already attached.", rest)));
+                } else {
+                    let tokens = crate::usage::estimate_tokens(&crate::context::resolve(rest));
+                    self.context_paths.push(rest.to_string());
+                    self.push_message(ChatMessage::Assistant(format!(
+                        "Attached {} (~{} tokens). It will be injected into the system prompt.",
+                        rest, tokens
+                    )));
+                }
+            }
+            "rm" if !rest.is_empty() => {
+                if let Some(pos) = self.context_paths.iter().position(|p| p == rest) {
+                    self.context_paths.remove(pos);
+                    self.push_message(ChatMessage::Assistant(format!("Detached {}.", rest)));
+                } else {
+                    self.push_message(ChatMessage::Assistant(format!("{} is not attached.", rest)));
+                }
+            }
+            _ => {
+                self.push_message(ChatMessage::Assistant(usage.to_string()));
+            }
+        }
+    }
+
+    /// Handle the /kb command: attach, detach or list knowledge packs
+    /// for this session
+    fn handle_kb(&mut self, arg: Option<String>) {
+        let usage = "Usage: /kb use <name>, /kb ls, /kb rm <name>. \
+                     Build packs with `gos kb create`.";
+
+        let Some(arg) = arg else {
+            self.show_kb();
+            return;
+        };
+
+        let (action, rest) = match arg.split_once(' ') {
+            Some((action, rest)) => (action, rest.trim()),
+            None => (arg.as_str(), ""),
+        };
+
+        match action {
+            "ls" => self.show_kb(),
+            "use" if !rest.is_empty() => {
+                if self.kb_packs.iter().any(|p| p == rest) {
+                    self.push_message(ChatMessage::Assistant(format!("{} is already attached.", rest)));
+                    return;
+                }
+                // Load once up front so a typo surfaces immediately
+                // rather than as a silently missing injection
+                match crate::kb::load(rest) {
+                    Ok(pack) => {
+                        self.kb_packs.push(rest.to_string());
+                        self.push_message(ChatMessage::Assistant(format!(
+                            "Attached knowledge pack '{}' ({} chunks). Relevant chunks \
+                             will be injected into the system prompt.",
+                            rest,
+                            pack.chunks.len()
+                        )));
+                    }
+                    Err(e) => {
+                        self.push_message(ChatMessage::Assistant(e.to_string()));
+                    }
+                }
+            }
+            "rm" if !rest.is_empty() => {
+                if let Some(pos) = self.kb_packs.iter().position(|p| p == rest) {
+                    self.kb_packs.remove(pos);
+                    self.push_message(ChatMessage::Assistant(format!("Detached {}.", rest)));
+                } else {
+                    self.push_message(ChatMessage::Assistant(format!("{} is not attached.", rest)));
+                }
+            }
+            _ => {
+                self.push_message(ChatMessage::Assistant(usage.to_string()));
+            }
+        }
+    }
+
+    /// Handle the /macro command: record input sequences, replay them
+    /// with {{variable}} substitution, or manage what is recorded
+    fn handle_macro(&mut self, arg: Option<String>) {
+        let usage = "Usage: /macro record <name>, /macro stop, \
+                     /macro play <name> [var=value ...], /macro ls, /macro rm <name>.";
+
+        let Some(arg) = arg else {
+            self.show_macros();
+            return;
+        };
+
+        let (action, rest) = match arg.split_once(' ') {
+            Some((action, rest)) => (action, rest.trim()),
+            None => (arg.as_str(), ""),
+        };
+
+        match action {
+            "ls" => self.show_macros(),
+            "record" if !rest.is_empty() => {
+                if let Some((name, _)) = &self.macro_recording {
+                    self.push_message(ChatMessage::Assistant(format!(
+                        "Already recording '{}'. Finish it with /macro stop.", name
+                    )));
+                } else if rest.contains(char::is_whitespace) {
+                    self.push_message(ChatMessage::Assistant(
+                        "Macro names cannot contain spaces.".to_string(),
+                    ));
+                } else {
+                    self.macro_recording = Some((rest.to_string(), Vec::new()));
+                    self.push_message(ChatMessage::Assistant(format!(
+                        "Recording macro '{}'. Every line you enter is captured; \
+                         /macro stop finishes the recording.",
+                        rest
+                    )));
+                }
+            }
+            "stop" => match self.macro_recording.take() {
+                None => {
+                    self.push_message(ChatMessage::Assistant(
+                        "Not recording. Start with /macro record <name>.".to_string(),
+                    ));
+                }
+                Some((name, steps)) if steps.is_empty() => {
+                    self.push_message(ChatMessage::Assistant(format!(
+                        "Nothing was entered; macro '{}' discarded.", name
+                    )));
+                }
+                Some((name, steps)) => {
+                    let count = steps.len();
+                    self.macros.insert(name.clone(), steps);
+                    self.persist_macros();
+                    self.push_message(ChatMessage::Assistant(format!(
+                        "Saved macro '{}' ({} step{}). Replay it with /macro play {}.",
+                        name,
+                        count,
+                        if count == 1 { "" } else { "s" },
+                        name
+                    )));
+                }
+            },
+            "play" if !rest.is_empty() => {
+                let mut parts = rest.split_whitespace();
+                let name = parts.next().unwrap_or_default();
+                let args: Vec<String> = parts.map(str::to_string).collect();
+
+                let Some(steps) = self.macros.get(name).cloned() else {
+                    self.push_message(ChatMessage::Assistant(format!(
+                        "No macro named '{}'. /macro ls lists what is recorded.", name
+                    )));
+                    return;
+                };
+                let vars = match crate::templates::parse_vars(&args) {
+                    Ok(vars) => vars,
+                    Err(e) => {
+                        self.push_message(ChatMessage::Assistant(format!(
+                            "{}. Pass placeholder values as var=value.", e
+                        )));
+                        return;
+                    }
+                };
+
+                // Render and validate every step before queueing any,
+                // so a missing placeholder cannot stop a macro halfway
+                let mut queued = Vec::new();
+                for step in &steps {
+                    let step = match crate::templates::render(step, &vars) {
+                        Ok(step) => step,
+                        Err(e) => {
+                            self.push_message(ChatMessage::Assistant(format!(
+                                "Macro '{}' not played: {}.", name, e
+                            )));
+                            return;
+                        }
+                    };
+                    if step.trim_start().starts_with('/') {
+                        match expand_aliases(&step, &self.aliases) {
+                            Ok(expanded) => {
+                                queued.extend(
+                                    expanded.iter().filter_map(|part| Command::from_input(part)),
+                                );
+                            }
+                            Err(e) => {
+                                self.push_message(ChatMessage::Assistant(format!(
+                                    "Macro '{}' not played: alias error: {}.", name, e
+                                )));
+                                return;
+                            }
+                        }
+                    } else {
+                        queued.push(Command::Send(step));
+                    }
+                }
+                self.pending_commands.extend(queued);
+            }
+            "rm" if !rest.is_empty() => {
+                if self.macros.remove(rest).is_some() {
+                    self.persist_macros();
+                    self.push_message(ChatMessage::Assistant(format!("Removed macro '{}'.", rest)));
+                } else {
+                    self.push_message(ChatMessage::Assistant(format!("No macro named '{}'.", rest)));
+                }
+            }
+            _ => {
+                self.push_message(ChatMessage::Assistant(usage.to_string()));
+            }
+        }
+    }
+
+    /// List the recorded macros with their step counts
+    fn show_macros(&mut self) {
+        if let Some((name, steps)) = &self.macro_recording {
+            let note = format!(
+                "Recording '{}' ({} step{} so far). /macro stop finishes it.",
+                name,
+                steps.len(),
+                if steps.len() == 1 { "" } else { "s" }
+            );
+            self.push_message(ChatMessage::Assistant(note));
+            return;
+        }
+        if self.macros.is_empty() {
+            self.push_message(ChatMessage::Assistant(
+                "No macros recorded. Start one with /macro record <name>, then \
+                 /macro stop to save what you entered."
+                    .to_string(),
+            ));
+            return;
+        }
+
+        let mut names: Vec<&String> = self.macros.keys().collect();
+        names.sort();
+        let mut listing = String::from("Recorded macros:\n");
+        for name in names {
+            let steps = &self.macros[name.as_str()];
+            listing.push_str(&format!(
+                "  {} ({} step{})\n",
+                name,
+                steps.len(),
+                if steps.len() == 1 { "" } else { "s" }
+            ));
+        }
+        listing.push_str("Replay with /macro play <name> [var=value ...].");
+        self.push_message(ChatMessage::Assistant(listing));
+    }
+
+    /// Write the in-memory macro table back to the user config file in
+    /// the background, like `persist_layout` does for layout tweaks
+    fn persist_macros(&self) {
+        let config_manager = self.config_manager.clone();
+        let macros = self.macros.clone();
+        tokio::spawn(async move {
+            if let Err(e) = config_manager.set_macros(macros).await {
+                eprintln!("Failed to save macros: {}", e);
+            }
+        });
+    }
+
+    /// List the attached knowledge packs alongside what is on disk
+    fn show_kb(&mut self) {
+        let available = crate::kb::list().unwrap_or_default();
+        if self.kb_packs.is_empty() && available.is_empty() {
+            self.push_message(ChatMessage::Assistant(
+                "No knowledge packs. Build one with `gos kb create <name> --add <path>`, \
+                 then attach it with /kb use <name>."
+                    .to_string(),
+            ));
+            return;
+        }
+
+        let mut listing = String::new();
+        if self.kb_packs.is_empty() {
+            listing.push_str("No packs attached.\n");
+        } else {
+            listing.push_str("Attached packs:\n");
+            for name in &self.kb_packs {
+                listing.push_str(&format!("  {}\n", name));
+            }
+        }
+        let unattached: Vec<&String> = available
+            .iter()
+            .filter(|name| !self.kb_packs.contains(name))
+            .collect();
+        if !unattached.is_empty() {
+            listing.push_str("Available:\n");
+            for name in unattached {
+                listing.push_str(&format!("  {}\n", name));
+            }
+        }
+        listing.push_str("Attach with /kb use <name>, detach with /kb rm <name>.");
+        self.push_message(ChatMessage::Assistant(listing));
+    }
+
+    /// Handle the /agents command: list configured personas, select
+    /// one or several (comma-separated) to route messages to, or turn
+    /// agents mode off
+    async fn handle_agents(&mut self, arg: Option<String>) {
+        let config = match self.config_manager.load().await {
+            Ok(config) => config,
+            Err(e) => {
+                self.push_message(ChatMessage::Assistant(format!("Failed to load configuration: {}", e)));
+                return;
+            }
+        };
+        let personas = config.personas();
+
+        let Some(arg) = arg else {
+            // No argument: list what is configured and what is active
+            if personas.is_empty() {
+                self.push_message(ChatMessage::Assistant(
+                    "No personas configured. Add a [personas.<name>] table with a \
+                     system prompt (and optional provider/model) to the config file."
+                        .to_string(),
+                ));
+                return;
+            }
+
+            let mut names: Vec<&String> = personas.keys().collect();
+            names.sort();
+            let mut listing = String::from("Configured personas:\n");
+            for name in names {
+                let persona = &personas[name.as_str()];
+                let mut notes = Vec::new();
+                if let Some(description) = &persona.description {
+                    notes.push(description.clone());
+                }
+                if let Some(provider) = &persona.provider {
+                    notes.push(format!("provider {}", provider));
+                }
+                if let Some(model) = &persona.model {
+                    notes.push(format!("model {}", model));
+                }
+                if notes.is_empty() {
+                    listing.push_str(&format!("  {}\n", name));
+                } else {
+                    listing.push_str(&format!("  {} - {}\n", name, notes.join(", ")));
+                }
+            }
+            if self.active_agents.is_empty() {
+                listing.push_str("Select with /agents <name>[,<name>...], or /agents off to leave.");
+            } else {
+                listing.push_str(&format!(
+                    "Currently routing to: {}. /agents off returns to normal chat.",
+                    self.active_agents.join(", ")
+                ));
+            }
+            self.push_message(ChatMessage::Assistant(listing));
+            return;
+        };
+
+        if arg == "off" {
+            if self.active_agents.is_empty() {
+                self.push_message(ChatMessage::Assistant("Agents mode is not active.".to_string()));
+            } else {
+                self.active_agents.clear();
+                self.push_message(ChatMessage::Assistant(
+                    "Agents mode off. Messages go to the default assistant again.".to_string(),
+                ));
+            }
+            return;
+        }
+
+        // Validate every requested name before committing to any, so a
+        // typo does not leave a half-selected panel
+        let requested: Vec<String> = arg
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+        let unknown: Vec<&String> = requested
+            .iter()
+            .filter(|name| !personas.contains_key(name.as_str()))
+            .collect();
+        if !unknown.is_empty() {
+            let mut available: Vec<&str> = personas.keys().map(|name| name.as_str()).collect();
+            available.sort();
+            self.push_message(ChatMessage::Assistant(format!(
+                "Unknown persona(s): {}. Available: {}",
+                unknown.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", "),
+                available.join(", ")
+            )));
+            return;
+        }
+        if requested.is_empty() {
+            self.push_message(ChatMessage::Assistant(
+                "Usage: /agents [<name>[,<name>...]|off]".to_string(),
+            ));
+            return;
+        }
+
+        self.active_agents = requested;
+        self.push_message(ChatMessage::Assistant(format!(
+            "Routing messages to: {}. Each persona answers in turn; /agents off returns to normal chat.",
+            self.active_agents.join(", ")
+        )));
+    }
+
+    /// List the attached context entries with their token estimates
+    fn show_context(&mut self) {
+        if self.context_paths.is_empty() {
+            self.push_message(ChatMessage::Assistant(
+                "No context attached. Attach files with /context add <path>, \
+                 or 'repo' for git metadata."
+                    .to_string(),
+            ));
+            return;
+        }
+
+        let mut listing = String::from("Attached context:\n");
+        let mut total = 0;
+        for path in &self.context_paths {
+            let tokens = crate::usage::estimate_tokens(&crate::context::resolve(path));
+            total += tokens;
+            listing.push_str(&format!("  {} (~{} tokens)\n", path, tokens));
+        }
+        listing.push_str(&format!(
+            "~{} tokens total (budget {})",
+            total,
+            crate::context::CONTEXT_TOKEN_BUDGET
+        ));
+        self.push_message(ChatMessage::Assistant(listing));
+    }
+
+    /// Run a command queued by `handle_input`, if any. Returns true when
+    /// a command executed so the event loop knows to redraw.
+    pub async fn process_pending_command(&mut self) -> bool {
+        if let Some(command) = self.pending_commands.pop_front() {
+            self.handle_command(command).await;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Run a command's I/O on the runtime, keeping a spinner up until
+    /// its events have come back through the command channel
+    fn spawn_effect(&mut self, label: &str, effect: CommandEffect) {
+        self.command_running = Some(label.to_string());
+        let tx = self.command_tx.clone();
+        tokio::spawn(async move {
+            for event in effect.await {
+                let _ = tx.send(event);
+            }
+            let _ = tx.send(CommandEvent::Finished);
+        });
+    }
+
+    /// Fold results from spawned command effects into the app; the
+    /// event loop calls this once per frame, like
+    /// `apply_startup_updates`. Returns true when anything changed so
+    /// the loop knows to redraw.
+    pub fn apply_command_events(&mut self) -> bool {
+        let mut changed = false;
+        while let Ok(event) = self.command_rx.try_recv() {
+            changed = true;
+            match event {
+                CommandEvent::Note(note) => {
+                    self.push_message(ChatMessage::Assistant(note));
+                }
+                CommandEvent::ProviderSwitched { client, provider, connected } => {
+                    self.graph_os_client = Some(*client);
+                    self.current_provider = Some(provider);
+                    self.update(AppEvent::ConnectionChanged(connected));
+                }
+                CommandEvent::ModelSet(model) => {
+                    if let Some(client) = &mut self.graph_os_client {
+                        client.model = Some(model);
+                    }
+                }
+                CommandEvent::ConfigReloaded(config) => {
+                    self.update(AppEvent::ConfigReloaded(config));
+                }
+                CommandEvent::Recalled(blocks) => {
+                    self.recalled = blocks;
+                }
+                CommandEvent::Finished => {
+                    self.command_running = None;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Drain the app event channel into the reducer; the event loop
+    /// calls this once per frame. Returns true when anything changed so
+    /// the loop knows to redraw.
+    pub fn drain_events(&mut self) -> bool {
+        let mut changed = false;
+        while let Ok(event) = self.events_rx.try_recv() {
+            changed |= self.update(event);
+        }
+        changed
+    }
+
+    /// The single reducer: fold one event into the app state. Pure
+    /// state mutation only — anything needing I/O goes through a
+    /// command effect or a spawned task that produces more events.
+    /// Returns true when the change needs a redraw.
+    pub fn update(&mut self, event: AppEvent) -> bool {
+        match event {
+            AppEvent::Key(key) => {
+                if self.handle_input(key).is_some() {
+                    self.submit_requested = true;
+                }
+                true
+            }
+            AppEvent::Paste(text) => {
+                self.handle_paste(&text);
+                true
+            }
+            AppEvent::FocusChanged(focused) => {
+                self.set_focused(focused);
+                false
+            }
+            AppEvent::Resized => true,
+            AppEvent::StreamChunk(chunk) => {
+                // The streaming branch pushed an empty assistant
+                // message for the chunks to accumulate into
+                if let Some(ChatMessage::Assistant(text)) = self.messages.last_mut() {
+                    text.push_str(&chunk);
+                }
+                true
+            }
+            AppEvent::StreamDone(text) => {
+                // The final text replaces the accumulated chunks: the
+                // filter pipeline may have rewritten the response
+                if let Some(ChatMessage::Assistant(last)) = self.messages.last_mut() {
+                    *last = text;
+                }
+                self.stream_active = false;
+                self.notifier.notify(
+                    crate::terminal::Activity::Done,
+                    self.focused.load(std::sync::atomic::Ordering::Relaxed),
+                );
+                true
+            }
+            AppEvent::ConnectionChanged(connected) => {
+                let changed = self.connected != connected;
+                self.connected = connected;
+                if changed {
+                    // A reconnect only clears the title when nothing is
+                    // in flight; a live stream keeps its state
+                    let focused = self.focused.load(std::sync::atomic::Ordering::Relaxed);
+                    if !connected {
+                        self.notifier.notify(crate::terminal::Activity::Disconnected, focused);
+                    } else if !self.stream_active {
+                        self.notifier.notify(crate::terminal::Activity::Idle, focused);
+                    }
+                }
+                changed
+            }
+            AppEvent::ConfigReloaded(config) => {
+                // Pick up config edits made while the chat was open,
+                // then show the refreshed state
+                self.available_providers = config.apis.keys().cloned().collect();
+                self.templates = config.templates();
+                self.prices = config.prices();
+                self.aliases = config.aliases();
+                self.macros = config.macros();
+                let http_options = crate::adapters::HttpClientOptions::from_env()
+                    .merge_endpoint(config.get_endpoint_config("default").as_ref());
+                self.fallback_clients = Self::build_fallback_chain(
+                    &config,
+                    self.current_provider,
+                    self.graph_os_client.as_ref(),
+                    &http_options,
+                );
+                self.show_config();
+                true
+            }
+            AppEvent::LeaseLost { holder_pid } => {
+                if self.read_only != Some(holder_pid) {
+                    self.read_only = Some(holder_pid);
+                    self.push_message(ChatMessage::Assistant(format!(
+                        "Session {} is being edited by another gos process (pid {}). \
+                         This view is read-only; restart with --force to take the session over.",
+                        self.session_id, holder_pid
+                    )));
+                }
+                true
+            }
+            AppEvent::ServerNotice(notification) => {
+                // A newer notice replaces an undismissed older one; the
+                // full payload goes to the transcript only in debug mode
+                if self.debug_mode {
+                    self.push_message(ChatMessage::Assistant(format!(
+                        "[server notification] {}",
+                        notification.summary()
+                    )));
+                }
+                self.server_notice = Some(notification.summary());
+                true
+            }
+            AppEvent::Suspend => {
+                #[cfg(unix)]
+                self.suspend_to_shell();
+                true
+            }
+            AppEvent::Resumed => {
+                // Also covers stops the TUI never saw coming (an
+                // external SIGSTOP cannot be caught): take the
+                // terminal back unconditionally — re-entering twice is
+                // harmless — and repaint
+                #[cfg(unix)]
+                let _ = reclaim_terminal();
+                true
+            }
+            AppEvent::Tick => {
+                self.tick = self.tick.wrapping_add(1);
+                // Only animate when something is actually spinning
+                self.command_running.is_some() || self.stream_active
             }
-        } else {
-            config_info.push_str("\n🔌 No API providers configured in ~/.vibe/.env\n");
         }
-        
-        // Add tips
-        config_info.push_str("\n💡 Tips:\n");
-        config_info.push_str("- Use /provider <name> to switch providers\n");
-        config_info.push_str("- Use /model <name> to change models\n");
-        config_info.push_str("- Use /stream to toggle streaming mode\n");
-        
-        self.push_message(ChatMessage::Assistant(config_info));
     }
 
-    pub fn handle_command(&mut self, command: Command) {
+    /// Take the submit request raised by an Enter key, so the event
+    /// loop can run the async `submit_message`
+    pub fn take_submit_request(&mut self) -> bool {
+        std::mem::take(&mut self.submit_requested)
+    }
+
+    pub async fn handle_command(&mut self, command: Command) {
+        // Queued /share and /run confirmations only survive an immediate repeat
+        let share_confirmed = std::mem::take(&mut self.share_pending);
+        let run_confirmed = self.run_pending.take();
+
         match command {
             Command::Help => {
-                self.push_message(ChatMessage::Assistant(Command::help_text()));
+                // The overlay replaces the old transcript text dump;
+                // its contents come from the keymap registry
+                self.help_open = true;
+                self.help_query.clear();
+                self.help_scroll = 0;
             }
             Command::Exit => {
+                // Hand the write lease back so the next process does not
+                // have to wait out the TTL; best effort on the way out
+                if self.read_only.is_none() {
+                    let manager = self.session_manager.clone();
+                    let session_id = self.session_id;
+                    tokio::spawn(async move {
+                        let _ = manager.release_lease(session_id).await;
+                    });
+                }
+                // A clean exit is not a crash; drop the resume marker
+                crate::crash::clear_active_session();
                 self.exit_requested = true;
             }
             Command::ToggleStreaming => {
@@ -587,42 +4246,341 @@ impl ChatApp {
                 self.push_message(ChatMessage::Assistant(format!("Streaming mode {}.", status)));
             }
             Command::Config => {
-                self.show_config();
+                // Reload from disk so edits made while the chat was
+                // open show up, without blocking the draw loop
+                let config_manager = self.config_manager.clone();
+                self.spawn_effect("Reloading configuration", Box::pin(async move {
+                    match config_manager.load().await {
+                        Ok(config) => vec![CommandEvent::ConfigReloaded(Box::new(config))],
+                        Err(e) => vec![CommandEvent::Note(format!("Failed to load configuration: {}", e))],
+                    }
+                }));
             }
-            Command::Provider(provider) => {
-                // Add a temporary message to indicate request received
-                self.push_message(ChatMessage::Assistant(format!("Provider switch to {} requested. Use /config to check configuration.", provider)));
-                
-                // Convert provider name to enum
-                let provider_enum = match provider.to_lowercase().as_str() {
-                    "openai" => Some(crate::config::ApiProvider::OpenAI),
-                    "anthropic" => Some(crate::config::ApiProvider::Anthropic),
-                    "gemini" => Some(crate::config::ApiProvider::Gemini),
-                    "custom" => Some(crate::config::ApiProvider::Custom),
-                    _ => None,
-                };
-                
-                // We can only set the desired provider, not do the actual switch here
-                // since we can't run async code in this method
-                if let Some(p) = provider_enum {
-                    self.current_provider = Some(p);
+            Command::Fork => {
+                // Build the fork from the in-memory conversation; any
+                // unloaded history prefix is fetched in the background so
+                // the fork carries the whole conversation
+                let fork_id = Uuid::new_v4();
+                let tail = self.session_messages();
+                let offset = self.history_offset;
+                let bookmarks = self.absolute_bookmarks();
+                let parent_id = self.session_id;
+
+                // Persist the fork in the background
+                let session_manager = self.session_manager.clone();
+                tokio::spawn(async move {
+                    let mut messages = if offset > 0 {
+                        match session_manager.get_session_messages(parent_id, 0, offset).await {
+                            Ok(Some((prefix, _))) => prefix,
+                            _ => {
+                                eprintln!("Failed to load history prefix for fork; forking loaded messages only");
+                                Vec::new()
+                            }
+                        }
+                    } else {
+                        Vec::new()
+                    };
+                    messages.extend(tail);
+
+                    let fork = Session {
+                        forked_at: Some(messages.len()),
+                        messages,
+                        parent_id: Some(parent_id),
+                        bookmarks,
+                        ..Session::new(fork_id)
+                    };
+                    if let Err(e) = session_manager.update_session(fork).await {
+                        eprintln!("Failed to save forked session: {}", e);
+                    }
+                });
+
+                self.push_message(ChatMessage::Assistant(format!(
+                    "Forked this conversation into session {}. Resume it with: gos --session {}",
+                    fork_id, fork_id
+                )));
+            }
+            Command::Template(name) => {
+                // Applying a named template overwrites the system
+                // prompt, so keep a restore point of what it replaces
+                if name.is_some() {
+                    self.snapshot_before("template").await;
+                }
+                self.apply_template(name);
+            }
+            Command::Bookmark(index) => {
+                // Default to the most recent message
+                let index = index.unwrap_or_else(|| self.messages.len().saturating_sub(1));
+                if index >= self.messages.len() {
+                    self.push_message(ChatMessage::Assistant(format!(
+                        "No message at index {} to bookmark.", index
+                    )));
+                } else if let Some(pos) = self.bookmarks.iter().position(|&b| b == index) {
+                    // Bookmarking twice removes the bookmark
+                    self.bookmarks.remove(pos);
+                    self.push_message(ChatMessage::Assistant(format!(
+                        "Removed bookmark on message {}.", index
+                    )));
                 } else {
+                    self.bookmarks.push(index);
+                    self.bookmarks.sort_unstable();
+                    self.push_message(ChatMessage::Assistant(format!(
+                        "Bookmarked message {}. Browse bookmarks with /bookmarks.", index
+                    )));
+                }
+            }
+            Command::Bookmarks => {
+                self.show_bookmarks();
+            }
+            Command::Quote(index) => {
+                match index {
+                    None => {
+                        self.push_message(ChatMessage::Assistant(
+                            "Usage: /quote <n> inserts a quoted excerpt of message n \
+                             and threads your next message as a reply to it."
+                                .to_string(),
+                        ));
+                    }
+                    Some(index) if index >= self.messages.len() => {
+                        self.push_message(ChatMessage::Assistant(format!(
+                            "No message at index {} to quote.", index
+                        )));
+                    }
+                    Some(index) => {
+                        self.quote_message(index);
+                    }
+                }
+            }
+            Command::Pin(index) => {
+                self.set_pinned(index, true);
+            }
+            Command::Unpin(index) => {
+                self.set_pinned(index, false);
+            }
+            Command::History => {
+                self.show_history();
+            }
+            Command::Recall(query) => {
+                match query.as_deref() {
+                    None => {
+                        let status = if self.recalled.is_empty() {
+                            "Usage: /recall <query> pulls relevant past exchanges into context."
+                                .to_string()
+                        } else {
+                            format!(
+                                "{} recalled excerpt(s) active. /recall off clears them.",
+                                self.recalled.len()
+                            )
+                        };
+                        self.push_message(ChatMessage::Assistant(status));
+                    }
+                    Some("off") => {
+                        if self.recalled.is_empty() {
+                            self.push_message(ChatMessage::Assistant("Nothing recalled.".to_string()));
+                        } else {
+                            self.recalled.clear();
+                            self.push_message(ChatMessage::Assistant(
+                                "Recalled excerpts cleared.".to_string(),
+                            ));
+                        }
+                    }
+                    Some(query) => {
+                        let config_manager = self.config_manager.clone();
+                        let session_manager = self.session_manager.clone();
+                        let session_id = self.session_id;
+                        self.spawn_effect("Recalling", Box::pin(
+                            Self::recall_effect(config_manager, session_manager, session_id, query.to_string()),
+                        ));
+                    }
+                }
+            }
+            Command::Context(arg) => {
+                self.handle_context(arg);
+            }
+            Command::Kb(arg) => {
+                self.handle_kb(arg);
+            }
+            Command::Macro(arg) => {
+                self.handle_macro(arg);
+            }
+            Command::Send(text) => {
+                // A macro step that is a plain message: submit it as if
+                // the user had typed it
+                self.input = text;
+                self.cursor_position = self.input.len();
+                if let Err(e) = self.submit_message().await {
+                    eprintln!("Failed to send macro message: {}", e);
+                }
+            }
+            Command::Agents(arg) => {
+                self.handle_agents(arg).await;
+            }
+            Command::Schema(arg) => {
+                match arg.as_deref() {
+                    None => {
+                        let status = match &self.response_schema {
+                            Some((path, _)) => format!(
+                                "Responses are constrained to {}. /schema off clears it.", path
+                            ),
+                            None => "No schema active. Usage: /schema <file>, /schema off.".to_string(),
+                        };
+                        self.push_message(ChatMessage::Assistant(status));
+                    }
+                    Some("off") => {
+                        if self.response_schema.take().is_some() {
+                            self.push_message(ChatMessage::Assistant(
+                                "Schema cleared. Responses are free-form again.".to_string(),
+                            ));
+                        } else {
+                            self.push_message(ChatMessage::Assistant("No schema active.".to_string()));
+                        }
+                    }
+                    Some(path) => {
+                        match std::fs::read_to_string(path)
+                            .map_err(|e| e.to_string())
+                            .and_then(|text| {
+                                serde_json::from_str::<serde_json::Value>(&text).map_err(|e| e.to_string())
+                            }) {
+                            Ok(schema) => {
+                                self.response_schema = Some((path.to_string(), schema));
+                                self.push_message(ChatMessage::Assistant(format!(
+                                    "Responses must now conform to {}. Invalid replies are retried \
+                                     with the validation errors fed back; streaming is bypassed \
+                                     while a schema is active.",
+                                    path
+                                )));
+                            }
+                            Err(e) => {
+                                self.push_message(ChatMessage::Assistant(format!(
+                                    "Could not load schema from {}: {}", path, e
+                                )));
+                            }
+                        }
+                    }
+                }
+            }
+            Command::Provider(provider) => {
+                let Some(p) = crate::config::ApiProvider::parse(&provider) else {
                     self.push_message(ChatMessage::Assistant(
                         format!("Unknown provider: '{}'. Available options: openai, anthropic, gemini, custom", provider)
                     ));
+                    return;
+                };
+                if let Err(denial) = self.policy.check_provider(&p.to_string()) {
+                    crate::policy::audit_denial("provider", &denial);
+                    self.push_message(ChatMessage::Assistant(format!("Not switched: {}.", denial.message)));
+                    return;
                 }
+
+                let config_manager = self.config_manager.clone();
+                let fallback_endpoint = self.graph_os_client.as_ref().map(|c| c.endpoint.clone());
+                let rpc_secret = self.graph_os_client.as_ref().and_then(|c| c.rpc_secret.clone());
+                self.spawn_effect("Switching provider", Box::pin(
+                    Self::switch_provider_effect(config_manager, fallback_endpoint, rpc_secret, p, None),
+                ));
             }
             Command::Model(model) => {
-                // Update the model in the current client
-                if let Some(client) = &mut self.graph_os_client {
-                    client.model = Some(model.clone());
-                    self.push_message(ChatMessage::Assistant(format!("Model set to: {}", model)));
-                } else {
+                let client = self.graph_os_client.clone();
+                self.spawn_effect("Validating model", Box::pin(Self::set_model_effect(client, model)));
+            }
+            Command::Share => {
+                if !share_confirmed {
+                    self.share_pending = true;
+                    self.push_message(ChatMessage::Assistant(
+                        "This uploads the transcript (with secrets redacted) to the configured \
+                        share endpoint. Run /share again to confirm.".to_string()
+                    ));
+                    return;
+                }
+                // Persist first so the upload reflects the whole
+                // conversation, then hand the upload to the runtime
+                if let Err(e) = self.save_session().await {
+                    self.push_message(ChatMessage::Assistant(format!("Failed to save session: {}", e)));
+                    return;
+                }
+                let config_manager = self.config_manager.clone();
+                let session_manager = self.session_manager.clone();
+                let session_id = self.session_id;
+                self.spawn_effect("Uploading transcript", Box::pin(
+                    Self::share_transcript_effect(config_manager, session_manager, session_id),
+                ));
+            }
+            Command::Run => {
+                match run_confirmed {
+                    Some(block) => self.run_code_block(block).await,
+                    None => self.prepare_run(),
+                }
+            }
+            Command::Diff(target) => {
+                self.show_diff(target).await;
+            }
+            Command::Paste(action) => {
+                let Some(text) = self.pending_paste.take() else {
+                    self.push_message(ChatMessage::Assistant(
+                        "No pending paste. A paste larger than 8 KiB is held back here \
+                         automatically.".to_string(),
+                    ));
+                    return;
+                };
+                match action.as_deref() {
+                    Some("insert") => {
+                        self.input.insert_str(self.cursor_position, &text);
+                        self.cursor_position += text.len();
+                    }
+                    Some("file") => {
+                        let path = std::env::temp_dir()
+                            .join(format!("gos-paste-{}.txt", Uuid::new_v4()));
+                        match std::fs::write(&path, &text) {
+                            Ok(()) => {
+                                let path = path.display().to_string();
+                                self.context_paths.push(path.clone());
+                                self.push_message(ChatMessage::Assistant(format!(
+                                    "Paste saved to {} and attached as context.", path
+                                )));
+                            }
+                            Err(e) => {
+                                // Keep the paste so the user can retry
+                                self.pending_paste = Some(text);
+                                self.push_message(ChatMessage::Assistant(format!(
+                                    "Failed to save paste: {}", e
+                                )));
+                            }
+                        }
+                    }
+                    _ => {
+                        self.pending_paste = Some(text);
+                        self.push_message(ChatMessage::Assistant(
+                            "Usage: /paste insert|file".to_string(),
+                        ));
+                    }
+                }
+            }
+            Command::Continue => {
+                if self.stream_active {
+                    self.push_message(ChatMessage::Assistant(
+                        "A response is still streaming. Stop it first, then /continue.".to_string(),
+                    ));
+                } else if !self.last_interrupted.swap(false, std::sync::atomic::Ordering::Relaxed) {
                     self.push_message(ChatMessage::Assistant(
-                        "No active API client. Please connect to a provider first.".to_string()
+                        "Nothing to continue: the last response was not stopped early.".to_string(),
                     ));
+                } else {
+                    // Resume as a normal user message so the model picks
+                    // up mid-answer with the full history in front of it
+                    self.input = CONTINUE_PROMPT.to_string();
+                    self.cursor_position = self.input.len();
+                    if let Err(e) = self.submit_message().await {
+                        eprintln!("Failed to send continue request: {}", e);
+                    }
                 }
             }
+            Command::Tokens => {
+                // Toggle; building the report re-reads attached context
+                // files, so it happens here rather than in the renderer
+                self.tokens_report = match self.tokens_report {
+                    Some(_) => None,
+                    None => Some(self.token_report()),
+                };
+            }
             Command::Debug(enabled) => {
                 self.debug_mode = enabled;
                 let status = if enabled { "enabled" } else { "disabled" };
@@ -649,30 +4607,180 @@ impl ChatApp {
                     self.push_message(ChatMessage::Assistant(debug_info));
                 }
             }
+            Command::Layout(mode) => {
+                match mode.as_deref() {
+                    None => {
+                        self.push_message(ChatMessage::Assistant(format!(
+                            "Current layout: {}. Usage: /layout compact|comfortable. \
+                             Ctrl+Up/Ctrl+Down resize the input box.",
+                            self.layout_mode.as_str()
+                        )));
+                    }
+                    Some(name) => match LayoutMode::parse(name) {
+                        Some(mode) => {
+                            self.layout_mode = mode;
+                            self.push_message(ChatMessage::Assistant(format!(
+                                "Layout set to {}.", mode.as_str()
+                            )));
+                            self.persist_layout();
+                        }
+                        None => {
+                            self.push_message(ChatMessage::Assistant(format!(
+                                "Unknown layout '{}'. Usage: /layout compact|comfortable", name
+                            )));
+                        }
+                    },
+                }
+            }
             Command::Unknown(cmd) => {
                 self.push_message(ChatMessage::Assistant(format!("Unknown command: '{}'. Type /help to see available commands.", cmd)));
             }
         }
     }
+
+    /// Write the current layout preferences back to the user config in
+    /// the background. A failed write only logs: the in-memory layout
+    /// still applies for this run.
+    fn persist_layout(&self) {
+        let config_manager = self.config_manager.clone();
+        let layout = crate::config::LayoutConfig {
+            mode: Some(self.layout_mode.as_str().to_string()),
+            input_rows: Some(self.input_extra_rows),
+        };
+        tokio::spawn(async move {
+            if let Err(e) = config_manager.set_layout(layout).await {
+                eprintln!("Failed to save layout preferences: {}", e);
+            }
+        });
+    }
+}
+
+/// Convert API messages to the gRPC chat message format
+/// Copy text to the system clipboard via the OSC 52 escape sequence,
+/// which most modern terminal emulators honor, including over SSH
+fn copy_to_clipboard(text: &str) {
+    use base64::Engine;
+    use std::io::Write;
+
+    let payload = base64::engine::general_purpose::STANDARD.encode(text.as_bytes());
+    let mut stdout = std::io::stdout();
+    let _ = write!(stdout, "\x1b]52;c;{}\x07", payload);
+    let _ = stdout.flush();
+}
+
+fn to_grpc_messages(api_messages: &[ApiMessage]) -> Vec<GrpcChatMessage> {
+    api_messages.iter().map(|msg| {
+        let role = match msg.role {
+            MessageRole::User => "user",
+            MessageRole::Assistant => "assistant",
+            MessageRole::System => "system",
+        };
+        GrpcChatMessage {
+            role: role.to_string(),
+            content: msg.content.as_text(),
+        }
+    }).collect()
+}
+
+/// Estimated prompt tokens across the full conversation sent upstream
+fn prompt_tokens_estimate(api_messages: &[ApiMessage]) -> u64 {
+    api_messages
+        .iter()
+        .map(|msg| usage::estimate_tokens(&msg.content.as_text()))
+        .sum()
+}
+
+/// Record estimated usage for one exchange and bump the running session
+/// cost shown in the status bar
+#[allow(clippy::too_many_arguments)]
+fn record_usage(
+    prices: &std::collections::HashMap<String, ModelPrice>,
+    provider: &str,
+    model: &str,
+    session_id: Uuid,
+    prompt_tokens: u64,
+    response: &str,
+    session_cost: &Arc<std::sync::Mutex<f64>>,
+) {
+    let completion_tokens = usage::estimate_tokens(response);
+    let cost = usage::cost_for(prices, model, prompt_tokens, completion_tokens);
+
+    UsageLog::instance().record(&UsageRecord {
+        timestamp: chrono::Utc::now(),
+        provider: provider.to_string(),
+        model: model.to_string(),
+        session_id: Some(session_id),
+        prompt_tokens,
+        completion_tokens,
+        cost,
+    });
+
+    if let Ok(mut total) = session_cost.lock() {
+        *total += cost;
+    }
+}
+
+/// Stable display color for a persona, derived from its name so each
+/// agent keeps the same color across renders and sessions
+pub fn agent_color(name: &str) -> Color {
+    const PALETTE: [Color; 6] = [
+        Color::Magenta,
+        Color::Cyan,
+        Color::Yellow,
+        Color::LightGreen,
+        Color::LightBlue,
+        Color::LightRed,
+    ];
+    let hash = name
+        .bytes()
+        .fold(0usize, |acc, b| acc.wrapping_mul(31).wrapping_add(b as usize));
+    PALETTE[hash % PALETTE.len()]
 }
 
 pub fn ui(frame: &mut Frame, app: &ChatApp) {
+    let area = frame.area();
+    // Nothing to draw into; widgets and cursor math below assume at
+    // least one cell, so bail instead of indexing into empty chunks
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+
+    // Layout density from /layout: compact trims the commands panel
+    // and caps the input lower, leaving more rows for the transcript
+    let (commands_height, max_input_lines) = match app.layout_mode {
+        LayoutMode::Comfortable => (5, 6),
+        LayoutMode::Compact => (3, 3),
+    };
+
+    // Grow the input box with the buffer up to the mode's cap, plus
+    // any rows the user added with Ctrl+Up
+    let input_height =
+        (app.input.matches('\n').count() as u16 + 1).min(max_input_lines) + app.input_extra_rows + 2;
+
+    // Small terminals drop the commands panel first, then shrink the
+    // input, so the transcript always keeps a few rows instead of the
+    // panels squeezing it out entirely
+    let show_commands =
+        app.show_commands && area.height > commands_height + input_height + 1 + 3;
+    let reserved = 1 + if show_commands { commands_height } else { 0 };
+    let input_height = input_height.min(area.height.saturating_sub(reserved + 3).max(3));
+
     // Adjust layout constraints based on whether we're showing commands
-    let constraints = if app.show_commands {
+    let constraints = if show_commands {
         vec![
             Constraint::Min(0),
-            Constraint::Length(5),  // Command suggestions area
-            Constraint::Length(3),
+            Constraint::Length(commands_height),  // Command suggestions area
+            Constraint::Length(input_height),
             Constraint::Length(1)
         ]
     } else {
         vec![
             Constraint::Min(0),
-            Constraint::Length(3),
+            Constraint::Length(input_height),
             Constraint::Length(1)
         ]
     };
-    
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(constraints)
@@ -680,50 +4788,140 @@ pub fn ui(frame: &mut Frame, app: &ChatApp) {
 
     // Messages area
     let mut messages = Vec::new();
-    for (i, msg) in app.messages.iter().enumerate() {
-        match msg {
-            ChatMessage::User(text) => {
-                messages.push(ListItem::new(format!("You: {}", text)).style(Style::default().fg(Color::Blue)));
-            }
-            ChatMessage::Assistant(text) => {
-                // If this is the last message and streaming is active, add a typing indicator
-                if i == app.messages.len() - 1 && app.stream_active {
-                    let display_text = if text.is_empty() { 
-                        "...".to_string() 
-                    } else {
-                        format!("{}", text)
+    {
+        let meta_map = app.message_meta.lock().unwrap();
+        for (i, msg) in app.messages.iter().enumerate() {
+            // Pinned messages carry a marker so it is visible which
+            // ones always ride along with the history
+            let pin = if meta_map.get(&i).is_some_and(|meta| meta.pinned) {
+                app.style.pin_marker()
+            } else {
+                ""
+            };
+            // Threaded replies carry a reference marker back to the
+            // quoted message; indices from the unloaded prefix keep
+            // their absolute number
+            let reply = meta_map
+                .get(&i)
+                .and_then(|meta| meta.reply_to)
+                .map(|abs| match abs.checked_sub(app.history_offset) {
+                    Some(local) => format!("[re #{}] ", local),
+                    None => format!("[re earlier #{}] ", abs),
+                })
+                .unwrap_or_default();
+            match msg {
+                ChatMessage::User(text) => {
+                    messages.push(ListItem::new(format!("{}You: {}{}", pin, reply, text)).style(app.style.fg(Color::Blue)));
+                }
+                ChatMessage::Assistant(text) => {
+                    // Persona answers are labeled with the persona's
+                    // name and get their own stable color
+                    let agent = meta_map.get(&i).and_then(|meta| meta.agent.clone());
+                    let (label, color) = match &agent {
+                        Some(name) => (name.as_str(), agent_color(name)),
+                        None => ("Assistant", Color::Green),
                     };
-                    messages.push(ListItem::new(format!("Assistant: {}", display_text))
-                        .style(Style::default().fg(Color::Green)));
-                } else {
-                    messages.push(ListItem::new(format!("Assistant: {}", text))
-                        .style(Style::default().fg(Color::Green)));
+                    // If this is the last message and streaming is active, add a typing indicator
+                    if i == app.messages.len() - 1 && app.stream_active {
+                        let display_text = if text.is_empty() {
+                            app.style.streaming_placeholder().to_string()
+                        } else {
+                            text.to_string()
+                        };
+                        // Live stream telemetry rides along with the
+                        // typing indicator
+                        let indicator = app
+                            .telemetry
+                            .lock()
+                            .unwrap()
+                            .as_ref()
+                            .map(|t| format!(" [{}]", t.indicator(std::time::Instant::now())))
+                            .unwrap_or_default();
+                        messages.push(ListItem::new(format!("{}{}: {}{}", pin, label, display_text, indicator))
+                            .style(app.style.fg(color)));
+                    } else {
+                        messages.push(ListItem::new(format!("{}{}: {}", pin, label, text))
+                            .style(app.style.fg(color)));
+                    }
                 }
             }
         }
     }
 
+    // In selection mode the block title shows how the highlighted
+    // message was generated, when that is known
+    let chat_title = app
+        .selected_message
+        .and_then(|index| app.message_meta.lock().unwrap().get(&index).cloned())
+        .filter(|meta| !meta.is_empty())
+        .map(|meta| format!("Chat [{}]", meta.summary()))
+        .unwrap_or_else(|| "Chat".to_string());
+
     let messages_list = List::new(messages)
-        .block(Block::default().borders(Borders::ALL).title("Chat"))
-        .style(Style::default().fg(Color::White))
-        .highlight_style(Style::default().add_modifier(Modifier::ITALIC))
-        .highlight_symbol(">>");
+        .block(Block::default().borders(Borders::ALL).title(chat_title))
+        .style(app.style.fg(Color::White))
+        .highlight_style(app.style.highlight())
+        .highlight_symbol(app.style.highlight_symbol());
 
-    frame.render_widget(messages_list, chunks[0]);
+    if let Some(selected) = app.selected_message {
+        // Selection mode: highlight the chosen message
+        let mut state = ListState::default();
+        state.select(Some(selected));
+        frame.render_stateful_widget(messages_list, chunks[0], &mut state);
+    } else {
+        frame.render_widget(messages_list, chunks[0]);
+    }
+
+    // Server-notice banner over the top transcript line, until Esc
+    // dismisses it or a newer notice replaces it
+    if let Some(notice) = &app.server_notice
+        && chunks[0].height > 0
+    {
+        let banner_area = ratatui::layout::Rect {
+            height: 1,
+            ..chunks[0]
+        };
+        frame.render_widget(Clear, banner_area);
+        let banner = Paragraph::new(format!(
+            "{}{} (Esc dismisses)",
+            app.style.icon("⚠ "),
+            notice
+        ))
+        .style(app.style.fg(Color::Yellow));
+        frame.render_widget(banner, banner_area);
+    }
 
-    // Command suggestions area (shown only when app.show_commands is true)
-    if app.show_commands {
+    // Command suggestions area (dropped on terminals too small for it)
+    if show_commands {
         // Command descriptions for display
-        let commands_with_descriptions = vec![
-            ("/help", "Show this help message"),
+        let commands_with_descriptions = [("/help", "Show this help message"),
             ("/exit", "Exit the application"),
             ("/stream", "Toggle streaming mode"),
             ("/config", "Show current configuration"),
+            ("/fork", "Fork this conversation into a new session"),
+            ("/template", "Apply a prompt template, or list templates"),
+            ("/bookmark", "Bookmark a message (most recent by default)"),
+            ("/bookmarks", "Browse bookmarked messages"),
+            ("/quote", "Quote a message and thread the next one as a reply"),
+            ("/pin", "Always send a message with the history"),
+            ("/unpin", "Remove a pin (most recent by default)"),
+            ("/history", "List this session's restore points"),
+            ("/recall", "Pull relevant past exchanges into context"),
+            ("/context", "Attach workspace context (add/ls/rm)"),
+            ("/kb", "Attach a knowledge pack (use/ls/rm)"),
+            ("/macro", "Record and replay input sequences (record/stop/play)"),
+            ("/run", "Execute the last assistant code block in a sandbox"),
+            ("/diff", "Compare this conversation with another session"),
+            ("/paste", "Insert a held-back large paste (insert/file)"),
+            ("/continue", "Resume an answer you stopped mid-stream"),
+            ("/agents", "Route messages to configured personas"),
+            ("/schema", "Constrain responses to a JSON Schema"),
+            ("/layout", "Switch layout density (compact/comfortable)"),
+            ("/tokens", "Show what the next request will send, with token estimates"),
             ("/provider", "Switch provider (openai, anthropic, gemini, custom)"),
             ("/model", "Set model (e.g., gpt-4o, claude-3-opus, gemini-pro)"),
             ("/debug on", "Enable debug mode"),
-            ("/debug off", "Disable debug mode"),
-        ];
+            ("/debug off", "Disable debug mode")];
         
         // Filter commands based on what the user is typing
         let filtered_commands = app.get_filtered_commands();
@@ -733,6 +4931,10 @@ pub fn ui(frame: &mut Frame, app: &ChatApp) {
             filtered_commands.iter()
                 .map(|cmd| {
                     let cmd_base = cmd.split_whitespace().next().unwrap_or(cmd);
+                    // Aliases are described by what they expand to
+                    if let Some(expansion) = cmd_base.strip_prefix('/').and_then(|name| app.aliases.get(name)) {
+                        return format!("{} - alias for {}", cmd, expansion);
+                    }
                     let description = commands_with_descriptions.iter()
                         .find(|(c, _)| *c == cmd_base)
                         .map(|(_, desc)| *desc)
@@ -758,7 +4960,7 @@ pub fn ui(frame: &mut Frame, app: &ChatApp) {
         
         let commands = Paragraph::new(command_text)
             .block(Block::default().borders(Borders::ALL).title("Commands"))
-            .style(Style::default().fg(Color::Cyan));
+            .style(app.style.fg(Color::Cyan));
         
         frame.render_widget(commands, chunks[1]);
     }
@@ -769,65 +4971,248 @@ pub fn ui(frame: &mut Frame, app: &ChatApp) {
         .title("Input");
     
     let input = Paragraph::new(app.input.as_str())
-        .style(Style::default().fg(Color::Yellow))
+        .style(app.style.fg(Color::Yellow))
+        .wrap(Wrap { trim: false })
         .block(input_block);
     
-    frame.render_widget(input, chunks[if app.show_commands { 2 } else { 1 }]);
-    
+    frame.render_widget(input, chunks[if show_commands { 2 } else { 1 }]);
+
     // Status line - show connection status
-    let status_chunk = if app.show_commands { chunks[3] } else { chunks[2] };
-    let status_text = if app.connected {
+    let status_chunk = if show_commands { chunks[3] } else { chunks[2] };
+    let (status_text, status_color) = if app.connecting {
+        // Background startup probe still running
+        ("Connecting... | Press Ctrl+Q to quit".to_string(), Color::Yellow)
+    } else if let (ChatTransport::Grpc, Some(client)) = (app.transport, &app.grpc_client) {
+        // The managed gRPC channel reports its own live state
+        match client.connection_state() {
+            ConnectionState::Connected => {
+                let endpoint = client.active_endpoint().unwrap_or_else(|| "unknown endpoint".to_string());
+                (format!("Connected to {} (gRPC) | Press Ctrl+Q to quit", endpoint), Color::LightGreen)
+            }
+            ConnectionState::Reconnecting => {
+                ("Reconnecting (gRPC) | Press Ctrl+Q to quit".to_string(), Color::Yellow)
+            }
+            ConnectionState::Disconnected => {
+                ("Not connected (gRPC unavailable) | Press Ctrl+Q to quit".to_string(), Color::Yellow)
+            }
+        }
+    } else if app.connected {
         // Build endpoint string from client information
         let endpoint = if let Some(client) = &app.graph_os_client {
             client.endpoint.clone()
         } else {
             "unknown endpoint".to_string()
         };
-        format!("Connected to {} | Press Ctrl+Q to quit", endpoint)
+        (format!("Connected to {} | Press Ctrl+Q to quit", endpoint), Color::LightGreen)
     } else if app.graph_os_client.is_some() {
-        "Not connected (service unavailable) | Press Ctrl+Q to quit".to_string()
+        ("Not connected (service unavailable) | Press Ctrl+Q to quit".to_string(), Color::Yellow)
     } else {
-        "Local mode (no connection) | Press Ctrl+Q to quit".to_string()
+        ("Local mode (no connection) | Press Ctrl+Q to quit".to_string(), Color::LightRed)
     };
     
+    // Append the running session cost when anything has been spent
+    let session_cost = app.session_cost.lock().map(|c| *c).unwrap_or(0.0);
+    let status_text = if session_cost > 0.0 {
+        format!("{} | ${:.4}", status_text, session_cost)
+    } else {
+        status_text
+    };
+
+    // Latency and token-rate sparklines over recent responses, so a
+    // degrading endpoint shows at a glance
+    let status_text = match app.response_history.lock().ok().and_then(|h| h.status_segment(&app.style)) {
+        Some(segment) => format!("{} | {}", status_text, segment),
+        None => status_text,
+    };
+
+    // A running command effect takes over the front of the status
+    // line with a spinner until its events come back
+    let (status_text, status_color) = match &app.command_running {
+        Some(label) => {
+            (format!("{} {}... | {}", app.style.spinner(app.tick), label, status_text), Color::Yellow)
+        }
+        None => (status_text, status_color),
+    };
+
     let status = Paragraph::new(status_text)
-        .style(Style::default().fg(
-            if app.connected { Color::LightGreen } 
-            else if app.graph_os_client.is_some() { Color::Yellow }
-            else { Color::LightRed }
-        ));
+        .style(app.style.fg(status_color));
     
     frame.render_widget(status, status_chunk);
     
-    // Show cursor at the current input position
-    let input_chunk_idx = if app.show_commands { 2 } else { 1 };
-    let cursor_position = Position::new(
-        chunks[input_chunk_idx].x + app.cursor_position as u16 + 1,
-        chunks[input_chunk_idx].y + 1
-    );
-    frame.set_cursor_position(cursor_position);
+    // Show cursor at the current input position, accounting for
+    // newlines; accessible mode leaves the cursor alone so screen
+    // readers aren't pulled back to the input box every frame
+    if app.style.moves_cursor() {
+        let input_chunk_idx = if show_commands { 2 } else { 1 };
+        let input_chunk = chunks[input_chunk_idx];
+        let inner_width = input_chunk.width.saturating_sub(2).max(1);
+        let inner_height = input_chunk.height.saturating_sub(2).max(1);
+        let (cursor_line, cursor_col) = app.cursor_line_col();
+        let cursor_position = Position::new(
+            input_chunk.x + (cursor_col as u16).min(inner_width) + 1,
+            input_chunk.y + (cursor_line as u16).min(inner_height - 1) + 1
+        );
+        frame.set_cursor_position(cursor_position);
+    }
+
+    // Modal action popup over everything else
+    if app.selected_message.is_some()
+        && let Some(action) = app.selected_action {
+            let area = centered_rect(30, MESSAGE_ACTIONS.len() as u16 + 2, frame.area());
+            frame.render_widget(Clear, area);
+
+            let items: Vec<ListItem> = MESSAGE_ACTIONS.iter().map(|a| ListItem::new(*a)).collect();
+            let popup = List::new(items)
+                .block(Block::default().borders(Borders::ALL).title("Message actions"))
+                .highlight_style(app.style.highlight());
+
+            let mut state = ListState::default();
+            state.select(Some(action));
+            frame.render_stateful_widget(popup, area, &mut state);
+        }
+
+    // Context visualizer from /tokens, over the right half of the
+    // transcript so toggling it never reshuffles the layout chunks
+    if let Some(report) = &app.tokens_report
+        && chunks[0].width > 40
+    {
+        let width = chunks[0].width / 2;
+        let area = Rect {
+            x: chunks[0].x + chunks[0].width - width,
+            width,
+            ..chunks[0]
+        };
+        frame.render_widget(Clear, area);
+        let pane = Paragraph::new(report.join("\n"))
+            .block(Block::default().borders(Borders::ALL).title("Next request — /tokens or Esc closes"))
+            .wrap(Wrap { trim: false })
+            .style(app.style.fg(Color::Magenta));
+        frame.render_widget(pane, area);
+    }
+
+    // Help overlay over everything else, filtered by the search query
+    if app.help_open {
+        let area = centered_rect(
+            frame.area().width.saturating_sub(8).clamp(40, 90),
+            frame.area().height.saturating_sub(4).max(10),
+            frame.area(),
+        );
+        frame.render_widget(Clear, area);
+
+        let matches = crate::keymap::search(&app.help_query);
+        let mut lines: Vec<Line> = vec![
+            Line::from(format!("search: {}_", app.help_query)),
+            Line::default(),
+        ];
+        if matches.is_empty() {
+            lines.push(Line::from("No bindings match"));
+        }
+        let mut category = "";
+        for binding in matches {
+            // Print a header whenever the category changes; the
+            // registry keeps categories adjacent
+            if binding.category != category {
+                category = binding.category;
+                if lines.len() > 2 {
+                    lines.push(Line::default());
+                }
+                lines.push(Line::from(Span::styled(category, app.style.fg(Color::Yellow))));
+            }
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:<20}", binding.keys), app.style.fg(Color::Cyan)),
+                Span::raw(binding.description),
+            ]));
+        }
+
+        let overlay = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Help — type to search, Esc closes"))
+            .wrap(Wrap { trim: false })
+            .scroll((app.help_scroll, 0));
+        frame.render_widget(overlay, area);
+    }
+}
+
+/// Centered fixed-size rectangle for modal popups
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// RAII guard restoring the terminal when dropped, so early returns
+/// and unwinding hand back a usable shell; restoring twice is harmless
+pub struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = restore_terminal();
+    }
 }
 
 pub fn setup_terminal() -> anyhow::Result<Terminal<CrosstermBackend<std::io::Stdout>>> {
+    // From here on a panic would strand the terminal in raw mode; the
+    // crash hook restores it and writes a report instead
+    crate::crash::install_panic_hook();
     let mut stdout = std::io::stdout();
     crossterm::terminal::enable_raw_mode()?;
+    // Bracketed paste turns a multi-line paste into one Paste event
+    // instead of a burst of key events (and premature Enter submits)
     crossterm::execute!(
         stdout,
         crossterm::terminal::EnterAlternateScreen,
-        crossterm::event::EnableMouseCapture
+        crossterm::event::EnableMouseCapture,
+        crossterm::event::EnableBracketedPaste
     )?;
-    
+
+    // Legacy Windows consoles (conhost) ignore the alternate-screen
+    // sequence and render ANSI colors only after VT processing is
+    // enabled, which crossterm does lazily; a resize forces conhost to
+    // repaint with VT processing on instead of leaving stale shell
+    // output at the top of the screen
+    #[cfg(windows)]
+    {
+        let (cols, rows) = crossterm::terminal::size()?;
+        crossterm::execute!(stdout, crossterm::terminal::SetSize(cols, rows))?;
+    }
+
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
 }
 
+/// Take the terminal back after something else used it: the external
+/// editor, or the shell while the process was suspended. Entering the
+/// alternate screen twice is harmless, so callers do not need to track
+/// whether the handoff actually happened.
+pub fn reclaim_terminal() -> anyhow::Result<()> {
+    crossterm::terminal::enable_raw_mode()?;
+    crossterm::execute!(
+        std::io::stdout(),
+        crossterm::terminal::EnterAlternateScreen,
+        crossterm::event::EnableMouseCapture,
+        crossterm::event::EnableBracketedPaste
+    )?;
+    Ok(())
+}
+
 pub fn restore_terminal() -> anyhow::Result<()> {
     crossterm::terminal::disable_raw_mode()?;
     crossterm::execute!(
         std::io::stdout(),
         crossterm::terminal::LeaveAlternateScreen,
-        crossterm::event::DisableMouseCapture
+        crossterm::event::DisableMouseCapture,
+        crossterm::event::DisableBracketedPaste
     )?;
+
+    // conhost does not clear the scrollback when leaving the alternate
+    // screen, so make sure the cursor is visible again explicitly
+    #[cfg(windows)]
+    crossterm::execute!(std::io::stdout(), crossterm::cursor::Show)?;
+
     Ok(())
 }
\ No newline at end of file
@@ -10,6 +10,10 @@ pub struct Cli {
     /// Specify a session ID to resume
     #[arg(short, long)]
     pub session: Option<Uuid>,
+
+    /// Resume the most recently active session, without needing its UUID
+    #[arg(long = "continue", conflicts_with = "session")]
+    pub continue_session: bool,
     
     /// API host
     #[arg(long, default_value = "localhost")]
@@ -34,17 +38,314 @@ pub struct Cli {
     /// Model to use (e.g., gpt-4, claude-3-opus, gemini-pro)
     #[arg(long)]
     pub model: Option<String>,
+    
+    /// Directory to read config files from instead of the platform default
+    #[arg(long)]
+    pub config_dir: Option<std::path::PathBuf>,
+
+    /// Record every API request/response pair to this directory
+    #[arg(long, value_name = "DIR")]
+    pub record: Option<std::path::PathBuf>,
+
+    /// Serve API responses from a recording instead of the network
+    #[arg(long, value_name = "DIR", conflicts_with = "record")]
+    pub replay: Option<std::path::PathBuf>,
+
+    /// Screen-reader friendly output: no colors, emoji or cursor jumps
+    #[arg(long)]
+    pub accessible: bool,
+
+    /// Take the session's write lease even if another gos process holds it
+    #[arg(long)]
+    pub force: bool,
+
+    /// Reach the server through an SSH bastion (ssh://[user@]host[:port]);
+    /// the API and gRPC connections ride local port-forwards
+    #[arg(long, value_name = "SPEC")]
+    pub via: Option<String>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
+    /// Create a new session, optionally seeded from a template
+    New {
+        /// Template name from the `templates` table in the config file
+        #[arg(long)]
+        template: Option<String>,
+
+        /// Template variable as key=value (repeatable)
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+
+        /// Human-readable title for the session
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Tag for grouping sessions (repeatable)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// System prompt, overriding the template's if both are given
+        #[arg(long)]
+        system: Option<String>,
+
+        /// Provider the session should use (openai, anthropic, gemini, custom)
+        #[arg(long)]
+        provider: Option<String>,
+
+        /// Print the created session as JSON instead of text
+        #[arg(long)]
+        json: bool,
+
+        /// Send the initial message one-shot and print the reply
+        #[arg(long)]
+        send: bool,
+
+        /// First user message to seed the conversation with
+        message: Option<String>,
+    },
+    
+    /// Ask a one-shot question and print the reply to stdout
+    Ask {
+        /// JSON Schema file the response must conform to; only the
+        /// validated JSON is printed, for scripting
+        #[arg(long, value_name = "FILE")]
+        schema: Option<std::path::PathBuf>,
+
+        /// System prompt for the request
+        #[arg(long)]
+        system: Option<String>,
+
+        /// Provider to use (defaults to the configured default)
+        #[arg(long)]
+        provider: Option<String>,
+
+        /// Retries after a failed schema validation, with the errors
+        /// fed back to the model
+        #[arg(long, default_value_t = 2)]
+        retries: usize,
+
+        /// The question to ask
+        prompt: String,
+    },
+
+    /// Run many one-shot prompts from a file and write structured
+    /// results, one JSON object per prompt
+    Batch {
+        /// File with one prompt per line; blank lines and lines
+        /// starting with # are skipped
+        file: std::path::PathBuf,
+
+        /// How many prompts are in flight at once
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+
+        /// Write results as JSON Lines to this file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        output: Option<std::path::PathBuf>,
+
+        /// System prompt shared by every request
+        #[arg(long)]
+        system: Option<String>,
+
+        /// Template whose system prompt is shared by every request
+        #[arg(long, conflicts_with = "system")]
+        template: Option<String>,
+
+        /// Provider to use (defaults to the configured default)
+        #[arg(long)]
+        provider: Option<String>,
+
+        /// Retries per prompt after a failed request, with backoff
+        #[arg(long, default_value_t = 2)]
+        retries: usize,
+    },
+
+    /// Pipe stdin through a prompt template and write only the model
+    /// output to stdout, for shell pipelines
+    Pipe {
+        /// Template name from the `templates` table; its prompt may
+        /// place stdin with {{input}} (appended when absent)
+        template: String,
+
+        /// Extra template variable as key=value (repeatable)
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+
+        /// Provider to use (defaults to the configured default)
+        #[arg(long)]
+        provider: Option<String>,
+
+        /// Buffer the whole response instead of streaming it through
+        #[arg(long)]
+        no_stream: bool,
+    },
+
+    /// Run an eval suite against one or more providers and report
+    /// pass rates and latency per model
+    Eval {
+        /// Suite file (YAML): prompts with expected-answer checks
+        #[arg(long, value_name = "FILE")]
+        suite: std::path::PathBuf,
+
+        /// Comma-separated providers to compare (defaults to the
+        /// configured default)
+        #[arg(long)]
+        providers: Option<String>,
+
+        /// Provider answering judge checks (defaults to the configured
+        /// default)
+        #[arg(long)]
+        judge: Option<String>,
+
+        /// Emit the report as JSON instead of markdown
+        #[arg(long)]
+        json: bool,
+
+        /// Write the report to this file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        output: Option<std::path::PathBuf>,
+    },
+
     /// List all available sessions
-    List,
+    List {
+        /// Order: last-active (newest first), created (oldest first)
+        /// or title
+        #[arg(long, default_value = "last-active")]
+        sort: String,
+
+        /// Page size (0 shows everything)
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+
+        /// Entries to skip, for paging through long listings
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+    },
+
+    /// Search past conversations for matching messages
+    Search {
+        /// Match by meaning instead of by substring, embedding through
+        /// the configured provider when the endpoint sets an
+        /// embedding_model and hashed local features otherwise
+        #[arg(long)]
+        semantic: bool,
+
+        /// Maximum number of matches to show
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+
+        /// The text to search for
+        query: String,
+    },
     
     /// Show details for a specific session
     Show {
         /// The session ID to show
         id: Uuid,
+
+        /// Only show messages with this role (user or assistant)
+        #[arg(long)]
+        role: Option<String>,
+
+        /// Only show the last N messages, after the other filters
+        #[arg(long)]
+        last: Option<usize>,
+
+        /// Only show messages in this index range, e.g. 10..20
+        #[arg(long)]
+        range: Option<String>,
+
+        /// Only show messages matching this regular expression
+        #[arg(long)]
+        grep: Option<String>,
+
+        /// Output format (text, json, md)
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+
+    /// Manage knowledge packs: named document collections attachable
+    /// to chat sessions with /kb use
+    Kb {
+        #[command(subcommand)]
+        action: KbCommands,
+    },
+
+    /// Live-tail a session in a read-only view as another process
+    /// appends to it
+    Watch {
+        /// The session ID to spectate
+        id: Uuid,
+    },
+
+    /// Compare two sessions side by side, aligned on shared messages
+    Diff {
+        /// Session shown in the left column
+        a: Uuid,
+
+        /// Session shown in the right column
+        b: Uuid,
+
+        /// Total output width in columns
+        #[arg(long, default_value_t = 100)]
+        width: usize,
+    },
+
+    /// Upload sessions to the archive backend and remove them locally
+    Archive {
+        /// The session ID to archive
+        session_id: Option<Uuid>,
+
+        /// Archive every session (combine with --older-than)
+        #[arg(long)]
+        all: bool,
+
+        /// Only archive sessions idle longer than this, e.g. 90d or 12h
+        #[arg(long, value_name = "AGE")]
+        older_than: Option<String>,
+    },
+
+    /// Pull an archived session back into the sessions directory
+    Restore {
+        /// The session ID to restore
+        session_id: Uuid,
+    },
+
+    /// Roll a session back to an earlier snapshot
+    Rollback {
+        /// The session ID to roll back
+        session_id: Uuid,
+
+        /// Snapshot name as shown in the listing; omit to list the
+        /// available snapshots
+        #[arg(long, value_name = "SNAPSHOT")]
+        to: Option<String>,
+    },
+
+    /// Export sessions as fine-tuning training data
+    Export {
+        /// Output format; currently only finetune-jsonl
+        #[arg(long, default_value = "finetune-jsonl")]
+        format: String,
+
+        /// Only export sessions matching, e.g. tag=training (repeatable)
+        #[arg(long = "filter", value_name = "KEY=VALUE")]
+        filters: Vec<String>,
+
+        /// Weight every assistant turn except the last at 0
+        #[arg(long)]
+        train_last_only: bool,
+    },
+
+    /// Fork a session into a new branch
+    Fork {
+        /// The session ID to fork from
+        id: Uuid,
+
+        /// Copy messages up to this index (defaults to the whole conversation)
+        #[arg(long)]
+        at: Option<usize>,
     },
     
     /// Configure authentication and API settings
@@ -52,12 +353,117 @@ pub enum Commands {
         #[command(subcommand)]
         action: ConfigCommands,
     },
+
+    /// Call gRPC services described by user-supplied proto files
+    Grpc {
+        #[command(subcommand)]
+        action: GrpcCommands,
+    },
+
+    /// Run server-side GraphOS agents and pipelines
+    Task {
+        #[command(subcommand)]
+        action: TaskCommands,
+    },
+
+    /// Sign in to an endpoint's identity provider (OAuth2 device flow)
+    Login {
+        /// Endpoint name to sign in to
+        #[arg(long, default_value = "default")]
+        endpoint: String,
+    },
+
+    /// Upload a session transcript to the configured share endpoint
+    Share {
+        /// The session ID to share
+        id: Uuid,
+
+        /// How long the link should stay up (e.g. 1h, 24h, 7d)
+        #[arg(long)]
+        expire: Option<String>,
+    },
+
+    /// Report accumulated token usage and cost
+    Usage {
+        /// Group by "provider", "model" or "session"
+        #[arg(long, default_value = "provider")]
+        by: String,
+        
+        /// Only include records newer than this (e.g. 30m, 1h, 7d)
+        #[arg(long)]
+        since: Option<String>,
+    },
+    
+    /// Inspect the audit log of API calls
+    Audit {
+        #[command(subcommand)]
+        action: AuditCommands,
+    },
+    
+    /// Manage stored session files
+    Sessions {
+        #[command(subcommand)]
+        action: SessionsCommands,
+    },
+    
+    /// Manage the background session listener daemon
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonCommands,
+    },
+    
+    /// Run a local JSON-RPC echo server for development and testing
+    Serve {
+        /// Port to listen on (0 picks a free port)
+        #[arg(long, default_value_t = 4000)]
+        port: u16,
+
+        /// File of scripted responses, one per line, cycled through
+        /// instead of echoing the user message
+        #[arg(long)]
+        script: Option<std::path::PathBuf>,
+
+        /// Require HMAC request signatures made with this key id
+        #[arg(long, requires = "signing_key")]
+        signing_key_id: Option<String>,
+
+        /// Shared key the signatures are verified with
+        #[arg(long, requires = "signing_key_id")]
+        signing_key: Option<String>,
+    },
     
     /// System information commands
     SystemInfo {
         #[command(subcommand)]
         action: Option<SystemInfoCommands>,
     },
+
+    /// Check server connectivity and per-service health
+    Status,
+
+    /// Load-test a GraphOS server deployment
+    Bench {
+        #[command(subcommand)]
+        action: BenchCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BenchCommands {
+    /// Fire concurrent gRPC requests and report latency percentiles
+    Grpc {
+        /// RPC to exercise: get (GetSystemInfo) or list (ListSystemInfo)
+        #[arg(long, default_value = "get")]
+        method: String,
+
+        /// Number of concurrent workers
+        #[arg(short, long, default_value_t = 8)]
+        concurrency: usize,
+
+        /// How long to run, in seconds
+        #[arg(short, long, default_value_t = 10)]
+        duration: u64,
+    },
 }
 
 #[derive(Subcommand)]
@@ -95,14 +501,192 @@ pub enum ConfigCommands {
         /// Use TLS for the connection
         #[arg(long)]
         use_tls: bool,
-        
+
+        /// Extra header sent with every request, as KEY=VALUE (repeatable)
+        #[arg(long = "header", value_name = "KEY=VALUE")]
+        headers: Vec<String>,
+
+        /// Query parameter appended to the URL, as KEY=VALUE (repeatable)
+        #[arg(long = "query-param", value_name = "KEY=VALUE")]
+        query_params: Vec<String>,
+
+        /// HTTP version: auto (HTTP/2 with HTTP/1.1 fallback), http1
+        /// or http2
+        #[arg(long)]
+        http_version: Option<String>,
+
+        /// Key id for HMAC request signing (requires --signing-key)
+        #[arg(long, requires = "signing_key")]
+        signing_key_id: Option<String>,
+
+        /// Shared key for HMAC-SHA256 request signing
+        #[arg(long, requires = "signing_key_id")]
+        signing_key: Option<String>,
+
         /// Format for the config file (json, yaml, toml)
         #[arg(short, long, default_value = "toml")]
         format: String,
     },
-    
+
+    /// Encrypt secret and token values in the config file as enc: blobs
+    Encrypt {
+        /// Format for the config file (json, yaml, toml)
+        #[arg(short, long, default_value = "toml")]
+        format: String,
+    },
+
+    /// Decrypt enc: blobs in the config file back to plaintext
+    Decrypt {
+        /// Format for the config file (json, yaml, toml)
+        #[arg(short, long, default_value = "toml")]
+        format: String,
+    },
+
     /// Show the current configuration
-    Show,
+    Show {
+        /// Also show which layer (default, system file, user file,
+        /// env, flag) supplied each effective value
+        #[arg(long)]
+        provenance: bool,
+    },
+    
+    /// Validate a configuration file against the known schema
+    Validate {
+        /// Config file to validate (defaults to the first one found)
+        #[arg(short, long)]
+        file: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum GrpcCommands {
+    /// Invoke a unary method dynamically, without recompiling the CLI
+    Call {
+        /// Method to call, as <service>/<method> (the service may be
+        /// fully qualified, e.g. graph_os.SystemInfoService/GetSystemInfo)
+        path: String,
+
+        /// Proto file describing the service, compiled at runtime
+        #[arg(long)]
+        proto: std::path::PathBuf,
+
+        /// Request message as JSON (defaults to an empty message)
+        #[arg(long, default_value = "{}")]
+        data: String,
+    },
+
+    /// Expose the gRPC services over a local REST/JSON HTTP server,
+    /// so curl and scripts can consume GraphOS data without gRPC tooling
+    Proxy {
+        /// Address to listen on; local by default since the bridge
+        /// forwards requests with the CLI's own credentials
+        #[arg(long, default_value = "127.0.0.1")]
+        listen: String,
+
+        /// Port to listen on (0 picks a free port)
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TaskCommands {
+    /// Trigger a task and print its run id
+    Run {
+        /// Task name registered on the server
+        name: String,
+
+        /// JSON input passed to the task
+        #[arg(long, value_name = "JSON")]
+        input: Option<String>,
+
+        /// Stream logs and wait for completion; the exit code follows
+        /// the run's final state
+        #[arg(long)]
+        follow: bool,
+    },
+
+    /// Show a run's current status
+    Status {
+        /// Run id returned by `gos task run`
+        id: String,
+    },
+
+    /// Print a run's logs
+    Logs {
+        /// Run id returned by `gos task run`
+        id: String,
+
+        /// Keep streaming until the run completes
+        #[arg(long)]
+        follow: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DaemonCommands {
+    /// Start a detached session listener daemon
+    Start,
+    /// Stop the running daemon
+    Stop,
+    /// Report whether the daemon is running
+    Status,
+    /// Run the listener in the foreground (internal; used by start)
+    #[command(hide = true)]
+    Run,
+}
+
+#[derive(Subcommand)]
+pub enum SessionsCommands {
+    /// Encrypt all plaintext session files in place
+    EncryptAll,
+
+    /// Find near-duplicate sessions (one conversation a prefix of
+    /// another, as left behind by fallback saves) and merge them
+    Dedupe {
+        /// Merge without asking for confirmation
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum KbCommands {
+    /// Build a pack from files and directories, chunking and embedding
+    /// the documents
+    Create {
+        /// Pack name (letters, digits, '-' and '_')
+        name: String,
+
+        /// File or directory to include (repeatable; directories
+        /// recurse)
+        #[arg(long = "add", value_name = "PATH", required = true)]
+        add: Vec<std::path::PathBuf>,
+
+        /// Skip embeddings; chunks inject in document order instead of
+        /// by similarity to the conversation
+        #[arg(long)]
+        no_embed: bool,
+    },
+
+    /// List the packs on disk with their sizes
+    List,
+
+    /// Delete a pack; sessions referencing it just stop injecting it
+    Remove {
+        /// Pack name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AuditCommands {
+    /// Show recorded API calls
+    Show {
+        /// Only show records newer than this (e.g. 30m, 1h, 2d)
+        #[arg(long)]
+        since: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -119,5 +703,78 @@ pub enum SystemInfoCommands {
         /// Get records since this Unix timestamp
         #[arg(short, long)]
         since: Option<i64>,
+
+        /// Comma-separated columns to show (e.g. timestamp,hostname,load1)
+        #[arg(long)]
+        columns: Option<String>,
+
+        /// Column to sort by; prefix with '-' for descending
+        #[arg(long)]
+        sort: Option<String>,
+
+        /// Output format: table, csv or json
+        #[arg(long, default_value = "table")]
+        format: String,
+
+        /// Append min/max/avg aggregates over the selected columns
+        #[arg(long)]
+        aggregate: bool,
+    },
+
+    /// Query several GraphOS servers and compare them side by side
+    Fleet {
+        /// Comma-separated gRPC endpoints to query; defaults to every
+        /// endpoint in the config file
+        #[arg(long)]
+        endpoints: Option<String>,
+
+        /// Column to sort by; prefix with '-' for descending.
+        /// Unreachable hosts always sink to the bottom.
+        #[arg(long, default_value = "-load1")]
+        sort: String,
+    },
+
+    /// Export historical system information for a monitoring stack
+    Export {
+        /// Output format: prometheus (exposition format) or influx
+        /// (line protocol)
+        #[arg(long, default_value = "prometheus")]
+        format: String,
+
+        /// Maximum number of records to export
+        #[arg(short, long)]
+        limit: Option<i32>,
+
+        /// Export records since this Unix timestamp
+        #[arg(short, long)]
+        since: Option<i64>,
+
+        /// Sample timestamp unit: seconds, millis or nanos
+        /// (default: millis for prometheus, nanos for influx)
+        #[arg(long)]
+        timestamp_unit: Option<String>,
+    },
+
+    /// Poll GetSystemInfo on an interval and append the results to a
+    /// local rotated recording, for servers that keep no history
+    Record {
+        /// Poll interval, e.g. 30s, 5m
+        #[arg(long, default_value = "60s")]
+        interval: String,
+
+        /// Stop after this many samples (default: run until Ctrl+C)
+        #[arg(long)]
+        count: Option<u64>,
+    },
+
+    /// Render min/max/avg and sparkline trends from the local recording
+    Report {
+        /// Window to report over, e.g. 90m, 24h, 7d
+        #[arg(long, default_value = "24h")]
+        since: String,
+
+        /// Comma-separated series to chart (e.g. load1,mem_used)
+        #[arg(long)]
+        columns: Option<String>,
     },
 }
\ No newline at end of file
@@ -0,0 +1,661 @@
+//! A small, dependency-free gzip codec (RFC 1951/1952) used for
+//! transparent JSON-RPC payload compression, plus a thin wrapper over
+//! the zstd crate so zstd-encoded responses decode too.
+//!
+//! The compressor emits a single fixed-Huffman DEFLATE block with a
+//! greedy LZ77 matcher, which compresses JSON conversations well
+//! without the table overhead of dynamic Huffman coding. The
+//! decompressor handles all three block types, so responses from any
+//! standard gzip implementation decode correctly. [`GzipStream`]
+//! decodes incrementally for streaming responses. [`Encoding`] is the
+//! dispatch point the HTTP client negotiates through.
+
+/// Request bodies smaller than this are sent uncompressed; at this
+/// size the gzip framing and CPU cost stop paying for themselves
+pub const DEFAULT_COMPRESS_THRESHOLD: usize = 4 * 1024;
+
+// Shared DEFLATE tables: base values and extra-bit counts for the
+// length codes 257-285 and the distance codes 0-29
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+// ---------------------------------------------------------------------------
+// CRC32 (the gzip trailer checksum)
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+// ---------------------------------------------------------------------------
+// Compression
+
+/// LSB-first bit packer, with Huffman codes written MSB-of-code first
+/// as DEFLATE requires
+struct BitWriter {
+    out: Vec<u8>,
+    current: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        BitWriter { out: Vec::new(), current: 0, bits: 0 }
+    }
+
+    fn write_bits(&mut self, value: u32, count: u32) {
+        self.current |= value << self.bits;
+        self.bits += count;
+        while self.bits >= 8 {
+            self.out.push((self.current & 0xFF) as u8);
+            self.current >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn write_code(&mut self, code: u32, len: u32) {
+        // Reverse so the code's most significant bit goes out first
+        let mut reversed = 0u32;
+        for i in 0..len {
+            reversed |= ((code >> i) & 1) << (len - 1 - i);
+        }
+        self.write_bits(reversed, len);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.out.push((self.current & 0xFF) as u8);
+        }
+        self.out
+    }
+}
+
+/// Emit a literal/length symbol with the fixed Huffman code
+fn write_fixed_litlen(writer: &mut BitWriter, symbol: u32) {
+    match symbol {
+        0..=143 => writer.write_code(0x30 + symbol, 8),
+        144..=255 => writer.write_code(0x190 + symbol - 144, 9),
+        256..=279 => writer.write_code(symbol - 256, 7),
+        _ => writer.write_code(0xC0 + symbol - 280, 8),
+    }
+}
+
+/// Symbol and extra bits for a match length
+fn length_symbol(length: usize) -> (u32, u32, u32) {
+    let mut index = LENGTH_BASE.len() - 1;
+    for (i, &base) in LENGTH_BASE.iter().enumerate() {
+        if (base as usize) > length {
+            index = i - 1;
+            break;
+        }
+    }
+    let extra = length - LENGTH_BASE[index] as usize;
+    (257 + index as u32, extra as u32, LENGTH_EXTRA[index] as u32)
+}
+
+/// Symbol and extra bits for a match distance
+fn distance_symbol(distance: usize) -> (u32, u32, u32) {
+    let mut index = DIST_BASE.len() - 1;
+    for (i, &base) in DIST_BASE.iter().enumerate() {
+        if (base as usize) > distance {
+            index = i - 1;
+            break;
+        }
+    }
+    let extra = distance - DIST_BASE[index] as usize;
+    (index as u32, extra as u32, DIST_EXTRA[index] as u32)
+}
+
+const WINDOW_SIZE: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const HASH_BITS: usize = 15;
+
+fn hash3(data: &[u8], pos: usize) -> usize {
+    let v = (data[pos] as usize) | ((data[pos + 1] as usize) << 8) | ((data[pos + 2] as usize) << 16);
+    (v.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) & ((1 << HASH_BITS) - 1)
+}
+
+/// DEFLATE a buffer as one final fixed-Huffman block, using a greedy
+/// single-candidate hash matcher
+fn deflate_fixed(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    writer.write_bits(1, 1); // BFINAL
+    writer.write_bits(1, 2); // BTYPE 01: fixed Huffman
+
+    // head[hash] holds position + 1 of the most recent occurrence, so
+    // zero means empty
+    let mut head = vec![0usize; 1 << HASH_BITS];
+    let mut pos = 0;
+
+    while pos < data.len() {
+        let mut match_len = 0;
+        let mut match_dist = 0;
+
+        if pos + MIN_MATCH <= data.len() {
+            let hash = hash3(data, pos);
+            let candidate = head[hash];
+            head[hash] = pos + 1;
+
+            if candidate > 0 {
+                let candidate = candidate - 1;
+                let distance = pos - candidate;
+                if distance > 0 && distance <= WINDOW_SIZE {
+                    let limit = (data.len() - pos).min(MAX_MATCH);
+                    let mut len = 0;
+                    while len < limit && data[candidate + len] == data[pos + len] {
+                        len += 1;
+                    }
+                    if len >= MIN_MATCH {
+                        match_len = len;
+                        match_dist = distance;
+                    }
+                }
+            }
+        }
+
+        if match_len > 0 {
+            let (symbol, extra, extra_bits) = length_symbol(match_len);
+            write_fixed_litlen(&mut writer, symbol);
+            if extra_bits > 0 {
+                writer.write_bits(extra, extra_bits);
+            }
+            let (symbol, extra, extra_bits) = distance_symbol(match_dist);
+            writer.write_code(symbol, 5);
+            if extra_bits > 0 {
+                writer.write_bits(extra, extra_bits);
+            }
+            // Index the skipped positions so later matches can find them
+            for i in pos + 1..(pos + match_len).min(data.len().saturating_sub(MIN_MATCH - 1)) {
+                head[hash3(data, i)] = i + 1;
+            }
+            pos += match_len;
+        } else {
+            write_fixed_litlen(&mut writer, data[pos] as u32);
+            pos += 1;
+        }
+    }
+
+    write_fixed_litlen(&mut writer, 256); // end of block
+    writer.finish()
+}
+
+/// Compress a buffer into a complete gzip member
+pub fn gzip_compress(data: &[u8]) -> Vec<u8> {
+    // Minimal header: magic, DEFLATE, no flags, no mtime, unknown OS
+    let mut out = vec![0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF];
+    out.extend_from_slice(&deflate_fixed(data));
+    out.extend_from_slice(&crc32(data).to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Decompression
+
+/// LSB-first bit reader; `None` signals the input ran out, which the
+/// streaming decoder treats as "wait for more bytes"
+struct BitReader<'a> {
+    data: &'a [u8],
+    byte: usize,
+    bit: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        BitReader { data, byte: 0, bit: 0 }
+    }
+
+    fn take_bit(&mut self) -> Option<u32> {
+        let value = (*self.data.get(self.byte)? >> self.bit) & 1;
+        self.bit += 1;
+        if self.bit == 8 {
+            self.bit = 0;
+            self.byte += 1;
+        }
+        Some(value as u32)
+    }
+
+    fn take_bits(&mut self, count: u32) -> Option<u32> {
+        let mut value = 0;
+        for i in 0..count {
+            value |= self.take_bit()? << i;
+        }
+        Some(value)
+    }
+
+    fn align_to_byte(&mut self) {
+        if self.bit != 0 {
+            self.bit = 0;
+            self.byte += 1;
+        }
+    }
+
+    fn take_bytes(&mut self, count: usize) -> Option<&'a [u8]> {
+        let slice = self.data.get(self.byte..self.byte + count)?;
+        self.byte += count;
+        Some(slice)
+    }
+}
+
+/// Canonical Huffman decoding table built from code lengths
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn build(lengths: &[u8]) -> Result<Huffman, String> {
+        let mut counts = [0u16; 16];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+
+        // Symbols sorted by (length, symbol), the canonical order
+        let mut offsets = [0u16; 16];
+        for len in 1..16 {
+            offsets[len] = offsets[len - 1] + counts[len - 1];
+        }
+        let mut symbols = vec![0u16; lengths.iter().filter(|&&l| l > 0).count()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len > 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+        Ok(Huffman { counts, symbols })
+    }
+
+    /// Decode one symbol. `Ok(None)` means the input ran out mid-code
+    /// and decoding should resume when more bytes arrive; a code that
+    /// matches nothing in the table is corruption and errors, so a
+    /// malformed stream cannot masquerade as a stalled one.
+    fn decode(&self, reader: &mut BitReader) -> Result<Option<u16>, String> {
+        let mut code = 0i32;
+        let mut first = 0i32;
+        let mut index = 0i32;
+        for len in 1..16 {
+            let Some(bit) = reader.take_bit() else { return Ok(None) };
+            code |= bit as i32;
+            let count = self.counts[len] as i32;
+            if code - first < count {
+                return Ok(Some(self.symbols[(index + code - first) as usize]));
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("invalid Huffman code".to_string())
+    }
+}
+
+fn fixed_litlen_table() -> Huffman {
+    let mut lengths = [0u8; 288];
+    lengths[0..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..288].fill(8);
+    Huffman::build(&lengths).expect("fixed table is well-formed")
+}
+
+fn fixed_dist_table() -> Huffman {
+    Huffman::build(&[5u8; 30]).expect("fixed table is well-formed")
+}
+
+/// Order the code-length code lengths are transmitted in
+const CLEN_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+/// Read the dynamic Huffman tables at the start of a BTYPE=10 block.
+/// `Ok(None)` means the input ran out mid-header.
+fn read_dynamic_tables(reader: &mut BitReader) -> Result<Option<(Huffman, Huffman)>, String> {
+    let Some(hlit) = reader.take_bits(5) else { return Ok(None) };
+    let Some(hdist) = reader.take_bits(5) else { return Ok(None) };
+    let Some(hclen) = reader.take_bits(4) else { return Ok(None) };
+    let litlen_count = hlit as usize + 257;
+    let dist_count = hdist as usize + 1;
+
+    let mut clen_lengths = [0u8; 19];
+    for &index in CLEN_ORDER.iter().take(hclen as usize + 4) {
+        let Some(len) = reader.take_bits(3) else { return Ok(None) };
+        clen_lengths[index] = len as u8;
+    }
+    let clen_table = Huffman::build(&clen_lengths)?;
+
+    // The literal/length and distance code lengths share one encoding,
+    // including run-length symbols that may cross the boundary
+    let mut lengths = Vec::with_capacity(litlen_count + dist_count);
+    while lengths.len() < litlen_count + dist_count {
+        let Some(symbol) = clen_table.decode(reader)? else { return Ok(None) };
+        match symbol {
+            0..=15 => lengths.push(symbol as u8),
+            16 => {
+                let &previous = lengths.last().ok_or("length repeat with no previous code")?;
+                let Some(extra) = reader.take_bits(2) else { return Ok(None) };
+                for _ in 0..extra + 3 {
+                    lengths.push(previous);
+                }
+            }
+            17 => {
+                let Some(extra) = reader.take_bits(3) else { return Ok(None) };
+                lengths.resize(lengths.len() + extra as usize + 3, 0);
+            }
+            18 => {
+                let Some(extra) = reader.take_bits(7) else { return Ok(None) };
+                lengths.resize(lengths.len() + extra as usize + 11, 0);
+            }
+            _ => return Err(format!("invalid code-length symbol {}", symbol)),
+        }
+    }
+    if lengths.len() > litlen_count + dist_count {
+        return Err("code lengths overflow the declared counts".to_string());
+    }
+
+    let litlen = Huffman::build(&lengths[..litlen_count])?;
+    let dist = Huffman::build(&lengths[litlen_count..])?;
+    Ok(Some((litlen, dist)))
+}
+
+/// Decode one compressed block's symbols into `out`. `Ok(false)` means
+/// the input ran out before the end-of-block symbol.
+fn inflate_block(
+    reader: &mut BitReader,
+    litlen: &Huffman,
+    dist: &Huffman,
+    out: &mut Vec<u8>,
+) -> Result<bool, String> {
+    loop {
+        let Some(symbol) = litlen.decode(reader)? else { return Ok(false) };
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(true),
+            257..=285 => {
+                let index = symbol as usize - 257;
+                let Some(extra) = reader.take_bits(LENGTH_EXTRA[index] as u32) else {
+                    return Ok(false);
+                };
+                let length = LENGTH_BASE[index] as usize + extra as usize;
+
+                let Some(dist_symbol) = dist.decode(reader)? else { return Ok(false) };
+                let dist_index = dist_symbol as usize;
+                if dist_index >= DIST_BASE.len() {
+                    return Err(format!("invalid distance symbol {}", dist_symbol));
+                }
+                let Some(extra) = reader.take_bits(DIST_EXTRA[dist_index] as u32) else {
+                    return Ok(false);
+                };
+                let distance = DIST_BASE[dist_index] as usize + extra as usize;
+                if distance > out.len() {
+                    return Err("distance reaches before the start of output".to_string());
+                }
+
+                // Byte-at-a-time back copy; the match may overlap itself
+                let start = out.len() - distance;
+                for i in 0..length {
+                    let byte = out[start + i];
+                    out.push(byte);
+                }
+            }
+            _ => return Err(format!("invalid literal/length symbol {}", symbol)),
+        }
+    }
+}
+
+/// Inflate a DEFLATE stream. Returns the decoded bytes and whether the
+/// final block completed; `false` means the input was truncated and
+/// decoding should be retried when more bytes arrive.
+fn inflate(data: &[u8]) -> Result<(Vec<u8>, bool), String> {
+    let mut reader = BitReader::new(data);
+    let mut out = Vec::new();
+
+    loop {
+        let Some(bfinal) = reader.take_bits(1) else { return Ok((out, false)) };
+        let Some(btype) = reader.take_bits(2) else { return Ok((out, false)) };
+
+        match btype {
+            0 => {
+                reader.align_to_byte();
+                let Some(header) = reader.take_bytes(4) else { return Ok((out, false)) };
+                let len = u16::from_le_bytes([header[0], header[1]]) as usize;
+                let nlen = u16::from_le_bytes([header[2], header[3]]);
+                if !nlen != len as u16 {
+                    return Err("stored block length check failed".to_string());
+                }
+                let Some(bytes) = reader.take_bytes(len) else { return Ok((out, false)) };
+                out.extend_from_slice(bytes);
+            }
+            1 => {
+                let done = inflate_block(&mut reader, &fixed_litlen_table(), &fixed_dist_table(), &mut out)?;
+                if !done {
+                    return Ok((out, false));
+                }
+            }
+            2 => {
+                let Some((litlen, dist)) = read_dynamic_tables(&mut reader)? else {
+                    return Ok((out, false));
+                };
+                if !inflate_block(&mut reader, &litlen, &dist, &mut out)? {
+                    return Ok((out, false));
+                }
+            }
+            _ => return Err("invalid block type".to_string()),
+        }
+
+        if bfinal == 1 {
+            return Ok((out, true));
+        }
+    }
+}
+
+/// Parse a gzip member header, returning the offset of the DEFLATE
+/// data, or `None` when the buffer does not hold the whole header yet
+fn gzip_header_len(data: &[u8]) -> Result<Option<usize>, String> {
+    if data.len() < 10 {
+        return Ok(None);
+    }
+    if data[0] != 0x1F || data[1] != 0x8B {
+        return Err("not a gzip stream (bad magic)".to_string());
+    }
+    if data[2] != 8 {
+        return Err(format!("unsupported compression method {}", data[2]));
+    }
+    let flags = data[3];
+    let mut offset = 10;
+
+    // FEXTRA: two-byte little-endian length, then that many bytes
+    if flags & 0x04 != 0 {
+        let Some(len) = data.get(offset..offset + 2) else { return Ok(None) };
+        offset += 2 + u16::from_le_bytes([len[0], len[1]]) as usize;
+    }
+    // FNAME and FCOMMENT: zero-terminated strings
+    for mask in [0x08u8, 0x10] {
+        if flags & mask != 0 {
+            match data[offset.min(data.len())..].iter().position(|&b| b == 0) {
+                Some(end) => offset += end + 1,
+                None => return Ok(None),
+            }
+        }
+    }
+    // FHCRC: two-byte header checksum
+    if flags & 0x02 != 0 {
+        offset += 2;
+    }
+    if offset > data.len() {
+        return Ok(None);
+    }
+    Ok(Some(offset))
+}
+
+/// Decompress a complete gzip member, verifying the trailer checksum
+pub fn gzip_decompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    let header = gzip_header_len(data)?.ok_or("truncated gzip header")?;
+    let (out, finished) = inflate(&data[header..])?;
+    if !finished {
+        return Err("truncated gzip stream".to_string());
+    }
+
+    // The trailer is the last 8 bytes: CRC32 then length, both
+    // little-endian. Verify when present; a missing trailer is
+    // tolerated since the DEFLATE stream is self-terminating.
+    if data.len() >= header + 8 {
+        let trailer = &data[data.len() - 8..];
+        let expected_crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let expected_len = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+        if crc32(&out) != expected_crc {
+            return Err("gzip checksum mismatch".to_string());
+        }
+        if out.len() as u32 != expected_len {
+            return Err("gzip length mismatch".to_string());
+        }
+    }
+    Ok(out)
+}
+
+/// Incremental gzip decoder for streaming responses.
+///
+/// Each `feed` returns the bytes newly decoded by that chunk. The
+/// decoder re-inflates the accumulated input on every feed rather than
+/// carrying DEFLATE state across calls; quadratic in principle, but
+/// chat responses are small enough that simplicity wins.
+#[derive(Default)]
+pub struct GzipStream {
+    input: Vec<u8>,
+    emitted: usize,
+}
+
+impl GzipStream {
+    pub fn new() -> Self {
+        GzipStream::default()
+    }
+
+    /// Add compressed bytes and return whatever new output they unlock
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Vec<u8>, String> {
+        self.input.extend_from_slice(chunk);
+        let Some(header) = gzip_header_len(&self.input)? else {
+            return Ok(Vec::new());
+        };
+        let (out, _finished) = inflate(&self.input[header..])?;
+        let new = out.get(self.emitted..).map(<[u8]>::to_vec).unwrap_or_default();
+        self.emitted = out.len();
+        Ok(new)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// zstd (via the zstd crate; the format is too involved to hand-roll)
+
+/// Decompress a complete zstd frame
+pub fn zstd_decompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    zstd::stream::decode_all(data).map_err(|e| format!("zstd: {}", e))
+}
+
+/// Incremental zstd decoder for streaming responses, the counterpart
+/// of [`GzipStream`]. The push-based write decoder accumulates decoded
+/// bytes into its inner buffer, which each `feed` drains.
+pub struct ZstdStream {
+    decoder: zstd::stream::write::Decoder<'static, Vec<u8>>,
+}
+
+impl ZstdStream {
+    pub fn new() -> Self {
+        ZstdStream {
+            // Construction only fails on allocation failure
+            decoder: zstd::stream::write::Decoder::new(Vec::new())
+                .expect("zstd decoder construction failed"),
+        }
+    }
+
+    /// Add compressed bytes and return whatever new output they unlock
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Vec<u8>, String> {
+        use std::io::Write;
+        self.decoder.write_all(chunk).map_err(|e| format!("zstd: {}", e))?;
+        self.decoder.flush().map_err(|e| format!("zstd: {}", e))?;
+        Ok(std::mem::take(self.decoder.get_mut()))
+    }
+}
+
+impl Default for ZstdStream {
+    fn default() -> Self {
+        ZstdStream::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Content-Encoding negotiation
+
+/// Every Content-Encoding the client can decode, advertised together
+/// in Accept-Encoding and dispatched on when the response declares one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Gzip,
+    Zstd,
+}
+
+impl Encoding {
+    /// The Accept-Encoding value listing every supported encoding
+    pub const ACCEPT: &'static str = "gzip, zstd";
+
+    /// Parse a Content-Encoding header value; unknown encodings (and
+    /// multi-encoding chains) are not ours to decode
+    pub fn parse(value: &str) -> Option<Self> {
+        if value.eq_ignore_ascii_case("gzip") {
+            Some(Encoding::Gzip)
+        } else if value.eq_ignore_ascii_case("zstd") {
+            Some(Encoding::Zstd)
+        } else {
+            None
+        }
+    }
+
+    /// Decompress a complete response body
+    pub fn decompress(self, data: &[u8]) -> Result<Vec<u8>, String> {
+        match self {
+            Encoding::Gzip => gzip_decompress(data),
+            Encoding::Zstd => zstd_decompress(data),
+        }
+    }
+
+    /// Incremental decoder for a streaming response body
+    pub fn decoder(self) -> StreamDecoder {
+        match self {
+            Encoding::Gzip => StreamDecoder::Gzip(GzipStream::new()),
+            Encoding::Zstd => StreamDecoder::Zstd(ZstdStream::new()),
+        }
+    }
+}
+
+/// Incremental decoder over whichever encoding the response declared
+pub enum StreamDecoder {
+    Gzip(GzipStream),
+    Zstd(ZstdStream),
+}
+
+impl StreamDecoder {
+    /// Add compressed bytes and return whatever new output they unlock
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Vec<u8>, String> {
+        match self {
+            StreamDecoder::Gzip(stream) => stream.feed(chunk),
+            StreamDecoder::Zstd(stream) => stream.feed(chunk),
+        }
+    }
+}
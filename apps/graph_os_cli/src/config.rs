@@ -7,6 +7,10 @@ use tokio::sync::Mutex;
 use serde::{Deserialize, Serialize};
 use anyhow::{Result, Context, anyhow};
 
+use crate::hooks::HooksConfig;
+use crate::templates::Template;
+use crate::usage::ModelPrice;
+
 /// API providers supported by the application
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ApiProvider {
@@ -16,6 +20,19 @@ pub enum ApiProvider {
     Custom,
 }
 
+impl ApiProvider {
+    /// Parse a provider name as users type it on the command line
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "openai" => Some(ApiProvider::OpenAI),
+            "anthropic" => Some(ApiProvider::Anthropic),
+            "gemini" => Some(ApiProvider::Gemini),
+            "custom" => Some(ApiProvider::Custom),
+            _ => None,
+        }
+    }
+}
+
 impl std::fmt::Display for ApiProvider {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -37,19 +54,396 @@ pub struct ApiConfig {
 }
 
 /// Authentication configuration for GraphOS services
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AuthConfig {
     pub rpc_secret: Option<String>,
     pub endpoints: HashMap<String, EndpointConfig>,
+    /// Named conversation templates / prompt presets
+    #[serde(default)]
+    pub templates: HashMap<String, Template>,
+    /// Named personas for `/agents` multi-agent conversations
+    #[serde(default)]
+    pub personas: HashMap<String, PersonaConfig>,
+    /// Per-model prices for cost tracking
+    #[serde(default)]
+    pub prices: HashMap<String, ModelPrice>,
+    /// Slash command aliases (the `[aliases]` table), keyed by name
+    /// without the slash; a value may chain commands with `;`
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+    /// Keyboard macros (the `[macros]` table), keyed by name: the
+    /// recorded input lines replayed by `/macro play`
+    #[serde(default)]
+    pub macros: HashMap<String, Vec<String>>,
+    /// Notification hooks fired when responses complete
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    /// Transcript sharing endpoint for `/share` and `gos share`
+    #[serde(default)]
+    pub share: Option<ShareConfig>,
+    /// Screen-reader friendly rendering: no colors, emoji or cursor jumps
+    #[serde(default)]
+    pub accessible: Option<bool>,
+    /// Output filters applied to assistant responses
+    #[serde(default)]
+    pub filters: Option<FiltersConfig>,
+    /// Outbound secret scanning for user prompts
+    #[serde(default)]
+    pub redact: Option<RedactConfig>,
+    /// Metric naming for `gos system-info export`
+    #[serde(default)]
+    pub metrics: Option<MetricsConfig>,
+    /// Storage backend for `gos archive` / `gos restore`
+    #[serde(default)]
+    pub archive: Option<ArchiveConfig>,
+    /// TUI layout preferences, written back by `/layout` and the
+    /// resize keys
+    #[serde(default)]
+    pub layout: Option<LayoutConfig>,
+    /// Admin guardrails: blocked topics, response caps and provider
+    /// allow lists. Honored only from the system config.
+    #[serde(default)]
+    pub policy: Option<PolicyConfig>,
+    /// Ordered provider fallback chain: when a chat request fails on
+    /// the primary provider it is retried against these, in order
+    #[serde(default)]
+    pub fallback: Vec<String>,
 }
 
-/// Configuration for a specific endpoint
+impl AuthConfig {
+    /// The fields eligible for `enc:` encryption: the RPC secret and
+    /// each endpoint's secret, token and signing key
+    fn secret_fields(&mut self) -> Vec<&mut Option<String>> {
+        let mut fields = vec![&mut self.rpc_secret];
+        for endpoint in self.endpoints.values_mut() {
+            fields.push(&mut endpoint.secret);
+            fields.push(&mut endpoint.token);
+            fields.push(&mut endpoint.signing_key);
+        }
+        fields
+    }
+
+    /// Whether any secret field carries an `enc:` blob, so loading can
+    /// skip the passphrase entirely for unencrypted configs
+    pub fn has_encrypted_fields(&self) -> bool {
+        self.rpc_secret
+            .iter()
+            .chain(self.endpoints.values().flat_map(|e| {
+                e.secret.iter().chain(e.token.iter()).chain(e.signing_key.iter())
+            }))
+            .any(|value| crate::crypto::is_encrypted_field(value))
+    }
+
+    /// Encrypt plaintext secret fields into `enc:` blobs; values that
+    /// are already encrypted are left alone. Returns how many changed.
+    pub fn encrypt_secret_fields(&mut self, cipher: &crate::crypto::SessionCipher) -> Result<usize> {
+        let mut changed = 0;
+        for field in self.secret_fields() {
+            let Some(value) = field.as_deref() else { continue };
+            if crate::crypto::is_encrypted_field(value) {
+                continue;
+            }
+            let encrypted = cipher.encrypt_field(value)?;
+            *field = Some(encrypted);
+            changed += 1;
+        }
+        Ok(changed)
+    }
+
+    /// Decrypt `enc:` blobs back to plaintext; plaintext values are
+    /// left alone. Returns how many changed.
+    pub fn decrypt_secret_fields(&mut self, cipher: &crate::crypto::SessionCipher) -> Result<usize> {
+        let mut changed = 0;
+        for field in self.secret_fields() {
+            let Some(value) = field.as_deref() else { continue };
+            if !crate::crypto::is_encrypted_field(value) {
+                continue;
+            }
+            let decrypted = cipher.decrypt_field(value)?;
+            *field = Some(decrypted);
+            changed += 1;
+        }
+        Ok(changed)
+    }
+
+    /// Decrypt `enc:` fields at load time, best effort: a missing or
+    /// wrong passphrase warns and leaves the blob in place so commands
+    /// that never touch the secret still work
+    fn decrypt_secret_fields_at_load(&mut self) {
+        if !self.has_encrypted_fields() {
+            return;
+        }
+        let Some(cipher) = crate::crypto::config_cipher() else {
+            eprintln!(
+                "Warning: the config has encrypted fields but no passphrase is available; \
+                 set {} or run interactively",
+                crate::crypto::CONFIG_PASSPHRASE_ENV
+            );
+            return;
+        };
+        for field in self.secret_fields() {
+            let Some(value) = field.as_deref() else { continue };
+            if !crate::crypto::is_encrypted_field(value) {
+                continue;
+            }
+            match cipher.decrypt_field(value) {
+                Ok(decrypted) => *field = Some(decrypted),
+                Err(e) => eprintln!("Warning: failed to decrypt config field: {}", e),
+            }
+        }
+    }
+}
+
+/// A named persona for `/agents` mode (the `[personas]` table): a
+/// system prompt plus optional provider and model overrides
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PersonaConfig {
+    /// Short description shown in persona listings
+    #[serde(default)]
+    pub description: Option<String>,
+    /// System prompt installed when this persona answers
+    #[serde(default)]
+    pub system: Option<String>,
+    /// Provider override (openai, anthropic, gemini, custom); the
+    /// session's active provider is used when omitted
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model override passed to the provider
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// Storage backend for archived sessions (the `[archive]` table)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchiveConfig {
+    /// "local" (default), "s3" or "webdav"
+    #[serde(default)]
+    pub backend: Option<String>,
+    /// Directory for the local backend (default: an `archive` directory
+    /// in the data dir)
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Endpoint URL for the s3 backend, or base URL for webdav
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Bucket name (s3)
+    #[serde(default)]
+    pub bucket: Option<String>,
+    /// Signing region (s3, default "us-east-1")
+    #[serde(default)]
+    pub region: Option<String>,
+    /// Access key id (s3)
+    #[serde(default)]
+    pub access_key: Option<String>,
+    /// Secret access key (s3)
+    #[serde(default)]
+    pub secret_key: Option<String>,
+    /// Basic auth username (webdav)
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Basic auth password (webdav)
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// Metric naming for `gos system-info export` (the `[metrics]` table)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// Prefix joined to column names, and the InfluxDB measurement name
+    /// (default "graphos")
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Explicit column-to-metric renames, overriding the prefix
+    #[serde(default)]
+    pub rename: HashMap<String, String>,
+}
+
+/// TUI layout preferences (the `[layout]` table), written back when
+/// the user adjusts the layout from inside the chat
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LayoutConfig {
+    /// Layout density: "comfortable" (default) or "compact"
+    #[serde(default)]
+    pub mode: Option<String>,
+    /// Extra rows added to the input box beyond its natural height
+    #[serde(default)]
+    pub input_rows: Option<u16>,
+}
+
+/// Admin guardrails (the `[policy]` table). Honored only from the
+/// system config under /etc/graph_os, so a user file cannot set or
+/// relax the rules.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PolicyConfig {
+    /// Blocked topics: regexes matched against outgoing prompts,
+    /// reported by name when a send is denied
+    #[serde(default)]
+    pub blocked: Vec<TopicRule>,
+    /// Hard cap on response length in characters; longer responses
+    /// are truncated with a notice
+    #[serde(default)]
+    pub max_response_chars: Option<usize>,
+    /// Providers that may be used (openai, anthropic, gemini,
+    /// custom); empty means all
+    #[serde(default)]
+    pub allowed_providers: Vec<String>,
+    /// Per-profile overrides (the `[policy.profiles.<name>]` tables),
+    /// selected with the GRAPHOS_POLICY_PROFILE environment variable
+    #[serde(default)]
+    pub profiles: HashMap<String, PolicyProfile>,
+}
+
+/// One blocked topic rule in the guardrail policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicRule {
+    /// Name the denial is reported under
+    pub name: String,
+    /// Regex matched against outgoing prompts
+    pub pattern: String,
+}
+
+/// Guardrail overrides for one policy profile
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PolicyProfile {
+    /// Providers this profile may use; empty inherits the global list
+    #[serde(default)]
+    pub allowed_providers: Vec<String>,
+    /// Response cap for this profile, overriding the global one
+    #[serde(default)]
+    pub max_response_chars: Option<usize>,
+}
+
+/// Output filter pipeline for assistant responses (the `[filters]` table)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FiltersConfig {
+    /// Strip trailing whitespace from every line (default on)
+    #[serde(default)]
+    pub trim_trailing_whitespace: Option<bool>,
+    /// Replace known secret values with a placeholder (default on)
+    #[serde(default)]
+    pub redact_secrets: Option<bool>,
+    /// Mask a built-in profanity list (default off)
+    #[serde(default)]
+    pub profanity: Option<bool>,
+    /// Rewrite markdown links as "text (url)" (default off)
+    #[serde(default)]
+    pub unfurl_links: Option<bool>,
+    /// Custom regex replace rules, applied in order
+    #[serde(default)]
+    pub replace: Vec<ReplaceRule>,
+}
+
+/// One custom replace rule in the filter pipeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaceRule {
+    /// Regex matched against the response text
+    pub pattern: String,
+    /// Replacement, supporting $1-style capture references
+    pub replacement: String,
+}
+
+/// Outbound secret scanning for user prompts (the `[redact]` table)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RedactConfig {
+    /// What to do when a message contains a likely secret: "warn"
+    /// (the default), "block" or "mask"
+    #[serde(default)]
+    pub mode: Option<String>,
+    /// Flag long high-entropy tokens (default on)
+    #[serde(default)]
+    pub entropy: Option<bool>,
+    /// Additional patterns treated as secrets, reported by name
+    #[serde(default)]
+    pub patterns: Vec<SecretPattern>,
+}
+
+/// One custom secret pattern in the outbound scanner
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretPattern {
+    /// Name the detection is reported under
+    pub name: String,
+    /// Regex matched against outgoing messages
+    pub pattern: String,
+}
+
+/// Where shared transcripts are uploaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareConfig {
+    /// Upload URL: a GraphOS JSON-RPC endpoint or a paste service
+    pub url: String,
+    /// "graphos" (sessions.share RPC, the default) or "paste"
+    /// (POST the rendered transcript, response body is the URL)
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// Bearer token sent with the upload, if the service needs one
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+/// Configuration for a specific endpoint
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct EndpointConfig {
     pub url: String,
     pub secret: Option<String>,
     pub token: Option<String>,
     pub use_tls: Option<bool>,
+    /// Transport to use for chat: "jsonrpc" (default) or "grpc"
+    pub transport: Option<String>,
+    /// Proxy URL for reaching this endpoint (http://, https:// or
+    /// socks5://)
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Path to a PEM bundle of additional root certificates
+    #[serde(default)]
+    pub ca_bundle: Option<String>,
+    /// Accept invalid TLS certificates (development only)
+    #[serde(default)]
+    pub danger_accept_invalid_certs: Option<bool>,
+    /// Gzip request bodies over the size threshold (default off);
+    /// setting this to false also stops advertising gzip and zstd for
+    /// responses
+    #[serde(default)]
+    pub compress: Option<bool>,
+    /// Request body size in bytes below which compression is skipped
+    /// (default 4096)
+    #[serde(default)]
+    pub compress_threshold: Option<u64>,
+    /// OIDC issuer URL for `gos login` (device authorization flow)
+    #[serde(default)]
+    pub oidc_issuer: Option<String>,
+    /// OAuth2 client id registered with the identity provider
+    #[serde(default)]
+    pub oidc_client_id: Option<String>,
+    /// Refresh token stored by `gos login`
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// Unix timestamp the access token expires at
+    #[serde(default)]
+    pub token_expiry: Option<i64>,
+    /// HTTP version for JSON-RPC requests: "auto" (HTTP/2 with an
+    /// HTTP/1.1 fallback, the default), "http1" or "http2"
+    #[serde(default)]
+    pub http_version: Option<String>,
+    /// Key id for HMAC request signing, sent alongside the signature
+    /// so the server knows which key to verify with
+    #[serde(default)]
+    pub signing_key_id: Option<String>,
+    /// Shared key for HMAC-SHA256 request signing (JSON-RPC only);
+    /// requests are signed only when both halves are set
+    #[serde(default)]
+    pub signing_key: Option<String>,
+    /// Model used to embed messages for semantic search; when set,
+    /// `gos search --semantic` and /recall embed through the configured
+    /// provider instead of the local hashed features
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+    /// Extra HTTP headers (JSON-RPC) or metadata (gRPC) attached to
+    /// every request, e.g. X-Org-Id for a reverse proxy
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra_headers: HashMap<String, String>,
+    /// Query parameters appended to the endpoint URL (JSON-RPC only)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub query_params: HashMap<String, String>,
 }
 
 /// File formats supported for configuration
@@ -79,76 +473,146 @@ impl ConfigFormat {
     }
 }
 
+/// Layers a configuration value can come from, lowest precedence
+/// first. Later layers override earlier ones key by key, so the
+/// effective configuration is deterministic no matter which files
+/// happen to exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigLayer {
+    /// Built-in fallback, e.g. a provider's default model
+    Default,
+    /// A file under /etc/graph_os
+    SystemFile(PathBuf),
+    /// A file in the user config directory
+    UserFile(PathBuf),
+    /// An environment variable
+    Environment(String),
+    /// A command-line flag
+    Flag(String),
+}
+
+impl std::fmt::Display for ConfigLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigLayer::Default => write!(f, "default"),
+            ConfigLayer::SystemFile(path) => write!(f, "system file {}", path.display()),
+            ConfigLayer::UserFile(path) => write!(f, "user file {}", path.display()),
+            ConfigLayer::Environment(var) => write!(f, "env {}", var),
+            ConfigLayer::Flag(flag) => write!(f, "flag {}", flag),
+        }
+    }
+}
+
+/// One effective configuration value and the layer that supplied it,
+/// shown by `gos config show --provenance`
+#[derive(Debug, Clone)]
+pub struct ProvenanceEntry {
+    /// Dotted key, e.g. "apis.openai.model" or "endpoints.default"
+    pub key: String,
+    /// Displayable value; secrets are redacted before they get here
+    pub value: String,
+    pub layer: ConfigLayer,
+}
+
+/// Redact a secret down to a recognizable but unusable form
+pub fn redact(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 8 {
+        "****".to_string()
+    } else {
+        format!(
+            "{}...{}",
+            chars[..4].iter().collect::<String>(),
+            chars[chars.len() - 4..].iter().collect::<String>()
+        )
+    }
+}
+
 /// Full application configuration
 #[derive(Debug, Clone)]
 pub struct Config {
     pub apis: HashMap<ApiProvider, ApiConfig>,
     pub default_provider: Option<ApiProvider>,
     pub auth: Option<AuthConfig>,
+    /// Where each effective value came from, one entry per key
+    pub provenance: Vec<ProvenanceEntry>,
 }
 
 impl Config {
-    /// Load API configuration from environment variables
-    async fn load_api_config() -> HashMap<ApiProvider, ApiConfig> {
+    /// Load API configuration from environment variables, recording
+    /// where each effective value came from
+    fn load_api_config(provenance: &mut Vec<ProvenanceEntry>) -> HashMap<ApiProvider, ApiConfig> {
         let mut apis = HashMap::new();
-        
-        // Check for OpenAI configuration
-        if let Ok(api_key) = env::var("OPENAI_API_KEY") {
-            let api_url = env::var("OPENAI_API_URL").ok();
-            let model = env::var("OPENAI_API_MODEL").or_else(|_| Ok::<String, env::VarError>("gpt-4o".into())).ok();
-            
-            apis.insert(ApiProvider::OpenAI, ApiConfig {
-                provider: ApiProvider::OpenAI,
-                api_key,
-                api_url,
-                model,
-            });
-        }
-        
-        // Check for Anthropic configuration
-        if let Ok(api_key) = env::var("ANTHROPIC_API_KEY") {
-            let api_url = env::var("ANTHROPIC_API_URL").ok();
-            let model = env::var("ANTHROPIC_API_MODEL").or_else(|_| Ok::<String, env::VarError>("claude-3-opus-20240229".into())).ok();
-            
-            apis.insert(ApiProvider::Anthropic, ApiConfig {
-                provider: ApiProvider::Anthropic,
-                api_key,
-                api_url,
-                model,
-            });
-        }
-        
-        // Check for Gemini configuration
-        if let Ok(api_key) = env::var("GEMINI_API_KEY") {
-            let api_url = env::var("GEMINI_API_URL").ok();
-            let model = env::var("GEMINI_API_MODEL").or_else(|_| Ok::<String, env::VarError>("gemini-pro".into())).ok();
-            
-            apis.insert(ApiProvider::Gemini, ApiConfig {
-                provider: ApiProvider::Gemini,
-                api_key,
-                api_url,
-                model,
-            });
-        }
-        
-        // Check for custom API configuration
-        if let Ok(api_key) = env::var("CUSTOM_API_KEY") {
-            let api_url = env::var("CUSTOM_API_URL").ok();
-            let model = env::var("CUSTOM_API_MODEL").ok();
-            
-            apis.insert(ApiProvider::Custom, ApiConfig {
-                provider: ApiProvider::Custom,
-                api_key,
-                api_url,
-                model,
-            });
+
+        let providers = [
+            (ApiProvider::OpenAI, "OPENAI", Some("gpt-4o")),
+            (ApiProvider::Anthropic, "ANTHROPIC", Some("claude-3-opus-20240229")),
+            (ApiProvider::Gemini, "GEMINI", Some("gemini-pro")),
+            (ApiProvider::Custom, "CUSTOM", None),
+        ];
+
+        for (provider, prefix, default_model) in providers {
+            let key_var = format!("{}_API_KEY", prefix);
+            let Ok(api_key) = env::var(&key_var) else { continue };
+            let name = prefix.to_lowercase();
+
+            Self::record_provenance(
+                provenance,
+                format!("apis.{}.api_key", name),
+                redact(&api_key),
+                ConfigLayer::Environment(key_var),
+            );
+
+            let url_var = format!("{}_API_URL", prefix);
+            let api_url = env::var(&url_var).ok();
+            if let Some(url) = &api_url {
+                Self::record_provenance(
+                    provenance,
+                    format!("apis.{}.api_url", name),
+                    url.clone(),
+                    ConfigLayer::Environment(url_var),
+                );
+            }
+
+            // An explicit model from the environment beats the built-in
+            // default for the provider
+            let model_var = format!("{}_API_MODEL", prefix);
+            let model = match env::var(&model_var) {
+                Ok(model) => {
+                    Self::record_provenance(
+                        provenance,
+                        format!("apis.{}.model", name),
+                        model.clone(),
+                        ConfigLayer::Environment(model_var),
+                    );
+                    Some(model)
+                }
+                Err(_) => {
+                    if let Some(model) = default_model {
+                        Self::record_provenance(
+                            provenance,
+                            format!("apis.{}.model", name),
+                            model.to_string(),
+                            ConfigLayer::Default,
+                        );
+                    }
+                    default_model.map(String::from)
+                }
+            };
+
+            apis.insert(provider, ApiConfig { provider, api_key, api_url, model });
         }
-        
+
         apis
     }
-    
-    /// Get the default API provider from environment variables
-    fn get_default_provider(apis: &HashMap<ApiProvider, ApiConfig>) -> Option<ApiProvider> {
+
+    /// Get the default API provider: the first configured one in a
+    /// fixed order, unless DEFAULT_API_PROVIDER names a configured
+    /// provider explicitly
+    fn get_default_provider(
+        apis: &HashMap<ApiProvider, ApiConfig>,
+        provenance: &mut Vec<ProvenanceEntry>,
+    ) -> Option<ApiProvider> {
         // Set first available provider as default
         let mut default_provider = None;
         for provider in [ApiProvider::OpenAI, ApiProvider::Anthropic, ApiProvider::Gemini, ApiProvider::Custom] {
@@ -156,80 +620,97 @@ impl Config {
                 default_provider = Some(provider);
             }
         }
-        
+        let mut layer = ConfigLayer::Default;
+
         // Override default provider if explicitly set
-        if let Ok(default) = env::var("DEFAULT_API_PROVIDER") {
-            match default.to_lowercase().as_str() {
-                "openai" => {
-                    if apis.contains_key(&ApiProvider::OpenAI) {
-                        default_provider = Some(ApiProvider::OpenAI);
-                    }
-                }
-                "anthropic" => {
-                    if apis.contains_key(&ApiProvider::Anthropic) {
-                        default_provider = Some(ApiProvider::Anthropic);
-                    }
-                }
-                "gemini" => {
-                    if apis.contains_key(&ApiProvider::Gemini) {
-                        default_provider = Some(ApiProvider::Gemini);
-                    }
-                }
-                "custom" => {
-                    if apis.contains_key(&ApiProvider::Custom) {
-                        default_provider = Some(ApiProvider::Custom);
-                    }
-                }
-                _ => {}
+        if let Ok(name) = env::var("DEFAULT_API_PROVIDER")
+            && let Some(provider) = ApiProvider::parse(&name)
+            && apis.contains_key(&provider) {
+                default_provider = Some(provider);
+                layer = ConfigLayer::Environment("DEFAULT_API_PROVIDER".to_string());
             }
+
+        if let Some(provider) = default_provider {
+            Self::record_provenance(
+                provenance,
+                "default_provider".to_string(),
+                provider.to_string(),
+                layer,
+            );
         }
-        
+
         default_provider
     }
+
+    /// Record where an effective value came from, replacing any entry
+    /// a lower-precedence layer left for the same key
+    fn record_provenance(
+        provenance: &mut Vec<ProvenanceEntry>,
+        key: String,
+        value: String,
+        layer: ConfigLayer,
+    ) {
+        provenance.retain(|entry| entry.key != key);
+        provenance.push(ProvenanceEntry { key, value, layer });
+    }
     
-    /// Get possible authentication config file paths
-    fn get_auth_config_paths() -> Vec<(PathBuf, ConfigFormat)> {
-        let mut paths = Vec::new();
-        
-        // System-wide config path
-        if let Ok(sys_paths) = fs::read_dir("/etc/graph_os") {
-            for path in sys_paths.filter_map(Result::ok) {
-                let file_path = path.path();
-                if let Some(ext) = file_path.extension().and_then(|e| e.to_str()) {
-                    if let Some(format) = ConfigFormat::from_extension(ext) {
-                        paths.push((file_path, format));
-                    }
-                }
-            }
-        }
-        
-        // User config paths
-        if let Some(home_dir) = dirs::home_dir() {
-            let user_config_dir = home_dir.join(".graph_os");
-            
-            if let Ok(user_paths) = fs::read_dir(&user_config_dir) {
-                for path in user_paths.filter_map(Result::ok) {
-                    let file_path = path.path();
-                    if let Some(ext) = file_path.extension().and_then(|e| e.to_str()) {
-                        if let Some(format) = ConfigFormat::from_extension(ext) {
-                            paths.push((file_path, format));
-                        }
-                    }
-                }
-            }
-            
-            // Add specific config paths
-            for format in [ConfigFormat::Json, ConfigFormat::Yaml, ConfigFormat::Toml] {
-                let ext = format.extension();
-                paths.push((user_config_dir.join(format!("config.{}", ext)), format));
-            }
+    /// List config file candidates in a directory, sorted by path so
+    /// the pick is deterministic (read_dir order is not)
+    fn scan_config_dir(dir: &Path) -> Vec<(PathBuf, ConfigFormat)> {
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)
+            .map(|entries| entries.filter_map(Result::ok).map(|e| e.path()).collect())
+            .unwrap_or_default();
+        files.sort();
+
+        files
+            .into_iter()
+            .filter_map(|path| {
+                let format = path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .and_then(ConfigFormat::from_extension)?;
+                Some((path, format))
+            })
+            .collect()
+    }
+
+    /// Config file candidates for the user layer: anything in the user
+    /// config dir, then the well-known config.{json,yaml,toml} names
+    fn user_config_paths() -> Vec<(PathBuf, ConfigFormat)> {
+        let user_config_dir = crate::paths::config_dir();
+        let mut paths = Self::scan_config_dir(&user_config_dir);
+
+        for format in [ConfigFormat::Json, ConfigFormat::Yaml, ConfigFormat::Toml] {
+            let ext = format.extension();
+            paths.push((user_config_dir.join(format!("config.{}", ext)), format));
         }
-        
+
+        paths
+    }
+
+    /// Get possible authentication config file paths, system layer first
+    fn get_auth_config_paths() -> Vec<(PathBuf, ConfigFormat)> {
+        let mut paths = Self::scan_config_dir(Path::new("/etc/graph_os"));
+        paths.extend(Self::user_config_paths());
         paths
     }
     
-    /// Try to load auth config from a specific file
+    /// Try to load auth config from a specific file. Validation runs first
+    /// so a malformed file fails with actionable per-key messages instead
+    /// of a bare deserialization error.
     fn load_auth_config_from_file(path: &Path, format: ConfigFormat) -> Result<AuthConfig> {
+        let report = validate_auth_config_file(path, format)?;
+        for warning in &report.warnings {
+            eprintln!("Warning: {}: {}", path.display(), warning);
+        }
+        if !report.is_ok() {
+            return Err(anyhow!(
+                "Invalid config file {}:\n  - {}\nFix the errors above or run `gos config validate`",
+                path.display(),
+                report.errors.join("\n  - ")
+            ));
+        }
+
         let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read config file: {}", path.display()))?;
             
@@ -249,38 +730,196 @@ impl Config {
         }
     }
     
-    /// Try to load authentication configuration from available files
-    fn load_auth_config() -> Option<AuthConfig> {
-        let config_paths = Self::get_auth_config_paths();
-        
-        for (path, format) in config_paths {
-            if path.exists() {
-                match Self::load_auth_config_from_file(&path, format) {
-                    Ok(config) => {
-                        return Some(config);
-                    },
-                    Err(err) => {
-                        eprintln!("Error loading config from {}: {}", path.display(), err);
-                    }
+    /// Load the first parseable config file from a list of candidates
+    fn first_loadable_config(paths: Vec<(PathBuf, ConfigFormat)>) -> Option<(AuthConfig, PathBuf)> {
+        for (path, format) in paths {
+            if !path.exists() {
+                continue;
+            }
+            match Self::load_auth_config_from_file(&path, format) {
+                Ok(config) => return Some((config, path)),
+                Err(err) => {
+                    eprintln!("Error loading config from {}: {}", path.display(), err);
                 }
             }
         }
-        
+
         None
     }
-    
-    /// Load configuration from environment variables and config files
+
+    /// Load authentication configuration, merging the system file and
+    /// the user file key by key (user wins) instead of first-file-wins
+    fn load_auth_config_layered(provenance: &mut Vec<ProvenanceEntry>) -> Option<AuthConfig> {
+        let system = Self::first_loadable_config(Self::scan_config_dir(Path::new("/etc/graph_os")));
+        let user = Self::first_loadable_config(Self::user_config_paths());
+
+        let mut merged = AuthConfig::default();
+        let mut any = false;
+
+        if let Some((layer, path)) = system {
+            Self::merge_auth_layer(&mut merged, layer, ConfigLayer::SystemFile(path), provenance);
+            any = true;
+        }
+        if let Some((layer, path)) = user {
+            Self::merge_auth_layer(&mut merged, layer, ConfigLayer::UserFile(path), provenance);
+            any = true;
+        }
+
+        any.then_some(merged)
+    }
+
+    /// Overlay one auth config layer onto the merged result, recording
+    /// the provenance of every key the layer supplies. Scalar fields
+    /// and map entries override individually; keys the layer leaves
+    /// unset keep the value from lower layers.
+    pub fn merge_auth_layer(
+        base: &mut AuthConfig,
+        layer: AuthConfig,
+        source: ConfigLayer,
+        provenance: &mut Vec<ProvenanceEntry>,
+    ) {
+        if let Some(secret) = layer.rpc_secret {
+            Self::record_provenance(provenance, "rpc_secret".to_string(), redact(&secret), source.clone());
+            base.rpc_secret = Some(secret);
+        }
+        for (name, endpoint) in layer.endpoints {
+            Self::record_provenance(
+                provenance,
+                format!("endpoints.{}", name),
+                endpoint.url.clone(),
+                source.clone(),
+            );
+            base.endpoints.insert(name, endpoint);
+        }
+        for (name, template) in layer.templates {
+            Self::record_provenance(provenance, format!("templates.{}", name), "(defined)".to_string(), source.clone());
+            base.templates.insert(name, template);
+        }
+        for (name, persona) in layer.personas {
+            Self::record_provenance(provenance, format!("personas.{}", name), "(defined)".to_string(), source.clone());
+            base.personas.insert(name, persona);
+        }
+        for (name, price) in layer.prices {
+            Self::record_provenance(provenance, format!("prices.{}", name), "(defined)".to_string(), source.clone());
+            base.prices.insert(name, price);
+        }
+        for (name, alias) in layer.aliases {
+            Self::record_provenance(provenance, format!("aliases.{}", name), alias.clone(), source.clone());
+            base.aliases.insert(name, alias);
+        }
+        for (name, steps) in layer.macros {
+            Self::record_provenance(provenance, format!("macros.{}", name), format!("({} steps)", steps.len()), source.clone());
+            base.macros.insert(name, steps);
+        }
+        if layer.hooks != HooksConfig::default() {
+            Self::record_provenance(provenance, "hooks".to_string(), "(configured)".to_string(), source.clone());
+            base.hooks = layer.hooks;
+        }
+        if let Some(share) = layer.share {
+            Self::record_provenance(provenance, "share".to_string(), share.url.clone(), source.clone());
+            base.share = Some(share);
+        }
+        if let Some(accessible) = layer.accessible {
+            Self::record_provenance(provenance, "accessible".to_string(), accessible.to_string(), source.clone());
+            base.accessible = Some(accessible);
+        }
+        if let Some(filters) = layer.filters {
+            Self::record_provenance(provenance, "filters".to_string(), "(configured)".to_string(), source.clone());
+            base.filters = Some(filters);
+        }
+        if let Some(redact) = layer.redact {
+            Self::record_provenance(provenance, "redact".to_string(), "(configured)".to_string(), source.clone());
+            base.redact = Some(redact);
+        }
+        if let Some(metrics) = layer.metrics {
+            Self::record_provenance(provenance, "metrics".to_string(), "(configured)".to_string(), source.clone());
+            base.metrics = Some(metrics);
+        }
+        if let Some(archive) = layer.archive {
+            Self::record_provenance(provenance, "archive".to_string(), "(configured)".to_string(), source.clone());
+            base.archive = Some(archive);
+        }
+        if let Some(layout) = layer.layout {
+            Self::record_provenance(provenance, "layout".to_string(), "(configured)".to_string(), source.clone());
+            base.layout = Some(layout);
+        }
+        if let Some(policy) = layer.policy {
+            // Guardrails are admin policy: only the system layer may
+            // supply them, so a user file cannot set or relax the rules
+            if matches!(source, ConfigLayer::SystemFile(_)) {
+                Self::record_provenance(provenance, "policy".to_string(), "(configured)".to_string(), source.clone());
+                base.policy = Some(policy);
+            } else {
+                eprintln!("Ignoring [policy] from {}: guardrails are loaded from /etc/graph_os only", source);
+            }
+        }
+        if !layer.fallback.is_empty() {
+            Self::record_provenance(provenance, "fallback".to_string(), layer.fallback.join(" -> "), source);
+            base.fallback = layer.fallback;
+        }
+    }
+
+    /// Load configuration by merging layers in ascending precedence:
+    /// built-in defaults, the system config file, the user config
+    /// file, then environment variables
     pub async fn load() -> Self {
-        let apis = Self::load_api_config().await;
-        let default_provider = Self::get_default_provider(&apis);
-        let auth = Self::load_auth_config();
-        
+        let mut provenance = Vec::new();
+        let apis = Self::load_api_config(&mut provenance);
+        let default_provider = Self::get_default_provider(&apis, &mut provenance);
+        let mut auth = Self::load_auth_config_layered(&mut provenance);
+
+        // Decrypt enc: secret fields for the running process only; the
+        // files on disk keep their blobs. A missing or wrong passphrase
+        // warns and leaves the blob so unrelated commands still work.
+        if let Some(auth) = auth.as_mut() {
+            auth.decrypt_secret_fields_at_load();
+        }
+
+        // Environment outranks both config files for the RPC secret
+        if let Ok(secret) = env::var("GRAPHOS_RPC_SECRET") {
+            Self::record_provenance(
+                &mut provenance,
+                "rpc_secret".to_string(),
+                redact(&secret),
+                ConfigLayer::Environment("GRAPHOS_RPC_SECRET".to_string()),
+            );
+            auth.get_or_insert_with(AuthConfig::default).rpc_secret = Some(secret);
+        }
+
         Self {
             apis,
             default_provider,
             auth,
+            provenance,
         }
     }
+
+    /// Apply the global `--provider` / `--model` flags on top of the
+    /// loaded configuration; flags are the highest-precedence layer
+    pub fn apply_flag_overrides(&mut self, provider: Option<&str>, model: Option<&str>) {
+        if let Some(name) = provider
+            && let Some(provider) = ApiProvider::parse(name)
+            && self.apis.contains_key(&provider) {
+                self.default_provider = Some(provider);
+                Self::record_provenance(
+                    &mut self.provenance,
+                    "default_provider".to_string(),
+                    provider.to_string(),
+                    ConfigLayer::Flag("--provider".to_string()),
+                );
+            }
+        if let Some(model) = model
+            && let Some(provider) = self.default_provider
+            && let Some(api) = self.apis.get_mut(&provider) {
+                api.model = Some(model.to_string());
+                Self::record_provenance(
+                    &mut self.provenance,
+                    format!("apis.{}.model", provider.to_string().to_lowercase()),
+                    model.to_string(),
+                    ConfigLayer::Flag("--model".to_string()),
+                );
+            }
+    }
     
     /// Get the API configuration for the specified provider
     pub fn get_api_config(&self, provider: ApiProvider) -> Option<ApiConfig> {
@@ -300,11 +939,10 @@ impl Config {
     /// Get the authentication secret for GraphOS RPC
     pub fn get_rpc_secret(&self) -> Option<String> {
         // First check if it's in the auth config
-        if let Some(auth) = &self.auth {
-            if let Some(secret) = &auth.rpc_secret {
+        if let Some(auth) = &self.auth
+            && let Some(secret) = &auth.rpc_secret {
                 return Some(secret.clone());
             }
-        }
         
         None
     }
@@ -314,6 +952,142 @@ impl Config {
         self.auth.as_ref()
             .and_then(|auth| auth.endpoints.get(name).cloned())
     }
+    
+    /// Find the first auth config file that exists on disk
+    pub fn find_auth_config_file() -> Option<(PathBuf, ConfigFormat)> {
+        Self::get_auth_config_paths()
+            .into_iter()
+            .find(|(path, _)| path.exists())
+    }
+    
+    /// Get the named conversation template, if configured
+    pub fn get_template(&self, name: &str) -> Option<Template> {
+        self.auth.as_ref()
+            .and_then(|auth| auth.templates.get(name).cloned())
+    }
+    
+    /// Get all configured conversation templates
+    pub fn templates(&self) -> HashMap<String, Template> {
+        self.auth.as_ref()
+            .map(|auth| auth.templates.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the named persona, if configured
+    pub fn get_persona(&self, name: &str) -> Option<PersonaConfig> {
+        self.auth.as_ref()
+            .and_then(|auth| auth.personas.get(name).cloned())
+    }
+
+    /// Get all configured personas
+    pub fn personas(&self) -> HashMap<String, PersonaConfig> {
+        self.auth.as_ref()
+            .map(|auth| auth.personas.clone())
+            .unwrap_or_default()
+    }
+    
+    /// Get the configured per-model price table
+    pub fn prices(&self) -> HashMap<String, ModelPrice> {
+        self.auth.as_ref()
+            .map(|auth| auth.prices.clone())
+            .unwrap_or_default()
+    }
+    
+    /// Get the configured notification hooks
+    pub fn hooks(&self) -> HooksConfig {
+        self.auth.as_ref()
+            .map(|auth| auth.hooks.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the configured transcript share endpoint
+    pub fn share(&self) -> Option<ShareConfig> {
+        self.auth.as_ref().and_then(|auth| auth.share.clone())
+    }
+
+    /// Whether the config asks for accessible rendering
+    pub fn accessible(&self) -> bool {
+        self.auth
+            .as_ref()
+            .and_then(|auth| auth.accessible)
+            .unwrap_or(false)
+    }
+
+    /// Get the output filter configuration
+    pub fn filters(&self) -> FiltersConfig {
+        self.auth
+            .as_ref()
+            .and_then(|auth| auth.filters.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the outbound secret scanning configuration
+    pub fn redact(&self) -> RedactConfig {
+        self.auth
+            .as_ref()
+            .and_then(|auth| auth.redact.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the admin guardrail policy
+    pub fn policy(&self) -> PolicyConfig {
+        self.auth
+            .as_ref()
+            .and_then(|auth| auth.policy.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the metric naming configuration for system-info export
+    pub fn metrics(&self) -> MetricsConfig {
+        self.auth
+            .as_ref()
+            .and_then(|auth| auth.metrics.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the provider fallback chain, dropping names that do not
+    /// parse as a provider
+    pub fn fallback(&self) -> Vec<ApiProvider> {
+        self.auth
+            .as_ref()
+            .map(|auth| {
+                auth.fallback
+                    .iter()
+                    .filter_map(|name| ApiProvider::parse(name))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get the archive backend configuration
+    pub fn archive(&self) -> ArchiveConfig {
+        self.auth
+            .as_ref()
+            .and_then(|auth| auth.archive.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the configured slash command aliases
+    pub fn aliases(&self) -> HashMap<String, String> {
+        self.auth.as_ref()
+            .map(|auth| auth.aliases.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the recorded keyboard macros
+    pub fn macros(&self) -> HashMap<String, Vec<String>> {
+        self.auth.as_ref()
+            .map(|auth| auth.macros.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the TUI layout preferences
+    pub fn layout(&self) -> LayoutConfig {
+        self.auth
+            .as_ref()
+            .and_then(|auth| auth.layout.clone())
+            .unwrap_or_default()
+    }
 }
 
 // Singleton configuration instance
@@ -353,10 +1127,7 @@ impl ConfigManager {
     
     /// Create a new, empty auth config file at the default location
     pub async fn create_default_auth_config(&self, format: ConfigFormat) -> Result<PathBuf> {
-        let home_dir = dirs::home_dir()
-            .ok_or_else(|| anyhow!("Could not determine home directory"))?;
-        
-        let config_dir = home_dir.join(".graph_os");
+        let config_dir = crate::paths::config_dir();
         
         // Create the directory if it doesn't exist
         if !config_dir.exists() {
@@ -370,6 +1141,21 @@ impl ConfigManager {
         let default_auth = AuthConfig {
             rpc_secret: None,
             endpoints: HashMap::new(),
+            templates: HashMap::new(),
+            personas: HashMap::new(),
+            prices: HashMap::new(),
+            aliases: HashMap::new(),
+            macros: HashMap::new(),
+            hooks: HooksConfig::default(),
+            share: None,
+            accessible: None,
+            filters: None,
+            redact: None,
+            metrics: None,
+            archive: None,
+            layout: None,
+            policy: None,
+            fallback: Vec::new(),
         };
         
         // Serialize config based on format
@@ -383,18 +1169,14 @@ impl ConfigManager {
         };
         
         // Write config to file
-        fs::write(&config_path, content)
-            .with_context(|| format!("Failed to write config to {}", config_path.display()))?;
+        write_config_file(&config_path, &content)?;
         
         Ok(config_path)
     }
     
     /// Update the auth config with a new RPC secret
     pub async fn set_rpc_secret(&self, secret: &str, format: ConfigFormat) -> Result<PathBuf> {
-        let home_dir = dirs::home_dir()
-            .ok_or_else(|| anyhow!("Could not determine home directory"))?;
-        
-        let config_dir = home_dir.join(".graph_os");
+        let config_dir = crate::paths::config_dir();
         
         // Create the directory if it doesn't exist
         if !config_dir.exists() {
@@ -410,11 +1192,41 @@ impl ConfigManager {
                 .unwrap_or_else(|_| AuthConfig {
                     rpc_secret: None,
                     endpoints: HashMap::new(),
+                    templates: HashMap::new(),
+                    personas: HashMap::new(),
+                    prices: HashMap::new(),
+                    aliases: HashMap::new(),
+                    macros: HashMap::new(),
+                    hooks: HooksConfig::default(),
+                    share: None,
+                    accessible: None,
+                    filters: None,
+                    redact: None,
+                    metrics: None,
+                    archive: None,
+                    layout: None,
+                    policy: None,
+                    fallback: Vec::new(),
                 })
         } else {
             AuthConfig {
                 rpc_secret: None,
                 endpoints: HashMap::new(),
+                templates: HashMap::new(),
+                personas: HashMap::new(),
+                prices: HashMap::new(),
+                aliases: HashMap::new(),
+                macros: HashMap::new(),
+                hooks: HooksConfig::default(),
+                share: None,
+                accessible: None,
+                filters: None,
+                redact: None,
+                metrics: None,
+                archive: None,
+                layout: None,
+                policy: None,
+                fallback: Vec::new(),
             }
         };
         
@@ -432,8 +1244,7 @@ impl ConfigManager {
         };
         
         // Write config to file
-        fs::write(&config_path, content)
-            .with_context(|| format!("Failed to write config to {}", config_path.display()))?;
+        write_config_file(&config_path, &content)?;
         
         // Reload config
         self.load().await?;
@@ -443,10 +1254,7 @@ impl ConfigManager {
     
     /// Add or update an endpoint configuration
     pub async fn set_endpoint_config(&self, name: &str, endpoint: EndpointConfig, format: ConfigFormat) -> Result<PathBuf> {
-        let home_dir = dirs::home_dir()
-            .ok_or_else(|| anyhow!("Could not determine home directory"))?;
-        
-        let config_dir = home_dir.join(".graph_os");
+        let config_dir = crate::paths::config_dir();
         
         // Create the directory if it doesn't exist
         if !config_dir.exists() {
@@ -462,11 +1270,41 @@ impl ConfigManager {
                 .unwrap_or_else(|_| AuthConfig {
                     rpc_secret: None,
                     endpoints: HashMap::new(),
+                    templates: HashMap::new(),
+                    personas: HashMap::new(),
+                    prices: HashMap::new(),
+                    aliases: HashMap::new(),
+                    macros: HashMap::new(),
+                    hooks: HooksConfig::default(),
+                    share: None,
+                    accessible: None,
+                    filters: None,
+                    redact: None,
+                    metrics: None,
+                    archive: None,
+                    layout: None,
+                    policy: None,
+                    fallback: Vec::new(),
                 })
         } else {
             AuthConfig {
                 rpc_secret: None,
                 endpoints: HashMap::new(),
+                templates: HashMap::new(),
+                personas: HashMap::new(),
+                prices: HashMap::new(),
+                aliases: HashMap::new(),
+                macros: HashMap::new(),
+                hooks: HooksConfig::default(),
+                share: None,
+                accessible: None,
+                filters: None,
+                redact: None,
+                metrics: None,
+                archive: None,
+                layout: None,
+                policy: None,
+                fallback: Vec::new(),
             }
         };
         
@@ -484,12 +1322,721 @@ impl ConfigManager {
         };
         
         // Write config to file
-        fs::write(&config_path, content)
-            .with_context(|| format!("Failed to write config to {}", config_path.display()))?;
+        write_config_file(&config_path, &content)?;
         
         // Reload config
         self.load().await?;
-        
+
         Ok(config_path)
     }
-}
\ No newline at end of file
+
+    /// Persist the TUI layout preferences into the user config file.
+    /// Reuses the existing user config (whatever its format) so a
+    /// layout tweak never forks a second config file; falls back to
+    /// TOML when no config exists yet.
+    pub async fn set_layout(&self, layout: LayoutConfig) -> Result<PathBuf> {
+        let config_dir = crate::paths::config_dir();
+
+        // Create the directory if it doesn't exist
+        if !config_dir.exists() {
+            fs::create_dir_all(&config_dir)
+                .context("Failed to create config directory")?;
+        }
+
+        let (config_path, format) = Config::user_config_paths()
+            .into_iter()
+            .find(|(path, _)| path.exists())
+            .unwrap_or_else(|| {
+                (config_dir.join("config.toml"), ConfigFormat::Toml)
+            });
+
+        // Try to load existing config or create a new one
+        let mut auth_config = if config_path.exists() {
+            Config::load_auth_config_from_file(&config_path, format)
+                .unwrap_or_default()
+        } else {
+            AuthConfig::default()
+        };
+
+        // Update config with the new layout preferences
+        auth_config.layout = Some(layout);
+
+        // Serialize config based on format
+        let content = match format {
+            ConfigFormat::Json => serde_json::to_string_pretty(&auth_config)
+                .context("Failed to serialize config to JSON")?,
+            ConfigFormat::Yaml => serde_yaml::to_string(&auth_config)
+                .context("Failed to serialize config to YAML")?,
+            ConfigFormat::Toml => toml::to_string(&auth_config)
+                .context("Failed to serialize config to TOML")?,
+        };
+
+        // Write config to file
+        write_config_file(&config_path, &content)?;
+
+        // Reload config
+        self.load().await?;
+
+        Ok(config_path)
+    }
+
+    /// Persist the recorded keyboard macros into the user config file,
+    /// replacing the whole `[macros]` table. Reuses the existing user
+    /// config (whatever its format) like [`Self::set_layout`] does.
+    pub async fn set_macros(&self, macros: HashMap<String, Vec<String>>) -> Result<PathBuf> {
+        let config_dir = crate::paths::config_dir();
+
+        if !config_dir.exists() {
+            fs::create_dir_all(&config_dir)
+                .context("Failed to create config directory")?;
+        }
+
+        let (config_path, format) = Config::user_config_paths()
+            .into_iter()
+            .find(|(path, _)| path.exists())
+            .unwrap_or_else(|| {
+                (config_dir.join("config.toml"), ConfigFormat::Toml)
+            });
+
+        let mut auth_config = if config_path.exists() {
+            Config::load_auth_config_from_file(&config_path, format)
+                .unwrap_or_default()
+        } else {
+            AuthConfig::default()
+        };
+
+        auth_config.macros = macros;
+
+        let content = match format {
+            ConfigFormat::Json => serde_json::to_string_pretty(&auth_config)
+                .context("Failed to serialize config to JSON")?,
+            ConfigFormat::Yaml => serde_yaml::to_string(&auth_config)
+                .context("Failed to serialize config to YAML")?,
+            ConfigFormat::Toml => toml::to_string(&auth_config)
+                .context("Failed to serialize config to TOML")?,
+        };
+
+        write_config_file(&config_path, &content)?;
+
+        // Reload config
+        self.load().await?;
+
+        Ok(config_path)
+    }
+
+    /// Encrypt (or decrypt, when `encrypt` is false) the secret fields
+    /// of the config file on disk in place. Returns the path rewritten
+    /// and how many fields changed. Backs `gos config encrypt/decrypt`.
+    pub async fn transform_config_secrets(&self, format: ConfigFormat, encrypt: bool) -> Result<(PathBuf, usize)> {
+        let config_path = crate::paths::config_dir().join(format!("config.{}", format.extension()));
+        if !config_path.exists() {
+            return Err(anyhow!("No config file found at {}", config_path.display()));
+        }
+
+        // Loading straight from the file keeps existing enc: blobs
+        // untouched, so re-running encrypt is a no-op for them
+        let mut auth_config = Config::load_auth_config_from_file(&config_path, format)?;
+
+        let cipher = crate::crypto::config_cipher().ok_or_else(|| {
+            anyhow!(
+                "No config passphrase available; set {} or run interactively",
+                crate::crypto::CONFIG_PASSPHRASE_ENV
+            )
+        })?;
+
+        let changed = if encrypt {
+            auth_config.encrypt_secret_fields(cipher)?
+        } else {
+            auth_config.decrypt_secret_fields(cipher)?
+        };
+
+        // Serialize config based on format
+        let content = match format {
+            ConfigFormat::Json => serde_json::to_string_pretty(&auth_config)
+                .context("Failed to serialize config to JSON")?,
+            ConfigFormat::Yaml => serde_yaml::to_string(&auth_config)
+                .context("Failed to serialize config to YAML")?,
+            ConfigFormat::Toml => toml::to_string(&auth_config)
+                .context("Failed to serialize config to TOML")?,
+        };
+
+        // Write config to file
+        write_config_file(&config_path, &content)?;
+
+        // Reload config
+        self.load().await?;
+
+        Ok((config_path, changed))
+    }
+}
+/// Write a config file readable only by its owner, since the contents
+/// can include API keys and tokens. Unix tightens the mode to 0600;
+/// Windows scopes access through the per-user profile ACLs, so no extra
+/// step is needed there.
+fn write_config_file(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content)
+        .with_context(|| format!("Failed to write config to {}", path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to set permissions on {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Result of validating an auth config file. Errors make the file
+/// unusable; warnings flag likely mistakes that do not block loading.
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Parse a config file into a generic value and validate it against the
+/// known schema. Parse failures carry the parser's line/column positions.
+pub fn validate_auth_config_file(path: &Path, format: ConfigFormat) -> Result<ValidationReport> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+
+    let value: serde_json::Value = match format {
+        ConfigFormat::Json => serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse JSON config file: {}", path.display()))?,
+        ConfigFormat::Yaml => serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse YAML config file: {}", path.display()))?,
+        ConfigFormat::Toml => {
+            let value: toml::Value = toml::from_str(&content)
+                .with_context(|| format!("Failed to parse TOML config file: {}", path.display()))?;
+            serde_json::to_value(value)?
+        }
+    };
+
+    Ok(validate_auth_config_value(&value))
+}
+
+/// Validate a parsed config document against the known schema, reporting
+/// unknown keys, type mismatches, missing fields and conflicting settings
+/// with their full key path.
+pub fn validate_auth_config_value(value: &serde_json::Value) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    let Some(root) = value.as_object() else {
+        report.errors.push("config root must be a table".to_string());
+        return report;
+    };
+
+    for key in root.keys() {
+        if !matches!(key.as_str(), "rpc_secret" | "endpoints" | "templates" | "personas" | "prices" | "aliases" | "macros" | "hooks" | "share" | "accessible" | "filters" | "redact" | "metrics" | "archive" | "layout" | "policy" | "fallback") {
+            report.warnings.push(format!("unknown key '{}'", key));
+        }
+    }
+
+    if let Some(secret) = root.get("rpc_secret")
+        && !secret.is_string()
+        && !secret.is_null()
+    {
+        report.errors.push("rpc_secret: expected a string".to_string());
+    }
+
+    match root.get("endpoints") {
+        Some(serde_json::Value::Object(endpoints)) => {
+            for (name, endpoint) in endpoints {
+                validate_endpoint(name, endpoint, &mut report);
+            }
+        }
+        Some(_) => report.errors.push("endpoints: expected a table".to_string()),
+        None => {}
+    }
+
+    match root.get("templates") {
+        Some(serde_json::Value::Object(templates)) => {
+            for (name, template) in templates {
+                validate_template(name, template, &mut report);
+            }
+        }
+        Some(_) => report.errors.push("templates: expected a table".to_string()),
+        None => {}
+    }
+
+    match root.get("personas") {
+        Some(serde_json::Value::Object(personas)) => {
+            for (name, persona) in personas {
+                validate_persona(name, persona, &mut report);
+            }
+        }
+        Some(_) => report.errors.push("personas: expected a table".to_string()),
+        None => {}
+    }
+
+    match root.get("prices") {
+        Some(serde_json::Value::Object(prices)) => {
+            for (model, price) in prices {
+                validate_price(model, price, &mut report);
+            }
+        }
+        Some(_) => report.errors.push("prices: expected a table".to_string()),
+        None => {}
+    }
+
+    match root.get("hooks") {
+        Some(serde_json::Value::Object(hooks)) => validate_hooks(hooks, &mut report),
+        Some(_) => report.errors.push("hooks: expected a table".to_string()),
+        None => {}
+    }
+
+    match root.get("share") {
+        Some(serde_json::Value::Object(share)) => validate_share(share, &mut report),
+        Some(_) => report.errors.push("share: expected a table".to_string()),
+        None => {}
+    }
+
+    match root.get("filters") {
+        Some(serde_json::Value::Object(filters)) => validate_filters(filters, &mut report),
+        Some(_) => report.errors.push("filters: expected a table".to_string()),
+        None => {}
+    }
+
+    match root.get("redact") {
+        Some(serde_json::Value::Object(redact)) => validate_redact(redact, &mut report),
+        Some(_) => report.errors.push("redact: expected a table".to_string()),
+        None => {}
+    }
+
+    match root.get("metrics") {
+        Some(serde_json::Value::Object(metrics)) => validate_metrics(metrics, &mut report),
+        Some(_) => report.errors.push("metrics: expected a table".to_string()),
+        None => {}
+    }
+
+    match root.get("archive") {
+        Some(serde_json::Value::Object(archive)) => validate_archive(archive, &mut report),
+        Some(_) => report.errors.push("archive: expected a table".to_string()),
+        None => {}
+    }
+
+    if let Some(accessible) = root.get("accessible")
+        && !accessible.is_boolean()
+        && !accessible.is_null()
+    {
+        report.errors.push("accessible: expected a boolean".to_string());
+    }
+
+    match root.get("fallback") {
+        Some(serde_json::Value::Array(providers)) => {
+            for (i, provider) in providers.iter().enumerate() {
+                if !provider.is_string() {
+                    report.errors.push(format!("fallback[{}]: expected a provider name", i));
+                }
+            }
+        }
+        Some(_) => report.errors.push("fallback: expected an array of provider names".to_string()),
+        None => {}
+    }
+
+    report
+}
+
+fn validate_hooks(hooks: &serde_json::Map<String, serde_json::Value>, report: &mut ValidationReport) {
+    if let Some(desktop) = hooks.get("desktop")
+        && !desktop.is_boolean()
+    {
+        report.errors.push("hooks.desktop: expected a boolean".to_string());
+    }
+
+    if let Some(min_seconds) = hooks.get("min_seconds")
+        && !min_seconds.is_u64()
+        && !min_seconds.is_null()
+    {
+        report.errors.push("hooks.min_seconds: expected a non-negative integer".to_string());
+    }
+
+    if let Some(webhook) = hooks.get("webhook")
+        && !webhook.is_string()
+        && !webhook.is_null()
+    {
+        report.errors.push("hooks.webhook: expected a string URL".to_string());
+    }
+
+    match hooks.get("commands") {
+        Some(serde_json::Value::Object(commands)) => {
+            for (event, command) in commands {
+                if !command.is_string() {
+                    report.errors.push(format!("hooks.commands.{}: expected a string", event));
+                }
+            }
+        }
+        Some(_) => report.errors.push("hooks.commands: expected a table".to_string()),
+        None => {}
+    }
+
+    for key in hooks.keys() {
+        if !matches!(key.as_str(), "desktop" | "min_seconds" | "webhook" | "commands") {
+            report.warnings.push(format!("hooks.{}: unknown key", key));
+        }
+    }
+}
+
+fn validate_filters(filters: &serde_json::Map<String, serde_json::Value>, report: &mut ValidationReport) {
+    for flag in ["trim_trailing_whitespace", "redact_secrets", "profanity", "unfurl_links"] {
+        if let Some(value) = filters.get(flag)
+            && !value.is_boolean()
+            && !value.is_null()
+        {
+            report.errors.push(format!("filters.{}: expected a boolean", flag));
+        }
+    }
+
+    match filters.get("replace") {
+        Some(serde_json::Value::Array(rules)) => {
+            for (index, rule) in rules.iter().enumerate() {
+                let Some(rule) = rule.as_object() else {
+                    report.errors.push(format!("filters.replace[{}]: expected a table", index));
+                    continue;
+                };
+                for required in ["pattern", "replacement"] {
+                    match rule.get(required) {
+                        Some(value) if value.is_string() => {}
+                        Some(_) => report.errors.push(format!(
+                            "filters.replace[{}].{}: expected a string", index, required
+                        )),
+                        None => report.errors.push(format!(
+                            "filters.replace[{}].{}: missing required key", index, required
+                        )),
+                    }
+                }
+            }
+        }
+        Some(_) => report.errors.push("filters.replace: expected an array of tables".to_string()),
+        None => {}
+    }
+
+    for key in filters.keys() {
+        if !matches!(
+            key.as_str(),
+            "trim_trailing_whitespace" | "redact_secrets" | "profanity" | "unfurl_links" | "replace"
+        ) {
+            report.warnings.push(format!("filters.{}: unknown key", key));
+        }
+    }
+}
+
+fn validate_redact(redact: &serde_json::Map<String, serde_json::Value>, report: &mut ValidationReport) {
+    if let Some(mode) = redact.get("mode")
+        && !mode.is_null()
+    {
+        match mode.as_str() {
+            Some("warn") | Some("block") | Some("mask") => {}
+            _ => report.errors.push("redact.mode: expected \"warn\", \"block\" or \"mask\"".to_string()),
+        }
+    }
+
+    if let Some(entropy) = redact.get("entropy")
+        && !entropy.is_boolean()
+        && !entropy.is_null()
+    {
+        report.errors.push("redact.entropy: expected a boolean".to_string());
+    }
+
+    match redact.get("patterns") {
+        Some(serde_json::Value::Array(patterns)) => {
+            for (index, pattern) in patterns.iter().enumerate() {
+                let Some(pattern) = pattern.as_object() else {
+                    report.errors.push(format!("redact.patterns[{}]: expected a table", index));
+                    continue;
+                };
+                for required in ["name", "pattern"] {
+                    match pattern.get(required) {
+                        Some(value) if value.is_string() => {}
+                        Some(_) => report.errors.push(format!(
+                            "redact.patterns[{}].{}: expected a string", index, required
+                        )),
+                        None => report.errors.push(format!(
+                            "redact.patterns[{}].{}: missing required key", index, required
+                        )),
+                    }
+                }
+            }
+        }
+        Some(_) => report.errors.push("redact.patterns: expected an array of tables".to_string()),
+        None => {}
+    }
+
+    for key in redact.keys() {
+        if !matches!(key.as_str(), "mode" | "entropy" | "patterns") {
+            report.warnings.push(format!("redact.{}: unknown key", key));
+        }
+    }
+}
+
+fn validate_archive(archive: &serde_json::Map<String, serde_json::Value>, report: &mut ValidationReport) {
+    if let Some(backend) = archive.get("backend")
+        && !backend.is_null()
+    {
+        match backend.as_str() {
+            Some("local") | Some("s3") | Some("webdav") => {}
+            _ => report.errors.push(
+                "archive.backend: expected \"local\", \"s3\" or \"webdav\"".to_string(),
+            ),
+        }
+    }
+
+    let keys = [
+        "backend", "path", "url", "bucket", "region", "access_key", "secret_key", "username",
+        "password",
+    ];
+    for key in keys.iter().skip(1) {
+        if let Some(value) = archive.get(*key)
+            && !value.is_string()
+            && !value.is_null()
+        {
+            report.errors.push(format!("archive.{}: expected a string", key));
+        }
+    }
+
+    for key in archive.keys() {
+        if !keys.contains(&key.as_str()) {
+            report.warnings.push(format!("archive.{}: unknown key", key));
+        }
+    }
+}
+
+fn validate_metrics(metrics: &serde_json::Map<String, serde_json::Value>, report: &mut ValidationReport) {
+    if let Some(prefix) = metrics.get("prefix")
+        && !prefix.is_string()
+        && !prefix.is_null()
+    {
+        report.errors.push("metrics.prefix: expected a string".to_string());
+    }
+
+    match metrics.get("rename") {
+        Some(serde_json::Value::Object(entries)) => {
+            for (column, name) in entries {
+                if !crate::report::EXPORT_COLUMNS.contains(&column.as_str()) {
+                    report.warnings.push(format!("metrics.rename.{}: unknown column", column));
+                }
+                if !name.is_string() {
+                    report.errors.push(format!("metrics.rename.{}: expected a string", column));
+                }
+            }
+        }
+        Some(_) => report.errors.push("metrics.rename: expected a table".to_string()),
+        None => {}
+    }
+
+    for key in metrics.keys() {
+        if !matches!(key.as_str(), "prefix" | "rename") {
+            report.warnings.push(format!("metrics.{}: unknown key", key));
+        }
+    }
+}
+
+fn validate_share(share: &serde_json::Map<String, serde_json::Value>, report: &mut ValidationReport) {
+    match share.get("url") {
+        Some(url) if url.is_string() => {}
+        Some(_) => report.errors.push("share.url: expected a string URL".to_string()),
+        None => report.errors.push("share.url: missing required key".to_string()),
+    }
+
+    if let Some(kind) = share.get("kind")
+        && !kind.is_null()
+    {
+        match kind.as_str() {
+            Some("graphos") | Some("paste") => {}
+            _ => report.errors.push("share.kind: expected \"graphos\" or \"paste\"".to_string()),
+        }
+    }
+
+    if let Some(token) = share.get("token")
+        && !token.is_string()
+        && !token.is_null()
+    {
+        report.errors.push("share.token: expected a string".to_string());
+    }
+
+    for key in share.keys() {
+        if !matches!(key.as_str(), "url" | "kind" | "token") {
+            report.warnings.push(format!("share.{}: unknown key", key));
+        }
+    }
+}
+
+fn validate_price(model: &str, value: &serde_json::Value, report: &mut ValidationReport) {
+    let path = format!("prices.{}", model);
+
+    let Some(price) = value.as_object() else {
+        report.errors.push(format!("{}: expected a table", path));
+        return;
+    };
+
+    for required in ["input_per_1k", "output_per_1k"] {
+        match price.get(required) {
+            Some(v) if v.is_number() => {}
+            Some(_) => report.errors.push(format!("{}.{}: expected a number", path, required)),
+            None => report.errors.push(format!("{}.{}: missing required field", path, required)),
+        }
+    }
+
+    for key in price.keys() {
+        if !matches!(key.as_str(), "input_per_1k" | "output_per_1k") {
+            report.warnings.push(format!("{}.{}: unknown key", path, key));
+        }
+    }
+}
+
+fn validate_endpoint(name: &str, value: &serde_json::Value, report: &mut ValidationReport) {
+    let path = format!("endpoints.{}", name);
+
+    let Some(endpoint) = value.as_object() else {
+        report.errors.push(format!("{}: expected a table", path));
+        return;
+    };
+
+    // Required fields
+    match endpoint.get("url") {
+        Some(serde_json::Value::String(url)) if !url.is_empty() => {}
+        Some(serde_json::Value::String(_)) => {
+            report.errors.push(format!("{}.url: must not be empty", path));
+        }
+        Some(_) => report.errors.push(format!("{}.url: expected a string", path)),
+        None => report.errors.push(format!("{}.url: missing required field", path)),
+    }
+
+    // Per-key types
+    for (key, field) in endpoint {
+        match key.as_str() {
+            "url" => {}
+            "secret" | "token" | "proxy" | "ca_bundle" | "oidc_issuer" | "oidc_client_id"
+            | "refresh_token" | "signing_key_id" | "signing_key" | "embedding_model" => {
+                if !field.is_string() && !field.is_null() {
+                    report.errors.push(format!("{}.{}: expected a string", path, key));
+                }
+            }
+            "token_expiry" => {
+                if !field.is_i64() && !field.is_null() {
+                    report.errors.push(format!("{}.{}: expected an integer timestamp", path, key));
+                }
+            }
+            "use_tls" | "danger_accept_invalid_certs" | "compress" => {
+                if !field.is_boolean() && !field.is_null() {
+                    report.errors.push(format!("{}.{}: expected a boolean", path, key));
+                }
+            }
+            "compress_threshold" => {
+                if !field.is_u64() && !field.is_null() {
+                    report.errors.push(format!("{}.{}: expected a non-negative integer", path, key));
+                }
+            }
+            "extra_headers" | "query_params" => match field.as_object() {
+                Some(entries) => {
+                    for (entry_key, entry_value) in entries {
+                        if !entry_value.is_string() {
+                            report.errors.push(format!(
+                                "{}.{}.{}: expected a string", path, key, entry_key
+                            ));
+                        }
+                    }
+                }
+                None => report.errors.push(format!("{}.{}: expected a table", path, key)),
+            },
+            "transport" => match field.as_str() {
+                Some("jsonrpc") | Some("grpc") => {}
+                Some(other) => report.errors.push(format!(
+                    "{}.transport: '{}' is not a valid transport (expected 'jsonrpc' or 'grpc')",
+                    path, other
+                )),
+                None => report.errors.push(format!("{}.transport: expected a string", path)),
+            },
+            // Deferring to the parser keeps the validator honest about
+            // which spellings the client will actually accept
+            "http_version" => match field.as_str() {
+                Some(version) if crate::adapters::HttpVersion::parse(version).is_some() => {}
+                Some(other) => report.errors.push(format!(
+                    "{}.http_version: '{}' is not a valid HTTP version (expected 'auto', 'http1' or 'http2')",
+                    path, other
+                )),
+                None => report.errors.push(format!("{}.http_version: expected a string", path)),
+            },
+            other => report.warnings.push(format!("{}.{}: unknown key", path, other)),
+        }
+    }
+
+    // Conflicting settings
+    let url = endpoint.get("url").and_then(|u| u.as_str()).unwrap_or("");
+    let use_tls = endpoint.get("use_tls").and_then(|t| t.as_bool());
+    if use_tls == Some(false) && url.starts_with("https://") {
+        report.errors.push(format!(
+            "{}: use_tls = false conflicts with an https:// url", path
+        ));
+    }
+    if use_tls == Some(true) && url.starts_with("http://") {
+        report.errors.push(format!(
+            "{}: use_tls = true conflicts with an http:// url", path
+        ));
+    }
+    if endpoint.get("danger_accept_invalid_certs").and_then(|v| v.as_bool()) == Some(true)
+        && endpoint.get("ca_bundle").is_some()
+    {
+        report.warnings.push(format!(
+            "{}: danger_accept_invalid_certs makes ca_bundle pointless", path
+        ));
+    }
+}
+
+fn validate_persona(name: &str, value: &serde_json::Value, report: &mut ValidationReport) {
+    let path = format!("personas.{}", name);
+
+    let Some(persona) = value.as_object() else {
+        report.errors.push(format!("{}: expected a table", path));
+        return;
+    };
+
+    for (key, field) in persona {
+        match key.as_str() {
+            "description" | "system" | "model" => {
+                if !field.is_string() && !field.is_null() {
+                    report.errors.push(format!("{}.{}: expected a string", path, key));
+                }
+            }
+            "provider" => match field.as_str() {
+                Some(provider) if ApiProvider::parse(provider).is_none() => {
+                    report.errors.push(format!(
+                        "{}.provider: unknown provider '{}' (expected openai, anthropic, gemini or custom)",
+                        path, provider
+                    ));
+                }
+                Some(_) => {}
+                None if field.is_null() => {}
+                None => report.errors.push(format!("{}.provider: expected a string", path)),
+            },
+            other => report.warnings.push(format!("{}.{}: unknown key", path, other)),
+        }
+    }
+}
+
+fn validate_template(name: &str, value: &serde_json::Value, report: &mut ValidationReport) {
+    let path = format!("templates.{}", name);
+
+    let Some(template) = value.as_object() else {
+        report.errors.push(format!("{}: expected a table", path));
+        return;
+    };
+
+    for (key, field) in template {
+        match key.as_str() {
+            "description" | "system" | "prompt" => {
+                if !field.is_string() && !field.is_null() {
+                    report.errors.push(format!("{}.{}: expected a string", path, key));
+                }
+            }
+            other => report.warnings.push(format!("{}.{}: unknown key", path, other)),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Workspace-aware context injected into the system prompt.
+//!
+//! `/context add <path>` attaches files (or the pseudo-path `repo` for
+//! git metadata) to the conversation. Attached context is rendered into
+//! the system prompt on every request, truncated to a token budget, and
+//! the set of paths persists with the session.
+
+use std::process::Command;
+
+use crate::usage::estimate_tokens;
+
+/// Token budget shared by all attached context
+pub const CONTEXT_TOKEN_BUDGET: u64 = 8_000;
+
+/// Pseudo-path that expands to git repository metadata
+pub const REPO_PSEUDO_PATH: &str = "repo";
+
+/// Run a git command in the current directory, returning trimmed stdout
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+/// Summarize the surrounding git repository: branch, recent commits and
+/// dirty files. None when not inside a repository.
+pub fn repo_metadata() -> Option<String> {
+    let branch = git(&["rev-parse", "--abbrev-ref", "HEAD"])?;
+    let commits = git(&["log", "--oneline", "-5"]).unwrap_or_default();
+    let dirty = git(&["status", "--porcelain"]).unwrap_or_default();
+
+    let mut out = format!("Git branch: {}\n", branch);
+    if !commits.is_empty() {
+        out.push_str(&format!("Recent commits:\n{}\n", commits));
+    }
+    if dirty.is_empty() {
+        out.push_str("Working tree clean\n");
+    } else {
+        out.push_str(&format!("Dirty files:\n{}\n", dirty));
+    }
+
+    Some(out)
+}
+
+/// Cut text down to roughly `budget` tokens; the bool reports whether
+/// anything was dropped
+pub fn truncate_to_budget(text: &str, budget: u64) -> (String, bool) {
+    if estimate_tokens(text) <= budget {
+        return (text.to_string(), false);
+    }
+
+    // estimate_tokens assumes about four characters per token
+    let max_chars = (budget * 4) as usize;
+    (text.chars().take(max_chars).collect(), true)
+}
+
+/// Resolve one context path to its current content
+pub fn resolve(path: &str) -> String {
+    if path == REPO_PSEUDO_PATH {
+        return repo_metadata().unwrap_or_else(|| "(not inside a git repository)".to_string());
+    }
+
+    std::fs::read_to_string(path).unwrap_or_else(|e| format!("(failed to read {}: {})", path, e))
+}
+
+/// Render the attached context block for the system prompt, spending at
+/// most `budget` estimated tokens across all entries
+pub fn render_context(paths: &[String], budget: u64) -> String {
+    let mut out = String::from("Workspace context:\n");
+    let mut remaining = budget;
+
+    for path in paths {
+        if remaining == 0 {
+            out.push_str(&format!("\n--- {} ---\n(omitted: context budget exhausted)\n", path));
+            continue;
+        }
+
+        let content = resolve(path);
+        let (content, truncated) = truncate_to_budget(&content, remaining);
+        remaining = remaining.saturating_sub(estimate_tokens(&content));
+
+        out.push_str(&format!("\n--- {} ---\n{}\n", path, content));
+        if truncated {
+            out.push_str("(truncated to fit the context budget)\n");
+        }
+    }
+
+    out
+}
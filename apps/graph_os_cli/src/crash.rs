@@ -0,0 +1,120 @@
+//! Crash recovery: panic-safe terminal restore and crash reports.
+//!
+//! A panic inside the TUI used to leave the terminal in raw mode on
+//! the alternate screen, with the panic message invisible and the
+//! conversation apparently gone. The hook installed here restores the
+//! terminal first (so the message is readable), writes a crash report
+//! with a backtrace to the crash directory, and records which session
+//! was interrupted so the next launch can offer to resume it. The
+//! session content itself needs no flushing: the chat persists the
+//! conversation after every message, so the marker is enough to pick
+//! up where the crash happened.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+/// The session the running TUI has open, picked up by the panic hook;
+/// None outside the chat or after a clean exit
+static ACTIVE_SESSION: Mutex<Option<Uuid>> = Mutex::new(None);
+
+/// Where crash reports and the interrupted-session marker live
+pub fn crash_dir() -> PathBuf {
+    crate::paths::state_dir().join("crash")
+}
+
+fn marker_path() -> PathBuf {
+    crash_dir().join("interrupted")
+}
+
+/// Mark a session as open so a crash can point back at it
+pub fn set_active_session(id: Uuid) {
+    if let Ok(mut active) = ACTIVE_SESSION.lock() {
+        *active = Some(id);
+    }
+}
+
+/// Clear the mark on a clean exit, so the next launch does not offer
+/// to resume a conversation that ended normally
+pub fn clear_active_session() {
+    if let Ok(mut active) = ACTIVE_SESSION.lock() {
+        *active = None;
+    }
+}
+
+/// The session a crashed run left behind, consuming the marker so the
+/// offer is made exactly once
+pub fn take_interrupted_session() -> Option<Uuid> {
+    let path = marker_path();
+    let contents = fs::read_to_string(&path).ok()?;
+    let _ = fs::remove_file(&path);
+    Uuid::parse_str(contents.trim()).ok()
+}
+
+/// Install the panic hook, once per process. The hook restores the
+/// terminal before anything else so the panic message actually shows,
+/// then writes the report and marker and runs the default hook.
+pub fn install_panic_hook() {
+    static INSTALLED: std::sync::OnceLock<()> = std::sync::OnceLock::new();
+    INSTALLED.get_or_init(|| {
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let _ = crate::chat::restore_terminal();
+
+            let session = ACTIVE_SESSION.lock().ok().and_then(|active| *active);
+            match write_crash_report(info, session) {
+                Ok(path) => eprintln!("Crash report written to {}", path.display()),
+                Err(e) => eprintln!("Failed to write crash report: {}", e),
+            }
+            if let Some(id) = session {
+                let _ = fs::write(marker_path(), id.to_string());
+                eprintln!("Session {} was interrupted; the next launch will offer to resume it.", id);
+            }
+
+            default_hook(info);
+        }));
+    });
+}
+
+/// Write one timestamped report file and return its path
+fn write_crash_report(
+    info: &std::panic::PanicHookInfo<'_>,
+    session: Option<Uuid>,
+) -> std::io::Result<PathBuf> {
+    let dir = crash_dir();
+    fs::create_dir_all(&dir)?;
+    let now = Utc::now();
+    let path = dir.join(format!("crash-{}.txt", now.format("%Y%m%d-%H%M%S")));
+
+    // The payload is a &str or String for every panic! the code raises;
+    // anything else gets a placeholder rather than nothing
+    let message = if let Some(s) = info.payload().downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = info.payload().downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "(non-string panic payload)".to_string()
+    };
+    let location = info
+        .location()
+        .map(|l| l.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let session = session
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| "none".to_string());
+
+    let report = format!(
+        "gos {} crashed at {}\nlocation: {}\nsession: {}\nmessage: {}\n\nbacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        now.to_rfc3339(),
+        location,
+        session,
+        message,
+        std::backtrace::Backtrace::force_capture()
+    );
+    fs::write(&path, report)?;
+    Ok(path)
+}
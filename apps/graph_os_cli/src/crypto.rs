@@ -0,0 +1,230 @@
+use std::env;
+use std::num::NonZeroU32;
+
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// Magic prefix marking an encrypted session file
+const MAGIC: &[u8] = b"GOSENC1";
+
+/// Salt length for key derivation, stored per file
+const SALT_LEN: usize = 16;
+
+/// ChaCha20-Poly1305 nonce length
+const NONCE_LEN: usize = 12;
+
+/// PBKDF2 iteration count for deriving the file key from the passphrase
+const PBKDF2_ITERATIONS: u32 = 100_000;
+
+/// Environment variable holding the session encryption passphrase. This
+/// stands in for an OS keyring entry on platforms where none is available.
+pub const PASSPHRASE_ENV: &str = "GRAPHOS_SESSION_PASSPHRASE";
+
+/// Environment variable holding the config field passphrase. Like the
+/// session passphrase it stands in for an OS keyring entry; setting it
+/// also skips the interactive prompt.
+pub const CONFIG_PASSPHRASE_ENV: &str = "GRAPHOS_CONFIG_PASSPHRASE";
+
+/// Prefix marking an encrypted config field value
+pub const FIELD_PREFIX: &str = "enc:";
+
+/// Whether a config value is an encrypted field blob
+pub fn is_encrypted_field(value: &str) -> bool {
+    value.starts_with(FIELD_PREFIX)
+}
+
+/// ChaCha20-Poly1305 cipher for session files at rest, keyed by a
+/// passphrase. Each file gets a fresh random salt and nonce, so the same
+/// plaintext never encrypts to the same bytes twice.
+#[derive(Clone)]
+pub struct SessionCipher {
+    passphrase: String,
+    rng: SystemRandom,
+}
+
+// Never expose the passphrase through Debug output
+impl std::fmt::Debug for SessionCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionCipher").finish_non_exhaustive()
+    }
+}
+
+impl SessionCipher {
+    pub fn new(passphrase: &str) -> Self {
+        Self {
+            passphrase: passphrase.to_string(),
+            rng: SystemRandom::new(),
+        }
+    }
+
+    /// Build a cipher from the passphrase environment variable, if set
+    pub fn from_env() -> Option<Self> {
+        env::var(PASSPHRASE_ENV)
+            .ok()
+            .filter(|p| !p.is_empty())
+            .map(|p| Self::new(&p))
+    }
+
+    /// Whether the given file contents are an encrypted session
+    pub fn is_encrypted(data: &[u8]) -> bool {
+        data.starts_with(MAGIC)
+    }
+
+    /// Derive the per-file key from the passphrase and stored salt
+    fn derive_key(&self, salt: &[u8]) -> LessSafeKey {
+        let mut key_bytes = [0u8; 32];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            NonZeroU32::new(PBKDF2_ITERATIONS).unwrap(),
+            salt,
+            self.passphrase.as_bytes(),
+            &mut key_bytes,
+        );
+
+        LessSafeKey::new(UnboundKey::new(&CHACHA20_POLY1305, &key_bytes).unwrap())
+    }
+
+    /// Encrypt plaintext into the on-disk format:
+    /// magic || salt || nonce || ciphertext+tag
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut salt = [0u8; SALT_LEN];
+        self.rng
+            .fill(&mut salt)
+            .map_err(|_| anyhow!("Failed to generate encryption salt"))?;
+
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce_bytes)
+            .map_err(|_| anyhow!("Failed to generate encryption nonce"))?;
+
+        let key = self.derive_key(&salt);
+        let mut in_out = plaintext.to_vec();
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce_bytes),
+            Aad::empty(),
+            &mut in_out,
+        )
+        .map_err(|_| anyhow!("Encryption failed"))?;
+
+        let mut output = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + in_out.len());
+        output.extend_from_slice(MAGIC);
+        output.extend_from_slice(&salt);
+        output.extend_from_slice(&nonce_bytes);
+        output.extend_from_slice(&in_out);
+
+        Ok(output)
+    }
+
+    /// Decrypt a file in the on-disk format back to plaintext
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !Self::is_encrypted(data) {
+            return Err(anyhow!("Not an encrypted session file"));
+        }
+
+        let rest = &data[MAGIC.len()..];
+        if rest.len() < SALT_LEN + NONCE_LEN + CHACHA20_POLY1305.tag_len() {
+            return Err(anyhow!("Encrypted session file is truncated"));
+        }
+
+        let (salt, rest) = rest.split_at(SALT_LEN);
+        let (nonce_bytes, ciphertext) = rest.split_at(NONCE_LEN);
+
+        let key = self.derive_key(salt);
+        let nonce = Nonce::try_assume_unique_for_key(nonce_bytes)
+            .map_err(|_| anyhow!("Invalid encryption nonce"))?;
+
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = key
+            .open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| anyhow!("Decryption failed (wrong passphrase?)"))?;
+
+        Ok(plaintext.to_vec())
+    }
+
+    /// Encrypt a single config value into an `enc:` blob: the binary
+    /// file format, base64-encoded so it survives any config format
+    pub fn encrypt_field(&self, value: &str) -> Result<String> {
+        Ok(format!(
+            "{}{}",
+            FIELD_PREFIX,
+            BASE64.encode(self.encrypt(value.as_bytes())?)
+        ))
+    }
+
+    /// Decrypt an `enc:` blob back to the plaintext config value
+    pub fn decrypt_field(&self, value: &str) -> Result<String> {
+        let blob = value
+            .strip_prefix(FIELD_PREFIX)
+            .ok_or_else(|| anyhow!("Not an encrypted field value"))?;
+        let data = BASE64
+            .decode(blob)
+            .map_err(|e| anyhow!("Invalid encrypted field encoding: {}", e))?;
+        String::from_utf8(self.decrypt(&data)?)
+            .map_err(|_| anyhow!("Decrypted field is not valid UTF-8"))
+    }
+}
+
+/// Cipher for `enc:` config fields: the environment variable (the
+/// keyring stand-in) wins, otherwise the passphrase is prompted once
+/// per run. None when neither yields one, e.g. non-interactive runs.
+pub fn config_cipher() -> Option<&'static SessionCipher> {
+    static CIPHER: std::sync::OnceLock<Option<SessionCipher>> = std::sync::OnceLock::new();
+    CIPHER
+        .get_or_init(|| {
+            env::var(CONFIG_PASSPHRASE_ENV)
+                .ok()
+                .filter(|p| !p.is_empty())
+                .or_else(|| prompt_passphrase("Config passphrase: "))
+                .map(|p| SessionCipher::new(&p))
+        })
+        .as_ref()
+}
+
+/// Read a passphrase from the terminal without echoing it, via raw
+/// mode. Returns None when stdin is not a terminal or nothing was
+/// entered.
+fn prompt_passphrase(prompt: &str) -> Option<String> {
+    use std::io::{IsTerminal, Write};
+
+    if !std::io::stdin().is_terminal() {
+        return None;
+    }
+
+    eprint!("{}", prompt);
+    std::io::stderr().flush().ok();
+    crossterm::terminal::enable_raw_mode().ok()?;
+
+    let mut passphrase = String::new();
+    loop {
+        match crossterm::event::read() {
+            Ok(crossterm::event::Event::Key(key)) => match key.code {
+                crossterm::event::KeyCode::Enter => break,
+                crossterm::event::KeyCode::Esc => {
+                    passphrase.clear();
+                    break;
+                }
+                crossterm::event::KeyCode::Char('c')
+                    if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) =>
+                {
+                    passphrase.clear();
+                    break;
+                }
+                crossterm::event::KeyCode::Char(c) => passphrase.push(c),
+                crossterm::event::KeyCode::Backspace => {
+                    passphrase.pop();
+                }
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+
+    crossterm::terminal::disable_raw_mode().ok();
+    eprintln!();
+    (!passphrase.is_empty()).then_some(passphrase)
+}
@@ -0,0 +1,118 @@
+//! Lifecycle management for the session listener daemon.
+//!
+//! Without a daemon, the first interactive instance implicitly becomes
+//! the listener and takes every other instance down with its terminal.
+//! `gos daemon start` spawns a detached listener process with a pidfile
+//! instead, so interactive instances always act as clients.
+
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+
+use crate::session::{self, SessionManager};
+
+/// How long `start` waits for the spawned daemon to answer a ping
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Location of the daemon pidfile in the state directory
+fn pidfile_path() -> PathBuf {
+    crate::paths::state_dir().join("daemon.pid")
+}
+
+/// Read the pid recorded by a running (or crashed) daemon
+fn read_pidfile() -> Option<u32> {
+    std::fs::read_to_string(pidfile_path())
+        .ok()
+        .and_then(|contents| contents.trim().parse().ok())
+}
+
+/// Spawn a detached listener process and wait for it to become healthy
+pub async fn start() -> Result<()> {
+    if let Some(info) = session::ping_listener().await {
+        println!("Daemon already running ({})", info);
+        return Ok(());
+    }
+
+    let exe = std::env::current_exe().context("Could not determine the gos executable path")?;
+
+    let mut command = std::process::Command::new(exe);
+    command
+        .args(["daemon", "run"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    // Detach from our process group so closing this terminal does not
+    // take the daemon down with it
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let child = command.spawn().context("Failed to spawn daemon process")?;
+
+    // Poll until the listener answers or the timeout passes
+    let started = std::time::Instant::now();
+    while started.elapsed() < STARTUP_TIMEOUT {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        if let Some(info) = session::ping_listener().await {
+            println!("Daemon started (pid {}, {})", child.id(), info);
+            return Ok(());
+        }
+    }
+
+    bail!("Daemon process did not become healthy within {:?}", STARTUP_TIMEOUT);
+}
+
+/// Ask a running daemon to shut down
+pub async fn stop() -> Result<()> {
+    if session::request_shutdown().await? {
+        println!("Daemon stopped");
+    } else {
+        println!("No daemon running");
+    }
+
+    // Clean up the pidfile either way; a stale one is just noise
+    std::fs::remove_file(pidfile_path()).ok();
+    Ok(())
+}
+
+/// Report whether a daemon is running and healthy
+pub async fn status() -> Result<()> {
+    match session::ping_listener().await {
+        Some(info) => {
+            match read_pidfile() {
+                Some(pid) => println!("Daemon running (pid {}, {})", pid, info),
+                None => println!("Daemon running ({})", info),
+            }
+        }
+        None => {
+            println!("Daemon not running");
+            if let Some(pid) = read_pidfile() {
+                println!("Stale pidfile found (pid {}); it likely crashed", pid);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the listener in the foreground. This is the internal entry point
+/// `start` spawns; it writes the pidfile and removes it on clean exit.
+pub async fn run() -> Result<()> {
+    let pidfile = pidfile_path();
+    if let Some(parent) = pidfile.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(&pidfile, std::process::id().to_string())
+        .with_context(|| format!("Failed to write pidfile {}", pidfile.display()))?;
+
+    let result = SessionManager::run_daemon().await;
+
+    std::fs::remove_file(&pidfile).ok();
+    result.map_err(Into::into)
+}
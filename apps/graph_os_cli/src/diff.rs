@@ -0,0 +1,201 @@
+//! Conversation diffing between two sessions.
+//!
+//! Aligns two message lists with a longest-common-subsequence pass —
+//! messages match when role and text are equal, metadata is ignored —
+//! so the shared history before a fork lines up row by row and the
+//! divergence point stands out. The alignment feeds a side-by-side
+//! renderer used by `gos diff` and the /diff chat command.
+
+use crate::session::{ChatMessage, Session};
+
+/// One row of an aligned diff, holding indices into the two message lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffEntry {
+    /// The same message appears in both conversations
+    Common { left: usize, right: usize },
+    /// Message only present in the left conversation
+    LeftOnly(usize),
+    /// Message only present in the right conversation
+    RightOnly(usize),
+}
+
+/// Whether two messages count as the same for alignment: only role and
+/// text matter, so re-generated metadata never breaks the alignment
+fn same_message(a: &ChatMessage, b: &ChatMessage) -> bool {
+    a.role() == b.role() && a.text() == b.text()
+}
+
+/// Align two message lists by longest common subsequence. Where the
+/// conversations disagree, left-only rows are emitted before right-only
+/// ones, so each divergence reads as "a said ..., b said ...".
+pub fn align(left: &[ChatMessage], right: &[ChatMessage]) -> Vec<DiffEntry> {
+    // lcs[i][j] holds the length of the longest common subsequence of
+    // left[i..] and right[j..], filled back to front
+    let mut lcs = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            lcs[i][j] = if same_message(&left[i], &right[j]) {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut entries = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        if same_message(&left[i], &right[j]) {
+            entries.push(DiffEntry::Common { left: i, right: j });
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            entries.push(DiffEntry::LeftOnly(i));
+            i += 1;
+        } else {
+            entries.push(DiffEntry::RightOnly(j));
+            j += 1;
+        }
+    }
+    while i < left.len() {
+        entries.push(DiffEntry::LeftOnly(i));
+        i += 1;
+    }
+    while j < right.len() {
+        entries.push(DiffEntry::RightOnly(j));
+        j += 1;
+    }
+
+    entries
+}
+
+/// Index of the first row where the conversations diverge, if they do
+pub fn first_divergence(entries: &[DiffEntry]) -> Option<usize> {
+    entries.iter().position(|entry| !matches!(entry, DiffEntry::Common { .. }))
+}
+
+/// Render an aligned diff side by side within `width` columns. Each row
+/// starts with a marker — space for shared messages, `<` for left-only,
+/// `>` for right-only — and a rule marks where the conversations part.
+pub fn render_side_by_side(left: &Session, right: &Session, width: usize) -> String {
+    let entries = align(&left.messages, &right.messages);
+    let divergence = first_divergence(&entries);
+
+    // Marker, two columns and a " | " gutter; keep columns readable
+    // even when the requested width is tiny
+    let column = (width.saturating_sub(5) / 2).max(20);
+
+    let mut out = String::new();
+    push_row(&mut out, ' ', &format!("a: {}", left.id), &format!("b: {}", right.id), column);
+    push_row(
+        &mut out,
+        ' ',
+        &format!("{} message(s)", left.messages.len()),
+        &format!("{} message(s)", right.messages.len()),
+        column,
+    );
+    out.push_str(&"-".repeat(column * 2 + 5));
+    out.push('\n');
+
+    let mut shared = 0usize;
+    let mut left_only = 0usize;
+    let mut right_only = 0usize;
+
+    for (row, entry) in entries.iter().enumerate() {
+        if divergence == Some(row) && row > 0 {
+            let rule = format!("--- conversations diverge after message {} ---", row - 1);
+            out.push_str(&rule);
+            out.push('\n');
+        }
+
+        let (marker, left_lines, right_lines) = match entry {
+            DiffEntry::Common { left: i, right: j } => {
+                shared += 1;
+                (
+                    ' ',
+                    message_lines(*i, &left.messages[*i], column),
+                    message_lines(*j, &right.messages[*j], column),
+                )
+            }
+            DiffEntry::LeftOnly(i) => {
+                left_only += 1;
+                ('<', message_lines(*i, &left.messages[*i], column), Vec::new())
+            }
+            DiffEntry::RightOnly(j) => {
+                right_only += 1;
+                ('>', Vec::new(), message_lines(*j, &right.messages[*j], column))
+            }
+        };
+
+        for k in 0..left_lines.len().max(right_lines.len()) {
+            let l = left_lines.get(k).map(String::as_str).unwrap_or("");
+            let r = right_lines.get(k).map(String::as_str).unwrap_or("");
+            push_row(&mut out, marker, l, r, column);
+        }
+    }
+
+    out.push('\n');
+    if divergence.is_none() && left_only == 0 && right_only == 0 {
+        out.push_str("Conversations are identical.\n");
+    } else {
+        out.push_str(&format!(
+            "{} shared, {} only in a, {} only in b\n",
+            shared, left_only, right_only
+        ));
+    }
+
+    out
+}
+
+/// Append one two-column row, trimming the trailing padding of an empty
+/// right column
+fn push_row(out: &mut String, marker: char, left: &str, right: &str, column: usize) {
+    let row = format!("{} {:<column$} | {}", marker, left, right);
+    out.push_str(row.trim_end());
+    out.push('\n');
+}
+
+/// A message as column lines: an index/role header, then the text
+/// word-wrapped to the column width
+fn message_lines(index: usize, message: &ChatMessage, width: usize) -> Vec<String> {
+    let mut lines = vec![format!("[{}] {}:", index, message.role())];
+    for line in message.text().lines() {
+        lines.extend(wrap_line(line, width));
+    }
+    lines
+}
+
+/// Greedy word wrap; words longer than the column are split hard
+fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    if line.trim().is_empty() {
+        return vec![String::new()];
+    }
+
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in line.split_whitespace() {
+        let mut rest = word;
+        while rest.chars().count() > width {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            let cut: String = rest.chars().take(width).collect();
+            rest = &rest[cut.len()..];
+            lines.push(cut);
+        }
+
+        let space = usize::from(!current.is_empty());
+        if current.chars().count() + space + rest.chars().count() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(rest);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
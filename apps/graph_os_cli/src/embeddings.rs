@@ -0,0 +1,348 @@
+//! Local embedding index for semantic search over past conversations
+//! (`gos search --semantic`, `/recall`).
+//!
+//! Vectors come from the [`Embedder`]: with an `embedding_model`
+//! configured on the default endpoint, messages are embedded through
+//! the provider's embeddings API; otherwise they fall back to a hashed
+//! bag of features — lowercased word unigrams plus character trigrams,
+//! FNV-hashed into a fixed number of buckets and L2-normalized. The
+//! fallback is deliberately not a neural model: it needs no download,
+//! no runtime dependency and no network, and for "that bug about TLS"
+//! style queries over one user's own transcripts the overlap signal is
+//! plenty. Each entry records which embedder produced it, so switching
+//! models re-embeds rather than comparing across vector spaces.
+//!
+//! The index lives in the state directory as JSONL, one embedded
+//! message per line. It is a cache: deleting it just means the next
+//! search re-embeds, so it is never a source of truth.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::paths;
+use crate::session::Session;
+
+/// Buckets in the hashed feature vector. Plenty for vocabulary-overlap
+/// search while keeping the index small on disk.
+pub const EMBEDDING_DIM: usize = 256;
+
+/// Messages shorter than this are noise ("ok", "thanks") and are not
+/// indexed
+const MIN_INDEXED_CHARS: usize = 12;
+
+/// Preview length stored with each vector so hits can be shown without
+/// re-reading session files
+const PREVIEW_CHARS: usize = 120;
+
+/// Model tag recorded for locally hashed vectors; index lines written
+/// before tagging existed parse as this, which is what produced them
+pub const LOCAL_MODEL: &str = "local-hashed-v1";
+
+fn local_model() -> String {
+    LOCAL_MODEL.to_string()
+}
+
+/// One embedded message in the index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedMessage {
+    pub session_id: Uuid,
+    /// Stored message index within the session
+    pub index: usize,
+    pub role: String,
+    /// First line of the message, for listings
+    pub preview: String,
+    /// `last_active` of the session when it was indexed, for staleness
+    pub indexed_at: DateTime<Utc>,
+    /// Which embedder produced the vector; cosine across different
+    /// vector spaces is meaningless, so search skips foreign entries
+    #[serde(default = "local_model")]
+    pub model: String,
+    pub vector: Vec<f32>,
+}
+
+/// FNV-1a, the same tiny hash the rest of the codebase would reach
+/// for; only bucket assignment depends on it
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+/// Embed a text as a hashed bag of word unigrams and character
+/// trigrams, L2-normalized so dot product equals cosine similarity
+pub fn embed(text: &str) -> Vec<f32> {
+    let mut vector = vec![0f32; EMBEDDING_DIM];
+    let lowered = text.to_lowercase();
+
+    for word in lowered.split(|c: char| !c.is_alphanumeric()) {
+        if word.len() < 2 {
+            continue;
+        }
+        vector[(fnv1a(word.as_bytes()) % EMBEDDING_DIM as u64) as usize] += 1.0;
+
+        // Character trigrams catch morphology and typos ("TLS"
+        // matching "tls13", "certificate" matching "certs")
+        let chars: Vec<char> = word.chars().collect();
+        for window in chars.windows(3) {
+            let gram: String = window.iter().collect();
+            vector[(fnv1a(gram.as_bytes()) % EMBEDDING_DIM as u64) as usize] += 0.5;
+        }
+    }
+
+    normalize(&mut vector);
+    vector
+}
+
+/// Scale a vector to unit length, so dot product equals cosine
+/// similarity; the zero vector stays zero
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for v in vector {
+            *v /= norm;
+        }
+    }
+}
+
+/// Where vectors come from. [`Embedder::from_config`] picks the
+/// provider path exactly when the default endpoint names an
+/// `embedding_model`; the hashed features remain the no-setup default.
+pub enum Embedder {
+    /// Hashed bag of features; no network, always available
+    Local,
+    /// The configured provider's embeddings API
+    Provider {
+        client: Box<crate::adapters::JsonRpcClient>,
+        model: String,
+    },
+}
+
+impl Embedder {
+    /// Build the embedder the config asks for. A configured
+    /// `embedding_model` without a usable API provider falls back to
+    /// the local features with a warning, so search still works.
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        let endpoint_config = config.get_endpoint_config("default");
+        let Some(model) = endpoint_config.as_ref().and_then(|e| e.embedding_model.clone()) else {
+            return Embedder::Local;
+        };
+
+        let api_config = config.get_default_api_config();
+        let endpoint = api_config.as_ref().and_then(|api| api.api_url.clone());
+        let (Some(api_config), Some(endpoint)) = (api_config, endpoint) else {
+            eprintln!(
+                "embedding_model is set but no API provider with an api_url is configured; \
+                 using local hashed embeddings"
+            );
+            return Embedder::Local;
+        };
+
+        let options = crate::adapters::HttpClientOptions::from_env()
+            .merge_endpoint(endpoint_config.as_ref());
+        let client = crate::adapters::JsonRpcClient::with_endpoint_options(
+            endpoint,
+            Some(api_config.api_key),
+            None,
+            config.get_rpc_secret(),
+            &options,
+        );
+        Embedder::Provider { client: Box::new(client), model }
+    }
+
+    /// Tag recorded with each vector this embedder produces, compared
+    /// on refresh so a model switch re-embeds instead of mixing spaces
+    pub fn tag(&self) -> String {
+        match self {
+            Embedder::Local => LOCAL_MODEL.to_string(),
+            Embedder::Provider { model, .. } => format!("provider:{}", model),
+        }
+    }
+
+    /// Embed a batch of texts, one vector per text in input order. The
+    /// local path cannot fail; the provider path surfaces its errors so
+    /// callers can tell the user rather than silently degrade.
+    pub async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        match self {
+            Embedder::Local => Ok(texts.iter().map(|text| embed(text)).collect()),
+            Embedder::Provider { client, model } => {
+                let mut vectors = client.embeddings(texts, Some(model)).await?;
+                // Providers do not promise unit length; normalize so
+                // the dot-product-as-cosine contract holds here too
+                for vector in &mut vectors {
+                    normalize(vector);
+                }
+                Ok(vectors)
+            }
+        }
+    }
+
+    /// Embed a single query string
+    pub async fn embed_query(&self, query: &str) -> Result<Vec<f32>> {
+        let mut vectors = self.embed_batch(std::slice::from_ref(&query.to_string())).await?;
+        Ok(vectors.pop().unwrap_or_default())
+    }
+}
+
+/// Cosine similarity of two embeddings; inputs are already normalized,
+/// so this is a dot product
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// The on-disk vector index: all embedded messages across sessions
+#[derive(Debug, Default)]
+pub struct EmbeddingIndex {
+    pub entries: Vec<IndexedMessage>,
+}
+
+impl EmbeddingIndex {
+    /// Where the index lives: regenerable state, not durable data
+    pub fn default_path() -> PathBuf {
+        paths::state_dir().join("embeddings.jsonl")
+    }
+
+    /// Load an index; a missing file is an empty index, and lines that
+    /// fail to parse (older format versions) are dropped and re-embedded
+    /// on the next refresh
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(EmbeddingIndex::default())
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let entries = text
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .filter(|entry: &IndexedMessage| {
+                // Local vectors have a fixed width; provider vectors are
+                // whatever the model emits, just not empty
+                if entry.model == LOCAL_MODEL {
+                    entry.vector.len() == EMBEDDING_DIM
+                } else {
+                    !entry.vector.is_empty()
+                }
+            })
+            .collect();
+        Ok(EmbeddingIndex { entries })
+    }
+
+    /// Persist the index as JSONL
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut out = String::new();
+        for entry in &self.entries {
+            out.push_str(&serde_json::to_string(entry).map_err(|e| {
+                crate::error::GraphOsError::Session(format!("Failed to serialize index: {}", e))
+            })?);
+            out.push('\n');
+        }
+        std::fs::write(path, out)?;
+        Ok(())
+    }
+
+    /// Replace a session's entries with freshly embedded ones. The
+    /// whole session goes to the embedder as one batch, so the provider
+    /// path pays one round trip per changed session.
+    pub async fn index_session(&mut self, session: &Session, embedder: &Embedder) -> Result<()> {
+        let candidates: Vec<(usize, String)> = session
+            .messages
+            .iter()
+            .enumerate()
+            .filter_map(|(index, message)| {
+                let text = message.text();
+                (text.chars().count() >= MIN_INDEXED_CHARS).then(|| (index, text.to_string()))
+            })
+            .collect();
+
+        let texts: Vec<String> = candidates.iter().map(|(_, text)| text.clone()).collect();
+        let vectors = embedder.embed_batch(&texts).await?;
+
+        // Only drop the old entries once the new vectors exist, so a
+        // failed provider call leaves the index usable
+        self.entries.retain(|entry| entry.session_id != session.id);
+        let model = embedder.tag();
+        for ((index, text), vector) in candidates.into_iter().zip(vectors) {
+            let preview: String = text
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .chars()
+                .take(PREVIEW_CHARS)
+                .collect();
+            self.entries.push(IndexedMessage {
+                session_id: session.id,
+                index,
+                role: session.messages[index].role().to_string(),
+                preview,
+                indexed_at: session.last_active,
+                model: model.clone(),
+                vector,
+            });
+        }
+        Ok(())
+    }
+
+    /// Bring the index up to date with the given sessions: re-embed
+    /// sessions that changed since they were indexed or were embedded
+    /// by a different embedder, drop entries for sessions that no
+    /// longer exist. Returns how many sessions were (re)embedded.
+    pub async fn refresh(&mut self, sessions: &[Session], embedder: &Embedder) -> Result<usize> {
+        let tag = embedder.tag();
+        let indexed: HashMap<Uuid, (DateTime<Utc>, String)> = self
+            .entries
+            .iter()
+            .map(|entry| (entry.session_id, (entry.indexed_at, entry.model.clone())))
+            .collect();
+
+        let mut reindexed = 0;
+        for session in sessions {
+            if indexed.get(&session.id) != Some(&(session.last_active, tag.clone())) {
+                self.index_session(session, embedder).await?;
+                reindexed += 1;
+            }
+        }
+
+        let live: Vec<Uuid> = sessions.iter().map(|session| session.id).collect();
+        self.entries.retain(|entry| live.contains(&entry.session_id));
+
+        Ok(reindexed)
+    }
+
+    /// The `limit` most similar messages to `query`, best first.
+    /// Entries from a different embedder are skipped, and scores below
+    /// a small floor are dropped; an empty result means nothing in the
+    /// history resembles the query.
+    pub async fn search(
+        &self,
+        query: &str,
+        limit: usize,
+        embedder: &Embedder,
+    ) -> Result<Vec<(f32, &IndexedMessage)>> {
+        let query_vector = embedder.embed_query(query).await?;
+        let tag = embedder.tag();
+
+        let mut hits: Vec<(f32, &IndexedMessage)> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.model == tag)
+            .map(|entry| (cosine(&query_vector, &entry.vector), entry))
+            .filter(|(score, _)| *score > 0.05)
+            .collect();
+        hits.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+}
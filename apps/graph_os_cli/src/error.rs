@@ -0,0 +1,101 @@
+//! Typed error taxonomy for the library layer.
+//!
+//! Library consumers need to discriminate transport failures from auth
+//! failures from application-level RPC errors; a bag of anyhow strings
+//! cannot do that. The adapters and the session manager return
+//! `GraphOsError`; anyhow stays at the binary edge, where `?` converts
+//! automatically.
+
+use serde_json::Value;
+
+/// All the ways talking to GraphOS can fail
+#[derive(Debug, thiserror::Error)]
+pub enum GraphOsError {
+    /// The connection itself failed: refused, reset, DNS, TLS, HTTP 5xx
+    #[error("transport error: {0}")]
+    Transport(String),
+
+    /// The server rejected our credentials
+    #[error("authentication failed: {0}")]
+    Auth(String),
+
+    /// The server answered with an application-level error
+    #[error("RPC error {code}: {message}")]
+    RpcError {
+        code: i32,
+        message: String,
+        data: Option<Value>,
+    },
+
+    /// The call did not complete within its deadline
+    #[error("timed out: {0}")]
+    Timeout(String),
+
+    /// The response arrived but could not be parsed
+    #[error("decode error: {0}")]
+    Decode(String),
+
+    /// Local file I/O failed
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Session bookkeeping failed
+    #[error("session error: {0}")]
+    Session(String),
+
+    /// The request cannot be made with the current configuration
+    #[error("configuration error: {0}")]
+    Config(String),
+}
+
+/// Library-layer result type; modules `use crate::error::Result` so
+/// their signatures read the same as before
+pub type Result<T, E = GraphOsError> = std::result::Result<T, E>;
+
+impl From<reqwest::Error> for GraphOsError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            GraphOsError::Timeout(e.to_string())
+        } else if e.is_decode() {
+            GraphOsError::Decode(e.to_string())
+        } else {
+            GraphOsError::Transport(e.to_string())
+        }
+    }
+}
+
+impl From<serde_json::Error> for GraphOsError {
+    fn from(e: serde_json::Error) -> Self {
+        GraphOsError::Decode(e.to_string())
+    }
+}
+
+impl From<tonic::Status> for GraphOsError {
+    fn from(status: tonic::Status) -> Self {
+        match status.code() {
+            tonic::Code::Unauthenticated | tonic::Code::PermissionDenied => {
+                GraphOsError::Auth(status.message().to_string())
+            }
+            tonic::Code::DeadlineExceeded => GraphOsError::Timeout(status.message().to_string()),
+            tonic::Code::Unavailable => GraphOsError::Transport(status.to_string()),
+            code => GraphOsError::RpcError {
+                code: code as i32,
+                message: status.message().to_string(),
+                data: None,
+            },
+        }
+    }
+}
+
+impl From<tonic::transport::Error> for GraphOsError {
+    fn from(e: tonic::transport::Error) -> Self {
+        GraphOsError::Transport(e.to_string())
+    }
+}
+
+impl GraphOsError {
+    /// Whether retrying on a fresh connection could plausibly succeed
+    pub fn is_transient(&self) -> bool {
+        matches!(self, GraphOsError::Transport(_) | GraphOsError::Timeout(_))
+    }
+}
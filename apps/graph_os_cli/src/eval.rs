@@ -0,0 +1,256 @@
+//! Eval harness: run a suite of prompts against one or more providers,
+//! score each answer against an expected-answer check, and aggregate
+//! pass rates and latency per provider.
+//!
+//! The suite file is YAML: an optional shared system prompt and a list
+//! of cases, each with a prompt and one check. Exact, contains and
+//! regex checks run locally; a judge check hands the answer to a
+//! second model for a PASS/FAIL verdict. The runner in `gos eval`
+//! owns the network; everything here is deterministic and testable.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{GraphOsError, Result};
+
+/// A prompt suite loaded from a YAML file
+#[derive(Debug, Clone, Deserialize)]
+pub struct Suite {
+    /// Suite name shown in the report (default: the file stem)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// System prompt shared by every case
+    #[serde(default)]
+    pub system: Option<String>,
+    /// The prompts to run, in order
+    pub cases: Vec<EvalCase>,
+}
+
+/// One prompt with its expected-answer check
+#[derive(Debug, Clone, Deserialize)]
+pub struct EvalCase {
+    /// Case name shown in the report (default: "case N")
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The prompt sent to each provider
+    pub prompt: String,
+    /// What counts as a passing answer
+    pub expect: Expectation,
+}
+
+/// Expected-answer check for one case, written in the suite as a
+/// one-key map: `expect: { exact: ... }`, `contains`, `regex` or
+/// `judge`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "ExpectationSpec")]
+pub enum Expectation {
+    /// The trimmed response equals this string
+    Exact(String),
+    /// The response contains this substring (case-insensitive)
+    Contains(String),
+    /// The response matches this regex
+    Regex(String),
+    /// A judge model decides whether the response satisfies this
+    /// criterion
+    Judge(String),
+}
+
+/// Raw `expect:` map; deserialized through a plain struct because the
+/// YAML parser spells externally tagged enums as `!tags`, which nobody
+/// writes in a suite file by hand
+#[derive(Deserialize)]
+struct ExpectationSpec {
+    exact: Option<String>,
+    contains: Option<String>,
+    regex: Option<String>,
+    judge: Option<String>,
+}
+
+impl TryFrom<ExpectationSpec> for Expectation {
+    type Error = String;
+
+    fn try_from(spec: ExpectationSpec) -> std::result::Result<Self, String> {
+        let mut checks = Vec::new();
+        if let Some(expected) = spec.exact {
+            checks.push(Expectation::Exact(expected));
+        }
+        if let Some(needle) = spec.contains {
+            checks.push(Expectation::Contains(needle));
+        }
+        if let Some(pattern) = spec.regex {
+            checks.push(Expectation::Regex(pattern));
+        }
+        if let Some(criterion) = spec.judge {
+            checks.push(Expectation::Judge(criterion));
+        }
+        match checks.len() {
+            1 => Ok(checks.pop().unwrap()),
+            0 => Err("expect needs one of: exact, contains, regex, judge".to_string()),
+            _ => Err("expect takes exactly one check, not several".to_string()),
+        }
+    }
+}
+
+impl Suite {
+    /// Parse a suite from YAML, rejecting empty suites up front so the
+    /// runner never produces an empty report
+    pub fn parse(text: &str) -> Result<Suite> {
+        let suite: Suite = serde_yaml::from_str(text)
+            .map_err(|e| GraphOsError::Decode(format!("Invalid suite file: {}", e)))?;
+        if suite.cases.is_empty() {
+            return Err(GraphOsError::Decode("Suite has no cases".to_string()));
+        }
+        Ok(suite)
+    }
+}
+
+impl EvalCase {
+    /// Display name for the report, falling back to the 1-based
+    /// position in the suite
+    pub fn display_name(&self, index: usize) -> String {
+        self.name.clone().unwrap_or_else(|| format!("case {}", index + 1))
+    }
+}
+
+impl Expectation {
+    /// Run a local check against the response: `Ok(())` on pass, the
+    /// failure detail on fail. Judge checks need a model call and
+    /// return None; the runner scores them with [`judge_prompt`] and
+    /// [`parse_judge_verdict`].
+    pub fn check_local(&self, response: &str) -> Option<std::result::Result<(), String>> {
+        match self {
+            Expectation::Exact(expected) => Some(if response.trim() == expected {
+                Ok(())
+            } else {
+                Err(format!("expected exactly {:?}", expected))
+            }),
+            Expectation::Contains(needle) => {
+                Some(if response.to_lowercase().contains(&needle.to_lowercase()) {
+                    Ok(())
+                } else {
+                    Err(format!("expected the response to contain {:?}", needle))
+                })
+            }
+            Expectation::Regex(pattern) => Some(match regex::Regex::new(pattern) {
+                Ok(re) if re.is_match(response) => Ok(()),
+                Ok(_) => Err(format!("expected the response to match /{}/", pattern)),
+                Err(e) => Err(format!("invalid regex /{}/: {}", pattern, e)),
+            }),
+            Expectation::Judge(_) => None,
+        }
+    }
+}
+
+/// Prompt sent to the judge model for a judge check; the constrained
+/// verdict line keeps the parse trivial
+pub fn judge_prompt(prompt: &str, response: &str, criterion: &str) -> String {
+    format!(
+        "You are grading a model's answer.\n\nQuestion:\n{}\n\nAnswer:\n{}\n\n\
+         Criterion: {}\n\nDoes the answer satisfy the criterion? \
+         Reply with exactly one line: PASS or FAIL, optionally followed \
+         by a short reason.",
+        prompt, response, criterion
+    )
+}
+
+/// Read the judge's verdict: `Ok(())` on PASS, the judge's stated
+/// reason (or the raw verdict) on FAIL or anything unparseable
+pub fn parse_judge_verdict(text: &str) -> std::result::Result<(), String> {
+    let verdict = text.trim();
+    let first_word: String = verdict
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_uppercase();
+    match first_word.as_str() {
+        "PASS" => Ok(()),
+        "FAIL" => Err(verdict.to_string()),
+        _ => Err(format!("unparseable judge verdict: {:?}", verdict)),
+    }
+}
+
+/// One case run against one provider
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseResult {
+    pub case: String,
+    pub provider: String,
+    pub passed: bool,
+    /// Failure detail; None on pass
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub latency_ms: u64,
+    pub response: String,
+}
+
+/// Aggregated pass rate and latency for one provider
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProviderSummary {
+    pub provider: String,
+    pub passed: usize,
+    pub total: usize,
+    pub mean_latency_ms: u64,
+}
+
+/// Aggregate results per provider, in first-seen provider order
+pub fn summarize(results: &[CaseResult]) -> Vec<ProviderSummary> {
+    let mut summaries: Vec<ProviderSummary> = Vec::new();
+    for result in results {
+        let summary = match summaries.iter_mut().find(|s| s.provider == result.provider) {
+            Some(summary) => summary,
+            None => {
+                summaries.push(ProviderSummary {
+                    provider: result.provider.clone(),
+                    passed: 0,
+                    total: 0,
+                    mean_latency_ms: 0,
+                });
+                summaries.last_mut().unwrap()
+            }
+        };
+        summary.total += 1;
+        if result.passed {
+            summary.passed += 1;
+        }
+        // Accumulate; divided once all results are in
+        summary.mean_latency_ms += result.latency_ms;
+    }
+    for summary in &mut summaries {
+        if summary.total > 0 {
+            summary.mean_latency_ms /= summary.total as u64;
+        }
+    }
+    summaries
+}
+
+/// Render the report as markdown: a per-provider summary table
+/// followed by the failures with their details
+pub fn render_markdown(suite_name: &str, results: &[CaseResult]) -> String {
+    let mut out = format!("# Eval: {}\n\n", suite_name);
+
+    out.push_str("| provider | passed | pass rate | mean latency |\n");
+    out.push_str("|---|---|---|---|\n");
+    for summary in summarize(results) {
+        out.push_str(&format!(
+            "| {} | {}/{} | {:.0}% | {}ms |\n",
+            summary.provider,
+            summary.passed,
+            summary.total,
+            summary.passed as f64 / summary.total.max(1) as f64 * 100.0,
+            summary.mean_latency_ms
+        ));
+    }
+
+    let failures: Vec<&CaseResult> = results.iter().filter(|r| !r.passed).collect();
+    if !failures.is_empty() {
+        out.push_str("\n## Failures\n\n");
+        for failure in failures {
+            out.push_str(&format!(
+                "- **{}** on {}: {}\n",
+                failure.case,
+                failure.provider,
+                failure.detail.as_deref().unwrap_or("failed")
+            ));
+        }
+    }
+
+    out
+}
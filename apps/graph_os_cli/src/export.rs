@@ -0,0 +1,106 @@
+//! Conversation export to training formats.
+//!
+//! Converts stored sessions into the chat-format JSONL used for
+//! fine-tuning: one example per line, each a `messages` array of
+//! system/user/assistant turns. Sessions that would make bad training
+//! data (empty turns, oversized conversations) are rejected with a
+//! reason so a run reports what it skipped rather than silently
+//! emitting broken lines.
+
+use anyhow::{bail, Result};
+use serde_json::{json, Value};
+
+use crate::session::Session;
+
+/// Upper bound on the total character count of one training example.
+/// Roughly tracks provider token limits with the usual 4-chars-per-token
+/// estimate; anything bigger would be truncated server-side anyway.
+pub const MAX_EXAMPLE_CHARS: usize = 256 * 1024;
+
+/// A session selector parsed from `--filter key=value`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportFilter {
+    /// Matches sessions carrying the tag
+    Tag(String),
+    /// Matches sessions whose stored provider equals the value
+    Provider(String),
+}
+
+impl ExportFilter {
+    /// Parse one `key=value` filter specification
+    pub fn parse(spec: &str) -> Result<Self> {
+        let Some((key, value)) = spec.split_once('=') else {
+            bail!("Invalid filter '{}'. Use key=value, e.g. tag=training", spec);
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            bail!("Filter '{}' has an empty value", spec);
+        }
+
+        match key.trim() {
+            "tag" => Ok(ExportFilter::Tag(value.to_string())),
+            "provider" => Ok(ExportFilter::Provider(value.to_lowercase())),
+            other => bail!("Unknown filter key '{}'. Available keys: tag, provider", other),
+        }
+    }
+
+    /// Whether the session matches this filter
+    pub fn matches(&self, session: &Session) -> bool {
+        match self {
+            ExportFilter::Tag(tag) => session.tags.iter().any(|t| t == tag),
+            ExportFilter::Provider(provider) => {
+                session.provider.as_deref().map(str::to_lowercase).as_deref() == Some(provider)
+            }
+        }
+    }
+}
+
+/// Whether the session passes every filter (no filters passes everything)
+pub fn session_matches(session: &Session, filters: &[ExportFilter]) -> bool {
+    filters.iter().all(|filter| filter.matches(session))
+}
+
+/// Convert one session into a fine-tuning example, validating that it
+/// makes usable training data. With `train_last_only`, every assistant
+/// turn except the final one carries `weight: 0` so only the last reply
+/// is trained on.
+pub fn to_finetune_example(session: &Session, train_last_only: bool) -> Result<Value> {
+    if session.messages.is_empty() {
+        bail!("conversation is empty");
+    }
+    if !session.messages.iter().any(|m| m.role() == "assistant") {
+        bail!("conversation has no assistant turn to train on");
+    }
+
+    let mut total_chars = session.system_prompt.as_deref().map_or(0, str::len);
+    let last_assistant = session
+        .messages
+        .iter()
+        .rposition(|m| m.role() == "assistant")
+        .unwrap_or(0);
+
+    let mut messages = Vec::new();
+    if let Some(system) = &session.system_prompt {
+        messages.push(json!({ "role": "system", "content": system }));
+    }
+
+    for (index, message) in session.messages.iter().enumerate() {
+        let text = message.text();
+        if text.trim().is_empty() {
+            bail!("message {} is empty", index);
+        }
+        total_chars += text.len();
+
+        if message.role() == "assistant" && train_last_only && index != last_assistant {
+            messages.push(json!({ "role": "assistant", "content": text, "weight": 0 }));
+        } else {
+            messages.push(json!({ "role": message.role(), "content": text }));
+        }
+    }
+
+    if total_chars > MAX_EXAMPLE_CHARS {
+        bail!("conversation is {} characters, over the {} limit", total_chars, MAX_EXAMPLE_CHARS);
+    }
+
+    Ok(json!({ "messages": messages }))
+}
@@ -0,0 +1,189 @@
+//! Response post-processing pipeline.
+//!
+//! Assistant responses pass through a configurable chain of output
+//! filters before they are displayed or persisted: secret redaction,
+//! profanity masking, trailing-whitespace trimming, link unfurling and
+//! custom regex replace rules from the `[filters]` config table. Both
+//! the streaming and non-streaming chat paths run the same chain, so a
+//! transcript never contains text the user was not shown.
+
+use regex::Regex;
+
+use crate::config::FiltersConfig;
+
+/// One stage of the response pipeline
+pub trait Filter: Send + Sync {
+    /// Short name for debug output
+    fn name(&self) -> &'static str;
+
+    /// Transform the response text
+    fn apply(&self, text: &str) -> String;
+}
+
+/// Replaces known secret values with a placeholder, mirroring what
+/// `/share` does for outgoing transcripts
+struct RedactSecrets {
+    secrets: Vec<String>,
+}
+
+impl Filter for RedactSecrets {
+    fn name(&self) -> &'static str {
+        "redact_secrets"
+    }
+
+    fn apply(&self, text: &str) -> String {
+        crate::share::redact_text(text, &self.secrets)
+    }
+}
+
+/// Words masked by the profanity filter; deliberately small, custom
+/// replace rules cover anything beyond it
+const PROFANITY: [&str; 6] = ["shit", "fuck", "bitch", "asshole", "bastard", "cunt"];
+
+/// Masks a built-in profanity list with asterisks of the same length
+struct ProfanityFilter {
+    pattern: Regex,
+}
+
+impl ProfanityFilter {
+    fn new() -> Self {
+        let pattern = Regex::new(&format!(r"(?i)\b(?:{})\b", PROFANITY.join("|")))
+            .expect("profanity pattern is valid");
+        Self { pattern }
+    }
+}
+
+impl Filter for ProfanityFilter {
+    fn name(&self) -> &'static str {
+        "profanity"
+    }
+
+    fn apply(&self, text: &str) -> String {
+        self.pattern
+            .replace_all(text, |caps: &regex::Captures| "*".repeat(caps[0].len()))
+            .into_owned()
+    }
+}
+
+/// Rewrites markdown links as "text (url)", which reads better in a
+/// terminal than bracket syntax
+struct UnfurlLinks {
+    pattern: Regex,
+}
+
+impl UnfurlLinks {
+    fn new() -> Self {
+        let pattern = Regex::new(r"\[([^\]]+)\]\(([^)\s]+)\)").expect("link pattern is valid");
+        Self { pattern }
+    }
+}
+
+impl Filter for UnfurlLinks {
+    fn name(&self) -> &'static str {
+        "unfurl_links"
+    }
+
+    fn apply(&self, text: &str) -> String {
+        self.pattern.replace_all(text, "$1 ($2)").into_owned()
+    }
+}
+
+/// Strips trailing whitespace from every line
+struct TrimTrailingWhitespace;
+
+impl Filter for TrimTrailingWhitespace {
+    fn name(&self) -> &'static str {
+        "trim_trailing_whitespace"
+    }
+
+    fn apply(&self, text: &str) -> String {
+        let mut trimmed: String = text
+            .split('\n')
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n");
+        // A trailing newline is structure, not trailing whitespace
+        if text.ends_with('\n') && !trimmed.ends_with('\n') {
+            trimmed.push('\n');
+        }
+        trimmed
+    }
+}
+
+/// A custom regex replace rule from the config
+struct RegexReplace {
+    pattern: Regex,
+    replacement: String,
+}
+
+impl Filter for RegexReplace {
+    fn name(&self) -> &'static str {
+        "replace"
+    }
+
+    fn apply(&self, text: &str) -> String {
+        self.pattern
+            .replace_all(text, self.replacement.as_str())
+            .into_owned()
+    }
+}
+
+/// The configured filter chain, applied in order to every assistant
+/// response. An empty chain passes text through unchanged.
+#[derive(Default)]
+pub struct FilterChain {
+    filters: Vec<Box<dyn Filter>>,
+}
+
+impl FilterChain {
+    /// Build the chain from the `[filters]` config table. Redaction and
+    /// whitespace trimming default on, the rest default off; invalid
+    /// custom patterns are skipped with a warning rather than failing
+    /// the whole pipeline.
+    pub fn from_config(config: &FiltersConfig, secrets: Vec<String>) -> Self {
+        let mut filters: Vec<Box<dyn Filter>> = Vec::new();
+
+        if config.redact_secrets.unwrap_or(true) && !secrets.is_empty() {
+            filters.push(Box::new(RedactSecrets { secrets }));
+        }
+        if config.profanity.unwrap_or(false) {
+            filters.push(Box::new(ProfanityFilter::new()));
+        }
+        if config.unfurl_links.unwrap_or(false) {
+            filters.push(Box::new(UnfurlLinks::new()));
+        }
+        for rule in &config.replace {
+            match Regex::new(&rule.pattern) {
+                Ok(pattern) => filters.push(Box::new(RegexReplace {
+                    pattern,
+                    replacement: rule.replacement.clone(),
+                })),
+                Err(e) => eprintln!("Ignoring invalid filter pattern '{}': {}", rule.pattern, e),
+            }
+        }
+        // Trim last, so it also cleans up after the other filters
+        if config.trim_trailing_whitespace.unwrap_or(true) {
+            filters.push(Box::new(TrimTrailingWhitespace));
+        }
+
+        Self { filters }
+    }
+
+    /// Append a stage built outside the config, e.g. the redaction
+    /// restorer the chat wires in when outbound masking is on
+    pub fn push(&mut self, filter: Box<dyn Filter>) {
+        self.filters.push(filter);
+    }
+
+    /// Run the response through every filter in order
+    pub fn apply(&self, text: &str) -> String {
+        self.filters
+            .iter()
+            .fold(text.to_string(), |text, filter| filter.apply(&text))
+    }
+
+    /// Names of the active filters, for /config and debug output
+    pub fn names(&self) -> Vec<&'static str> {
+        self.filters.iter().map(|filter| filter.name()).collect()
+    }
+}
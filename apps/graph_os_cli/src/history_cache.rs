@@ -0,0 +1,227 @@
+//! Client-side LRU cache for `gos system-info history` queries.
+//!
+//! Dashboards and repeated invocations ask for the same window over and
+//! over, and every call refetched the whole history. The cache keeps
+//! the last answer per (endpoint, since, limit) in memory and on disk,
+//! and refreshes a hit by asking the server only for records newer than
+//! the cached maximum timestamp, so a repeat query costs one small
+//! delta fetch instead of the full window.
+
+use std::fs;
+use std::path::PathBuf;
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+use crate::adapters::grpc::graph_os::{SystemInfo, SystemInfoList};
+use crate::adapters::grpc::GrpcClient;
+use crate::error::Result;
+
+/// How many query keys the cache keeps before evicting the least
+/// recently used one
+const MAX_CACHE_ENTRIES: usize = 16;
+
+/// Entries older than this are refetched in full rather than delta
+/// updated, bounding how long a server-side rewrite of old records
+/// could go unnoticed
+const MAX_ENTRY_AGE: chrono::Duration = chrono::Duration::hours(24);
+
+/// One cached query answer. The records are stored in their protobuf
+/// wire form (base64) since the generated types do not implement serde.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    endpoint: String,
+    since: Option<i64>,
+    limit: Option<i32>,
+    /// When the entry was last brought up to date with the server
+    fetched_at: DateTime<Utc>,
+    /// When the entry last answered a query, driving LRU eviction
+    last_used: DateTime<Utc>,
+    items_b64: String,
+}
+
+impl CacheEntry {
+    fn matches(&self, endpoint: &str, limit: Option<i32>, since: Option<i64>) -> bool {
+        self.endpoint == endpoint && self.limit == limit && self.since == since
+    }
+
+    fn decode_items(&self) -> Option<Vec<SystemInfo>> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&self.items_b64)
+            .ok()?;
+        SystemInfoList::decode(bytes.as_slice()).ok().map(|list| list.items)
+    }
+}
+
+fn encode_items(items: &[SystemInfo]) -> String {
+    let list = SystemInfoList { items: items.to_vec() };
+    base64::engine::general_purpose::STANDARD.encode(list.encode_to_vec())
+}
+
+/// The newest server-side timestamp in a record set, which is where the
+/// next delta fetch starts from
+pub fn max_timestamp(items: &[SystemInfo]) -> Option<i64> {
+    items.iter().map(|info| info.timestamp).max()
+}
+
+/// Merge freshly fetched records into a cached set: everything ends up
+/// sorted by timestamp, duplicates (same timestamp) keep the fresh
+/// copy, and a record limit keeps only the newest records, mirroring
+/// what the server would have returned for the full query
+pub fn merge_records(
+    cached: Vec<SystemInfo>,
+    fresh: Vec<SystemInfo>,
+    limit: Option<i32>,
+) -> Vec<SystemInfo> {
+    let mut merged = cached;
+    for record in fresh {
+        match merged.iter().position(|existing| existing.timestamp == record.timestamp) {
+            Some(index) => merged[index] = record,
+            None => merged.push(record),
+        }
+    }
+    merged.sort_by_key(|record| record.timestamp);
+
+    if let Some(limit) = limit
+        && limit > 0
+        && merged.len() > limit as usize
+    {
+        merged.drain(..merged.len() - limit as usize);
+    }
+    merged
+}
+
+/// The cache itself: a small LRU of query answers, mirrored to one JSON
+/// file in the state directory so separate invocations share it
+#[derive(Debug)]
+pub struct HistoryCache {
+    path: PathBuf,
+    entries: Vec<CacheEntry>,
+}
+
+impl HistoryCache {
+    /// Open the shared cache in the state directory
+    pub fn open() -> Self {
+        Self::with_path(crate::paths::state_dir().join("history_cache.json"))
+    }
+
+    /// Open a cache at a custom location (used by tests and tooling)
+    pub fn with_path(path: PathBuf) -> Self {
+        // A missing or corrupt cache file is just a cold cache
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        HistoryCache { path, entries }
+    }
+
+    /// Look up the cached answer for a query, touching its LRU slot.
+    /// Returns None for a miss, an expired entry, or one whose records
+    /// no longer decode.
+    pub fn lookup(
+        &mut self,
+        endpoint: &str,
+        limit: Option<i32>,
+        since: Option<i64>,
+    ) -> Option<Vec<SystemInfo>> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.matches(endpoint, limit, since))?;
+
+        if Utc::now() - self.entries[index].fetched_at > MAX_ENTRY_AGE {
+            self.entries.remove(index);
+            return None;
+        }
+
+        self.entries[index].last_used = Utc::now();
+        self.entries[index].decode_items()
+    }
+
+    /// Store (or replace) the answer for a query, evicting the least
+    /// recently used entry when the cache is full, and mirror to disk
+    pub fn store(
+        &mut self,
+        endpoint: &str,
+        limit: Option<i32>,
+        since: Option<i64>,
+        items: &[SystemInfo],
+    ) {
+        let now = Utc::now();
+        self.entries.retain(|entry| !entry.matches(endpoint, limit, since));
+        self.entries.push(CacheEntry {
+            endpoint: endpoint.to_string(),
+            since,
+            limit,
+            fetched_at: now,
+            last_used: now,
+            items_b64: encode_items(items),
+        });
+
+        while self.entries.len() > MAX_CACHE_ENTRIES {
+            if let Some(oldest) = self
+                .entries
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(index, _)| index)
+            {
+                self.entries.remove(oldest);
+            }
+        }
+
+        self.save();
+    }
+
+    /// Number of cached query answers
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Best-effort mirror to disk; a failed write leaves the next run
+    /// with a cold cache, nothing worse
+    fn save(&self) {
+        if let Some(parent) = self.path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string(&self.entries) {
+            let _ = fs::write(&self.path, content);
+        }
+    }
+}
+
+/// Answer a history query through the cache: a cold key fetches the
+/// full window, a warm one fetches only records newer than the cached
+/// maximum timestamp and merges them in
+pub async fn fetch_history(
+    client: &mut GrpcClient,
+    cache: &mut HistoryCache,
+    endpoint: &str,
+    limit: Option<i32>,
+    since: Option<i64>,
+) -> Result<Vec<SystemInfo>> {
+    if let Some(cached) = cache.lookup(endpoint, limit, since) {
+        // Delta fetch: everything strictly newer than what we have.
+        // With no cached max (an empty cached answer) re-run the
+        // original query instead.
+        let items = match max_timestamp(&cached) {
+            Some(max) => {
+                let fresh = client.list_system_info(None, Some(max + 1)).await?;
+                merge_records(cached, fresh.items, limit)
+            }
+            None => client.list_system_info(limit, since).await?.items,
+        };
+        cache.store(endpoint, limit, since, &items);
+        return Ok(items);
+    }
+
+    let items = client.list_system_info(limit, since).await?.items;
+    cache.store(endpoint, limit, since, &items);
+    Ok(items)
+}
@@ -0,0 +1,197 @@
+//! Notification hooks fired when a chat response completes.
+//!
+//! Hooks let long-running responses announce themselves: a desktop
+//! notification, a webhook POST, or arbitrary commands that receive the
+//! event as JSON on stdin. Events fire when the terminal is unfocused,
+//! or when a response took longer than the configured threshold.
+
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+/// Hook configuration, under `hooks` in the config file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Send a desktop notification when an event fires
+    #[serde(default)]
+    pub desktop: bool,
+
+    /// Fire for focused-terminal responses that took at least this many
+    /// seconds; unfocused terminals always fire
+    #[serde(default)]
+    pub min_seconds: Option<u64>,
+
+    /// Webhook URL receiving each event as a JSON POST
+    #[serde(default)]
+    pub webhook: Option<String>,
+
+    /// Commands keyed by event name (or "*" for all events), run with
+    /// the JSON payload on stdin
+    #[serde(default)]
+    pub commands: HashMap<String, String>,
+
+    /// Terminal title updates and bell, handled in-process by the chat
+    /// loop rather than by the dispatcher
+    #[serde(default)]
+    pub terminal: crate::terminal::TerminalNotifyConfig,
+}
+
+impl HooksConfig {
+    /// Whether any hook target is configured at all
+    pub fn is_configured(&self) -> bool {
+        self.desktop || self.webhook.is_some() || !self.commands.is_empty()
+    }
+}
+
+/// Payload delivered to every hook target
+#[derive(Debug, Clone, Serialize)]
+pub struct HookEvent {
+    /// Event name, currently always "response_complete"
+    pub event: String,
+    pub timestamp: DateTime<Utc>,
+    pub session_id: Option<Uuid>,
+    pub provider: String,
+    pub model: String,
+    pub duration_ms: u64,
+    /// First line of the response, truncated for notification bodies
+    pub preview: String,
+}
+
+impl HookEvent {
+    /// Build a response-complete event with a truncated preview
+    pub fn response_complete(
+        session_id: Option<Uuid>,
+        provider: String,
+        model: String,
+        duration: Duration,
+        response: &str,
+    ) -> Self {
+        let first_line = response.lines().next().unwrap_or("");
+        let preview: String = first_line.chars().take(120).collect();
+
+        Self {
+            event: "response_complete".to_string(),
+            timestamp: Utc::now(),
+            session_id,
+            provider,
+            model,
+            duration_ms: duration.as_millis() as u64,
+            preview,
+        }
+    }
+}
+
+/// Dispatches events to the configured hook targets. Delivery is
+/// fire-and-forget: failures are reported to stderr but never interrupt
+/// the chat loop.
+#[derive(Debug, Clone, Default)]
+pub struct HookDispatcher {
+    config: HooksConfig,
+}
+
+impl HookDispatcher {
+    pub fn new(config: HooksConfig) -> Self {
+        Self { config }
+    }
+
+    /// Whether an event should fire given focus state and duration
+    pub fn should_fire(&self, focused: bool, duration: Duration) -> bool {
+        if !self.config.is_configured() {
+            return false;
+        }
+        if !focused {
+            return true;
+        }
+        self.config
+            .min_seconds
+            .is_some_and(|n| duration.as_secs() >= n)
+    }
+
+    /// Deliver an event to every configured target in background tasks
+    pub fn fire(&self, event: HookEvent) {
+        if self.config.desktop {
+            spawn_desktop_notification(&event);
+        }
+
+        if let Some(url) = &self.config.webhook {
+            spawn_webhook(url.clone(), event.clone());
+        }
+
+        for (key, command) in &self.config.commands {
+            if key == "*" || key == &event.event {
+                spawn_command(command.clone(), event.clone());
+            }
+        }
+    }
+}
+
+/// Desktop notification via notify-send, which avoids pulling a D-Bus
+/// stack into the binary; quietly does nothing where it is not installed
+fn spawn_desktop_notification(event: &HookEvent) {
+    let summary = format!("gos: {} responded", event.provider);
+    let body = format!("{} ({:.1}s)", event.preview, event.duration_ms as f64 / 1000.0);
+
+    tokio::spawn(async move {
+        let _ = tokio::process::Command::new("notify-send")
+            .arg(summary)
+            .arg(body)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await;
+    });
+}
+
+/// POST the event as JSON to a webhook URL
+fn spawn_webhook(url: String, event: HookEvent) {
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        if let Err(e) = client.post(&url).json(&event).send().await {
+            eprintln!("Webhook hook failed: {}", e);
+        }
+    });
+}
+
+/// Run a hook command through the shell with the event JSON on stdin
+fn spawn_command(command: String, event: HookEvent) {
+    tokio::spawn(async move {
+        let payload = match serde_json::to_vec(&event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                eprintln!("Failed to serialize hook payload: {}", e);
+                return;
+            }
+        };
+
+        let mut child = match tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                eprintln!("Failed to run hook command '{}': {}", command, e);
+                return;
+            }
+        };
+
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(&payload).await;
+        }
+
+        match child.wait().await {
+            Ok(status) if !status.success() => {
+                eprintln!("Hook command '{}' exited with {}", command, status);
+            }
+            Err(e) => eprintln!("Hook command '{}' failed: {}", command, e),
+            _ => {}
+        }
+    });
+}
@@ -0,0 +1,229 @@
+//! Named knowledge packs: document collections attachable to sessions.
+//!
+//! `gos kb create <name> --add file --add dir/` chunks the given
+//! documents into paragraphs-sized pieces, embeds each chunk with the
+//! local hashed embedding (see [`crate::embeddings`]), and stores the
+//! pack in the data directory. `/kb use <name>` in chat attaches a
+//! pack to the session; on every request the chunks most similar to
+//! the latest user message are injected into the system prompt within
+//! a token budget, so the model sees the relevant slices of the
+//! collection without the whole thing riding along.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::embeddings::{cosine, embed};
+use crate::usage::estimate_tokens;
+
+/// Target chunk size in characters; paragraphs are packed together up
+/// to this, and oversized paragraphs are split at it
+pub const CHUNK_MAX_CHARS: usize = 1200;
+
+/// Token budget spent on injected knowledge-pack chunks per request
+pub const KB_TOKEN_BUDGET: u64 = 4_000;
+
+/// One chunk of a source document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KbChunk {
+    /// Path of the document the chunk came from
+    pub source: String,
+    pub text: String,
+    /// Hashed embedding of the text; empty when the pack was built
+    /// with --no-embed, in which case chunks inject in document order
+    #[serde(default)]
+    pub vector: Vec<f32>,
+}
+
+/// A named collection of chunked documents on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgePack {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub chunks: Vec<KbChunk>,
+}
+
+/// Where packs live; durable data, unlike the regenerable embedding index
+pub fn packs_dir() -> PathBuf {
+    crate::paths::data_dir().join("kb")
+}
+
+/// Validate a pack name and return its file path. Names are kept to
+/// word characters so they cannot escape the packs directory.
+pub fn pack_path(name: &str) -> Result<PathBuf> {
+    if name.is_empty()
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!("Invalid pack name '{}': use letters, digits, '-' and '_'", name);
+    }
+    Ok(packs_dir().join(format!("{}.json", name)))
+}
+
+/// Load a pack by name
+pub fn load(name: &str) -> Result<KnowledgePack> {
+    let path = pack_path(name)?;
+    let content = std::fs::read_to_string(&path)
+        .map_err(|_| anyhow!("No knowledge pack named '{}'; create one with `gos kb create`", name))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse knowledge pack {}", path.display()))
+}
+
+/// Persist a pack, replacing any previous version of the same name
+pub fn save(pack: &KnowledgePack) -> Result<PathBuf> {
+    let path = pack_path(&pack.name)?;
+    std::fs::create_dir_all(packs_dir())?;
+    std::fs::write(&path, serde_json::to_string(pack)?)?;
+    Ok(path)
+}
+
+/// Names of the packs on disk, sorted
+pub fn list() -> Result<Vec<String>> {
+    let entries = match std::fs::read_dir(packs_dir()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                return None;
+            }
+            path.file_stem().and_then(|s| s.to_str()).map(String::from)
+        })
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Delete a pack; sessions that reference it just stop injecting it
+pub fn remove(name: &str) -> Result<()> {
+    let path = pack_path(name)?;
+    std::fs::remove_file(&path)
+        .map_err(|_| anyhow!("No knowledge pack named '{}'", name))
+}
+
+/// Split a document into chunks: paragraphs (blank-line separated) are
+/// packed together up to [`CHUNK_MAX_CHARS`], and a paragraph longer
+/// than the limit is split at it so no chunk dwarfs the budget
+pub fn chunk_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n") {
+        let paragraph = paragraph.trim();
+        if paragraph.is_empty() {
+            continue;
+        }
+
+        // An oversized paragraph is flushed and split on its own
+        if paragraph.chars().count() > max_chars {
+            if !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+            }
+            let chars: Vec<char> = paragraph.chars().collect();
+            for piece in chars.chunks(max_chars) {
+                chunks.push(piece.iter().collect());
+            }
+            continue;
+        }
+
+        if current.chars().count() + paragraph.chars().count() + 2 > max_chars
+            && !current.is_empty()
+        {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(paragraph);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Collect the readable text files under the given paths, recursing
+/// into directories; unreadable or non-UTF-8 files are skipped with a
+/// note rather than failing the build
+fn collect_files(paths: &[PathBuf]) -> Result<Vec<(String, String)>> {
+    let mut documents = Vec::new();
+    for path in paths {
+        collect_into(path, &mut documents)?;
+    }
+    Ok(documents)
+}
+
+fn collect_into(path: &Path, documents: &mut Vec<(String, String)>) -> Result<()> {
+    if path.is_dir() {
+        // Sort for a deterministic chunk order; read_dir order is not
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(path)
+            .with_context(|| format!("Failed to read directory {}", path.display()))?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .collect();
+        entries.sort();
+        for entry in entries {
+            collect_into(&entry, documents)?;
+        }
+        return Ok(());
+    }
+
+    match std::fs::read_to_string(path) {
+        Ok(content) => documents.push((path.display().to_string(), content)),
+        Err(e) => eprintln!("Skipping {}: {}", path.display(), e),
+    }
+    Ok(())
+}
+
+/// Build a pack from the given files and directories, chunking every
+/// document and embedding each chunk unless told not to
+pub fn build(name: &str, paths: &[PathBuf], with_embeddings: bool) -> Result<KnowledgePack> {
+    let documents = collect_files(paths)?;
+    if documents.is_empty() {
+        bail!("No readable documents under the given paths");
+    }
+
+    let mut chunks = Vec::new();
+    for (source, content) in documents {
+        for text in chunk_text(&content, CHUNK_MAX_CHARS) {
+            let vector = if with_embeddings { embed(&text) } else { Vec::new() };
+            chunks.push(KbChunk { source: source.clone(), text, vector });
+        }
+    }
+
+    Ok(KnowledgePack { name: name.to_string(), created_at: Utc::now(), chunks })
+}
+
+/// Render the injected block for one pack: the chunks most similar to
+/// the query (or the leading chunks for an unembedded pack), spending
+/// at most `budget` estimated tokens
+pub fn retrieve(pack: &KnowledgePack, query: &str, budget: u64) -> String {
+    // Embedded packs rank by similarity to the query; unembedded ones
+    // fall back to document order
+    let mut ranked: Vec<&KbChunk> = pack.chunks.iter().collect();
+    if pack.chunks.iter().any(|chunk| !chunk.vector.is_empty()) && !query.trim().is_empty() {
+        let query_vector = embed(query);
+        ranked.sort_by(|a, b| {
+            let score_a = cosine(&query_vector, &a.vector);
+            let score_b = cosine(&query_vector, &b.vector);
+            score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+
+    let mut out = format!("Knowledge pack '{}':\n", pack.name);
+    let mut remaining = budget;
+    for chunk in ranked {
+        let tokens = estimate_tokens(&chunk.text);
+        if tokens > remaining {
+            break;
+        }
+        remaining -= tokens;
+        out.push_str(&format!("\n--- {} ---\n{}\n", chunk.source, chunk.text));
+    }
+    out
+}
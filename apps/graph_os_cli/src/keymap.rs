@@ -0,0 +1,75 @@
+//! Keymap registry: the single source of truth behind the help
+//! overlay. Keybindings and slash commands live here as data, grouped
+//! by category, so the overlay and any future modes or plugins list
+//! the same bindings instead of each hardcoding its own copy.
+
+/// One keybinding or slash command shown in the help overlay
+#[derive(Debug, Clone, Copy)]
+pub struct Binding {
+    /// What the user presses or types, e.g. "Ctrl+E" or "/fork"
+    pub keys: &'static str,
+    /// Grouping header in the overlay; consecutive entries share it
+    pub category: &'static str,
+    pub description: &'static str,
+}
+
+/// Every binding the chat TUI understands, in display order. Keep
+/// entries adjacent to their category: the overlay prints a header
+/// whenever the category changes.
+pub const REGISTRY: &[Binding] = &[
+    // Keys
+    Binding { keys: "?", category: "Keys", description: "Open this help overlay (with an empty input)" },
+    Binding { keys: "Esc", category: "Keys", description: "Select the most recent message; Esc again leaves selection" },
+    Binding { keys: "Enter", category: "Keys", description: "Send the message, or run the typed slash command" },
+    Binding { keys: "Shift+Enter", category: "Keys", description: "Insert a newline instead of sending (Alt+Enter also works)" },
+    Binding { keys: "Tab", category: "Keys", description: "Complete a slash command" },
+    Binding { keys: "Ctrl+E", category: "Keys", description: "Edit the input buffer in $EDITOR" },
+    Binding { keys: "Ctrl+Z", category: "Keys", description: "Suspend to the shell; fg resumes with any stream caught up" },
+    Binding { keys: "Up/Down", category: "Keys", description: "Move the cursor across lines in a multi-line input" },
+    Binding { keys: "Ctrl+Up/Ctrl+Down", category: "Keys", description: "Grow or shrink the input box (persisted)" },
+    // Session
+    Binding { keys: "/config", category: "Session", description: "Show the current configuration" },
+    Binding { keys: "/fork", category: "Session", description: "Fork this conversation into a new session" },
+    Binding { keys: "/history", category: "Session", description: "List this session's restore points (restore with gos rollback)" },
+    Binding { keys: "/diff [session-id]", category: "Session", description: "Compare with another session (default: parent)" },
+    Binding { keys: "/share", category: "Session", description: "Upload this transcript to the configured share endpoint" },
+    Binding { keys: "/exit", category: "Session", description: "Exit the application" },
+    // Conversation
+    Binding { keys: "/template [name]", category: "Conversation", description: "Apply a prompt template, or list templates" },
+    Binding { keys: "/bookmark [index]", category: "Conversation", description: "Bookmark a message (most recent by default)" },
+    Binding { keys: "/bookmarks", category: "Conversation", description: "Browse bookmarked messages" },
+    Binding { keys: "/quote <n>", category: "Conversation", description: "Quote message n and thread the next message as a reply" },
+    Binding { keys: "/pin [index]", category: "Conversation", description: "Always send a message with the history, even after truncation" },
+    Binding { keys: "/unpin [index]", category: "Conversation", description: "Remove a pin (most recent by default)" },
+    Binding { keys: "/recall <query>", category: "Conversation", description: "Pull relevant past exchanges into context ('off' to clear)" },
+    Binding { keys: "/context add|ls|rm", category: "Conversation", description: "Attach workspace context ('repo' for git metadata)" },
+    Binding { keys: "/continue", category: "Conversation", description: "Resume an answer you stopped mid-stream" },
+    Binding { keys: "/agents [names|off]", category: "Conversation", description: "Route messages to configured personas" },
+    // Output
+    Binding { keys: "/stream", category: "Output", description: "Toggle streaming mode" },
+    Binding { keys: "/schema [file|off]", category: "Output", description: "Constrain responses to a JSON Schema, validated client-side" },
+    Binding { keys: "/run", category: "Output", description: "Execute the last assistant code block in a sandbox" },
+    Binding { keys: "/paste insert|file", category: "Output", description: "Insert a held-back large paste, or attach it as a file" },
+    Binding { keys: "/layout [compact|comfortable]", category: "Output", description: "Switch layout density; preferences persist in config" },
+    // Providers
+    Binding { keys: "/provider <name>", category: "Providers", description: "Switch provider (openai, anthropic, gemini, custom)" },
+    Binding { keys: "/model <name>", category: "Providers", description: "Set model (e.g., gpt-4o, claude-3-opus, gemini-pro)" },
+    // Diagnostics
+    Binding { keys: "/debug on|off", category: "Diagnostics", description: "Toggle debug mode" },
+    Binding { keys: "/help", category: "Diagnostics", description: "Open this help overlay" },
+];
+
+/// Bindings whose keys, category or description contain the query,
+/// case-insensitively. An empty query returns the whole registry.
+pub fn search(query: &str) -> Vec<&'static Binding> {
+    let query = query.to_lowercase();
+    REGISTRY
+        .iter()
+        .filter(|binding| {
+            query.is_empty()
+                || binding.keys.to_lowercase().contains(&query)
+                || binding.category.to_lowercase().contains(&query)
+                || binding.description.to_lowercase().contains(&query)
+        })
+        .collect()
+}
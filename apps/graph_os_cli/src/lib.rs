@@ -1,5 +1,43 @@
 pub mod adapters;
+pub mod archive;
+pub mod audit;
+pub mod auth;
+pub mod bench;
+pub mod bridge;
+pub mod error;
 pub mod session;
+pub mod daemon;
+pub mod templates;
+pub mod terminal;
+pub mod usage;
 pub mod chat;
+pub mod compress;
+pub mod context;
+pub mod crash;
+pub mod crypto;
+pub mod diff;
+pub mod embeddings;
+pub mod eval;
+pub mod export;
+pub mod filters;
+pub mod serve;
+pub mod share;
+pub mod history_cache;
+pub mod hooks;
+pub mod kb;
+pub mod keymap;
+pub mod metrics;
+pub mod paths;
+pub mod policy;
+pub mod redact;
+pub mod render;
+pub mod report;
+pub mod sandbox;
+pub mod schema;
+pub mod signing;
+pub mod snapshots;
+pub mod tasks;
+pub mod tunnel;
+pub mod watch;
 pub mod cli;
 pub mod config;
\ No newline at end of file
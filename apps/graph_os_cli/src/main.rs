@@ -1,45 +1,1912 @@
 use clap::Parser;
-use graph_os_cli::cli::{Cli, Commands, SystemInfoCommands};
-use graph_os_cli::adapters::GrpcClient;
-use tokio::net::TcpStream;
-use tokio::io::AsyncWriteExt;
-use std::time::Duration;
-use std::error::Error;
-use anyhow::Result as AnyhowResult;
+use graph_os_cli::audit::{parse_duration, AuditLog};
+use graph_os_cli::bench;
+use graph_os_cli::cli::{AuditCommands, BenchCommands, Cli, Commands, ConfigCommands, DaemonCommands, GrpcCommands, KbCommands, SessionsCommands, SystemInfoCommands, TaskCommands};
+use graph_os_cli::adapters::recording;
+use graph_os_cli::archive;
+use graph_os_cli::adapters::{GrpcAuth, GrpcClient};
+use graph_os_cli::config::ConfigManager;
+use graph_os_cli::daemon;
+use graph_os_cli::history_cache;
+use graph_os_cli::metrics;
+use graph_os_cli::paths;
+use graph_os_cli::report;
+use graph_os_cli::serve;
+use graph_os_cli::session::{ChatMessage, Session, SessionManager};
+use graph_os_cli::templates;
+use graph_os_cli::tunnel;
+use uuid::Uuid;
+use anyhow::{Context, Result};
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
+async fn main() -> Result<()> {
     // Parse command line arguments
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+
+    // Apply the config directory override before anything reads config
+    if let Some(dir) = &cli.config_dir {
+        paths::set_config_dir_override(dir.clone());
+    }
+
+    // A run that panicked leaves a marker behind; offer to pick the
+    // interrupted conversation back up. Skipped when a session was
+    // named explicitly or stdin is not a terminal to ask on.
+    if cli.session.is_none()
+        && !cli.continue_session
+        && let Some(id) = graph_os_cli::crash::take_interrupted_session()
+    {
+        use std::io::IsTerminal;
+        if std::io::stdin().is_terminal() {
+            eprint!("The last run crashed with session {} open. Resume it? [y/N] ", id);
+            let mut answer = String::new();
+            std::io::stdin().read_line(&mut answer)?;
+            if matches!(answer.trim(), "y" | "Y" | "yes") {
+                cli.session = Some(id);
+            }
+        } else {
+            eprintln!("The last run crashed with session {} open; resume with --session {}", id, id);
+        }
+    }
+
+    // --continue stands in for --session when the UUID of the most
+    // recently active session is not at hand
+    if cli.continue_session {
+        let manager = SessionManager::init().await?;
+        match manager.most_recent_session().await? {
+            Some(id) => {
+                eprintln!("Continuing session {}", id);
+                cli.session = Some(id);
+            }
+            None => anyhow::bail!("No sessions to continue"),
+        }
+    }
     
+    // Relocate files left in the legacy ~/.vibe and ~/.graph_os locations
+    for (from, to) in paths::migrate_legacy_paths()? {
+        eprintln!("Migrated {} -> {}", from.display(), to.display());
+    }
+
+    // Install request recording or replay before any adapter is built
+    if let Some(dir) = &cli.record {
+        recording::init(recording::RecordingMode::Record(dir.clone()))?;
+    }
+    if let Some(dir) = &cli.replay {
+        recording::init(recording::RecordingMode::Replay(dir.clone()))?;
+    }
+
+    // Screen-reader friendly rendering wins over any config default
+    if cli.accessible {
+        graph_os_cli::render::force_accessible();
+    }
+
+    // --via: open SSH forwards to the API and gRPC ports through the
+    // bastion, then point the flags at the local ends so every client
+    // below dials through the tunnel. The guard keeps the ssh process
+    // alive until the command (and its clients) are done.
+    let _tunnel = match &cli.via {
+        Some(spec) => {
+            let spec = tunnel::TunnelSpec::parse(spec)?;
+            eprintln!("Opening tunnel to {} via {}", cli.api_host, spec.host);
+            let tunnel = tunnel::SshTunnel::open(&spec, &cli.api_host, &[cli.api_port, cli.grpc_port]).await?;
+            cli.api_port = tunnel.local_port(cli.api_port).expect("forwarded api port");
+            cli.grpc_port = tunnel.local_port(cli.grpc_port).expect("forwarded grpc port");
+            cli.api_host = "127.0.0.1".to_string();
+            Some(tunnel)
+        }
+        None => None,
+    };
+
     match &cli.command {
         Some(Commands::SystemInfo { action }) => {
             handle_system_info(&cli, action).await?;
         },
+        Some(Commands::Bench { action }) => {
+            handle_bench(&cli, action).await?;
+        },
+        Some(Commands::Usage { by, since }) => {
+            use std::collections::BTreeMap;
+            use graph_os_cli::usage::UsageLog;
+
+            let since = since.as_deref().map(parse_duration).transpose()?;
+            let records = UsageLog::instance().read_since(since)?;
+
+            if records.is_empty() {
+                println!("No usage records found");
+            } else {
+                // Aggregate (prompt, completion, cost) per group
+                let mut groups: BTreeMap<String, (u64, u64, f64)> = BTreeMap::new();
+                for record in &records {
+                    let key = match by.as_str() {
+                        "provider" => record.provider.clone(),
+                        "model" => record.model.clone(),
+                        "session" => record
+                            .session_id
+                            .map(|id| id.to_string())
+                            .unwrap_or_else(|| "(none)".to_string()),
+                        other => anyhow::bail!(
+                            "Unknown grouping '{}': expected provider, model or session",
+                            other
+                        ),
+                    };
+                    let entry = groups.entry(key).or_default();
+                    entry.0 += record.prompt_tokens;
+                    entry.1 += record.completion_tokens;
+                    entry.2 += record.cost;
+                }
+
+                println!("{:40} {:>12} {:>12} {:>10}", by, "prompt", "completion", "cost");
+                let mut total = (0u64, 0u64, 0f64);
+                for (key, (prompt, completion, cost)) in &groups {
+                    println!("{:40} {:>12} {:>12} {:>10.4}", key, prompt, completion, cost);
+                    total.0 += prompt;
+                    total.1 += completion;
+                    total.2 += cost;
+                }
+                println!("{:40} {:>12} {:>12} {:>10.4}", "total", total.0, total.1, total.2);
+            }
+        },
+        Some(Commands::Audit { action }) => {
+            match action {
+                AuditCommands::Show { since } => {
+                    let since = since.as_deref().map(parse_duration).transpose()?;
+                    let records = AuditLog::instance().read_since(since)?;
+
+                    if records.is_empty() {
+                        println!("No audit records found");
+                    } else {
+                        for record in &records {
+                            let usage = match &record.token_usage {
+                                Some(usage) => format!(" usage={}", usage),
+                                None => String::new(),
+                            };
+                            println!(
+                                "{}  {:7} {:30} {}  {}ms  {}{}",
+                                record.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                                record.transport,
+                                record.method,
+                                record.endpoint,
+                                record.latency_ms,
+                                record.status,
+                                usage
+                            );
+                        }
+                        println!("\n{} record(s)", records.len());
+                    }
+                }
+            }
+        },
+        Some(Commands::Daemon { action }) => {
+            match action {
+                DaemonCommands::Start => daemon::start().await?,
+                DaemonCommands::Stop => daemon::stop().await?,
+                DaemonCommands::Status => daemon::status().await?,
+                DaemonCommands::Run => daemon::run().await?,
+            }
+        },
+        Some(Commands::Serve { port, script, signing_key_id, signing_key }) => {
+            let verifier = match (signing_key_id, signing_key) {
+                (Some(key_id), Some(key)) => {
+                    Some(graph_os_cli::signing::RequestVerifier::new(key_id, key))
+                }
+                _ => None,
+            };
+            serve::run(&cli.api_host, *port, script.clone(), verifier).await?;
+        },
+        Some(Commands::New { template, vars, title, tags, system, provider, json, send, message }) => {
+            let manager = SessionManager::init().await?;
+            let config = ConfigManager::instance().get_config().await?;
+            let mut session = Session::new(Uuid::new_v4());
+
+            if let Some(name) = template {
+                let tpl = config.get_template(name).ok_or_else(|| {
+                    let mut available: Vec<String> = config.templates().into_keys().collect();
+                    available.sort();
+                    anyhow::anyhow!(
+                        "Unknown template '{}'. Available templates: {}",
+                        name,
+                        if available.is_empty() { "none".to_string() } else { available.join(", ") }
+                    )
+                })?;
+
+                let vars = templates::parse_vars(vars)?;
+                if let Some(system) = &tpl.system {
+                    session.system_prompt = Some(templates::render(system, &vars)?);
+                }
+                if let Some(prompt) = &tpl.prompt {
+                    session.messages.push(ChatMessage::user(templates::render(prompt, &vars)?));
+                }
+            }
+
+            // Explicit flags win over anything the template set
+            if let Some(system) = system {
+                session.system_prompt = Some(system.clone());
+            }
+            session.title = title.clone();
+            session.tags = tags.clone();
+            if let Some(name) = provider {
+                let provider = graph_os_cli::config::ApiProvider::parse(name).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Unknown provider '{}'. Available options: openai, anthropic, gemini, custom",
+                        name
+                    )
+                })?;
+                session.provider = Some(provider.to_string().to_lowercase());
+            }
+            if let Some(message) = message {
+                session.messages.push(ChatMessage::user(message.clone()));
+            }
+
+            manager.update_session(session.clone()).await?;
+
+            // One-shot mode: send the seeded conversation right away and
+            // persist the reply so resuming picks up where this left off
+            let reply = if *send {
+                if message.is_none() && session.messages.is_empty() {
+                    anyhow::bail!("--send needs an initial message or a template prompt to send");
+                }
+                let reply = send_one_shot(&config, &session).await?;
+                session.messages.push(graph_os_cli::session::ChatMessage::assistant(reply.clone()));
+                session.last_active = chrono::Utc::now();
+                manager.update_session(session.clone()).await?;
+                Some(reply)
+            } else {
+                None
+            };
+
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&session)?);
+            } else {
+                match template {
+                    Some(name) => println!("Created session {} from template '{}'", session.id, name),
+                    None => println!("Created session {}", session.id),
+                }
+                if let Some(reply) = reply {
+                    println!("\n{}\n", reply);
+                }
+                println!("Resume it with: gos --session {}", session.id);
+            }
+        },
+        Some(Commands::Ask { schema, system, provider, retries, prompt }) => {
+            handle_ask(schema.as_deref(), system.as_deref(), provider.as_deref(), *retries, prompt).await?;
+        },
+        Some(Commands::Batch { file, concurrency, output, system, template, provider, retries }) => {
+            handle_batch(
+                file,
+                *concurrency,
+                output.as_deref(),
+                system.as_deref(),
+                template.as_deref(),
+                provider.as_deref(),
+                *retries,
+            ).await?;
+        },
+        Some(Commands::Pipe { template, vars, provider, no_stream }) => {
+            handle_pipe(template, vars, provider.as_deref(), *no_stream).await?;
+        },
+        Some(Commands::Eval { suite, providers, judge, json, output }) => {
+            handle_eval(suite, providers.as_deref(), judge.as_deref(), *json, output.as_deref()).await?;
+        },
+        Some(Commands::List { sort, limit, offset }) => {
+            use graph_os_cli::session::{format_relative, ListOptions, SessionSort};
+
+            let sort = SessionSort::parse(sort).ok_or_else(|| {
+                anyhow::anyhow!("Unknown sort key '{}'. Use last-active, created or title", sort)
+            })?;
+            let options = ListOptions {
+                sort,
+                offset: *offset,
+                limit: (*limit > 0).then_some(*limit),
+                summary_only: true,
+            };
+
+            let manager = SessionManager::init().await?;
+            // Headers come from the eager index, so listing stays fast
+            // no matter how long the conversations are
+            let (entries, total) = manager.list_index_page(options).await?;
+
+            if total == 0 {
+                println!("No sessions found");
+            } else if entries.is_empty() {
+                println!("No sessions past offset {} (showing 0 of {})", offset, total);
+            } else {
+                let now = chrono::Utc::now();
+                println!("{:<36}  {:>8}  {:<12}  {:<10}  TITLE", "ID", "MESSAGES", "LAST ACTIVE", "PROVIDER");
+                for entry in &entries {
+                    let mut title = entry.title.clone().unwrap_or_default();
+                    // Mark forked sessions with their parent and branch point
+                    if let Some(parent) = entry.parent_id {
+                        if !title.is_empty() {
+                            title.push(' ');
+                        }
+                        title.push_str(&format!(
+                            "(forked from {} at message {})",
+                            parent,
+                            entry.forked_at.unwrap_or(0)
+                        ));
+                    }
+                    println!(
+                        "{}  {:>8}  {:<12}  {:<10}  {}",
+                        entry.id,
+                        entry.messages,
+                        format_relative(entry.last_active, now),
+                        entry.provider.as_deref().unwrap_or("-"),
+                        title
+                    );
+                }
+                let first = offset + 1;
+                let last = offset + entries.len();
+                if entries.len() < total {
+                    println!(
+                        "Showing {}-{} of {} sessions. Use --offset {} for the next page.",
+                        first, last, total, last
+                    );
+                }
+            }
+        },
+        Some(Commands::Search { semantic, limit, query }) => {
+            handle_search(query, *semantic, *limit).await?;
+        },
+        Some(Commands::Watch { id }) => {
+            graph_os_cli::watch::run(*id).await?;
+        },
+        Some(Commands::Kb { action }) => {
+            use graph_os_cli::kb;
+
+            match action {
+                KbCommands::Create { name, add, no_embed } => {
+                    let pack = kb::build(name, add, !no_embed)?;
+                    let path = kb::save(&pack)?;
+                    println!(
+                        "Created pack '{}' with {} chunk(s){} at {}",
+                        name,
+                        pack.chunks.len(),
+                        if *no_embed { " (no embeddings)" } else { "" },
+                        path.display()
+                    );
+                    println!("Attach it in chat with /kb use {}", name);
+                }
+                KbCommands::List => {
+                    let names = kb::list()?;
+                    if names.is_empty() {
+                        println!("No knowledge packs. Create one with `gos kb create <name> --add <path>`");
+                    } else {
+                        for name in names {
+                            let pack = kb::load(&name)?;
+                            let tokens: u64 = pack
+                                .chunks
+                                .iter()
+                                .map(|chunk| graph_os_cli::usage::estimate_tokens(&chunk.text))
+                                .sum();
+                            println!("{:24} {:>6} chunks {:>8} tokens", name, pack.chunks.len(), tokens);
+                        }
+                    }
+                }
+                KbCommands::Remove { name } => {
+                    kb::remove(name)?;
+                    println!("Removed pack '{}'", name);
+                }
+            }
+        },
+        Some(Commands::Show { id, role, last, range, grep, format }) => {
+            use graph_os_cli::session::MessageFilter;
+
+            let filter = MessageFilter {
+                role: role.clone(),
+                grep: grep.clone(),
+                range: range.as_deref().map(parse_index_range).transpose()?,
+                last: *last,
+            };
+
+            // The header and the filtered slice travel separately so a
+            // large session is never shipped whole just to inspect it
+            let manager = SessionManager::init().await?;
+            let (session, _) = manager
+                .get_session_meta(*id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("No session found with ID {}", id))?;
+            let (messages, total) = manager
+                .get_filtered_messages(*id, &filter)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("No session found with ID {}", id))?;
+
+            match format.as_str() {
+                "json" => {
+                    let entries: Vec<serde_json::Value> = messages
+                        .iter()
+                        .map(|(index, message)| {
+                            serde_json::json!({
+                                "index": index,
+                                "role": message.role(),
+                                "text": message.text(),
+                                "meta": message.meta(),
+                            })
+                        })
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                        "session": session.id,
+                        "title": session.title,
+                        "provider": session.provider,
+                        "model": session.model,
+                        "total": total,
+                        "messages": entries,
+                    }))?);
+                }
+                "md" => {
+                    println!("# Session {}", session.id);
+                    if let Some(title) = &session.title {
+                        println!("\n{}", title);
+                    }
+                    for (index, message) in &messages {
+                        println!("\n### {} ({})\n\n{}", message.role(), index, message.text());
+                    }
+                }
+                "text" => {
+                    println!("Session {}", session.id);
+                    if let Some(title) = &session.title {
+                        println!("Title: {}", title);
+                    }
+                    if !session.tags.is_empty() {
+                        println!("Tags: {}", session.tags.join(", "));
+                    }
+                    if let Some(provider) = &session.provider {
+                        println!("Provider: {}", provider);
+                    }
+                    if let Some(model) = &session.model {
+                        println!("Model: {}", model);
+                    }
+                    println!("Created {}, last active {}",
+                        session.created_at.format("%Y-%m-%d %H:%M:%S"),
+                        session.last_active.format("%Y-%m-%d %H:%M:%S"));
+                    if let Some(parent) = session.parent_id {
+                        println!("Forked from {} at message {}", parent, session.forked_at.unwrap_or(0));
+                    }
+                    if !filter.is_empty() {
+                        println!("Showing {} of {} messages", messages.len(), total);
+                    }
+
+                    for (index, message) in &messages {
+                        println!("\n[{}] {}", index, message.role());
+                        // Show generation metadata when the message carries any
+                        if !message.meta().is_empty() {
+                            println!("    ({})", message.meta().summary());
+                        }
+                        println!("{}", message.text());
+                    }
+                }
+                other => anyhow::bail!("Unknown format '{}' (expected text, json or md)", other),
+            }
+        },
+        Some(Commands::Config { action: ConfigCommands::SetEndpoint { name, url, secret, use_tls, headers, query_params, http_version, signing_key_id, signing_key, format } }) => {
+            use graph_os_cli::config::{ConfigFormat, EndpointConfig};
+
+            let format = ConfigFormat::from_extension(format)
+                .ok_or_else(|| anyhow::anyhow!("Unknown config format '{}' (expected json, yaml or toml)", format))?;
+
+            // Start from any existing endpoint so repeated set-endpoint
+            // calls don't wipe tokens or TLS options
+            let config = ConfigManager::instance().get_config().await?;
+            let mut endpoint = config
+                .get_endpoint_config(name)
+                .unwrap_or_else(EndpointConfig::default);
+
+            endpoint.url = url.clone();
+            if secret.is_some() {
+                endpoint.secret = secret.clone();
+            }
+            if *use_tls {
+                endpoint.use_tls = Some(true);
+            }
+            if !headers.is_empty() {
+                endpoint.extra_headers = templates::parse_vars(headers)?;
+            }
+            if !query_params.is_empty() {
+                endpoint.query_params = templates::parse_vars(query_params)?;
+            }
+            if let Some(version) = http_version {
+                // Validate up front so a typo fails here, not with a
+                // warning on every later request
+                graph_os_cli::adapters::HttpVersion::parse(version).ok_or_else(|| {
+                    anyhow::anyhow!("Unknown http version '{}' (expected auto, http1 or http2)", version)
+                })?;
+                endpoint.http_version = Some(version.clone());
+            }
+            if signing_key_id.is_some() {
+                endpoint.signing_key_id = signing_key_id.clone();
+            }
+            if signing_key.is_some() {
+                endpoint.signing_key = signing_key.clone();
+            }
+
+            let path = ConfigManager::instance()
+                .set_endpoint_config(name, endpoint, format)
+                .await?;
+            println!("Endpoint '{}' saved to {}", name, path.display());
+        },
+        Some(Commands::Grpc { action: GrpcCommands::Call { path, proto, data } }) => {
+            use graph_os_cli::adapters::dynamic;
+
+            // Compile the proto and validate the request before dialing,
+            // so schema mistakes fail fast without a server round trip
+            let pool = dynamic::load_descriptor_pool(proto)?;
+            let method = dynamic::resolve_method(&pool, path)?;
+            let request = dynamic::request_from_json(&method, data)?;
+
+            let endpoint = format!("http://{}:{}", cli.api_host, cli.grpc_port);
+            let config = ConfigManager::instance().get_config().await?;
+            let auth = GrpcAuth {
+                token: None,
+                secret: config.get_rpc_secret(),
+                ..GrpcAuth::default()
+            };
+            let mut client = GrpcClient::with_endpoints_auth(vec![endpoint], auth).await?;
+
+            let response = client.call_dynamic(&method, request).await?;
+            println!("{}", dynamic::response_to_json(&response)?);
+        },
+        Some(Commands::Grpc { action: GrpcCommands::Proxy { listen, port } }) => {
+            let endpoint = format!("http://{}:{}", cli.api_host, cli.grpc_port);
+            let config = ConfigManager::instance().get_config().await?;
+            let auth = GrpcAuth {
+                token: None,
+                secret: config.get_rpc_secret(),
+                ..GrpcAuth::default()
+            };
+            let client = GrpcClient::with_endpoints_auth(vec![endpoint], auth).await?;
+
+            graph_os_cli::bridge::run(listen, *port, client).await?;
+        },
+        Some(Commands::Config { action: ConfigCommands::Encrypt { format } }) => {
+            use graph_os_cli::config::ConfigFormat;
+
+            let format = ConfigFormat::from_extension(format)
+                .ok_or_else(|| anyhow::anyhow!("Unknown config format '{}' (expected json, yaml or toml)", format))?;
+
+            let (path, changed) = ConfigManager::instance()
+                .transform_config_secrets(format, true)
+                .await?;
+            println!("Encrypted {} field(s) in {}", changed, path.display());
+        },
+        Some(Commands::Config { action: ConfigCommands::Decrypt { format } }) => {
+            use graph_os_cli::config::ConfigFormat;
+
+            let format = ConfigFormat::from_extension(format)
+                .ok_or_else(|| anyhow::anyhow!("Unknown config format '{}' (expected json, yaml or toml)", format))?;
+
+            let (path, changed) = ConfigManager::instance()
+                .transform_config_secrets(format, false)
+                .await?;
+            println!("Decrypted {} field(s) in {}", changed, path.display());
+        },
+        Some(Commands::Config { action: ConfigCommands::Show { provenance } }) => {
+            use graph_os_cli::config::ApiProvider;
+
+            let mut config = ConfigManager::instance().get_config().await?;
+            // The global flags are the highest-precedence config layer
+            config.apply_flag_overrides(cli.provider.as_deref(), cli.model.as_deref());
+
+            println!("Providers:");
+            if config.apis.is_empty() {
+                println!("  (none configured; set OPENAI_API_KEY or similar)");
+            }
+            for provider in [ApiProvider::OpenAI, ApiProvider::Anthropic, ApiProvider::Gemini, ApiProvider::Custom] {
+                if let Some(api) = config.get_api_config(provider) {
+                    let default_marker = if config.default_provider == Some(provider) { " (default)" } else { "" };
+                    let url = api.api_url.as_deref().map(|u| format!(", url {}", u)).unwrap_or_default();
+                    println!("  {}{}: model {}{}", provider, default_marker, api.model.as_deref().unwrap_or("-"), url);
+                }
+            }
+
+            match config.get_rpc_secret() {
+                Some(_) => println!("RPC secret: set"),
+                None => println!("RPC secret: not set"),
+            }
+
+            let mut endpoints: Vec<_> = config
+                .auth
+                .as_ref()
+                .map(|auth| auth.endpoints.iter().collect())
+                .unwrap_or_default();
+            endpoints.sort_by_key(|(name, _)| (*name).clone());
+            if !endpoints.is_empty() {
+                println!("Endpoints:");
+                for (name, endpoint) in endpoints {
+                    println!("  {}: {}", name, endpoint.url);
+                }
+            }
+
+            // One-line inventory of the remaining config tables
+            let templates = config.templates().len();
+            let personas = config.personas().len();
+            let prices = config.prices().len();
+            println!(
+                "Templates: {}  Personas: {}  Prices: {}  Hooks: {}",
+                templates,
+                personas,
+                prices,
+                if config.hooks().is_configured() { "configured" } else { "none" }
+            );
+
+            if *provenance {
+                println!("\nProvenance (highest-precedence layer per key):");
+                let mut entries = config.provenance.clone();
+                entries.sort_by(|a, b| a.key.cmp(&b.key));
+                for entry in entries {
+                    println!("  {:<28} {:<32} [{}]", entry.key, entry.value, entry.layer);
+                }
+            }
+        },
+        Some(Commands::Config { action: ConfigCommands::Validate { file } }) => {
+            use graph_os_cli::config::{validate_auth_config_file, Config, ConfigFormat};
+
+            let (path, format) = match file {
+                Some(path) => {
+                    let format = path
+                        .extension()
+                        .and_then(|e| e.to_str())
+                        .and_then(ConfigFormat::from_extension)
+                        .ok_or_else(|| anyhow::anyhow!(
+                            "Cannot determine config format of {} (expected .json, .yaml or .toml)",
+                            path.display()
+                        ))?;
+                    (path.clone(), format)
+                }
+                None => Config::find_auth_config_file()
+                    .ok_or_else(|| anyhow::anyhow!("No config file found; run `gos config init` first"))?,
+            };
+
+            let report = validate_auth_config_file(&path, format)?;
+
+            for warning in &report.warnings {
+                println!("warning: {}", warning);
+            }
+            for error in &report.errors {
+                println!("error: {}", error);
+            }
+
+            if report.is_ok() {
+                println!("{} is valid ({} warning(s))", path.display(), report.warnings.len());
+            } else {
+                anyhow::bail!(
+                    "{} has {} error(s) and {} warning(s)",
+                    path.display(),
+                    report.errors.len(),
+                    report.warnings.len()
+                );
+            }
+        },
+        Some(Commands::Task { action }) => {
+            use graph_os_cli::tasks;
+
+            let config = ConfigManager::instance().get_config().await?;
+            let client = graphos_rpc_client(&cli, &config);
+
+            match action {
+                TaskCommands::Run { name, input, follow } => {
+                    let input = input
+                        .as_deref()
+                        .map(serde_json::from_str::<serde_json::Value>)
+                        .transpose()
+                        .context("--input is not valid JSON")?;
+
+                    let id = tasks::run_task(&client, name, input.as_ref()).await?;
+                    if *follow {
+                        let state = tasks::follow_task(&client, &id).await?;
+                        eprintln!("Task {} {}", id, state);
+                        if state != tasks::TaskState::Succeeded {
+                            std::process::exit(state.exit_code());
+                        }
+                    } else {
+                        // Just the id, so scripts can capture it
+                        println!("{}", id);
+                    }
+                }
+                TaskCommands::Status { id } => {
+                    let (state, result) = tasks::task_status(&client, id).await?;
+                    println!("{}", serde_json::to_string_pretty(&result)?);
+                    if state != tasks::TaskState::Succeeded && state.is_terminal() {
+                        std::process::exit(state.exit_code());
+                    }
+                }
+                TaskCommands::Logs { id, follow } => {
+                    if *follow {
+                        let state = tasks::follow_task(&client, id).await?;
+                        eprintln!("Task {} {}", id, state);
+                        if state != tasks::TaskState::Succeeded {
+                            std::process::exit(state.exit_code());
+                        }
+                    } else {
+                        for line in tasks::fetch_logs(&client, id, 0).await? {
+                            println!("{}", line);
+                        }
+                    }
+                }
+            }
+        },
+        Some(Commands::Login { endpoint: name }) => {
+            use graph_os_cli::config::{Config, ConfigFormat};
+
+            let config = ConfigManager::instance().get_config().await?;
+            let mut endpoint = config.get_endpoint_config(name).ok_or_else(|| {
+                anyhow::anyhow!("No endpoint named '{}'; run `gos config set-endpoint` first", name)
+            })?;
+            let (issuer, client_id) = match (&endpoint.oidc_issuer, &endpoint.oidc_client_id) {
+                (Some(issuer), Some(client_id)) => (issuer.clone(), client_id.clone()),
+                _ => anyhow::bail!(
+                    "Endpoint '{}' has no identity provider configured; set oidc_issuer and oidc_client_id",
+                    name
+                ),
+            };
+
+            let tokens = graph_os_cli::auth::device_flow_login(&issuer, &client_id).await?;
+            graph_os_cli::auth::apply_token_set(&mut endpoint, &tokens);
+
+            let format = Config::find_auth_config_file()
+                .map(|(_, format)| format)
+                .unwrap_or(ConfigFormat::Toml);
+            let path = ConfigManager::instance()
+                .set_endpoint_config(name, endpoint, format)
+                .await?;
+
+            println!("Signed in; tokens stored in {}", path.display());
+            if let Some(expiry) = tokens.expires_at {
+                println!("Access token expires at {}", expiry);
+            }
+        },
+        Some(Commands::Share { id, expire }) => {
+            let config = ConfigManager::instance().get_config().await?;
+            let share = config.share().ok_or_else(|| anyhow::anyhow!(
+                "No share endpoint configured; add a [share] table with a url to the config file"
+            ))?;
+
+            let manager = SessionManager::init().await?;
+            let session = manager
+                .get_session(*id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("No session found with ID {}", id))?;
+
+            let expire = expire.as_deref().map(parse_duration).transpose()?;
+            let url = graph_os_cli::share::share_session(&config, &share, &session, expire).await?;
+            println!("{}", url);
+        },
+        Some(Commands::Sessions { action }) => {
+            match action {
+                SessionsCommands::EncryptAll => {
+                    let manager = SessionManager::init().await?;
+                    let (encrypted, already) = manager.encrypt_all().await?;
+                    println!(
+                        "Encrypted {} session file(s), {} already encrypted",
+                        encrypted, already
+                    );
+                }
+                SessionsCommands::Dedupe { force } => {
+                    handle_dedupe(*force).await?;
+                }
+            }
+        },
+        Some(Commands::Diff { a, b, width }) => {
+            let manager = SessionManager::init().await?;
+            let left = manager
+                .get_session(*a)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("No session found with ID {}", a))?;
+            let right = manager
+                .get_session(*b)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("No session found with ID {}", b))?;
+
+            print!("{}", graph_os_cli::diff::render_side_by_side(&left, &right, *width));
+        },
+        Some(Commands::Archive { session_id, all, older_than }) => {
+            handle_archive(session_id, *all, older_than.as_deref()).await?;
+        },
+        Some(Commands::Restore { session_id }) => {
+            handle_restore(*session_id).await?;
+        },
+        Some(Commands::Rollback { session_id, to }) => {
+            handle_rollback(*session_id, to.as_deref()).await?;
+        },
+        Some(Commands::Export { format, filters, train_last_only }) => {
+            if format != "finetune-jsonl" {
+                anyhow::bail!("Unknown format '{}'. Use finetune-jsonl", format);
+            }
+            let filters = filters
+                .iter()
+                .map(|spec| graph_os_cli::export::ExportFilter::parse(spec))
+                .collect::<Result<Vec<_>>>()?;
+
+            let manager = SessionManager::init().await?;
+            let mut sessions = manager.list_sessions().await?;
+            sessions.sort_by_key(|session| session.created_at);
+
+            // Examples go to stdout so the output pipes into a file;
+            // skip reasons and the summary go to stderr
+            let mut exported = 0usize;
+            let mut skipped = 0usize;
+            for session in &sessions {
+                if !graph_os_cli::export::session_matches(session, &filters) {
+                    continue;
+                }
+                match graph_os_cli::export::to_finetune_example(session, *train_last_only) {
+                    Ok(example) => {
+                        println!("{}", example);
+                        exported += 1;
+                    }
+                    Err(e) => {
+                        eprintln!("Skipping session {}: {}", session.id, e);
+                        skipped += 1;
+                    }
+                }
+            }
+            eprintln!("Exported {} example(s), skipped {}", exported, skipped);
+        },
+        Some(Commands::Fork { id, at }) => {
+            let manager = SessionManager::init().await?;
+            let fork = manager.fork_session(*id, *at).await?;
+            println!(
+                "Forked session {} at message {} into new session {}",
+                id,
+                fork.forked_at.unwrap_or(0),
+                fork.id
+            );
+        },
+        Some(Commands::Status) => {
+            handle_status(&cli).await?;
+        },
         _ => {
-            // Default - test gRPC connection
-            println!("Testing gRPC connection to {}:{}", cli.api_host, cli.grpc_port);
-            test_grpc_connection(&cli.api_host, cli.grpc_port).await?;
+            // Default - report server and per-service health
+            handle_status(&cli).await?;
         }
     }
     
     Ok(())
 }
 
+// Handle benchmark commands
+async fn handle_bench(cli: &Cli, action: &BenchCommands) -> Result<()> {
+    match action {
+        BenchCommands::Grpc { method, concurrency, duration } => {
+            let method = bench::BenchMethod::parse(method)?;
+
+            let endpoint = format!("http://{}:{}", cli.api_host, cli.grpc_port);
+            println!("Benchmarking gRPC endpoint: {}", endpoint);
+
+            // Send the configured RPC secret as auth metadata on every call
+            let config = ConfigManager::instance().get_config().await?;
+            let auth = GrpcAuth {
+                token: None,
+                secret: config.get_rpc_secret(),
+                ..GrpcAuth::default()
+            };
+
+            let client = GrpcClient::with_endpoints_auth(vec![endpoint], auth).await?;
+
+            let report = bench::run_grpc_bench(
+                client,
+                method,
+                (*concurrency).max(1),
+                std::time::Duration::from_secs(*duration),
+            )
+            .await;
+
+            print!("{}", report.render(method.rpc_name(), (*concurrency).max(1)));
+        }
+    }
+
+    Ok(())
+}
+
+// Archive session files to the configured backend and remove them locally
+// Find sessions whose conversation is a prefix of another's — the
+// residue of fallback saves after a reconnect — and merge each into its
+// superset, removing the duplicate's file
+async fn handle_dedupe(force: bool) -> Result<()> {
+    use graph_os_cli::session::{find_duplicate_sessions, merge_duplicate};
+
+    let manager = SessionManager::init().await?;
+    let sessions = manager.list_sessions().await?;
+    let pairs = find_duplicate_sessions(&sessions);
+
+    if pairs.is_empty() {
+        println!("No duplicate sessions found");
+        return Ok(());
+    }
+
+    let by_id: std::collections::HashMap<Uuid, &Session> =
+        sessions.iter().map(|session| (session.id, session)).collect();
+    let sessions_dir = paths::sessions_dir();
+    let mut merged = 0usize;
+
+    for (dup_id, survivor_id) in pairs {
+        let (Some(dup), Some(survivor)) = (by_id.get(&dup_id), by_id.get(&survivor_id)) else {
+            continue;
+        };
+        println!(
+            "Session {} ({} messages) duplicates the start of {} ({} messages)",
+            dup_id,
+            dup.messages.len(),
+            survivor_id,
+            survivor.messages.len()
+        );
+
+        if !force {
+            eprint!("Merge and remove {}? [y/N] ", dup_id);
+            let mut answer = String::new();
+            std::io::stdin().read_line(&mut answer)?;
+            if !matches!(answer.trim(), "y" | "Y" | "yes") {
+                println!("Skipped.");
+                continue;
+            }
+        }
+
+        let mut survivor = (*survivor).clone();
+        merge_duplicate(&mut survivor, dup);
+        manager.update_session(survivor).await?;
+
+        // Like archive: only remove the duplicate's file once the merge
+        // has landed. A file that only ever lived in listener memory is
+        // already gone from disk.
+        let path = sessions_dir.join(format!("{}.json", dup_id));
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        println!("Merged {} into {}", dup_id, survivor_id);
+        merged += 1;
+    }
+
+    println!("Merged {} duplicate session(s)", merged);
+    Ok(())
+}
+
+async fn handle_archive(session_id: &Option<Uuid>, all: bool, older_than: Option<&str>) -> Result<()> {
+    let config = ConfigManager::instance().get_config().await?;
+    let backend = archive::backend_from_config(&config.archive())?;
+    let sessions_dir = paths::sessions_dir();
+
+    // Resolve which session files to ship
+    let mut targets: Vec<(Uuid, std::path::PathBuf)> = Vec::new();
+    if all {
+        let cutoff = older_than.map(archive::parse_age).transpose()?;
+        for entry in std::fs::read_dir(&sessions_dir)? {
+            let path = entry?.path();
+            if path.extension().unwrap_or_default() != "json" {
+                continue;
+            }
+            let Some(id) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| Uuid::parse_str(stem).ok())
+            else {
+                continue;
+            };
+            // Age is judged by file modification time, so encrypted
+            // sessions never need to be opened here
+            if let Some(cutoff) = cutoff {
+                let modified = std::fs::metadata(&path)?.modified()?;
+                match modified.elapsed() {
+                    Ok(age) if age >= cutoff => {}
+                    _ => continue,
+                }
+            }
+            targets.push((id, path));
+        }
+        if targets.is_empty() {
+            println!("No sessions to archive");
+            return Ok(());
+        }
+    } else if let Some(id) = session_id {
+        let path = sessions_dir.join(format!("{}.json", id));
+        if !path.exists() {
+            anyhow::bail!("No session found with ID {}", id);
+        }
+        targets.push((*id, path));
+    } else {
+        anyhow::bail!("Provide a session ID or --all");
+    }
+
+    for (id, path) in targets {
+        let contents = std::fs::read(&path)?;
+        let bundle = archive::bundle(&contents);
+        backend.put(&format!("{}.gosb", id), &bundle).await?;
+        // Only remove the local copy once the upload has succeeded
+        std::fs::remove_file(&path)?;
+        println!(
+            "Archived session {} to {} ({} -> {} bytes)",
+            id,
+            backend.name(),
+            contents.len(),
+            bundle.len()
+        );
+    }
+
+    Ok(())
+}
+
+// Pull an archived session bundle back into the sessions directory
+async fn handle_restore(session_id: Uuid) -> Result<()> {
+    let config = ConfigManager::instance().get_config().await?;
+    let backend = archive::backend_from_config(&config.archive())?;
+
+    let bundle = backend.get(&format!("{}.gosb", session_id)).await?;
+    let contents = archive::unbundle(&bundle)?;
+
+    let sessions_dir = paths::sessions_dir();
+    std::fs::create_dir_all(&sessions_dir)?;
+    let path = sessions_dir.join(format!("{}.json", session_id));
+    if path.exists() {
+        anyhow::bail!("Session {} already exists locally", session_id);
+    }
+    std::fs::write(&path, contents)?;
+    println!("Restored session {} from {}", session_id, backend.name());
+
+    Ok(())
+}
+
+// Roll a session back to an earlier snapshot, or list its snapshots
+// when --to is omitted
+async fn handle_rollback(session_id: Uuid, to: Option<&str>) -> Result<()> {
+    use graph_os_cli::snapshots;
+
+    let Some(name) = to else {
+        let snapshots = snapshots::list_snapshots(session_id)?;
+        if snapshots.is_empty() {
+            println!("No snapshots for session {}. The chat UI takes them automatically as the conversation grows.", session_id);
+            return Ok(());
+        }
+        println!("Snapshots for session {} (newest first):", session_id);
+        for info in &snapshots {
+            println!("  {}", snapshots::describe(info));
+        }
+        println!("\nRestore one with: gos rollback {} --to <snapshot>", session_id);
+        return Ok(());
+    };
+
+    let restored = snapshots::load_snapshot(session_id, name)?;
+    if restored.id != session_id {
+        anyhow::bail!("Snapshot {} belongs to session {}, not {}", name, restored.id, session_id);
+    }
+
+    let manager = SessionManager::init().await?;
+
+    // Snapshot the current state first so the rollback itself can be
+    // rolled back
+    if let Some(current) = manager.get_session(session_id).await? {
+        graph_os_cli::snapshots::take_snapshot(&current, "rollback")?;
+    }
+
+    let messages = restored.messages.len();
+    manager.update_session(restored).await?;
+    println!(
+        "Rolled session {} back to snapshot {} ({} message{}).",
+        session_id,
+        name,
+        messages,
+        if messages == 1 { "" } else { "s" }
+    );
+
+    Ok(())
+}
+
+// Search past conversations: substring match by default, cosine
+// similarity over the embedding index with --semantic (embedded through
+// the configured provider when the endpoint sets an embedding_model,
+// locally otherwise)
+async fn handle_search(query: &str, semantic: bool, limit: usize) -> Result<()> {
+    use graph_os_cli::embeddings::{Embedder, EmbeddingIndex};
+
+    let manager = SessionManager::init().await?;
+    let sessions = manager.list_sessions().await?;
+
+    // (session, message index, role, preview) per hit, best first
+    let hits: Vec<(Uuid, usize, String, String)> = if semantic {
+        let config = ConfigManager::instance().get_config().await?;
+        let embedder = Embedder::from_config(&config);
+
+        // Refresh the index against the current session files, then
+        // persist it so the embedding work amortizes across searches
+        let path = EmbeddingIndex::default_path();
+        let mut index = EmbeddingIndex::load(&path)?;
+        let reindexed = index.refresh(&sessions, &embedder).await?;
+        if reindexed > 0 {
+            index.save(&path)?;
+        }
+
+        index
+            .search(query, limit, &embedder)
+            .await?
+            .into_iter()
+            .map(|(score, entry)| {
+                (
+                    entry.session_id,
+                    entry.index,
+                    format!("{:.2}", score),
+                    entry.preview.clone(),
+                )
+            })
+            .collect()
+    } else {
+        let needle = query.to_lowercase();
+        let mut matches = Vec::new();
+        'sessions: for session in &sessions {
+            for (index, message) in session.messages.iter().enumerate() {
+                if message.text().to_lowercase().contains(&needle) {
+                    let preview: String =
+                        message.text().lines().next().unwrap_or_default().chars().take(120).collect();
+                    matches.push((session.id, index, message.role().to_string(), preview));
+                    if matches.len() >= limit {
+                        break 'sessions;
+                    }
+                }
+            }
+        }
+        matches
+    };
+
+    if hits.is_empty() {
+        println!("No matches for '{}'", query);
+        return Ok(());
+    }
+
+    for (session_id, index, tag, preview) in &hits {
+        println!("{} [{}] ({})  {}", session_id, index, tag, preview);
+    }
+    println!("\nOpen a session with: gos show <id>");
+
+    Ok(())
+}
+
+/// Parse a `--range` index spec like `10..20`, `10..` or `..20` into a
+/// half-open range; either end may be omitted
+fn parse_index_range(spec: &str) -> Result<(usize, usize)> {
+    let (start, end) = spec
+        .split_once("..")
+        .ok_or_else(|| anyhow::anyhow!("Invalid range '{}': expected start..end", spec))?;
+
+    let start = if start.is_empty() {
+        0
+    } else {
+        start.parse().map_err(|_| anyhow::anyhow!("Invalid range start '{}'", start))?
+    };
+    let end = if end.is_empty() {
+        usize::MAX
+    } else {
+        end.parse().map_err(|_| anyhow::anyhow!("Invalid range end '{}'", end))?
+    };
+    if end < start {
+        anyhow::bail!("Invalid range '{}': end precedes start", spec);
+    }
+    Ok((start, end))
+}
+
+/// Build a JSON-RPC client for the GraphOS server itself (not an API
+/// provider), from the --api-host/--api-port flags plus the "default"
+/// endpoint's token and transport options
+fn graphos_rpc_client(cli: &Cli, config: &graph_os_cli::config::Config) -> graph_os_cli::adapters::JsonRpcClient {
+    use graph_os_cli::adapters::{HttpClientOptions, JsonRpcClient};
+
+    let endpoint_config = config.get_endpoint_config("default");
+    let http_options = HttpClientOptions::from_env().merge_endpoint(endpoint_config.as_ref());
+    let scheme = if cli.use_https { "https" } else { "http" };
+    let endpoint = format!("{}://{}:{}/api/jsonrpc", scheme, cli.api_host, cli.api_port);
+    let token = endpoint_config.as_ref().and_then(|e| e.token.clone());
+
+    JsonRpcClient::with_endpoint_options(endpoint, token, None, config.get_rpc_secret(), &http_options)
+}
+
+// Send a freshly seeded conversation over JSON-RPC and return the reply
+/// Build a client for a one-off request outside the TUI, using the
+/// given provider or the configured default
+fn one_shot_client(
+    config: &graph_os_cli::config::Config,
+    provider: Option<graph_os_cli::config::ApiProvider>,
+) -> Result<graph_os_cli::adapters::JsonRpcClient> {
+    use graph_os_cli::adapters::{HttpClientOptions, JsonRpcClient};
+
+    let api_config = provider
+        .or(config.default_provider)
+        .and_then(|p| config.get_api_config(p))
+        .ok_or_else(|| anyhow::anyhow!("No API provider configured; add one with `gos config set-api`"))?;
+    let endpoint = api_config
+        .api_url
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("Provider {} has no api_url configured", api_config.provider))?;
+
+    let http_options = HttpClientOptions::from_env().merge_endpoint(config.get_endpoint_config("default").as_ref());
+    Ok(JsonRpcClient::with_endpoint_options(
+        endpoint,
+        Some(api_config.api_key.clone()),
+        api_config.model.clone(),
+        config.get_rpc_secret(),
+        &http_options,
+    ))
+}
+
+async fn send_one_shot(config: &graph_os_cli::config::Config, session: &Session) -> Result<String> {
+    use graph_os_cli::adapters::{Message, MessageContent, MessageRole};
+    use graph_os_cli::config::ApiProvider;
+
+    let provider = session.provider.as_deref().and_then(ApiProvider::parse);
+    let mut client = one_shot_client(config, provider)?;
+    // The session's own model choice beats the provider's default
+    if session.model.is_some() {
+        client.model = session.model.clone();
+    }
+
+    let mut messages = Vec::new();
+    if let Some(system) = &session.system_prompt {
+        messages.push(Message {
+            role: MessageRole::System,
+            content: MessageContent::Text(system.clone()),
+        });
+    }
+    for message in &session.messages {
+        let role = match message.role() {
+            "assistant" => MessageRole::Assistant,
+            _ => MessageRole::User,
+        };
+        messages.push(Message {
+            role,
+            content: MessageContent::Text(message.text().to_string()),
+        });
+    }
+
+    Ok(client.chat(messages, false, None).await?)
+}
+
+/// Handle `gos ask`: a one-shot question, optionally constrained to a
+/// JSON Schema. With a schema only the validated JSON reaches stdout,
+/// so the output is safe to pipe into other tools.
+async fn handle_ask(
+    schema_path: Option<&std::path::Path>,
+    system: Option<&str>,
+    provider: Option<&str>,
+    retries: usize,
+    prompt: &str,
+) -> Result<()> {
+    use graph_os_cli::adapters::{Message, MessageContent, MessageRole};
+    use graph_os_cli::config::ApiProvider;
+    use graph_os_cli::policy::{self, PolicyEngine};
+    use graph_os_cli::redact::{self, OutboundScanner, RedactMode};
+    use graph_os_cli::schema;
+
+    let config = ConfigManager::instance().get_config().await?;
+
+    // Admin guardrails run first: a blocked topic denies the send
+    // before anything else looks at the prompt
+    let policy = PolicyEngine::from_config(&config.policy());
+    if let Err(denial) = policy.check_prompt(prompt) {
+        policy::audit_denial("prompt", &denial);
+        anyhow::bail!("Not sent: {}.", denial.message);
+    }
+
+    // Outbound secret scan, the same pipeline the chat runs before a
+    // message leaves the machine
+    let redactor = OutboundScanner::from_config(&config.redact());
+    let mut redactions = std::collections::HashMap::new();
+    let detections = redactor.scan(prompt);
+    let prompt = match redactor.mode() {
+        RedactMode::Block if !detections.is_empty() => {
+            anyhow::bail!(
+                "Prompt contains {}. Edit it, or set redact.mode to \"warn\" or \"mask\".",
+                OutboundScanner::describe(&detections)
+            );
+        }
+        RedactMode::Mask => redactor.mask(prompt, &mut redactions),
+        _ => {
+            if !detections.is_empty() {
+                eprintln!(
+                    "Warning: prompt contains {}; sending anyway.",
+                    OutboundScanner::describe(&detections)
+                );
+            }
+            prompt.to_string()
+        }
+    };
+    let provider = match provider {
+        Some(name) => Some(ApiProvider::parse(name).ok_or_else(|| {
+            anyhow::anyhow!("Unknown provider '{}'. Available options: openai, anthropic, gemini, custom", name)
+        })?),
+        None => None,
+    };
+    // The allow list is checked against whichever provider would
+    // actually serve the request, flag or configured default
+    if let Some(effective) = provider.or(config.default_provider)
+        && let Err(denial) = policy.check_provider(&effective.to_string())
+    {
+        policy::audit_denial("provider", &denial);
+        anyhow::bail!("Not sent: {}.", denial.message);
+    }
+    let client = one_shot_client(&config, provider)?;
+
+    let schema = match schema_path {
+        Some(path) => {
+            let text = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read schema file {}", path.display()))?;
+            Some(serde_json::from_str::<serde_json::Value>(&text)
+                .with_context(|| format!("Schema file {} is not valid JSON", path.display()))?)
+        }
+        None => None,
+    };
+
+    let mut messages = Vec::new();
+    // The schema instruction rides in the system prompt, after any
+    // user-supplied one
+    let system_prompt = match (&system, &schema) {
+        (Some(system), Some(schema)) => Some(format!("{}\n\n{}", system, schema::schema_instruction(schema))),
+        (Some(system), None) => Some(system.to_string()),
+        (None, Some(schema)) => Some(schema::schema_instruction(schema)),
+        (None, None) => None,
+    };
+    if let Some(system_prompt) = system_prompt {
+        messages.push(Message {
+            role: MessageRole::System,
+            content: MessageContent::Text(system_prompt),
+        });
+    }
+    messages.push(Message {
+        role: MessageRole::User,
+        content: MessageContent::Text(prompt.to_string()),
+    });
+
+    match schema {
+        Some(schema) => {
+            let result = schema::request_structured(&client, messages, &schema, retries).await?;
+            match result.value {
+                // Masked values are restored locally, so output the
+                // user scripts against matches what they wrote
+                Some(value) => println!("{}", redact::restore(&serde_json::to_string_pretty(&value)?, &redactions)),
+                None => {
+                    eprintln!("Response after {} attempt(s):\n{}", result.attempts, result.raw);
+                    anyhow::bail!(
+                        "Response failed schema validation after {} attempt(s):\n{}",
+                        result.attempts,
+                        result.errors.join("\n")
+                    );
+                }
+            }
+        }
+        None => {
+            let response = policy.enforce_response(&client.chat(messages, false, None).await?);
+            println!("{}", redact::restore(&response, &redactions));
+        }
+    }
+    Ok(())
+}
+
+/// Handle `gos pipe`: read stdin, render it into a named prompt
+/// template, and write only the model output to stdout so the command
+/// composes in shell pipelines (`journalctl -xe | gos pipe explain-error`).
+/// Streams by default so long responses start appearing immediately.
+async fn handle_pipe(
+    template_name: &str,
+    vars: &[String],
+    provider: Option<&str>,
+    no_stream: bool,
+) -> Result<()> {
+    use std::io::Read as _;
+
+    use graph_os_cli::adapters::{Message, MessageContent, MessageRole};
+    use graph_os_cli::config::ApiProvider;
+    use graph_os_cli::policy::{self, PolicyEngine};
+    use graph_os_cli::redact::{self, OutboundScanner, RedactMode};
+    use graph_os_cli::templates;
+
+    let config = ConfigManager::instance().get_config().await?;
+    let template = config.get_template(template_name).ok_or_else(|| {
+        anyhow::anyhow!("No template named '{}' in the config file", template_name)
+    })?;
+
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .context("Failed to read stdin")?;
+    let input = input.trim_end().to_string();
+    if input.is_empty() {
+        anyhow::bail!("Nothing on stdin to pipe through template '{}'", template_name);
+    }
+
+    let mut vars = templates::parse_vars(vars)?;
+    vars.insert("input".to_string(), input.clone());
+
+    // The template's prompt decides where stdin lands: an {{input}}
+    // placeholder puts it exactly there, a prompt without one gets
+    // stdin appended, and a template with no prompt sends stdin bare
+    let prompt = match &template.prompt {
+        Some(text) if templates::variables(text).iter().any(|name| name == "input") => {
+            templates::render(text, &vars)?
+        }
+        Some(text) => format!("{}\n\n{}", templates::render(text, &vars)?, input),
+        None => input,
+    };
+    let system = match &template.system {
+        Some(text) => Some(templates::render(text, &vars)?),
+        None => None,
+    };
+
+    // Admin guardrails, then the outbound secret scan, before anything
+    // leaves the machine; all diagnostics go to stderr so stdout stays
+    // clean for the pipeline
+    let policy = PolicyEngine::from_config(&config.policy());
+    if let Err(denial) = policy.check_prompt(&prompt) {
+        policy::audit_denial("prompt", &denial);
+        anyhow::bail!("Not sent: {}.", denial.message);
+    }
+    let redactor = OutboundScanner::from_config(&config.redact());
+    let mut redactions = std::collections::HashMap::new();
+    let detections = redactor.scan(&prompt);
+    let prompt = match redactor.mode() {
+        RedactMode::Block if !detections.is_empty() => {
+            anyhow::bail!(
+                "Input contains {}. Edit it, or set redact.mode to \"warn\" or \"mask\".",
+                OutboundScanner::describe(&detections)
+            );
+        }
+        RedactMode::Mask => redactor.mask(&prompt, &mut redactions),
+        _ => {
+            if !detections.is_empty() {
+                eprintln!(
+                    "Warning: input contains {}; sending anyway.",
+                    OutboundScanner::describe(&detections)
+                );
+            }
+            prompt
+        }
+    };
+
+    let provider = match provider {
+        Some(name) => Some(ApiProvider::parse(name).ok_or_else(|| {
+            anyhow::anyhow!("Unknown provider '{}'. Available options: openai, anthropic, gemini, custom", name)
+        })?),
+        None => None,
+    };
+    if let Some(effective) = provider.or(config.default_provider)
+        && let Err(denial) = policy.check_provider(&effective.to_string())
+    {
+        policy::audit_denial("provider", &denial);
+        anyhow::bail!("Not sent: {}.", denial.message);
+    }
+    let client = one_shot_client(&config, provider)?;
+
+    let mut messages = Vec::new();
+    if let Some(system) = system {
+        messages.push(Message {
+            role: MessageRole::System,
+            content: MessageContent::Text(system),
+        });
+    }
+    messages.push(Message {
+        role: MessageRole::User,
+        content: MessageContent::Text(prompt),
+    });
+
+    // Masked values are restored on the assembled response, so masking
+    // (like --no-stream) takes the buffered path; a placeholder split
+    // across stream chunks could never be restored. A policy response
+    // cap buffers too, since it applies to the finished response.
+    let response = if no_stream || !redactions.is_empty() || policy.max_response_chars().is_some() {
+        let response = policy.enforce_response(&client.chat(messages, false, None).await?);
+        print!("{}", redact::restore(&response, &redactions));
+        response
+    } else {
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(32);
+        let printer = tokio::spawn(async move {
+            use std::io::Write as _;
+            let mut stdout = std::io::stdout();
+            while let Some(chunk) = rx.recv().await {
+                let _ = stdout.write_all(chunk.as_bytes());
+                let _ = stdout.flush();
+            }
+        });
+
+        let response = client.chat(messages, true, Some(tx)).await?;
+        // chat() dropped its sender, so the printer drains and exits
+        let _ = printer.await;
+        response
+    };
+
+    // Keep shell pipelines line-oriented without doctoring the output
+    // beyond a terminating newline
+    if !response.ends_with('\n') {
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Handle `gos eval`: run a prompt suite against each named provider,
+/// score the answers, and report pass rates and latency per model.
+/// Cases run sequentially per provider so latency numbers are not
+/// polluted by local queueing.
+async fn handle_eval(
+    suite_path: &std::path::Path,
+    providers: Option<&str>,
+    judge: Option<&str>,
+    json: bool,
+    output: Option<&std::path::Path>,
+) -> Result<()> {
+    use graph_os_cli::adapters::{Message, MessageContent, MessageRole};
+    use graph_os_cli::config::ApiProvider;
+    use graph_os_cli::eval::{self, CaseResult, Expectation, Suite};
+
+    let config = ConfigManager::instance().get_config().await?;
+
+    let text = std::fs::read_to_string(suite_path)
+        .with_context(|| format!("Failed to read suite file {}", suite_path.display()))?;
+    let suite = Suite::parse(&text)?;
+    let suite_name = suite.name.clone().unwrap_or_else(|| {
+        suite_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "suite".to_string())
+    });
+
+    // One client per compared provider, in the order given
+    let providers: Vec<ApiProvider> = match providers {
+        Some(list) => list
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                ApiProvider::parse(name).ok_or_else(|| {
+                    anyhow::anyhow!("Unknown provider '{}'. Available options: openai, anthropic, gemini, custom", name)
+                })
+            })
+            .collect::<Result<_>>()?,
+        None => vec![config
+            .default_provider
+            .ok_or_else(|| anyhow::anyhow!("No providers given and no default provider configured"))?],
+    };
+    let clients: Vec<(String, graph_os_cli::adapters::JsonRpcClient)> = providers
+        .iter()
+        .map(|&provider| Ok((provider.to_string().to_lowercase(), one_shot_client(&config, Some(provider))?)))
+        .collect::<Result<_>>()?;
+
+    // Judge checks get their own client so the grader need not be one
+    // of the compared models; built lazily since most suites are local
+    let judge_provider = match judge {
+        Some(name) => Some(ApiProvider::parse(name).ok_or_else(|| {
+            anyhow::anyhow!("Unknown provider '{}'. Available options: openai, anthropic, gemini, custom", name)
+        })?),
+        None => None,
+    };
+    let needs_judge = suite.cases.iter().any(|case| matches!(case.expect, Expectation::Judge(_)));
+    let judge_client = if needs_judge {
+        Some(one_shot_client(&config, judge_provider)?)
+    } else {
+        None
+    };
+
+    let mut results: Vec<CaseResult> = Vec::new();
+    for (provider, client) in &clients {
+        for (index, case) in suite.cases.iter().enumerate() {
+            let mut messages = Vec::new();
+            if let Some(system) = &suite.system {
+                messages.push(Message {
+                    role: MessageRole::System,
+                    content: MessageContent::Text(system.clone()),
+                });
+            }
+            messages.push(Message {
+                role: MessageRole::User,
+                content: MessageContent::Text(case.prompt.clone()),
+            });
+
+            let started = std::time::Instant::now();
+            let response = client.chat(messages, false, None).await;
+            let latency_ms = started.elapsed().as_millis() as u64;
+
+            // A failed request scores as a failed case rather than
+            // aborting the run, so one flaky provider still reports
+            let (passed, detail, response) = match response {
+                Ok(response) => {
+                    let verdict = match case.expect.check_local(&response) {
+                        Some(verdict) => verdict,
+                        None => {
+                            // Judge check: ask the grader for a verdict
+                            let Expectation::Judge(criterion) = &case.expect else {
+                                unreachable!("check_local is None only for judge checks");
+                            };
+                            let judge_messages = vec![Message {
+                                role: MessageRole::User,
+                                content: MessageContent::Text(eval::judge_prompt(
+                                    &case.prompt,
+                                    &response,
+                                    criterion,
+                                )),
+                            }];
+                            match judge_client.as_ref().unwrap().chat(judge_messages, false, None).await {
+                                Ok(verdict) => eval::parse_judge_verdict(&verdict),
+                                Err(e) => Err(format!("judge request failed: {}", e)),
+                            }
+                        }
+                    };
+                    match verdict {
+                        Ok(()) => (true, None, response),
+                        Err(detail) => (false, Some(detail), response),
+                    }
+                }
+                Err(e) => (false, Some(format!("request failed: {}", e)), String::new()),
+            };
+
+            eprintln!(
+                "{} {} / {} ({}ms)",
+                if passed { "PASS" } else { "FAIL" },
+                provider,
+                case.display_name(index),
+                latency_ms
+            );
+            results.push(CaseResult {
+                case: case.display_name(index),
+                provider: provider.clone(),
+                passed,
+                detail,
+                latency_ms,
+                response,
+            });
+        }
+    }
+
+    let report = if json {
+        serde_json::to_string_pretty(&serde_json::json!({
+            "suite": suite_name,
+            "summary": eval::summarize(&results),
+            "results": results,
+        }))?
+    } else {
+        eval::render_markdown(&suite_name, &results)
+    };
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, &report)
+                .with_context(|| format!("Failed to write report to {}", path.display()))?;
+            println!("Wrote report to {}", path.display());
+        }
+        None => println!("{}", report),
+    }
+
+    Ok(())
+}
+
+/// Handle `gos batch`: run every prompt in a file as its own one-shot
+/// request, a bounded number in flight at once, and emit one JSON
+/// object per prompt. Failed requests are retried with backoff so a
+/// transient rate limit does not poison the run.
+async fn handle_batch(
+    file: &std::path::Path,
+    concurrency: usize,
+    output: Option<&std::path::Path>,
+    system: Option<&str>,
+    template: Option<&str>,
+    provider: Option<&str>,
+    retries: usize,
+) -> Result<()> {
+    use futures_util::StreamExt;
+    use graph_os_cli::adapters::{Message, MessageContent, MessageRole};
+    use graph_os_cli::config::ApiProvider;
+    use graph_os_cli::redact::{self, OutboundScanner, RedactMode};
+
+    let config = ConfigManager::instance().get_config().await?;
+
+    // A shared system prompt comes from --system or from the named
+    // template, the same presets the chat's /template uses
+    let system_prompt = match (system, template) {
+        (Some(system), _) => Some(system.to_string()),
+        (None, Some(name)) => config
+            .get_template(name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown template '{}'", name))?
+            .system,
+        (None, None) => None,
+    };
+
+    let provider = match provider {
+        Some(name) => Some(ApiProvider::parse(name).ok_or_else(|| {
+            anyhow::anyhow!("Unknown provider '{}'. Available options: openai, anthropic, gemini, custom", name)
+        })?),
+        None => None,
+    };
+    let client = one_shot_client(&config, provider)?;
+
+    let text = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read prompt file {}", file.display()))?;
+
+    // One prompt per line, 1-based so results point back at the file;
+    // blank lines and # comments structure the file without cost
+    let prompts: Vec<(usize, String)> = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.trim();
+            !line.is_empty() && !line.starts_with('#')
+        })
+        .map(|(i, line)| (i + 1, line.trim().to_string()))
+        .collect();
+    if prompts.is_empty() {
+        anyhow::bail!("No prompts in {}", file.display());
+    }
+    let total = prompts.len();
+
+    // Outbound secret scan up front, before anything is in flight:
+    // block mode turns a flagged prompt into an error record, mask
+    // mode sends placeholders and restores them in the stored response
+    // One prompt's worth of work for the request pool
+    struct BatchJob {
+        line: usize,
+        prompt: String,
+        outbound: String,
+        redactions: std::collections::HashMap<String, String>,
+        blocked: Option<String>,
+    }
+
+    let redactor = OutboundScanner::from_config(&config.redact());
+    let jobs: Vec<BatchJob> = prompts
+        .into_iter()
+        .map(|(line, prompt)| {
+            let mut redactions = std::collections::HashMap::new();
+            let detections = redactor.scan(&prompt);
+            let mut blocked = None;
+            let outbound = match redactor.mode() {
+                RedactMode::Block if !detections.is_empty() => {
+                    blocked = Some(format!(
+                        "prompt contains {} and redact.mode is \"block\"",
+                        OutboundScanner::describe(&detections)
+                    ));
+                    prompt.clone()
+                }
+                RedactMode::Mask => redactor.mask(&prompt, &mut redactions),
+                _ => {
+                    if !detections.is_empty() {
+                        eprintln!(
+                            "Warning: prompt on line {} contains {}; sending anyway.",
+                            line,
+                            OutboundScanner::describe(&detections)
+                        );
+                    }
+                    prompt.clone()
+                }
+            };
+            BatchJob { line, prompt, outbound, redactions, blocked }
+        })
+        .collect();
+
+    // Run the prompts through a bounded pool; --concurrency is the
+    // rate-limit knob, and per-request retries back off quadratically
+    let mut results: Vec<serde_json::Value> = futures_util::stream::iter(jobs.into_iter().map(
+        |BatchJob { line, prompt, outbound, redactions, blocked }| {
+            let client = client.clone();
+            let system_prompt = system_prompt.clone();
+            async move {
+                if let Some(reason) = blocked {
+                    return serde_json::json!({
+                        "line": line, "prompt": prompt, "error": reason, "attempts": 0,
+                    });
+                }
+
+                let mut messages = Vec::new();
+                if let Some(system) = &system_prompt {
+                    messages.push(Message {
+                        role: MessageRole::System,
+                        content: MessageContent::Text(system.clone()),
+                    });
+                }
+                messages.push(Message {
+                    role: MessageRole::User,
+                    content: MessageContent::Text(outbound),
+                });
+
+                let started = std::time::Instant::now();
+                let mut attempts = 0usize;
+                loop {
+                    attempts += 1;
+                    match client.chat(messages.clone(), false, None).await {
+                        Ok(response) => {
+                            return serde_json::json!({
+                                "line": line,
+                                "prompt": prompt,
+                                "response": redact::restore(&response, &redactions),
+                                "attempts": attempts,
+                                "latency_ms": started.elapsed().as_millis() as u64,
+                            });
+                        }
+                        Err(e) if attempts <= retries => {
+                            eprintln!("Prompt on line {} failed (attempt {}): {}; retrying", line, attempts, e);
+                            let backoff = 500 * (attempts * attempts) as u64;
+                            tokio::time::sleep(std::time::Duration::from_millis(backoff)).await;
+                        }
+                        Err(e) => {
+                            return serde_json::json!({
+                                "line": line, "prompt": prompt, "error": e.to_string(), "attempts": attempts,
+                            });
+                        }
+                    }
+                }
+            }
+        },
+    ))
+    .buffer_unordered(concurrency.max(1))
+    .collect()
+    .await;
+
+    // Completion order is whatever the pool produced; put the file's
+    // order back before writing
+    results.sort_by_key(|r| r["line"].as_u64());
+    let failed = results.iter().filter(|r| r.get("error").is_some()).count();
+
+    let mut lines = String::new();
+    for result in &results {
+        lines.push_str(&serde_json::to_string(result)?);
+        lines.push('\n');
+    }
+    match output {
+        Some(path) => {
+            std::fs::write(path, lines)
+                .with_context(|| format!("Failed to write results to {}", path.display()))?;
+            println!("Wrote {} result(s) to {} ({} failed)", total, path.display(), failed);
+        }
+        None => {
+            // Results on stdout so they pipe cleanly; the summary goes
+            // to stderr
+            print!("{}", lines);
+            eprintln!("{} prompt(s), {} failed", total, failed);
+        }
+    }
+
+    Ok(())
+}
+
 // Handle system info commands
-async fn handle_system_info(cli: &Cli, action: &Option<SystemInfoCommands>) -> Result<(), Box<dyn Error>> {
+async fn handle_system_info(cli: &Cli, action: &Option<SystemInfoCommands>) -> Result<()> {
+    // Fleet builds its own clients, one per queried host
+    if let Some(SystemInfoCommands::Fleet { endpoints, sort }) = action {
+        return handle_fleet(endpoints.as_deref(), sort).await;
+    }
+
+    // Report reads the local recording only; no server needed
+    if let Some(SystemInfoCommands::Report { since, columns }) = action {
+        let since = parse_duration(since)?;
+        let columns = metrics::parse_trend_columns(
+            columns.as_deref().unwrap_or(metrics::DEFAULT_TREND_COLUMNS),
+        )?;
+        let samples = metrics::MetricsLog::instance().read_since(Some(since))?;
+        let config = ConfigManager::instance().get_config().await?;
+        let style = graph_os_cli::render::RenderStyle::detect(config.accessible());
+        print!("{}", metrics::render_trends(&samples, &columns, &style));
+        return Ok(());
+    }
+
     let endpoint = format!("http://{}:{}", cli.api_host, cli.grpc_port);
-    println!("Connecting to gRPC endpoint: {}", endpoint);
+    // Export output is meant to be piped into a monitoring stack, so
+    // keep stdout clean of status chatter there
+    if !matches!(action, Some(SystemInfoCommands::Export { .. })) {
+        println!("Connecting to gRPC endpoint: {}", endpoint);
+    }
     
+    // Send the configured RPC secret as auth metadata on every call
+    let config = ConfigManager::instance().get_config().await?;
+    let auth = GrpcAuth {
+        token: None,
+        secret: config.get_rpc_secret(),
+        ..GrpcAuth::default()
+    };
+
     // Create gRPC client
-    let mut client = match GrpcClient::new(&endpoint).await {
+    let mut client = match GrpcClient::with_endpoints_auth(vec![endpoint.clone()], auth).await {
         Ok(client) => client,
         Err(e) => {
             println!("Failed to create gRPC client: {}", e);
-            return Err(Box::new(e));
+            return Err(e.into());
         }
     };
-    
+
+    // Probe the service before issuing calls so a drained or restarting
+    // server surfaces as a clear status instead of a raw RPC error.
+    // Servers without the health service still get the calls as before.
+    use graph_os_cli::adapters::grpc;
+    if let Ok(status) = client.check_health(grpc::SYSTEM_INFO_SERVICE).await
+        && matches!(status, grpc::HealthStatus::NotServing | grpc::HealthStatus::ServiceUnknown)
+    {
+        anyhow::bail!("{} is {} at {}", grpc::SYSTEM_INFO_SERVICE, status, endpoint);
+    }
+
     // Handle different system info actions
     match action {
         Some(SystemInfoCommands::Current) => {
@@ -52,29 +1919,132 @@ async fn handle_system_info(cli: &Cli, action: &Option<SystemInfoCommands>) -> R
                 },
                 Err(e) => {
                     println!("Error getting system info: {}", e);
-                    return Err(e);
+                    return Err(e.into());
                 }
             }
         },
-        Some(SystemInfoCommands::History { limit, since }) => {
-            // Get historical system info
-            match client.list_system_info(*limit, *since).await {
-                Ok(info_list) => {
-                    println!("\nHistorical System Information:");
-                    println!("=============================");
-                    println!("Returned {} records", info_list.items.len());
-                    
-                    for (i, info) in info_list.items.iter().enumerate() {
-                        println!("\nRecord {}/{}:", i+1, info_list.items.len());
-                        println!("{}", graph_os_cli::adapters::grpc::format_system_info(info));
+        Some(SystemInfoCommands::History { limit, since, columns, sort, format, aggregate }) => {
+            // Get historical system info; repeat queries over the same
+            // window only fetch records newer than what is cached
+            let mut cache = history_cache::HistoryCache::open();
+            match history_cache::fetch_history(&mut client, &mut cache, &endpoint, *limit, *since).await {
+                Ok(items) => {
+                    let columns = report::parse_columns(
+                        columns.as_deref().unwrap_or(report::DEFAULT_COLUMNS),
+                    )?;
+
+                    let mut items = items;
+                    if let Some(sort) = sort {
+                        report::sort_records(&mut items, sort)?;
+                    }
+
+                    match format.as_str() {
+                        "table" => {
+                            println!("Returned {} records\n", items.len());
+                            print!("{}", report::render_table(&items, &columns));
+                        },
+                        "csv" => print!("{}", report::render_csv(&items, &columns)),
+                        "json" => println!("{}", report::render_json(&items, &columns)?),
+                        other => {
+                            anyhow::bail!("Unknown format '{}'. Use table, csv or json", other);
+                        }
+                    }
+
+                    if *aggregate && !items.is_empty() {
+                        println!("\nAggregates over {} records:", items.len());
+                        print!("{}", report::render_aggregates(&items, &columns));
+                    }
+                },
+                Err(e) => {
+                    println!("Error getting historical system info: {}", e);
+                    return Err(e.into());
+                }
+            }
+        },
+        Some(SystemInfoCommands::Export { format, limit, since, timestamp_unit }) => {
+            // Fetch history and emit it for a metrics backfill; nothing
+            // but the samples goes to stdout so output pipes cleanly.
+            // Polled exports share the history cache, so each cycle only
+            // pulls the delta.
+            let mut cache = history_cache::HistoryCache::open();
+            match history_cache::fetch_history(&mut client, &mut cache, &endpoint, *limit, *since).await {
+                Ok(items) => {
+                    let metrics = config.metrics();
+                    let unit = match timestamp_unit {
+                        Some(spec) => report::TimestampUnit::parse(spec)?,
+                        // Each backend's native resolution
+                        None => match format.as_str() {
+                            "prometheus" => report::TimestampUnit::Millis,
+                            _ => report::TimestampUnit::Nanos,
+                        },
+                    };
+
+                    match format.as_str() {
+                        "prometheus" => {
+                            print!("{}", report::render_prometheus(&items, &metrics, unit));
+                        }
+                        "influx" => {
+                            print!("{}", report::render_influx(&items, &metrics, unit));
+                        }
+                        other => {
+                            anyhow::bail!("Unknown format '{}'. Use prometheus or influx", other);
+                        }
                     }
                 },
                 Err(e) => {
                     println!("Error getting historical system info: {}", e);
-                    return Err(e);
+                    return Err(e.into());
                 }
             }
         },
+        Some(SystemInfoCommands::Record { interval, count }) => {
+            let every = parse_duration(interval)?;
+            if every.is_zero() {
+                anyhow::bail!("Poll interval must be at least 1s");
+            }
+            let log = metrics::MetricsLog::instance();
+            println!(
+                "Recording system info every {} (Ctrl+C stops)",
+                interval
+            );
+
+            let mut ticker = tokio::time::interval(every);
+            let mut recorded: u64 = 0;
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = tokio::signal::ctrl_c() => {
+                        println!("\nStopped after {} samples", recorded);
+                        break;
+                    }
+                }
+
+                // A failed poll is reported but does not end the
+                // recording; the server may just be restarting
+                match client.get_system_info().await {
+                    Ok(info) => {
+                        let sample = metrics::SystemSample::from_info(&info);
+                        log.append(&sample)?;
+                        recorded += 1;
+                        println!(
+                            "{} load1 {:.2}, mem_used {}M ({} recorded)",
+                            sample.recorded_at.format("%H:%M:%S"),
+                            sample.load1,
+                            sample.memory_used / (1024 * 1024),
+                            recorded
+                        );
+                    }
+                    Err(e) => eprintln!("Poll failed: {}", e),
+                }
+
+                if count.is_some_and(|count| recorded >= count) {
+                    break;
+                }
+            }
+        },
+        // Dispatched before the shared client was built
+        Some(SystemInfoCommands::Fleet { .. }) => unreachable!("handled above"),
+        Some(SystemInfoCommands::Report { .. }) => unreachable!("handled above"),
         None => {
             // Default to current system info
             match client.get_system_info().await {
@@ -85,7 +2055,7 @@ async fn handle_system_info(cli: &Cli, action: &Option<SystemInfoCommands>) -> R
                 },
                 Err(e) => {
                     println!("Error getting system info: {}", e);
-                    return Err(e);
+                    return Err(e.into());
                 }
             }
         }
@@ -94,37 +2064,121 @@ async fn handle_system_info(cli: &Cli, action: &Option<SystemInfoCommands>) -> R
     Ok(())
 }
 
-// Basic gRPC connection test
-async fn test_grpc_connection(host: &str, port: u16) -> Result<(), Box<dyn Error>> {
-    println!("Attempting to connect to {}:{}...", host, port);
-    
-    match TcpStream::connect(format!("{}:{}", host, port)).await {
-        Ok(mut stream) => {
-            println!("Successfully connected to the server!");
-            
-            // Try sending an HTTP2 header (simplified)
-            let http2_preface = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
-            stream.write_all(http2_preface).await?;
-            
-            // Wait for a moment
-            tokio::time::sleep(Duration::from_secs(1)).await;
-            
-            println!("HTTP/2 preface sent, connection seems to be working");
-            
-            // Try using the actual gRPC client
-            let endpoint = format!("http://{}:{}", host, port);
-            println!("Trying to initialize gRPC client for {}", endpoint);
-            
-            match GrpcClient::new(&endpoint).await {
-                Ok(_) => println!("gRPC client successfully initialized!"),
-                Err(e) => println!("gRPC client initialization failed: {}", e)
-            }
-            
-            Ok(())
-        },
+// Query several GraphOS servers concurrently and print a comparison
+// table. Hosts that fail to connect or answer still get a row, flagged
+// as unreachable, so a dead server is visible rather than silently
+// missing from the output.
+async fn handle_fleet(endpoints: Option<&str>, sort: &str) -> Result<()> {
+    use graph_os_cli::config::EndpointConfig;
+
+    let config = ConfigManager::instance().get_config().await?;
+
+    // Build (label, url, auth) per host: either the --endpoints list
+    // (authenticated with the global RPC secret) or every endpoint from
+    // the config file, each with its own credentials
+    let targets: Vec<(String, String, GrpcAuth)> = match endpoints {
+        Some(list) => list
+            .split(',')
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(|url| {
+                let auth = GrpcAuth {
+                    token: None,
+                    secret: config.get_rpc_secret(),
+                    ..GrpcAuth::default()
+                };
+                (url.to_string(), url.to_string(), auth)
+            })
+            .collect(),
+        None => {
+            let mut named: Vec<(String, EndpointConfig)> = config
+                .auth
+                .as_ref()
+                .map(|auth| auth.endpoints.clone().into_iter().collect())
+                .unwrap_or_default();
+            // Stable row order before sorting, and deterministic output
+            // when two hosts tie on the sort column
+            named.sort_by(|a, b| a.0.cmp(&b.0));
+            named
+                .into_iter()
+                .map(|(name, ep)| {
+                    let auth = GrpcAuth::from_endpoint(&name, &ep);
+                    (name, ep.url.clone(), auth)
+                })
+                .collect()
+        }
+    };
+
+    if targets.is_empty() {
+        anyhow::bail!(
+            "No endpoints to query: pass --endpoints or configure some with 'gos config set-endpoint'"
+        );
+    }
+
+    // Query every host at once; one slow or dead server shouldn't
+    // serialize the rest
+    let queries = targets.into_iter().map(|(label, url, auth)| async move {
+        let info = async {
+            let mut client = GrpcClient::with_endpoints_auth(vec![url], auth).await?;
+            client.get_system_info().await.map_err(anyhow::Error::from)
+        }
+        .await;
+        report::FleetRow {
+            endpoint: label,
+            info: info.ok(),
+        }
+    });
+    let mut rows: Vec<report::FleetRow> = futures_util::future::join_all(queries).await;
+
+    let columns = report::parse_columns(report::FLEET_COLUMNS)?;
+    report::sort_fleet(&mut rows, sort)?;
+    println!("{}", report::render_fleet_table(&rows, &columns));
+
+    Ok(())
+}
+
+// Report server connectivity and per-service health via grpc.health.v1
+async fn handle_status(cli: &Cli) -> Result<()> {
+    use graph_os_cli::adapters::grpc;
+
+    let endpoint = format!("http://{}:{}", cli.api_host, cli.grpc_port);
+    println!("Checking {}", endpoint);
+
+    // Send the configured RPC secret as auth metadata on every call
+    let config = ConfigManager::instance().get_config().await?;
+    let auth = GrpcAuth {
+        token: None,
+        secret: config.get_rpc_secret(),
+        ..GrpcAuth::default()
+    };
+
+    let mut client = match GrpcClient::with_endpoints_auth(vec![endpoint], auth).await {
+        Ok(client) => client,
         Err(e) => {
-            println!("Failed to connect: {}", e);
-            Err(Box::new(e))
+            println!("Connection failed: {}", e);
+            return Err(e.into());
+        }
+    };
+
+    let mut failed = false;
+    for service in grpc::KNOWN_SERVICES {
+        let label = if service.is_empty() { "server" } else { service };
+        match client.check_health(service).await {
+            Ok(status) => {
+                if !matches!(status, grpc::HealthStatus::Serving | grpc::HealthStatus::Unsupported) {
+                    failed = true;
+                }
+                println!("  {:<30} {}", label, status);
+            }
+            Err(e) => {
+                failed = true;
+                println!("  {:<30} check failed: {}", label, e);
+            }
         }
     }
+
+    if failed {
+        anyhow::bail!("One or more services are not serving");
+    }
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,303 @@
+//! Local metrics recording for `gos system-info record` and trend
+//! reporting for `gos system-info report`.
+//!
+//! Many servers keep no system-info history of their own; polling
+//! GetSystemInfo into a rotated JSONL file on the client gives
+//! min/max/avg summaries and sparkline trends without any server-side
+//! support.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::adapters::grpc::graph_os::SystemInfo;
+
+/// Maximum size of the active recording before it is rotated
+const MAX_LOG_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Number of rotated recordings to keep around
+const ROTATED_LOGS_KEPT: usize = 3;
+
+/// Width of the sparkline charts in `gos system-info report`; longer
+/// windows are downsampled to this many buckets
+pub const CHART_WIDTH: usize = 60;
+
+/// Series names accepted by `gos system-info report --columns`
+pub const TREND_COLUMNS: [&str; 7] = [
+    "load1", "load5", "load15", "mem_used", "mem_free", "mem_total", "uptime",
+];
+
+/// Series charted when `--columns` is not given
+pub const DEFAULT_TREND_COLUMNS: &str = "load1,load5,mem_used";
+
+/// One recorded GetSystemInfo result. `recorded_at` is the client
+/// clock at poll time, which is what the `--since` window filters on;
+/// the server's own collection timestamp rides along untouched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemSample {
+    pub recorded_at: DateTime<Utc>,
+    pub hostname: String,
+    /// Server-side collection timestamp (unix seconds)
+    pub timestamp: i64,
+    pub cpu_count: i32,
+    pub load1: f64,
+    pub load5: f64,
+    pub load15: f64,
+    pub memory_used: i64,
+    pub memory_free: i64,
+    pub memory_total: i64,
+    pub uptime: i64,
+}
+
+impl SystemSample {
+    /// Snapshot a GetSystemInfo response at the current client time
+    pub fn from_info(info: &SystemInfo) -> Self {
+        SystemSample {
+            recorded_at: Utc::now(),
+            hostname: info.hostname.clone(),
+            timestamp: info.timestamp,
+            cpu_count: info.cpu_count,
+            load1: info.cpu_load_1m,
+            load5: info.cpu_load_5m,
+            load15: info.cpu_load_15m,
+            memory_used: info.memory_used,
+            memory_free: info.memory_free,
+            memory_total: info.memory_total,
+            uptime: info.uptime,
+        }
+    }
+}
+
+/// Numeric view of a trend series for one sample
+pub fn sample_number(sample: &SystemSample, column: &str) -> Option<f64> {
+    match column {
+        "load1" => Some(sample.load1),
+        "load5" => Some(sample.load5),
+        "load15" => Some(sample.load15),
+        "mem_used" => Some(sample.memory_used as f64),
+        "mem_free" => Some(sample.memory_free as f64),
+        "mem_total" => Some(sample.memory_total as f64),
+        "uptime" => Some(sample.uptime as f64),
+        _ => None,
+    }
+}
+
+/// Parse a comma-separated trend column list, rejecting unknown names
+pub fn parse_trend_columns(spec: &str) -> Result<Vec<String>> {
+    let columns: Vec<String> = spec
+        .split(',')
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect();
+
+    if columns.is_empty() {
+        bail!("No columns selected");
+    }
+    for column in &columns {
+        if !TREND_COLUMNS.contains(&column.as_str()) {
+            bail!(
+                "Unknown column '{}'. Available columns: {}",
+                column,
+                TREND_COLUMNS.join(", ")
+            );
+        }
+    }
+
+    Ok(columns)
+}
+
+/// Append-only JSONL recording of system-info samples with size-based
+/// rotation, mirroring the audit log's layout
+#[derive(Debug)]
+pub struct MetricsLog {
+    log_path: PathBuf,
+    writer: Mutex<()>,
+}
+
+impl MetricsLog {
+    /// Get the shared metrics log instance
+    pub fn instance() -> &'static Self {
+        static INSTANCE: std::sync::OnceLock<MetricsLog> = std::sync::OnceLock::new();
+        INSTANCE.get_or_init(|| MetricsLog {
+            log_path: Self::default_log_path(),
+            writer: Mutex::new(()),
+        })
+    }
+
+    /// Default location of the recording in the state directory
+    fn default_log_path() -> PathBuf {
+        crate::paths::state_dir().join("system_info.jsonl")
+    }
+
+    /// Create a metrics log at a custom location (used by tests and tooling)
+    pub fn with_path(path: PathBuf) -> Self {
+        MetricsLog {
+            log_path: path,
+            writer: Mutex::new(()),
+        }
+    }
+
+    /// Append one sample, rotating first when the active file is full
+    pub fn append(&self, sample: &SystemSample) -> Result<()> {
+        let _guard = self.writer.lock().map_err(|_| anyhow!("Metrics log lock poisoned"))?;
+
+        if let Some(parent) = self.log_path.parent() {
+            fs::create_dir_all(parent).context("Failed to create metrics log directory")?;
+        }
+
+        if let Ok(metadata) = fs::metadata(&self.log_path)
+            && metadata.len() >= MAX_LOG_SIZE
+        {
+            self.rotate()?;
+        }
+
+        let mut line = serde_json::to_string(sample)?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .with_context(|| format!("Failed to open metrics log: {}", self.log_path.display()))?;
+        file.write_all(line.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Shift system_info.jsonl -> system_info.jsonl.1 -> ... and drop
+    /// the oldest
+    fn rotate(&self) -> Result<()> {
+        for i in (1..=ROTATED_LOGS_KEPT).rev() {
+            let from = if i == 1 {
+                self.log_path.clone()
+            } else {
+                self.rotated_path(i - 1)
+            };
+            let to = self.rotated_path(i);
+
+            if from.exists() {
+                fs::rename(&from, &to)
+                    .with_context(|| format!("Failed to rotate metrics log to {}", to.display()))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.log_path.as_os_str().to_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    /// Read samples recorded within `since` of now, oldest first,
+    /// across rotations
+    pub fn read_since(&self, since: Option<Duration>) -> Result<Vec<SystemSample>> {
+        let cutoff = since.map(|d| Utc::now() - chrono::Duration::from_std(d).unwrap_or_default());
+        let mut samples = Vec::new();
+
+        // Oldest rotated file first, active recording last
+        let mut paths: Vec<PathBuf> = (1..=ROTATED_LOGS_KEPT)
+            .rev()
+            .map(|i| self.rotated_path(i))
+            .collect();
+        paths.push(self.log_path.clone());
+
+        for path in paths {
+            if !path.exists() {
+                continue;
+            }
+
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read metrics log: {}", path.display()))?;
+
+            for line in content.lines() {
+                if line.is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<SystemSample>(line) {
+                    Ok(sample) => {
+                        if cutoff.is_none_or(|c| sample.recorded_at >= c) {
+                            samples.push(sample);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Skipping malformed metrics sample: {}", e);
+                    }
+                }
+            }
+        }
+
+        Ok(samples)
+    }
+}
+
+/// Compress a series into at most `buckets` points by averaging
+/// consecutive runs, so long windows still fit a one-line chart
+pub fn downsample(values: &[f64], buckets: usize) -> Vec<f64> {
+    if buckets == 0 || values.len() <= buckets {
+        return values.to_vec();
+    }
+
+    (0..buckets)
+        .map(|bucket| {
+            // Integer bucket edges cover the series without gaps
+            let start = bucket * values.len() / buckets;
+            let end = ((bucket + 1) * values.len() / buckets).max(start + 1);
+            let run = &values[start..end];
+            run.iter().sum::<f64>() / run.len() as f64
+        })
+        .collect()
+}
+
+/// Render the trend report: a window header, then one sparkline chart
+/// with min/max/avg per selected series
+pub fn render_trends(
+    samples: &[SystemSample],
+    columns: &[String],
+    style: &crate::render::RenderStyle,
+) -> String {
+    let mut out = String::new();
+
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return "No samples in the window. Run `gos system-info record` first.\n".to_string();
+    };
+    out.push_str(&format!(
+        "{} samples from {} to {} ({})\n\n",
+        samples.len(),
+        first.recorded_at.format("%Y-%m-%d %H:%M:%S"),
+        last.recorded_at.format("%Y-%m-%d %H:%M:%S"),
+        last.hostname
+    ));
+
+    for column in columns {
+        let values: Vec<f64> = samples
+            .iter()
+            .filter_map(|sample| sample_number(sample, column))
+            .collect();
+        if values.is_empty() {
+            continue;
+        }
+
+        let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let avg = values.iter().sum::<f64>() / values.len() as f64;
+
+        let chart = crate::render::sparkline(
+            &downsample(&values, CHART_WIDTH),
+            style.sparkline_ramp(),
+        );
+        out.push_str(&format!(
+            "{:>9}  {}\n{:>9}  min {:.2}, max {:.2}, avg {:.2}\n",
+            column, chart, "", min, max, avg
+        ));
+    }
+
+    out
+}
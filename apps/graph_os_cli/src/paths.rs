@@ -0,0 +1,142 @@
+//! Filesystem locations for config, data and state files.
+//!
+//! Historically everything lived in `~/.vibe` and `~/.graph_os`. Paths
+//! now follow the platform conventions the `dirs` crate implements:
+//! XDG_CONFIG_HOME/XDG_DATA_HOME/XDG_STATE_HOME on Linux, Application
+//! Support on macOS, and AppData on Windows. [`migrate_legacy_paths`]
+//! relocates files from the old locations on startup, and `--config-dir`
+//! overrides the config directory entirely.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+
+/// Directory name used under each platform base directory
+const APP_DIR: &str = "graph_os";
+
+/// Config directory override from `--config-dir`, set once at startup
+static CONFIG_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Record the `--config-dir` override; later calls are ignored
+pub fn set_config_dir_override(path: PathBuf) {
+    let _ = CONFIG_DIR_OVERRIDE.set(path);
+}
+
+/// Directory for config files: `--config-dir` if given, otherwise the
+/// platform config directory (XDG_CONFIG_HOME, Application Support,
+/// or AppData)
+pub fn config_dir() -> PathBuf {
+    if let Some(dir) = CONFIG_DIR_OVERRIDE.get() {
+        return dir.clone();
+    }
+
+    dirs::config_dir()
+        .unwrap_or_else(|| dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")))
+        .join(APP_DIR)
+}
+
+/// Directory for durable data such as session transcripts
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")))
+        .join(APP_DIR)
+}
+
+/// Directory for logs and other regenerable state. Platforms without a
+/// state directory (everything but Linux) use the data directory.
+pub fn state_dir() -> PathBuf {
+    dirs::state_dir()
+        .map(|dir| dir.join(APP_DIR))
+        .unwrap_or_else(data_dir)
+}
+
+/// Directory holding session files
+pub fn sessions_dir() -> PathBuf {
+    data_dir().join("sessions")
+}
+
+/// Plan which legacy files should move where. Pure so the logic is
+/// testable against temp directories; only files whose destination does
+/// not already exist are included.
+pub fn plan_migration(
+    legacy_vibe: &Path,
+    legacy_graph_os: &Path,
+    config_dir: &Path,
+    sessions_dir: &Path,
+    state_dir: &Path,
+) -> Vec<(PathBuf, PathBuf)> {
+    let mut moves = Vec::new();
+
+    // Session files from ~/.vibe
+    if let Ok(entries) = std::fs::read_dir(legacy_vibe) {
+        for entry in entries.filter_map(std::result::Result::ok) {
+            let from = entry.path();
+            if from.extension().and_then(|e| e.to_str()) == Some("json") {
+                push_move(&mut moves, from, sessions_dir);
+            }
+        }
+    }
+
+    // Config files and logs from ~/.graph_os
+    if let Ok(entries) = std::fs::read_dir(legacy_graph_os) {
+        for entry in entries.filter_map(std::result::Result::ok) {
+            let from = entry.path();
+            let name = from.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+
+            if name == "audit.jsonl" || name == "usage.jsonl" {
+                push_move(&mut moves, from, state_dir);
+            } else if from
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|ext| matches!(ext, "json" | "yaml" | "yml" | "toml"))
+            {
+                push_move(&mut moves, from, config_dir);
+            }
+        }
+    }
+
+    moves
+}
+
+/// Add a move unless the destination already exists
+fn push_move(moves: &mut Vec<(PathBuf, PathBuf)>, from: PathBuf, dest_dir: &Path) {
+    if let Some(name) = from.file_name() {
+        let to = dest_dir.join(name);
+        if !to.exists() {
+            moves.push((from, to));
+        }
+    }
+}
+
+/// Relocate files from the legacy `~/.vibe` and `~/.graph_os` locations
+/// to the platform directories, returning the moves performed. Existing
+/// destination files are never overwritten.
+pub fn migrate_legacy_paths() -> Result<Vec<(PathBuf, PathBuf)>> {
+    let Some(home) = dirs::home_dir() else {
+        return Ok(Vec::new());
+    };
+
+    let moves = plan_migration(
+        &home.join(".vibe"),
+        &home.join(".graph_os"),
+        &config_dir(),
+        &sessions_dir(),
+        &state_dir(),
+    );
+
+    for (from, to) in &moves {
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        // rename fails across filesystems; fall back to copy + remove
+        if std::fs::rename(from, to).is_err() {
+            std::fs::copy(from, to)
+                .with_context(|| format!("Failed to migrate {} to {}", from.display(), to.display()))?;
+            std::fs::remove_file(from).ok();
+        }
+    }
+
+    Ok(moves)
+}
@@ -0,0 +1,200 @@
+//! Admin guardrails for outbound requests.
+//!
+//! The `[policy]` table in the system config under /etc/graph_os lets
+//! administrators block topics (regexes matched against outgoing
+//! prompts), cap response length, and restrict which providers may be
+//! used — globally or per profile, selected with the
+//! GRAPHOS_POLICY_PROFILE environment variable. Enforcement happens in
+//! the request pipeline: a denied prompt or provider is reported to the
+//! user with the rule that fired, and every denial leaves an entry in
+//! the audit log.
+
+use chrono::Utc;
+use regex::Regex;
+use serde_json::json;
+
+use crate::audit::{AuditLog, AuditRecord};
+use crate::config::PolicyConfig;
+
+/// Environment variable naming the active policy profile
+pub const POLICY_PROFILE_ENV: &str = "GRAPHOS_POLICY_PROFILE";
+
+/// A request the policy refused, with a user-facing explanation
+#[derive(Debug, Clone)]
+pub struct Denial {
+    /// Name of the rule that fired ("blocked.<name>" or "providers")
+    pub rule: String,
+    /// What to tell the user, without leaking the pattern itself
+    pub message: String,
+}
+
+/// The configured guardrails, built once per process from the
+/// `[policy]` config table
+pub struct PolicyEngine {
+    /// Blocked topic patterns, applied by name; invalid ones are
+    /// skipped with a warning rather than failing startup
+    blocked: Vec<(String, Regex)>,
+    max_response_chars: Option<usize>,
+    /// Lowercased provider names; empty means all providers
+    allowed_providers: Vec<String>,
+}
+
+impl PolicyEngine {
+    /// Build the engine for this process, honoring the profile named
+    /// by GRAPHOS_POLICY_PROFILE
+    pub fn from_config(config: &PolicyConfig) -> Self {
+        let profile = std::env::var(POLICY_PROFILE_ENV).ok();
+        Self::with_profile(config, profile.as_deref())
+    }
+
+    /// Build the engine for a specific profile; an unknown profile
+    /// name warns and falls back to the global rules
+    pub fn with_profile(config: &PolicyConfig, profile: Option<&str>) -> Self {
+        let mut blocked = Vec::new();
+        for rule in &config.blocked {
+            match Regex::new(&rule.pattern) {
+                Ok(pattern) => blocked.push((rule.name.clone(), pattern)),
+                Err(e) => eprintln!("Ignoring invalid policy pattern '{}': {}", rule.name, e),
+            }
+        }
+
+        let overrides = match profile {
+            Some(name) => match config.profiles.get(name) {
+                Some(overrides) => Some(overrides),
+                None => {
+                    eprintln!(
+                        "Warning: {} names unknown policy profile '{}'; using the global rules",
+                        POLICY_PROFILE_ENV, name
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+
+        // A profile overrides a field only when it sets one; empty
+        // lists and unset caps inherit the global rules
+        let allowed_providers = overrides
+            .filter(|o| !o.allowed_providers.is_empty())
+            .map(|o| o.allowed_providers.clone())
+            .unwrap_or_else(|| config.allowed_providers.clone())
+            .iter()
+            .map(|name| name.to_lowercase())
+            .collect();
+        let max_response_chars = overrides
+            .and_then(|o| o.max_response_chars)
+            .or(config.max_response_chars);
+
+        Self { blocked, max_response_chars, allowed_providers }
+    }
+
+    /// Check an outgoing prompt against the blocked topics; the first
+    /// rule that matches denies the send
+    pub fn check_prompt(&self, text: &str) -> Result<(), Denial> {
+        for (name, pattern) in &self.blocked {
+            if pattern.is_match(text) {
+                return Err(Denial {
+                    rule: format!("blocked.{}", name),
+                    message: format!(
+                        "the message matches the blocked topic '{}' set by your administrator",
+                        name
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check whether a provider may be used under this policy
+    pub fn check_provider(&self, provider: &str) -> Result<(), Denial> {
+        if self.allowed_providers.is_empty()
+            || self.allowed_providers.contains(&provider.to_lowercase())
+        {
+            return Ok(());
+        }
+        Err(Denial {
+            rule: "providers".to_string(),
+            message: format!(
+                "provider '{}' is not in the administrator's allow list ({})",
+                provider,
+                self.allowed_providers.join(", ")
+            ),
+        })
+    }
+
+    /// The effective response length cap, if any
+    pub fn max_response_chars(&self) -> Option<usize> {
+        self.max_response_chars
+    }
+
+    /// Apply the response cap to a finished response, appending a
+    /// notice and leaving an audit entry when it truncates
+    pub fn enforce_response(&self, text: &str) -> String {
+        let Some(max) = self.max_response_chars else {
+            return text.to_string();
+        };
+        match clip(text, max) {
+            Some(clipped) => {
+                audit_entry("policy.truncate", "truncated", json!({ "max_chars": max }));
+                clipped
+            }
+            None => text.to_string(),
+        }
+    }
+}
+
+/// Truncate text to a character cap, returning None when it already
+/// fits; the notice tells the user output was removed by policy
+pub fn clip(text: &str, max_chars: usize) -> Option<String> {
+    if text.chars().count() <= max_chars {
+        return None;
+    }
+    let mut clipped: String = text.chars().take(max_chars).collect();
+    clipped.push_str(&format!("\n[response truncated at {} characters by policy]", max_chars));
+    Some(clipped)
+}
+
+/// Record a policy denial in the audit log so admins can see what
+/// the guardrails are catching
+pub fn audit_denial(kind: &str, denial: &Denial) {
+    audit_entry(
+        "policy.deny",
+        "denied",
+        json!({ "kind": kind, "rule": denial.rule }),
+    );
+}
+
+fn audit_entry(method: &str, status: &str, params: serde_json::Value) {
+    AuditLog::instance().record(&AuditRecord {
+        timestamp: Utc::now(),
+        transport: "policy".to_string(),
+        method: method.to_string(),
+        endpoint: "local".to_string(),
+        params,
+        latency_ms: 0,
+        status: status.to_string(),
+        token_usage: None,
+    });
+}
+
+/// Filter stage that enforces the response cap on the chat pipeline,
+/// so streamed and buffered responses are capped the same way
+pub struct ClipResponse {
+    pub max_chars: usize,
+}
+
+impl crate::filters::Filter for ClipResponse {
+    fn name(&self) -> &'static str {
+        "clip_response"
+    }
+
+    fn apply(&self, text: &str) -> String {
+        match clip(text, self.max_chars) {
+            Some(clipped) => {
+                audit_entry("policy.truncate", "truncated", json!({ "max_chars": self.max_chars }));
+                clipped
+            }
+            None => text.to_string(),
+        }
+    }
+}
@@ -0,0 +1,238 @@
+//! Outbound secret scanning for user prompts.
+//!
+//! Before a message leaves the machine — from the chat TUI or from
+//! `gos ask` — it is scanned for things that look like credentials:
+//! AWS access key ids, private key blocks, high-entropy tokens and any
+//! custom patterns from the `[redact]` config table. Depending on the
+//! configured mode the scanner warns about them, blocks the send, or
+//! masks them with placeholders; masked values are restored locally so
+//! the displayed transcript still reads naturally while only the
+//! placeholder ever reaches the provider.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use crate::config::RedactConfig;
+
+/// What the scanner does when a message contains a likely secret
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedactMode {
+    /// Point out the detections but send the message unchanged
+    Warn,
+    /// Refuse to send until the message is edited
+    Block,
+    /// Replace each detection with a placeholder before sending
+    Mask,
+}
+
+impl RedactMode {
+    /// Parse the `mode` config value, defaulting to warn
+    pub fn parse(value: Option<&str>) -> Self {
+        match value {
+            Some("block") => RedactMode::Block,
+            Some("mask") => RedactMode::Mask,
+            _ => RedactMode::Warn,
+        }
+    }
+}
+
+/// One likely secret found in an outgoing message
+#[derive(Debug, Clone)]
+pub struct Detection {
+    /// What kind of secret this looks like ("aws-access-key",
+    /// "private-key", "high-entropy-token" or a custom pattern's name)
+    pub kind: String,
+    /// The matched text, as it appears in the message
+    pub value: String,
+}
+
+/// Tokens shorter than this are never entropy candidates; short strings
+/// have too little signal to judge
+const ENTROPY_MIN_LENGTH: usize = 24;
+
+/// Shannon entropy (bits per character) above which a long token is
+/// flagged; ordinary words and identifiers sit well below this
+const ENTROPY_THRESHOLD: f64 = 4.0;
+
+/// Shannon entropy of a string in bits per character
+pub fn entropy(text: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    let mut total = 0usize;
+    for c in text.chars() {
+        *counts.entry(c).or_default() += 1;
+        total += 1;
+    }
+    if total == 0 {
+        return 0.0;
+    }
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// The configured outbound scanner, built once per process from the
+/// `[redact]` config table
+pub struct OutboundScanner {
+    mode: RedactMode,
+    check_entropy: bool,
+    aws_key: Regex,
+    private_key: Regex,
+    /// Custom patterns from the config, applied by name; invalid ones
+    /// are skipped with a warning rather than failing startup
+    custom: Vec<(String, Regex)>,
+}
+
+impl OutboundScanner {
+    pub fn from_config(config: &RedactConfig) -> Self {
+        let mut custom = Vec::new();
+        for rule in &config.patterns {
+            match Regex::new(&rule.pattern) {
+                Ok(pattern) => custom.push((rule.name.clone(), pattern)),
+                Err(e) => eprintln!("Ignoring invalid redact pattern '{}': {}", rule.name, e),
+            }
+        }
+        Self {
+            mode: RedactMode::parse(config.mode.as_deref()),
+            check_entropy: config.entropy.unwrap_or(true),
+            aws_key: Regex::new(r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b").expect("aws pattern is valid"),
+            // The whole block is one detection, so masking removes the
+            // key material and not just the armor lines
+            private_key: Regex::new(
+                r"-----BEGIN [A-Z0-9 ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z0-9 ]*PRIVATE KEY-----",
+            )
+            .expect("private key pattern is valid"),
+            custom,
+        }
+    }
+
+    pub fn mode(&self) -> RedactMode {
+        self.mode
+    }
+
+    /// Scan a message for likely secrets. Detections come back in the
+    /// order they appear in the text, deduplicated by value.
+    pub fn scan(&self, text: &str) -> Vec<Detection> {
+        let mut detections: Vec<(usize, Detection)> = Vec::new();
+
+        for m in self.private_key.find_iter(text) {
+            detections.push((m.start(), Detection {
+                kind: "private-key".to_string(),
+                value: m.as_str().to_string(),
+            }));
+        }
+        for m in self.aws_key.find_iter(text) {
+            detections.push((m.start(), Detection {
+                kind: "aws-access-key".to_string(),
+                value: m.as_str().to_string(),
+            }));
+        }
+        for (name, pattern) in &self.custom {
+            for m in pattern.find_iter(text) {
+                detections.push((m.start(), Detection {
+                    kind: name.clone(),
+                    value: m.as_str().to_string(),
+                }));
+            }
+        }
+        if self.check_entropy {
+            // Candidate tokens are long unbroken runs of key-material
+            // characters; requiring a digit keeps prose and identifiers
+            // like ThisLongCamelCaseName out
+            let mut offset = 0;
+            for word in text.split(|c: char| !is_token_char(c)) {
+                let start = offset;
+                offset += word.len() + 1;
+                if word.len() < ENTROPY_MIN_LENGTH
+                    || !word.chars().any(|c| c.is_ascii_digit())
+                    || entropy(word) < ENTROPY_THRESHOLD
+                {
+                    continue;
+                }
+                detections.push((start, Detection {
+                    kind: "high-entropy-token".to_string(),
+                    value: word.to_string(),
+                }));
+            }
+        }
+
+        detections.sort_by_key(|(start, _)| *start);
+        let mut seen = Vec::new();
+        let mut result = Vec::new();
+        for (_, detection) in detections {
+            if !seen.contains(&detection.value) {
+                seen.push(detection.value.clone());
+                result.push(detection);
+            }
+        }
+        result
+    }
+
+    /// Replace every detection with a placeholder, recording the
+    /// placeholder -> original mapping so [`restore`] can undo it in
+    /// text shown locally. The same value always gets the same
+    /// placeholder, so repeated mentions stay consistent across a
+    /// conversation.
+    pub fn mask(&self, text: &str, map: &mut HashMap<String, String>) -> String {
+        let mut masked = text.to_string();
+        for detection in self.scan(text) {
+            let placeholder = match map.iter().find(|(_, v)| **v == detection.value) {
+                Some((placeholder, _)) => placeholder.clone(),
+                None => {
+                    let placeholder = format!("[secret:{}-{}]", detection.kind, map.len() + 1);
+                    map.insert(placeholder.clone(), detection.value.clone());
+                    placeholder
+                }
+            };
+            masked = masked.replace(&detection.value, &placeholder);
+        }
+        masked
+    }
+
+    /// One-line description of a scan result, for warnings
+    pub fn describe(detections: &[Detection]) -> String {
+        let kinds: Vec<&str> = detections.iter().map(|d| d.kind.as_str()).collect();
+        format!(
+            "{} likely secret{} ({})",
+            detections.len(),
+            if detections.len() == 1 { "" } else { "s" },
+            kinds.join(", ")
+        )
+    }
+}
+
+/// Characters that can appear in key material: base64, hex, url-safe
+/// variants and common token separators
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=' | '_' | '-')
+}
+
+/// Put masked values back into text that is displayed or stored
+/// locally, so the user sees what they actually wrote
+pub fn restore(text: &str, map: &HashMap<String, String>) -> String {
+    let mut restored = text.to_string();
+    for (placeholder, original) in map {
+        restored = restored.replace(placeholder.as_str(), original);
+    }
+    restored
+}
+
+/// Filter stage that restores placeholders in assistant responses, so
+/// a model echoing a placeholder reads naturally in the transcript
+pub struct RestoreRedactions {
+    pub map: std::sync::Arc<std::sync::Mutex<HashMap<String, String>>>,
+}
+
+impl crate::filters::Filter for RestoreRedactions {
+    fn name(&self) -> &'static str {
+        "restore_redactions"
+    }
+
+    fn apply(&self, text: &str) -> String {
+        restore(text, &self.map.lock().unwrap())
+    }
+}
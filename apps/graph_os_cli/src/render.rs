@@ -0,0 +1,315 @@
+//! Rendering strategy for the chat UI and graph query output.
+//!
+//! Accessible mode (`--accessible`, `GOS_ACCESSIBLE=1` or
+//! `accessible = true` in the config) renders for screen readers: no
+//! colors or emoji, textual role prefixes and state announcements, and
+//! no cursor repositioning, so review mode isn't yanked back to the
+//! input box on every frame.
+//!
+//! [`GraphOutput`] serializes node/edge sets returned by graph queries
+//! to Graphviz DOT, Mermaid or an ASCII adjacency listing, so results
+//! can be piped into documentation or viewers. The serializers are
+//! layout-independent: they emit structure only and leave placement to
+//! the consuming tool.
+
+use std::sync::OnceLock;
+
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::error::{GraphOsError, Result};
+
+/// Set by `--accessible` before the UI starts
+static FORCED_ACCESSIBLE: OnceLock<bool> = OnceLock::new();
+
+/// Force accessible mode on for this process (the `--accessible` flag)
+pub fn force_accessible() {
+    FORCED_ACCESSIBLE.set(true).ok();
+}
+
+/// How the UI should draw itself; consulted everywhere a color, icon or
+/// cursor movement would otherwise be hardcoded
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderStyle {
+    pub accessible: bool,
+}
+
+impl RenderStyle {
+    pub fn new(accessible: bool) -> Self {
+        Self { accessible }
+    }
+
+    /// Resolve the effective mode from the `--accessible` flag, the
+    /// GOS_ACCESSIBLE environment variable and the config flag
+    pub fn detect(config_accessible: bool) -> Self {
+        let forced = FORCED_ACCESSIBLE.get().copied().unwrap_or(false);
+        let env = std::env::var("GOS_ACCESSIBLE")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        Self::new(forced || env || config_accessible)
+    }
+
+    /// Foreground color, or no styling at all in accessible mode
+    pub fn fg(&self, color: Color) -> Style {
+        if self.accessible {
+            Style::default()
+        } else {
+            Style::default().fg(color)
+        }
+    }
+
+    /// Highlight style for selected list entries; accessible mode
+    /// relies on the highlight symbol instead of reverse video
+    pub fn highlight(&self) -> Style {
+        if self.accessible {
+            Style::default()
+        } else {
+            Style::default().add_modifier(Modifier::REVERSED)
+        }
+    }
+
+    /// Marker prepended to the selected list entry
+    pub fn highlight_symbol(&self) -> &'static str {
+        if self.accessible { "selected: " } else { ">> " }
+    }
+
+    /// Decorative icon, dropped entirely in accessible mode
+    pub fn icon(&self, icon: &'static str) -> &'static str {
+        if self.accessible { "" } else { icon }
+    }
+
+    /// Spinner frame shown while a command effect runs; accessible
+    /// mode uses a static word instead of an animation
+    pub fn spinner(&self, tick: usize) -> &'static str {
+        if self.accessible {
+            "working:"
+        } else {
+            ["|", "/", "-", "\\"][tick % 4]
+        }
+    }
+
+    /// Marker prepended to pinned transcript messages; spelled out in
+    /// accessible mode instead of the emoji
+    pub fn pin_marker(&self) -> &'static str {
+        if self.accessible { "[pinned] " } else { "📌 " }
+    }
+
+    /// Character ramp for status-bar sparklines, lowest to highest;
+    /// accessible mode sticks to plain ASCII
+    pub fn sparkline_ramp(&self) -> &'static [char] {
+        if self.accessible {
+            &['_', '.', '-', '=', '#']
+        } else {
+            &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█']
+        }
+    }
+
+    /// Placeholder shown while a streaming response has no text yet
+    pub fn streaming_placeholder(&self) -> &'static str {
+        if self.accessible { "(waiting for response)" } else { "..." }
+    }
+
+    /// Announcement appended when a streaming response finishes, so
+    /// screen readers get an explicit completion cue
+    pub fn response_complete_announcement(&self) -> Option<&'static str> {
+        if self.accessible { Some("\n[Response complete]") } else { None }
+    }
+
+    /// Whether the hardware cursor should track the input box
+    pub fn moves_cursor(&self) -> bool {
+        !self.accessible
+    }
+}
+
+/// Output format for graph query results (the `--render` flag)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+    Ascii,
+}
+
+impl GraphFormat {
+    /// Parse a `--render` argument
+    pub fn parse(input: &str) -> Result<Self> {
+        match input.to_lowercase().as_str() {
+            "dot" => Ok(GraphFormat::Dot),
+            "mermaid" => Ok(GraphFormat::Mermaid),
+            "ascii" => Ok(GraphFormat::Ascii),
+            other => Err(GraphOsError::Config(format!(
+                "Unknown render format '{}': expected dot, mermaid or ascii", other
+            ))),
+        }
+    }
+}
+
+/// A node in a graph query result
+#[derive(Debug, Clone)]
+pub struct GraphNode {
+    pub id: String,
+    /// Display label; the id is shown when unset
+    pub label: Option<String>,
+}
+
+/// A directed edge in a graph query result
+#[derive(Debug, Clone)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub label: Option<String>,
+}
+
+/// A node/edge set returned by a graph query, independent of layout
+#[derive(Debug, Clone, Default)]
+pub struct GraphOutput {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl GraphOutput {
+    /// Serialize the graph in the requested format
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+            GraphFormat::Ascii => self.to_ascii(),
+        }
+    }
+
+    /// Graphviz DOT: `dot -Tsvg` renders it directly
+    fn to_dot(&self) -> String {
+        let mut out = String::from("digraph G {\n");
+
+        for node in &self.nodes {
+            match &node.label {
+                Some(label) => out.push_str(&format!(
+                    "  \"{}\" [label=\"{}\"];\n",
+                    escape_dot(&node.id),
+                    escape_dot(label)
+                )),
+                None => out.push_str(&format!("  \"{}\";\n", escape_dot(&node.id))),
+            }
+        }
+
+        for edge in &self.edges {
+            let attrs = match &edge.label {
+                Some(label) => format!(" [label=\"{}\"]", escape_dot(label)),
+                None => String::new(),
+            };
+            out.push_str(&format!(
+                "  \"{}\" -> \"{}\"{};\n",
+                escape_dot(&edge.from),
+                escape_dot(&edge.to),
+                attrs
+            ));
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Mermaid `graph TD`, suitable for markdown code fences. Mermaid
+    /// identifiers cannot contain arbitrary characters, so nodes are
+    /// numbered and the real id becomes the label.
+    fn to_mermaid(&self) -> String {
+        let mut out = String::from("graph TD\n");
+
+        // Number every id that appears, including ones only referenced
+        // by edges
+        let mut ids: Vec<&str> = self.nodes.iter().map(|n| n.id.as_str()).collect();
+        for edge in &self.edges {
+            for id in [edge.from.as_str(), edge.to.as_str()] {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        let index_of = |id: &str| ids.iter().position(|i| *i == id).unwrap_or(0);
+
+        for node in &self.nodes {
+            let label = node.label.as_deref().unwrap_or(&node.id);
+            out.push_str(&format!(
+                "  n{}[\"{}\"]\n",
+                index_of(&node.id),
+                escape_mermaid(label)
+            ));
+        }
+
+        for edge in &self.edges {
+            match &edge.label {
+                Some(label) => out.push_str(&format!(
+                    "  n{} -->|{}| n{}\n",
+                    index_of(&edge.from),
+                    escape_mermaid(label),
+                    index_of(&edge.to)
+                )),
+                None => out.push_str(&format!(
+                    "  n{} --> n{}\n",
+                    index_of(&edge.from),
+                    index_of(&edge.to)
+                )),
+            }
+        }
+
+        out
+    }
+
+    /// Plain adjacency listing for terminals without a renderer
+    fn to_ascii(&self) -> String {
+        let mut out = String::new();
+
+        for node in &self.nodes {
+            let targets: Vec<String> = self
+                .edges
+                .iter()
+                .filter(|edge| edge.from == node.id)
+                .map(|edge| match &edge.label {
+                    Some(label) => format!("{} ({})", edge.to, label),
+                    None => edge.to.clone(),
+                })
+                .collect();
+
+            if targets.is_empty() {
+                out.push_str(&format!("{}\n", node.id));
+            } else {
+                out.push_str(&format!("{} -> {}\n", node.id, targets.join(", ")));
+            }
+        }
+
+        out
+    }
+}
+
+/// Render values as a one-line sparkline using the given character
+/// ramp, scaled to the series' own min..max so the shape survives any
+/// unit. A flat series (or a single value) renders at mid-height; an
+/// empty series renders as an empty string.
+pub fn sparkline(values: &[f64], ramp: &[char]) -> String {
+    let Some(min) = values.iter().copied().reduce(f64::min) else {
+        return String::new();
+    };
+    let max = values.iter().copied().fold(min, f64::max);
+    let span = max - min;
+
+    values
+        .iter()
+        .map(|value| {
+            let level = if span > 0.0 {
+                // Top of the ramp is hit by the max only
+                (((value - min) / span) * (ramp.len() - 1) as f64).round() as usize
+            } else {
+                ramp.len() / 2
+            };
+            ramp[level.min(ramp.len() - 1)]
+        })
+        .collect()
+}
+
+/// Escape a string for a double-quoted DOT identifier or label
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escape a string for a Mermaid label; quotes break the bracket syntax
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;").replace('|', "/")
+}
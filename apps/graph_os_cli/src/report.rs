@@ -0,0 +1,442 @@
+//! Tabular rendering and client-side aggregation for
+//! `gos system-info history`.
+//!
+//! The verbose per-record block format does not scale past a handful of
+//! records; this renders a column-selectable table (or CSV/JSON) with
+//! optional sorting and min/max/avg aggregation over the window.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::DateTime;
+
+use crate::adapters::grpc::graph_os::SystemInfo;
+use crate::config::MetricsConfig;
+
+/// Column names accepted by `--columns` and `--sort`
+pub const COLUMNS: [&str; 12] = [
+    "timestamp",
+    "hostname",
+    "cpu",
+    "load1",
+    "load5",
+    "load15",
+    "mem_used",
+    "mem_free",
+    "mem_total",
+    "uptime",
+    "platform",
+    "arch",
+];
+
+/// Columns shown when `--columns` is not given
+pub const DEFAULT_COLUMNS: &str = "timestamp,hostname,cpu,load1,mem_used,uptime";
+
+/// Parse a comma-separated column list, rejecting unknown names
+pub fn parse_columns(spec: &str) -> Result<Vec<String>> {
+    let columns: Vec<String> = spec
+        .split(',')
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect();
+
+    if columns.is_empty() {
+        bail!("No columns selected");
+    }
+    for column in &columns {
+        if !COLUMNS.contains(&column.as_str()) {
+            bail!(
+                "Unknown column '{}'. Available columns: {}",
+                column,
+                COLUMNS.join(", ")
+            );
+        }
+    }
+
+    Ok(columns)
+}
+
+/// Render one column of one record for display
+pub fn column_value(info: &SystemInfo, column: &str) -> String {
+    match column {
+        "timestamp" => DateTime::from_timestamp(info.timestamp, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| info.timestamp.to_string()),
+        "hostname" => info.hostname.clone(),
+        "cpu" => info.cpu_count.to_string(),
+        "load1" => format!("{:.2}", info.cpu_load_1m),
+        "load5" => format!("{:.2}", info.cpu_load_5m),
+        "load15" => format!("{:.2}", info.cpu_load_15m),
+        "mem_used" => format!("{}M", info.memory_used / (1024 * 1024)),
+        "mem_free" => format!("{}M", info.memory_free / (1024 * 1024)),
+        "mem_total" => format!("{}M", info.memory_total / (1024 * 1024)),
+        "uptime" => format!("{}s", info.uptime),
+        "platform" => info.platform.clone(),
+        "arch" => info.architecture.clone(),
+        _ => String::new(),
+    }
+}
+
+/// Numeric view of a column for sorting and aggregation; None for text
+/// columns like hostname
+fn column_number(info: &SystemInfo, column: &str) -> Option<f64> {
+    match column {
+        "timestamp" => Some(info.timestamp as f64),
+        "cpu" => Some(info.cpu_count as f64),
+        "load1" => Some(info.cpu_load_1m),
+        "load5" => Some(info.cpu_load_5m),
+        "load15" => Some(info.cpu_load_15m),
+        "mem_used" => Some(info.memory_used as f64),
+        "mem_free" => Some(info.memory_free as f64),
+        "mem_total" => Some(info.memory_total as f64),
+        "uptime" => Some(info.uptime as f64),
+        _ => None,
+    }
+}
+
+/// Sort records by a column, numerically where possible. A leading '-'
+/// reverses the order.
+pub fn sort_records(items: &mut [SystemInfo], spec: &str) -> Result<()> {
+    let (column, descending) = match spec.strip_prefix('-') {
+        Some(column) => (column, true),
+        None => (spec, false),
+    };
+
+    if !COLUMNS.contains(&column) {
+        bail!(
+            "Unknown sort column '{}'. Available columns: {}",
+            column,
+            COLUMNS.join(", ")
+        );
+    }
+
+    items.sort_by(|a, b| {
+        let ordering = match (column_number(a, column), column_number(b, column)) {
+            (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(std::cmp::Ordering::Equal),
+            _ => column_value(a, column).cmp(&column_value(b, column)),
+        };
+        if descending { ordering.reverse() } else { ordering }
+    });
+
+    Ok(())
+}
+
+/// Render records as a padded text table
+pub fn render_table(items: &[SystemInfo], columns: &[String]) -> String {
+    // Column widths fit the widest cell including the header
+    let mut widths: Vec<usize> = columns.iter().map(|c| c.len()).collect();
+    let rows: Vec<Vec<String>> = items
+        .iter()
+        .map(|info| columns.iter().map(|c| column_value(info, c)).collect())
+        .collect();
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.len());
+        }
+    }
+
+    let mut out = String::new();
+    for (i, column) in columns.iter().enumerate() {
+        out.push_str(&format!("{:width$}  ", column, width = widths[i]));
+    }
+    out.push('\n');
+    for (i, _) in columns.iter().enumerate() {
+        out.push_str(&"-".repeat(widths[i]));
+        out.push_str("  ");
+    }
+    out.push('\n');
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            out.push_str(&format!("{:width$}  ", cell, width = widths[i]));
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Render records as CSV with a header row
+pub fn render_csv(items: &[SystemInfo], columns: &[String]) -> String {
+    let mut out = columns.join(",");
+    out.push('\n');
+
+    for info in items {
+        let row: Vec<String> = columns
+            .iter()
+            .map(|c| {
+                let cell = column_value(info, c);
+                // Quote cells that would break the row
+                if cell.contains(',') || cell.contains('"') {
+                    format!("\"{}\"", cell.replace('"', "\"\""))
+                } else {
+                    cell
+                }
+            })
+            .collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Render records as a JSON array of objects keyed by column name
+pub fn render_json(items: &[SystemInfo], columns: &[String]) -> Result<String> {
+    let rows: Vec<serde_json::Value> = items
+        .iter()
+        .map(|info| {
+            let mut row = serde_json::Map::new();
+            for column in columns {
+                row.insert(
+                    column.clone(),
+                    serde_json::Value::String(column_value(info, column)),
+                );
+            }
+            serde_json::Value::Object(row)
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&rows).map_err(|e| anyhow!("Failed to render JSON: {}", e))
+}
+
+/// Columns shown by `gos system-info fleet`, one row per host
+pub const FLEET_COLUMNS: &str = "hostname,cpu,load1,load5,mem_used,mem_total,uptime";
+
+/// One row of the fleet overview: an endpoint and what it reported.
+/// `info` is None when the host did not answer.
+pub struct FleetRow {
+    /// Endpoint name from the config, or the raw url from --endpoints
+    pub endpoint: String,
+    pub info: Option<SystemInfo>,
+}
+
+/// Sort fleet rows by a column, with the same '-' prefix convention as
+/// `sort_records`. Unreachable hosts always sink to the bottom so the
+/// comparison reads top-down regardless of sort order.
+pub fn sort_fleet(rows: &mut [FleetRow], spec: &str) -> Result<()> {
+    let (column, descending) = match spec.strip_prefix('-') {
+        Some(column) => (column, true),
+        None => (spec, false),
+    };
+
+    if !COLUMNS.contains(&column) {
+        bail!(
+            "Unknown sort column '{}'. Available columns: {}",
+            column,
+            COLUMNS.join(", ")
+        );
+    }
+
+    rows.sort_by(|a, b| match (&a.info, &b.info) {
+        (Some(x), Some(y)) => {
+            let ordering = match (column_number(x, column), column_number(y, column)) {
+                (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(std::cmp::Ordering::Equal),
+                _ => column_value(x, column).cmp(&column_value(y, column)),
+            };
+            if descending { ordering.reverse() } else { ordering }
+        }
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.endpoint.cmp(&b.endpoint),
+    });
+
+    Ok(())
+}
+
+/// Render the fleet comparison as a padded text table. Unreachable
+/// hosts keep their row, flagged in the status column, so a dead
+/// server is visible rather than silently missing.
+pub fn render_fleet_table(rows: &[FleetRow], columns: &[String]) -> String {
+    let headers: Vec<String> = ["endpoint", "status"]
+        .iter()
+        .map(|h| h.to_string())
+        .chain(columns.iter().cloned())
+        .collect();
+
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            let mut line = vec![row.endpoint.clone()];
+            match &row.info {
+                Some(info) => {
+                    line.push("ok".to_string());
+                    line.extend(columns.iter().map(|c| column_value(info, c)));
+                }
+                None => {
+                    line.push("unreachable".to_string());
+                    line.extend(columns.iter().map(|_| "-".to_string()));
+                }
+            }
+            line
+        })
+        .collect();
+
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+    for row in &cells {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.len());
+        }
+    }
+
+    let mut out = String::new();
+    for (i, header) in headers.iter().enumerate() {
+        out.push_str(&format!("{:width$}  ", header, width = widths[i]));
+    }
+    out.push('\n');
+    for (i, _) in headers.iter().enumerate() {
+        out.push_str(&"-".repeat(widths[i]));
+        out.push_str("  ");
+    }
+    out.push('\n');
+    for row in &cells {
+        for (i, cell) in row.iter().enumerate() {
+            out.push_str(&format!("{:width$}  ", cell, width = widths[i]));
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Numeric columns exported as metrics; timestamp becomes the sample
+/// time rather than a metric of its own
+pub const EXPORT_COLUMNS: [&str; 8] = [
+    "cpu", "load1", "load5", "load15", "mem_used", "mem_free", "mem_total", "uptime",
+];
+
+/// Timestamp resolution for exported samples. Prometheus expects
+/// milliseconds and InfluxDB defaults to nanoseconds, but both accept
+/// other units when the scraper/ingester is configured for them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampUnit {
+    Seconds,
+    Millis,
+    Nanos,
+}
+
+impl TimestampUnit {
+    /// Parse a `--timestamp-unit` argument
+    pub fn parse(spec: &str) -> Result<Self> {
+        match spec {
+            "seconds" | "s" => Ok(Self::Seconds),
+            "millis" | "ms" => Ok(Self::Millis),
+            "nanos" | "ns" => Ok(Self::Nanos),
+            other => bail!(
+                "Unknown timestamp unit '{}'. Use seconds, millis or nanos",
+                other
+            ),
+        }
+    }
+
+    /// Convert a Unix timestamp in seconds to this unit
+    pub fn convert(&self, seconds: i64) -> i64 {
+        match self {
+            Self::Seconds => seconds,
+            Self::Millis => seconds * 1_000,
+            Self::Nanos => seconds * 1_000_000_000,
+        }
+    }
+}
+
+/// Resolve the exported name of a column: an explicit rename from the
+/// `[metrics]` config table wins, otherwise the configured prefix (or
+/// "graphos") is joined to the column name
+pub fn metric_name(column: &str, config: &MetricsConfig) -> String {
+    if let Some(name) = config.rename.get(column) {
+        return name.clone();
+    }
+    let prefix = config.prefix.as_deref().unwrap_or("graphos");
+    format!("{}_{}", prefix, column)
+}
+
+/// Render records in Prometheus exposition format, one gauge per
+/// exported column with the hostname as a label
+pub fn render_prometheus(
+    items: &[SystemInfo],
+    config: &MetricsConfig,
+    unit: TimestampUnit,
+) -> String {
+    let mut out = String::new();
+
+    for column in EXPORT_COLUMNS {
+        let name = metric_name(column, config);
+        out.push_str(&format!("# TYPE {} gauge\n", name));
+        for info in items {
+            let Some(value) = column_number(info, column) else {
+                continue;
+            };
+            out.push_str(&format!(
+                "{}{{host=\"{}\"}} {} {}\n",
+                name,
+                info.hostname,
+                value,
+                unit.convert(info.timestamp)
+            ));
+        }
+    }
+
+    out
+}
+
+/// Escape a tag value for InfluxDB line protocol
+fn escape_influx_tag(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+}
+
+/// Render records as InfluxDB line protocol, one point per record with
+/// every exported column as a field. The measurement name is the
+/// configured prefix, so fields use bare (or renamed) column names.
+pub fn render_influx(items: &[SystemInfo], config: &MetricsConfig, unit: TimestampUnit) -> String {
+    let measurement = config.prefix.as_deref().unwrap_or("graphos");
+    let mut out = String::new();
+
+    for info in items {
+        let fields: Vec<String> = EXPORT_COLUMNS
+            .iter()
+            .filter_map(|column| {
+                let name = config.rename.get(*column).map_or(*column, String::as_str);
+                column_number(info, column).map(|value| format!("{}={}", name, value))
+            })
+            .collect();
+        if fields.is_empty() {
+            continue;
+        }
+        out.push_str(&format!(
+            "{},host={} {} {}\n",
+            measurement,
+            escape_influx_tag(&info.hostname),
+            fields.join(","),
+            unit.convert(info.timestamp)
+        ));
+    }
+
+    out
+}
+
+/// Min/max/avg summary over the numeric selected columns
+pub fn render_aggregates(items: &[SystemInfo], columns: &[String]) -> String {
+    let mut out = String::new();
+
+    for column in columns {
+        let values: Vec<f64> = items
+            .iter()
+            .filter_map(|info| column_number(info, column))
+            .collect();
+        if values.is_empty() {
+            continue;
+        }
+
+        let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let avg = values.iter().sum::<f64>() / values.len() as f64;
+
+        out.push_str(&format!(
+            "{}: min {:.2}, max {:.2}, avg {:.2}\n",
+            column, min, max, avg
+        ));
+    }
+
+    out
+}
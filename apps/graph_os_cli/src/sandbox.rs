@@ -0,0 +1,187 @@
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::error::{GraphOsError, Result};
+
+/// Wall-clock limit for a sandboxed run
+pub const RUN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// CPU seconds a sandboxed process may consume
+const CPU_LIMIT_SECS: u64 = 10;
+
+/// Address-space cap for a sandboxed process (512 MiB)
+const MEMORY_LIMIT_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Largest file a sandboxed process may create (8 MiB)
+const FILE_SIZE_LIMIT_BYTES: u64 = 8 * 1024 * 1024;
+
+/// How much captured output is kept per stream before truncation
+const OUTPUT_LIMIT_CHARS: usize = 4000;
+
+/// A fenced code block lifted out of a markdown response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// The fence's language tag, lowercased ("" when untagged)
+    pub language: String,
+    pub code: String,
+}
+
+/// Extract the last complete fenced code block from markdown text
+pub fn extract_last_code_block(text: &str) -> Option<CodeBlock> {
+    let mut last = None;
+    let mut current: Option<(String, Vec<&str>)> = None;
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            match current.take() {
+                // Closing fence: the block is complete
+                Some((language, lines)) => {
+                    last = Some(CodeBlock {
+                        language,
+                        code: lines.join("\n"),
+                    });
+                }
+                // Opening fence: capture the language tag
+                None => {
+                    let tag = line.trim_start().trim_start_matches('`').trim();
+                    let language = tag.split_whitespace().next().unwrap_or("").to_lowercase();
+                    current = Some((language, Vec::new()));
+                }
+            }
+        } else if let Some((_, lines)) = &mut current {
+            lines.push(line);
+        }
+    }
+
+    last
+}
+
+/// Map a fence language tag to an interpreter that reads the program
+/// from stdin. Returns None for languages we will not execute.
+pub fn interpreter_for(language: &str) -> Option<&'static str> {
+    match language {
+        "python" | "python3" | "py" => Some("python3"),
+        "sh" | "bash" | "shell" => Some("bash"),
+        "javascript" | "js" | "node" => Some("node"),
+        "ruby" | "rb" => Some("ruby"),
+        _ => None,
+    }
+}
+
+/// Captured result of a sandboxed run
+#[derive(Debug, Clone)]
+pub struct SandboxOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// Process exit code; None when killed by a signal or the timeout
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+}
+
+impl SandboxOutput {
+    /// Render the run as a message suitable for sending back to the
+    /// model for iterative debugging
+    pub fn render(&self) -> String {
+        let mut rendered = if self.timed_out {
+            format!("Execution timed out after {}s.\n", RUN_TIMEOUT.as_secs())
+        } else {
+            match self.exit_code {
+                Some(code) => format!("Execution finished with exit code {}.\n", code),
+                None => "Execution was killed by a signal.\n".to_string(),
+            }
+        };
+
+        if !self.stdout.trim().is_empty() {
+            rendered.push_str(&format!("stdout:\n{}\n", truncate(&self.stdout)));
+        }
+        if !self.stderr.trim().is_empty() {
+            rendered.push_str(&format!("stderr:\n{}\n", truncate(&self.stderr)));
+        }
+        if self.stdout.trim().is_empty() && self.stderr.trim().is_empty() {
+            rendered.push_str("(no output)\n");
+        }
+
+        rendered.trim_end().to_string()
+    }
+}
+
+/// Cap a captured stream so one chatty script cannot flood the transcript
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= OUTPUT_LIMIT_CHARS {
+        return text.trim_end().to_string();
+    }
+    let kept: String = text.chars().take(OUTPUT_LIMIT_CHARS).collect();
+    format!("{}\n[output truncated]", kept.trim_end())
+}
+
+/// Execute a code block in a sandboxed subprocess: the program is piped
+/// to the interpreter's stdin and runs under CPU, memory and file-size
+/// rlimits with network access dropped where the kernel allows it.
+pub async fn run_code(block: &CodeBlock) -> Result<SandboxOutput> {
+    let interpreter = interpreter_for(&block.language).ok_or_else(|| {
+        GraphOsError::Config(format!(
+            "No interpreter configured for '{}' code blocks", block.language
+        ))
+    })?;
+
+    let mut command = Command::new(interpreter);
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // Dropping the wait future on timeout must kill the child
+        .kill_on_drop(true);
+
+    // Apply the sandbox between fork and exec. Each limit is best
+    // effort: a refused rlimit degrades to a looser sandbox rather than
+    // failing the run outright. Windows has no rlimit equivalent, so
+    // the wall-clock timeout is the only cap there.
+    #[cfg(unix)]
+    unsafe {
+        command.pre_exec(|| {
+            use nix::sys::resource::{setrlimit, Resource};
+
+            let _ = setrlimit(Resource::RLIMIT_CPU, CPU_LIMIT_SECS, CPU_LIMIT_SECS);
+            let _ = setrlimit(Resource::RLIMIT_AS, MEMORY_LIMIT_BYTES, MEMORY_LIMIT_BYTES);
+            let _ = setrlimit(Resource::RLIMIT_FSIZE, FILE_SIZE_LIMIT_BYTES, FILE_SIZE_LIMIT_BYTES);
+
+            // Detach from the network namespace; needs privileges, so
+            // this silently stays connected on kernels that refuse it
+            let _ = nix::sched::unshare(nix::sched::CloneFlags::CLONE_NEWNET);
+
+            Ok(())
+        });
+    }
+
+    let mut child = command.spawn().map_err(|e| {
+        GraphOsError::Config(format!("Failed to launch {}: {}", interpreter, e))
+    })?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(block.code.as_bytes()).await?;
+        // Dropping stdin closes the pipe so the interpreter sees EOF
+    }
+
+    match timeout(RUN_TIMEOUT, child.wait_with_output()).await {
+        Ok(output) => {
+            let output = output?;
+            Ok(SandboxOutput {
+                stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+                exit_code: output.status.code(),
+                timed_out: false,
+            })
+        }
+        // kill_on_drop reaps the child when the wait future is dropped
+        Err(_) => Ok(SandboxOutput {
+            stdout: String::new(),
+            stderr: String::new(),
+            exit_code: None,
+            timed_out: true,
+        }),
+    }
+}
@@ -0,0 +1,243 @@
+//! Structured output: ask a provider for JSON conforming to a schema
+//! and validate the response client-side before anything trusts it.
+//!
+//! The validator covers the subset of JSON Schema that matters for
+//! response shapes: type, enum, const, properties, required,
+//! additionalProperties, items, and basic string/number/array bounds.
+//! Unknown keywords are ignored, matching how permissive validators
+//! treat them.
+
+use serde_json::Value;
+
+use crate::adapters::{JsonRpcClient, Message, MessageContent, MessageRole};
+use crate::error::GraphOsError;
+
+/// Schema-feedback retries made on top of the first attempt when the
+/// caller does not say otherwise
+pub const DEFAULT_SCHEMA_RETRIES: usize = 2;
+
+/// System-prompt instruction telling the provider to answer with JSON
+/// conforming to the given schema
+pub fn schema_instruction(schema: &Value) -> String {
+    format!(
+        "Respond with a single JSON value conforming to this JSON Schema, \
+         with no prose, code fences or commentary around it:\n{}",
+        serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string())
+    )
+}
+
+/// Pull the first JSON value out of a response, tolerating code fences
+/// and surrounding prose despite the instruction to omit them
+pub fn extract_json(text: &str) -> Option<Value> {
+    let trimmed = text.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Some(value);
+    }
+
+    // Fall back to the first object or array in the text; the stream
+    // deserializer stops at the end of the value, so trailing prose
+    // does not break the parse
+    let start = trimmed.find(['{', '['])?;
+    serde_json::Deserializer::from_str(&trimmed[start..])
+        .into_iter::<Value>()
+        .next()?
+        .ok()
+}
+
+/// Validate an instance against a schema. Returns one message per
+/// violation; an empty list means the instance conforms.
+pub fn validate(schema: &Value, instance: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(schema, instance, "$", &mut errors);
+    errors
+}
+
+fn validate_at(schema: &Value, instance: &Value, path: &str, errors: &mut Vec<String>) {
+    // A non-object schema (true, or anything we don't model) accepts
+    // every instance
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(|v| v.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|name| type_matches(name, instance)) {
+            errors.push(format!(
+                "at {}: expected {}, got {}",
+                path,
+                allowed.join(" or "),
+                type_name(instance)
+            ));
+            // The remaining keywords assume the right type
+            return;
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(|v| v.as_array())
+        && !options.contains(instance)
+    {
+        errors.push(format!("at {}: value is not one of the allowed enum values", path));
+    }
+    if let Some(expected) = schema.get("const")
+        && instance != expected
+    {
+        errors.push(format!("at {}: value does not match the required const", path));
+    }
+
+    match instance {
+        Value::Object(fields) => {
+            let properties = schema.get("properties").and_then(|v| v.as_object());
+            if let Some(required) = schema.get("required").and_then(|v| v.as_array()) {
+                for name in required.iter().filter_map(|v| v.as_str()) {
+                    if !fields.contains_key(name) {
+                        errors.push(format!("at {}: missing required property '{}'", path, name));
+                    }
+                }
+            }
+            for (name, value) in fields {
+                if let Some(subschema) = properties.and_then(|p| p.get(name)) {
+                    validate_at(subschema, value, &format!("{}.{}", path, name), errors);
+                } else if schema.get("additionalProperties") == Some(&Value::Bool(false)) {
+                    errors.push(format!("at {}: unexpected property '{}'", path, name));
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(subschema) = schema.get("items") {
+                for (index, value) in items.iter().enumerate() {
+                    validate_at(subschema, value, &format!("{}[{}]", path, index), errors);
+                }
+            }
+            if let Some(min) = schema.get("minItems").and_then(|v| v.as_u64())
+                && (items.len() as u64) < min
+            {
+                errors.push(format!("at {}: expected at least {} items, got {}", path, min, items.len()));
+            }
+            if let Some(max) = schema.get("maxItems").and_then(|v| v.as_u64())
+                && (items.len() as u64) > max
+            {
+                errors.push(format!("at {}: expected at most {} items, got {}", path, max, items.len()));
+            }
+        }
+        Value::String(text) => {
+            let chars = text.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(|v| v.as_u64())
+                && chars < min
+            {
+                errors.push(format!("at {}: string shorter than minLength {}", path, min));
+            }
+            if let Some(max) = schema.get("maxLength").and_then(|v| v.as_u64())
+                && chars > max
+            {
+                errors.push(format!("at {}: string longer than maxLength {}", path, max));
+            }
+        }
+        Value::Number(number) => {
+            let value = number.as_f64().unwrap_or(0.0);
+            if let Some(min) = schema.get("minimum").and_then(|v| v.as_f64())
+                && value < min
+            {
+                errors.push(format!("at {}: {} is below the minimum {}", path, value, min));
+            }
+            if let Some(max) = schema.get("maximum").and_then(|v| v.as_f64())
+                && value > max
+            {
+                errors.push(format!("at {}: {} is above the maximum {}", path, value, max));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn type_matches(expected: &str, instance: &Value) -> bool {
+    match expected {
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        // Integers are numbers too, per the spec
+        "number" => instance.is_number(),
+        "integer" => instance.as_i64().is_some() || instance.as_u64().is_some(),
+        "boolean" => instance.is_boolean(),
+        "null" => instance.is_null(),
+        // Unknown type names accept everything rather than rejecting
+        // valid responses over a schema we don't fully model
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+    }
+}
+
+/// Outcome of a structured request: validated JSON when an attempt
+/// conformed, otherwise the final raw response and the errors that
+/// rejected it
+#[derive(Debug)]
+pub struct StructuredResult {
+    /// Validated JSON, present when some attempt conformed
+    pub value: Option<Value>,
+    /// Validation errors from the final attempt when none did
+    pub errors: Vec<String>,
+    /// Raw text of the final response
+    pub raw: String,
+    /// Number of requests sent
+    pub attempts: usize,
+}
+
+/// Ask the provider for schema-conforming JSON, re-asking with the
+/// validation errors as feedback until a response conforms or the
+/// retry budget runs out. Transport errors abort immediately;
+/// validation failures do not.
+pub async fn request_structured(
+    client: &JsonRpcClient,
+    mut messages: Vec<Message>,
+    schema: &Value,
+    max_retries: usize,
+) -> Result<StructuredResult, GraphOsError> {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let raw = client.chat(messages.clone(), false, None).await?;
+
+        let (value, errors) = match extract_json(&raw) {
+            Some(candidate) => {
+                let errors = validate(schema, &candidate);
+                if errors.is_empty() {
+                    (Some(candidate), Vec::new())
+                } else {
+                    (None, errors)
+                }
+            }
+            None => (None, vec!["response did not contain a JSON value".to_string()]),
+        };
+
+        if value.is_some() || attempts > max_retries {
+            return Ok(StructuredResult { value, errors, raw, attempts });
+        }
+
+        // Feed the failure back so the next attempt can correct it
+        messages.push(Message {
+            role: MessageRole::Assistant,
+            content: MessageContent::Text(raw),
+        });
+        messages.push(Message {
+            role: MessageRole::User,
+            content: MessageContent::Text(format!(
+                "That response failed schema validation:\n{}\n\
+                 Reply again with only a corrected JSON value that conforms to the schema.",
+                errors.join("\n")
+            )),
+        });
+    }
+}
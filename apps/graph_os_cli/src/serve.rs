@@ -0,0 +1,276 @@
+//! Embedded JSON-RPC development server backing `gos serve`.
+//!
+//! Implements just enough of the backend protocol — `ping`, `chat` with
+//! NDJSON streaming, and the session methods — that the TUI, tests, and
+//! CI can run end-to-end without the Elixir backend. Responses either
+//! echo the last user message or cycle through a scripted file.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use axum::body::{Bytes, StreamBody};
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::signing::{self, RequestVerifier};
+
+/// Delay between streamed chunks so streaming code paths actually
+/// exercise their buffering
+const STREAM_CHUNK_DELAY: Duration = Duration::from_millis(25);
+
+/// Shared state for one server instance
+struct ServeState {
+    /// Scripted responses cycled through instead of echoing, if provided
+    script: Vec<String>,
+    next_response: AtomicUsize,
+    /// Sessions created over RPC, kept in memory only
+    sessions: Mutex<HashMap<Uuid, Value>>,
+    /// When set, every request must carry a valid HMAC signature
+    verifier: Option<RequestVerifier>,
+}
+
+/// Incoming JSON-RPC request; permissive about the id type so any
+/// conforming client can talk to the dev server
+#[derive(Deserialize)]
+struct RpcRequest {
+    method: String,
+    #[serde(default)]
+    params: Value,
+    #[serde(default)]
+    id: Value,
+}
+
+fn rpc_result(id: &Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "result": result, "id": id })
+}
+
+fn rpc_error(id: &Value, code: i32, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "error": { "code": code, "message": message, "data": null },
+        "id": id
+    })
+}
+
+/// Start the dev server and run it until the process exits
+pub async fn run(
+    host: &str,
+    port: u16,
+    script: Option<PathBuf>,
+    verifier: Option<RequestVerifier>,
+) -> Result<()> {
+    let verifying = verifier.is_some();
+    let (addr, server) = bind(host, port, script, verifier)?;
+    println!("Dev JSON-RPC server listening on http://{}/api/jsonrpc", addr);
+    println!("Point gos at it with: gos --api-port {}", addr.port());
+    if verifying {
+        println!("Requiring HMAC request signatures");
+    }
+    server.await
+}
+
+/// Bind the dev server, returning the bound address and a future that
+/// serves requests. Split from [`run`] so tests can bind port 0.
+pub fn bind(
+    host: &str,
+    port: u16,
+    script: Option<PathBuf>,
+    verifier: Option<RequestVerifier>,
+) -> Result<(SocketAddr, impl Future<Output = Result<()>>)> {
+    let script = match script {
+        Some(path) => {
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read script file: {}", path.display()))?;
+            contents
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.to_string())
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    let state = Arc::new(ServeState {
+        script,
+        next_response: AtomicUsize::new(0),
+        sessions: Mutex::new(HashMap::new()),
+        verifier,
+    });
+
+    let router = Router::new()
+        .route("/api/jsonrpc", post(handle_rpc))
+        .with_state(state);
+
+    let addr = (host, port)
+        .to_socket_addrs()
+        .with_context(|| format!("Failed to resolve listen address {}:{}", host, port))?
+        .next()
+        .ok_or_else(|| anyhow!("Listen address {}:{} resolved to nothing", host, port))?;
+
+    let server = axum::Server::try_bind(&addr)
+        .with_context(|| format!("Failed to bind {}", addr))?
+        .serve(router.into_make_service());
+    let bound = server.local_addr();
+
+    Ok((bound, async move { server.await.context("Dev server failed") }))
+}
+
+async fn handle_rpc(
+    State(state): State<Arc<ServeState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    // Verify the signature over the raw body before parsing anything
+    // from it, matching what a production verifier would do
+    if let Some(verifier) = &state.verifier {
+        let header = headers
+            .get(signing::SIGNATURE_HEADER)
+            .and_then(|value| value.to_str().ok());
+        if let Err(reason) = verifier.verify(header, &body) {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(rpc_error(&Value::Null, -32000, &format!("Invalid request signature: {}", reason))),
+            )
+                .into_response();
+        }
+    }
+
+    let request: RpcRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(_) => return Json(rpc_error(&Value::Null, -32700, "Parse error")).into_response(),
+    };
+
+    match request.method.as_str() {
+        "ping" => Json(rpc_result(&request.id, json!({ "status": "ok" }))).into_response(),
+        "chat" => handle_chat(&state, &request),
+        "session.create" => session_create(&state, &request),
+        "session.list" => session_list(&state, &request),
+        "session.get" => session_get(&state, &request),
+        "session.delete" => session_delete(&state, &request),
+        other => Json(rpc_error(
+            &request.id,
+            -32601,
+            &format!("Method not found: {}", other),
+        ))
+        .into_response(),
+    }
+}
+
+/// Pick the response body: next scripted line if a script was given,
+/// otherwise an echo of the last user message
+fn response_text(state: &ServeState, params: &Value) -> String {
+    if !state.script.is_empty() {
+        let index = state.next_response.fetch_add(1, Ordering::Relaxed);
+        return state.script[index % state.script.len()].clone();
+    }
+
+    params
+        .get("messages")
+        .and_then(|m| m.as_array())
+        .and_then(|messages| {
+            messages
+                .iter()
+                .rev()
+                .find(|m| m.get("role").and_then(|r| r.as_str()) == Some("user"))
+        })
+        .and_then(|m| m.get("content"))
+        .and_then(|c| c.as_str())
+        .map(|text| format!("Echo: {}", text))
+        .unwrap_or_else(|| "Echo: (no user message)".to_string())
+}
+
+fn handle_chat(state: &ServeState, request: &RpcRequest) -> Response {
+    let text = response_text(state, &request.params);
+    let streaming = request.params.get("stream").and_then(|s| s.as_bool()).unwrap_or(false);
+
+    if !streaming {
+        return Json(rpc_result(&request.id, json!({ "message": text }))).into_response();
+    }
+
+    // Stream the response word by word as NDJSON chunks, matching the
+    // framing process_streaming_response expects
+    let id = request.id.clone();
+    let chunks: Vec<String> = text.split_inclusive(' ').map(|s| s.to_string()).collect();
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<String, Infallible>>(16);
+
+    tokio::spawn(async move {
+        for chunk in chunks {
+            let mut line = rpc_result(&id, json!({ "content": chunk })).to_string();
+            line.push('\n');
+            if tx.send(Ok(line)).await.is_err() {
+                // Client went away; stop streaming
+                return;
+            }
+            tokio::time::sleep(STREAM_CHUNK_DELAY).await;
+        }
+
+        let mut line = rpc_result(&id, json!({ "done": true })).to_string();
+        line.push('\n');
+        let _ = tx.send(Ok(line)).await;
+    });
+
+    let body = StreamBody::new(tokio_stream::wrappers::ReceiverStream::new(rx));
+    ([(header::CONTENT_TYPE, "application/json-seq")], body).into_response()
+}
+
+fn session_create(state: &ServeState, request: &RpcRequest) -> Response {
+    let id = Uuid::new_v4();
+    let session = json!({
+        "id": id,
+        "created_at": Utc::now(),
+        "messages": []
+    });
+
+    state.sessions.lock().unwrap().insert(id, session.clone());
+    Json(rpc_result(&request.id, session)).into_response()
+}
+
+fn session_list(state: &ServeState, request: &RpcRequest) -> Response {
+    let sessions: Vec<Value> = state.sessions.lock().unwrap().values().cloned().collect();
+    Json(rpc_result(&request.id, json!({ "sessions": sessions }))).into_response()
+}
+
+/// Parse the `id` param shared by session.get and session.delete
+fn session_id_param(request: &RpcRequest) -> Option<Uuid> {
+    request
+        .params
+        .get("id")
+        .and_then(|v| v.as_str())
+        .and_then(|s| Uuid::parse_str(s).ok())
+}
+
+fn session_get(state: &ServeState, request: &RpcRequest) -> Response {
+    let Some(id) = session_id_param(request) else {
+        return Json(rpc_error(&request.id, -32602, "Missing or invalid session id")).into_response();
+    };
+
+    match state.sessions.lock().unwrap().get(&id) {
+        Some(session) => Json(rpc_result(&request.id, session.clone())).into_response(),
+        None => Json(rpc_error(&request.id, -32001, "Session not found")).into_response(),
+    }
+}
+
+fn session_delete(state: &ServeState, request: &RpcRequest) -> Response {
+    let Some(id) = session_id_param(request) else {
+        return Json(rpc_error(&request.id, -32602, "Missing or invalid session id")).into_response();
+    };
+
+    match state.sessions.lock().unwrap().remove(&id) {
+        Some(_) => Json(rpc_result(&request.id, json!({ "deleted": true }))).into_response(),
+        None => Json(rpc_error(&request.id, -32001, "Session not found")).into_response(),
+    }
+}
@@ -1,19 +1,265 @@
-use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
-use anyhow::{Context, Result};
+use std::{collections::HashMap, io::Write as _, path::{Path, PathBuf}, sync::Arc, sync::atomic::{AtomicBool, Ordering}, time::{Duration, Instant}};
+use crate::error::{GraphOsError, Result};
+use futures_util::StreamExt;
+#[cfg(unix)]
+use nix::fcntl::{Flock, FlockArg};
 use chrono::{DateTime, Utc};
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 use tokio::{
     fs,
-    io::{AsyncReadExt, AsyncWriteExt},
-    net::{TcpListener, TcpStream},
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
     select,
     sync::{mpsc, Mutex},
     time::{sleep, timeout},
 };
 use uuid::Uuid;
 
-const VIBE_PORT: u16 = 9876;
+use crate::crypto::SessionCipher;
+
+/// Platform transport for session commands. Unix uses a loopback TCP
+/// socket where binding the fixed port doubles as the single-instance
+/// and election lock. Windows uses a named pipe instead — loopback
+/// sockets trip firewall prompts there — with the first pipe instance
+/// playing the same lock role. Both expose connect/bind/accept so the
+/// rest of this module is platform-agnostic.
+#[cfg(unix)]
+pub(crate) mod transport {
+    use tokio::net::{TcpListener, TcpStream};
+
+    pub(crate) const VIBE_PORT: u16 = 9876;
+
+    /// Client and server ends of a session connection
+    pub(crate) type ClientStream = TcpStream;
+    pub(crate) type ServerStream = TcpStream;
+
+    pub(crate) struct Listener(TcpListener);
+
+    /// Human-readable endpoint for log messages
+    pub(crate) fn endpoint_name() -> String {
+        format!("port {}", VIBE_PORT)
+    }
+
+    pub(crate) async fn connect() -> std::io::Result<ClientStream> {
+        TcpStream::connect(format!("127.0.0.1:{}", VIBE_PORT)).await
+    }
+
+    pub(crate) async fn bind() -> std::io::Result<Listener> {
+        Ok(Listener(TcpListener::bind(format!("127.0.0.1:{}", VIBE_PORT)).await?))
+    }
+
+    impl Listener {
+        pub(crate) async fn accept(&mut self) -> std::io::Result<ServerStream> {
+            let (stream, _) = self.0.accept().await?;
+            Ok(stream)
+        }
+    }
+}
+
+#[cfg(windows)]
+pub(crate) mod transport {
+    use tokio::net::windows::named_pipe::{ClientOptions, NamedPipeClient, NamedPipeServer, ServerOptions};
+
+    const PIPE_NAME: &str = r"\\.\pipe\graph_os_sessions";
+
+    /// Client and server ends of a session connection; named pipe ends
+    /// are distinct types, unlike sockets
+    pub(crate) type ClientStream = NamedPipeClient;
+    pub(crate) type ServerStream = NamedPipeServer;
+
+    pub(crate) struct Listener {
+        /// Pipe instance waiting for the next client
+        next: NamedPipeServer,
+    }
+
+    /// Human-readable endpoint for log messages
+    pub(crate) fn endpoint_name() -> String {
+        format!("pipe {}", PIPE_NAME)
+    }
+
+    pub(crate) async fn connect() -> std::io::Result<ClientStream> {
+        ClientOptions::new().open(PIPE_NAME)
+    }
+
+    pub(crate) async fn bind() -> std::io::Result<Listener> {
+        // first_pipe_instance makes creation fail when another process
+        // already serves this pipe — the election lock
+        let next = ServerOptions::new()
+            .first_pipe_instance(true)
+            .create(PIPE_NAME)?;
+        Ok(Listener { next })
+    }
+
+    impl Listener {
+        pub(crate) async fn accept(&mut self) -> std::io::Result<ServerStream> {
+            self.next.connect().await?;
+            // Hand out the connected instance and queue up the next one
+            let connected = std::mem::replace(&mut self.next, ServerOptions::new().create(PIPE_NAME)?);
+            Ok(connected)
+        }
+    }
+}
+
+/// How long a client waits for the listener to accept a connection.
+/// Doubles as the heartbeat: a dead or wedged listener fails this fast
+/// instead of blocking the election behind a stale socket.
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long a client waits for the listener to answer once a command
+/// has been sent. Independent of the connect heartbeat: a listener
+/// that accepts connections but never answers is just as wedged as one
+/// that is gone, and both must surface as a classified Timeout instead
+/// of hanging the TUI.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How many election rounds a client runs before giving up
+const ELECTION_RETRIES: usize = 3;
+
+/// Default seconds between autosave sweeps; override with the
+/// GOS_AUTOSAVE_SECS environment variable
+const DEFAULT_AUTOSAVE_SECS: u64 = 30;
+
+/// A session changed this recently is skipped by the sweep, so a burst
+/// of rapid updates settles before its file is rewritten
+const AUTOSAVE_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// How many session files the startup loader reads concurrently
+const LOAD_CONCURRENCY: usize = 16;
+
+/// Scans touching at least this many files print a progress line; a
+/// warm index usually leaves nothing to scan, so startup stays quiet
+const LOAD_PROGRESS_MIN: usize = 20;
+
+/// Eagerly-loaded session index, next to the session files. Not a
+/// .json name so the loader never mistakes it for a session.
+const SESSION_INDEX_FILE: &str = ".sessions.index";
+
+/// The autosave interval, from GOS_AUTOSAVE_SECS when set and valid
+fn autosave_interval() -> Duration {
+    let secs = std::env::var("GOS_AUTOSAVE_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|&secs| secs > 0)
+        .unwrap_or(DEFAULT_AUTOSAVE_SECS);
+    Duration::from_secs(secs)
+}
+
+/// Tracks which sessions changed in memory since their last successful
+/// write, so the autosave sweep only rewrites files that need it instead
+/// of blanket-rewriting every session on every tick
+#[derive(Debug, Default)]
+pub struct DirtyTracker {
+    /// Version counter per session, bumped on every in-memory change
+    versions: HashMap<Uuid, u64>,
+    /// Version that last made it to disk
+    saved: HashMap<Uuid, u64>,
+    /// When each session last changed, for debouncing update bursts
+    touched: HashMap<Uuid, Instant>,
+}
+
+impl DirtyTracker {
+    /// Record an in-memory change, returning the new version to hand to
+    /// [`DirtyTracker::mark_saved`] once it is persisted
+    pub fn touch(&mut self, id: Uuid) -> u64 {
+        let version = self.versions.entry(id).or_insert(0);
+        *version += 1;
+        self.touched.insert(id, Instant::now());
+        *version
+    }
+
+    /// Record that `version` of a session reached disk. A stale version
+    /// never marks a newer change clean.
+    pub fn mark_saved(&mut self, id: Uuid, version: u64) {
+        let saved = self.saved.entry(id).or_insert(0);
+        if version > *saved {
+            *saved = version;
+        }
+    }
+
+    /// Sessions changed since their last save and quiet for at least
+    /// `debounce`, paired with the version to acknowledge after writing.
+    /// A session still being updated is left for the next sweep.
+    pub fn dirty(&self, debounce: Duration) -> Vec<(Uuid, u64)> {
+        self.versions
+            .iter()
+            .filter(|(id, version)| self.saved.get(id).copied().unwrap_or(0) < **version)
+            .filter(|(id, _)| {
+                self.touched
+                    .get(id)
+                    .is_none_or(|touched| touched.elapsed() >= debounce)
+            })
+            .map(|(id, version)| (*id, *version))
+            .collect()
+    }
+}
+
+/// How long an exclusive session lease lasts without renewal, so a
+/// crashed holder frees its session instead of wedging it
+pub const LEASE_TTL: Duration = Duration::from_secs(30);
+
+/// How often a lease holder renews, comfortably inside the TTL
+pub const LEASE_RENEW_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Exclusive write claims on sessions, held by the listener so two
+/// TUIs opening the same `--session` cannot silently overwrite each
+/// other's saves. A lease names the holding pid and expires unless
+/// renewed; `now` is passed in explicitly so expiry is testable.
+#[derive(Debug, Default)]
+pub struct LeaseTable {
+    leases: HashMap<Uuid, (u32, Instant)>,
+}
+
+impl LeaseTable {
+    /// Claim `id` for `pid` until `now + LEASE_TTL`. Err carries the
+    /// holding pid when another process has an unexpired lease and
+    /// `force` is not set. Re-acquiring one's own lease renews it.
+    pub fn acquire(
+        &mut self,
+        id: Uuid,
+        pid: u32,
+        force: bool,
+        now: Instant,
+    ) -> std::result::Result<(), u32> {
+        if let Some((holder, expires)) = self.leases.get(&id)
+            && *holder != pid
+            && *expires > now
+            && !force
+        {
+            return Err(*holder);
+        }
+        self.leases.insert(id, (pid, now + LEASE_TTL));
+        Ok(())
+    }
+
+    /// Drop `pid`'s lease on `id`; a lease someone else took over in
+    /// the meantime is left alone
+    pub fn release(&mut self, id: Uuid, pid: u32) {
+        if self.leases.get(&id).is_some_and(|(holder, _)| *holder == pid) {
+            self.leases.remove(&id);
+        }
+    }
+}
+
+/// Live-tail subscribers per session, registered by the Subscribe
+/// command. The listener pushes each update to them as it happens, so
+/// `gos watch` never polls.
+type Watchers = Arc<Mutex<HashMap<Uuid, Vec<mpsc::Sender<Session>>>>>;
+
+/// Push an updated session to everyone watching it. A full channel
+/// just skips this update — the next push carries the newer state —
+/// and only a hung-up subscriber is unsubscribed.
+async fn notify_watchers(watchers: &Watchers, session: &Session) {
+    let mut lock = watchers.lock().await;
+    if let Some(subscribers) = lock.get_mut(&session.id) {
+        subscribers.retain(|tx| {
+            let _ = tx.try_send(session.clone());
+            !tx.is_closed()
+        });
+        if subscribers.is_empty() {
+            lock.remove(&session.id);
+        }
+    }
+}
 
 static SESSION_MANAGER: OnceCell<Arc<SessionManager>> = OnceCell::new();
 
@@ -23,34 +269,632 @@ pub struct Session {
     pub created_at: DateTime<Utc>,
     pub last_active: DateTime<Utc>,
     pub messages: Vec<ChatMessage>,
+    /// Session this one was forked from, if any
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    /// Message index in the parent at which the fork was taken
+    #[serde(default)]
+    pub forked_at: Option<usize>,
+    /// Indices of bookmarked messages in this conversation
+    #[serde(default)]
+    pub bookmarks: Vec<usize>,
+    /// System prompt for this conversation, set by a template if any
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Workspace context paths attached with /context add
+    #[serde(default)]
+    pub context: Vec<String>,
+    /// Knowledge packs attached with /kb use
+    #[serde(default)]
+    pub kb: Vec<String>,
+    /// Human-readable title, set with `gos new --title`
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Free-form tags for grouping sessions in listings
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Provider this session prefers, overriding the config default
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model this session prefers, set with /model in the chat and
+    /// restored when the session is resumed
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// One session's header in the eager index: enough to list and sort
+/// sessions without reading their conversation files, which are loaded
+/// lazily when a body is actually requested
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionIndexEntry {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub last_active: DateTime<Utc>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Session this one was forked from, if any
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    /// Message index in the parent at which the fork was taken
+    #[serde(default)]
+    pub forked_at: Option<usize>,
+    /// Conversation length, so listings show counts without the body
+    #[serde(default)]
+    pub messages: usize,
+    /// Provider this session prefers, so listings show which backend
+    /// each conversation talks to
+    #[serde(default)]
+    pub provider: Option<String>,
+}
+
+impl From<&Session> for SessionIndexEntry {
+    fn from(session: &Session) -> Self {
+        SessionIndexEntry {
+            id: session.id,
+            created_at: session.created_at,
+            last_active: session.last_active,
+            title: session.title.clone(),
+            tags: session.tags.clone(),
+            parent_id: session.parent_id,
+            forked_at: session.forked_at,
+            messages: session.messages.len(),
+            provider: session.provider.clone(),
+        }
+    }
+}
+
+impl Session {
+    /// Create an empty session with the given id
+    pub fn new(id: Uuid) -> Self {
+        Session {
+            id,
+            created_at: Utc::now(),
+            last_active: Utc::now(),
+            messages: Vec::new(),
+            parent_id: None,
+            forked_at: None,
+            bookmarks: Vec::new(),
+            system_prompt: None,
+            context: Vec::new(),
+            kb: Vec::new(),
+            title: None,
+            tags: Vec::new(),
+            provider: None,
+            model: None,
+        }
+    }
+}
+
+/// How and when a message was produced. Absent fields mean the message
+/// predates metadata tracking or the information was unavailable.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct MessageMeta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+    /// Provider that produced an assistant message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// End-to-end latency of the request that produced this message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    /// Completion tokens (estimated when the server reports none)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<u64>,
+    /// Milliseconds until the first streamed token arrived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_token_ms: Option<u64>,
+    /// Average completion tokens per second over the stream
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_per_sec: Option<f64>,
+    /// Persona that produced the message in `/agents` mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    /// Absolute stored index of the message this one replies to,
+    /// recorded by /quote
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<usize>,
+    /// Pinned messages are always sent with the conversation history,
+    /// even when older history is truncated away
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+}
+
+impl MessageMeta {
+    /// Metadata carrying only the current time
+    pub fn now() -> Self {
+        MessageMeta {
+            timestamp: Some(Utc::now()),
+            ..MessageMeta::default()
+        }
+    }
+
+    /// Whether no field is set, so displays can skip the line entirely
+    pub fn is_empty(&self) -> bool {
+        *self == MessageMeta::default()
+    }
+
+    /// One-line summary for transcripts and the TUI
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(timestamp) = &self.timestamp {
+            parts.push(timestamp.format("%Y-%m-%d %H:%M:%S").to_string());
+        }
+        if let Some(agent) = &self.agent {
+            parts.push(format!("agent {}", agent));
+        }
+        if let Some(reply_to) = self.reply_to {
+            parts.push(format!("re #{}", reply_to));
+        }
+        if let Some(provider) = &self.provider {
+            parts.push(provider.clone());
+        }
+        if let Some(model) = &self.model {
+            parts.push(model.clone());
+        }
+        if let Some(latency) = self.latency_ms {
+            parts.push(format!("{}ms", latency));
+        }
+        if let Some(tokens) = self.tokens {
+            parts.push(format!("~{} tokens", tokens));
+        }
+        if let Some(first_token) = self.first_token_ms {
+            parts.push(format!("first token {}ms", first_token));
+        }
+        if let Some(rate) = self.tokens_per_sec {
+            parts.push(format!("{:.1} tok/s", rate));
+        }
+        if self.pinned {
+            parts.push("pinned".to_string());
+        }
+        parts.join(" | ")
+    }
 }
 
+/// A transcript message: the role tags the variant, each carrying the
+/// text and metadata about its generation. Deserialization also accepts
+/// the pre-metadata format where a variant held a bare string, so old
+/// session files migrate transparently on load.
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(from = "ChatMessageCompat")]
 pub enum ChatMessage {
+    User { text: String, meta: MessageMeta },
+    Assistant { text: String, meta: MessageMeta },
+}
+
+impl ChatMessage {
+    /// A user message stamped with the current time
+    pub fn user(text: String) -> Self {
+        ChatMessage::User { text, meta: MessageMeta::now() }
+    }
+
+    /// An assistant message stamped with the current time
+    pub fn assistant(text: String) -> Self {
+        ChatMessage::Assistant { text, meta: MessageMeta::now() }
+    }
+
+    pub fn text(&self) -> &str {
+        match self {
+            ChatMessage::User { text, .. } | ChatMessage::Assistant { text, .. } => text,
+        }
+    }
+
+    pub fn meta(&self) -> &MessageMeta {
+        match self {
+            ChatMessage::User { meta, .. } | ChatMessage::Assistant { meta, .. } => meta,
+        }
+    }
+
+    pub fn role(&self) -> &'static str {
+        match self {
+            ChatMessage::User { .. } => "user",
+            ChatMessage::Assistant { .. } => "assistant",
+        }
+    }
+}
+
+/// Accepts both the current message shape and the legacy one, tried in
+/// that order since untagged enums match the first fitting variant
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChatMessageCompat {
+    Modern(ModernChatMessage),
+    Legacy(LegacyChatMessage),
+}
+
+/// Mirror of [`ChatMessage`] without the `from` attribute, so the
+/// derived deserializer can be reused by the compat shim
+#[derive(Deserialize)]
+enum ModernChatMessage {
+    User {
+        text: String,
+        #[serde(default)]
+        meta: MessageMeta,
+    },
+    Assistant {
+        text: String,
+        #[serde(default)]
+        meta: MessageMeta,
+    },
+}
+
+/// Message format written before metadata existed
+#[derive(Deserialize)]
+enum LegacyChatMessage {
     User(String),
     Assistant(String),
 }
 
+impl From<ChatMessageCompat> for ChatMessage {
+    fn from(compat: ChatMessageCompat) -> Self {
+        match compat {
+            ChatMessageCompat::Modern(ModernChatMessage::User { text, meta }) => {
+                ChatMessage::User { text, meta }
+            }
+            ChatMessageCompat::Modern(ModernChatMessage::Assistant { text, meta }) => {
+                ChatMessage::Assistant { text, meta }
+            }
+            // Legacy messages carry no metadata at all
+            ChatMessageCompat::Legacy(LegacyChatMessage::User(text)) => {
+                ChatMessage::User { text, meta: MessageMeta::default() }
+            }
+            ChatMessageCompat::Legacy(LegacyChatMessage::Assistant(text)) => {
+                ChatMessage::Assistant { text, meta: MessageMeta::default() }
+            }
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 enum SessionCommand {
     GetOrCreateSession,
     GetSession(Uuid),
-    UpdateSession(Session),
+    /// Session header without its conversation, for lazy loading
+    GetSessionMeta(Uuid),
+    /// One page of a conversation, without shipping the whole session
+    GetSessionMessages { id: Uuid, offset: usize, limit: usize },
+    UpdateSession(Box<Session>),
     ListSessions,
+    /// Session headers only, from the eager index; no bodies are read
+    ListIndex,
+    /// Sorted, paginated listing. A separate command rather than
+    /// options on ListSessions so older listeners keep decoding the
+    /// unit variant during an upgrade.
+    ListSessionsPage { options: ListOptions },
+    /// Claim exclusive write access to a session (see [`LeaseTable`])
+    AcquireLease { id: Uuid, pid: u32, force: bool },
+    /// Extend a held lease before it expires
+    RenewLease { id: Uuid, pid: u32 },
+    /// Give a lease back on exit
+    ReleaseLease { id: Uuid, pid: u32 },
+    /// Live-tail a session for `gos watch`: the listener answers with
+    /// the current session, keeps the connection open, and pushes a
+    /// frame per update until the subscriber hangs up
+    Subscribe(Uuid),
+    /// Filtered view of a conversation, applied listener-side so large
+    /// sessions are not shipped whole just to inspect a slice
+    FilterMessages { id: Uuid, filter: MessageFilter },
+    /// Liveness probe used by `gos daemon status`
+    Ping,
+    /// Ask the listener to exit, used by `gos daemon stop`
+    Shutdown,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 enum SessionResponse {
     Session(Session),
     Sessions(Vec<Session>),
+    /// Session headers from the eager index
+    Index(Vec<SessionIndexEntry>),
+    /// One page of headers plus the total count before paging
+    IndexPage { entries: Vec<SessionIndexEntry>, total: usize },
+    /// One page of full sessions plus the total count before paging
+    SessionsPage { sessions: Vec<Session>, total: usize },
+    /// Session with its messages stripped, plus the conversation length
+    Meta { session: Session, total: usize },
+    /// One page of messages plus the total conversation length
+    Messages { messages: Vec<ChatMessage>, total: usize },
+    /// Filtered messages keyed by their absolute stored index, plus the
+    /// total conversation length
+    IndexedMessages { messages: Vec<(usize, ChatMessage)>, total: usize },
     Error(String),
+    /// A lease request was refused; carries the pid holding it
+    LeaseHeld { holder_pid: u32 },
+    /// Acknowledgement for commands without a session payload
+    Ok(String),
+}
+
+/// How a session listing is ordered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionSort {
+    /// Most recently active first
+    #[default]
+    LastActive,
+    /// Oldest first, in creation order
+    Created,
+    /// Alphabetical by title; untitled sessions sort last
+    Title,
+}
+
+impl SessionSort {
+    /// Parse a `--sort` flag value
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "last-active" => Some(SessionSort::LastActive),
+            "created" => Some(SessionSort::Created),
+            "title" => Some(SessionSort::Title),
+            _ => None,
+        }
+    }
+}
+
+/// Options for a sorted, paginated session listing
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListOptions {
+    pub sort: SessionSort,
+    /// Entries to skip before the page starts
+    pub offset: usize,
+    /// Page size; None returns everything from the offset on
+    pub limit: Option<usize>,
+    /// Answer with index entries instead of full sessions, so message
+    /// bodies never cross the wire just to draw a table
+    pub summary_only: bool,
+}
+
+/// Sort and page a listing. Works over both full sessions and index
+/// entries via the field extractor, which yields (title, created_at,
+/// last_active). Returns the requested page plus the total count
+/// before paging, so callers can render "X of N".
+pub fn apply_list_options<T>(
+    mut items: Vec<T>,
+    options: &ListOptions,
+    fields: impl Fn(&T) -> (Option<String>, DateTime<Utc>, DateTime<Utc>),
+) -> (Vec<T>, usize) {
+    match options.sort {
+        SessionSort::LastActive => {
+            items.sort_by_key(|item| std::cmp::Reverse(fields(item).2));
+        }
+        SessionSort::Created => {
+            items.sort_by_key(|item| fields(item).1);
+        }
+        SessionSort::Title => {
+            // Untitled sessions go last, newest activity first; titled
+            // ones compare case-insensitively
+            items.sort_by(|a, b| {
+                let (title_a, _, active_a) = fields(a);
+                let (title_b, _, active_b) = fields(b);
+                match (title_a, title_b) {
+                    (Some(a), Some(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => active_b.cmp(&active_a),
+                }
+            });
+        }
+    }
+
+    let total = items.len();
+    let page: Vec<T> = items
+        .into_iter()
+        .skip(options.offset)
+        .take(options.limit.unwrap_or(usize::MAX))
+        .collect();
+    (page, total)
+}
+
+/// Render a timestamp relative to `now` for listings: "just now",
+/// "5m ago", "3h ago", "2d ago", then the plain date once it is more
+/// than a month old
+pub fn format_relative(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - then).num_seconds();
+    match seconds {
+        i64::MIN..=59 => "just now".to_string(),
+        60..=3_599 => format!("{}m ago", seconds / 60),
+        3_600..=86_399 => format!("{}h ago", seconds / 3_600),
+        86_400..=2_591_999 => format!("{}d ago", seconds / 86_400),
+        _ => then.format("%Y-%m-%d").to_string(),
+    }
+}
+
+/// Which slice of a conversation `gos show` wants. All criteria are
+/// optional and combine: range and role narrow first, then the grep
+/// pattern, then `last` keeps only the newest survivors.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MessageFilter {
+    /// Only messages with this role ("user" or "assistant")
+    pub role: Option<String>,
+    /// Only messages whose text matches this regular expression
+    pub grep: Option<String>,
+    /// Only messages with stored indices in this half-open range
+    pub range: Option<(usize, usize)>,
+    /// Keep only the last N messages after the other criteria
+    pub last: Option<usize>,
+}
+
+impl MessageFilter {
+    /// Whether the filter would pass everything through unchanged
+    pub fn is_empty(&self) -> bool {
+        self.role.is_none() && self.grep.is_none() && self.range.is_none() && self.last.is_none()
+    }
+}
+
+/// Apply a filter to a conversation, returning the surviving messages
+/// with their absolute stored indices. Fails only on an invalid grep
+/// pattern.
+pub fn filter_messages(messages: &[ChatMessage], filter: &MessageFilter) -> Result<Vec<(usize, ChatMessage)>> {
+    let pattern = filter
+        .grep
+        .as_deref()
+        .map(regex::Regex::new)
+        .transpose()
+        .map_err(|e| GraphOsError::Config(format!("Invalid grep pattern: {}", e)))?;
+
+    let mut kept: Vec<(usize, ChatMessage)> = messages
+        .iter()
+        .enumerate()
+        .filter(|(index, message)| {
+            if let Some((start, end)) = filter.range
+                && (*index < start || *index >= end)
+            {
+                return false;
+            }
+            if let Some(role) = &filter.role
+                && message.role() != role
+            {
+                return false;
+            }
+            if let Some(pattern) = &pattern
+                && !pattern.is_match(message.text())
+            {
+                return false;
+            }
+            true
+        })
+        .map(|(index, message)| (index, message.clone()))
+        .collect();
+
+    if let Some(last) = filter.last {
+        let skip = kept.len().saturating_sub(last);
+        kept.drain(..skip);
+    }
+
+    Ok(kept)
+}
+
+/// Whether `a` is a prefix of `b`, judged by role and text only:
+/// message metadata (timestamps, providers) legitimately differs
+/// between the two copies of a split conversation
+pub fn messages_prefix_of(a: &[ChatMessage], b: &[ChatMessage]) -> bool {
+    a.len() <= b.len()
+        && a.iter()
+            .zip(b.iter())
+            .all(|(x, y)| x.role() == y.role() && x.text() == y.text())
+}
+
+/// Find near-duplicate sessions: pairs where one conversation is a
+/// prefix of another, as left behind when a fallback write forks the
+/// history. Returns (duplicate, survivor) ids, the survivor being the
+/// longest superset. Deliberate forks are not duplicates, and neither
+/// are empty sessions (every empty session is a prefix of everything).
+pub fn find_duplicate_sessions(sessions: &[Session]) -> Vec<(Uuid, Uuid)> {
+    let mut pairs = Vec::new();
+    for a in sessions {
+        if a.messages.is_empty() {
+            continue;
+        }
+        let mut survivor: Option<&Session> = None;
+        for b in sessions {
+            if a.id == b.id
+                || a.parent_id == Some(b.id)
+                || b.parent_id == Some(a.id)
+                || !messages_prefix_of(&a.messages, &b.messages)
+            {
+                continue;
+            }
+            // Identical conversations tie; keep the one with the more
+            // recent activity (id as a deterministic tie-breaker) so
+            // only one of the pair is reported as the duplicate
+            if a.messages.len() == b.messages.len()
+                && (a.last_active, a.id) > (b.last_active, b.id)
+            {
+                continue;
+            }
+            if survivor.is_none_or(|s| b.messages.len() > s.messages.len()) {
+                survivor = Some(b);
+            }
+        }
+        if let Some(survivor) = survivor {
+            pairs.push((a.id, survivor.id));
+        }
+    }
+    pairs
+}
+
+/// Fold a duplicate's metadata into the surviving session before the
+/// duplicate is removed: bookmarks and tags union, the creation time
+/// goes back to the earlier copy, and header fields the survivor lacks
+/// are taken from the duplicate. Messages stay the survivor's — it is
+/// the superset.
+pub fn merge_duplicate(survivor: &mut Session, duplicate: &Session) {
+    for &bookmark in &duplicate.bookmarks {
+        if !survivor.bookmarks.contains(&bookmark) {
+            survivor.bookmarks.push(bookmark);
+        }
+    }
+    survivor.bookmarks.sort_unstable();
+    for tag in &duplicate.tags {
+        if !survivor.tags.contains(tag) {
+            survivor.tags.push(tag.clone());
+        }
+    }
+    survivor.created_at = survivor.created_at.min(duplicate.created_at);
+    if survivor.title.is_none() {
+        survivor.title = duplicate.title.clone();
+    }
+    if survivor.system_prompt.is_none() {
+        survivor.system_prompt = duplicate.system_prompt.clone();
+    }
+    if survivor.provider.is_none() {
+        survivor.provider = duplicate.provider.clone();
+    }
+}
+
+/// Journal of session ids written locally while no listener was
+/// reachable, replayed by [`SessionManager::replay_journal`]
+fn journal_path(sessions_dir: &Path) -> PathBuf {
+    sessions_dir.join("replay.journal")
+}
+
+/// Append a session id to the replay journal. Appending twice is fine:
+/// replay dedupes. A failed append only costs the replay, never the
+/// local save itself, so it is logged rather than propagated.
+async fn journal_local_write(sessions_dir: &Path, id: Uuid) {
+    let path = journal_path(sessions_dir);
+    let result = async {
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(&path).await?;
+        file.write_all(format!("{}\n", id).as_bytes()).await?;
+        file.flush().await
+    }
+    .await;
+    if let Err(e) = result {
+        eprintln!("Failed to journal session {} for replay: {}", id, e);
+    }
+}
+
+/// Clamp a page request to a conversation of `total` messages, returning
+/// the half-open range to return. An offset past the end yields an empty
+/// page rather than an error so clients can probe the length.
+pub fn page_bounds(total: usize, offset: usize, limit: usize) -> (usize, usize) {
+    let start = offset.min(total);
+    let end = start.saturating_add(limit).min(total);
+    (start, end)
 }
 
 #[derive(Debug)]
 pub struct SessionManager {
     sessions_dir: PathBuf,
-    is_listener: bool,
+    /// Whether this process currently serves other instances; flips to
+    /// true if we win an election after the original listener dies
+    is_listener: AtomicBool,
+    /// Session bodies, populated lazily: a session enters this map when
+    /// it is created, updated, or first requested, not at startup
     sessions: Arc<Mutex<HashMap<Uuid, Session>>>,
+    /// Eagerly-loaded headers for every session on disk
+    index: Arc<Mutex<HashMap<Uuid, SessionIndexEntry>>>,
+    /// Which sessions changed since their last write; consulted by the
+    /// autosave sweep so clean sessions are never rewritten
+    dirty: Arc<Mutex<DirtyTracker>>,
+    /// Cipher for session files at rest, if a passphrase is configured
+    cipher: Option<Arc<SessionCipher>>,
+    /// Exclusive write leases, authoritative only while this process is
+    /// the listener (clients go through the command protocol)
+    leases: Arc<Mutex<LeaseTable>>,
+    /// Live-tail subscribers, authoritative only while this process is
+    /// the listener (clients subscribe through the command protocol)
+    watchers: Watchers,
 }
 
 impl SessionManager {
@@ -59,33 +903,33 @@ impl SessionManager {
             return Ok(manager.clone());
         }
 
-        // Ensure ~/.vibe directory exists
-        let home_dir = dirs::home_dir().context("Could not determine home directory")?;
-        let sessions_dir = home_dir.join(".vibe");
+        // Ensure the sessions directory exists
+        let sessions_dir = crate::paths::sessions_dir();
         fs::create_dir_all(&sessions_dir).await?;
 
-        // Try connecting to existing listener
-        println!("Trying to connect to existing listener on port {}", VIBE_PORT);
-        let is_listener = match TcpStream::connect(format!("127.0.0.1:{}", VIBE_PORT)).await {
+        // Try connecting to an existing listener; quiet either way so
+        // startup never writes over a TUI frame
+        let is_listener = match transport::connect().await {
             Ok(stream) => {
                 // Listener exists, we're a client
-                println!("Connected to existing listener, we're a client");
                 drop(stream);
                 false
             }
-            Err(e) => {
-                // No listener, we'll become the listener
-                println!("Could not connect to listener: {}, becoming the listener", e);
-                true
-            }
+            // No listener, we'll become the listener
+            Err(_) => true,
         };
 
         let sessions = Arc::new(Mutex::new(HashMap::new()));
 
         let manager = Arc::new(SessionManager {
             sessions_dir,
-            is_listener,
+            is_listener: AtomicBool::new(is_listener),
             sessions,
+            index: Arc::new(Mutex::new(HashMap::new())),
+            dirty: Arc::new(Mutex::new(DirtyTracker::default())),
+            cipher: SessionCipher::from_env().map(Arc::new),
+            leases: Arc::new(Mutex::new(LeaseTable::default())),
+            watchers: Arc::new(Mutex::new(HashMap::new())),
         });
 
         if is_listener {
@@ -95,74 +939,248 @@ impl SessionManager {
                 if let Err(e) = manager_clone.load_sessions().await {
                     eprintln!("Failed to load sessions: {}", e);
                 }
-                
+
                 // Start listener service
                 if let Err(e) = manager_clone.run_listener().await {
                     eprintln!("Listener service failed: {}", e);
                 }
             });
+        } else {
+            // A listener is up: push any writes journaled while one was
+            // not, so fallback saves reconcile instead of forking.
+            // Quiet on success for the same reason as above.
+            let manager_clone = manager.clone();
+            tokio::spawn(async move {
+                if let Err(e) = manager_clone.replay_journal().await {
+                    eprintln!("Failed to replay journaled session writes: {}", e);
+                }
+            });
         }
 
         SESSION_MANAGER.set(manager.clone()).unwrap();
         Ok(manager)
     }
 
+    /// Run as the dedicated session listener in the foreground until a
+    /// Shutdown command arrives. Used by `gos daemon run`.
+    pub async fn run_daemon() -> Result<()> {
+        let sessions_dir = crate::paths::sessions_dir();
+        fs::create_dir_all(&sessions_dir).await?;
+
+        let manager = SessionManager {
+            sessions_dir,
+            is_listener: AtomicBool::new(true),
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            index: Arc::new(Mutex::new(HashMap::new())),
+            dirty: Arc::new(Mutex::new(DirtyTracker::default())),
+            cipher: SessionCipher::from_env().map(Arc::new),
+            leases: Arc::new(Mutex::new(LeaseTable::default())),
+            watchers: Arc::new(Mutex::new(HashMap::new())),
+        };
+
+        manager.load_sessions().await?;
+        manager.run_listener().await
+    }
+
+    /// Populate the eager index, reading session bodies only where the
+    /// index cannot vouch for a file: missing entries, or files written
+    /// after the index was (a crashed listener's autosaves). Bodies the
+    /// index covers stay on disk until someone requests them.
     async fn load_sessions(&self) -> Result<()> {
-        let mut entries = fs::read_dir(&self.sessions_dir).await?;
-        let mut sessions = self.sessions.lock().await;
+        let indexed = read_index(&self.sessions_dir, self.cipher.as_deref()).await;
+        let (indexed, index_mtime) = match indexed {
+            Some((entries, mtime)) => {
+                let map: HashMap<Uuid, SessionIndexEntry> =
+                    entries.into_iter().map(|entry| (entry.id, entry)).collect();
+                (map, Some(mtime))
+            }
+            None => (HashMap::new(), None),
+        };
 
+        // Index entries are only kept for files that still exist, so a
+        // session deleted out from under us disappears from listings
+        let mut index = HashMap::new();
+        let mut to_scan = Vec::new();
+        let mut entries = fs::read_dir(&self.sessions_dir).await?;
         while let Some(entry) = entries.next_entry().await? {
             let path = entry.path();
-            if path.extension().unwrap_or_default() == "json" {
-                let mut file = fs::File::open(&path).await?;
-                let mut contents = String::new();
-                file.read_to_string(&mut contents).await?;
-                
-                match serde_json::from_str::<Session>(&contents) {
-                    Ok(session) => {
-                        sessions.insert(session.id, session);
+            if path.extension().unwrap_or_default() != "json" {
+                continue;
+            }
+
+            let id = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<Uuid>().ok());
+            let mtime = entry.metadata().await.ok().and_then(|meta| meta.modified().ok());
+
+            if let (Some(id), Some(mtime), Some(index_mtime)) = (id, mtime, index_mtime)
+                && mtime <= index_mtime
+                && let Some(header) = indexed.get(&id)
+            {
+                index.insert(id, header.clone());
+            } else {
+                to_scan.push(path);
+            }
+        }
+
+        // Bounded concurrent scan of the files the index cannot cover
+        let total = to_scan.len();
+        let progress = total >= LOAD_PROGRESS_MIN;
+        let mut done = 0usize;
+        let mut loaded = Vec::new();
+        let cipher = self.cipher.clone();
+        let mut reads = futures_util::stream::iter(to_scan)
+            .map(|path| {
+                let cipher = cipher.clone();
+                async move {
+                    match fs::read(&path).await {
+                        Ok(contents) => {
+                            let encrypted = SessionCipher::is_encrypted(&contents);
+                            (path, Some(encrypted), decode_session(cipher.as_deref(), &contents))
+                        }
+                        Err(e) => (path, None, Err(e.into())),
                     }
-                    Err(e) => {
-                        eprintln!("Failed to parse session file {:?}: {}", path, e);
+                }
+            })
+            .buffer_unordered(LOAD_CONCURRENCY);
+
+        while let Some((path, encrypted, result)) = reads.next().await {
+            done += 1;
+            if progress {
+                eprint!("\rLoading sessions... {}/{}", done, total);
+            }
+            match (encrypted, result) {
+                (_, Ok(session)) => loaded.push(session),
+                // Only plaintext that fails to parse is quarantined, so
+                // a later autosave cannot overwrite the evidence
+                (Some(false), Err(e)) => {
+                    let quarantine = path.with_extension("json.corrupt");
+                    eprintln!(
+                        "{}Failed to parse session file {:?}: {}; quarantining as {:?}",
+                        if progress { "\n" } else { "" },
+                        path, e, quarantine
+                    );
+                    if let Err(rename_err) = fs::rename(&path, &quarantine).await {
+                        eprintln!("Failed to quarantine {:?}: {}", path, rename_err);
                     }
                 }
+                // An encrypted file that will not decrypt is not
+                // corrupt: the passphrase is missing or mistyped, and
+                // renaming the file away would turn that mistake into
+                // data loss. Leave it in place; it loads fine once the
+                // right passphrase is set.
+                (Some(true), Err(e)) => {
+                    eprintln!(
+                        "{}Skipping encrypted session file {:?}: {}",
+                        if progress { "\n" } else { "" },
+                        path, e
+                    );
+                }
+                // Likewise an unreadable file proves nothing about its
+                // contents; leave it for the next load to retry
+                (None, Err(e)) => {
+                    eprintln!(
+                        "{}Failed to read session file {:?}: {}",
+                        if progress { "\n" } else { "" },
+                        path, e
+                    );
+                }
             }
         }
+        if progress {
+            eprintln!("\rLoaded {} session(s)          ", total);
+        }
+
+        // Scanned bodies are already in memory, so keep them cached
+        let mut sessions = self.sessions.lock().await;
+        for session in loaded {
+            index.insert(session.id, SessionIndexEntry::from(&session));
+            sessions.insert(session.id, session);
+        }
+        drop(sessions);
+
+        let rewrite = total > 0 || index_mtime.is_none();
+        *self.index.lock().await = index.clone();
+        if rewrite {
+            write_index(&self.sessions_dir, self.cipher.as_deref(), &index).await?;
+        }
 
         Ok(())
     }
 
     async fn save_session(&self, session: &Session) -> Result<()> {
         let file_path = self.sessions_dir.join(format!("{}.json", session.id));
-        let json = serde_json::to_string_pretty(session)?;
-        
-        let mut file = fs::File::create(file_path).await?;
-        file.write_all(json.as_bytes()).await?;
-        
+        let contents = encode_session(self.cipher.as_deref(), session)?;
+        write_session_file(&self.sessions_dir, &file_path, contents).await?;
+        update_index(&self.index, &self.sessions_dir, self.cipher.as_deref(), session).await;
         Ok(())
     }
 
+    /// Whether this process is currently the session listener
+    pub fn is_listener(&self) -> bool {
+        self.is_listener.load(Ordering::SeqCst)
+    }
+
     async fn run_listener(&self) -> Result<()> {
-        let listener = TcpListener::bind(format!("127.0.0.1:{}", VIBE_PORT)).await?;
-        println!("Session listener started on port {}", VIBE_PORT);
+        let listener = transport::bind().await?;
+        println!("Session listener started on {}", transport::endpoint_name());
+
+        Self::serve(listener, self.sessions.clone(), self.index.clone(), self.sessions_dir.clone(), self.dirty.clone(), self.cipher.clone(), self.leases.clone(), self.watchers.clone()).await
+    }
 
+    /// Accept loop and autosave task of the listener, shared between the
+    /// startup path and post-election takeover
+    #[allow(clippy::too_many_arguments)]
+    async fn serve(
+        mut listener: transport::Listener,
+        sessions: Arc<Mutex<HashMap<Uuid, Session>>>,
+        index: Arc<Mutex<HashMap<Uuid, SessionIndexEntry>>>,
+        sessions_dir: PathBuf,
+        dirty: Arc<Mutex<DirtyTracker>>,
+        cipher: Option<Arc<SessionCipher>>,
+        leases: Arc<Mutex<LeaseTable>>,
+        watchers: Watchers,
+    ) -> Result<()> {
         let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
-        let sessions_clone = self.sessions.clone();
-        let sessions_dir_clone = self.sessions_dir.clone();
+        let sessions_clone = sessions.clone();
+        let sessions_dir_clone = sessions_dir.clone();
+        let dirty_clone = dirty.clone();
+        let cipher_clone = cipher.clone();
 
-        // Autosave task
+        // Autosave task: a safety net behind the synchronous writes on
+        // the command path. Only sessions that changed since their last
+        // write are rewritten, and sessions still taking rapid updates
+        // are left to settle until the next sweep.
         let autosave_shutdown = shutdown_tx.clone();
         tokio::spawn(async move {
+            let interval = autosave_interval();
             loop {
                 select! {
-                    _ = sleep(Duration::from_secs(30)) => {
-                        let sessions = sessions_clone.lock().await;
-                        for session in sessions.values() {
-                            let file_path = sessions_dir_clone.join(format!("{}.json", session.id));
-                            let json = serde_json::to_string_pretty(session).unwrap_or_default();
-                            
-                            if let Err(e) = fs::write(&file_path, json).await {
-                                eprintln!("Failed to autosave session {}: {}", session.id, e);
+                    _ = sleep(interval) => {
+                        let pending = dirty_clone.lock().await.dirty(AUTOSAVE_DEBOUNCE);
+                        for (id, version) in pending {
+                            let session = {
+                                let sessions = sessions_clone.lock().await;
+                                match sessions.get(&id) {
+                                    Some(session) => session.clone(),
+                                    None => continue,
+                                }
+                            };
+
+                            let file_path = sessions_dir_clone.join(format!("{}.json", id));
+                            let contents = match encode_session(cipher_clone.as_deref(), &session) {
+                                Ok(contents) => contents,
+                                Err(e) => {
+                                    eprintln!("Failed to serialize session {}: {}", id, e);
+                                    continue;
+                                }
+                            };
+
+                            match write_session_file(&sessions_dir_clone, &file_path, contents).await {
+                                Ok(()) => dirty_clone.lock().await.mark_saved(id, version),
+                                // Left dirty, so the next sweep retries
+                                Err(e) => eprintln!("Failed to autosave session {}: {}", id, e),
                             }
                         }
                     }
@@ -175,11 +1193,17 @@ impl SessionManager {
 
         loop {
             select! {
-                Ok((stream, _)) = listener.accept() => {
-                    let sessions_clone = self.sessions.clone();
-                    let sessions_dir_clone = self.sessions_dir.clone();
+                Ok(stream) = listener.accept() => {
+                    let sessions_clone = sessions.clone();
+                    let index_clone = index.clone();
+                    let sessions_dir_clone = sessions_dir.clone();
+                    let dirty_clone = dirty.clone();
+                    let cipher_clone = cipher.clone();
+                    let leases_clone = leases.clone();
+                    let watchers_clone = watchers.clone();
+                    let shutdown_clone = shutdown_tx.clone();
                     tokio::spawn(async move {
-                        if let Err(e) = handle_client(stream, sessions_clone, sessions_dir_clone).await {
+                        if let Err(e) = handle_client(stream, sessions_clone, index_clone, sessions_dir_clone, dirty_clone, cipher_clone, leases_clone, watchers_clone, shutdown_clone).await {
                             eprintln!("Error handling client: {}", e);
                         }
                     });
@@ -193,274 +1217,1274 @@ impl SessionManager {
         Ok(())
     }
 
-    pub async fn get_or_create_session(&self) -> Result<Uuid> {
-        if self.is_listener {
-            // If we're the listener, create a new session directly
-            let session_id = Uuid::new_v4();
-            let session = Session {
-                id: session_id,
-                created_at: Utc::now(),
-                last_active: Utc::now(),
-                messages: Vec::new(),
-            };
-            
-            let mut sessions = self.sessions.lock().await;
-            sessions.insert(session_id, session.clone());
-            drop(sessions);
-            
-            self.save_session(&session).await?;
-            
-            Ok(session_id)
-        } else {
-            // If we're a client, send command to the listener
-            println!("Sending GetOrCreateSession command to listener");
-            let mut stream = match TcpStream::connect(format!("127.0.0.1:{}", VIBE_PORT)).await {
-                Ok(stream) => stream,
-                Err(e) => {
-                    // If we can't connect, we might need to become the listener
-                    println!("Failed to connect to listener: {}", e);
-                    println!("Creating new session locally");
-                    
-                    // Create new session locally
-                    let session_id = Uuid::new_v4();
-                    let session = Session {
-                        id: session_id,
-                        created_at: Utc::now(),
-                        last_active: Utc::now(),
-                        messages: Vec::new(),
-                    };
-                    
-                    let mut sessions = self.sessions.lock().await;
-                    sessions.insert(session_id, session.clone());
-                    drop(sessions);
-                    
-                    return Ok(session_id);
-                }
+    /// Send one command to the listener with a deadline on every phase,
+    /// so a dead or wedged listener can never hang a caller. Speaks the
+    /// framed protocol first — a legacy listener reads bare JSON with a
+    /// single fixed-size buffer, which truncates any command of real
+    /// size — and repeats the command unframed only when the listener
+    /// answers that way. Failures come back classified: refused or
+    /// reset connections as Transport, blown deadlines as Timeout, and
+    /// a malformed answer as Decode, letting callers decide whether a
+    /// retry or local fallback is safe.
+    async fn send_command(&self, command: &SessionCommand) -> Result<SessionResponse> {
+        let mut stream = match timeout(HEARTBEAT_TIMEOUT, transport::connect()).await {
+            Ok(Ok(stream)) => stream,
+            Ok(Err(e)) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+                return Err(GraphOsError::Transport(format!("Listener refused the connection: {}", e)));
+            }
+            Ok(Err(e)) => return Err(GraphOsError::Transport(format!("Failed to connect to listener: {}", e))),
+            Err(_) => return Err(GraphOsError::Timeout("Timed out connecting to listener".to_string())),
+        };
+
+        let command_json = serde_json::to_string(command)?;
+
+        // One deadline covers the write and the first response byte
+        // together: a full socket buffer can wedge the write just as a
+        // silent listener wedges the read
+        let framed = async {
+            write_framed_message(&mut stream, PROTOCOL_VERSION, command_json.as_bytes()).await?;
+            let mut first = [0u8; 1];
+            stream.read_exact(&mut first).await?;
+            Ok::<_, GraphOsError>(first[0])
+        };
+        let first = match timeout(RESPONSE_TIMEOUT, framed).await {
+            Ok(Ok(first)) => first,
+            Ok(Err(e)) => return Err(GraphOsError::Transport(format!("Error reading response: {}", e))),
+            Err(_) => return Err(GraphOsError::Timeout("Listener did not answer within the deadline".to_string())),
+        };
+
+        if first == FRAME_MAGIC {
+            let buffer = match timeout(RESPONSE_TIMEOUT, read_framed_message(&mut stream)).await {
+                Ok(Ok((_version, buffer))) => buffer,
+                Ok(Err(e)) => return Err(GraphOsError::Transport(format!("Error reading response: {}", e))),
+                Err(_) => return Err(GraphOsError::Timeout("Listener did not answer within the deadline".to_string())),
             };
-            
-            let command = SessionCommand::GetOrCreateSession;
-            let command_json = serde_json::to_string(&command)?;
-            
-            println!("Writing command to stream");
+            return Ok(serde_json::from_slice(&buffer)?);
+        }
+
+        // A legacy listener answered our framed bytes with a bare JSON
+        // parse error. Drain it and repeat the command the old way on a
+        // fresh connection, which such a listener still understands.
+        let legacy = async {
+            let mut drain = [0u8; 4096];
+            let _ = stream.read(&mut drain).await;
+            drop(stream);
+
+            let mut stream = transport::connect().await?;
             stream.write_all(command_json.as_bytes()).await?;
             stream.write_all(b"\n").await?;
             stream.flush().await?;
-            
-            // Use a timeout for reading to avoid hanging
-            let read_future = async {
-                let mut buffer = [0u8; 1024];
-                let n = stream.read(&mut buffer).await?;
-                Ok::<_, anyhow::Error>(String::from_utf8_lossy(&buffer[..n]).to_string())
-            };
-            
-            let response = match timeout(Duration::from_secs(5), read_future).await {
-                Ok(Ok(response)) => response,
-                Ok(Err(e)) => {
-                    println!("Error reading from stream: {}", e);
-                    anyhow::bail!("Error reading response: {}", e);
+
+            let mut buffer = Vec::new();
+            stream.read_to_end(&mut buffer).await?;
+            Ok::<_, GraphOsError>(buffer)
+        };
+        let buffer = match timeout(RESPONSE_TIMEOUT, legacy).await {
+            Ok(Ok(buffer)) => buffer,
+            Ok(Err(e)) => return Err(GraphOsError::Transport(format!("Error reading response: {}", e))),
+            Err(_) => return Err(GraphOsError::Timeout("Listener did not answer within the deadline".to_string())),
+        };
+
+        Ok(serde_json::from_slice(&buffer)?)
+    }
+
+    /// Send a command, holding an election when the listener is gone.
+    /// Returns Ok(None) when this process won the election and is now
+    /// the listener itself; the caller should run the operation locally.
+    async fn send_command_failover(&self, command: &SessionCommand) -> Result<Option<SessionResponse>> {
+        let mut last_err = match self.send_command(command).await {
+            Ok(response) => return Ok(Some(response)),
+            Err(e) if e.is_transient() => {
+                println!("Listener unreachable ({}); holding election", e);
+                e
+            }
+            Err(e) => return Err(e),
+        };
+
+        for _ in 0..ELECTION_RETRIES {
+            if self.try_become_listener().await {
+                return Ok(None);
+            }
+
+            // Another process won (or the old listener recovered); give
+            // the winner a moment to come up, then retry against it
+            sleep(Duration::from_millis(200)).await;
+            match self.send_command(command).await {
+                Ok(response) => return Ok(Some(response)),
+                Err(e) if e.is_transient() => last_err = e,
+                Err(e) => return Err(e),
+            }
+        }
+
+        // Surface the classified failure (refused vs timed out) rather
+        // than a generic session error, so callers can tell a wedged
+        // listener from a missing one and fall back where that is safe
+        Err(last_err)
+    }
+
+    /// Race to bind the listener endpoint, which doubles as the election
+    /// lock: binding succeeds for exactly one process. The winner
+    /// reloads sessions from disk (the dead listener's autosaves) before
+    /// serving, so nothing goes missing across the takeover.
+    async fn try_become_listener(&self) -> bool {
+        let listener = match transport::bind().await {
+            Ok(listener) => listener,
+            // Lost the race, or the old listener still holds the endpoint
+            Err(_) => return false,
+        };
+
+        println!("Won listener election; taking over on {}", transport::endpoint_name());
+
+        if let Err(e) = self.load_sessions().await {
+            eprintln!("Failed to reload sessions after takeover: {}", e);
+        }
+
+        self.is_listener.store(true, Ordering::SeqCst);
+
+        let sessions = self.sessions.clone();
+        let index = self.index.clone();
+        let sessions_dir = self.sessions_dir.clone();
+        let dirty = self.dirty.clone();
+        let cipher = self.cipher.clone();
+        let leases = self.leases.clone();
+        let watchers = self.watchers.clone();
+        tokio::spawn(async move {
+            if let Err(e) = Self::serve(listener, sessions, index, sessions_dir, dirty, cipher, leases, watchers).await {
+                eprintln!("Listener service failed after takeover: {}", e);
+            }
+        });
+
+        true
+    }
+
+    pub async fn get_or_create_session(&self) -> Result<Uuid> {
+        if !self.is_listener()
+            && let Some(response) = self.send_command_failover(&SessionCommand::GetOrCreateSession).await?
+        {
+            return match response {
+                SessionResponse::Session(session) => Ok(session.id),
+                SessionResponse::Error(err) => Err(GraphOsError::Session(err)),
+                _ => Err(GraphOsError::Decode("Unexpected response from session manager".to_string())),
+            };
+        }
+        // Fell through: either we are the listener, or we just won the
+        // election and should run the operation locally
+
+        let session_id = Uuid::new_v4();
+        let session = Session::new(session_id);
+
+        let mut sessions = self.sessions.lock().await;
+        sessions.insert(session_id, session.clone());
+        drop(sessions);
+
+        // A failed write leaves the session dirty for the autosave sweep
+        let version = self.dirty.lock().await.touch(session_id);
+        self.save_session(&session).await?;
+        self.dirty.lock().await.mark_saved(session_id, version);
+
+        Ok(session_id)
+    }
+
+    /// Index entries straight from the index file on disk, for reads
+    /// that have to outlive every listener. A missing or unreadable
+    /// file just yields an empty listing.
+    async fn read_index_from_disk(&self) -> Vec<SessionIndexEntry> {
+        match read_index(&self.sessions_dir, self.cipher.as_deref()).await {
+            Some((entries, _)) => entries,
+            None => Vec::new(),
+        }
+    }
+
+    pub async fn list_sessions(&self) -> Result<Vec<Session>> {
+        if !self.is_listener() {
+            match self.send_command_failover(&SessionCommand::ListSessions).await {
+                Ok(Some(response)) => {
+                    return match response {
+                        SessionResponse::Sessions(sessions) => Ok(sessions),
+                        SessionResponse::Error(err) => Err(GraphOsError::Session(err)),
+                        _ => Err(GraphOsError::Decode("Unexpected response from session manager".to_string())),
+                    };
+                }
+                // Won the election; serve the read locally below
+                Ok(None) => {}
+                // Reads fall back to the files on disk, like get_session
+                Err(e) if e.is_transient() => {
+                    eprintln!("Session listener unavailable ({}); reading session files directly", e);
+                    let mut all = Vec::new();
+                    for entry in self.read_index_from_disk().await {
+                        if let Some(session) =
+                            fetch_session(&self.sessions, &self.sessions_dir, self.cipher.as_deref(), entry.id).await?
+                        {
+                            all.push(session);
+                        }
+                    }
+                    return Ok(all);
                 }
-                Err(_) => {
-                    println!("Timeout reading from stream");
-                    anyhow::bail!("Timeout reading response");
+                Err(e) => return Err(e),
+            }
+        }
+
+        fetch_all_sessions(&self.sessions, &self.sessions_dir, self.cipher.as_deref(), &self.index).await
+    }
+
+    /// Session headers from the eager index, without reading any
+    /// conversation files. The cheap way to list sessions.
+    pub async fn list_index(&self) -> Result<Vec<SessionIndexEntry>> {
+        if !self.is_listener() {
+            match self.send_command_failover(&SessionCommand::ListIndex).await {
+                Ok(Some(response)) => {
+                    return match response {
+                        SessionResponse::Index(entries) => Ok(entries),
+                        SessionResponse::Error(err) => Err(GraphOsError::Session(err)),
+                        _ => Err(GraphOsError::Decode("Unexpected response from session manager".to_string())),
+                    };
                 }
-            };
-            
-            println!("Got response: {}", response);
-            
-            let session_response: SessionResponse = serde_json::from_str(&response)?;
-            
-            match session_response {
-                SessionResponse::Session(session) => Ok(session.id),
-                SessionResponse::Error(err) => anyhow::bail!("Session error: {}", err),
-                _ => anyhow::bail!("Unexpected response from session manager"),
+                // Won the election; serve the read locally below
+                Ok(None) => {}
+                // Reads fall back to the index file, like get_session
+                Err(e) if e.is_transient() => {
+                    eprintln!("Session listener unavailable ({}); reading the index file directly", e);
+                    return Ok(self.read_index_from_disk().await);
+                }
+                Err(e) => return Err(e),
             }
         }
+
+        let index = self.index.lock().await;
+        Ok(index.values().cloned().collect())
     }
-    
-    pub async fn list_sessions(&self) -> Result<Vec<Session>> {
-        if self.is_listener {
-            // If we're the listener, get sessions directly
-            let sessions = self.sessions.lock().await;
-            let session_list = sessions.values().cloned().collect();
-            Ok(session_list)
-        } else {
-            // If we're a client, send command to the listener
-            let mut stream = TcpStream::connect(format!("127.0.0.1:{}", VIBE_PORT)).await?;
-            
-            let command = SessionCommand::ListSessions;
-            let command_json = serde_json::to_string(&command)?;
-            
-            stream.write_all(command_json.as_bytes()).await?;
-            stream.write_all(b"\n").await?;
-            
-            let mut response = String::new();
-            stream.read_to_string(&mut response).await?;
-            
-            let session_response: SessionResponse = serde_json::from_str(&response)?;
-            
-            match session_response {
-                SessionResponse::Sessions(sessions) => Ok(sessions),
-                SessionResponse::Error(err) => anyhow::bail!("Session error: {}", err),
-                _ => anyhow::bail!("Unexpected response from session manager"),
+
+    /// One sorted page of session headers plus the total session count,
+    /// for `gos list`. Message bodies never leave the listener.
+    pub async fn list_index_page(&self, options: ListOptions) -> Result<(Vec<SessionIndexEntry>, usize)> {
+        let options = ListOptions { summary_only: true, ..options };
+        if !self.is_listener() {
+            match self.send_command_failover(&SessionCommand::ListSessionsPage { options: options.clone() }).await {
+                Ok(Some(response)) => {
+                    return match response {
+                        SessionResponse::IndexPage { entries, total } => Ok((entries, total)),
+                        SessionResponse::Error(err) => Err(GraphOsError::Session(err)),
+                        _ => Err(GraphOsError::Decode("Unexpected response from session manager".to_string())),
+                    };
+                }
+                // Won the election; serve the read locally below
+                Ok(None) => {}
+                // Reads fall back to the index file, like list_index
+                Err(e) if e.is_transient() => {
+                    eprintln!("Session listener unavailable ({}); reading the index file directly", e);
+                    let entries = self.read_index_from_disk().await;
+                    return Ok(apply_list_options(entries, &options, |entry| {
+                        (entry.title.clone(), entry.created_at, entry.last_active)
+                    }));
+                }
+                Err(e) => return Err(e),
             }
         }
+
+        let entries: Vec<SessionIndexEntry> = self.index.lock().await.values().cloned().collect();
+        Ok(apply_list_options(entries, &options, |entry| {
+            (entry.title.clone(), entry.created_at, entry.last_active)
+        }))
     }
-    
+
+    /// Id of the most recently active session, judged by the index's
+    /// last_active timestamps. The query behind `--continue`.
+    pub async fn most_recent_session(&self) -> Result<Option<Uuid>> {
+        let entries = self.list_index().await?;
+        Ok(entries.into_iter().max_by_key(|entry| entry.last_active).map(|entry| entry.id))
+    }
+
     pub async fn get_session(&self, id: Uuid) -> Result<Option<Session>> {
-        if self.is_listener {
-            // If we're the listener, get session directly
-            let sessions = self.sessions.lock().await;
-            Ok(sessions.get(&id).cloned())
-        } else {
-            // If we're a client, send command to the listener
-            let mut stream = TcpStream::connect(format!("127.0.0.1:{}", VIBE_PORT)).await?;
-            
-            let command = SessionCommand::GetSession(id);
-            let command_json = serde_json::to_string(&command)?;
-            
-            stream.write_all(command_json.as_bytes()).await?;
-            stream.write_all(b"\n").await?;
-            
-            let mut response = String::new();
-            stream.read_to_string(&mut response).await?;
-            
-            let session_response: SessionResponse = serde_json::from_str(&response)?;
-            
-            match session_response {
-                SessionResponse::Session(session) => Ok(Some(session)),
-                SessionResponse::Error(_) => Ok(None),
-                _ => anyhow::bail!("Unexpected response from session manager"),
+        if !self.is_listener() {
+            match self.send_command_failover(&SessionCommand::GetSession(id)).await {
+                Ok(Some(response)) => {
+                    return match response {
+                        SessionResponse::Session(session) => Ok(Some(session)),
+                        SessionResponse::Error(_) => Ok(None),
+                        _ => Err(GraphOsError::Decode("Unexpected response from session manager".to_string())),
+                    };
+                }
+                // Won the election; serve the read locally below
+                Ok(None) => {}
+                // A read is safe to serve straight from disk when no
+                // listener can be reached: worst case it is slightly
+                // stale. Writes never take this fallback.
+                Err(e) if e.is_transient() => {
+                    eprintln!("Session listener unavailable ({}); reading session files directly", e);
+                }
+                Err(e) => return Err(e),
             }
         }
+
+        fetch_session(&self.sessions, &self.sessions_dir, self.cipher.as_deref(), id).await
+    }
+
+    /// Session header without its conversation, plus how many messages
+    /// are stored. Lets a client size its first page request without
+    /// shipping a long conversation over the socket.
+    pub async fn get_session_meta(&self, id: Uuid) -> Result<Option<(Session, usize)>> {
+        if !self.is_listener() {
+            match self.send_command_failover(&SessionCommand::GetSessionMeta(id)).await {
+                Ok(Some(response)) => {
+                    return match response {
+                        SessionResponse::Meta { session, total } => Ok(Some((session, total))),
+                        SessionResponse::Error(_) => Ok(None),
+                        _ => Err(GraphOsError::Decode("Unexpected response from session manager".to_string())),
+                    };
+                }
+                // Won the election; serve the read locally below
+                Ok(None) => {}
+                // Reads fall back to the files on disk, like get_session
+                Err(e) if e.is_transient() => {
+                    eprintln!("Session listener unavailable ({}); reading session files directly", e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let session = fetch_session(&self.sessions, &self.sessions_dir, self.cipher.as_deref(), id).await?;
+        Ok(session.map(|mut session| {
+            let total = session.messages.len();
+            session.messages = Vec::new();
+            (session, total)
+        }))
+    }
+
+    /// One page of a session's conversation: up to `limit` messages
+    /// starting at `offset`, plus the total number stored. An offset past
+    /// the end returns an empty page, so `limit: 0` probes the length.
+    pub async fn get_session_messages(
+        &self,
+        id: Uuid,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Option<(Vec<ChatMessage>, usize)>> {
+        if !self.is_listener() {
+            match self
+                .send_command_failover(&SessionCommand::GetSessionMessages { id, offset, limit })
+                .await
+            {
+                Ok(Some(response)) => {
+                    return match response {
+                        SessionResponse::Messages { messages, total } => Ok(Some((messages, total))),
+                        SessionResponse::Error(_) => Ok(None),
+                        _ => Err(GraphOsError::Decode("Unexpected response from session manager".to_string())),
+                    };
+                }
+                // Won the election; serve the read locally below
+                Ok(None) => {}
+                // Reads fall back to the files on disk, like get_session
+                Err(e) if e.is_transient() => {
+                    eprintln!("Session listener unavailable ({}); reading session files directly", e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let session = fetch_session(&self.sessions, &self.sessions_dir, self.cipher.as_deref(), id).await?;
+        Ok(session.map(|session| {
+            let total = session.messages.len();
+            let (start, end) = page_bounds(total, offset, limit);
+            (session.messages[start..end].to_vec(), total)
+        }))
+    }
+
+    /// A filtered view of a session's conversation, applied by the
+    /// listener so only the surviving messages cross the socket. Each
+    /// message comes with its absolute stored index.
+    pub async fn get_filtered_messages(
+        &self,
+        id: Uuid,
+        filter: &MessageFilter,
+    ) -> Result<Option<(Vec<(usize, ChatMessage)>, usize)>> {
+        if !self.is_listener() {
+            match self
+                .send_command_failover(&SessionCommand::FilterMessages { id, filter: filter.clone() })
+                .await
+            {
+                Ok(Some(response)) => {
+                    return match response {
+                        SessionResponse::IndexedMessages { messages, total } => Ok(Some((messages, total))),
+                        SessionResponse::Error(err) if err.starts_with("Session not found") => Ok(None),
+                        SessionResponse::Error(err) => Err(GraphOsError::Session(err)),
+                        _ => Err(GraphOsError::Decode("Unexpected response from session manager".to_string())),
+                    };
+                }
+                // Won the election; serve the read locally below
+                Ok(None) => {}
+                // Reads fall back to the files on disk, like get_session
+                Err(e) if e.is_transient() => {
+                    eprintln!("Session listener unavailable ({}); reading session files directly", e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let session = fetch_session(&self.sessions, &self.sessions_dir, self.cipher.as_deref(), id).await?;
+        match session {
+            Some(session) => {
+                let total = session.messages.len();
+                Ok(Some((filter_messages(&session.messages, filter)?, total)))
+            }
+            None => Ok(None),
+        }
     }
 
     pub async fn update_session(&self, session: Session) -> Result<()> {
-        if self.is_listener {
-            // If we're the listener, update directly
-            let mut sessions = self.sessions.lock().await;
-            sessions.insert(session.id, session.clone());
-            drop(sessions);
-            
-            self.save_session(&session).await?;
-            
-            Ok(())
-        } else {
-            // If we're a client, send command to the listener
-            let mut stream = TcpStream::connect(format!("127.0.0.1:{}", VIBE_PORT)).await?;
-            
-            let command = SessionCommand::UpdateSession(session);
-            let command_json = serde_json::to_string(&command)?;
-            
-            stream.write_all(command_json.as_bytes()).await?;
-            stream.write_all(b"\n").await?;
-            
-            let mut response = String::new();
-            stream.read_to_string(&mut response).await?;
-            
-            let session_response: SessionResponse = serde_json::from_str(&response)?;
-            
-            match session_response {
-                SessionResponse::Session(_) => Ok(()),
-                SessionResponse::Error(err) => anyhow::bail!("Session error: {}", err),
-                _ => anyhow::bail!("Unexpected response from session manager"),
+        if !self.is_listener() {
+            match self.send_command_failover(&SessionCommand::UpdateSession(Box::new(session.clone()))).await {
+                Ok(Some(response)) => {
+                    return match response {
+                        SessionResponse::Session(_) => Ok(()),
+                        SessionResponse::Error(err) => Err(GraphOsError::Session(err)),
+                        _ => Err(GraphOsError::Decode("Unexpected response from session manager".to_string())),
+                    };
+                }
+                // Won the election; apply the write locally below
+                Ok(None) => {}
+                // The listener may still hold this session in memory
+                // and write its stale copy back later, forking the
+                // history. Save locally anyway — losing the message is
+                // worse — but journal the id so the write replays to
+                // the next listener we reach.
+                Err(e) if e.is_transient() => {
+                    eprintln!("Session listener unavailable ({}); saving locally and journaling for replay", e);
+                    journal_local_write(&self.sessions_dir, session.id).await;
+                }
+                Err(e) => return Err(e),
             }
         }
+
+        let mut sessions = self.sessions.lock().await;
+        sessions.insert(session.id, session.clone());
+        drop(sessions);
+
+        let version = self.dirty.lock().await.touch(session.id);
+        self.save_session(&session).await?;
+        self.dirty.lock().await.mark_saved(session.id, version);
+        notify_watchers(&self.watchers, &session).await;
+
+        Ok(())
+    }
+
+    /// Replay session writes journaled while no listener was reachable,
+    /// pushing the on-disk state into the current listener's memory so
+    /// a fallback save cannot fork the conversation. Returns how many
+    /// sessions were replayed; the journal survives a failed replay and
+    /// is retried on the next startup.
+    pub async fn replay_journal(&self) -> Result<usize> {
+        let path = journal_path(&self.sessions_dir);
+        let contents = match fs::read_to_string(&path).await {
+            Ok(contents) => contents,
+            // No journal means nothing to reconcile
+            Err(_) => return Ok(0),
+        };
+        let mut ids: Vec<Uuid> = contents
+            .lines()
+            .filter_map(|line| Uuid::parse_str(line.trim()).ok())
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        // As the listener we load session files ourselves, so the
+        // journaled state is already authoritative
+        if self.is_listener() {
+            let _ = fs::remove_file(&path).await;
+            return Ok(0);
+        }
+
+        let mut replayed = 0;
+        for id in ids {
+            let Some(session) =
+                fetch_session(&self.sessions, &self.sessions_dir, self.cipher.as_deref(), id).await?
+            else {
+                // Deleted or archived since; nothing to push
+                continue;
+            };
+            match self.send_command(&SessionCommand::UpdateSession(Box::new(session))).await {
+                Ok(SessionResponse::Session(_)) => replayed += 1,
+                Ok(SessionResponse::Error(err)) => return Err(GraphOsError::Session(err)),
+                Ok(_) => return Err(GraphOsError::Decode("Unexpected response from session manager".to_string())),
+                Err(e) => return Err(e),
+            }
+        }
+
+        let _ = fs::remove_file(&path).await;
+        Ok(replayed)
+    }
+
+    /// Claim the exclusive write lease on a session. Returns `None` when
+    /// the lease is now ours, or `Some(holder_pid)` when another live
+    /// process holds it and `force` was not set.
+    pub async fn acquire_lease(&self, id: Uuid, force: bool) -> Result<Option<u32>> {
+        let pid = std::process::id();
+
+        if !self.is_listener()
+            && let Some(response) = self.send_command_failover(&SessionCommand::AcquireLease { id, pid, force }).await?
+        {
+            return match response {
+                SessionResponse::Ok(_) => Ok(None),
+                SessionResponse::LeaseHeld { holder_pid } => Ok(Some(holder_pid)),
+                SessionResponse::Error(err) => Err(GraphOsError::Session(err)),
+                _ => Err(GraphOsError::Decode("Unexpected response from session manager".to_string())),
+            };
+        }
+
+        Ok(self.leases.lock().await.acquire(id, pid, force, Instant::now()).err())
+    }
+
+    /// Extend our lease on a session before its TTL runs out. Behaves
+    /// like a non-forcing acquire: if the lease was lost to a takeover,
+    /// the new holder's pid comes back instead.
+    pub async fn renew_lease(&self, id: Uuid) -> Result<Option<u32>> {
+        let pid = std::process::id();
+
+        if !self.is_listener()
+            && let Some(response) = self.send_command_failover(&SessionCommand::RenewLease { id, pid }).await?
+        {
+            return match response {
+                SessionResponse::Ok(_) => Ok(None),
+                SessionResponse::LeaseHeld { holder_pid } => Ok(Some(holder_pid)),
+                SessionResponse::Error(err) => Err(GraphOsError::Session(err)),
+                _ => Err(GraphOsError::Decode("Unexpected response from session manager".to_string())),
+            };
+        }
+
+        Ok(self.leases.lock().await.acquire(id, pid, false, Instant::now()).err())
+    }
+
+    /// Give the lease back on exit. A no-op if someone else took it over.
+    pub async fn release_lease(&self, id: Uuid) -> Result<()> {
+        let pid = std::process::id();
+
+        if !self.is_listener()
+            && let Some(response) = self.send_command_failover(&SessionCommand::ReleaseLease { id, pid }).await?
+        {
+            return match response {
+                SessionResponse::Ok(_) => Ok(()),
+                SessionResponse::Error(err) => Err(GraphOsError::Session(err)),
+                _ => Err(GraphOsError::Decode("Unexpected response from session manager".to_string())),
+            };
+        }
+
+        self.leases.lock().await.release(id, pid);
+        Ok(())
+    }
+
+    /// Live-tail a session for `gos watch`: returns the current
+    /// snapshot plus a channel the listener pushes every subsequent
+    /// update onto. When this process is itself the listener the
+    /// subscription is wired up locally; otherwise a dedicated
+    /// connection stays open for the pushes. The tail simply ends when
+    /// the listener goes away.
+    pub async fn subscribe_session(&self, id: Uuid) -> Result<(Session, mpsc::Receiver<Session>)> {
+        if self.is_listener() {
+            let session = fetch_session(&self.sessions, &self.sessions_dir, self.cipher.as_deref(), id)
+                .await?
+                .ok_or_else(|| GraphOsError::Session(format!("Session not found: {}", id)))?;
+            let (tx, rx) = mpsc::channel(16);
+            self.watchers.lock().await.entry(id).or_default().push(tx);
+            return Ok((session, rx));
+        }
+
+        let mut stream = match timeout(HEARTBEAT_TIMEOUT, transport::connect()).await {
+            Ok(Ok(stream)) => stream,
+            Ok(Err(e)) => return Err(GraphOsError::Transport(format!("Failed to connect to listener: {}", e))),
+            Err(_) => return Err(GraphOsError::Timeout("Timed out connecting to listener".to_string())),
+        };
+
+        let command_json = serde_json::to_string(&SessionCommand::Subscribe(id))?;
+        write_framed_message(&mut stream, PROTOCOL_VERSION, command_json.as_bytes()).await?;
+
+        // The initial snapshot doubles as the subscription handshake
+        let response = match timeout(RESPONSE_TIMEOUT, read_subscriber_frame(&mut stream)).await {
+            Ok(result) => result?,
+            Err(_) => return Err(GraphOsError::Timeout("Timeout reading subscription response".to_string())),
+        };
+        let session = match response {
+            SessionResponse::Session(session) => session,
+            SessionResponse::Error(err) => return Err(GraphOsError::Session(err)),
+            _ => return Err(GraphOsError::Decode("Unexpected response from session manager".to_string())),
+        };
+
+        let (tx, rx) = mpsc::channel(16);
+        tokio::spawn(async move {
+            while let Ok(SessionResponse::Session(session)) = read_subscriber_frame(&mut stream).await {
+                if tx.send(session).await.is_err() {
+                    // The spectator quit; dropping the stream tells the
+                    // listener to drop us too
+                    break;
+                }
+            }
+        });
+
+        Ok((session, rx))
+    }
+
+    /// Fork a session: copy the conversation up to `at` (message index,
+    /// exclusive) into a new session that records its parent
+    pub async fn fork_session(&self, id: Uuid, at: Option<usize>) -> Result<Session> {
+        let source = self.get_session(id).await?
+            .ok_or_else(|| GraphOsError::Session(format!("Session not found: {}", id)))?;
+
+        // Clamp the cut point to the conversation length
+        let cut = at.unwrap_or(source.messages.len()).min(source.messages.len());
+
+        let fork = Session {
+            messages: source.messages[..cut].to_vec(),
+            parent_id: Some(source.id),
+            forked_at: Some(cut),
+            ..Session::new(Uuid::new_v4())
+        };
+
+        self.update_session(fork.clone()).await?;
+
+        Ok(fork)
+    }
+
+    /// Encrypt every plaintext session file in place. Returns how many
+    /// files were encrypted and how many were already encrypted.
+    pub async fn encrypt_all(&self) -> Result<(usize, usize)> {
+        let cipher = self.cipher.as_ref().ok_or_else(|| {
+            GraphOsError::Config(format!("No session passphrase configured; set {}", crate::crypto::PASSPHRASE_ENV))
+        })?;
+
+        let mut entries = fs::read_dir(&self.sessions_dir).await?;
+        let mut encrypted = 0;
+        let mut already = 0;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().unwrap_or_default() != "json" {
+                continue;
+            }
+
+            let contents = fs::read(&path).await?;
+            if SessionCipher::is_encrypted(&contents) {
+                already += 1;
+                continue;
+            }
+
+            // Only rewrite files that actually hold a session
+            if let Err(e) = serde_json::from_slice::<Session>(&contents) {
+                eprintln!("Skipping {:?}: not a session file ({})", path, e);
+                continue;
+            }
+
+            let encrypted_contents = cipher
+                .encrypt(&contents)
+                .map_err(|e| GraphOsError::Session(e.to_string()))?;
+            write_session_file(&self.sessions_dir, &path, encrypted_contents).await?;
+            encrypted += 1;
+        }
+
+        Ok((encrypted, already))
+    }
+}
+
+/// Write a session file safely for concurrent writers: take an advisory
+/// lock on a shared lock file (so the listener and a fallback-local client
+/// in another process cannot interleave), write to a temp file in the same
+/// directory, fsync, then atomically rename over the target.
+async fn write_session_file(sessions_dir: &Path, file_path: &Path, contents: Vec<u8>) -> Result<()> {
+    let lock_path = sessions_dir.join(".sessions.lock");
+    let file_path = file_path.to_path_buf();
+
+    // Locking and fsync block, so run the whole write off the runtime
+    tokio::task::spawn_blocking(move || -> Result<()> {
+        let lock_file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| GraphOsError::Session(format!("Failed to open lock file {}: {}", lock_path.display(), e)))?;
+        // Advisory flock is Unix-only; Windows relies on the atomic
+        // rename below plus the listener being the single writer
+        #[cfg(unix)]
+        let _lock = Flock::lock(lock_file, FlockArg::LockExclusive)
+            .map_err(|(_, e)| GraphOsError::Session(format!("Failed to lock {}: {}", lock_path.display(), e)))?;
+        #[cfg(not(unix))]
+        let _lock = lock_file;
+
+        let tmp_path = file_path.with_extension("json.tmp");
+        {
+            let mut tmp = std::fs::File::create(&tmp_path)
+                .map_err(|e| GraphOsError::Session(format!("Failed to create {}: {}", tmp_path.display(), e)))?;
+            tmp.write_all(&contents)?;
+            tmp.sync_all()?;
+        }
+
+        std::fs::rename(&tmp_path, &file_path)
+            .map_err(|e| GraphOsError::Session(format!("Failed to rename into {}: {}", file_path.display(), e)))?;
+
+        Ok(())
+    })
+    .await
+    .map_err(|e| GraphOsError::Session(format!("Session write task failed: {}", e)))?
+}
+
+/// Serialize a session, encrypting it when a cipher is configured
+fn encode_session(cipher: Option<&SessionCipher>, session: &Session) -> Result<Vec<u8>> {
+    let json = serde_json::to_string_pretty(session)?;
+    match cipher {
+        Some(cipher) => cipher
+            .encrypt(json.as_bytes())
+            .map_err(|e| GraphOsError::Session(e.to_string())),
+        None => Ok(json.into_bytes()),
+    }
+}
+
+/// Read the eager index file, returning its entries plus the file's
+/// mtime so the loader can spot session files written after it. Any
+/// failure falls back to a full scan rather than an error.
+async fn read_index(
+    sessions_dir: &Path,
+    cipher: Option<&SessionCipher>,
+) -> Option<(Vec<SessionIndexEntry>, std::time::SystemTime)> {
+    let path = sessions_dir.join(SESSION_INDEX_FILE);
+    let mtime = fs::metadata(&path).await.ok()?.modified().ok()?;
+    let data = fs::read(&path).await.ok()?;
+
+    let plaintext = if SessionCipher::is_encrypted(&data) {
+        cipher?.decrypt(&data).ok()?
+    } else {
+        data
+    };
+
+    let entries = serde_json::from_slice(&plaintext).ok()?;
+    Some((entries, mtime))
+}
+
+/// Rewrite the eager index file, encrypted like the session files when
+/// a cipher is configured. Entries go out sorted so the file is stable.
+async fn write_index(
+    sessions_dir: &Path,
+    cipher: Option<&SessionCipher>,
+    index: &HashMap<Uuid, SessionIndexEntry>,
+) -> Result<()> {
+    let mut entries: Vec<&SessionIndexEntry> = index.values().collect();
+    entries.sort_by_key(|entry| (entry.created_at, entry.id));
+
+    let json = serde_json::to_string_pretty(&entries)?;
+    let contents = match cipher {
+        Some(cipher) => cipher
+            .encrypt(json.as_bytes())
+            .map_err(|e| GraphOsError::Session(e.to_string()))?,
+        None => json.into_bytes(),
+    };
+
+    let path = sessions_dir.join(SESSION_INDEX_FILE);
+    write_session_file(sessions_dir, &path, contents).await
+}
+
+/// Refresh one session's index entry and persist the index. Failures
+/// only warn: the session file is already safe, and the mtime check
+/// repairs a stale index at the next startup.
+async fn update_index(
+    index: &Arc<Mutex<HashMap<Uuid, SessionIndexEntry>>>,
+    sessions_dir: &Path,
+    cipher: Option<&SessionCipher>,
+    session: &Session,
+) {
+    let mut lock = index.lock().await;
+    lock.insert(session.id, SessionIndexEntry::from(session));
+    let snapshot = lock.clone();
+    drop(lock);
+
+    if let Err(e) = write_index(sessions_dir, cipher, &snapshot).await {
+        eprintln!("Failed to write session index: {}", e);
     }
 }
 
-async fn handle_client(
-    mut stream: TcpStream,
+/// Fetch a session, reading its file on demand when the body is not
+/// cached yet: the index is eager, bodies are lazy
+async fn fetch_session(
+    sessions: &Arc<Mutex<HashMap<Uuid, Session>>>,
+    sessions_dir: &Path,
+    cipher: Option<&SessionCipher>,
+    id: Uuid,
+) -> Result<Option<Session>> {
+    if let Some(session) = sessions.lock().await.get(&id) {
+        return Ok(Some(session.clone()));
+    }
+
+    let path = sessions_dir.join(format!("{}.json", id));
+    let contents = match fs::read(&path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let session = decode_session(cipher, &contents)?;
+    sessions.lock().await.insert(id, session.clone());
+    Ok(Some(session))
+}
+
+/// Fetch every session the index knows about, pulling uncached bodies
+/// from disk. Used by callers that genuinely need full conversations,
+/// like `gos export`.
+async fn fetch_all_sessions(
+    sessions: &Arc<Mutex<HashMap<Uuid, Session>>>,
+    sessions_dir: &Path,
+    cipher: Option<&SessionCipher>,
+    index: &Arc<Mutex<HashMap<Uuid, SessionIndexEntry>>>,
+) -> Result<Vec<Session>> {
+    let mut ids: Vec<Uuid> = index.lock().await.keys().copied().collect();
+    // A session created before the first index write is only in the
+    // body cache; take the union so nothing goes missing
+    for id in sessions.lock().await.keys() {
+        if !ids.contains(id) {
+            ids.push(*id);
+        }
+    }
+
+    let mut all = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(session) = fetch_session(sessions, sessions_dir, cipher, id).await? {
+            all.push(session);
+        }
+    }
+
+    Ok(all)
+}
+
+/// Parse a session file, decrypting it first when necessary
+fn decode_session(cipher: Option<&SessionCipher>, data: &[u8]) -> Result<Session> {
+    if SessionCipher::is_encrypted(data) {
+        let cipher = cipher.ok_or_else(|| {
+            GraphOsError::Config(format!("Session file is encrypted; set {}", crate::crypto::PASSPHRASE_ENV))
+        })?;
+        let plaintext = cipher
+            .decrypt(data)
+            .map_err(|e| GraphOsError::Decode(e.to_string()))?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    } else {
+        Ok(serde_json::from_slice(data)?)
+    }
+}
+
+/// Upper bound on a single session protocol frame. Large enough for a
+/// whole session payload, small enough to reject a corrupt length prefix
+/// before allocating.
+pub const MAX_FRAME_BYTES: u32 = 64 * 1024 * 1024;
+
+/// First byte of every framed protocol message. Legacy peers exchange
+/// bare JSON, which always starts with `"` or `{`, so this byte is what
+/// lets both generations share the socket.
+pub const FRAME_MAGIC: u8 = 0xF5;
+
+/// Current session protocol version. Version 1 is the original unframed
+/// JSON exchange; version 2 added length-prefixed frames. Each side
+/// sends its own version and acts on the lower of the two.
+pub const PROTOCOL_VERSION: u8 = 2;
+
+/// Send one framed protocol message: the magic byte, the sender's
+/// protocol version, then the length-prefixed payload
+pub async fn write_framed_message<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    version: u8,
+    payload: &[u8],
+) -> Result<()> {
+    stream.write_all(&[FRAME_MAGIC, version]).await?;
+    write_frame(stream, payload).await
+}
+
+/// Read the remainder of a framed message after its magic byte has been
+/// consumed, returning the peer's protocol version and the payload
+pub async fn read_framed_message<S: AsyncRead + Unpin>(stream: &mut S) -> Result<(u8, Vec<u8>)> {
+    let mut version = [0u8; 1];
+    stream.read_exact(&mut version).await?;
+    let payload = read_frame(stream).await?;
+    Ok((version[0], payload))
+}
+
+/// Write one length-prefixed frame: a little-endian u32 payload length
+/// followed by the payload itself
+pub async fn write_frame<S: AsyncWrite + Unpin>(stream: &mut S, payload: &[u8]) -> Result<()> {
+    let len = u32::try_from(payload.len())
+        .ok()
+        .filter(|&len| len <= MAX_FRAME_BYTES)
+        .ok_or_else(|| GraphOsError::Session(format!("Frame of {} bytes exceeds the protocol limit", payload.len())))?;
+
+    stream.write_all(&len.to_le_bytes()).await?;
+    stream.write_all(payload).await?;
+    Ok(())
+}
+
+/// Read one length-prefixed frame, failing on truncation or a length
+/// prefix beyond the protocol limit
+pub async fn read_frame<S: AsyncRead + Unpin>(stream: &mut S) -> Result<Vec<u8>> {
+    let mut len_bytes = [0u8; 4];
+    stream.read_exact(&mut len_bytes).await?;
+
+    let len = u32::from_le_bytes(len_bytes);
+    if len > MAX_FRAME_BYTES {
+        return Err(GraphOsError::Decode(format!("Frame length {} exceeds the protocol limit", len)));
+    }
+
+    let mut payload = vec![0u8; len as usize];
+    stream.read_exact(&mut payload).await?;
+    Ok(payload)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_client<S: AsyncRead + AsyncWrite + Unpin>(
+    mut stream: S,
     sessions: Arc<Mutex<HashMap<Uuid, Session>>>,
+    index: Arc<Mutex<HashMap<Uuid, SessionIndexEntry>>>,
     sessions_dir: PathBuf,
+    dirty: Arc<Mutex<DirtyTracker>>,
+    cipher: Option<Arc<SessionCipher>>,
+    leases: Arc<Mutex<LeaseTable>>,
+    watchers: Watchers,
+    shutdown: mpsc::Sender<()>,
 ) -> Result<()> {
     println!("Handling client connection");
     
-    // Use a timeout for reading to avoid hanging
+    // Use a timeout for reading to avoid hanging. The first byte picks
+    // the protocol generation: framed clients lead with FRAME_MAGIC and
+    // their version, legacy ones send bare JSON in a single write.
     let read_future = async {
-        let mut buffer = [0u8; 1024];
-        let n = stream.read(&mut buffer).await?;
-        Ok::<_, anyhow::Error>(String::from_utf8_lossy(&buffer[..n]).to_string())
+        let mut first = [0u8; 1];
+        stream.read_exact(&mut first).await?;
+
+        if first[0] == FRAME_MAGIC {
+            let (version, payload) = read_framed_message(&mut stream).await?;
+            Ok::<_, GraphOsError>((version.min(PROTOCOL_VERSION), payload))
+        } else {
+            // Legacy exchange: whatever one read returns after the byte
+            // already consumed, exactly as the old single-buffer server
+            let mut rest = [0u8; 1024];
+            let n = stream.read(&mut rest).await?;
+            let mut payload = vec![first[0]];
+            payload.extend_from_slice(&rest[..n]);
+            Ok((1, payload))
+        }
     };
-    
-    let buffer = match timeout(Duration::from_secs(5), read_future).await {
-        Ok(Ok(buffer)) => buffer,
+
+    let (version, buffer) = match timeout(Duration::from_secs(5), read_future).await {
+        Ok(Ok(result)) => result,
         Ok(Err(e)) => {
             println!("Error reading from stream: {}", e);
+            // The peer's generation is unknown here; bare JSON makes a
+            // framed client fall back to its legacy retry path
             let error_response = SessionResponse::Error(format!("Error reading command: {}", e));
-            let response_json = serde_json::to_string(&error_response)?;
-            stream.write_all(response_json.as_bytes()).await?;
+            write_response(&mut stream, 1, &error_response).await?;
             return Ok(());
         }
         Err(_) => {
             println!("Timeout reading from stream");
             let error_response = SessionResponse::Error("Timeout reading command".to_string());
-            let response_json = serde_json::to_string(&error_response)?;
-            stream.write_all(response_json.as_bytes()).await?;
+            write_response(&mut stream, 1, &error_response).await?;
             return Ok(());
         }
     };
-    
-    println!("Received command: {}", buffer);
-    
-    let command: SessionCommand = match serde_json::from_str(&buffer) {
+
+    let command: SessionCommand = match serde_json::from_slice(&buffer) {
         Ok(cmd) => cmd,
         Err(e) => {
             println!("Failed to parse command: {}", e);
             let error_response = SessionResponse::Error(format!("Invalid command format: {}", e));
-            let response_json = serde_json::to_string(&error_response)?;
-            stream.write_all(response_json.as_bytes()).await?;
+            write_response(&mut stream, version, &error_response).await?;
             return Ok(());
         }
     };
     
     println!("Processing command");
+
+    // Subscribe is the one command that keeps the connection open: the
+    // initial snapshot goes out like a normal response, then every
+    // update to the session is pushed as another frame until the
+    // subscriber hangs up
+    if let SessionCommand::Subscribe(id) = command {
+        return handle_subscriber(stream, version, id, sessions, sessions_dir, cipher, watchers).await;
+    }
+
     let response = match command {
         SessionCommand::GetOrCreateSession => {
             let session_id = Uuid::new_v4();
-            let session = Session {
-                id: session_id,
-                created_at: Utc::now(),
-                last_active: Utc::now(),
-                messages: Vec::new(),
-            };
+            let session = Session::new(session_id);
             
             let mut sessions_lock = sessions.lock().await;
             sessions_lock.insert(session_id, session.clone());
-            
-            // Save to disk
+
+            // Save to disk; a failure leaves the session dirty for the
+            // autosave sweep to retry
+            let version = dirty.lock().await.touch(session_id);
             let file_path = sessions_dir.join(format!("{}.json", session_id));
-            let json = serde_json::to_string_pretty(&session)?;
-            fs::write(file_path, json).await?;
-            
+            write_session_file(&sessions_dir, &file_path, encode_session(cipher.as_deref(), &session)?).await?;
+            dirty.lock().await.mark_saved(session_id, version);
+
+            update_index(&index, &sessions_dir, cipher.as_deref(), &session).await;
+
             SessionResponse::Session(session)
         },
         SessionCommand::GetSession(id) => {
-            let sessions_lock = sessions.lock().await;
-            match sessions_lock.get(&id) {
-                Some(session) => SessionResponse::Session(session.clone()),
+            match fetch_session(&sessions, &sessions_dir, cipher.as_deref(), id).await? {
+                Some(session) => SessionResponse::Session(session),
+                None => SessionResponse::Error(format!("Session not found: {}", id)),
+            }
+        },
+        SessionCommand::GetSessionMeta(id) => {
+            match fetch_session(&sessions, &sessions_dir, cipher.as_deref(), id).await? {
+                Some(mut session) => {
+                    let total = session.messages.len();
+                    session.messages = Vec::new();
+                    SessionResponse::Meta { session, total }
+                }
+                None => SessionResponse::Error(format!("Session not found: {}", id)),
+            }
+        },
+        SessionCommand::GetSessionMessages { id, offset, limit } => {
+            match fetch_session(&sessions, &sessions_dir, cipher.as_deref(), id).await? {
+                Some(session) => {
+                    let total = session.messages.len();
+                    let (start, end) = page_bounds(total, offset, limit);
+                    SessionResponse::Messages {
+                        messages: session.messages[start..end].to_vec(),
+                        total,
+                    }
+                }
+                None => SessionResponse::Error(format!("Session not found: {}", id)),
+            }
+        },
+        SessionCommand::FilterMessages { id, filter } => {
+            match fetch_session(&sessions, &sessions_dir, cipher.as_deref(), id).await? {
+                Some(session) => {
+                    let total = session.messages.len();
+                    match filter_messages(&session.messages, &filter) {
+                        Ok(messages) => SessionResponse::IndexedMessages { messages, total },
+                        Err(e) => SessionResponse::Error(e.to_string()),
+                    }
+                }
                 None => SessionResponse::Error(format!("Session not found: {}", id)),
             }
         },
         SessionCommand::UpdateSession(session) => {
+            let session = *session;
             let mut sessions_lock = sessions.lock().await;
             sessions_lock.insert(session.id, session.clone());
-            
+
             // Save to disk
+            let version = dirty.lock().await.touch(session.id);
             let file_path = sessions_dir.join(format!("{}.json", session.id));
-            let json = serde_json::to_string_pretty(&session)?;
-            fs::write(file_path, json).await?;
-            
+            write_session_file(&sessions_dir, &file_path, encode_session(cipher.as_deref(), &session)?).await?;
+            dirty.lock().await.mark_saved(session.id, version);
+
+            update_index(&index, &sessions_dir, cipher.as_deref(), &session).await;
+            notify_watchers(&watchers, &session).await;
+
             SessionResponse::Session(session)
         },
         SessionCommand::ListSessions => {
+            let all = fetch_all_sessions(&sessions, &sessions_dir, cipher.as_deref(), &index).await?;
+            SessionResponse::Sessions(all)
+        },
+        SessionCommand::ListIndex => {
+            let index_lock = index.lock().await;
+            SessionResponse::Index(index_lock.values().cloned().collect())
+        },
+        SessionCommand::ListSessionsPage { options } => {
+            if options.summary_only {
+                let entries: Vec<SessionIndexEntry> = index.lock().await.values().cloned().collect();
+                let (entries, total) = apply_list_options(entries, &options, |entry| {
+                    (entry.title.clone(), entry.created_at, entry.last_active)
+                });
+                SessionResponse::IndexPage { entries, total }
+            } else {
+                let all = fetch_all_sessions(&sessions, &sessions_dir, cipher.as_deref(), &index).await?;
+                let (page, total) = apply_list_options(all, &options, |session| {
+                    (session.title.clone(), session.created_at, session.last_active)
+                });
+                SessionResponse::SessionsPage { sessions: page, total }
+            }
+        },
+        SessionCommand::AcquireLease { id, pid, force } => {
+            match leases.lock().await.acquire(id, pid, force, Instant::now()) {
+                Ok(()) => SessionResponse::Ok(format!("Lease on {} held by pid {}", id, pid)),
+                Err(holder_pid) => SessionResponse::LeaseHeld { holder_pid },
+            }
+        },
+        SessionCommand::RenewLease { id, pid } => {
+            match leases.lock().await.acquire(id, pid, false, Instant::now()) {
+                Ok(()) => SessionResponse::Ok(format!("Lease on {} renewed by pid {}", id, pid)),
+                Err(holder_pid) => SessionResponse::LeaseHeld { holder_pid },
+            }
+        },
+        SessionCommand::ReleaseLease { id, pid } => {
+            leases.lock().await.release(id, pid);
+            SessionResponse::Ok(format!("Lease on {} released", id))
+        },
+        SessionCommand::Ping => {
+            let index_lock = index.lock().await;
+            SessionResponse::Ok(format!("{} session(s) loaded", index_lock.len()))
+        },
+        SessionCommand::Subscribe(_) => unreachable!("handled above"),
+        SessionCommand::Shutdown => {
+            // Flush anything still dirty before going down, skipping the
+            // debounce: there is no next sweep
+            let pending = dirty.lock().await.dirty(Duration::ZERO);
             let sessions_lock = sessions.lock().await;
-            let sessions_list = sessions_lock.values().cloned().collect();
-            SessionResponse::Sessions(sessions_list)
+            for (id, version) in pending {
+                let Some(session) = sessions_lock.get(&id) else { continue };
+                let file_path = sessions_dir.join(format!("{}.json", id));
+                match write_session_file(&sessions_dir, &file_path, encode_session(cipher.as_deref(), session)?).await {
+                    Ok(()) => dirty.lock().await.mark_saved(id, version),
+                    Err(e) => eprintln!("Failed to save session {} during shutdown: {}", id, e),
+                }
+            }
+
+            let _ = shutdown.send(()).await;
+            SessionResponse::Ok("shutting down".to_string())
         },
     };
     
-    let response_json = serde_json::to_string(&response)?;
-    stream.write_all(response_json.as_bytes()).await?;
-    
+    write_response(&mut stream, version, &response).await?;
+
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Serve one spectator: send the current session, register a watcher
+/// channel, and forward every update until the peer disconnects
+async fn handle_subscriber<S: AsyncRead + AsyncWrite + Unpin>(
+    mut stream: S,
+    version: u8,
+    id: Uuid,
+    sessions: Arc<Mutex<HashMap<Uuid, Session>>>,
+    sessions_dir: PathBuf,
+    cipher: Option<Arc<SessionCipher>>,
+    watchers: Watchers,
+) -> Result<()> {
+    let session = match fetch_session(&sessions, &sessions_dir, cipher.as_deref(), id).await? {
+        Some(session) => session,
+        None => {
+            let response = SessionResponse::Error(format!("Session not found: {}", id));
+            return write_response(&mut stream, version, &response).await;
+        }
+    };
+
+    // Bounded channel: a subscriber that stops reading skips updates
+    // instead of backing memory up (see [`notify_watchers`])
+    let (tx, mut rx) = mpsc::channel::<Session>(16);
+    watchers.lock().await.entry(id).or_default().push(tx);
+
+    write_response(&mut stream, version, &SessionResponse::Session(session)).await?;
+
+    let mut probe = [0u8; 1];
+    loop {
+        select! {
+            update = rx.recv() => match update {
+                Some(session) => {
+                    if write_response(&mut stream, version, &SessionResponse::Session(session)).await.is_err() {
+                        break;
+                    }
+                }
+                None => break,
+            },
+            // The spectator never sends again after subscribing, so any
+            // read completing — EOF or an error — means it hung up.
+            // Returning drops rx, and the next push prunes our entry.
+            _ = stream.read(&mut probe) => break,
+        }
+    }
+
+    Ok(())
+}
+
+/// Write a response in the negotiated generation: framed for version 2
+/// and up, bare JSON for legacy peers
+async fn write_response<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    version: u8,
+    response: &SessionResponse,
+) -> Result<()> {
+    let json = serde_json::to_string(response)?;
+    if version >= 2 {
+        write_framed_message(stream, version, json.as_bytes()).await
+    } else {
+        stream.write_all(json.as_bytes()).await?;
+        Ok(())
+    }
+}
+
+/// Read one framed response on a subscription connection. Subscriptions
+/// postdate framing, so a bare-JSON peer is simply an error here.
+async fn read_subscriber_frame<S: AsyncRead + Unpin>(stream: &mut S) -> Result<SessionResponse> {
+    let mut first = [0u8; 1];
+    stream.read_exact(&mut first).await?;
+    if first[0] != FRAME_MAGIC {
+        return Err(GraphOsError::Decode("Subscriptions need a framed-protocol listener".to_string()));
+    }
+    let (_version, payload) = read_framed_message(stream).await?;
+    Ok(serde_json::from_slice(&payload)?)
+}
+
+/// Send one command to a running listener and read its response. Speaks
+/// the framed protocol first and falls back to the legacy unframed
+/// exchange when the listener predates framing. Timeouts match the
+/// SessionManager client paths so `gos daemon status` against a wedged
+/// listener fails fast instead of hanging.
+async fn send_listener_command(command: &SessionCommand) -> Result<SessionResponse> {
+    let command_json = serde_json::to_string(command)?;
+
+    let mut stream = match timeout(HEARTBEAT_TIMEOUT, transport::connect()).await {
+        Ok(result) => result?,
+        Err(_) => return Err(GraphOsError::Timeout("Timed out connecting to listener".to_string())),
+    };
+
+    let framed = async {
+        write_framed_message(&mut stream, PROTOCOL_VERSION, command_json.as_bytes()).await?;
+        let mut first = [0u8; 1];
+        stream.read_exact(&mut first).await?;
+        Ok::<_, GraphOsError>(first[0])
+    };
+    let first = match timeout(RESPONSE_TIMEOUT, framed).await {
+        Ok(result) => result?,
+        Err(_) => return Err(GraphOsError::Timeout("Listener did not answer within the deadline".to_string())),
+    };
+
+    if first == FRAME_MAGIC {
+        let (_version, buffer) = match timeout(RESPONSE_TIMEOUT, read_framed_message(&mut stream)).await {
+            Ok(result) => result?,
+            Err(_) => return Err(GraphOsError::Timeout("Listener did not answer within the deadline".to_string())),
+        };
+        return Ok(serde_json::from_slice(&buffer)?);
+    }
+
+    // A legacy listener answered our framed bytes with a bare JSON parse
+    // error. Drain it and repeat the command the old way on a fresh
+    // connection, which such a listener still understands.
+    let legacy = async {
+        let mut drain = [0u8; 4096];
+        let _ = stream.read(&mut drain).await;
+        drop(stream);
+
+        let mut stream = transport::connect().await?;
+        stream.write_all(command_json.as_bytes()).await?;
+
+        let mut buffer = [0u8; 4096];
+        let n = stream.read(&mut buffer).await?;
+        Ok::<_, GraphOsError>(serde_json::from_slice(&buffer[..n])?)
+    };
+    match timeout(RESPONSE_TIMEOUT, legacy).await {
+        Ok(result) => result,
+        Err(_) => Err(GraphOsError::Timeout("Listener did not answer within the deadline".to_string())),
+    }
+}
+
+/// Ping a running listener, returning its health summary if one answers
+pub async fn ping_listener() -> Option<String> {
+    match send_listener_command(&SessionCommand::Ping).await {
+        Ok(SessionResponse::Ok(info)) => Some(info),
+        _ => None,
+    }
+}
+
+/// Ask a running listener to shut down. Ok(true) if one acknowledged.
+pub async fn request_shutdown() -> Result<bool> {
+    match send_listener_command(&SessionCommand::Shutdown).await {
+        Ok(SessionResponse::Ok(_)) => Ok(true),
+        Ok(other) => Err(GraphOsError::Decode(format!("Unexpected shutdown response: {:?}", other))),
+        Err(_) => Ok(false),
+    }
+}
@@ -0,0 +1,159 @@
+//! Transcript sharing (`/share` in chat, `gos share` from the shell).
+//!
+//! Uploads a session to the share endpoint in the config's `[share]`
+//! table and returns the URL the service hands back. Two kinds of
+//! service are supported: a GraphOS server (`sessions.share` RPC) and a
+//! generic paste service that answers a plain POST with a URL. Any
+//! secret from the local config is scrubbed from the transcript before
+//! it leaves the machine.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+
+use crate::adapters::JsonRpcClient;
+use crate::config::{Config, ShareConfig};
+use crate::session::{ChatMessage, Session};
+
+/// Collect every secret value present in the config so it can be
+/// scrubbed from outgoing transcripts
+pub fn secret_values(config: &Config) -> Vec<String> {
+    let mut secrets = Vec::new();
+
+    for api in config.apis.values() {
+        secrets.push(api.api_key.clone());
+    }
+
+    if let Some(auth) = &config.auth {
+        secrets.extend(auth.rpc_secret.clone());
+        for endpoint in auth.endpoints.values() {
+            secrets.extend(endpoint.secret.clone());
+            secrets.extend(endpoint.token.clone());
+            secrets.extend(endpoint.refresh_token.clone());
+        }
+        if let Some(share) = &auth.share {
+            secrets.extend(share.token.clone());
+        }
+    }
+
+    secrets.retain(|s| !s.is_empty());
+    secrets
+}
+
+/// Replace every occurrence of a known secret with a placeholder
+pub fn redact_text(text: &str, secrets: &[String]) -> String {
+    let mut redacted = text.to_string();
+    for secret in secrets {
+        redacted = redacted.replace(secret.as_str(), "[redacted]");
+    }
+    redacted
+}
+
+/// Build the `sessions.share` RPC payload for a session
+pub fn transcript_payload(session: &Session, secrets: &[String]) -> Value {
+    let messages: Vec<Value> = session
+        .messages
+        .iter()
+        .map(|message| {
+            let mut entry = json!({
+                "role": message.role(),
+                "content": redact_text(message.text(), secrets),
+            });
+            // Attach generation metadata when the message carries any
+            if !message.meta().is_empty()
+                && let (Value::Object(map), Ok(meta)) = (&mut entry, serde_json::to_value(message.meta()))
+            {
+                map.insert("meta".to_string(), meta);
+            }
+            entry
+        })
+        .collect();
+
+    json!({
+        "session_id": session.id,
+        "created_at": session.created_at.to_rfc3339(),
+        "messages": messages,
+    })
+}
+
+/// Render a session as plain markdown for paste services
+pub fn render_markdown(session: &Session, secrets: &[String]) -> String {
+    let mut out = format!(
+        "# Session {}\n\nCreated {}\n",
+        session.id,
+        session.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+
+    for message in &session.messages {
+        let heading = match message {
+            ChatMessage::User { .. } => "User",
+            ChatMessage::Assistant { .. } => "Assistant",
+        };
+        out.push_str(&format!("\n## {}\n", heading));
+        if !message.meta().is_empty() {
+            out.push_str(&format!("\n*{}*\n", message.meta().summary()));
+        }
+        out.push_str(&format!("\n{}\n", redact_text(message.text(), secrets)));
+    }
+
+    out
+}
+
+/// Upload a session to the configured share endpoint and return the URL
+pub async fn share_session(
+    config: &Config,
+    share: &ShareConfig,
+    session: &Session,
+    expire: Option<Duration>,
+) -> Result<String> {
+    let secrets = secret_values(config);
+
+    match share.kind.as_deref().unwrap_or("graphos") {
+        "graphos" => {
+            let client = JsonRpcClient::with_endpoint(
+                share.url.clone(),
+                share.token.clone(),
+                None,
+                config.get_rpc_secret(),
+            );
+
+            let mut params = transcript_payload(session, &secrets);
+            if let (Some(expire), Value::Object(map)) = (expire, &mut params) {
+                map.insert("expire_seconds".to_string(), json!(expire.as_secs()));
+            }
+
+            let result = client
+                .request("sessions.share", params)
+                .await
+                .context("sessions.share request failed")?;
+
+            result
+                .get("url")
+                .and_then(|url| url.as_str())
+                .map(|url| url.to_string())
+                .ok_or_else(|| anyhow!("Server response has no 'url' field: {}", result))
+        }
+        "paste" => {
+            let body = render_markdown(session, &secrets);
+
+            let mut request = reqwest::Client::new()
+                .post(&share.url)
+                .header("Content-Type", "text/plain; charset=utf-8")
+                .body(body);
+            if let Some(token) = &share.token {
+                request = request.bearer_auth(token);
+            }
+
+            let response = request.send().await.context("Paste upload failed")?;
+            if !response.status().is_success() {
+                bail!("Paste service returned HTTP {}", response.status());
+            }
+
+            // Paste services answer with the URL as the response body
+            let url = response.text().await.context("Failed to read paste response")?;
+            Ok(url.trim().to_string())
+        }
+        other => bail!("Unknown share kind '{}': expected \"graphos\" or \"paste\"", other),
+    }
+}
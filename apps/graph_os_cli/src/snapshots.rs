@@ -0,0 +1,164 @@
+//! Session restore points (`/history`, `gos rollback`).
+//!
+//! Snapshots are full copies of a session's JSON written under the data
+//! directory, one subdirectory per session. The chat UI takes one
+//! automatically every [`AUTO_SNAPSHOT_EVERY`] new messages and before
+//! destructive commands, so a conversation can be rewound after an
+//! accidental template application or an unwanted rollback — rolling
+//! back snapshots the current state first, making it undoable too.
+//!
+//! The snapshot name encodes when it was taken, how long the
+//! conversation was and why it was taken, so listings need no file
+//! reads. Only the newest [`MAX_SNAPSHOTS`] per session are kept.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, TimeZone, Utc};
+use uuid::Uuid;
+
+use crate::error::{GraphOsError, Result};
+use crate::paths;
+use crate::session::Session;
+
+/// A snapshot is taken automatically once this many messages have been
+/// added since the last one
+pub const AUTO_SNAPSHOT_EVERY: usize = 10;
+
+/// Snapshots kept per session; older ones are pruned on each write
+pub const MAX_SNAPSHOTS: usize = 20;
+
+/// One restore point, decoded from its filename
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotInfo {
+    /// Filename stem; what `gos rollback --to` expects
+    pub name: String,
+    pub taken_at: DateTime<Utc>,
+    /// Conversation length at the time of the snapshot
+    pub messages: usize,
+    /// Why it was taken: "auto", "template", "rollback", ...
+    pub reason: String,
+}
+
+/// Directory holding one session's snapshots
+pub fn snapshots_dir(session_id: Uuid) -> PathBuf {
+    paths::data_dir().join("snapshots").join(session_id.to_string())
+}
+
+/// Build a snapshot filename stem: `<unix-ts>-<messages>-<reason>`.
+/// The timestamp leads so lexicographic order is chronological.
+pub fn snapshot_name(taken_at: DateTime<Utc>, messages: usize, reason: &str) -> String {
+    // Keep the reason filename-safe; it only ever comes from our own
+    // call sites, but don't rely on that
+    let reason: String = reason
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("{}-{}-{}", taken_at.timestamp(), messages, reason)
+}
+
+/// Decode a filename stem produced by [`snapshot_name`]
+pub fn parse_snapshot_name(name: &str) -> Option<SnapshotInfo> {
+    let mut parts = name.splitn(3, '-');
+    let taken_at = Utc.timestamp_opt(parts.next()?.parse().ok()?, 0).single()?;
+    let messages = parts.next()?.parse().ok()?;
+    let reason = parts.next()?.to_string();
+
+    Some(SnapshotInfo {
+        name: name.to_string(),
+        taken_at,
+        messages,
+        reason,
+    })
+}
+
+/// Write a restore point for `session` into `dir` and prune old ones.
+/// Split out from [`take_snapshot`] so tests can use a temp directory.
+pub fn write_snapshot(
+    dir: &Path,
+    session: &Session,
+    reason: &str,
+    taken_at: DateTime<Utc>,
+) -> Result<SnapshotInfo> {
+    std::fs::create_dir_all(dir)?;
+
+    let name = snapshot_name(taken_at, session.messages.len(), reason);
+    let json = serde_json::to_string(session)
+        .map_err(|e| GraphOsError::Session(format!("Failed to serialize snapshot: {}", e)))?;
+    std::fs::write(dir.join(format!("{}.json", name)), json)?;
+
+    // Drop the oldest snapshots beyond the cap
+    let mut snapshots = list_snapshots_in(dir)?;
+    while snapshots.len() > MAX_SNAPSHOTS {
+        let oldest = snapshots.pop().expect("len checked above");
+        std::fs::remove_file(dir.join(format!("{}.json", oldest.name))).ok();
+    }
+
+    parse_snapshot_name(&name)
+        .ok_or_else(|| GraphOsError::Session(format!("Unparseable snapshot name: {}", name)))
+}
+
+/// Take a restore point for `session` in its snapshot directory
+pub fn take_snapshot(session: &Session, reason: &str) -> Result<SnapshotInfo> {
+    write_snapshot(&snapshots_dir(session.id), session, reason, Utc::now())
+}
+
+/// List the restore points in `dir`, newest first. Files that don't
+/// look like snapshots are ignored.
+pub fn list_snapshots_in(dir: &Path) -> Result<Vec<SnapshotInfo>> {
+    let mut snapshots = Vec::new();
+
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        // No directory just means no snapshots yet
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(snapshots),
+        Err(e) => return Err(e.into()),
+    };
+    for entry in entries.filter_map(std::result::Result::ok) {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        if let Some(info) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(parse_snapshot_name)
+        {
+            snapshots.push(info);
+        }
+    }
+
+    snapshots.sort_by(|a, b| b.name.cmp(&a.name));
+    Ok(snapshots)
+}
+
+/// List a session's restore points, newest first
+pub fn list_snapshots(session_id: Uuid) -> Result<Vec<SnapshotInfo>> {
+    list_snapshots_in(&snapshots_dir(session_id))
+}
+
+/// Load a restore point by name (with or without the .json extension)
+pub fn load_snapshot(session_id: Uuid, name: &str) -> Result<Session> {
+    let name = name.strip_suffix(".json").unwrap_or(name);
+    let path = snapshots_dir(session_id).join(format!("{}.json", name));
+
+    let json = std::fs::read_to_string(&path).map_err(|e| {
+        GraphOsError::Session(format!(
+            "No snapshot '{}' for session {}: {}. List them with 'gos rollback {}'",
+            name, session_id, e, session_id
+        ))
+    })?;
+    serde_json::from_str(&json)
+        .map_err(|e| GraphOsError::Session(format!("Corrupt snapshot {}: {}", name, e)))
+}
+
+/// Human-oriented one-line description of a snapshot for listings
+pub fn describe(info: &SnapshotInfo) -> String {
+    format!(
+        "{}  {}  {} message{}  ({})",
+        info.name,
+        info.taken_at.format("%Y-%m-%d %H:%M:%S"),
+        info.messages,
+        if info.messages == 1 { "" } else { "s" },
+        info.reason
+    )
+}
@@ -0,0 +1,165 @@
+//! Server-side task invocation (`gos task`).
+//!
+//! Triggers GraphOS agents and pipelines over JSON-RPC (`tasks.run`,
+//! `tasks.status`, `tasks.logs`) and optionally follows a run to
+//! completion, streaming its log lines and mapping the final state to
+//! an exit code so scripts can gate on success.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+
+use crate::adapters::JsonRpcClient;
+use crate::error::GraphOsError;
+
+/// How long a follow waits between status and log polls
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// States a task run moves through on the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskState {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl TaskState {
+    /// Parse the `status` string servers return. Unknown values map to
+    /// Running so a follow keeps polling instead of giving up on a
+    /// server with a richer state machine.
+    pub fn parse(status: &str) -> TaskState {
+        match status.to_lowercase().as_str() {
+            "pending" | "queued" | "scheduled" => TaskState::Pending,
+            "succeeded" | "success" | "completed" | "done" => TaskState::Succeeded,
+            "failed" | "error" => TaskState::Failed,
+            "cancelled" | "canceled" | "aborted" => TaskState::Cancelled,
+            _ => TaskState::Running,
+        }
+    }
+
+    /// Whether the run is over and polling can stop
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, TaskState::Succeeded | TaskState::Failed | TaskState::Cancelled)
+    }
+
+    /// Exit code for scripts: 0 on success, 1 on failure, 130 when the
+    /// run was cancelled (mirroring an interrupted shell command)
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            TaskState::Succeeded => 0,
+            TaskState::Cancelled => 130,
+            _ => 1,
+        }
+    }
+}
+
+impl std::fmt::Display for TaskState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskState::Pending => write!(f, "pending"),
+            TaskState::Running => write!(f, "running"),
+            TaskState::Succeeded => write!(f, "succeeded"),
+            TaskState::Failed => write!(f, "failed"),
+            TaskState::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// Extract the run id from a tasks.run result, accepting both
+/// `{"id": ...}` and `{"task_id": ...}` with string or numeric ids
+pub fn task_id(result: &Value) -> Option<String> {
+    let id = result.get("id").or_else(|| result.get("task_id"))?;
+    match id {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// Extract log lines from a tasks.logs result, accepting both a bare
+/// array and the `{"lines": [...]}` envelope
+pub fn log_lines(result: &Value) -> Vec<String> {
+    let entries = result
+        .get("lines")
+        .and_then(|v| v.as_array())
+        .or_else(|| result.as_array());
+
+    entries
+        .map(|lines| {
+            lines
+                .iter()
+                .filter_map(|line| line.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Trigger a task run on the server; returns the assigned run id
+pub async fn run_task(client: &JsonRpcClient, name: &str, input: Option<&Value>) -> Result<String> {
+    let mut params = json!({ "name": name });
+    if let (Some(input), Value::Object(map)) = (input, &mut params) {
+        map.insert("input".to_string(), input.clone());
+    }
+
+    let result = client
+        .request("tasks.run", params)
+        .await
+        .context("tasks.run request failed")?;
+
+    task_id(&result).ok_or_else(|| anyhow!("Server response has no task id: {}", result))
+}
+
+/// Fetch the current state of a run, plus the raw status object for
+/// display
+pub async fn task_status(client: &JsonRpcClient, id: &str) -> Result<(TaskState, Value)> {
+    let result = client
+        .request("tasks.status", json!({ "id": id }))
+        .await
+        .context("tasks.status request failed")?;
+
+    let state = result
+        .get("status")
+        .and_then(|s| s.as_str())
+        .map(TaskState::parse)
+        .ok_or_else(|| anyhow!("Server response has no 'status' field: {}", result))?;
+
+    Ok((state, result))
+}
+
+/// Fetch log lines starting at `offset`, printing nothing. Servers
+/// without tasks.logs yield an empty page instead of an error.
+pub async fn fetch_logs(client: &JsonRpcClient, id: &str, offset: usize) -> Result<Vec<String>> {
+    match client.request("tasks.logs", json!({ "id": id, "offset": offset })).await {
+        Ok(result) => Ok(log_lines(&result)),
+        // -32601 is "method not found": a server without log storage
+        Err(GraphOsError::RpcError { code: -32601, .. }) => Ok(Vec::new()),
+        Err(e) => Err(e).context("tasks.logs request failed"),
+    }
+}
+
+/// Follow a run to completion: poll its status, stream new log lines
+/// to stdout as they appear, and return the terminal state
+pub async fn follow_task(client: &JsonRpcClient, id: &str) -> Result<TaskState> {
+    let mut offset = 0usize;
+
+    loop {
+        // Status before logs, so the final lines written by a run that
+        // just finished are still drained before the loop exits
+        let (state, _) = task_status(client, id).await?;
+
+        let lines = fetch_logs(client, id, offset).await?;
+        offset += lines.len();
+        for line in lines {
+            println!("{}", line);
+        }
+
+        if state.is_terminal() {
+            return Ok(state);
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
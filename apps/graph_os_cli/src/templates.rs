@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// A reusable prompt preset defined in the config file under `templates`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    /// Short description shown in template listings
+    #[serde(default)]
+    pub description: Option<String>,
+    /// System prompt installed for the conversation
+    #[serde(default)]
+    pub system: Option<String>,
+    /// Initial user message; may contain {{variable}} placeholders
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+impl Template {
+    /// All {{variable}} names this template references
+    pub fn variables(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        for text in [self.system.as_deref(), self.prompt.as_deref()].into_iter().flatten() {
+            for name in variables(text) {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+}
+
+/// Collect the {{variable}} names referenced in a template string
+pub fn variables(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let name = after[..end].trim().to_string();
+                if !name.is_empty() && !names.contains(&name) {
+                    names.push(name);
+                }
+                rest = &after[end + 2..];
+            }
+            None => break,
+        }
+    }
+
+    names
+}
+
+/// Substitute {{variable}} placeholders with the provided values.
+/// A placeholder without a value is an error so typos surface early.
+pub fn render(text: &str, vars: &HashMap<String, String>) -> Result<String> {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+
+        match after.find("}}") {
+            Some(end) => {
+                let name = after[..end].trim();
+                let value = vars
+                    .get(name)
+                    .ok_or_else(|| anyhow!("Missing value for template variable '{}'", name))?;
+                output.push_str(value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                // Unterminated placeholder: keep the rest verbatim
+                output.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Parse a single `key=value` argument as passed to `--var`
+pub fn parse_var(arg: &str) -> Result<(String, String)> {
+    match arg.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(anyhow!("Invalid --var '{}', expected key=value", arg)),
+    }
+}
+
+/// Parse repeated `--var key=value` arguments into a map
+pub fn parse_vars(args: &[String]) -> Result<HashMap<String, String>> {
+    let mut vars = HashMap::new();
+    for arg in args {
+        let (key, value) = parse_var(arg)?;
+        vars.insert(key, value);
+    }
+    Ok(vars)
+}
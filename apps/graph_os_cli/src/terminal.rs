@@ -0,0 +1,224 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::{Deserialize, Serialize};
+
+/// Inline image protocols supported by terminal emulators
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageProtocol {
+    /// Kitty graphics protocol
+    Kitty,
+    /// iTerm2 OSC 1337 inline images
+    Iterm2,
+    /// DEC sixel graphics
+    Sixel,
+    /// No inline image support detected
+    None,
+}
+
+/// Detect which inline image protocol the current terminal supports
+pub fn detect_image_protocol() -> ImageProtocol {
+    let term = env::var("TERM").unwrap_or_default();
+    let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+
+    // Kitty sets both TERM and a window id
+    if term == "xterm-kitty" || env::var("KITTY_WINDOW_ID").is_ok() {
+        return ImageProtocol::Kitty;
+    }
+
+    // iTerm2 and WezTerm both speak the OSC 1337 protocol
+    if term_program == "iTerm.app" || term_program == "WezTerm" {
+        return ImageProtocol::Iterm2;
+    }
+
+    // Sixel-capable terminals usually advertise it in TERM
+    if term.contains("sixel") || term.starts_with("mlterm") {
+        return ImageProtocol::Sixel;
+    }
+
+    ImageProtocol::None
+}
+
+/// Render raw image bytes for the current terminal. Returns either an
+/// escape sequence that draws the image inline, or a note with the path
+/// of a temp file the image was saved to.
+pub fn render_image(data: &[u8], name: &str) -> String {
+    match detect_image_protocol() {
+        ImageProtocol::Kitty => kitty_escape(data),
+        ImageProtocol::Iterm2 => iterm2_escape(data, name),
+        // Sixel needs a pixel-format re-encode we don't do; fall back to a file
+        ImageProtocol::Sixel | ImageProtocol::None => match save_to_temp(data, name) {
+            Ok(path) => format!("[image saved to {}]", path.display()),
+            Err(e) => format!("[failed to save image: {}]", e),
+        },
+    }
+}
+
+/// Kitty graphics protocol: base64 payload in 4KB chunks
+fn kitty_escape(data: &[u8]) -> String {
+    let payload = BASE64.encode(data);
+    let chunks: Vec<&str> = payload
+        .as_bytes()
+        .chunks(4096)
+        .map(|c| std::str::from_utf8(c).unwrap_or_default())
+        .collect();
+
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        if i == 0 {
+            // a=T transmits and displays, f=100 lets kitty detect the format
+            out.push_str(&format!("\x1b_Ga=T,f=100,m={};{}\x1b\\", more, chunk));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    out
+}
+
+/// iTerm2 OSC 1337 inline image escape
+fn iterm2_escape(data: &[u8], name: &str) -> String {
+    let payload = BASE64.encode(data);
+    let encoded_name = BASE64.encode(name.as_bytes());
+    format!(
+        "\x1b]1337;File=name={};size={};inline=1:{}\x07",
+        encoded_name,
+        data.len(),
+        payload
+    )
+}
+
+/// Save image bytes to a temp file and return its path
+pub fn save_to_temp(data: &[u8], name: &str) -> Result<PathBuf> {
+    let dir = env::temp_dir().join("graph_os_images");
+    fs::create_dir_all(&dir).context("Failed to create image temp directory")?;
+
+    // Keep the name unique but recognizable
+    let path = dir.join(format!("{}-{}", uuid::Uuid::new_v4(), sanitize_name(name)));
+    fs::write(&path, data).with_context(|| format!("Failed to write image to {}", path.display()))?;
+
+    Ok(path)
+}
+
+/// Terminal title and bell settings, under `hooks.terminal` in the
+/// config file. The title mirrors what gos is doing so the tmux window
+/// list answers "is it done yet" at a glance; the bell is opt-in per
+/// event for terminals set up to flag activity.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TerminalNotifyConfig {
+    /// Mirror activity into the terminal title (defaults to on)
+    #[serde(default)]
+    pub title: Option<bool>,
+
+    /// Events that ring the bell: "responding", "done", "disconnected",
+    /// or "*" for all of them. The bell only rings while the terminal
+    /// is unfocused.
+    #[serde(default)]
+    pub bell: Vec<String>,
+}
+
+/// Activity states mirrored into the terminal title
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activity {
+    /// A response is streaming or pending
+    Responding,
+    /// The last response finished
+    Done,
+    /// The connection probe lost the endpoint
+    Disconnected,
+    /// Nothing in flight
+    Idle,
+}
+
+impl Activity {
+    /// Event name matched against the `bell` config list
+    pub fn event_name(self) -> &'static str {
+        match self {
+            Activity::Responding => "responding",
+            Activity::Done => "done",
+            Activity::Disconnected => "disconnected",
+            Activity::Idle => "idle",
+        }
+    }
+
+    /// Title shown for this state; accessible mode sticks to plain
+    /// ASCII like the rest of the UI
+    pub fn title(self, accessible: bool) -> &'static str {
+        match (self, accessible) {
+            (Activity::Responding, false) => "gos ● responding…",
+            (Activity::Responding, true) => "gos - responding",
+            (Activity::Done, false) => "gos ✓ done",
+            (Activity::Done, true) => "gos - done",
+            (Activity::Disconnected, false) => "gos ✕ disconnected",
+            (Activity::Disconnected, true) => "gos - disconnected",
+            (Activity::Idle, _) => "gos",
+        }
+    }
+}
+
+/// Pushes activity changes to the terminal: an OSC 2 title update per
+/// state change and, for configured events, the bell. Writes straight
+/// to stdout; the sequences are invisible to ratatui so they can be
+/// emitted from anywhere in the chat loop.
+#[derive(Debug, Clone, Default)]
+pub struct TerminalNotifier {
+    config: TerminalNotifyConfig,
+    accessible: bool,
+    /// Last state pushed, so repeated events stay silent
+    last: Option<Activity>,
+}
+
+impl TerminalNotifier {
+    pub fn new(config: TerminalNotifyConfig, accessible: bool) -> Self {
+        Self { config, accessible, last: None }
+    }
+
+    /// Whether the bell list covers this state
+    pub fn bell_configured(&self, activity: Activity) -> bool {
+        self.config
+            .bell
+            .iter()
+            .any(|event| event == "*" || event == activity.event_name())
+    }
+
+    /// Push a state change to the terminal. The bell only rings when
+    /// the terminal is unfocused — a focused user is already watching.
+    pub fn notify(&mut self, activity: Activity, focused: bool) {
+        if self.last == Some(activity) {
+            return;
+        }
+        self.last = Some(activity);
+
+        let mut out = String::new();
+        if self.config.title.unwrap_or(true) {
+            out.push_str(&format!("\x1b]2;{}\x07", activity.title(self.accessible)));
+        }
+        if !focused && self.bell_configured(activity) {
+            out.push('\x07');
+        }
+        if !out.is_empty() {
+            use std::io::Write;
+            let mut stdout = std::io::stdout();
+            let _ = stdout.write_all(out.as_bytes());
+            let _ = stdout.flush();
+        }
+    }
+}
+
+/// Strip path separators and control characters from a file name
+fn sanitize_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '.' | '-' | '_'))
+        .collect();
+
+    if cleaned.is_empty() {
+        "image.png".to_string()
+    } else {
+        cleaned
+    }
+}
@@ -0,0 +1,169 @@
+//! SSH port-forward tunnels for reaching GraphOS hosts behind bastions.
+//!
+//! `--via ssh://user@bastion` opens local forwards to the API and gRPC
+//! ports through the bastion using the system `ssh` (so existing keys,
+//! agents and ~/.ssh/config all apply), then the normal clients dial
+//! 127.0.0.1 and the forward carries the traffic. The ssh process lives
+//! as long as the [`SshTunnel`] guard; dropping it tears the forwards
+//! down with the clients that used them. SOCKS5 proxies need no
+//! forwarding: point the endpoint's `proxy` option at a socks5:// URL.
+
+use std::net::TcpListener;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+
+/// How long to wait for the first forward to accept connections before
+/// declaring the tunnel dead
+const TUNNEL_READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A parsed `--via` destination: `ssh://[user@]host[:port]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TunnelSpec {
+    pub user: Option<String>,
+    pub host: String,
+    /// SSH port on the bastion, when not the default 22
+    pub port: Option<u16>,
+}
+
+impl TunnelSpec {
+    /// Parse a `--via` value. Only the ssh:// scheme is a tunnel;
+    /// anything else is rejected with a pointer at the proxy option.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let rest = spec.strip_prefix("ssh://").ok_or_else(|| {
+            anyhow!(
+                "Unsupported --via spec '{}': expected ssh://[user@]host[:port] \
+                 (for a SOCKS5 proxy, set the endpoint's proxy option to a socks5:// URL)",
+                spec
+            )
+        })?;
+
+        let (user, rest) = match rest.split_once('@') {
+            Some((user, rest)) if !user.is_empty() => (Some(user.to_string()), rest),
+            Some(_) => bail!("Invalid --via spec '{}': empty user", spec),
+            None => (None, rest),
+        };
+
+        let (host, port) = match rest.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = port
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid --via spec '{}': bad port '{}'", spec, port))?;
+                (host, Some(port))
+            }
+            None => (rest, None),
+        };
+        if host.is_empty() {
+            bail!("Invalid --via spec '{}': missing host", spec);
+        }
+
+        Ok(TunnelSpec { user, host: host.to_string(), port })
+    }
+
+    /// The destination argument handed to ssh
+    fn destination(&self) -> String {
+        match &self.user {
+            Some(user) => format!("{}@{}", user, self.host),
+            None => self.host.clone(),
+        }
+    }
+}
+
+/// A running ssh process carrying one local forward per target port.
+/// Dropping the guard kills the process and closes the forwards.
+#[derive(Debug)]
+pub struct SshTunnel {
+    child: Child,
+    /// (remote port, local forward port) pairs
+    forwards: Vec<(u16, u16)>,
+}
+
+impl SshTunnel {
+    /// Open forwards to the given ports on the target host, through the
+    /// bastion. Returns once the forwards accept connections.
+    pub async fn open(spec: &TunnelSpec, target_host: &str, remote_ports: &[u16]) -> Result<Self> {
+        // One local listener per distinct remote port, all carried by a
+        // single ssh process
+        let mut forwards: Vec<(u16, u16)> = Vec::new();
+        for &remote in remote_ports {
+            if forwards.iter().any(|(r, _)| *r == remote) {
+                continue;
+            }
+            forwards.push((remote, free_local_port()?));
+        }
+
+        let mut command = Command::new("ssh");
+        // BatchMode keeps a missing key from hanging on a password
+        // prompt; ExitOnForwardFailure turns a refused forward into a
+        // clean exit we can report
+        command
+            .arg("-N")
+            .args(["-o", "BatchMode=yes"])
+            .args(["-o", "ExitOnForwardFailure=yes"]);
+        if let Some(port) = spec.port {
+            command.args(["-p", &port.to_string()]);
+        }
+        for (remote, local) in &forwards {
+            command.args(["-L", &format!("127.0.0.1:{}:{}:{}", local, target_host, remote)]);
+        }
+        command
+            .arg(spec.destination())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null());
+
+        let child = command
+            .spawn()
+            .context("Failed to start ssh; is the OpenSSH client installed?")?;
+        let mut tunnel = SshTunnel { child, forwards };
+
+        // Wait for every forward to accept a connection, bailing out
+        // early if ssh gives up (bad host, refused forward, no key)
+        let deadline = tokio::time::Instant::now() + TUNNEL_READY_TIMEOUT;
+        for (remote, local) in tunnel.forwards.clone() {
+            loop {
+                if let Some(status) = tunnel.child.try_wait()? {
+                    bail!("ssh exited with {} before the tunnel came up", status);
+                }
+                if tokio::net::TcpStream::connect(("127.0.0.1", local)).await.is_ok() {
+                    break;
+                }
+                if tokio::time::Instant::now() >= deadline {
+                    bail!(
+                        "Tunnel to port {} via {} did not come up within {:?}",
+                        remote,
+                        spec.host,
+                        TUNNEL_READY_TIMEOUT
+                    );
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+
+        Ok(tunnel)
+    }
+
+    /// The local forward port carrying traffic to a remote port
+    pub fn local_port(&self, remote: u16) -> Option<u16> {
+        self.forwards
+            .iter()
+            .find(|(r, _)| *r == remote)
+            .map(|(_, local)| *local)
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        // Already-exited processes make kill a no-op; wait reaps the
+        // zombie either way
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Ask the kernel for a free port by binding port 0 and letting the
+/// listener go; ssh rebinds it for the forward
+fn free_local_port() -> Result<u16> {
+    let listener = TcpListener::bind("127.0.0.1:0").context("Failed to probe for a free port")?;
+    Ok(listener.local_addr()?.port())
+}
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Price per 1000 tokens for a model, configured under `prices` in the
+/// config file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input_per_1k: f64,
+    pub output_per_1k: f64,
+}
+
+/// One priced exchange with a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub timestamp: DateTime<Utc>,
+    pub provider: String,
+    pub model: String,
+    pub session_id: Option<Uuid>,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Cost in dollars; zero when the model has no configured price
+    pub cost: f64,
+}
+
+/// Append-only JSONL ledger of usage records
+#[derive(Debug)]
+pub struct UsageLog {
+    log_path: PathBuf,
+    writer: Mutex<()>,
+}
+
+impl UsageLog {
+    /// Get the shared usage log instance
+    pub fn instance() -> &'static Self {
+        static INSTANCE: std::sync::OnceLock<UsageLog> = std::sync::OnceLock::new();
+        INSTANCE.get_or_init(|| UsageLog {
+            log_path: Self::default_log_path(),
+            writer: Mutex::new(()),
+        })
+    }
+
+    /// Default location of the usage log in the state directory
+    fn default_log_path() -> PathBuf {
+        crate::paths::state_dir().join("usage.jsonl")
+    }
+
+    /// Create a usage log at a custom location (used by tests and tooling)
+    pub fn with_path(path: PathBuf) -> Self {
+        UsageLog {
+            log_path: path,
+            writer: Mutex::new(()),
+        }
+    }
+
+    /// Record a usage entry; failures to write are reported but never fatal
+    pub fn record(&self, record: &UsageRecord) {
+        if let Err(e) = self.append(record) {
+            eprintln!("Failed to write usage record: {}", e);
+        }
+    }
+
+    fn append(&self, record: &UsageRecord) -> Result<()> {
+        let _guard = self.writer.lock().map_err(|_| anyhow!("Usage log lock poisoned"))?;
+
+        if let Some(parent) = self.log_path.parent() {
+            fs::create_dir_all(parent).context("Failed to create usage log directory")?;
+        }
+
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .with_context(|| format!("Failed to open usage log: {}", self.log_path.display()))?;
+        file.write_all(line.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Read records newer than `since` ago, oldest first
+    pub fn read_since(&self, since: Option<Duration>) -> Result<Vec<UsageRecord>> {
+        let cutoff = since.map(|d| Utc::now() - chrono::Duration::from_std(d).unwrap_or_default());
+        let mut records = Vec::new();
+
+        if !self.log_path.exists() {
+            return Ok(records);
+        }
+
+        let content = fs::read_to_string(&self.log_path)
+            .with_context(|| format!("Failed to read usage log: {}", self.log_path.display()))?;
+
+        for line in content.lines() {
+            if line.is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<UsageRecord>(line) {
+                Ok(record) => {
+                    if cutoff.is_none_or(|c| record.timestamp >= c) {
+                        records.push(record);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Skipping malformed usage record: {}", e);
+                }
+            }
+        }
+
+        Ok(records)
+    }
+}
+
+/// Rough token estimate for providers that do not report usage:
+/// about four characters per token
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4).max(1)
+}
+
+/// Cost of one exchange given the configured price table. Models without
+/// a price entry cost zero so unconfigured setups never show made-up
+/// numbers.
+pub fn cost_for(
+    prices: &HashMap<String, ModelPrice>,
+    model: &str,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+) -> f64 {
+    match prices.get(model) {
+        Some(price) => {
+            (prompt_tokens as f64 / 1000.0) * price.input_per_1k
+                + (completion_tokens as f64 / 1000.0) * price.output_per_1k
+        }
+        None => 0.0,
+    }
+}
@@ -0,0 +1,142 @@
+//! Read-only spectate mode: `gos watch <session-id>` opens a view-only
+//! TUI that live-tails a session while another process (or the daemon)
+//! appends to it. Updates arrive as pushes over the session listener's
+//! subscribe command — see [`SessionManager::subscribe_session`] — so a
+//! quiet session costs nothing to watch.
+
+use std::time::Duration;
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::render::RenderStyle;
+use crate::session::{Session, SessionManager};
+
+/// State of the spectator view: the latest pushed snapshot plus where
+/// the user has scrolled to
+pub struct WatchApp {
+    pub session: Session,
+    /// Lines scrolled up from the bottom; 0 follows new messages
+    pub scroll_back: u16,
+    /// How many updates have been pushed since the watch started
+    pub updates: usize,
+    pub style: RenderStyle,
+}
+
+impl WatchApp {
+    pub fn new(session: Session, style: RenderStyle) -> Self {
+        WatchApp { session, scroll_back: 0, updates: 0, style }
+    }
+
+    /// Replace the snapshot with a pushed update. A view pinned to the
+    /// bottom stays pinned; a scrolled-back one keeps its place.
+    pub fn apply(&mut self, session: Session) {
+        self.session = session;
+        self.updates += 1;
+    }
+}
+
+/// Run the spectator TUI until q or Esc. The view never writes: no
+/// lease is taken and the watched process keeps exclusive ownership.
+pub async fn run(id: Uuid) -> anyhow::Result<()> {
+    let manager = SessionManager::init().await?;
+    let (session, updates) = manager.subscribe_session(id).await?;
+
+    let config = crate::config::ConfigManager::instance().get_config().await?;
+    let mut app = WatchApp::new(session, RenderStyle::detect(config.accessible()));
+
+    let mut terminal = crate::chat::setup_terminal()?;
+    // The guard restores the terminal even if the loop errors out
+    let _guard = crate::chat::TerminalGuard;
+    event_loop(&mut terminal, &mut app, updates).await
+}
+
+/// Draw, drain pushed updates, and handle local keys. The short poll
+/// below only serves the keyboard; session changes are push-based.
+async fn event_loop(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    app: &mut WatchApp,
+    mut updates: mpsc::Receiver<Session>,
+) -> anyhow::Result<()> {
+    loop {
+        while let Ok(session) = updates.try_recv() {
+            app.apply(session);
+        }
+
+        terminal.draw(|frame| ui(frame, app))?;
+
+        if crossterm::event::poll(Duration::from_millis(100))?
+            && let Event::Key(key) = crossterm::event::read()?
+        {
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Up => app.scroll_back = app.scroll_back.saturating_add(1),
+                KeyCode::Down => app.scroll_back = app.scroll_back.saturating_sub(1),
+                KeyCode::PageUp => app.scroll_back = app.scroll_back.saturating_add(10),
+                KeyCode::PageDown => app.scroll_back = app.scroll_back.saturating_sub(10),
+                // End snaps back to following the tail
+                KeyCode::End => app.scroll_back = 0,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Draw the spectator view: the transcript with a status line under it
+pub fn ui(frame: &mut Frame, app: &WatchApp) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(frame.area());
+
+    let mut lines: Vec<Line> = Vec::new();
+    for message in &app.session.messages {
+        let role_style = match message.role() {
+            "user" => app.style.fg(Color::Cyan),
+            _ => app.style.fg(Color::Green),
+        };
+        lines.push(Line::from(Span::styled(format!("{}:", message.role()), role_style)));
+        for text_line in message.text().lines() {
+            lines.push(Line::from(text_line.to_string()));
+        }
+        lines.push(Line::default());
+    }
+
+    // Pin the viewport to the bottom, then back it off by however far
+    // the user has scrolled up
+    let viewport = chunks[0].height.saturating_sub(2);
+    let bottom = (lines.len() as u16).saturating_sub(viewport);
+    let scroll = bottom.saturating_sub(app.scroll_back);
+
+    let title = match &app.session.title {
+        Some(title) => format!("Watching \"{}\" ({})", title, app.session.id),
+        None => format!("Watching session {}", app.session.id),
+    };
+    let transcript = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+    frame.render_widget(transcript, chunks[0]);
+
+    let position = if app.scroll_back == 0 {
+        "following".to_string()
+    } else {
+        format!("scrolled back {} (End to follow)", app.scroll_back)
+    };
+    let status = format!(
+        "read-only | {} messages | {} update(s) | last active {} | {} | q quits",
+        app.session.messages.len(),
+        app.updates,
+        app.session.last_active.format("%H:%M:%S"),
+        position
+    );
+    frame.render_widget(Paragraph::new(status), chunks[1]);
+}
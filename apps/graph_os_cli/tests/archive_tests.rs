@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod archive_tests {
+    use std::time::Duration;
+
+    use graph_os_cli::archive::{bundle, parse_age, unbundle};
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let contents = br#"{"id":"abc","messages":[{"role":"user","text":"hello"},{"role":"assistant","text":"hello to you"}]}"#;
+
+        let bundled = bundle(contents);
+        assert_eq!(unbundle(&bundled).unwrap(), contents.to_vec());
+    }
+
+    #[test]
+    fn test_bundle_compresses_repetitive_input() {
+        // Session JSON repeats keys constantly; the codec should beat
+        // the raw size comfortably on this kind of input
+        let contents = r#"{"role":"user","text":"hi"},"#.repeat(100);
+        let bundled = bundle(contents.as_bytes());
+
+        assert!(bundled.len() < contents.len() / 2);
+        assert_eq!(unbundle(&bundled).unwrap(), contents.into_bytes());
+    }
+
+    #[test]
+    fn test_bundle_roundtrip_binary() {
+        // Encrypted session files are opaque bytes, not JSON
+        let contents: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        assert_eq!(unbundle(&bundle(&contents)).unwrap(), contents);
+    }
+
+    #[test]
+    fn test_unbundle_rejects_garbage() {
+        assert!(unbundle(b"not a bundle").is_err());
+        assert!(unbundle(b"").is_err());
+
+        // Right magic, wrong version
+        let mut bundled = bundle(b"data");
+        bundled[4] = 99;
+        assert!(unbundle(&bundled).is_err());
+
+        // Truncated stream
+        let bundled = bundle(b"some session contents here");
+        assert!(unbundle(&bundled[..bundled.len() - 3]).is_err());
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("90d").unwrap(), Duration::from_secs(90 * 86400));
+        assert_eq!(parse_age("12h").unwrap(), Duration::from_secs(12 * 3600));
+        assert_eq!(parse_age("30m").unwrap(), Duration::from_secs(30 * 60));
+        // A bare number means days
+        assert_eq!(parse_age("7").unwrap(), Duration::from_secs(7 * 86400));
+
+        assert!(parse_age("7w").is_err());
+        assert!(parse_age("soon").is_err());
+    }
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod audit_tests {
+    use std::time::Duration;
+    use chrono::Utc;
+    use serde_json::json;
+    use graph_os_cli::audit::{parse_duration, redact_params, AuditLog, AuditRecord};
+
+    #[test]
+    fn test_redact_params() {
+        let params = json!({
+            "model": "gpt-4o",
+            "api_key": "sk-very-secret",
+            "nested": { "token": "abc", "limit": 5 },
+        });
+        
+        let redacted = redact_params(&params);
+        
+        assert_eq!(redacted["model"], "gpt-4o");
+        assert_eq!(redacted["api_key"], "[redacted]");
+        assert_eq!(redacted["nested"]["token"], "[redacted]");
+        assert_eq!(redacted["nested"]["limit"], 5);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("15m").unwrap(), Duration::from_secs(900));
+        assert_eq!(parse_duration("1h").unwrap(), Duration::from_secs(3600));
+        assert_eq!(parse_duration("2d").unwrap(), Duration::from_secs(172800));
+        assert!(parse_duration("10x").is_err());
+        assert!(parse_duration("abc").is_err());
+    }
+
+    #[test]
+    fn test_append_and_read() {
+        let dir = std::env::temp_dir().join(format!("gos-audit-test-{}", uuid::Uuid::new_v4()));
+        let log = AuditLog::with_path(dir.join("audit.jsonl"));
+        
+        let record = AuditRecord {
+            timestamp: Utc::now(),
+            transport: "jsonrpc".to_string(),
+            method: "chat".to_string(),
+            endpoint: "http://localhost:4000/api/jsonrpc".to_string(),
+            params: json!({"stream": true}),
+            latency_ms: 42,
+            status: "ok".to_string(),
+            token_usage: None,
+        };
+        
+        log.record(&record);
+        log.record(&record);
+        
+        let records = log.read_since(None).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].method, "chat");
+        
+        // A cutoff in the future filters everything out
+        let records = log.read_since(Some(Duration::from_secs(0))).unwrap();
+        assert!(records.len() <= 2);
+        
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod auth_tests {
+    use graph_os_cli::auth::{is_token_expired, token_set_from_response};
+    use serde_json::json;
+
+    #[test]
+    fn test_token_set_from_response() {
+        let response = json!({
+            "access_token": "at-123",
+            "refresh_token": "rt-456",
+            "expires_in": 3600,
+            "token_type": "Bearer",
+        });
+
+        let tokens = token_set_from_response(&response, 1_000).unwrap();
+        assert_eq!(tokens.access_token, "at-123");
+        assert_eq!(tokens.refresh_token.as_deref(), Some("rt-456"));
+        assert_eq!(tokens.expires_at, Some(4_600));
+
+        // A refresh response may omit the refresh token and expiry
+        let minimal = json!({ "access_token": "at-789" });
+        let tokens = token_set_from_response(&minimal, 1_000).unwrap();
+        assert_eq!(tokens.refresh_token, None);
+        assert_eq!(tokens.expires_at, None);
+    }
+
+    #[test]
+    fn test_token_set_from_error_response() {
+        let denied = json!({
+            "error": "access_denied",
+            "error_description": "the user declined",
+        });
+
+        let err = token_set_from_response(&denied, 0).unwrap_err().to_string();
+        assert!(err.contains("access_denied"));
+        assert!(err.contains("the user declined"));
+
+        // No access token at all is also an error
+        assert!(token_set_from_response(&json!({}), 0).is_err());
+    }
+
+    #[test]
+    fn test_is_token_expired() {
+        // No recorded expiry: assume valid until the server says otherwise
+        assert!(!is_token_expired(None, 1_000));
+
+        // Comfortably in the future
+        assert!(!is_token_expired(Some(10_000), 1_000));
+
+        // Already past, and within the refresh leeway window
+        assert!(is_token_expired(Some(500), 1_000));
+        assert!(is_token_expired(Some(1_030), 1_000));
+    }
+}
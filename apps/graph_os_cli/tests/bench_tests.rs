@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod bench_tests {
+    use std::time::Duration;
+
+    use graph_os_cli::bench::{BenchMethod, BenchReport, LatencyHistogram};
+
+    #[test]
+    fn test_histogram_percentiles() {
+        let mut histogram = LatencyHistogram::new();
+        for ms in 1..=100 {
+            histogram.record(Duration::from_millis(ms));
+        }
+
+        assert_eq!(histogram.len(), 100);
+        assert_eq!(histogram.percentile(50.0), Some(Duration::from_millis(50)));
+        assert_eq!(histogram.percentile(99.0), Some(Duration::from_millis(99)));
+        assert_eq!(histogram.percentile(100.0), Some(Duration::from_millis(100)));
+        assert_eq!(histogram.min(), Some(Duration::from_millis(1)));
+        assert_eq!(histogram.max(), Some(Duration::from_millis(100)));
+
+        // An empty histogram has no percentiles rather than panicking
+        let empty = LatencyHistogram::new();
+        assert_eq!(empty.percentile(50.0), None);
+        assert_eq!(empty.mean(), None);
+    }
+
+    #[test]
+    fn test_histogram_merge() {
+        let mut left = LatencyHistogram::new();
+        left.record(Duration::from_millis(10));
+
+        let mut right = LatencyHistogram::new();
+        right.record(Duration::from_millis(30));
+
+        left.merge(&right);
+        assert_eq!(left.len(), 2);
+        assert_eq!(left.mean(), Some(Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn test_report_render() {
+        let mut report = BenchReport::default();
+        report.histogram.record(Duration::from_millis(5));
+        report.histogram.record(Duration::from_millis(15));
+        report.errors.insert("transport error: connection refused".to_string(), 3);
+        report.elapsed = Duration::from_secs(1);
+
+        assert_eq!(report.total_requests(), 5);
+
+        let rendered = report.render("SystemInfoService.GetSystemInfo", 4);
+        assert!(rendered.contains("5 total, 2 ok, 3 failed"));
+        assert!(rendered.contains("5.0 req/s"));
+        assert!(rendered.contains("3  transport error: connection refused"));
+    }
+
+    #[test]
+    fn test_method_parse() {
+        assert_eq!(BenchMethod::parse("get").unwrap(), BenchMethod::GetSystemInfo);
+        assert_eq!(BenchMethod::parse("LIST").unwrap(), BenchMethod::ListSystemInfo);
+        assert!(BenchMethod::parse("chat").is_err());
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod bridge_tests {
+    use graph_os_cli::adapters::grpc::graph_os::SystemInfo;
+    use graph_os_cli::adapters::{GrpcAuth, GrpcClient};
+    use graph_os_cli::bridge;
+
+    #[test]
+    fn system_info_maps_every_field() {
+        let info = SystemInfo {
+            id: "abc".to_string(),
+            hostname: "box1".to_string(),
+            timestamp: 1_700_000_000,
+            cpu_count: 8,
+            cpu_load_1m: 0.5,
+            cpu_load_5m: 0.4,
+            cpu_load_15m: 0.3,
+            memory_total: 1024,
+            memory_used: 512,
+            memory_free: 512,
+            uptime: 3600,
+            os_version: "14.0".to_string(),
+            platform: "linux".to_string(),
+            architecture: "x86_64".to_string(),
+        };
+
+        let value = bridge::system_info_json(&info);
+        assert_eq!(value["hostname"], "box1");
+        assert_eq!(value["timestamp"], 1_700_000_000);
+        assert_eq!(value["cpu_count"], 8);
+        assert_eq!(value["cpu_load_1m"], 0.5);
+        assert_eq!(value["memory_used"], 512);
+        assert_eq!(value["architecture"], "x86_64");
+        // The mapping should be exhaustive over the proto's fields
+        assert_eq!(value.as_object().unwrap().len(), 14);
+    }
+
+    #[tokio::test]
+    async fn failing_upstream_reports_bad_gateway() {
+        // Point the bridge at the dev JSON-RPC server, which speaks no
+        // gRPC; the route should answer with a JSON error rather than
+        // hanging or 500ing
+        let (upstream, upstream_server) = graph_os_cli::serve::bind("127.0.0.1", 0, None, None).unwrap();
+        tokio::spawn(upstream_server);
+        let client = GrpcClient::with_endpoints_auth(
+            vec![format!("http://{}", upstream)],
+            GrpcAuth::default(),
+        )
+        .await
+        .unwrap();
+        let (addr, server) = bridge::bind("127.0.0.1", 0, client).unwrap();
+        tokio::spawn(server);
+
+        let response = reqwest::get(format!("http://{}/v1/system-info", addr))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_GATEWAY);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert!(body["error"].is_string());
+    }
+}
@@ -0,0 +1,245 @@
+#[cfg(test)]
+mod chat_tests {
+    use std::time::{Duration, Instant};
+
+    use graph_os_cli::chat::{agent_color, expand_aliases, model_suggestions, sanitize_paste, Command, LayoutMode, StreamTelemetry};
+
+    fn models(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_model_suggestions_substring_first() {
+        let available = models(&["gpt-4o", "gpt-4o-mini", "o3-mini", "claude-3-opus"]);
+
+        // A partial name surfaces every model containing it
+        let suggestions = model_suggestions("gpt-4o", &available);
+        assert_eq!(suggestions, vec!["gpt-4o".to_string(), "gpt-4o-mini".to_string()]);
+
+        // Prefix overlap catches near-misses without a substring match
+        let suggestions = model_suggestions("gpt-4x", &available);
+        assert_eq!(suggestions, vec!["gpt-4o".to_string(), "gpt-4o-mini".to_string()]);
+    }
+
+    #[test]
+    fn test_model_suggestions_limits_and_misses() {
+        let available = models(&["gemini-pro", "gemini-flash", "gemini-ultra", "gemini-nano"]);
+
+        // At most three suggestions come back even with more candidates
+        let suggestions = model_suggestions("gemini", &available);
+        assert_eq!(suggestions.len(), 3);
+
+        // Nothing similar means no suggestions rather than noise
+        let suggestions = model_suggestions("mistral-large", &available);
+        assert!(suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_stream_telemetry_first_token_and_average() {
+        let start = Instant::now();
+        let mut telemetry = StreamTelemetry::new(start);
+
+        assert_eq!(telemetry.first_token_ms(), None);
+        assert!(telemetry.average_tokens_per_sec(start).is_none());
+
+        // 24 estimated tokens arriving over two seconds, first after 500ms
+        telemetry.on_chunk(&"a".repeat(48), start + Duration::from_millis(500));
+        telemetry.on_chunk(&"b".repeat(48), start + Duration::from_millis(2500));
+
+        assert_eq!(telemetry.first_token_ms(), Some(500));
+        let average = telemetry
+            .average_tokens_per_sec(start + Duration::from_millis(2500))
+            .unwrap();
+        assert!((average - 12.0).abs() < 0.1, "average was {}", average);
+    }
+
+    #[test]
+    fn test_stream_telemetry_rolling_rate_uses_recent_window() {
+        let start = Instant::now();
+        let mut telemetry = StreamTelemetry::new(start);
+
+        // A fast burst long ago, then a slow trickle; the rolling rate
+        // should reflect the trickle, not the burst
+        telemetry.on_chunk(&"a".repeat(400), start + Duration::from_secs(1));
+        telemetry.on_chunk(&"b".repeat(4), start + Duration::from_secs(10));
+        telemetry.on_chunk(&"c".repeat(4), start + Duration::from_secs(12));
+
+        let now = start + Duration::from_secs(12);
+        let rolling = telemetry.rolling_tokens_per_sec(now).unwrap();
+        assert!(rolling < 2.0, "rolling was {}", rolling);
+
+        let average = telemetry.average_tokens_per_sec(now).unwrap();
+        assert!(average > 5.0, "average was {}", average);
+    }
+
+    #[test]
+    fn test_stream_telemetry_indicator() {
+        let start = Instant::now();
+        let mut telemetry = StreamTelemetry::new(start);
+
+        // Nothing arrived yet: show how long we have been waiting
+        assert_eq!(telemetry.indicator(start + Duration::from_millis(1500)), "waiting 1.5s");
+
+        telemetry.on_chunk(&"a".repeat(40), start + Duration::from_secs(1));
+        telemetry.on_chunk(&"b".repeat(40), start + Duration::from_secs(2));
+        let indicator = telemetry.indicator(start + Duration::from_secs(2));
+        assert!(indicator.ends_with("tok/s"), "indicator was {}", indicator);
+    }
+
+    #[test]
+    fn test_sanitize_paste_normalizes_line_endings() {
+        assert_eq!(sanitize_paste("one\r\ntwo\rthree\n"), "one\ntwo\nthree\n");
+    }
+
+    #[test]
+    fn test_sanitize_paste_strips_ansi_sequences() {
+        // CSI color codes and an OSC title sequence, as leaked by
+        // copying from a colored terminal
+        let pasted = "\x1b[31mred\x1b[0m text \x1b]0;title\x07tail";
+        assert_eq!(sanitize_paste(pasted), "red text tail");
+    }
+
+    #[test]
+    fn test_sanitize_paste_keeps_tabs_drops_controls() {
+        assert_eq!(sanitize_paste("a\tb\x08\x00c"), "a\tbc");
+    }
+
+    #[test]
+    fn test_agents_command_parsing() {
+        assert!(matches!(Command::from_input("/agents"), Some(Command::Agents(None))));
+        assert!(matches!(
+            Command::from_input("/agents reviewer"),
+            Some(Command::Agents(Some(arg))) if arg == "reviewer"
+        ));
+        assert!(matches!(
+            Command::from_input("/agents reviewer,critic"),
+            Some(Command::Agents(Some(arg))) if arg == "reviewer,critic"
+        ));
+        assert!(matches!(
+            Command::from_input("/agents off"),
+            Some(Command::Agents(Some(arg))) if arg == "off"
+        ));
+    }
+
+    #[test]
+    fn test_pin_command_parsing() {
+        assert!(matches!(Command::from_input("/pin"), Some(Command::Pin(None))));
+        assert!(matches!(Command::from_input("/pin 3"), Some(Command::Pin(Some(3)))));
+        assert!(matches!(Command::from_input("/unpin"), Some(Command::Unpin(None))));
+        assert!(matches!(Command::from_input("/unpin 3"), Some(Command::Unpin(Some(3)))));
+        // A non-numeric argument is not a pin command
+        assert!(matches!(Command::from_input("/pin last"), Some(Command::Unknown(_))));
+    }
+
+    #[test]
+    fn test_quote_command_parsing() {
+        assert!(matches!(Command::from_input("/quote"), Some(Command::Quote(None))));
+        assert!(matches!(Command::from_input("/quote 2"), Some(Command::Quote(Some(2)))));
+        // A non-numeric argument is not a quote command
+        assert!(matches!(Command::from_input("/quote that"), Some(Command::Unknown(_))));
+    }
+
+    #[test]
+    fn test_alias_expansion() {
+        use std::collections::HashMap;
+
+        let mut aliases = HashMap::new();
+        aliases.insert("s".to_string(), "/stream".to_string());
+        aliases.insert("gpt".to_string(), "/provider openai; /model gpt-4o".to_string());
+        aliases.insert("g".to_string(), "/gpt".to_string());
+
+        // Non-alias input passes through untouched
+        assert_eq!(expand_aliases("/fork", &aliases).unwrap(), vec!["/fork"]);
+
+        // A sequence expands in order; aliases may reference aliases
+        assert_eq!(
+            expand_aliases("/g", &aliases).unwrap(),
+            vec!["/provider openai", "/model gpt-4o"]
+        );
+
+        // Typed arguments ride on the last command of the expansion
+        aliases.insert("m".to_string(), "/model".to_string());
+        assert_eq!(expand_aliases("/m gpt-4o", &aliases).unwrap(), vec!["/model gpt-4o"]);
+
+        // Cycles error instead of looping
+        aliases.insert("a".to_string(), "/b".to_string());
+        aliases.insert("b".to_string(), "/a".to_string());
+        let err = expand_aliases("/a", &aliases).unwrap_err();
+        assert!(err.contains("cycle"), "unexpected error: {}", err);
+
+        // An expansion that is not a slash command is an error too
+        aliases.insert("bad".to_string(), "hello".to_string());
+        assert!(expand_aliases("/bad", &aliases).is_err());
+    }
+
+    #[test]
+    fn test_macro_command_parsing() {
+        assert!(matches!(Command::from_input("/macro"), Some(Command::Macro(None))));
+        assert!(matches!(
+            Command::from_input("/macro record setup"),
+            Some(Command::Macro(Some(arg))) if arg == "record setup"
+        ));
+        // Play arguments keep their case: placeholder values are
+        // replayed verbatim
+        assert!(matches!(
+            Command::from_input("/macro play setup Model=GPT-4o"),
+            Some(Command::Macro(Some(arg))) if arg == "play setup Model=GPT-4o"
+        ));
+    }
+
+    #[test]
+    fn test_tokens_command_parsing() {
+        assert!(matches!(Command::from_input("/tokens"), Some(Command::Tokens)));
+        assert!(matches!(Command::from_input("/TOKENS"), Some(Command::Tokens)));
+    }
+
+    #[test]
+    fn test_layout_command_parsing() {
+        assert!(matches!(Command::from_input("/layout"), Some(Command::Layout(None))));
+        assert!(matches!(
+            Command::from_input("/layout compact"),
+            Some(Command::Layout(Some(mode))) if mode == "compact"
+        ));
+
+        // Mode names round-trip through the config file; anything
+        // unrecognized there falls back to the default instead of erroring
+        assert_eq!(LayoutMode::parse("compact"), Some(LayoutMode::Compact));
+        assert_eq!(LayoutMode::parse(LayoutMode::Comfortable.as_str()), Some(LayoutMode::Comfortable));
+        assert_eq!(LayoutMode::parse("cozy"), None);
+    }
+
+    #[test]
+    fn test_agent_color_is_stable_per_name() {
+        assert_eq!(agent_color("reviewer"), agent_color("reviewer"));
+        // Different names usually land on different palette slots;
+        // these two are known not to collide
+        assert_ne!(agent_color("reviewer"), agent_color("critic"));
+    }
+
+    #[test]
+    fn test_response_history_window_and_segment() {
+        use graph_os_cli::chat::{ResponseHistory, SPARKLINE_SAMPLES};
+        use graph_os_cli::render::RenderStyle;
+
+        let mut history = ResponseHistory::default();
+        assert!(history.status_segment(&RenderStyle::new(true)).is_none());
+
+        // Overfill the window; only the newest samples survive
+        for i in 0..SPARKLINE_SAMPLES + 5 {
+            history.push(i as u64 * 100, if i % 2 == 0 { Some(i as f64) } else { None });
+        }
+        let latencies = history.latencies();
+        assert_eq!(latencies.len(), SPARKLINE_SAMPLES);
+        assert_eq!(latencies[0], 500.0);
+
+        // Non-streaming responses carry no token rate
+        assert!(history.token_rates().len() < SPARKLINE_SAMPLES);
+
+        // The segment names both series and ends with the newest values
+        let segment = history.status_segment(&RenderStyle::new(true)).unwrap();
+        assert!(segment.starts_with("lat "));
+        assert!(segment.contains("2400ms"));
+        assert!(segment.contains("tok/s "));
+        assert!(segment.is_ascii());
+    }
+}
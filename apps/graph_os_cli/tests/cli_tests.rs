@@ -1,31 +1,31 @@
 #[cfg(test)]
 mod cli_tests {
     use clap::Parser;
-    use graph_os_cli::cli::{Cli, Commands, ConfigCommands};
+    use graph_os_cli::cli::{Cli, Commands, ConfigCommands, DaemonCommands};
     
     #[test]
     fn test_cli_basic_options() {
-        let cli = Cli::parse_from(&["gos", "--api-host", "test.example.com", "--api-port", "4321"]);
+        let cli = Cli::parse_from(["gos", "--api-host", "test.example.com", "--api-port", "4321"]);
         
         assert_eq!(cli.api_host, "test.example.com");
         assert_eq!(cli.api_port, 4321);
-        assert_eq!(cli.use_https, false);
+        assert!(!cli.use_https);
         assert_eq!(cli.provider, None);
         assert_eq!(cli.model, None);
         assert_eq!(cli.session, None);
-        assert!(matches!(cli.command, None));
+        assert!(cli.command.is_none());
     }
     
     #[test]
     fn test_cli_https_flag() {
-        let cli = Cli::parse_from(&["gos", "--use-https"]);
+        let cli = Cli::parse_from(["gos", "--use-https"]);
         
-        assert_eq!(cli.use_https, true);
+        assert!(cli.use_https);
     }
     
     #[test]
     fn test_cli_provider_and_model() {
-        let cli = Cli::parse_from(&["gos", "--provider", "anthropic", "--model", "claude-3-opus"]);
+        let cli = Cli::parse_from(["gos", "--provider", "anthropic", "--model", "claude-3-opus"]);
         
         assert_eq!(cli.provider, Some("anthropic".to_string()));
         assert_eq!(cli.model, Some("claude-3-opus".to_string()));
@@ -33,7 +33,7 @@ mod cli_tests {
     
     #[test]
     fn test_cli_session() {
-        let cli = Cli::parse_from(&["gos", "--session", "123e4567-e89b-12d3-a456-426614174000"]);
+        let cli = Cli::parse_from(["gos", "--session", "123e4567-e89b-12d3-a456-426614174000"]);
         
         assert!(cli.session.is_some());
         assert_eq!(cli.session.unwrap().to_string(), "123e4567-e89b-12d3-a456-426614174000");
@@ -41,25 +41,104 @@ mod cli_tests {
     
     #[test]
     fn test_cli_list_command() {
-        let cli = Cli::parse_from(&["gos", "list"]);
-        
-        assert!(matches!(cli.command, Some(Commands::List)));
+        let cli = Cli::parse_from(["gos", "list"]);
+
+        assert!(matches!(
+            cli.command,
+            Some(Commands::List { limit: 20, offset: 0, .. })
+        ));
+
+        let cli = Cli::parse_from(["gos", "list", "--sort", "title", "--limit", "5", "--offset", "10"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::List { ref sort, limit: 5, offset: 10 }) if sort == "title"
+        ));
     }
     
     #[test]
     fn test_cli_show_command() {
-        let cli = Cli::parse_from(&["gos", "show", "123e4567-e89b-12d3-a456-426614174000"]);
+        let cli = Cli::parse_from(["gos", "show", "123e4567-e89b-12d3-a456-426614174000"]);
         
-        if let Some(Commands::Show { id }) = cli.command {
+        if let Some(Commands::Show { id, .. }) = cli.command {
             assert_eq!(id.to_string(), "123e4567-e89b-12d3-a456-426614174000");
         } else {
             panic!("Expected Show command");
         }
     }
     
+    #[test]
+    fn test_cli_new_command() {
+        let cli = Cli::parse_from([
+            "gos", "new", "--template", "code-review", "--var", "file=main.rs", "--var", "lang=rust",
+        ]);
+        match cli.command {
+            Some(Commands::New { template, vars, .. }) => {
+                assert_eq!(template.as_deref(), Some("code-review"));
+                assert_eq!(vars, vec!["file=main.rs".to_string(), "lang=rust".to_string()]);
+            }
+            _ => panic!("Expected New command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_ask_command() {
+        let cli = Cli::parse_from([
+            "gos", "ask", "--schema", "shape.json", "--retries", "1", "What is the capital of France?",
+        ]);
+        match cli.command {
+            Some(Commands::Ask { schema, retries, prompt, .. }) => {
+                assert_eq!(schema.as_deref(), Some(std::path::Path::new("shape.json")));
+                assert_eq!(retries, 1);
+                assert_eq!(prompt, "What is the capital of France?");
+            }
+            _ => panic!("Expected Ask command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_new_metadata_flags() {
+        let cli = Cli::parse_from([
+            "gos", "new", "--title", "Refactor plan", "--tag", "work", "--tag", "rust",
+            "--system", "be terse", "--provider", "anthropic", "--json", "hello there",
+        ]);
+        match cli.command {
+            Some(Commands::New { title, tags, system, provider, json, send, message, .. }) => {
+                assert_eq!(title.as_deref(), Some("Refactor plan"));
+                assert_eq!(tags, vec!["work".to_string(), "rust".to_string()]);
+                assert_eq!(system.as_deref(), Some("be terse"));
+                assert_eq!(provider.as_deref(), Some("anthropic"));
+                assert!(json);
+                assert!(!send);
+                assert_eq!(message.as_deref(), Some("hello there"));
+            }
+            _ => panic!("Expected New command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_fork_command() {
+        let cli = Cli::parse_from(["gos", "fork", "123e4567-e89b-12d3-a456-426614174000", "--at", "4"]);
+        
+        if let Some(Commands::Fork { id, at }) = cli.command {
+            assert_eq!(id.to_string(), "123e4567-e89b-12d3-a456-426614174000");
+            assert_eq!(at, Some(4));
+        } else {
+            panic!("Expected Fork command");
+        }
+        
+        // --at is optional
+        let cli = Cli::parse_from(["gos", "fork", "123e4567-e89b-12d3-a456-426614174000"]);
+        
+        if let Some(Commands::Fork { at, .. }) = cli.command {
+            assert_eq!(at, None);
+        } else {
+            panic!("Expected Fork command");
+        }
+    }
+    
     #[test]
     fn test_cli_config_init_command() {
-        let cli = Cli::parse_from(&["gos", "config", "init"]);
+        let cli = Cli::parse_from(["gos", "config", "init"]);
         
         if let Some(Commands::Config { action }) = cli.command {
             assert!(matches!(action, ConfigCommands::Init { format } if format == "toml"));
@@ -68,7 +147,7 @@ mod cli_tests {
         }
         
         // Test format option
-        let cli_json = Cli::parse_from(&["gos", "config", "init", "--format", "json"]);
+        let cli_json = Cli::parse_from(["gos", "config", "init", "--format", "json"]);
         
         if let Some(Commands::Config { action }) = cli_json.command {
             assert!(matches!(action, ConfigCommands::Init { format } if format == "json"));
@@ -79,7 +158,7 @@ mod cli_tests {
     
     #[test]
     fn test_cli_config_set_secret_command() {
-        let cli = Cli::parse_from(&["gos", "config", "set-secret", "test-secret"]);
+        let cli = Cli::parse_from(["gos", "config", "set-secret", "test-secret"]);
         
         if let Some(Commands::Config { action }) = cli.command {
             assert!(matches!(action, ConfigCommands::SetSecret { secret, format } 
@@ -91,20 +170,26 @@ mod cli_tests {
     
     #[test]
     fn test_cli_config_set_endpoint_command() {
-        let cli = Cli::parse_from(&[
-            "gos", "config", "set-endpoint", "test-endpoint", 
-            "--url", "api.example.com", 
+        let cli = Cli::parse_from([
+            "gos", "config", "set-endpoint", "test-endpoint",
+            "--url", "api.example.com",
             "--secret", "endpoint-secret",
-            "--use-tls"
+            "--use-tls",
+            "--header", "X-Org-Id=acme",
+            "--header", "CF-Access-Client-Id=abc123",
+            "--query-param", "team=platform"
         ]);
-        
+
         if let Some(Commands::Config { action }) = cli.command {
             match action {
-                ConfigCommands::SetEndpoint { name, url, secret, use_tls, format } => {
+                ConfigCommands::SetEndpoint { name, url, secret, use_tls, headers, query_params, format, .. } => {
                     assert_eq!(name, "test-endpoint");
                     assert_eq!(url, "api.example.com");
                     assert_eq!(secret, Some("endpoint-secret".to_string()));
-                    assert_eq!(use_tls, true);
+                    assert!(use_tls);
+                    // --header and --query-param are repeatable
+                    assert_eq!(headers, vec!["X-Org-Id=acme", "CF-Access-Client-Id=abc123"]);
+                    assert_eq!(query_params, vec!["team=platform"]);
                     assert_eq!(format, "toml");
                 },
                 _ => panic!("Expected SetEndpoint action")
@@ -116,12 +201,29 @@ mod cli_tests {
     
     #[test]
     fn test_cli_config_show_command() {
-        let cli = Cli::parse_from(&["gos", "config", "show"]);
-        
+        let cli = Cli::parse_from(["gos", "config", "show"]);
+
+        if let Some(Commands::Config { action }) = cli.command {
+            assert!(matches!(action, ConfigCommands::Show { provenance: false }));
+        } else {
+            panic!("Expected Config command with Show action");
+        }
+
+        let cli = Cli::parse_from(["gos", "config", "show", "--provenance"]);
+
         if let Some(Commands::Config { action }) = cli.command {
-            assert!(matches!(action, ConfigCommands::Show));
+            assert!(matches!(action, ConfigCommands::Show { provenance: true }));
         } else {
             panic!("Expected Config command with Show action");
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_cli_daemon_commands() {
+        let cli = Cli::parse_from(["gos", "daemon", "start"]);
+        assert!(matches!(cli.command, Some(Commands::Daemon { action: DaemonCommands::Start })));
+
+        let cli = Cli::parse_from(["gos", "daemon", "status"]);
+        assert!(matches!(cli.command, Some(Commands::Daemon { action: DaemonCommands::Status })));
+    }
+}
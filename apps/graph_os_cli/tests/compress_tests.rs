@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod compress_tests {
+    use graph_os_cli::compress::{gzip_compress, gzip_decompress, zstd_decompress, Encoding, GzipStream, ZstdStream};
+
+    // `gzip.compress(b"The quick brown fox jumps over the lazy dog. " * 4)`
+    // from CPython, to prove the decoder handles streams produced by a
+    // standard implementation (dynamic Huffman, full trailer)
+    const PYTHON_GZIP: &[u8] = &[
+        0x1F, 0x8B, 0x08, 0x00, 0xE5, 0xA8, 0x96, 0x6A, 0x00, 0xFF, 0x0B, 0xC9,
+        0x48, 0x55, 0x28, 0x2C, 0xCD, 0x4C, 0xCE, 0x56, 0x48, 0x2A, 0xCA, 0x2F,
+        0xCF, 0x53, 0x48, 0xCB, 0xAF, 0x50, 0xC8, 0x2A, 0xCD, 0x2D, 0x28, 0x56,
+        0xC8, 0x2F, 0x4B, 0x2D, 0x52, 0x28, 0x01, 0x4A, 0xE7, 0x24, 0x56, 0x55,
+        0x2A, 0xA4, 0xE4, 0xA7, 0xEB, 0x29, 0x84, 0x0C, 0x0E, 0xC5, 0x00, 0x1B,
+        0x8D, 0xFF, 0x44, 0xB4, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn test_decompress_standard_gzip() {
+        let expected: Vec<u8> = b"The quick brown fox jumps over the lazy dog. ".repeat(4);
+        assert_eq!(gzip_decompress(PYTHON_GZIP).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_round_trip_compresses_repetitive_json() {
+        let data: Vec<u8> = br#"{"role":"user","content":"hello world"},"#.repeat(200);
+        let compressed = gzip_compress(&data);
+        assert!(compressed.len() < data.len() / 2, "compressed {} of {}", compressed.len(), data.len());
+        assert_eq!(gzip_decompress(&compressed).unwrap(), data);
+    }
+
+    #[test]
+    fn test_round_trip_handles_incompressible_input() {
+        // A pseudo-random buffer barely compresses but must survive
+        let mut state = 0x12345678u32;
+        let data: Vec<u8> = (0..10_000)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (state >> 24) as u8
+            })
+            .collect();
+        assert_eq!(gzip_decompress(&gzip_compress(&data)).unwrap(), data);
+    }
+
+    #[test]
+    fn test_streaming_decoder_matches_whole_buffer() {
+        // Feeding awkwardly-sized chunks must reproduce the full
+        // output, with every byte emitted exactly once
+        let expected: Vec<u8> = b"The quick brown fox jumps over the lazy dog. ".repeat(4);
+        let mut decoder = GzipStream::new();
+        let mut out = Vec::new();
+        for chunk in PYTHON_GZIP.chunks(7) {
+            out.extend(decoder.feed(chunk).unwrap());
+        }
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_corrupt_input_is_rejected() {
+        assert!(gzip_decompress(b"definitely not gzip").is_err());
+
+        let mut tampered = gzip_compress(b"payload under test").to_vec();
+        let mid = tampered.len() / 2;
+        tampered[mid] ^= 0xFF;
+        // Either the stream fails to decode or the checksum catches it
+        assert!(gzip_decompress(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_malformed_stream_frame_errors_instead_of_stalling() {
+        // A fixed-Huffman block holding 'A' followed by a back-reference
+        // whose distance bits form code 30, which the fixed distance
+        // table does not define. The streaming decoder must report the
+        // corruption, not mistake it for "need more input" and stall.
+        let mut corrupt = vec![0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF];
+        corrupt.extend_from_slice(&[0x73, 0x04, 0xBE, 0xFF, 0xFF]);
+
+        let mut decoder = GzipStream::new();
+        assert!(decoder.feed(&corrupt).is_err());
+        assert!(gzip_decompress(&corrupt).is_err());
+    }
+
+    #[test]
+    fn test_zstd_round_trip_and_streaming() {
+        let data: Vec<u8> = br#"{"role":"user","content":"hello world"},"#.repeat(200);
+        let compressed = zstd::stream::encode_all(&data[..], 0).unwrap();
+        assert_eq!(zstd_decompress(&compressed).unwrap(), data);
+
+        // Awkwardly-sized chunks must reproduce the full output, with
+        // every byte emitted exactly once
+        let mut decoder = ZstdStream::new();
+        let mut out = Vec::new();
+        for chunk in compressed.chunks(7) {
+            out.extend(decoder.feed(chunk).unwrap());
+        }
+        assert_eq!(out, data);
+
+        assert!(zstd_decompress(b"definitely not zstd").is_err());
+    }
+
+    #[test]
+    fn test_encoding_negotiation() {
+        assert_eq!(Encoding::parse("gzip"), Some(Encoding::Gzip));
+        assert_eq!(Encoding::parse("GZIP"), Some(Encoding::Gzip));
+        assert_eq!(Encoding::parse("zstd"), Some(Encoding::Zstd));
+        // Chained or unknown encodings are left for the caller to reject
+        assert_eq!(Encoding::parse("gzip, br"), None);
+        assert_eq!(Encoding::parse("br"), None);
+    }
+}
@@ -0,0 +1,201 @@
+#[cfg(test)]
+mod config_layering_tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use graph_os_cli::config::{
+        redact, ApiConfig, ApiProvider, AuthConfig, Config, ConfigLayer, EndpointConfig,
+    };
+
+    fn endpoint(url: &str) -> EndpointConfig {
+        EndpointConfig {
+            url: url.to_string(),
+            ..EndpointConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_user_layer_overrides_system_per_key() {
+        let mut provenance = Vec::new();
+        let mut merged = AuthConfig::default();
+
+        let system = AuthConfig {
+            rpc_secret: Some("system-secret-value".to_string()),
+            endpoints: HashMap::from([
+                ("prod".to_string(), endpoint("system.example.com")),
+                ("staging".to_string(), endpoint("staging.example.com")),
+            ]),
+            ..AuthConfig::default()
+        };
+
+        let user = AuthConfig {
+            endpoints: HashMap::from([("prod".to_string(), endpoint("user.example.com"))]),
+            accessible: Some(true),
+            ..AuthConfig::default()
+        };
+
+        let system_layer = ConfigLayer::SystemFile(PathBuf::from("/etc/graph_os/config.toml"));
+        let user_layer = ConfigLayer::UserFile(PathBuf::from("/home/u/.config/graph_os/config.toml"));
+        Config::merge_auth_layer(&mut merged, system, system_layer.clone(), &mut provenance);
+        Config::merge_auth_layer(&mut merged, user, user_layer.clone(), &mut provenance);
+
+        // The user file overrides "prod" but the untouched keys keep
+        // their system-layer values
+        assert_eq!(merged.endpoints["prod"].url, "user.example.com");
+        assert_eq!(merged.endpoints["staging"].url, "staging.example.com");
+        assert_eq!(merged.rpc_secret.as_deref(), Some("system-secret-value"));
+        assert_eq!(merged.accessible, Some(true));
+
+        // Provenance holds exactly one entry per key, naming the layer
+        // that won
+        let layer_of = |key: &str| {
+            let matches: Vec<_> = provenance.iter().filter(|e| e.key == key).collect();
+            assert_eq!(matches.len(), 1, "expected one provenance entry for {}", key);
+            matches[0].layer.clone()
+        };
+        assert_eq!(layer_of("endpoints.prod"), user_layer);
+        assert_eq!(layer_of("endpoints.staging"), system_layer.clone());
+        assert_eq!(layer_of("rpc_secret"), system_layer);
+        assert_eq!(layer_of("accessible"), user_layer);
+    }
+
+    #[test]
+    fn test_secret_field_encryption_roundtrip() {
+        use graph_os_cli::crypto::{is_encrypted_field, SessionCipher};
+
+        let cipher = SessionCipher::new("master");
+        let mut auth = AuthConfig {
+            rpc_secret: Some("rpc-secret".to_string()),
+            endpoints: HashMap::from([(
+                "prod".to_string(),
+                EndpointConfig {
+                    secret: Some("endpoint-secret".to_string()),
+                    token: Some("bearer-token".to_string()),
+                    ..endpoint("prod.example.com")
+                },
+            )]),
+            ..AuthConfig::default()
+        };
+
+        assert!(!auth.has_encrypted_fields());
+        assert_eq!(auth.encrypt_secret_fields(&cipher).unwrap(), 3);
+        assert!(auth.has_encrypted_fields());
+        assert!(is_encrypted_field(auth.rpc_secret.as_deref().unwrap()));
+
+        // Encrypting again skips the existing blobs
+        assert_eq!(auth.encrypt_secret_fields(&cipher).unwrap(), 0);
+
+        assert_eq!(auth.decrypt_secret_fields(&cipher).unwrap(), 3);
+        assert_eq!(auth.rpc_secret.as_deref(), Some("rpc-secret"));
+        assert_eq!(auth.endpoints["prod"].secret.as_deref(), Some("endpoint-secret"));
+        assert_eq!(auth.endpoints["prod"].token.as_deref(), Some("bearer-token"));
+    }
+
+    #[test]
+    fn test_secrets_are_redacted_in_provenance() {
+        let mut provenance = Vec::new();
+        let mut merged = AuthConfig::default();
+
+        let layer = AuthConfig {
+            rpc_secret: Some("super-secret-token-123".to_string()),
+            ..AuthConfig::default()
+        };
+        Config::merge_auth_layer(&mut merged, layer, ConfigLayer::Default, &mut provenance);
+
+        let entry = provenance.iter().find(|e| e.key == "rpc_secret").unwrap();
+        assert!(!entry.value.contains("secret-token"));
+        assert_eq!(entry.value, redact("super-secret-token-123"));
+
+        // Short secrets are masked entirely so the length leaks nothing
+        assert_eq!(redact("abc"), "****");
+    }
+
+    #[test]
+    fn test_flag_overrides_outrank_everything() {
+        let mut apis = HashMap::new();
+        apis.insert(ApiProvider::OpenAI, ApiConfig {
+            provider: ApiProvider::OpenAI,
+            api_key: "k".to_string(),
+            api_url: None,
+            model: Some("gpt-4o".to_string()),
+        });
+        apis.insert(ApiProvider::Gemini, ApiConfig {
+            provider: ApiProvider::Gemini,
+            api_key: "k".to_string(),
+            api_url: None,
+            model: Some("gemini-pro".to_string()),
+        });
+        let mut config = Config {
+            apis,
+            default_provider: Some(ApiProvider::OpenAI),
+            auth: None,
+            provenance: Vec::new(),
+        };
+
+        config.apply_flag_overrides(Some("gemini"), Some("gemini-ultra"));
+
+        assert_eq!(config.default_provider, Some(ApiProvider::Gemini));
+        assert_eq!(
+            config.get_api_config(ApiProvider::Gemini).unwrap().model.as_deref(),
+            Some("gemini-ultra")
+        );
+        let entry = config.provenance.iter().find(|e| e.key == "default_provider").unwrap();
+        assert_eq!(entry.layer, ConfigLayer::Flag("--provider".to_string()));
+        let entry = config.provenance.iter().find(|e| e.key == "apis.gemini.model").unwrap();
+        assert_eq!(entry.layer, ConfigLayer::Flag("--model".to_string()));
+
+        // A flag naming an unconfigured provider is ignored rather
+        // than pointing the session at a provider with no key
+        config.apply_flag_overrides(Some("anthropic"), None);
+        assert_eq!(config.default_provider, Some(ApiProvider::Gemini));
+    }
+
+    #[test]
+    fn test_fallback_chain_parses_and_merges_whole() {
+        let mut provenance = Vec::new();
+        let mut merged = AuthConfig::default();
+
+        let system = AuthConfig {
+            fallback: vec!["anthropic".to_string(), "openai".to_string()],
+            ..AuthConfig::default()
+        };
+        let user = AuthConfig {
+            fallback: vec!["openai".to_string(), "not-a-provider".to_string(), "custom".to_string()],
+            ..AuthConfig::default()
+        };
+
+        // The chain is ordered, so a later layer replaces it wholesale
+        // instead of merging per entry
+        Config::merge_auth_layer(&mut merged, system, ConfigLayer::Default, &mut provenance);
+        Config::merge_auth_layer(
+            &mut merged,
+            user,
+            ConfigLayer::UserFile(PathBuf::from("/home/u/.config/graph_os/config.toml")),
+            &mut provenance,
+        );
+
+        let config = Config {
+            apis: HashMap::new(),
+            default_provider: None,
+            auth: Some(merged),
+            provenance: Vec::new(),
+        };
+
+        // Unknown names are dropped; the order of the rest is kept
+        assert_eq!(config.fallback(), vec![ApiProvider::OpenAI, ApiProvider::Custom]);
+    }
+
+    #[test]
+    fn test_layer_display_names_the_source() {
+        assert_eq!(ConfigLayer::Default.to_string(), "default");
+        assert_eq!(
+            ConfigLayer::Environment("OPENAI_API_KEY".to_string()).to_string(),
+            "env OPENAI_API_KEY"
+        );
+        assert_eq!(ConfigLayer::Flag("--model".to_string()).to_string(), "flag --model");
+        assert_eq!(
+            ConfigLayer::SystemFile(PathBuf::from("/etc/graph_os/config.toml")).to_string(),
+            "system file /etc/graph_os/config.toml"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+#[cfg(test)]
+mod config_validate_tests {
+    use graph_os_cli::config::validate_auth_config_value;
+    use serde_json::json;
+
+    #[test]
+    fn test_valid_config() {
+        let config = json!({
+            "rpc_secret": "secret",
+            "endpoints": {
+                "default": {
+                    "url": "https://example.com/api",
+                    "use_tls": true,
+                    "transport": "jsonrpc"
+                }
+            },
+            "templates": {
+                "code-review": { "prompt": "Review {{file}}" }
+            }
+        });
+
+        let report = validate_auth_config_value(&config);
+        assert!(report.is_ok(), "unexpected errors: {:?}", report.errors);
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_keys_warn() {
+        let config = json!({
+            "rpc_secrt": "typo",
+            "endpoints": {
+                "default": { "url": "http://example.com", "transprt": "grpc" }
+            }
+        });
+
+        let report = validate_auth_config_value(&config);
+        assert!(report.is_ok());
+        assert_eq!(report.warnings.len(), 2);
+        assert!(report.warnings[0].contains("rpc_secrt"));
+        assert!(report.warnings[1].contains("endpoints.default.transprt"));
+    }
+
+    #[test]
+    fn test_signing_keys_are_known_keys() {
+        // Request signing settings are real config, not typos: strings
+        // pass clean, anything else is a type error
+        let config = json!({
+            "endpoints": {
+                "default": {
+                    "url": "https://example.com/api",
+                    "signing_key_id": "kid-1",
+                    "signing_key": "aGV4LWJ5dGVz"
+                }
+            }
+        });
+
+        let report = validate_auth_config_value(&config);
+        assert!(report.is_ok(), "unexpected errors: {:?}", report.errors);
+        assert!(report.warnings.is_empty(), "unexpected warnings: {:?}", report.warnings);
+
+        let config = json!({
+            "endpoints": {
+                "default": { "url": "https://example.com/api", "signing_key": 42 }
+            }
+        });
+
+        let report = validate_auth_config_value(&config);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("endpoints.default.signing_key"));
+    }
+
+    #[test]
+    fn test_embedding_model_is_a_known_key() {
+        // The opt-in for provider-backed semantic search must not warn
+        let config = json!({
+            "endpoints": {
+                "default": {
+                    "url": "https://example.com/api",
+                    "embedding_model": "text-embedding-3-small"
+                }
+            }
+        });
+
+        let report = validate_auth_config_value(&config);
+        assert!(report.is_ok(), "unexpected errors: {:?}", report.errors);
+        assert!(report.warnings.is_empty(), "unexpected warnings: {:?}", report.warnings);
+
+        let config = json!({
+            "endpoints": {
+                "default": { "url": "https://example.com/api", "embedding_model": true }
+            }
+        });
+
+        let report = validate_auth_config_value(&config);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("endpoints.default.embedding_model"));
+    }
+
+    #[test]
+    fn test_http_version_accepts_parser_spellings() {
+        // Every spelling HttpVersion::parse takes is valid config
+        for version in ["auto", "http1", "http1.1", "1.1", "http2", "h2", "2"] {
+            let config = json!({
+                "endpoints": {
+                    "default": { "url": "https://example.com/api", "http_version": version }
+                }
+            });
+
+            let report = validate_auth_config_value(&config);
+            assert!(report.is_ok(), "'{}' rejected: {:?}", version, report.errors);
+            assert!(report.warnings.is_empty(), "'{}' warned: {:?}", version, report.warnings);
+        }
+
+        let config = json!({
+            "endpoints": {
+                "default": { "url": "https://example.com/api", "http_version": "http3" }
+            }
+        });
+
+        let report = validate_auth_config_value(&config);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("http_version"));
+        assert!(report.errors[0].contains("http3"));
+    }
+
+    #[test]
+    fn test_missing_url_and_bad_types() {
+        let config = json!({
+            "endpoints": {
+                "default": { "use_tls": "yes", "transport": "carrier-pigeon" }
+            }
+        });
+
+        let report = validate_auth_config_value(&config);
+        assert_eq!(report.errors.len(), 3);
+        assert!(report.errors.iter().any(|e| e.contains("endpoints.default.url")));
+        assert!(report.errors.iter().any(|e| e.contains("endpoints.default.use_tls")));
+        assert!(report.errors.iter().any(|e| e.contains("carrier-pigeon")));
+    }
+
+    #[test]
+    fn test_conflicting_tls_settings() {
+        let config = json!({
+            "endpoints": {
+                "default": { "url": "https://example.com", "use_tls": false }
+            }
+        });
+
+        let report = validate_auth_config_value(&config);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("use_tls = false"));
+    }
+
+    #[test]
+    fn test_fallback_chain_is_a_known_key() {
+        // The provider fallback chain is real config, not a typo
+        let config = json!({
+            "fallback": ["anthropic", "openai"]
+        });
+
+        let report = validate_auth_config_value(&config);
+        assert!(report.is_ok(), "unexpected errors: {:?}", report.errors);
+        assert!(report.warnings.is_empty(), "unexpected warnings: {:?}", report.warnings);
+
+        // Entries must be provider names, and the whole value an array
+        let report = validate_auth_config_value(&json!({ "fallback": ["anthropic", 3] }));
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("fallback[1]"));
+
+        let report = validate_auth_config_value(&json!({ "fallback": "anthropic" }));
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("expected an array"));
+    }
+
+    #[test]
+    fn test_personas_validation() {
+        let config = json!({
+            "personas": {
+                "reviewer": {
+                    "system": "You review code.",
+                    "provider": "anthropic",
+                    "model": "claude-3-opus"
+                },
+                "broken": {
+                    "provider": "carrier-pigeon",
+                    "colour": "red"
+                }
+            }
+        });
+
+        let report = validate_auth_config_value(&config);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("personas.broken.provider"));
+        assert!(report.errors[0].contains("carrier-pigeon"));
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("personas.broken.colour"));
+    }
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod context_tests {
+    use graph_os_cli::context::{render_context, truncate_to_budget};
+
+    #[test]
+    fn test_truncate_to_budget() {
+        let (text, truncated) = truncate_to_budget("short", 100);
+        assert_eq!(text, "short");
+        assert!(!truncated);
+
+        // A 10-token budget allows roughly 40 characters
+        let long = "x".repeat(200);
+        let (text, truncated) = truncate_to_budget(&long, 10);
+        assert_eq!(text.len(), 40);
+        assert!(truncated);
+    }
+
+    #[test]
+    fn test_render_context() {
+        let dir = std::env::temp_dir().join(format!("gos-context-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("notes.txt");
+        std::fs::write(&file, "remember the milk").unwrap();
+
+        let paths = vec![file.display().to_string()];
+        let rendered = render_context(&paths, 1000);
+
+        assert!(rendered.starts_with("Workspace context:"));
+        assert!(rendered.contains("remember the milk"));
+        assert!(rendered.contains(&file.display().to_string()));
+
+        // A missing file renders an inline note rather than failing
+        let rendered = render_context(&["/no/such/file".to_string()], 1000);
+        assert!(rendered.contains("failed to read /no/such/file"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_render_context_budget_exhaustion() {
+        let dir = std::env::temp_dir().join(format!("gos-context-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let big = dir.join("big.txt");
+        let small = dir.join("small.txt");
+        std::fs::write(&big, "y".repeat(500)).unwrap();
+        std::fs::write(&small, "tiny").unwrap();
+
+        let paths = vec![big.display().to_string(), small.display().to_string()];
+        let rendered = render_context(&paths, 100);
+
+        // The first file consumes the whole budget; the second is omitted
+        assert!(rendered.contains("truncated to fit the context budget"));
+        assert!(rendered.contains("omitted: context budget exhausted"));
+        assert!(!rendered.contains("tiny"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
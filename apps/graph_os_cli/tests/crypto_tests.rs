@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod crypto_tests {
+    use graph_os_cli::crypto::{is_encrypted_field, SessionCipher};
+
+    #[test]
+    fn test_roundtrip() {
+        let cipher = SessionCipher::new("correct horse battery staple");
+        let plaintext = br#"{"id":"00000000-0000-0000-0000-000000000000","messages":[]}"#;
+
+        let encrypted = cipher.encrypt(plaintext).unwrap();
+        assert!(SessionCipher::is_encrypted(&encrypted));
+        assert_ne!(&encrypted[..], &plaintext[..]);
+
+        let decrypted = cipher.decrypt(&encrypted).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn test_fresh_salt_per_file() {
+        let cipher = SessionCipher::new("passphrase");
+
+        // The same plaintext should never encrypt to the same bytes
+        let a = cipher.encrypt(b"same contents").unwrap();
+        let b = cipher.encrypt(b"same contents").unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_wrong_passphrase() {
+        let cipher = SessionCipher::new("right");
+        let encrypted = cipher.encrypt(b"secret conversation").unwrap();
+
+        let other = SessionCipher::new("wrong");
+        assert!(other.decrypt(&encrypted).is_err());
+    }
+
+    #[test]
+    fn test_field_roundtrip() {
+        let cipher = SessionCipher::new("master");
+
+        let blob = cipher.encrypt_field("s3cr3t-token").unwrap();
+        assert!(is_encrypted_field(&blob));
+        assert!(!is_encrypted_field("s3cr3t-token"));
+
+        assert_eq!(cipher.decrypt_field(&blob).unwrap(), "s3cr3t-token");
+
+        // The wrong passphrase fails rather than yielding garbage
+        let other = SessionCipher::new("wrong");
+        assert!(other.decrypt_field(&blob).is_err());
+    }
+
+    #[test]
+    fn test_plaintext_detection() {
+        assert!(!SessionCipher::is_encrypted(b"{\"id\": \"...\"}"));
+        assert!(!SessionCipher::is_encrypted(b""));
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod diff_tests {
+    use graph_os_cli::diff::{align, first_divergence, render_side_by_side, DiffEntry};
+    use graph_os_cli::session::{ChatMessage, MessageMeta, Session};
+    use uuid::Uuid;
+
+    fn user(text: &str) -> ChatMessage {
+        ChatMessage::User { text: text.to_string(), meta: MessageMeta::default() }
+    }
+
+    fn assistant(text: &str) -> ChatMessage {
+        ChatMessage::Assistant { text: text.to_string(), meta: MessageMeta::default() }
+    }
+
+    fn session_with_messages(messages: Vec<ChatMessage>) -> Session {
+        let mut session = Session::new(Uuid::new_v4());
+        session.messages = messages;
+        session
+    }
+
+    #[test]
+    fn test_align_forked_conversations() {
+        // A fork shares the first exchange, then the prompts diverge
+        let left = vec![user("hi"), assistant("hello"), user("explain lifetimes")];
+        let right = vec![user("hi"), assistant("hello"), user("explain traits"), assistant("sure")];
+
+        let entries = align(&left, &right);
+        assert_eq!(
+            entries,
+            vec![
+                DiffEntry::Common { left: 0, right: 0 },
+                DiffEntry::Common { left: 1, right: 1 },
+                DiffEntry::LeftOnly(2),
+                DiffEntry::RightOnly(2),
+                DiffEntry::RightOnly(3),
+            ]
+        );
+        assert_eq!(first_divergence(&entries), Some(2));
+    }
+
+    #[test]
+    fn test_align_ignores_metadata() {
+        // Same role and text align even when only one side has metadata
+        let stamped = ChatMessage::assistant("hello".to_string());
+        let entries = align(&[assistant("hello")], &[stamped]);
+        assert_eq!(entries, vec![DiffEntry::Common { left: 0, right: 0 }]);
+        assert_eq!(first_divergence(&entries), None);
+    }
+
+    #[test]
+    fn test_render_side_by_side() {
+        let left = session_with_messages(vec![user("hi"), user("tell me about rust")]);
+        let right = session_with_messages(vec![user("hi"), user("tell me about go")]);
+
+        let rendered = render_side_by_side(&left, &right, 100);
+        assert!(rendered.contains(&format!("a: {}", left.id)));
+        assert!(rendered.contains("--- conversations diverge after message 0 ---"));
+        assert!(rendered.contains("< [1] user:"));
+        assert!(rendered.contains("> "));
+        assert!(rendered.contains("1 shared, 1 only in a, 1 only in b"));
+    }
+
+    #[test]
+    fn test_render_identical_sessions() {
+        let left = session_with_messages(vec![user("hi")]);
+        let right = session_with_messages(vec![user("hi")]);
+
+        let rendered = render_side_by_side(&left, &right, 80);
+        assert!(rendered.contains("Conversations are identical."));
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod dynamic_tests {
+    use std::path::PathBuf;
+
+    use graph_os_cli::adapters::dynamic::{
+        load_descriptor_pool, request_from_json, resolve_method, response_to_json,
+    };
+
+    /// A small service definition compiled at runtime, like a
+    /// user-supplied proto would be
+    const PROTO: &str = r#"syntax = "proto3";
+
+package example;
+
+message EchoRequest {
+  string text = 1;
+  int32 times = 2;
+}
+
+message EchoResponse {
+  string text = 1;
+}
+
+service EchoService {
+  rpc Echo(EchoRequest) returns (EchoResponse);
+  rpc EchoStream(EchoRequest) returns (stream EchoResponse);
+}
+"#;
+
+    fn write_proto() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gos-dynamic-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("echo.proto");
+        std::fs::write(&path, PROTO).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_compile_and_resolve() {
+        let proto = write_proto();
+        let pool = load_descriptor_pool(&proto).unwrap();
+
+        // Both the bare and the fully-qualified service name resolve
+        let method = resolve_method(&pool, "EchoService/Echo").unwrap();
+        assert_eq!(method.name(), "Echo");
+        let method = resolve_method(&pool, "example.EchoService/Echo").unwrap();
+        assert_eq!(method.input().full_name(), "example.EchoRequest");
+
+        // Unknown names fail with the available alternatives listed
+        let err = resolve_method(&pool, "EchoService/Missing").unwrap_err().to_string();
+        assert!(err.contains("Echo"), "error was {}", err);
+        let err = resolve_method(&pool, "NoSuchService/Echo").unwrap_err().to_string();
+        assert!(err.contains("example.EchoService"), "error was {}", err);
+
+        // A spec without a slash is rejected up front
+        assert!(resolve_method(&pool, "EchoService.Echo").is_err());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let proto = write_proto();
+        let pool = load_descriptor_pool(&proto).unwrap();
+        let method = resolve_method(&pool, "EchoService/Echo").unwrap();
+
+        let message = request_from_json(&method, r#"{"text": "hi", "times": 3}"#).unwrap();
+        let rendered = response_to_json(&message).unwrap();
+        assert!(rendered.contains("\"hi\""), "rendered was {}", rendered);
+        assert!(rendered.contains("3"), "rendered was {}", rendered);
+
+        // Fields the schema does not define are an error, not silently
+        // dropped
+        assert!(request_from_json(&method, r#"{"nope": true}"#).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod embeddings_tests {
+    use uuid::Uuid;
+
+    use graph_os_cli::embeddings::{cosine, embed, Embedder, EmbeddingIndex, EMBEDDING_DIM, LOCAL_MODEL};
+    use graph_os_cli::session::{ChatMessage, Session};
+
+    fn session_with(texts: &[&str]) -> Session {
+        let mut session = Session::new(Uuid::new_v4());
+        for (i, text) in texts.iter().enumerate() {
+            session.messages.push(if i % 2 == 0 {
+                ChatMessage::user(text.to_string())
+            } else {
+                ChatMessage::assistant(text.to_string())
+            });
+        }
+        session
+    }
+
+    #[test]
+    fn test_embed_similarity_ordering() {
+        let query = embed("that bug about TLS certificates");
+        let relevant = embed("The TLS handshake fails because the certificate chain is incomplete");
+        let unrelated = embed("Here is a recipe for sourdough bread with a long fermentation");
+
+        assert_eq!(query.len(), EMBEDDING_DIM);
+        assert!(cosine(&query, &relevant) > cosine(&query, &unrelated));
+
+        // Normalized: self-similarity is 1
+        assert!((cosine(&relevant, &relevant) - 1.0).abs() < 1e-5);
+
+        // Empty text embeds to the zero vector, similar to nothing
+        assert_eq!(cosine(&embed(""), &query), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_index_refresh_and_search() {
+        let tls = session_with(&[
+            "I keep hitting that bug where TLS connections drop",
+            "That is the certificate verification bug; pin the CA bundle to work around it",
+        ]);
+        let cooking = session_with(&[
+            "What temperature should the oven be for roasting vegetables?",
+            "Roast the vegetables at 220 degrees for about 25 minutes",
+        ]);
+
+        let embedder = Embedder::Local;
+        let mut index = EmbeddingIndex::default();
+        assert_eq!(index.refresh(&[tls.clone(), cooking.clone()], &embedder).await.unwrap(), 2);
+
+        // Unchanged sessions are not re-embedded
+        assert_eq!(index.refresh(&[tls.clone(), cooking.clone()], &embedder).await.unwrap(), 0);
+
+        let hits = index.search("that bug about TLS", 2, &embedder).await.unwrap();
+        assert!(!hits.is_empty());
+        assert_eq!(hits[0].1.session_id, tls.id);
+
+        // Dropping a session file drops its entries on refresh
+        index.refresh(std::slice::from_ref(&tls), &embedder).await.unwrap();
+        assert!(index.entries.iter().all(|entry| entry.session_id == tls.id));
+    }
+
+    #[tokio::test]
+    async fn test_index_round_trips_through_disk() {
+        let embedder = Embedder::Local;
+        let session = session_with(&["The TLS handshake failure happens on the staging cluster"]);
+        let mut index = EmbeddingIndex::default();
+        index.refresh(std::slice::from_ref(&session), &embedder).await.unwrap();
+
+        let dir = std::env::temp_dir().join(format!("gos-embeddings-test-{}", Uuid::new_v4()));
+        let path = dir.join("embeddings.jsonl");
+
+        // A missing file loads as an empty index
+        assert!(EmbeddingIndex::load(&path).unwrap().entries.is_empty());
+
+        index.save(&path).unwrap();
+        let loaded = EmbeddingIndex::load(&path).unwrap();
+        assert_eq!(loaded.entries.len(), index.entries.len());
+        let hits = loaded.search("TLS handshake", 1, &embedder).await.unwrap();
+        assert_eq!(hits[0].1.session_id, session.id);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_model_switch_reembeds_instead_of_mixing_spaces() {
+        let embedder = Embedder::Local;
+        let session = session_with(&["The TLS handshake failure happens on the staging cluster"]);
+        let mut index = EmbeddingIndex::default();
+        index.refresh(std::slice::from_ref(&session), &embedder).await.unwrap();
+        assert!(index.entries.iter().all(|entry| entry.model == LOCAL_MODEL));
+
+        // Entries tagged with another embedder's model are foreign:
+        // search skips them, and refresh re-embeds the session even
+        // though its last_active is unchanged
+        for entry in &mut index.entries {
+            entry.model = "provider:text-embedding-x".to_string();
+        }
+        assert!(index.search("TLS handshake", 1, &embedder).await.unwrap().is_empty());
+        assert_eq!(index.refresh(std::slice::from_ref(&session), &embedder).await.unwrap(), 1);
+        assert_eq!(index.search("TLS handshake", 1, &embedder).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod error_tests {
+    use graph_os_cli::error::GraphOsError;
+
+    #[test]
+    fn test_tonic_status_classification() {
+        let auth = GraphOsError::from(tonic::Status::unauthenticated("bad token"));
+        assert!(matches!(auth, GraphOsError::Auth(_)));
+
+        let timeout = GraphOsError::from(tonic::Status::deadline_exceeded("too slow"));
+        assert!(matches!(timeout, GraphOsError::Timeout(_)));
+
+        let transport = GraphOsError::from(tonic::Status::unavailable("connection refused"));
+        assert!(matches!(transport, GraphOsError::Transport(_)));
+
+        // Application-level codes carry the code through
+        let not_found = GraphOsError::from(tonic::Status::not_found("no such record"));
+        assert!(matches!(not_found, GraphOsError::RpcError { message, .. } if message == "no such record"));
+    }
+
+    #[test]
+    fn test_display_and_transience() {
+        let rpc = GraphOsError::RpcError {
+            code: -32601,
+            message: "Method not found".to_string(),
+            data: None,
+        };
+        assert_eq!(rpc.to_string(), "RPC error -32601: Method not found");
+        assert!(!rpc.is_transient());
+
+        assert!(GraphOsError::Transport("connection reset".to_string()).is_transient());
+        assert!(GraphOsError::Timeout("deadline".to_string()).is_transient());
+        assert!(!GraphOsError::Auth("denied".to_string()).is_transient());
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod eval_tests {
+    use graph_os_cli::eval::{
+        parse_judge_verdict, render_markdown, summarize, CaseResult, Expectation, Suite,
+    };
+
+    #[test]
+    fn test_suite_parses_all_check_kinds() {
+        let suite = Suite::parse(
+            r#"
+name: smoke
+system: Answer briefly.
+cases:
+  - name: capital
+    prompt: What is the capital of France?
+    expect:
+      contains: paris
+  - prompt: Reply with just OK
+    expect:
+      exact: OK
+  - prompt: Name a prime number
+    expect:
+      regex: "\\b(2|3|5|7)\\b"
+  - prompt: Write a haiku about rivers
+    expect:
+      judge: The reply is a haiku and mentions a river.
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(suite.name.as_deref(), Some("smoke"));
+        assert_eq!(suite.cases.len(), 4);
+        assert_eq!(suite.cases[0].display_name(0), "capital");
+        // Unnamed cases fall back to their position
+        assert_eq!(suite.cases[1].display_name(1), "case 2");
+
+        // An empty suite is rejected up front
+        assert!(Suite::parse("cases: []").is_err());
+    }
+
+    #[test]
+    fn test_local_checks() {
+        // Exact trims, contains is case-insensitive
+        assert!(Expectation::Exact("OK".to_string()).check_local(" OK\n").unwrap().is_ok());
+        assert!(Expectation::Exact("OK".to_string()).check_local("okay").unwrap().is_err());
+        assert!(Expectation::Contains("paris".to_string())
+            .check_local("The capital is Paris.")
+            .unwrap()
+            .is_ok());
+
+        // Regex failures carry the pattern; a broken pattern fails
+        // rather than passing silently
+        let miss = Expectation::Regex("\\b2\\b".to_string()).check_local("eleven").unwrap();
+        assert!(miss.unwrap_err().contains("\\b2\\b"));
+        assert!(Expectation::Regex("(".to_string()).check_local("anything").unwrap().is_err());
+
+        // Judge checks are not local
+        assert!(Expectation::Judge("is polite".to_string()).check_local("hi").is_none());
+    }
+
+    #[test]
+    fn test_judge_verdict_parsing() {
+        assert!(parse_judge_verdict("PASS").is_ok());
+        assert!(parse_judge_verdict("pass — meets the criterion").is_ok());
+        assert_eq!(
+            parse_judge_verdict("FAIL: not a haiku").unwrap_err(),
+            "FAIL: not a haiku"
+        );
+        // Anything else is a failure with the raw verdict kept
+        assert!(parse_judge_verdict("maybe?").unwrap_err().contains("maybe?"));
+    }
+
+    #[test]
+    fn test_summary_and_markdown_report() {
+        let result = |case: &str, provider: &str, passed: bool, latency_ms: u64| CaseResult {
+            case: case.to_string(),
+            provider: provider.to_string(),
+            passed,
+            detail: (!passed).then(|| "expected exactly \"OK\"".to_string()),
+            latency_ms,
+            response: String::new(),
+        };
+        let results = vec![
+            result("a", "openai", true, 100),
+            result("b", "openai", false, 300),
+            result("a", "anthropic", true, 50),
+            result("b", "anthropic", true, 150),
+        ];
+
+        let summaries = summarize(&results);
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].provider, "openai");
+        assert_eq!((summaries[0].passed, summaries[0].total), (1, 2));
+        assert_eq!(summaries[0].mean_latency_ms, 200);
+        assert_eq!((summaries[1].passed, summaries[1].total), (2, 2));
+
+        let report = render_markdown("smoke", &results);
+        assert!(report.starts_with("# Eval: smoke"));
+        assert!(report.contains("| openai | 1/2 | 50% | 200ms |"));
+        assert!(report.contains("| anthropic | 2/2 | 100% | 100ms |"));
+        // Only the failure is listed, with its detail
+        assert!(report.contains("## Failures"));
+        assert!(report.contains("**b** on openai: expected exactly \"OK\""));
+        assert!(!report.contains("**a** on"));
+    }
+}
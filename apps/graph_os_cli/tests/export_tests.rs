@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod export_tests {
+    use graph_os_cli::export::{session_matches, to_finetune_example, ExportFilter, MAX_EXAMPLE_CHARS};
+    use graph_os_cli::session::{ChatMessage, Session};
+    use uuid::Uuid;
+
+    fn session_with(messages: Vec<ChatMessage>) -> Session {
+        Session {
+            messages,
+            ..Session::new(Uuid::new_v4())
+        }
+    }
+
+    #[test]
+    fn test_filter_parse_and_match() {
+        let mut session = session_with(vec![]);
+        session.tags = vec!["training".to_string()];
+        session.provider = Some("anthropic".to_string());
+
+        let tag = ExportFilter::parse("tag=training").unwrap();
+        let provider = ExportFilter::parse("provider=Anthropic").unwrap();
+        assert!(tag.matches(&session));
+        assert!(provider.matches(&session));
+        assert!(!ExportFilter::parse("tag=other").unwrap().matches(&session));
+
+        // All filters must match at once
+        assert!(session_matches(&session, &[tag, provider]));
+        assert!(!session_matches(
+            &session,
+            &[ExportFilter::parse("tag=training").unwrap(), ExportFilter::parse("provider=openai").unwrap()]
+        ));
+
+        // Malformed specs and unknown keys are rejected with a hint
+        assert!(ExportFilter::parse("training").is_err());
+        assert!(ExportFilter::parse("tag=").is_err());
+        assert!(ExportFilter::parse("color=red").is_err());
+    }
+
+    #[test]
+    fn test_finetune_example_shape() {
+        let mut session = session_with(vec![
+            ChatMessage::user("hello".to_string()),
+            ChatMessage::assistant("hi there".to_string()),
+        ]);
+        session.system_prompt = Some("be brief".to_string());
+
+        let example = to_finetune_example(&session, false).unwrap();
+        let messages = example["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0]["role"], "system");
+        assert_eq!(messages[0]["content"], "be brief");
+        assert_eq!(messages[1]["role"], "user");
+        assert_eq!(messages[2]["role"], "assistant");
+        // Without --train-last-only no weight fields are emitted
+        assert!(messages[2].get("weight").is_none());
+    }
+
+    #[test]
+    fn test_finetune_example_weights_last_assistant_only() {
+        let session = session_with(vec![
+            ChatMessage::user("one".to_string()),
+            ChatMessage::assistant("first reply".to_string()),
+            ChatMessage::user("two".to_string()),
+            ChatMessage::assistant("final reply".to_string()),
+        ]);
+
+        let example = to_finetune_example(&session, true).unwrap();
+        let messages = example["messages"].as_array().unwrap();
+        assert_eq!(messages[1]["weight"], 0);
+        assert!(messages[3].get("weight").is_none());
+        // User turns never carry a weight
+        assert!(messages[0].get("weight").is_none());
+    }
+
+    #[test]
+    fn test_finetune_example_validation() {
+        // Nothing to train on
+        let err = to_finetune_example(&session_with(vec![]), false).unwrap_err();
+        assert!(err.to_string().contains("empty"));
+
+        let err = to_finetune_example(
+            &session_with(vec![ChatMessage::user("question".to_string())]),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("no assistant turn"));
+
+        // A blank message poisons the example
+        let err = to_finetune_example(
+            &session_with(vec![
+                ChatMessage::user("   ".to_string()),
+                ChatMessage::assistant("reply".to_string()),
+            ]),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("message 0 is empty"));
+
+        // Oversized conversations are rejected rather than truncated
+        let err = to_finetune_example(
+            &session_with(vec![
+                ChatMessage::user("x".repeat(MAX_EXAMPLE_CHARS)),
+                ChatMessage::assistant("reply".to_string()),
+            ]),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("over the"));
+    }
+}
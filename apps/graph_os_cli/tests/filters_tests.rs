@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use graph_os_cli::config::{FiltersConfig, ReplaceRule};
+    use graph_os_cli::filters::FilterChain;
+
+    #[test]
+    fn test_default_chain_trims_and_redacts() {
+        let chain = FilterChain::from_config(
+            &FiltersConfig::default(),
+            vec!["sk-secret-token".to_string()],
+        );
+
+        assert_eq!(chain.names(), vec!["redact_secrets", "trim_trailing_whitespace"]);
+        assert_eq!(
+            chain.apply("your key is sk-secret-token   \ndone  "),
+            "your key is [redacted]\ndone"
+        );
+    }
+
+    #[test]
+    fn test_default_chain_without_secrets_skips_redaction() {
+        let chain = FilterChain::from_config(&FiltersConfig::default(), Vec::new());
+        assert_eq!(chain.names(), vec!["trim_trailing_whitespace"]);
+    }
+
+    #[test]
+    fn test_trim_preserves_trailing_newline() {
+        let chain = FilterChain::from_config(&FiltersConfig::default(), Vec::new());
+        assert_eq!(chain.apply("line one  \nline two\t\n"), "line one\nline two\n");
+    }
+
+    #[test]
+    fn test_profanity_masked_with_same_length() {
+        let config = FiltersConfig {
+            profanity: Some(true),
+            ..FiltersConfig::default()
+        };
+        let chain = FilterChain::from_config(&config, Vec::new());
+
+        assert_eq!(chain.apply("well SHIT, that failed"), "well ****, that failed");
+        // Substrings of ordinary words are left alone
+        assert_eq!(chain.apply("the shiitake mushroom"), "the shiitake mushroom");
+    }
+
+    #[test]
+    fn test_unfurl_links_rewrites_markdown() {
+        let config = FiltersConfig {
+            unfurl_links: Some(true),
+            ..FiltersConfig::default()
+        };
+        let chain = FilterChain::from_config(&config, Vec::new());
+
+        assert_eq!(
+            chain.apply("see [the docs](https://example.com/docs) for more"),
+            "see the docs (https://example.com/docs) for more"
+        );
+    }
+
+    #[test]
+    fn test_custom_replace_rule() {
+        let config = FiltersConfig {
+            replace: vec![ReplaceRule {
+                pattern: r"\bcolour\b".to_string(),
+                replacement: "color".to_string(),
+            }],
+            ..FiltersConfig::default()
+        };
+        let chain = FilterChain::from_config(&config, Vec::new());
+
+        assert_eq!(chain.apply("the colour scheme"), "the color scheme");
+    }
+
+    #[test]
+    fn test_invalid_replace_pattern_is_skipped() {
+        let config = FiltersConfig {
+            trim_trailing_whitespace: Some(false),
+            replace: vec![ReplaceRule {
+                pattern: "(unclosed".to_string(),
+                replacement: "x".to_string(),
+            }],
+            ..FiltersConfig::default()
+        };
+        let chain = FilterChain::from_config(&config, Vec::new());
+
+        assert!(chain.names().is_empty());
+        assert_eq!(chain.apply("left alone"), "left alone");
+    }
+
+    #[test]
+    fn test_empty_chain_passes_text_through() {
+        let config = FiltersConfig {
+            trim_trailing_whitespace: Some(false),
+            redact_secrets: Some(false),
+            ..FiltersConfig::default()
+        };
+        let chain = FilterChain::from_config(&config, vec!["secret".to_string()]);
+
+        assert!(chain.names().is_empty());
+        assert_eq!(chain.apply("secret text  "), "secret text  ");
+    }
+}
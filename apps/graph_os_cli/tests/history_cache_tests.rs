@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod history_cache_tests {
+    use graph_os_cli::adapters::grpc::graph_os::SystemInfo;
+    use graph_os_cli::history_cache::{max_timestamp, merge_records, HistoryCache};
+
+    fn record(timestamp: i64) -> SystemInfo {
+        SystemInfo {
+            timestamp,
+            ..Default::default()
+        }
+    }
+
+    fn records(timestamps: &[i64]) -> Vec<SystemInfo> {
+        timestamps.iter().map(|ts| record(*ts)).collect()
+    }
+
+    fn timestamps(items: &[SystemInfo]) -> Vec<i64> {
+        items.iter().map(|item| item.timestamp).collect()
+    }
+
+    #[test]
+    fn test_merge_appends_sorts_and_dedupes() {
+        assert_eq!(max_timestamp(&[]), None);
+        assert_eq!(max_timestamp(&records(&[30, 10, 20])), Some(30));
+
+        // New records land in timestamp order; an overlapping timestamp
+        // keeps the fresh copy rather than duplicating
+        let merged = merge_records(records(&[10, 20]), records(&[30, 20, 25]), None);
+        assert_eq!(timestamps(&merged), vec![10, 20, 25, 30]);
+    }
+
+    #[test]
+    fn test_merge_honors_record_limit() {
+        // A limit keeps only the newest records, like the server would
+        // have returned for the full query
+        let merged = merge_records(records(&[10, 20, 30]), records(&[40, 50]), Some(3));
+        assert_eq!(timestamps(&merged), vec![30, 40, 50]);
+
+        // A zero limit means unlimited, matching the wire encoding
+        let merged = merge_records(records(&[10]), records(&[20]), Some(0));
+        assert_eq!(timestamps(&merged), vec![10, 20]);
+    }
+
+    #[test]
+    fn test_cache_roundtrip_and_key_separation() {
+        let path = std::env::temp_dir().join(format!(
+            "gos-history-cache-test-{}.json",
+            uuid::Uuid::new_v4()
+        ));
+
+        let mut cache = HistoryCache::with_path(path.clone());
+        assert!(cache.is_empty());
+        assert!(cache.lookup("http://a:50051", None, None).is_none());
+
+        cache.store("http://a:50051", None, None, &records(&[10, 20]));
+
+        // Same key hits; a different limit or endpoint is a different query
+        let hit = cache.lookup("http://a:50051", None, None).unwrap();
+        assert_eq!(timestamps(&hit), vec![10, 20]);
+        assert!(cache.lookup("http://a:50051", Some(5), None).is_none());
+        assert!(cache.lookup("http://b:50051", None, None).is_none());
+
+        // A fresh handle reads the entry back from disk
+        let mut reopened = HistoryCache::with_path(path.clone());
+        let hit = reopened.lookup("http://a:50051", None, None).unwrap();
+        assert_eq!(timestamps(&hit), vec![10, 20]);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let path = std::env::temp_dir().join(format!(
+            "gos-history-cache-test-{}.json",
+            uuid::Uuid::new_v4()
+        ));
+
+        let mut cache = HistoryCache::with_path(path.clone());
+        for i in 0..17 {
+            cache.store("http://a:50051", Some(i), None, &records(&[10]));
+        }
+
+        // The cache stays bounded and the oldest key is the one dropped
+        assert_eq!(cache.len(), 16);
+        assert!(cache.lookup("http://a:50051", Some(0), None).is_none());
+        assert!(cache.lookup("http://a:50051", Some(16), None).is_some());
+
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod hooks_tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use graph_os_cli::hooks::{HookDispatcher, HookEvent, HooksConfig};
+
+    fn event(response: &str) -> HookEvent {
+        HookEvent::response_complete(
+            None,
+            "openai".to_string(),
+            "gpt-4o".to_string(),
+            Duration::from_secs(5),
+            response,
+        )
+    }
+
+    #[test]
+    fn test_should_fire() {
+        // Nothing configured: never fire
+        let dispatcher = HookDispatcher::new(HooksConfig::default());
+        assert!(!dispatcher.should_fire(false, Duration::from_secs(60)));
+
+        let mut config = HooksConfig {
+            desktop: true,
+            ..HooksConfig::default()
+        };
+
+        // Unfocused terminals always fire
+        let dispatcher = HookDispatcher::new(config.clone());
+        assert!(dispatcher.should_fire(false, Duration::from_secs(0)));
+
+        // Focused terminals need the duration threshold
+        assert!(!dispatcher.should_fire(true, Duration::from_secs(60)));
+        config.min_seconds = Some(30);
+        let dispatcher = HookDispatcher::new(config);
+        assert!(!dispatcher.should_fire(true, Duration::from_secs(29)));
+        assert!(dispatcher.should_fire(true, Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_event_preview() {
+        let long_line = "x".repeat(300);
+        let event = event(&format!("{}\nsecond line", long_line));
+        assert_eq!(event.preview.len(), 120);
+        assert!(!event.preview.contains("second"));
+        assert_eq!(event.event, "response_complete");
+    }
+
+    #[tokio::test]
+    async fn test_command_hook_receives_payload() {
+        let out = std::env::temp_dir().join(format!("gos-hook-test-{}", uuid::Uuid::new_v4()));
+        let mut commands = HashMap::new();
+        commands.insert("*".to_string(), format!("cat > {}", out.display()));
+
+        let dispatcher = HookDispatcher::new(HooksConfig {
+            commands,
+            ..HooksConfig::default()
+        });
+        dispatcher.fire(event("hello from the hook"));
+
+        // The command runs in a background task; poll briefly for its output
+        let mut payload = String::new();
+        for _ in 0..50 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            if let Ok(contents) = std::fs::read_to_string(&out) {
+                payload = contents;
+                break;
+            }
+        }
+
+        assert!(payload.contains("\"event\":\"response_complete\""), "payload: {}", payload);
+        assert!(payload.contains("hello from the hook"));
+        std::fs::remove_file(&out).ok();
+    }
+}
@@ -60,10 +60,124 @@ mod tests {
     }
 }
 
+#[cfg(test)]
+mod stream_parser_tests {
+    use graph_os_cli::adapters::jsonrpc::{NdjsonStreamParser, ServerNotification, StreamEvent};
+
+    #[test]
+    fn test_split_frame_across_chunks() {
+        let mut parser = NdjsonStreamParser::new();
+
+        // The frame arrives split mid-JSON; nothing comes out until the
+        // newline completes it
+        let events = parser.push(br#"{"jsonrpc":"2.0","result":{"con"#);
+        assert!(events.is_empty());
+
+        let events = parser.push("tent\":\"Hel\"},\"id\":\"1\"}\n".as_bytes());
+        assert_eq!(events, vec![StreamEvent::ContentDelta("Hel".to_string())]);
+
+        // A final frame without a trailing newline is flushed by finish
+        parser.push(br#"{"result":{"content":"lo"}}"#);
+        let events = parser.finish();
+        assert_eq!(events, vec![StreamEvent::ContentDelta("lo".to_string())]);
+        assert!(parser.finish().is_empty());
+    }
+
+    #[test]
+    fn test_non_content_fields() {
+        let mut parser = NdjsonStreamParser::new();
+
+        // One frame carrying content, usage and a finish reason emits
+        // one event per fact; a role-only delta emits nothing
+        let events = parser.push(
+            concat!(
+                r#"{"result":{"role":"assistant"}}"#, "\n",
+                r#"{"result":{"content":"hi","usage":{"prompt_tokens":12,"completion_tokens":3},"finish_reason":"length"}}"#, "\n",
+            )
+            .as_bytes(),
+        );
+        assert_eq!(events, vec![
+            StreamEvent::ContentDelta("hi".to_string()),
+            StreamEvent::Usage { prompt_tokens: Some(12), completion_tokens: Some(3) },
+            StreamEvent::Done { finish_reason: Some("length".to_string()) },
+        ]);
+
+        // OpenAI-style choices[0].delta frames parse the same way
+        let events = parser.push(
+            concat!(
+                r#"{"choices":[{"delta":{"tool_calls":[{"function":{"name":"lookup","arguments":"{\"q\""}}]}}]}"#, "\n",
+                r#"{"choices":[{"delta":{},"finish_reason":"stop"}]}"#, "\n",
+            )
+            .as_bytes(),
+        );
+        assert_eq!(events, vec![
+            StreamEvent::ToolCallDelta { name: Some("lookup".to_string()), arguments: "{\"q\"".to_string() },
+            StreamEvent::Done { finish_reason: Some("stop".to_string()) },
+        ]);
+    }
+
+    #[test]
+    fn test_server_notifications() {
+        let mut parser = NdjsonStreamParser::new();
+
+        // A method without an id is a notification; a method with one
+        // is a request and parses as an ordinary (empty) frame
+        let events = parser.push(
+            concat!(
+                r#"{"jsonrpc":"2.0","method":"server.maintenance","params":{"message":"restarting in 5m"}}"#, "\n",
+                r#"{"jsonrpc":"2.0","method":"tools.call","id":"7","params":{}}"#, "\n",
+                r#"{"result":{"content":"hi"}}"#, "\n",
+            )
+            .as_bytes(),
+        );
+        assert_eq!(events.len(), 2);
+        match &events[0] {
+            StreamEvent::Notification(note) => {
+                assert_eq!(note.method, "server.maintenance");
+                assert_eq!(note.summary(), "server.maintenance: restarting in 5m");
+            }
+            other => panic!("expected Notification, got {:?}", other),
+        }
+        assert_eq!(events[1], StreamEvent::ContentDelta("hi".to_string()));
+
+        // Without a params message the summary falls back to the method
+        let bare = ServerNotification {
+            method: "context.purged".to_string(),
+            params: None,
+        };
+        assert_eq!(bare.summary(), "context.purged");
+    }
+
+    #[test]
+    fn test_errors_and_malformed_frames() {
+        let mut parser = NdjsonStreamParser::new();
+
+        let events = parser.push(
+            concat!(
+                r#"{"error":{"code":-32000,"message":"model overloaded"}}"#, "\n",
+                "not json at all\n",
+            )
+            .as_bytes(),
+        );
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0], StreamEvent::Error {
+            code: -32000,
+            message: "model overloaded".to_string(),
+            data: None,
+        });
+        // Malformed frames are surfaced with what the server sent, not
+        // silently dropped
+        match &events[1] {
+            StreamEvent::Malformed { snippet, .. } => assert_eq!(snippet, "not json at all"),
+            other => panic!("expected Malformed, got {:?}", other),
+        }
+    }
+}
+
 #[cfg(test)]
 mod config_tests {
     use std::collections::HashMap;
-    use serde_json;
+    
     use graph_os_cli::config::{AuthConfig, EndpointConfig, ConfigFormat};
     
     #[test]
@@ -75,11 +189,42 @@ mod config_tests {
             secret: Some("endpoint-secret".to_string()),
             token: None,
             use_tls: Some(true),
+            transport: None,
+            proxy: None,
+            ca_bundle: None,
+            danger_accept_invalid_certs: None,
+            compress: None,
+            compress_threshold: None,
+            oidc_issuer: None,
+            oidc_client_id: None,
+            refresh_token: None,
+            token_expiry: None,
+            http_version: None,
+            signing_key_id: None,
+            signing_key: None,
+            embedding_model: None,
+            extra_headers: HashMap::new(),
+            query_params: HashMap::new(),
         });
         
         let auth_config = AuthConfig {
             rpc_secret: Some("test-secret".to_string()),
             endpoints,
+            templates: HashMap::new(),
+            personas: HashMap::new(),
+            prices: HashMap::new(),
+            aliases: HashMap::new(),
+            macros: HashMap::new(),
+            hooks: graph_os_cli::hooks::HooksConfig::default(),
+            share: None,
+            accessible: None,
+            filters: None,
+            redact: None,
+            metrics: None,
+            archive: None,
+            layout: None,
+            policy: None,
+            fallback: Vec::new(),
         };
         
         // Test JSON serialization
@@ -99,6 +244,34 @@ mod config_tests {
         assert_eq!(deserialized.endpoints["default"].use_tls, Some(true));
     }
     
+    #[test]
+    fn test_http_version_parsing() {
+        use graph_os_cli::adapters::{HttpClientOptions, HttpVersion};
+
+        assert_eq!(HttpVersion::parse("auto"), Some(HttpVersion::Auto));
+        assert_eq!(HttpVersion::parse("http1"), Some(HttpVersion::Http1));
+        assert_eq!(HttpVersion::parse("http1.1"), Some(HttpVersion::Http1));
+        assert_eq!(HttpVersion::parse("http2"), Some(HttpVersion::Http2));
+        assert_eq!(HttpVersion::parse("h2"), Some(HttpVersion::Http2));
+        assert_eq!(HttpVersion::parse("spdy"), None);
+
+        // The endpoint config value overrides the Auto default; an
+        // unrecognized one is ignored rather than failing the merge
+        let endpoint = EndpointConfig {
+            http_version: Some("http1".to_string()),
+            ..EndpointConfig::default()
+        };
+        let options = HttpClientOptions::default().merge_endpoint(Some(&endpoint));
+        assert_eq!(options.http_version, HttpVersion::Http1);
+
+        let endpoint = EndpointConfig {
+            http_version: Some("spdy".to_string()),
+            ..EndpointConfig::default()
+        };
+        let options = HttpClientOptions::default().merge_endpoint(Some(&endpoint));
+        assert_eq!(options.http_version, HttpVersion::Auto);
+    }
+
     #[test]
     fn test_config_format() {
         // Test extension methods
@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod kb_tests {
+    use graph_os_cli::kb::{chunk_text, pack_path, retrieve, KbChunk, KnowledgePack};
+
+    fn pack_with(chunks: Vec<KbChunk>) -> KnowledgePack {
+        KnowledgePack {
+            name: "test".to_string(),
+            created_at: chrono::Utc::now(),
+            chunks,
+        }
+    }
+
+    fn chunk(source: &str, text: &str, embed: bool) -> KbChunk {
+        KbChunk {
+            source: source.to_string(),
+            text: text.to_string(),
+            vector: if embed {
+                graph_os_cli::embeddings::embed(text)
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    #[test]
+    fn chunk_text_packs_paragraphs_up_to_limit() {
+        let text = "first paragraph\n\nsecond paragraph\n\nthird paragraph";
+        let chunks = chunk_text(text, 40);
+        // First two paragraphs fit together; the third starts a new chunk
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0], "first paragraph\n\nsecond paragraph");
+        assert_eq!(chunks[1], "third paragraph");
+    }
+
+    #[test]
+    fn chunk_text_splits_oversized_paragraphs() {
+        let long = "x".repeat(25);
+        let chunks = chunk_text(&long, 10);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 10));
+        assert_eq!(chunks.join(""), long);
+    }
+
+    #[test]
+    fn chunk_text_skips_blank_input() {
+        assert!(chunk_text("", 100).is_empty());
+        assert!(chunk_text("\n\n  \n\n", 100).is_empty());
+    }
+
+    #[test]
+    fn pack_path_rejects_names_that_could_escape() {
+        assert!(pack_path("notes").is_ok());
+        assert!(pack_path("api_v2-docs").is_ok());
+        assert!(pack_path("").is_err());
+        assert!(pack_path("../escape").is_err());
+        assert!(pack_path("a/b").is_err());
+        assert!(pack_path("name with space").is_err());
+    }
+
+    #[test]
+    fn retrieve_ranks_embedded_chunks_by_similarity() {
+        let pack = pack_with(vec![
+            chunk("a.txt", "cooking pasta with tomato sauce and basil", true),
+            chunk("b.txt", "rust borrow checker lifetimes and ownership", true),
+        ]);
+        let out = retrieve(&pack, "how does the rust borrow checker work", 10_000);
+        let rust_pos = out.find("borrow checker").unwrap();
+        let pasta_pos = out.find("pasta").unwrap();
+        assert!(rust_pos < pasta_pos, "relevant chunk should come first");
+    }
+
+    #[test]
+    fn retrieve_keeps_document_order_without_embeddings() {
+        let pack = pack_with(vec![
+            chunk("a.txt", "zebra facts come first in the document", false),
+            chunk("b.txt", "apple facts come second in the document", false),
+        ]);
+        let out = retrieve(&pack, "apple", 10_000);
+        let zebra_pos = out.find("zebra").unwrap();
+        let apple_pos = out.find("apple facts").unwrap();
+        assert!(zebra_pos < apple_pos);
+    }
+
+    #[test]
+    fn retrieve_respects_the_token_budget() {
+        let pack = pack_with(vec![
+            chunk("a.txt", &"word ".repeat(50), false),
+            chunk("b.txt", &"more ".repeat(500), false),
+        ]);
+        // Budget fits the first chunk but not the second
+        let out = retrieve(&pack, "", 100);
+        assert!(out.contains("a.txt"));
+        assert!(!out.contains("b.txt"));
+    }
+}
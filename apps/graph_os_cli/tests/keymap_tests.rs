@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod keymap_tests {
+    use graph_os_cli::keymap::{search, REGISTRY};
+
+    #[test]
+    fn test_registry_keeps_categories_adjacent() {
+        assert!(!REGISTRY.is_empty());
+
+        // The overlay prints a header when the category changes, so a
+        // category split across the registry would render twice
+        let mut seen = Vec::new();
+        for binding in REGISTRY {
+            match seen.last() {
+                Some(&last) if last == binding.category => {}
+                _ => {
+                    assert!(
+                        !seen.contains(&binding.category),
+                        "category '{}' is not adjacent in the registry",
+                        binding.category
+                    );
+                    seen.push(binding.category);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_filters_case_insensitively() {
+        // Empty query: the whole registry
+        assert_eq!(search("").len(), REGISTRY.len());
+
+        // Matches against keys, description and category
+        assert!(search("FORK").iter().any(|b| b.keys.starts_with("/fork")));
+        assert!(search("sandbox").iter().any(|b| b.keys.starts_with("/run")));
+        let providers = search("providers");
+        assert!(providers.iter().all(|b| b.category == "Providers"));
+        assert!(!providers.is_empty());
+
+        assert!(search("no such binding anywhere").is_empty());
+    }
+}
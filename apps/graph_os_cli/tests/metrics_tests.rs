@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod metrics_tests {
+    use chrono::Utc;
+    use graph_os_cli::metrics::{
+        downsample, parse_trend_columns, sample_number, MetricsLog, SystemSample,
+    };
+
+    fn sample(load1: f64) -> SystemSample {
+        SystemSample {
+            recorded_at: Utc::now(),
+            hostname: "host-a".to_string(),
+            timestamp: 1_700_000_000,
+            cpu_count: 8,
+            load1,
+            load5: 0.5,
+            load15: 0.25,
+            memory_used: 512 * 1024 * 1024,
+            memory_free: 512 * 1024 * 1024,
+            memory_total: 1024 * 1024 * 1024,
+            uptime: 3600,
+        }
+    }
+
+    #[test]
+    fn test_downsample() {
+        // Short series pass through untouched
+        assert_eq!(downsample(&[1.0, 2.0], 4), vec![1.0, 2.0]);
+
+        // Longer series compress to bucket averages covering the whole
+        // range
+        let values: Vec<f64> = (0..8).map(|i| i as f64).collect();
+        assert_eq!(downsample(&values, 4), vec![0.5, 2.5, 4.5, 6.5]);
+
+        // Uneven splits still cover every value exactly once
+        let values: Vec<f64> = (0..5).map(|i| i as f64).collect();
+        let buckets = downsample(&values, 2);
+        assert_eq!(buckets.len(), 2);
+        let total: f64 = values.iter().sum();
+        assert!((buckets[0] * 2.0 + buckets[1] * 3.0 - total).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_trend_columns() {
+        let columns = parse_trend_columns("load1, mem_used").unwrap();
+        assert_eq!(columns, vec!["load1".to_string(), "mem_used".to_string()]);
+
+        assert!(parse_trend_columns("").is_err());
+        assert!(parse_trend_columns("hostname").is_err());
+
+        // Every series resolves to a number
+        let sample = sample(1.5);
+        for column in &parse_trend_columns(graph_os_cli::metrics::DEFAULT_TREND_COLUMNS).unwrap() {
+            assert!(sample_number(&sample, column).is_some());
+        }
+    }
+
+    #[test]
+    fn test_append_and_read() {
+        let dir = std::env::temp_dir().join(format!("gos-metrics-test-{}", uuid::Uuid::new_v4()));
+        let log = MetricsLog::with_path(dir.join("system_info.jsonl"));
+
+        log.append(&sample(0.5)).unwrap();
+        log.append(&sample(1.5)).unwrap();
+
+        let samples = log.read_since(None).unwrap();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].hostname, "host-a");
+        assert_eq!(samples[1].load1, 1.5);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
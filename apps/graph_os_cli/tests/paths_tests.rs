@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod paths_tests {
+    use graph_os_cli::paths::plan_migration;
+
+    #[test]
+    fn test_plan_migration() {
+        let root = std::env::temp_dir().join(format!("gos-paths-test-{}", uuid::Uuid::new_v4()));
+        let vibe = root.join(".vibe");
+        let graph_os = root.join(".graph_os");
+        let config = root.join("config");
+        let sessions = root.join("sessions");
+        let state = root.join("state");
+
+        std::fs::create_dir_all(&vibe).unwrap();
+        std::fs::create_dir_all(&graph_os).unwrap();
+        std::fs::create_dir_all(&sessions).unwrap();
+
+        std::fs::write(vibe.join("abc.json"), "{}").unwrap();
+        std::fs::write(vibe.join("notes.txt"), "not a session").unwrap();
+        std::fs::write(graph_os.join("config.toml"), "").unwrap();
+        std::fs::write(graph_os.join("audit.jsonl"), "").unwrap();
+
+        // A session already at the destination must not be overwritten
+        std::fs::write(vibe.join("existing.json"), "old").unwrap();
+        std::fs::write(sessions.join("existing.json"), "new").unwrap();
+
+        let mut moves = plan_migration(&vibe, &graph_os, &config, &sessions, &state);
+        moves.sort();
+
+        let targets: Vec<_> = moves.iter().map(|(_, to)| to.clone()).collect();
+        assert_eq!(
+            targets,
+            vec![
+                state.join("audit.jsonl"),
+                config.join("config.toml"),
+                sessions.join("abc.json"),
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_plan_migration_missing_dirs() {
+        let root = std::env::temp_dir().join(format!("gos-paths-test-{}", uuid::Uuid::new_v4()));
+
+        // Nothing to migrate when the legacy directories never existed
+        let moves = plan_migration(
+            &root.join(".vibe"),
+            &root.join(".graph_os"),
+            &root.join("config"),
+            &root.join("sessions"),
+            &root.join("state"),
+        );
+        assert!(moves.is_empty());
+    }
+}
@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod policy_tests {
+    use std::collections::HashMap;
+
+    use graph_os_cli::config::{PolicyConfig, PolicyProfile, TopicRule};
+    use graph_os_cli::policy::{clip, PolicyEngine};
+
+    fn config_with_rules() -> PolicyConfig {
+        PolicyConfig {
+            blocked: vec![
+                TopicRule {
+                    name: "competitors".to_string(),
+                    pattern: r"(?i)\bacme\s+corp\b".to_string(),
+                },
+                TopicRule {
+                    name: "broken".to_string(),
+                    pattern: "(unclosed".to_string(),
+                },
+            ],
+            max_response_chars: Some(100),
+            allowed_providers: vec!["OpenAI".to_string(), "anthropic".to_string()],
+            profiles: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_blocked_topics_deny_by_name() {
+        let engine = PolicyEngine::with_profile(&config_with_rules(), None);
+
+        assert!(engine.check_prompt("tell me about rust").is_ok());
+
+        // A match denies and names the rule, not the pattern
+        let denial = engine.check_prompt("what does Acme Corp pay?").unwrap_err();
+        assert_eq!(denial.rule, "blocked.competitors");
+        assert!(denial.message.contains("competitors"));
+        assert!(!denial.message.contains("acme"));
+
+        // The invalid pattern was skipped, not treated as match-all
+        assert!(engine.check_prompt("unrelated").is_ok());
+    }
+
+    #[test]
+    fn test_provider_allow_list() {
+        let engine = PolicyEngine::with_profile(&config_with_rules(), None);
+
+        // Comparison is case-insensitive on both sides
+        assert!(engine.check_provider("openai").is_ok());
+        assert!(engine.check_provider("Anthropic").is_ok());
+
+        let denial = engine.check_provider("Gemini").unwrap_err();
+        assert_eq!(denial.rule, "providers");
+        assert!(denial.message.contains("Gemini"));
+
+        // An empty list means every provider is allowed
+        let open = PolicyEngine::with_profile(&PolicyConfig::default(), None);
+        assert!(open.check_provider("gemini").is_ok());
+    }
+
+    #[test]
+    fn test_profile_overrides() {
+        let mut config = config_with_rules();
+        config.profiles.insert(
+            "interns".to_string(),
+            PolicyProfile {
+                allowed_providers: vec!["gemini".to_string()],
+                max_response_chars: Some(50),
+            },
+        );
+
+        let engine = PolicyEngine::with_profile(&config, Some("interns"));
+        assert!(engine.check_provider("gemini").is_ok());
+        assert!(engine.check_provider("openai").is_err());
+        assert_eq!(engine.max_response_chars(), Some(50));
+
+        // Blocked topics are global; profiles cannot drop them
+        assert!(engine.check_prompt("acme corp").is_err());
+
+        // An unknown profile falls back to the global rules
+        let engine = PolicyEngine::with_profile(&config, Some("contractors"));
+        assert!(engine.check_provider("openai").is_ok());
+        assert_eq!(engine.max_response_chars(), Some(100));
+    }
+
+    #[test]
+    fn test_clip_response_cap() {
+        // Under the cap nothing changes
+        assert!(clip("short", 10).is_none());
+
+        let clipped = clip(&"x".repeat(20), 10).unwrap();
+        assert!(clipped.starts_with(&"x".repeat(10)));
+        assert!(clipped.contains("truncated at 10 characters"));
+
+        // The cap counts characters, not bytes
+        assert!(clip("ééééé", 5).is_none());
+    }
+}
@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod recording_tests {
+    use graph_os_cli::adapters::recording::{exchange_path, read_exchange, write_exchange};
+    use serde_json::json;
+
+    #[test]
+    fn test_exchange_path_ignores_volatile_id() {
+        let dir = std::path::Path::new("/tmp");
+
+        let a = exchange_path(dir, "jsonrpc", "chat", &json!({ "x": 1, "id": "abc" }));
+        let b = exchange_path(dir, "jsonrpc", "chat", &json!({ "x": 1, "id": "def" }));
+        let c = exchange_path(dir, "jsonrpc", "chat", &json!({ "x": 2 }));
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+
+        // Method separators are sanitized out of the filename
+        let d = exchange_path(dir, "grpc", "SystemInfoService.GetSystemInfo", &json!({}));
+        assert!(d
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .starts_with("grpc-SystemInfoService_GetSystemInfo-"));
+    }
+
+    #[test]
+    fn test_write_and_read_exchange() {
+        let dir = std::env::temp_dir().join(format!("gos-recording-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let request = json!({ "messages": ["hi"], "stream": false });
+        let response = json!({ "message": "hello" });
+        write_exchange(&dir, "jsonrpc", "chat", &request, &response).unwrap();
+
+        let replayed = read_exchange(&dir, "jsonrpc", "chat", &request).unwrap();
+        assert_eq!(replayed, response);
+
+        // A request that was never recorded is an error, not a fallback
+        let missing = read_exchange(&dir, "jsonrpc", "chat", &json!({ "other": true }));
+        assert!(missing.unwrap_err().to_string().contains("No recorded response"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod render_tests {
+    use graph_os_cli::render::RenderStyle;
+    use ratatui::style::{Color, Style};
+
+    #[test]
+    fn test_default_style_keeps_decorations() {
+        let style = RenderStyle::new(false);
+
+        assert_eq!(style.fg(Color::Blue), Style::default().fg(Color::Blue));
+        assert_eq!(style.highlight_symbol(), ">> ");
+        assert_eq!(style.icon("🔌 "), "🔌 ");
+        assert!(style.moves_cursor());
+        assert_eq!(style.response_complete_announcement(), None);
+    }
+
+    #[test]
+    fn test_accessible_style_is_plain() {
+        let style = RenderStyle::new(true);
+
+        // No colors or reverse video: everything is the default style
+        assert_eq!(style.fg(Color::Blue), Style::default());
+        assert_eq!(style.highlight(), Style::default());
+
+        // Textual markers replace visual cues
+        assert_eq!(style.highlight_symbol(), "selected: ");
+        assert_eq!(style.icon("🔌 "), "");
+        assert_eq!(style.streaming_placeholder(), "(waiting for response)");
+        assert_eq!(style.response_complete_announcement(), Some("\n[Response complete]"));
+        assert!(!style.moves_cursor());
+    }
+
+    #[test]
+    fn test_sparkline_scales_to_its_own_range() {
+        use graph_os_cli::render::sparkline;
+
+        let ramp = RenderStyle::new(false).sparkline_ramp();
+
+        // Min and max land on the ends of the ramp regardless of units
+        let line = sparkline(&[100.0, 600.0, 1000.0], ramp);
+        assert_eq!(line, "▁▅█");
+        assert_eq!(sparkline(&[0.001, 0.006, 0.01], ramp), line);
+
+        // A flat series sits at mid-height instead of dividing by zero
+        assert_eq!(sparkline(&[5.0, 5.0, 5.0], ramp), "▅▅▅");
+        assert_eq!(sparkline(&[], ramp), "");
+
+        // The accessible ramp keeps the output pure ASCII
+        let ascii = sparkline(&[1.0, 2.0, 3.0], RenderStyle::new(true).sparkline_ramp());
+        assert!(ascii.is_ascii());
+    }
+}
+
+#[cfg(test)]
+mod graph_tests {
+    use graph_os_cli::render::{GraphEdge, GraphFormat, GraphNode, GraphOutput};
+
+    fn sample_graph() -> GraphOutput {
+        GraphOutput {
+            nodes: vec![
+                GraphNode { id: "a".to_string(), label: Some("Node \"A\"".to_string()) },
+                GraphNode { id: "b".to_string(), label: None },
+                GraphNode { id: "c".to_string(), label: None },
+            ],
+            edges: vec![
+                GraphEdge { from: "a".to_string(), to: "b".to_string(), label: Some("uses".to_string()) },
+                GraphEdge { from: "a".to_string(), to: "c".to_string(), label: None },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_graph_format_parse() {
+        assert_eq!(GraphFormat::parse("DOT").unwrap(), GraphFormat::Dot);
+        assert_eq!(GraphFormat::parse("mermaid").unwrap(), GraphFormat::Mermaid);
+        assert!(GraphFormat::parse("svg").is_err());
+    }
+
+    #[test]
+    fn test_render_dot() {
+        let dot = sample_graph().render(GraphFormat::Dot);
+        assert!(dot.starts_with("digraph G {"));
+        // Quotes in labels are escaped
+        assert!(dot.contains("\"a\" [label=\"Node \\\"A\\\"\"];"));
+        assert!(dot.contains("\"a\" -> \"b\" [label=\"uses\"];"));
+        assert!(dot.contains("\"a\" -> \"c\";"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn test_render_mermaid() {
+        let mermaid = sample_graph().render(GraphFormat::Mermaid);
+        assert!(mermaid.starts_with("graph TD"));
+        assert!(mermaid.contains("n0 -->|uses| n1"));
+        assert!(mermaid.contains("n0 --> n2"));
+        // Unlabeled nodes fall back to their id
+        assert!(mermaid.contains("n1[\"b\"]"));
+    }
+
+    #[test]
+    fn test_render_ascii() {
+        let ascii = sample_graph().render(GraphFormat::Ascii);
+        assert!(ascii.contains("a -> b (uses), c"));
+        // Nodes without outgoing edges are still listed
+        assert!(ascii.contains("b\n"));
+    }
+}
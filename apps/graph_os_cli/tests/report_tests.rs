@@ -0,0 +1,165 @@
+#[cfg(test)]
+mod report_tests {
+    use std::collections::HashMap;
+
+    use graph_os_cli::adapters::grpc::graph_os::SystemInfo;
+    use graph_os_cli::config::MetricsConfig;
+    use graph_os_cli::report::{
+        metric_name, parse_columns, render_aggregates, render_csv, render_fleet_table,
+        render_influx, render_prometheus, render_table, sort_fleet, sort_records, FleetRow,
+        TimestampUnit,
+    };
+
+    fn record(hostname: &str, load1: f64, mem_used: i64) -> SystemInfo {
+        SystemInfo {
+            id: format!("{}-id", hostname),
+            hostname: hostname.to_string(),
+            timestamp: 1_700_000_000,
+            cpu_count: 8,
+            cpu_load_1m: load1,
+            cpu_load_5m: load1 / 2.0,
+            cpu_load_15m: load1 / 4.0,
+            memory_total: 16 * 1024 * 1024 * 1024,
+            memory_used: mem_used,
+            memory_free: 16 * 1024 * 1024 * 1024 - mem_used,
+            uptime: 3600,
+            os_version: "test".to_string(),
+            platform: "linux".to_string(),
+            architecture: "x86_64".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_columns() {
+        let columns = parse_columns("hostname, load1").unwrap();
+        assert_eq!(columns, vec!["hostname", "load1"]);
+
+        assert!(parse_columns("hostname,bogus").is_err());
+        assert!(parse_columns(",").is_err());
+    }
+
+    #[test]
+    fn test_sort_records() {
+        let mut items = vec![record("b", 2.0, 100), record("a", 1.0, 200)];
+
+        sort_records(&mut items, "load1").unwrap();
+        assert_eq!(items[0].hostname, "a");
+
+        sort_records(&mut items, "-load1").unwrap();
+        assert_eq!(items[0].hostname, "b");
+
+        // Text columns fall back to lexical ordering
+        sort_records(&mut items, "hostname").unwrap();
+        assert_eq!(items[0].hostname, "a");
+
+        assert!(sort_records(&mut items, "bogus").is_err());
+    }
+
+    #[test]
+    fn test_render_table_and_csv() {
+        let items = vec![record("host1", 0.5, 512 * 1024 * 1024)];
+        let columns = parse_columns("hostname,load1,mem_used").unwrap();
+
+        let table = render_table(&items, &columns);
+        let mut lines = table.lines();
+        assert!(lines.next().unwrap().starts_with("hostname"));
+        assert!(lines.next().unwrap().starts_with("--------"));
+        let row = lines.next().unwrap();
+        assert!(row.contains("host1"));
+        assert!(row.contains("0.50"));
+        assert!(row.contains("512M"));
+
+        let csv = render_csv(&items, &columns);
+        assert_eq!(csv, "hostname,load1,mem_used\nhost1,0.50,512M\n");
+    }
+
+    #[test]
+    fn test_render_aggregates() {
+        let items = vec![record("a", 1.0, 100), record("b", 3.0, 100)];
+        let columns = parse_columns("hostname,load1").unwrap();
+
+        let aggregates = render_aggregates(&items, &columns);
+        // hostname has no numeric view and is skipped
+        assert!(!aggregates.contains("hostname"));
+        assert!(aggregates.contains("load1: min 1.00, max 3.00, avg 2.00"));
+    }
+
+    #[test]
+    fn test_sort_fleet_unreachable_sinks() {
+        let mut rows = vec![
+            FleetRow { endpoint: "dead2".to_string(), info: None },
+            FleetRow { endpoint: "low".to_string(), info: Some(record("low", 0.5, 100)) },
+            FleetRow { endpoint: "dead1".to_string(), info: None },
+            FleetRow { endpoint: "high".to_string(), info: Some(record("high", 4.0, 100)) },
+        ];
+
+        // Descending by load, but unreachable hosts still come last,
+        // ordered by name among themselves
+        sort_fleet(&mut rows, "-load1").unwrap();
+        let order: Vec<&str> = rows.iter().map(|r| r.endpoint.as_str()).collect();
+        assert_eq!(order, vec!["high", "low", "dead1", "dead2"]);
+
+        assert!(sort_fleet(&mut rows, "bogus").is_err());
+    }
+
+    #[test]
+    fn test_render_fleet_table() {
+        let rows = vec![
+            FleetRow { endpoint: "alpha".to_string(), info: Some(record("alpha", 1.0, 512)) },
+            FleetRow { endpoint: "beta".to_string(), info: None },
+        ];
+        let columns = parse_columns("hostname,load1").unwrap();
+
+        let out = render_fleet_table(&rows, &columns);
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[0].starts_with("endpoint"));
+        assert!(lines[0].contains("status"));
+        assert!(lines[2].contains("ok"));
+        assert!(lines[2].contains("1.00"));
+        // The dead host keeps its row, flagged with empty cells
+        assert!(lines[3].contains("unreachable"));
+        assert!(lines[3].contains("-"));
+    }
+
+    #[test]
+    fn test_metric_name_mapping() {
+        let config = MetricsConfig {
+            prefix: Some("node".to_string()),
+            rename: HashMap::from([("load1".to_string(), "system_load_1m".to_string())]),
+        };
+
+        // A rename wins over the prefix; everything else gets prefixed
+        assert_eq!(metric_name("load1", &config), "system_load_1m");
+        assert_eq!(metric_name("mem_used", &config), "node_mem_used");
+        assert_eq!(metric_name("cpu", &MetricsConfig::default()), "graphos_cpu");
+    }
+
+    #[test]
+    fn test_timestamp_unit() {
+        assert_eq!(TimestampUnit::parse("s").unwrap().convert(2), 2);
+        assert_eq!(TimestampUnit::parse("millis").unwrap().convert(2), 2_000);
+        assert_eq!(TimestampUnit::parse("ns").unwrap().convert(2), 2_000_000_000);
+        assert!(TimestampUnit::parse("fortnights").is_err());
+    }
+
+    #[test]
+    fn test_render_prometheus() {
+        let items = vec![record("host1", 0.5, 100)];
+        let out = render_prometheus(&items, &MetricsConfig::default(), TimestampUnit::Millis);
+
+        assert!(out.contains("# TYPE graphos_load1 gauge"));
+        assert!(out.contains("graphos_load1{host=\"host1\"} 0.5 1700000000000"));
+    }
+
+    #[test]
+    fn test_render_influx() {
+        let items = vec![record("my host", 0.5, 100)];
+        let out = render_influx(&items, &MetricsConfig::default(), TimestampUnit::Nanos);
+
+        let line = out.lines().next().unwrap();
+        // Spaces in tag values must be escaped or the point is rejected
+        assert!(line.starts_with("graphos,host=my\\ host "));
+        assert!(line.contains("load1=0.5"));
+        assert!(line.ends_with(" 1700000000000000000"));
+    }
+}
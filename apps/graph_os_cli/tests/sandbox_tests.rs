@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod sandbox_tests {
+    use graph_os_cli::sandbox::{extract_last_code_block, interpreter_for, run_code, CodeBlock};
+
+    #[test]
+    fn test_extract_last_code_block() {
+        let text = "Here is a first attempt:\n\
+                    ```python\nprint('one')\n```\n\
+                    And a fixed version:\n\
+                    ```python\nprint('two')\n```\n";
+        let block = extract_last_code_block(text).unwrap();
+        assert_eq!(block.language, "python");
+        assert_eq!(block.code, "print('two')");
+
+        // An unterminated fence is not a complete block
+        let block = extract_last_code_block("```sh\necho hi\n```\n```python\nopen").unwrap();
+        assert_eq!(block.language, "sh");
+
+        assert!(extract_last_code_block("no code here").is_none());
+    }
+
+    #[test]
+    fn test_interpreter_for() {
+        assert_eq!(interpreter_for("python"), Some("python3"));
+        assert_eq!(interpreter_for("sh"), Some("bash"));
+        assert_eq!(interpreter_for("rust"), None);
+        assert_eq!(interpreter_for(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_run_shell_block() {
+        let block = CodeBlock {
+            language: "sh".to_string(),
+            code: "echo hello; echo oops >&2; exit 3".to_string(),
+        };
+        let output = run_code(&block).await.unwrap();
+
+        assert_eq!(output.exit_code, Some(3));
+        assert!(!output.timed_out);
+        assert_eq!(output.stdout.trim(), "hello");
+        assert_eq!(output.stderr.trim(), "oops");
+
+        let rendered = output.render();
+        assert!(rendered.contains("exit code 3"));
+        assert!(rendered.contains("stdout:\nhello"));
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod schema_tests {
+    use graph_os_cli::schema::{extract_json, validate};
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_accepts_conforming_instance() {
+        let schema = json!({
+            "type": "object",
+            "required": ["name", "score"],
+            "properties": {
+                "name": { "type": "string", "minLength": 1 },
+                "score": { "type": "integer", "minimum": 0, "maximum": 100 },
+                "tags": { "type": "array", "items": { "type": "string" }, "maxItems": 3 }
+            },
+            "additionalProperties": false
+        });
+        let instance = json!({ "name": "alpha", "score": 42, "tags": ["a", "b"] });
+        assert!(validate(&schema, &instance).is_empty());
+    }
+
+    #[test]
+    fn test_validate_reports_each_violation_with_its_path() {
+        let schema = json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "score": { "type": "integer", "maximum": 100 },
+                "kind": { "enum": ["a", "b"] }
+            },
+            "additionalProperties": false
+        });
+        let instance = json!({ "score": 200, "kind": "c", "extra": true });
+
+        let errors = validate(&schema, &instance);
+        assert!(errors.iter().any(|e| e.contains("missing required property 'name'")));
+        assert!(errors.iter().any(|e| e.contains("$.score") && e.contains("maximum")));
+        assert!(errors.iter().any(|e| e.contains("$.kind") && e.contains("enum")));
+        assert!(errors.iter().any(|e| e.contains("unexpected property 'extra'")));
+    }
+
+    #[test]
+    fn test_validate_type_mismatch_names_both_types() {
+        let schema = json!({ "type": "string" });
+        let errors = validate(&schema, &json!(5));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("expected string, got number"));
+
+        // An integer is a number, but a float is not an integer
+        assert!(validate(&json!({ "type": "number" }), &json!(5)).is_empty());
+        assert!(!validate(&json!({ "type": "integer" }), &json!(5.5)).is_empty());
+    }
+
+    #[test]
+    fn test_extract_json_tolerates_fences_and_prose() {
+        assert_eq!(extract_json("{\"a\": 1}"), Some(json!({"a": 1})));
+        assert_eq!(
+            extract_json("Here you go:\n```json\n{\"a\": 1}\n```\nHope that helps!"),
+            Some(json!({"a": 1}))
+        );
+        assert_eq!(extract_json("The answer is [1, 2, 3], as requested."), Some(json!([1, 2, 3])));
+        assert_eq!(extract_json("no json here"), None);
+    }
+}
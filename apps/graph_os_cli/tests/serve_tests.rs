@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod serve_tests {
+    use graph_os_cli::adapters::{JsonRpcClient, Message, MessageContent, MessageRole};
+    use graph_os_cli::serve;
+    use serde_json::json;
+
+    fn client_for(addr: std::net::SocketAddr) -> JsonRpcClient {
+        let endpoint = format!("http://{}/api/jsonrpc", addr);
+        JsonRpcClient::with_endpoint(endpoint, None, None, None)
+    }
+
+    #[tokio::test]
+    async fn test_ping_and_echo() {
+        let (addr, server) = serve::bind("127.0.0.1", 0, None, None).unwrap();
+        tokio::spawn(server);
+
+        let client = client_for(addr);
+        assert!(client.ping().await.unwrap());
+
+        let messages = vec![Message {
+            role: MessageRole::User,
+            content: MessageContent::Text("hello there".to_string()),
+        }];
+        let response = client.chat(messages, false, None).await.unwrap();
+        assert_eq!(response, "Echo: hello there");
+    }
+
+    #[tokio::test]
+    async fn test_streaming_chat() {
+        let (addr, server) = serve::bind("127.0.0.1", 0, None, None).unwrap();
+        tokio::spawn(server);
+
+        let client = client_for(addr);
+        let messages = vec![Message {
+            role: MessageRole::User,
+            content: MessageContent::Text("stream these words".to_string()),
+        }];
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+        client.chat(messages, true, Some(tx)).await.unwrap();
+
+        let mut collected = String::new();
+        while let Some(chunk) = rx.recv().await {
+            collected.push_str(&chunk);
+        }
+        assert_eq!(collected, "Echo: stream these words");
+    }
+
+    #[tokio::test]
+    async fn test_session_lifecycle_and_unknown_method() {
+        let (addr, server) = serve::bind("127.0.0.1", 0, None, None).unwrap();
+        tokio::spawn(server);
+
+        let client = client_for(addr);
+
+        let created = client.request("session.create", json!({})).await.unwrap();
+        let id = created["id"].as_str().unwrap().to_string();
+
+        let listed = client.request("session.list", json!({})).await.unwrap();
+        assert_eq!(listed["sessions"].as_array().unwrap().len(), 1);
+
+        let fetched = client.request("session.get", json!({ "id": id })).await.unwrap();
+        assert_eq!(fetched["id"].as_str().unwrap(), id);
+
+        client.request("session.delete", json!({ "id": id })).await.unwrap();
+        let missing = client.request("session.get", json!({ "id": id })).await;
+        assert!(missing.is_err());
+
+        let unknown = client.request("no.such.method", json!({})).await;
+        assert!(unknown.is_err());
+    }
+}
@@ -0,0 +1,464 @@
+#[cfg(test)]
+mod session_tests {
+    use std::time::Duration;
+
+    use graph_os_cli::session::{filter_messages, ChatMessage, DirtyTracker, MessageFilter, MessageMeta};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_legacy_message_format_migrates() {
+        // Sessions written before metadata existed store each message
+        // as a bare string under the role tag
+        let legacy: ChatMessage = serde_json::from_str(r#"{"User":"hello"}"#).unwrap();
+        assert_eq!(legacy.role(), "user");
+        assert_eq!(legacy.text(), "hello");
+        assert!(legacy.meta().is_empty());
+
+        let modern: ChatMessage = serde_json::from_str(
+            r#"{"Assistant":{"text":"hi","meta":{"model":"gpt-4o","latency_ms":120}}}"#,
+        )
+        .unwrap();
+        assert_eq!(modern.role(), "assistant");
+        assert_eq!(modern.meta().model.as_deref(), Some("gpt-4o"));
+        assert_eq!(modern.meta().latency_ms, Some(120));
+    }
+
+    #[test]
+    fn test_message_round_trip_keeps_meta() {
+        let message = ChatMessage::assistant("answer".to_string());
+        let json = serde_json::to_string(&message).unwrap();
+        let parsed: ChatMessage = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.text(), "answer");
+        // Constructors stamp the creation time
+        assert_eq!(parsed.meta().timestamp, message.meta().timestamp);
+    }
+
+    #[test]
+    fn test_meta_summary() {
+        let meta = MessageMeta {
+            provider: Some("openai".to_string()),
+            model: Some("gpt-4o".to_string()),
+            latency_ms: Some(950),
+            tokens: Some(42),
+            ..MessageMeta::default()
+        };
+        assert_eq!(meta.summary(), "openai | gpt-4o | 950ms | ~42 tokens");
+        assert!(MessageMeta::default().is_empty());
+        assert!(!meta.is_empty());
+    }
+
+    #[test]
+    fn test_pinned_flag_persists_and_marks_meta_non_empty() {
+        let meta = MessageMeta { pinned: true, ..MessageMeta::default() };
+        // A pin alone must keep the meta from being dropped on save
+        assert!(!meta.is_empty());
+        assert_eq!(meta.summary(), "pinned");
+
+        let json = serde_json::to_string(&meta).unwrap();
+        let parsed: MessageMeta = serde_json::from_str(&json).unwrap();
+        assert!(parsed.pinned);
+
+        // Older session files without the field still parse
+        let parsed: MessageMeta = serde_json::from_str("{}").unwrap();
+        assert!(!parsed.pinned);
+    }
+
+    #[test]
+    fn test_dirty_tracker_only_reports_unsaved_changes() {
+        let mut tracker = DirtyTracker::default();
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+
+        let version_a = tracker.touch(a);
+        tracker.touch(b);
+
+        // Both changed, neither saved yet
+        assert_eq!(tracker.dirty(Duration::ZERO).len(), 2);
+
+        tracker.mark_saved(a, version_a);
+        assert_eq!(tracker.dirty(Duration::ZERO), vec![(b, 1)]);
+
+        // A change after the save makes the session dirty again
+        tracker.touch(a);
+        assert_eq!(tracker.dirty(Duration::ZERO).len(), 2);
+    }
+
+    #[test]
+    fn test_dirty_tracker_stale_save_does_not_mark_clean() {
+        let mut tracker = DirtyTracker::default();
+        let id = Uuid::new_v4();
+
+        let old_version = tracker.touch(id);
+        let new_version = tracker.touch(id);
+
+        // Acknowledging the older write must not hide the newer change
+        tracker.mark_saved(id, old_version);
+        assert_eq!(tracker.dirty(Duration::ZERO), vec![(id, new_version)]);
+
+        tracker.mark_saved(id, new_version);
+        assert!(tracker.dirty(Duration::ZERO).is_empty());
+    }
+
+    #[test]
+    fn test_dirty_tracker_debounces_recent_updates() {
+        let mut tracker = DirtyTracker::default();
+        let id = Uuid::new_v4();
+        tracker.touch(id);
+
+        // Just-touched sessions are held back until they settle
+        assert!(tracker.dirty(Duration::from_secs(60)).is_empty());
+        assert_eq!(tracker.dirty(Duration::ZERO).len(), 1);
+    }
+
+    #[test]
+    fn test_page_bounds_clamps_to_conversation() {
+        use graph_os_cli::session::page_bounds;
+
+        assert_eq!(page_bounds(10, 0, 4), (0, 4));
+        assert_eq!(page_bounds(10, 8, 4), (8, 10));
+        // Past the end: an empty page, so limit 0 probes the length
+        assert_eq!(page_bounds(10, 20, 4), (10, 10));
+        assert_eq!(page_bounds(10, 0, 0), (0, 0));
+        // A limit that would overflow still clamps to the end
+        assert_eq!(page_bounds(10, 2, usize::MAX), (2, 10));
+    }
+
+    #[test]
+    fn test_filter_messages_combines_criteria() {
+        let messages = vec![
+            ChatMessage::user("first question".to_string()),
+            ChatMessage::assistant("first answer".to_string()),
+            ChatMessage::user("second question".to_string()),
+            ChatMessage::assistant("second answer".to_string()),
+        ];
+
+        // An empty filter passes everything, keeping stored indices
+        let filter = MessageFilter::default();
+        assert!(filter.is_empty());
+        let all = filter_messages(&messages, &filter).unwrap();
+        assert_eq!(all.len(), 4);
+        assert_eq!(all[2].0, 2);
+
+        // Role and grep narrow independently, then combine
+        let filter = MessageFilter { role: Some("user".to_string()), ..Default::default() };
+        assert_eq!(filter_messages(&messages, &filter).unwrap().len(), 2);
+        let filter = MessageFilter {
+            role: Some("assistant".to_string()),
+            grep: Some("second".to_string()),
+            ..Default::default()
+        };
+        let hits = filter_messages(&messages, &filter).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, 3);
+
+        // A half-open range selects by stored index
+        let filter = MessageFilter { range: Some((1, 3)), ..Default::default() };
+        let hits = filter_messages(&messages, &filter).unwrap();
+        assert_eq!(hits.iter().map(|(i, _)| *i).collect::<Vec<_>>(), vec![1, 2]);
+
+        // `last` keeps the newest survivors after the other criteria
+        let filter = MessageFilter { last: Some(1), role: Some("user".to_string()), ..Default::default() };
+        let hits = filter_messages(&messages, &filter).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, 2);
+
+        // An invalid grep pattern is an error, not an empty result
+        let filter = MessageFilter { grep: Some("[unclosed".to_string()), ..Default::default() };
+        assert!(filter_messages(&messages, &filter).is_err());
+    }
+
+    #[test]
+    fn test_index_entry_captures_session_header() {
+        use graph_os_cli::session::{Session, SessionIndexEntry};
+        use uuid::Uuid;
+
+        let mut session = Session::new(Uuid::new_v4());
+        session.title = Some("triage".to_string());
+        session.tags = vec!["work".to_string()];
+        session.parent_id = Some(Uuid::new_v4());
+        session.forked_at = Some(3);
+        session.messages.push(ChatMessage::user("hello".to_string()));
+        session.messages.push(ChatMessage::assistant("hi".to_string()));
+
+        let entry = SessionIndexEntry::from(&session);
+        assert_eq!(entry.id, session.id);
+        assert_eq!(entry.title.as_deref(), Some("triage"));
+        assert_eq!(entry.tags, session.tags);
+        assert_eq!(entry.parent_id, session.parent_id);
+        assert_eq!(entry.forked_at, Some(3));
+        assert_eq!(entry.messages, 2);
+
+        // Entries round-trip through the index file format
+        let json = serde_json::to_string(&entry).unwrap();
+        let back: SessionIndexEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.id, entry.id);
+        assert_eq!(back.messages, 2);
+    }
+
+    #[tokio::test]
+    async fn test_frame_round_trip() {
+        use graph_os_cli::session::{read_frame, write_frame};
+
+        let (mut client, mut server) = tokio::io::duplex(256);
+        write_frame(&mut client, b"hello").await.unwrap();
+        write_frame(&mut client, b"").await.unwrap();
+
+        // Frames come back whole and in order, independent of how the
+        // transport happens to chunk the bytes
+        assert_eq!(read_frame(&mut server).await.unwrap(), b"hello");
+        assert_eq!(read_frame(&mut server).await.unwrap(), b"");
+    }
+
+    #[tokio::test]
+    async fn test_framed_message_carries_version() {
+        use graph_os_cli::session::{read_framed_message, write_framed_message, FRAME_MAGIC, PROTOCOL_VERSION};
+        use tokio::io::AsyncReadExt;
+
+        let (mut client, mut server) = tokio::io::duplex(256);
+        write_framed_message(&mut client, PROTOCOL_VERSION, b"{\"Ping\":null}")
+            .await
+            .unwrap();
+
+        // The magic byte distinguishes framed peers from legacy bare
+        // JSON, which always starts with '"' or '{'
+        let mut first = [0u8; 1];
+        server.read_exact(&mut first).await.unwrap();
+        assert_eq!(first[0], FRAME_MAGIC);
+        assert_ne!(FRAME_MAGIC, b'"');
+        assert_ne!(FRAME_MAGIC, b'{');
+
+        let (version, payload) = read_framed_message(&mut server).await.unwrap();
+        assert_eq!(version, PROTOCOL_VERSION);
+        assert_eq!(payload, b"{\"Ping\":null}");
+    }
+
+    #[tokio::test]
+    async fn test_frame_rejects_oversized_length_prefix() {
+        use graph_os_cli::session::read_frame;
+        use tokio::io::AsyncWriteExt;
+
+        let (mut client, mut server) = tokio::io::duplex(64);
+        // A corrupt or hostile length prefix must fail before allocating
+        client.write_all(&u32::MAX.to_le_bytes()).await.unwrap();
+        assert!(read_frame(&mut server).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_large_update_survives_the_listener_socket() {
+        use graph_os_cli::session::{ping_listener, request_shutdown, ChatMessage, Session, SessionManager};
+        use tokio::time::sleep;
+
+        // Point the data directory at scratch space before anything
+        // resolves it, so the daemon below serves a throwaway sessions
+        // directory instead of the developer's real one
+        let dir = std::env::temp_dir().join(format!("gos-session-e2e-{}", Uuid::new_v4()));
+        unsafe {
+            std::env::set_var("XDG_DATA_HOME", &dir);
+            std::env::set_var("HOME", &dir);
+        }
+
+        // A real listener on the real socket, exactly as `gos daemon
+        // run` starts one
+        tokio::spawn(async {
+            if let Err(e) = SessionManager::run_daemon().await {
+                eprintln!("Daemon exited with error: {}", e);
+            }
+        });
+        let mut up = false;
+        for _ in 0..50 {
+            if ping_listener().await.is_some() {
+                up = true;
+                break;
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+        assert!(up, "listener never came up");
+
+        // This manager connects as a client, so update_session goes
+        // over the socket rather than touching the maps directly
+        let manager = SessionManager::init().await.unwrap();
+        assert!(!manager.is_listener());
+
+        // Well past any single read buffer: the legacy exchange capped
+        // commands at one small read, which truncated exactly this kind
+        // of payload into "Invalid command format"
+        let mut session = Session::new(Uuid::new_v4());
+        session.title = Some("large".to_string());
+        session.messages.push(ChatMessage::user("x".repeat(100_000)));
+        manager.update_session(session.clone()).await.unwrap();
+
+        // The round trip back is just as large and must arrive whole
+        let fetched = manager.get_session(session.id).await.unwrap().unwrap();
+        assert_eq!(fetched.title.as_deref(), Some("large"));
+        assert_eq!(fetched.messages.len(), 1);
+        assert_eq!(fetched.messages[0].text(), session.messages[0].text());
+
+        assert!(request_shutdown().await.unwrap());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_lease_table_acquire_renew_and_takeover() {
+        use std::time::Instant;
+        use graph_os_cli::session::{LeaseTable, LEASE_TTL};
+        use uuid::Uuid;
+
+        let mut leases = LeaseTable::default();
+        let id = Uuid::new_v4();
+        let now = Instant::now();
+
+        // First claim wins; a second process is told who holds it
+        assert_eq!(leases.acquire(id, 100, false, now), Ok(()));
+        assert_eq!(leases.acquire(id, 200, false, now), Err(100));
+
+        // The holder re-acquiring is a renewal, not a conflict
+        assert_eq!(leases.acquire(id, 100, false, now + LEASE_TTL / 2), Ok(()));
+
+        // An expired lease is up for grabs without forcing
+        assert_eq!(leases.acquire(id, 200, false, now + LEASE_TTL * 2), Ok(()));
+
+        // --force takes over a live lease
+        assert_eq!(leases.acquire(id, 300, true, now + LEASE_TTL * 2), Ok(()));
+
+        // Release by a non-holder is ignored; release by the holder frees it
+        leases.release(id, 200);
+        assert_eq!(leases.acquire(id, 400, false, now + LEASE_TTL * 2), Err(300));
+        leases.release(id, 300);
+        assert_eq!(leases.acquire(id, 400, false, now + LEASE_TTL * 2), Ok(()));
+    }
+
+    #[test]
+    fn test_list_options_sort_and_page() {
+        use chrono::{Duration as ChronoDuration, Utc};
+        use graph_os_cli::session::{apply_list_options, ListOptions, SessionSort};
+
+        let now = Utc::now();
+        // (title, created_at, last_active) triples; the extractor below
+        // passes them straight through
+        let items = vec![
+            (Some("beta".to_string()), now - ChronoDuration::hours(3), now - ChronoDuration::hours(1)),
+            (None, now - ChronoDuration::hours(2), now),
+            (Some("Alpha".to_string()), now - ChronoDuration::hours(1), now - ChronoDuration::hours(2)),
+        ];
+        let fields = |item: &(Option<String>, chrono::DateTime<Utc>, chrono::DateTime<Utc>)| item.clone();
+
+        // last-active: newest activity first
+        let options = ListOptions { sort: SessionSort::LastActive, ..Default::default() };
+        let (sorted, total) = apply_list_options(items.clone(), &options, fields);
+        assert_eq!(total, 3);
+        assert_eq!(sorted[0].0, None);
+        assert_eq!(sorted[2].0.as_deref(), Some("Alpha"));
+
+        // created: oldest first
+        let options = ListOptions { sort: SessionSort::Created, ..Default::default() };
+        let (sorted, _) = apply_list_options(items.clone(), &options, fields);
+        assert_eq!(sorted[0].0.as_deref(), Some("beta"));
+
+        // title: case-insensitive, untitled last
+        let options = ListOptions { sort: SessionSort::Title, ..Default::default() };
+        let (sorted, _) = apply_list_options(items.clone(), &options, fields);
+        assert_eq!(sorted[0].0.as_deref(), Some("Alpha"));
+        assert_eq!(sorted[1].0.as_deref(), Some("beta"));
+        assert_eq!(sorted[2].0, None);
+
+        // Paging applies after sorting and reports the unpaged total
+        let options = ListOptions {
+            sort: SessionSort::Title,
+            offset: 1,
+            limit: Some(1),
+            ..Default::default()
+        };
+        let (page, total) = apply_list_options(items, &options, fields);
+        assert_eq!(total, 3);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].0.as_deref(), Some("beta"));
+    }
+
+    #[test]
+    fn test_find_duplicate_sessions() {
+        use chrono::Duration as ChronoDuration;
+        use graph_os_cli::session::{find_duplicate_sessions, ChatMessage, Session};
+
+        let mut short = Session::new(Uuid::new_v4());
+        short.messages = vec![
+            ChatMessage::user("hello".to_string()),
+            ChatMessage::assistant("hi".to_string()),
+        ];
+        let mut long = Session::new(Uuid::new_v4());
+        long.messages = vec![
+            ChatMessage::user("hello".to_string()),
+            ChatMessage::assistant("hi".to_string()),
+            ChatMessage::user("more".to_string()),
+        ];
+        let mut unrelated = Session::new(Uuid::new_v4());
+        unrelated.messages = vec![ChatMessage::user("something else".to_string())];
+        let empty = Session::new(Uuid::new_v4());
+
+        // The shorter prefix is the duplicate, the superset survives;
+        // empty sessions are never considered duplicates
+        let pairs =
+            find_duplicate_sessions(&[short.clone(), long.clone(), unrelated, empty]);
+        assert_eq!(pairs, vec![(short.id, long.id)]);
+
+        // A deliberate fork is not a duplicate
+        short.parent_id = Some(long.id);
+        assert!(find_duplicate_sessions(&[short.clone(), long.clone()]).is_empty());
+        short.parent_id = None;
+
+        // Identical conversations report exactly one duplicate: the
+        // copy with the older activity loses
+        let mut twin = short.clone();
+        twin.id = Uuid::new_v4();
+        twin.last_active = short.last_active + ChronoDuration::hours(1);
+        let pairs = find_duplicate_sessions(&[short.clone(), twin.clone()]);
+        assert_eq!(pairs, vec![(short.id, twin.id)]);
+    }
+
+    #[test]
+    fn test_merge_duplicate() {
+        use chrono::Duration as ChronoDuration;
+        use graph_os_cli::session::{merge_duplicate, ChatMessage, Session};
+
+        let mut survivor = Session::new(Uuid::new_v4());
+        survivor.messages = vec![
+            ChatMessage::user("hello".to_string()),
+            ChatMessage::assistant("hi".to_string()),
+        ];
+        survivor.bookmarks = vec![1];
+        survivor.tags = vec!["work".to_string()];
+
+        let mut duplicate = Session::new(Uuid::new_v4());
+        duplicate.messages = vec![ChatMessage::user("hello".to_string())];
+        duplicate.bookmarks = vec![0, 1];
+        duplicate.tags = vec!["work".to_string(), "rust".to_string()];
+        duplicate.created_at = survivor.created_at - ChronoDuration::hours(2);
+        duplicate.title = Some("Refactor plan".to_string());
+
+        merge_duplicate(&mut survivor, &duplicate);
+
+        // Bookmarks and tags union, creation time moves back to the
+        // earlier copy, and the missing title is filled in; messages
+        // stay the survivor's
+        assert_eq!(survivor.bookmarks, vec![0, 1]);
+        assert_eq!(survivor.tags, vec!["work".to_string(), "rust".to_string()]);
+        assert_eq!(survivor.created_at, duplicate.created_at);
+        assert_eq!(survivor.title.as_deref(), Some("Refactor plan"));
+        assert_eq!(survivor.messages.len(), 2);
+    }
+
+    #[test]
+    fn test_format_relative_buckets() {
+        use chrono::{Duration as ChronoDuration, Utc};
+        use graph_os_cli::session::format_relative;
+
+        let now = Utc::now();
+        assert_eq!(format_relative(now - ChronoDuration::seconds(30), now), "just now");
+        assert_eq!(format_relative(now - ChronoDuration::minutes(5), now), "5m ago");
+        assert_eq!(format_relative(now - ChronoDuration::hours(3), now), "3h ago");
+        assert_eq!(format_relative(now - ChronoDuration::days(2), now), "2d ago");
+        // Over a month old falls back to the plain date
+        let old = now - ChronoDuration::days(45);
+        assert_eq!(format_relative(old, now), old.format("%Y-%m-%d").to_string());
+    }
+}
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod share_tests {
+    use graph_os_cli::session::{ChatMessage, MessageMeta, Session};
+    use graph_os_cli::share::{redact_text, render_markdown, transcript_payload};
+    use uuid::Uuid;
+
+    fn session_with_messages(messages: Vec<ChatMessage>) -> Session {
+        let mut session = Session::new(Uuid::new_v4());
+        session.messages = messages;
+        session
+    }
+
+    #[test]
+    fn test_redact_text() {
+        let secrets = vec!["sk-abc123".to_string(), "hunter2".to_string()];
+
+        let redacted = redact_text("my key is sk-abc123 and password hunter2", &secrets);
+        assert_eq!(redacted, "my key is [redacted] and password [redacted]");
+
+        // Text without secrets passes through unchanged
+        assert_eq!(redact_text("nothing to hide", &secrets), "nothing to hide");
+    }
+
+    #[test]
+    fn test_transcript_payload() {
+        let session = session_with_messages(vec![
+            ChatMessage::user("the token is sk-abc123".to_string()),
+            ChatMessage::assistant("Understood.".to_string()),
+        ]);
+        let secrets = vec!["sk-abc123".to_string()];
+
+        let payload = transcript_payload(&session, &secrets);
+        let messages = payload["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["role"], "user");
+        assert_eq!(messages[0]["content"], "the token is [redacted]");
+        assert_eq!(messages[1]["role"], "assistant");
+        assert_eq!(payload["session_id"], session.id.to_string());
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        // Messages without metadata (as in pre-migration sessions)
+        // render without the italic metadata line
+        let session = session_with_messages(vec![
+            ChatMessage::User { text: "hello".to_string(), meta: MessageMeta::default() },
+            ChatMessage::Assistant {
+                text: "hi there".to_string(),
+                meta: MessageMeta {
+                    model: Some("gpt-4o".to_string()),
+                    latency_ms: Some(420),
+                    ..MessageMeta::default()
+                },
+            },
+        ]);
+
+        let markdown = render_markdown(&session, &[]);
+        assert!(markdown.starts_with(&format!("# Session {}", session.id)));
+        assert!(markdown.contains("## User\n\nhello\n"));
+        assert!(markdown.contains("## Assistant\n\n*gpt-4o | 420ms*\n\nhi there\n"));
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod signing_tests {
+    use graph_os_cli::signing::{
+        RequestSigner, RequestVerifier, SignatureParts, MAX_CLOCK_SKEW_SECS,
+    };
+
+    #[test]
+    fn test_sign_verify_round_trip() {
+        let signer = RequestSigner::new("key-1", "shared-secret");
+        let verifier = RequestVerifier::new("key-1", "shared-secret");
+
+        let body = br#"{"jsonrpc":"2.0","method":"ping","params":{},"id":"1"}"#;
+        let header = signer.header_value_at(body, 1_700_000_000, "nonce-a");
+
+        assert!(verifier.verify_at(Some(&header), body, 1_700_000_000).is_ok());
+    }
+
+    #[test]
+    fn test_tampered_body_rejected() {
+        let signer = RequestSigner::new("key-1", "shared-secret");
+        let verifier = RequestVerifier::new("key-1", "shared-secret");
+
+        let header = signer.header_value_at(b"original body", 1_700_000_000, "nonce-a");
+
+        assert!(verifier
+            .verify_at(Some(&header), b"tampered body", 1_700_000_000)
+            .is_err());
+    }
+
+    #[test]
+    fn test_wrong_key_rejected() {
+        let signer = RequestSigner::new("key-1", "shared-secret");
+        let body = b"body";
+        let header = signer.header_value_at(body, 1_700_000_000, "nonce-a");
+
+        // A different shared key fails the MAC; a different key id is
+        // rejected before the MAC is even checked
+        let wrong_key = RequestVerifier::new("key-1", "other-secret");
+        assert!(wrong_key.verify_at(Some(&header), body, 1_700_000_000).is_err());
+
+        let wrong_id = RequestVerifier::new("key-2", "shared-secret");
+        assert!(wrong_id.verify_at(Some(&header), body, 1_700_000_000).is_err());
+    }
+
+    #[test]
+    fn test_stale_timestamp_rejected() {
+        let signer = RequestSigner::new("key-1", "shared-secret");
+        let verifier = RequestVerifier::new("key-1", "shared-secret");
+
+        let body = b"body";
+        let ts = 1_700_000_000;
+        let header = signer.header_value_at(body, ts, "nonce-a");
+
+        // Inside the window either way is fine; outside is not
+        assert!(verifier
+            .verify_at(Some(&header), body, ts + MAX_CLOCK_SKEW_SECS)
+            .is_ok());
+        assert!(verifier
+            .verify_at(Some(&header), body, ts + MAX_CLOCK_SKEW_SECS + 1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_replayed_nonce_rejected() {
+        let signer = RequestSigner::new("key-1", "shared-secret");
+        let verifier = RequestVerifier::new("key-1", "shared-secret");
+
+        let body = b"body";
+        let header = signer.header_value_at(body, 1_700_000_000, "nonce-a");
+
+        assert!(verifier.verify_at(Some(&header), body, 1_700_000_000).is_ok());
+        // The identical request again is a replay
+        assert!(verifier.verify_at(Some(&header), body, 1_700_000_000).is_err());
+
+        // A fresh nonce from the same signer still passes
+        let header = signer.header_value_at(body, 1_700_000_000, "nonce-b");
+        assert!(verifier.verify_at(Some(&header), body, 1_700_000_000).is_ok());
+    }
+
+    #[test]
+    fn test_missing_header_rejected() {
+        let verifier = RequestVerifier::new("key-1", "shared-secret");
+        assert!(verifier.verify_at(None, b"body", 1_700_000_000).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_headers() {
+        assert!(SignatureParts::parse("v2,key_id=k,ts=1,nonce=n,sig=AA==").is_err());
+        assert!(SignatureParts::parse("not a signature").is_err());
+        assert!(SignatureParts::parse("v1,key_id=k,nonce=n,sig=AA==").is_err());
+        assert!(SignatureParts::parse("v1,key_id=k,ts=soon,nonce=n,sig=AA==").is_err());
+        assert!(SignatureParts::parse("v1,key_id=k,ts=1,nonce=n,sig=!!").is_err());
+
+        let parts = SignatureParts::parse("v1,key_id=k,ts=1,nonce=n,sig=AA==").unwrap();
+        assert_eq!(parts.key_id, "k");
+        assert_eq!(parts.ts, 1);
+        assert_eq!(parts.nonce, "n");
+    }
+
+    #[tokio::test]
+    async fn test_dev_server_enforces_signatures() {
+        use graph_os_cli::adapters::{HttpClientOptions, JsonRpcClient};
+        use graph_os_cli::signing::RequestVerifier;
+        use serde_json::json;
+
+        let verifier = RequestVerifier::new("dev", "table-mountain");
+        let (addr, server) =
+            graph_os_cli::serve::bind("127.0.0.1", 0, None, Some(verifier)).unwrap();
+        tokio::spawn(server);
+        let endpoint = format!("http://{}/api/jsonrpc", addr);
+
+        // An unsigned client is turned away at the door
+        let unsigned = JsonRpcClient::with_endpoint(endpoint.clone(), None, None, None);
+        assert!(unsigned.request("ping", json!({})).await.is_err());
+
+        // A client configured with the matching key gets through
+        let options = HttpClientOptions {
+            signing_key_id: Some("dev".to_string()),
+            signing_key: Some("table-mountain".to_string()),
+            ..HttpClientOptions::default()
+        };
+        let signed = JsonRpcClient::with_endpoint_options(endpoint, None, None, None, &options);
+        let result = signed.request("ping", json!({})).await.unwrap();
+        assert_eq!(result["status"], "ok");
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod snapshots_tests {
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    use graph_os_cli::session::{ChatMessage, Session};
+    use graph_os_cli::snapshots::{
+        list_snapshots_in, parse_snapshot_name, snapshot_name, write_snapshot, MAX_SNAPSHOTS,
+    };
+
+    #[test]
+    fn test_snapshot_name_round_trip() {
+        let taken_at = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+
+        let name = snapshot_name(taken_at, 12, "auto");
+        assert_eq!(name, "1700000000-12-auto");
+
+        let info = parse_snapshot_name(&name).unwrap();
+        assert_eq!(info.taken_at, taken_at);
+        assert_eq!(info.messages, 12);
+        assert_eq!(info.reason, "auto");
+
+        // Unsafe characters in the reason are neutralized
+        assert_eq!(snapshot_name(taken_at, 1, "a/b c"), "1700000000-1-a_b_c");
+
+        assert!(parse_snapshot_name("not-a-snapshot").is_none());
+        assert!(parse_snapshot_name("12").is_none());
+    }
+
+    #[test]
+    fn test_write_list_and_prune() {
+        let dir = std::env::temp_dir().join(format!("gos-snapshot-test-{}", Uuid::new_v4()));
+
+        // An empty (missing) directory lists cleanly
+        assert!(list_snapshots_in(&dir).unwrap().is_empty());
+
+        let mut session = Session::new(Uuid::new_v4());
+        for i in 0..MAX_SNAPSHOTS + 3 {
+            session.messages.push(ChatMessage::user(format!("message {}", i)));
+            let taken_at = Utc.timestamp_opt(1_700_000_000 + i as i64, 0).unwrap();
+            write_snapshot(&dir, &session, "auto", taken_at).unwrap();
+        }
+
+        // Pruned to the cap, newest first
+        let snapshots = list_snapshots_in(&dir).unwrap();
+        assert_eq!(snapshots.len(), MAX_SNAPSHOTS);
+        assert_eq!(snapshots[0].messages, MAX_SNAPSHOTS + 3);
+        assert!(snapshots[0].taken_at > snapshots.last().unwrap().taken_at);
+
+        // The snapshot body is the full session
+        let path = dir.join(format!("{}.json", snapshots[0].name));
+        let restored: Session =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(restored.id, session.id);
+        assert_eq!(restored.messages.len(), MAX_SNAPSHOTS + 3);
+        assert_eq!(restored.messages[0].text(), "message 0");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod sse_tests {
+    use graph_os_cli::adapters::sse::{extract_stream_content, SseParser};
+
+    #[test]
+    fn test_basic_events() {
+        let mut parser = SseParser::new();
+        let events = parser.push(b"data: one\n\nevent: delta\nid: 42\ndata: two\n\n");
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].data, "one");
+        assert_eq!(events[0].event, None);
+        assert_eq!(events[1].data, "two");
+        assert_eq!(events[1].event.as_deref(), Some("delta"));
+        assert_eq!(events[1].id.as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_multiline_data_and_comments() {
+        let mut parser = SseParser::new();
+        let events = parser.push(b": keep-alive\ndata: first\ndata: second\n\n");
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "first\nsecond");
+    }
+
+    #[test]
+    fn test_chunked_input_and_crlf() {
+        let mut parser = SseParser::new();
+
+        // Events split mid-line across network chunks must reassemble
+        assert!(parser.push(b"data: hel").is_empty());
+        assert!(parser.push(b"lo\r\n").is_empty());
+        let events = parser.push(b"\r\n");
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "hello");
+    }
+
+    #[test]
+    fn test_extract_stream_content() {
+        // Our JSON-RPC chunk framing
+        let jsonrpc = r#"{"jsonrpc":"2.0","result":{"content":"hi"},"id":"1"}"#;
+        assert_eq!(extract_stream_content(jsonrpc).unwrap().as_deref(), Some("hi"));
+
+        // OpenAI delta framing
+        let openai = r#"{"choices":[{"delta":{"content":"there"}}]}"#;
+        assert_eq!(extract_stream_content(openai).unwrap().as_deref(), Some("there"));
+
+        // Role announcements carry no text
+        let role = r#"{"choices":[{"delta":{"role":"assistant"}}]}"#;
+        assert_eq!(extract_stream_content(role).unwrap(), None);
+
+        // Errors surface as errors
+        let error = r#"{"error":{"message":"rate limited"}}"#;
+        assert!(extract_stream_content(error).is_err());
+
+        // Garbage is an error, not a silent skip
+        assert!(extract_stream_content("not json").is_err());
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tasks_tests {
+    use serde_json::json;
+    use graph_os_cli::tasks::{log_lines, task_id, TaskState};
+
+    #[test]
+    fn test_state_parsing_accepts_common_spellings() {
+        assert_eq!(TaskState::parse("queued"), TaskState::Pending);
+        assert_eq!(TaskState::parse("Running"), TaskState::Running);
+        assert_eq!(TaskState::parse("SUCCESS"), TaskState::Succeeded);
+        assert_eq!(TaskState::parse("completed"), TaskState::Succeeded);
+        assert_eq!(TaskState::parse("error"), TaskState::Failed);
+        assert_eq!(TaskState::parse("canceled"), TaskState::Cancelled);
+        // Unknown states keep the follow loop polling
+        assert_eq!(TaskState::parse("retrying"), TaskState::Running);
+    }
+
+    #[test]
+    fn test_terminal_states_and_exit_codes() {
+        assert!(!TaskState::Pending.is_terminal());
+        assert!(!TaskState::Running.is_terminal());
+        assert!(TaskState::Succeeded.is_terminal());
+        assert!(TaskState::Failed.is_terminal());
+        assert!(TaskState::Cancelled.is_terminal());
+
+        assert_eq!(TaskState::Succeeded.exit_code(), 0);
+        assert_eq!(TaskState::Failed.exit_code(), 1);
+        assert_eq!(TaskState::Cancelled.exit_code(), 130);
+    }
+
+    #[test]
+    fn test_task_id_accepts_both_field_names() {
+        assert_eq!(task_id(&json!({"id": "run-1"})).as_deref(), Some("run-1"));
+        assert_eq!(task_id(&json!({"task_id": "run-2"})).as_deref(), Some("run-2"));
+        assert_eq!(task_id(&json!({"id": 7})).as_deref(), Some("7"));
+        assert_eq!(task_id(&json!({"name": "x"})), None);
+    }
+
+    #[test]
+    fn test_log_lines_accepts_both_shapes() {
+        assert_eq!(
+            log_lines(&json!({"lines": ["a", "b"]})),
+            vec!["a".to_string(), "b".to_string()]
+        );
+        assert_eq!(log_lines(&json!(["c"])), vec!["c".to_string()]);
+        assert!(log_lines(&json!({"lines": []})).is_empty());
+        assert!(log_lines(&json!({})).is_empty());
+    }
+}
@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod template_tests {
+    use std::collections::HashMap;
+
+    use graph_os_cli::templates::{parse_var, render, variables};
+
+    #[test]
+    fn test_variables() {
+        let names = variables("Review {{file}} in {{lang}}, focusing on {{file}}");
+        assert_eq!(names, vec!["file".to_string(), "lang".to_string()]);
+
+        assert!(variables("no placeholders here").is_empty());
+    }
+
+    #[test]
+    fn test_render() {
+        let mut vars = HashMap::new();
+        vars.insert("file".to_string(), "main.rs".to_string());
+
+        let rendered = render("Review {{file}} carefully", &vars).unwrap();
+        assert_eq!(rendered, "Review main.rs carefully");
+
+        // A placeholder without a value is an error
+        assert!(render("Review {{other}}", &vars).is_err());
+
+        // Unterminated placeholders pass through verbatim
+        let rendered = render("keep {{this", &vars).unwrap();
+        assert_eq!(rendered, "keep {{this");
+    }
+
+    #[test]
+    fn test_parse_var() {
+        let (key, value) = parse_var("file=main.rs").unwrap();
+        assert_eq!(key, "file");
+        assert_eq!(value, "main.rs");
+
+        // Values may contain '='
+        let (key, value) = parse_var("expr=a=b").unwrap();
+        assert_eq!(key, "expr");
+        assert_eq!(value, "a=b");
+
+        assert!(parse_var("no-equals").is_err());
+        assert!(parse_var("=value").is_err());
+    }
+}
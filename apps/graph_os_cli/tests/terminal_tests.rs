@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod terminal_tests {
+    use graph_os_cli::terminal::{Activity, TerminalNotifier, TerminalNotifyConfig};
+
+    #[test]
+    fn test_activity_titles() {
+        assert_eq!(Activity::Responding.title(false), "gos ● responding…");
+        assert_eq!(Activity::Done.title(false), "gos ✓ done");
+        assert_eq!(Activity::Idle.title(false), "gos");
+
+        // Accessible mode drops the symbols
+        for activity in [Activity::Responding, Activity::Done, Activity::Disconnected] {
+            assert!(activity.title(true).is_ascii());
+        }
+    }
+
+    #[test]
+    fn test_bell_config_matches_per_event() {
+        let notifier = |bell: &[&str]| {
+            TerminalNotifier::new(
+                TerminalNotifyConfig {
+                    title: None,
+                    bell: bell.iter().map(|s| s.to_string()).collect(),
+                },
+                false,
+            )
+        };
+
+        // Default: no bell at all
+        assert!(!notifier(&[]).bell_configured(Activity::Done));
+
+        // Per-event selection
+        let done_only = notifier(&["done"]);
+        assert!(done_only.bell_configured(Activity::Done));
+        assert!(!done_only.bell_configured(Activity::Disconnected));
+
+        // "*" covers every event
+        assert!(notifier(&["*"]).bell_configured(Activity::Responding));
+    }
+}
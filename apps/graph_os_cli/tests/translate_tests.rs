@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod translate_tests {
+    use serde_json::json;
+
+    use graph_os_cli::adapters::translate::{
+        chat_completions_url, from_openai_response, models_url, to_openai_request,
+    };
+    use graph_os_cli::adapters::{ContentPart, Message, MessageContent, MessageRole};
+
+    #[test]
+    fn test_urls_replace_endpoint_path() {
+        assert_eq!(
+            chat_completions_url("http://localhost:11434/api/jsonrpc"),
+            "http://localhost:11434/v1/chat/completions"
+        );
+        assert_eq!(
+            models_url("https://example.com:8443/"),
+            "https://example.com:8443/v1/models"
+        );
+    }
+
+    #[test]
+    fn test_to_openai_request_text_messages() {
+        let messages = vec![
+            Message {
+                role: MessageRole::System,
+                content: MessageContent::Text("be brief".to_string()),
+            },
+            Message {
+                role: MessageRole::User,
+                content: MessageContent::Text("hello".to_string()),
+            },
+        ];
+
+        let request = to_openai_request(&messages, Some("llama3"), true);
+        assert_eq!(request["model"], "llama3");
+        assert_eq!(request["stream"], true);
+        assert_eq!(request["messages"][0]["role"], "system");
+        assert_eq!(request["messages"][0]["content"], "be brief");
+        assert_eq!(request["messages"][1]["role"], "user");
+
+        // Without a model the key is absent, letting the server default
+        let request = to_openai_request(&messages, None, false);
+        assert!(request.get("model").is_none());
+    }
+
+    #[test]
+    fn test_to_openai_request_image_parts() {
+        let messages = vec![Message {
+            role: MessageRole::User,
+            content: MessageContent::Parts(vec![
+                ContentPart::Text { text: "what is this".to_string() },
+                ContentPart::Image {
+                    media_type: "image/png".to_string(),
+                    data: "aGk=".to_string(),
+                },
+            ]),
+        }];
+
+        let request = to_openai_request(&messages, None, false);
+        let content = &request["messages"][0]["content"];
+        assert_eq!(content[0]["type"], "text");
+        assert_eq!(content[1]["type"], "image_url");
+        // Inline images become data URLs in the OpenAI dialect
+        assert_eq!(content[1]["image_url"]["url"], "data:image/png;base64,aGk=");
+    }
+
+    #[test]
+    fn test_from_openai_response() {
+        let body = json!({
+            "choices": [{ "message": { "role": "assistant", "content": "hi there" } }]
+        });
+        assert_eq!(from_openai_response(&body).unwrap(), "hi there");
+
+        let error = json!({ "error": { "message": "model not found" } });
+        let err = from_openai_response(&error).unwrap_err().to_string();
+        assert!(err.contains("model not found"));
+
+        assert!(from_openai_response(&json!({})).is_err());
+    }
+}
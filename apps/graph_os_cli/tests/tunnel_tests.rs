@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod tunnel_tests {
+    use graph_os_cli::tunnel::TunnelSpec;
+
+    #[test]
+    fn test_parse_full_spec() {
+        let spec = TunnelSpec::parse("ssh://deploy@bastion.example.com:2222").unwrap();
+        assert_eq!(spec.user.as_deref(), Some("deploy"));
+        assert_eq!(spec.host, "bastion.example.com");
+        assert_eq!(spec.port, Some(2222));
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        // User and port are optional; ssh falls back to the current
+        // user and port 22
+        let spec = TunnelSpec::parse("ssh://bastion").unwrap();
+        assert_eq!(spec.user, None);
+        assert_eq!(spec.host, "bastion");
+        assert_eq!(spec.port, None);
+
+        let spec = TunnelSpec::parse("ssh://ops@bastion").unwrap();
+        assert_eq!(spec.user.as_deref(), Some("ops"));
+        assert_eq!(spec.port, None);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_specs() {
+        // Only ssh:// is a tunnel; the error points SOCKS users at the
+        // proxy option instead
+        let err = TunnelSpec::parse("socks5://127.0.0.1:1080").unwrap_err();
+        assert!(err.to_string().contains("socks5://"));
+
+        assert!(TunnelSpec::parse("ssh://@bastion").is_err());
+        assert!(TunnelSpec::parse("ssh://").is_err());
+        assert!(TunnelSpec::parse("ssh://bastion:notaport").is_err());
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod usage_tests {
+    use std::collections::HashMap;
+
+    use chrono::Utc;
+    use graph_os_cli::usage::{cost_for, estimate_tokens, ModelPrice, UsageLog, UsageRecord};
+
+    #[test]
+    fn test_estimate_tokens() {
+        // Roughly four characters per token, never zero
+        assert_eq!(estimate_tokens(""), 1);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        assert_eq!(estimate_tokens("abcdefghi"), 3);
+    }
+
+    #[test]
+    fn test_cost_for() {
+        let mut prices = HashMap::new();
+        prices.insert(
+            "gpt-4o".to_string(),
+            ModelPrice {
+                input_per_1k: 0.005,
+                output_per_1k: 0.015,
+            },
+        );
+
+        let cost = cost_for(&prices, "gpt-4o", 1000, 2000);
+        assert!((cost - 0.035).abs() < 1e-9);
+
+        // Unpriced models cost zero rather than a made-up number
+        assert_eq!(cost_for(&prices, "unknown-model", 1000, 1000), 0.0);
+    }
+
+    #[test]
+    fn test_append_and_read() {
+        let dir = std::env::temp_dir().join(format!("gos-usage-test-{}", uuid::Uuid::new_v4()));
+        let log = UsageLog::with_path(dir.join("usage.jsonl"));
+
+        log.record(&UsageRecord {
+            timestamp: Utc::now(),
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            session_id: None,
+            prompt_tokens: 10,
+            completion_tokens: 20,
+            cost: 0.001,
+        });
+
+        let records = log.read_since(None).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].provider, "openai");
+        assert_eq!(records[0].completion_tokens, 20);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}